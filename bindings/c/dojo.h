@@ -8,38 +8,58 @@ namespace dojo_bindings {
 #endif  // __cplusplus
 
 struct ToriiClient;
+struct EndpointStatus;
+struct Diagnostic;
 struct FieldElement;
-struct Controller;
 struct OrderBy;
-struct Entity;
 struct COptionFieldElement;
-struct World;
+struct Controller;
+struct Entity;
+struct EntityLookup;
+struct EntityVersion;
+struct EntityIterator;
+struct ControllerIterator;
+struct TokenIterator;
+struct AttributeFilter;
+struct TokenBalanceIterator;
+struct TokenTransferIterator;
+struct TransactionIterator;
+struct ActivityIterator;
+struct AchievementIterator;
+struct PlayerAchievementIterator;
+struct AggregationIterator;
+struct Struct;
+struct Token;
+struct TokenBalance;
+struct TokenTransfer;
 struct Transaction;
-struct Subscription;
 struct TransactionCall;
-struct Struct;
-struct AggregationEntry;
-struct Achievement;
-struct PlayerAchievementEntry;
 struct Activity;
 struct ActionCount;
-struct Token;
-struct AttributeFilter;
-struct TokenBalance;
+struct Achievement;
+struct AchievementTask;
+struct PlayerAchievementEntry;
+struct PlayerAchievementProgress;
+struct AggregationEntry;
+struct World;
+struct WorldSchema;
+struct ModelSchema;
+struct MemberSchema;
+struct Subscription;
+struct ModelDispatcher;
+struct OptimisticStore;
+struct Member;
+struct EnumOption;
+struct TypeChange;
 struct TokenContract;
 struct Contract;
-struct TokenTransfer;
 struct TableSearchResults;
 struct Provider;
+struct TyBuilder;
 struct Account;
-struct Ty;
+struct TaskProgress;
 struct Model;
-struct Member;
-struct AchievementTask;
-struct PlayerAchievementProgress;
 struct SearchMatch;
-struct EnumOption;
-struct TaskProgress;
 struct SearchMatchField;
 
 typedef enum BlockTag {
@@ -52,6 +72,14 @@ typedef enum CallType {
   ExecuteFromOutside,
 } CallType;
 
+typedef enum ChangeKind {
+  EntityCreated,
+  ModelAdded,
+  MemberUpdated,
+  ModelRemoved,
+  EntityDeleted,
+} ChangeKind;
+
 typedef enum ComparisonOperator {
   Eq,
   Neq,
@@ -88,6 +116,12 @@ typedef enum OrderDirection {
   Desc,
 } OrderDirection;
 
+typedef enum Overflow {
+  DropOldest,
+  DropNewest,
+  Wait,
+} Overflow;
+
 typedef enum PaginationDirection {
   Forward,
   Backward,
@@ -98,6 +132,29 @@ typedef enum PatternMatching {
   VariableLen = 1,
 } PatternMatching;
 
+/**
+ * How a part of a model schema changed
+ */
+typedef enum TypeChangeKind {
+  Added,
+  Removed,
+  Changed,
+  KeyChanged,
+} TypeChangeKind;
+
+/**
+ * Shape of a type. Variants are suffixed so as not to clash with the type names of the header
+ */
+typedef enum TypeKind {
+  PrimitiveType,
+  StructType,
+  EnumType,
+  TupleType,
+  ArrayType,
+  FixedSizeArrayType,
+  ByteArrayType,
+} TypeKind;
+
 typedef struct Error {
   char *message;
 } Error;
@@ -119,114 +176,184 @@ typedef struct ResultToriiClient {
   };
 } ResultToriiClient;
 
-typedef enum Resultc_char_Tag {
-  Okc_char,
-  Errc_char,
-} Resultc_char_Tag;
+typedef struct CArrayc_char {
+  const char **data;
+  uintptr_t data_len;
+} CArrayc_char;
 
-typedef struct Resultc_char {
-  Resultc_char_Tag tag;
+typedef enum COptionu64_Tag {
+  Someu64,
+  Noneu64,
+} COptionu64_Tag;
+
+typedef struct COptionu64 {
+  COptionu64_Tag tag;
   union {
     struct {
-      const char *ok;
+      uint64_t some;
     };
+  };
+} COptionu64;
+
+typedef enum COptionu32_Tag {
+  Someu32,
+  Noneu32,
+} COptionu32_Tag;
+
+typedef struct COptionu32 {
+  COptionu32_Tag tag;
+  union {
     struct {
-      struct Error err;
+      uint32_t some;
     };
   };
-} Resultc_char;
+} COptionu32;
 
-typedef struct CArrayFieldElement {
-  struct FieldElement *data;
-  uintptr_t data_len;
-} CArrayFieldElement;
+typedef struct RetryPolicy {
+  uint64_t initial_backoff_ms;
+  uint64_t max_backoff_ms;
+  uint32_t multiplier;
+  struct COptionu32 max_attempts;
+} RetryPolicy;
 
-typedef struct FieldElement {
-  uint8_t data[32];
-} FieldElement;
+typedef enum COptionRetryPolicy_Tag {
+  SomeRetryPolicy,
+  NoneRetryPolicy,
+} COptionRetryPolicy_Tag;
 
-typedef struct Message {
-  const char *message;
-  struct CArrayFieldElement signature;
-  struct FieldElement world_address;
-} Message;
+typedef struct COptionRetryPolicy {
+  COptionRetryPolicy_Tag tag;
+  union {
+    struct {
+      struct RetryPolicy some;
+    };
+  };
+} COptionRetryPolicy;
 
-typedef struct CArrayc_char {
-  const char **data;
-  uintptr_t data_len;
-} CArrayc_char;
+typedef struct PollingConfig {
+  uint64_t interval_ms;
+  uint32_t after_failures;
+  uint32_t page_size;
+} PollingConfig;
 
-typedef enum ResultCArrayc_char_Tag {
-  OkCArrayc_char,
-  ErrCArrayc_char,
-} ResultCArrayc_char_Tag;
+typedef enum COptionPollingConfig_Tag {
+  SomePollingConfig,
+  NonePollingConfig,
+} COptionPollingConfig_Tag;
 
-typedef struct ResultCArrayc_char {
-  ResultCArrayc_char_Tag tag;
+typedef struct COptionPollingConfig {
+  COptionPollingConfig_Tag tag;
   union {
     struct {
-      struct CArrayc_char ok;
+      struct PollingConfig some;
     };
+  };
+} COptionPollingConfig;
+
+typedef struct CacheConfig {
+  uint64_t ttl_ms;
+  uint64_t max_entries;
+  bool stale_while_revalidate;
+} CacheConfig;
+
+typedef enum COptionCacheConfig_Tag {
+  SomeCacheConfig,
+  NoneCacheConfig,
+} COptionCacheConfig_Tag;
+
+typedef struct COptionCacheConfig {
+  COptionCacheConfig_Tag tag;
+  union {
     struct {
-      struct Error err;
+      struct CacheConfig some;
     };
   };
-} ResultCArrayc_char;
-
-typedef struct CArrayController {
-  struct Controller *data;
+} COptionCacheConfig;
+
+typedef struct ClientConfig {
+  const char *torii_url;
+  struct CArrayc_char fallback_urls;
+  struct COptionu64 connect_timeout_ms;
+  struct COptionu64 request_timeout_ms;
+  struct COptionu64 max_message_size;
+  struct COptionRetryPolicy retry;
+  struct COptionPollingConfig polling;
+  bool multiplex;
+  struct COptionCacheConfig cache;
+  bool validate_queries;
+} ClientConfig;
+
+typedef struct CArrayEndpointStatus {
+  struct EndpointStatus *data;
   uintptr_t data_len;
-} CArrayController;
+} CArrayEndpointStatus;
 
-typedef enum COptionc_char_Tag {
-  Somec_char,
-  Nonec_char,
-} COptionc_char_Tag;
+typedef enum ResultCArrayEndpointStatus_Tag {
+  OkCArrayEndpointStatus,
+  ErrCArrayEndpointStatus,
+} ResultCArrayEndpointStatus_Tag;
 
-typedef struct COptionc_char {
-  COptionc_char_Tag tag;
+typedef struct ResultCArrayEndpointStatus {
+  ResultCArrayEndpointStatus_Tag tag;
   union {
     struct {
-      const char *some;
+      struct CArrayEndpointStatus ok;
+    };
+    struct {
+      struct Error err;
     };
   };
-} COptionc_char;
-
-typedef struct PageController {
-  struct CArrayController items;
-  struct COptionc_char next_cursor;
-} PageController;
+} ResultCArrayEndpointStatus;
+
+typedef struct CacheStats {
+  uint64_t hits;
+  uint64_t stale_hits;
+  uint64_t misses;
+  uint64_t invalidations;
+  uint64_t evictions;
+  uint64_t entries;
+} CacheStats;
+
+typedef struct CArrayDiagnostic {
+  struct Diagnostic *data;
+  uintptr_t data_len;
+} CArrayDiagnostic;
 
-typedef enum ResultPageController_Tag {
-  OkPageController,
-  ErrPageController,
-} ResultPageController_Tag;
+typedef enum ResultCArrayDiagnostic_Tag {
+  OkCArrayDiagnostic,
+  ErrCArrayDiagnostic,
+} ResultCArrayDiagnostic_Tag;
 
-typedef struct ResultPageController {
-  ResultPageController_Tag tag;
+typedef struct ResultCArrayDiagnostic {
+  ResultCArrayDiagnostic_Tag tag;
   union {
     struct {
-      struct PageController ok;
+      struct CArrayDiagnostic ok;
     };
     struct {
       struct Error err;
     };
   };
-} ResultPageController;
+} ResultCArrayDiagnostic;
 
-typedef enum COptionu32_Tag {
-  Someu32,
-  Noneu32,
-} COptionu32_Tag;
+typedef struct CArrayFieldElement {
+  struct FieldElement *data;
+  uintptr_t data_len;
+} CArrayFieldElement;
 
-typedef struct COptionu32 {
-  COptionu32_Tag tag;
+typedef enum COptionc_char_Tag {
+  Somec_char,
+  Nonec_char,
+} COptionc_char_Tag;
+
+typedef struct COptionc_char {
+  COptionc_char_Tag tag;
   union {
     struct {
-      uint32_t some;
+      const char *some;
     };
   };
-} COptionu32;
+} COptionc_char;
 
 typedef struct CArrayOrderBy {
   struct OrderBy *data;
@@ -240,39 +367,6 @@ typedef struct Pagination {
   struct CArrayOrderBy order_by;
 } Pagination;
 
-typedef struct ControllerQuery {
-  struct Pagination pagination;
-  struct CArrayFieldElement contract_addresses;
-  struct CArrayc_char usernames;
-} ControllerQuery;
-
-typedef struct CArrayEntity {
-  struct Entity *data;
-  uintptr_t data_len;
-} CArrayEntity;
-
-typedef struct PageEntity {
-  struct CArrayEntity items;
-  struct COptionc_char next_cursor;
-} PageEntity;
-
-typedef enum ResultPageEntity_Tag {
-  OkPageEntity,
-  ErrPageEntity,
-} ResultPageEntity_Tag;
-
-typedef struct ResultPageEntity {
-  ResultPageEntity_Tag tag;
-  union {
-    struct {
-      struct PageEntity ok;
-    };
-    struct {
-      struct Error err;
-    };
-  };
-} ResultPageEntity;
-
 typedef struct CArrayCOptionFieldElement {
   struct COptionFieldElement *data;
   uintptr_t data_len;
@@ -288,6 +382,10 @@ typedef struct U256 {
   uint8_t data[32];
 } U256;
 
+typedef struct FieldElement {
+  uint8_t data[32];
+} FieldElement;
+
 typedef enum Primitive_Tag {
   I8,
   I16,
@@ -452,236 +550,277 @@ typedef struct Query {
   bool historical;
 } Query;
 
-typedef struct CArrayWorld {
-  struct World *data;
-  uintptr_t data_len;
-} CArrayWorld;
-
-typedef enum ResultCArrayWorld_Tag {
-  OkCArrayWorld,
-  ErrCArrayWorld,
-} ResultCArrayWorld_Tag;
+typedef enum Resultc_char_Tag {
+  Okc_char,
+  Errc_char,
+} Resultc_char_Tag;
 
-typedef struct ResultCArrayWorld {
-  ResultCArrayWorld_Tag tag;
+typedef struct Resultc_char {
+  Resultc_char_Tag tag;
   union {
     struct {
-      struct CArrayWorld ok;
+      const char *ok;
     };
     struct {
       struct Error err;
     };
   };
-} ResultCArrayWorld;
-
-typedef struct CArrayTransaction {
-  struct Transaction *data;
-  uintptr_t data_len;
-} CArrayTransaction;
+} Resultc_char;
 
-typedef struct PageTransaction {
-  struct CArrayTransaction items;
-  struct COptionc_char next_cursor;
-} PageTransaction;
+typedef struct Message {
+  const char *message;
+  struct CArrayFieldElement signature;
+  struct FieldElement world_address;
+} Message;
 
-typedef enum ResultPageTransaction_Tag {
-  OkPageTransaction,
-  ErrPageTransaction,
-} ResultPageTransaction_Tag;
+typedef enum ResultCArrayc_char_Tag {
+  OkCArrayc_char,
+  ErrCArrayc_char,
+} ResultCArrayc_char_Tag;
 
-typedef struct ResultPageTransaction {
-  ResultPageTransaction_Tag tag;
+typedef struct ResultCArrayc_char {
+  ResultCArrayc_char_Tag tag;
   union {
     struct {
-      struct PageTransaction ok;
+      struct CArrayc_char ok;
     };
     struct {
       struct Error err;
     };
   };
-} ResultPageTransaction;
+} ResultCArrayc_char;
 
-typedef enum COptionu64_Tag {
-  Someu64,
-  Noneu64,
-} COptionu64_Tag;
+typedef struct CArrayController {
+  struct Controller *data;
+  uintptr_t data_len;
+} CArrayController;
 
-typedef struct COptionu64 {
-  COptionu64_Tag tag;
+typedef struct PageController {
+  struct CArrayController items;
+  struct COptionc_char next_cursor;
+} PageController;
+
+typedef enum ResultPageController_Tag {
+  OkPageController,
+  ErrPageController,
+} ResultPageController_Tag;
+
+typedef struct ResultPageController {
+  ResultPageController_Tag tag;
   union {
     struct {
-      uint64_t some;
+      struct PageController ok;
+    };
+    struct {
+      struct Error err;
     };
   };
-} COptionu64;
+} ResultPageController;
 
-typedef struct TransactionFilter {
-  struct CArrayFieldElement transaction_hashes;
-  struct CArrayFieldElement caller_addresses;
+typedef struct ControllerQuery {
+  struct Pagination pagination;
   struct CArrayFieldElement contract_addresses;
-  struct CArrayc_char entrypoints;
-  struct CArrayFieldElement model_selectors;
-  struct COptionu64 from_block;
-  struct COptionu64 to_block;
-} TransactionFilter;
+  struct CArrayc_char usernames;
+} ControllerQuery;
 
-typedef enum COptionTransactionFilter_Tag {
-  SomeTransactionFilter,
-  NoneTransactionFilter,
-} COptionTransactionFilter_Tag;
+typedef struct CArrayEntity {
+  struct Entity *data;
+  uintptr_t data_len;
+} CArrayEntity;
 
-typedef struct COptionTransactionFilter {
-  COptionTransactionFilter_Tag tag;
+typedef struct PageEntity {
+  struct CArrayEntity items;
+  struct COptionc_char next_cursor;
+} PageEntity;
+
+typedef enum ResultPageEntity_Tag {
+  OkPageEntity,
+  ErrPageEntity,
+} ResultPageEntity_Tag;
+
+typedef struct ResultPageEntity {
+  ResultPageEntity_Tag tag;
   union {
     struct {
-      struct TransactionFilter some;
+      struct PageEntity ok;
+    };
+    struct {
+      struct Error err;
     };
   };
-} COptionTransactionFilter;
+} ResultPageEntity;
 
-typedef struct TransactionQuery {
-  struct COptionTransactionFilter filter;
-  struct Pagination pagination;
-} TransactionQuery;
+typedef struct CArrayEntityLookup {
+  struct EntityLookup *data;
+  uintptr_t data_len;
+} CArrayEntityLookup;
 
-typedef enum ResultSubscription_Tag {
-  OkSubscription,
-  ErrSubscription,
-} ResultSubscription_Tag;
+typedef enum ResultCArrayEntityLookup_Tag {
+  OkCArrayEntityLookup,
+  ErrCArrayEntityLookup,
+} ResultCArrayEntityLookup_Tag;
 
-typedef struct ResultSubscription {
-  ResultSubscription_Tag tag;
+typedef struct ResultCArrayEntityLookup {
+  ResultCArrayEntityLookup_Tag tag;
   union {
     struct {
-      struct Subscription *ok;
+      struct CArrayEntityLookup ok;
     };
     struct {
       struct Error err;
     };
   };
-} ResultSubscription;
-
-typedef struct CArrayTransactionCall {
-  struct TransactionCall *data;
-  uintptr_t data_len;
-} CArrayTransactionCall;
+} ResultCArrayEntityLookup;
 
-typedef struct Transaction {
-  struct FieldElement transaction_hash;
-  struct FieldElement sender_address;
-  struct CArrayFieldElement calldata;
-  struct FieldElement max_fee;
-  struct CArrayFieldElement signature;
-  struct FieldElement nonce;
-  uint64_t block_number;
-  const char *transaction_type;
-  uint64_t block_timestamp;
-  struct CArrayTransactionCall calls;
-  struct CArrayFieldElement unique_models;
-} Transaction;
-
-typedef struct CArrayStruct {
-  struct Struct *data;
+typedef struct CArrayEntityVersion {
+  struct EntityVersion *data;
   uintptr_t data_len;
-} CArrayStruct;
-
-typedef struct Entity {
-  struct FieldElement world_address;
-  struct FieldElement hashed_keys;
-  struct CArrayStruct models;
-  uint64_t created_at;
-  uint64_t updated_at;
-  uint64_t executed_at;
-} Entity;
+} CArrayEntityVersion;
 
-typedef enum Resultbool_Tag {
-  Okbool,
-  Errbool,
-} Resultbool_Tag;
+typedef enum ResultCArrayEntityVersion_Tag {
+  OkCArrayEntityVersion,
+  ErrCArrayEntityVersion,
+} ResultCArrayEntityVersion_Tag;
 
-typedef struct Resultbool {
-  Resultbool_Tag tag;
+typedef struct ResultCArrayEntityVersion {
+  ResultCArrayEntityVersion_Tag tag;
   union {
     struct {
-      bool ok;
+      struct CArrayEntityVersion ok;
     };
     struct {
       struct Error err;
     };
   };
-} Resultbool;
+} ResultCArrayEntityVersion;
 
-typedef struct CArrayAggregationEntry {
-  struct AggregationEntry *data;
-  uintptr_t data_len;
-} CArrayAggregationEntry;
+/**
+ * Point in the history of a world, a timestamp being in seconds
+ */
+typedef enum At_Tag {
+  Block,
+  Timestamp,
+} At_Tag;
 
-typedef struct PageAggregationEntry {
-  struct CArrayAggregationEntry items;
-  struct COptionc_char next_cursor;
-} PageAggregationEntry;
+typedef struct At {
+  At_Tag tag;
+  union {
+    struct {
+      uint64_t block;
+    };
+    struct {
+      uint64_t timestamp;
+    };
+  };
+} At;
 
-typedef enum ResultPageAggregationEntry_Tag {
-  OkPageAggregationEntry,
-  ErrPageAggregationEntry,
-} ResultPageAggregationEntry_Tag;
+typedef enum COptionAt_Tag {
+  SomeAt,
+  NoneAt,
+} COptionAt_Tag;
 
-typedef struct ResultPageAggregationEntry {
-  ResultPageAggregationEntry_Tag tag;
+typedef struct COptionAt {
+  COptionAt_Tag tag;
   union {
     struct {
-      struct PageAggregationEntry ok;
+      struct At some;
+    };
+  };
+} COptionAt;
+
+/**
+ * Inclusive bounds of an entity history, unbounded when none
+ */
+typedef struct HistoryRange {
+  struct COptionAt from;
+  struct COptionAt to;
+} HistoryRange;
+
+typedef enum ResultCArrayEntity_Tag {
+  OkCArrayEntity,
+  ErrCArrayEntity,
+} ResultCArrayEntity_Tag;
+
+typedef struct ResultCArrayEntity {
+  ResultCArrayEntity_Tag tag;
+  union {
+    struct {
+      struct CArrayEntity ok;
     };
     struct {
       struct Error err;
     };
   };
-} ResultPageAggregationEntry;
+} ResultCArrayEntity;
 
-typedef struct AggregationQuery {
-  struct CArrayc_char aggregator_ids;
-  struct CArrayc_char entity_ids;
+typedef struct CArrayU256 {
+  struct U256 *data;
+  uintptr_t data_len;
+} CArrayU256;
+
+typedef struct CArrayAttributeFilter {
+  struct AttributeFilter *data;
+  uintptr_t data_len;
+} CArrayAttributeFilter;
+
+typedef struct TokenQuery {
+  struct CArrayFieldElement contract_addresses;
+  struct CArrayU256 token_ids;
+  struct CArrayAttributeFilter attribute_filters;
   struct Pagination pagination;
-} AggregationQuery;
+} TokenQuery;
 
-typedef struct AggregationEntry {
-  const char *id;
-  const char *aggregator_id;
-  const char *entity_id;
-  struct U256 value;
-  const char *display_value;
-  uint64_t position;
-  const char *model_id;
-  uint64_t created_at;
-  uint64_t updated_at;
-} AggregationEntry;
+typedef struct TokenBalanceQuery {
+  struct CArrayFieldElement contract_addresses;
+  struct CArrayFieldElement account_addresses;
+  struct CArrayU256 token_ids;
+  struct Pagination pagination;
+} TokenBalanceQuery;
 
-typedef struct CArrayAchievement {
-  struct Achievement *data;
-  uintptr_t data_len;
-} CArrayAchievement;
+typedef struct TokenTransferQuery {
+  struct CArrayFieldElement contract_addresses;
+  struct CArrayFieldElement account_addresses;
+  struct CArrayU256 token_ids;
+  struct Pagination pagination;
+} TokenTransferQuery;
 
-typedef struct PageAchievement {
-  struct CArrayAchievement items;
-  struct COptionc_char next_cursor;
-} PageAchievement;
+typedef struct TransactionFilter {
+  struct CArrayFieldElement transaction_hashes;
+  struct CArrayFieldElement caller_addresses;
+  struct CArrayFieldElement contract_addresses;
+  struct CArrayc_char entrypoints;
+  struct CArrayFieldElement model_selectors;
+  struct COptionu64 from_block;
+  struct COptionu64 to_block;
+} TransactionFilter;
 
-typedef enum ResultPageAchievement_Tag {
-  OkPageAchievement,
-  ErrPageAchievement,
-} ResultPageAchievement_Tag;
+typedef enum COptionTransactionFilter_Tag {
+  SomeTransactionFilter,
+  NoneTransactionFilter,
+} COptionTransactionFilter_Tag;
 
-typedef struct ResultPageAchievement {
-  ResultPageAchievement_Tag tag;
+typedef struct COptionTransactionFilter {
+  COptionTransactionFilter_Tag tag;
   union {
     struct {
-      struct PageAchievement ok;
-    };
-    struct {
-      struct Error err;
+      struct TransactionFilter some;
     };
   };
-} ResultPageAchievement;
+} COptionTransactionFilter;
+
+typedef struct TransactionQuery {
+  struct COptionTransactionFilter filter;
+  struct Pagination pagination;
+} TransactionQuery;
+
+typedef struct ActivityQuery {
+  struct CArrayFieldElement world_addresses;
+  struct CArrayc_char namespaces;
+  struct CArrayFieldElement caller_addresses;
+  struct COptionu64 from_time;
+  struct COptionu64 to_time;
+  struct Pagination pagination;
+} ActivityQuery;
 
 typedef enum COptionbool_Tag {
   Somebool,
@@ -704,112 +843,162 @@ typedef struct AchievementQuery {
   struct Pagination pagination;
 } AchievementQuery;
 
-typedef struct CArrayPlayerAchievementEntry {
-  struct PlayerAchievementEntry *data;
-  uintptr_t data_len;
-} CArrayPlayerAchievementEntry;
+typedef struct PlayerAchievementQuery {
+  struct CArrayFieldElement world_addresses;
+  struct CArrayc_char namespaces;
+  struct CArrayFieldElement player_addresses;
+  struct Pagination pagination;
+} PlayerAchievementQuery;
 
-typedef struct PagePlayerAchievementEntry {
-  struct CArrayPlayerAchievementEntry items;
-  struct COptionc_char next_cursor;
-} PagePlayerAchievementEntry;
+typedef struct AggregationQuery {
+  struct CArrayc_char aggregator_ids;
+  struct CArrayc_char entity_ids;
+  struct Pagination pagination;
+} AggregationQuery;
 
-typedef enum ResultPagePlayerAchievementEntry_Tag {
-  OkPagePlayerAchievementEntry,
-  ErrPagePlayerAchievementEntry,
-} ResultPagePlayerAchievementEntry_Tag;
+typedef enum COptionPageEntity_Tag {
+  SomePageEntity,
+  NonePageEntity,
+} COptionPageEntity_Tag;
 
-typedef struct ResultPagePlayerAchievementEntry {
-  ResultPagePlayerAchievementEntry_Tag tag;
+typedef struct COptionPageEntity {
+  COptionPageEntity_Tag tag;
   union {
     struct {
-      struct PagePlayerAchievementEntry ok;
+      struct PageEntity some;
+    };
+  };
+} COptionPageEntity;
+
+typedef enum ResultCOptionPageEntity_Tag {
+  OkCOptionPageEntity,
+  ErrCOptionPageEntity,
+} ResultCOptionPageEntity_Tag;
+
+typedef struct ResultCOptionPageEntity {
+  ResultCOptionPageEntity_Tag tag;
+  union {
+    struct {
+      struct COptionPageEntity ok;
     };
     struct {
       struct Error err;
     };
   };
-} ResultPagePlayerAchievementEntry;
+} ResultCOptionPageEntity;
 
-typedef struct PlayerAchievementQuery {
-  struct CArrayFieldElement world_addresses;
-  struct CArrayc_char namespaces;
-  struct CArrayFieldElement player_addresses;
-  struct Pagination pagination;
-} PlayerAchievementQuery;
+typedef struct CArrayStruct {
+  struct Struct *data;
+  uintptr_t data_len;
+} CArrayStruct;
 
-typedef struct AchievementProgression {
-  const char *id;
-  const char *achievement_id;
-  const char *task_id;
+typedef struct Entity {
   struct FieldElement world_address;
-  const char *namespace_;
-  struct FieldElement player_id;
-  uint32_t count;
-  bool completed;
-  struct COptionu64 completed_at;
+  struct FieldElement hashed_keys;
+  struct CArrayStruct models;
   uint64_t created_at;
   uint64_t updated_at;
-} AchievementProgression;
+  uint64_t executed_at;
+} Entity;
 
-typedef struct CArrayActivity {
-  struct Activity *data;
-  uintptr_t data_len;
-} CArrayActivity;
+typedef enum COptionEntity_Tag {
+  SomeEntity,
+  NoneEntity,
+} COptionEntity_Tag;
 
-typedef struct PageActivity {
-  struct CArrayActivity items;
-  struct COptionc_char next_cursor;
-} PageActivity;
+typedef struct COptionEntity {
+  COptionEntity_Tag tag;
+  union {
+    struct {
+      struct Entity some;
+    };
+  };
+} COptionEntity;
 
-typedef enum ResultPageActivity_Tag {
-  OkPageActivity,
-  ErrPageActivity,
-} ResultPageActivity_Tag;
+typedef enum ResultCOptionEntity_Tag {
+  OkCOptionEntity,
+  ErrCOptionEntity,
+} ResultCOptionEntity_Tag;
 
-typedef struct ResultPageActivity {
-  ResultPageActivity_Tag tag;
+typedef struct ResultCOptionEntity {
+  ResultCOptionEntity_Tag tag;
   union {
     struct {
-      struct PageActivity ok;
+      struct COptionEntity ok;
     };
     struct {
       struct Error err;
     };
   };
-} ResultPageActivity;
+} ResultCOptionEntity;
 
-typedef struct ActivityQuery {
-  struct CArrayFieldElement world_addresses;
-  struct CArrayc_char namespaces;
-  struct CArrayFieldElement caller_addresses;
-  struct COptionu64 from_time;
-  struct COptionu64 to_time;
-  struct Pagination pagination;
-} ActivityQuery;
+typedef enum COptionPageController_Tag {
+  SomePageController,
+  NonePageController,
+} COptionPageController_Tag;
 
-typedef struct CArrayActionCount {
-  struct ActionCount *data;
-  uintptr_t data_len;
-} CArrayActionCount;
+typedef struct COptionPageController {
+  COptionPageController_Tag tag;
+  union {
+    struct {
+      struct PageController some;
+    };
+  };
+} COptionPageController;
 
-typedef struct Activity {
-  const char *id;
-  struct FieldElement world_address;
-  const char *namespace_;
-  struct FieldElement caller_address;
-  uint64_t session_start;
-  uint64_t session_end;
-  uint32_t action_count;
-  struct CArrayActionCount actions;
-  uint64_t updated_at;
-} Activity;
+typedef enum ResultCOptionPageController_Tag {
+  OkCOptionPageController,
+  ErrCOptionPageController,
+} ResultCOptionPageController_Tag;
 
-typedef struct Event {
-  struct CArrayFieldElement keys;
-  struct CArrayFieldElement data;
-  struct FieldElement transaction_hash;
-} Event;
+typedef struct ResultCOptionPageController {
+  ResultCOptionPageController_Tag tag;
+  union {
+    struct {
+      struct COptionPageController ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultCOptionPageController;
+
+typedef struct Controller {
+  struct FieldElement address;
+  const char *username;
+  uint64_t deployed_at_timestamp;
+} Controller;
+
+typedef enum COptionController_Tag {
+  SomeController,
+  NoneController,
+} COptionController_Tag;
+
+typedef struct COptionController {
+  COptionController_Tag tag;
+  union {
+    struct {
+      struct Controller some;
+    };
+  };
+} COptionController;
+
+typedef enum ResultCOptionController_Tag {
+  OkCOptionController,
+  ErrCOptionController,
+} ResultCOptionController_Tag;
+
+typedef struct ResultCOptionController {
+  ResultCOptionController_Tag tag;
+  union {
+    struct {
+      struct COptionController ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultCOptionController;
 
 typedef struct CArrayToken {
   struct Token *data;
@@ -821,39 +1010,36 @@ typedef struct PageToken {
   struct COptionc_char next_cursor;
 } PageToken;
 
-typedef enum ResultPageToken_Tag {
-  OkPageToken,
-  ErrPageToken,
-} ResultPageToken_Tag;
+typedef enum COptionPageToken_Tag {
+  SomePageToken,
+  NonePageToken,
+} COptionPageToken_Tag;
 
-typedef struct ResultPageToken {
-  ResultPageToken_Tag tag;
+typedef struct COptionPageToken {
+  COptionPageToken_Tag tag;
   union {
     struct {
-      struct PageToken ok;
+      struct PageToken some;
+    };
+  };
+} COptionPageToken;
+
+typedef enum ResultCOptionPageToken_Tag {
+  OkCOptionPageToken,
+  ErrCOptionPageToken,
+} ResultCOptionPageToken_Tag;
+
+typedef struct ResultCOptionPageToken {
+  ResultCOptionPageToken_Tag tag;
+  union {
+    struct {
+      struct COptionPageToken ok;
     };
     struct {
       struct Error err;
     };
   };
-} ResultPageToken;
-
-typedef struct CArrayU256 {
-  struct U256 *data;
-  uintptr_t data_len;
-} CArrayU256;
-
-typedef struct CArrayAttributeFilter {
-  struct AttributeFilter *data;
-  uintptr_t data_len;
-} CArrayAttributeFilter;
-
-typedef struct TokenQuery {
-  struct CArrayFieldElement contract_addresses;
-  struct CArrayU256 token_ids;
-  struct CArrayAttributeFilter attribute_filters;
-  struct Pagination pagination;
-} TokenQuery;
+} ResultCOptionPageToken;
 
 typedef enum COptionU256_Tag {
   SomeU256,
@@ -879,6 +1065,37 @@ typedef struct Token {
   struct COptionU256 total_supply;
 } Token;
 
+typedef enum COptionToken_Tag {
+  SomeToken,
+  NoneToken,
+} COptionToken_Tag;
+
+typedef struct COptionToken {
+  COptionToken_Tag tag;
+  union {
+    struct {
+      struct Token some;
+    };
+  };
+} COptionToken;
+
+typedef enum ResultCOptionToken_Tag {
+  OkCOptionToken,
+  ErrCOptionToken,
+} ResultCOptionToken_Tag;
+
+typedef struct ResultCOptionToken {
+  ResultCOptionToken_Tag tag;
+  union {
+    struct {
+      struct COptionToken ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultCOptionToken;
+
 typedef struct CArrayTokenBalance {
   struct TokenBalance *data;
   uintptr_t data_len;
@@ -889,94 +1106,74 @@ typedef struct PageTokenBalance {
   struct COptionc_char next_cursor;
 } PageTokenBalance;
 
-typedef enum ResultPageTokenBalance_Tag {
-  OkPageTokenBalance,
-  ErrPageTokenBalance,
-} ResultPageTokenBalance_Tag;
+typedef enum COptionPageTokenBalance_Tag {
+  SomePageTokenBalance,
+  NonePageTokenBalance,
+} COptionPageTokenBalance_Tag;
 
-typedef struct ResultPageTokenBalance {
-  ResultPageTokenBalance_Tag tag;
+typedef struct COptionPageTokenBalance {
+  COptionPageTokenBalance_Tag tag;
   union {
     struct {
-      struct PageTokenBalance ok;
-    };
-    struct {
-      struct Error err;
+      struct PageTokenBalance some;
     };
   };
-} ResultPageTokenBalance;
-
-typedef struct TokenBalanceQuery {
-  struct CArrayFieldElement contract_addresses;
-  struct CArrayFieldElement account_addresses;
-  struct CArrayU256 token_ids;
-  struct Pagination pagination;
-} TokenBalanceQuery;
-
-typedef struct CArrayTokenContract {
-  struct TokenContract *data;
-  uintptr_t data_len;
-} CArrayTokenContract;
-
-typedef struct PageTokenContract {
-  struct CArrayTokenContract items;
-  struct COptionc_char next_cursor;
-} PageTokenContract;
+} COptionPageTokenBalance;
 
-typedef enum ResultPageTokenContract_Tag {
-  OkPageTokenContract,
-  ErrPageTokenContract,
-} ResultPageTokenContract_Tag;
+typedef enum ResultCOptionPageTokenBalance_Tag {
+  OkCOptionPageTokenBalance,
+  ErrCOptionPageTokenBalance,
+} ResultCOptionPageTokenBalance_Tag;
 
-typedef struct ResultPageTokenContract {
-  ResultPageTokenContract_Tag tag;
+typedef struct ResultCOptionPageTokenBalance {
+  ResultCOptionPageTokenBalance_Tag tag;
   union {
     struct {
-      struct PageTokenContract ok;
+      struct COptionPageTokenBalance ok;
     };
     struct {
       struct Error err;
     };
   };
-} ResultPageTokenContract;
+} ResultCOptionPageTokenBalance;
 
-typedef struct CArrayContractType {
-  enum ContractType *data;
-  uintptr_t data_len;
-} CArrayContractType;
+typedef struct TokenBalance {
+  struct U256 balance;
+  struct FieldElement account_address;
+  struct FieldElement contract_address;
+  struct COptionU256 token_id;
+} TokenBalance;
 
-typedef struct TokenContractQuery {
-  struct CArrayFieldElement contract_addresses;
-  struct CArrayContractType contract_types;
-  struct Pagination pagination;
-} TokenContractQuery;
+typedef enum COptionTokenBalance_Tag {
+  SomeTokenBalance,
+  NoneTokenBalance,
+} COptionTokenBalance_Tag;
 
-typedef struct CArrayContract {
-  struct Contract *data;
-  uintptr_t data_len;
-} CArrayContract;
+typedef struct COptionTokenBalance {
+  COptionTokenBalance_Tag tag;
+  union {
+    struct {
+      struct TokenBalance some;
+    };
+  };
+} COptionTokenBalance;
 
-typedef enum ResultCArrayContract_Tag {
-  OkCArrayContract,
-  ErrCArrayContract,
-} ResultCArrayContract_Tag;
+typedef enum ResultCOptionTokenBalance_Tag {
+  OkCOptionTokenBalance,
+  ErrCOptionTokenBalance,
+} ResultCOptionTokenBalance_Tag;
 
-typedef struct ResultCArrayContract {
-  ResultCArrayContract_Tag tag;
+typedef struct ResultCOptionTokenBalance {
+  ResultCOptionTokenBalance_Tag tag;
   union {
     struct {
-      struct CArrayContract ok;
+      struct COptionTokenBalance ok;
     };
     struct {
       struct Error err;
     };
   };
-} ResultCArrayContract;
-
-typedef struct ContractQuery {
-  struct CArrayFieldElement contract_addresses;
-  struct CArrayContractType contract_types;
-} ContractQuery;
+} ResultCOptionTokenBalance;
 
 typedef struct CArrayTokenTransfer {
   struct TokenTransfer *data;
@@ -988,61 +1185,36 @@ typedef struct PageTokenTransfer {
   struct COptionc_char next_cursor;
 } PageTokenTransfer;
 
-typedef enum ResultPageTokenTransfer_Tag {
-  OkPageTokenTransfer,
-  ErrPageTokenTransfer,
-} ResultPageTokenTransfer_Tag;
+typedef enum COptionPageTokenTransfer_Tag {
+  SomePageTokenTransfer,
+  NonePageTokenTransfer,
+} COptionPageTokenTransfer_Tag;
 
-typedef struct ResultPageTokenTransfer {
-  ResultPageTokenTransfer_Tag tag;
+typedef struct COptionPageTokenTransfer {
+  COptionPageTokenTransfer_Tag tag;
   union {
     struct {
-      struct PageTokenTransfer ok;
+      struct PageTokenTransfer some;
+    };
+  };
+} COptionPageTokenTransfer;
+
+typedef enum ResultCOptionPageTokenTransfer_Tag {
+  OkCOptionPageTokenTransfer,
+  ErrCOptionPageTokenTransfer,
+} ResultCOptionPageTokenTransfer_Tag;
+
+typedef struct ResultCOptionPageTokenTransfer {
+  ResultCOptionPageTokenTransfer_Tag tag;
+  union {
+    struct {
+      struct COptionPageTokenTransfer ok;
     };
     struct {
       struct Error err;
     };
   };
-} ResultPageTokenTransfer;
-
-typedef struct TokenTransferQuery {
-  struct CArrayFieldElement contract_addresses;
-  struct CArrayFieldElement account_addresses;
-  struct CArrayU256 token_ids;
-  struct Pagination pagination;
-} TokenTransferQuery;
-
-typedef enum COptionFieldElement_Tag {
-  SomeFieldElement,
-  NoneFieldElement,
-} COptionFieldElement_Tag;
-
-typedef struct COptionFieldElement {
-  COptionFieldElement_Tag tag;
-  union {
-    struct {
-      struct FieldElement some;
-    };
-  };
-} COptionFieldElement;
-
-typedef struct Contract {
-  struct FieldElement contract_address;
-  enum ContractType contract_type;
-  struct COptionu64 head;
-  struct COptionu64 tps;
-  struct COptionu64 last_block_timestamp;
-  struct COptionFieldElement last_pending_block_tx;
-  uint64_t updated_at;
-  uint64_t created_at;
-} Contract;
-
-typedef struct TokenBalance {
-  struct U256 balance;
-  struct FieldElement account_address;
-  struct FieldElement contract_address;
-  struct COptionU256 token_id;
-} TokenBalance;
+} ResultCOptionPageTokenTransfer;
 
 typedef struct TokenTransfer {
   const char *id;
@@ -1055,202 +1227,257 @@ typedef struct TokenTransfer {
   struct COptionc_char event_id;
 } TokenTransfer;
 
-typedef struct CArrayTableSearchResults {
-  struct TableSearchResults *data;
-  uintptr_t data_len;
-} CArrayTableSearchResults;
+typedef enum COptionTokenTransfer_Tag {
+  SomeTokenTransfer,
+  NoneTokenTransfer,
+} COptionTokenTransfer_Tag;
 
-typedef struct SearchResponse {
-  uint32_t total;
-  struct CArrayTableSearchResults results;
-} SearchResponse;
+typedef struct COptionTokenTransfer {
+  COptionTokenTransfer_Tag tag;
+  union {
+    struct {
+      struct TokenTransfer some;
+    };
+  };
+} COptionTokenTransfer;
 
-typedef enum ResultSearchResponse_Tag {
-  OkSearchResponse,
-  ErrSearchResponse,
-} ResultSearchResponse_Tag;
+typedef enum ResultCOptionTokenTransfer_Tag {
+  OkCOptionTokenTransfer,
+  ErrCOptionTokenTransfer,
+} ResultCOptionTokenTransfer_Tag;
 
-typedef struct ResultSearchResponse {
-  ResultSearchResponse_Tag tag;
+typedef struct ResultCOptionTokenTransfer {
+  ResultCOptionTokenTransfer_Tag tag;
   union {
     struct {
-      struct SearchResponse ok;
+      struct COptionTokenTransfer ok;
     };
     struct {
       struct Error err;
     };
   };
-} ResultSearchResponse;
+} ResultCOptionTokenTransfer;
 
-typedef struct SearchQuery {
-  const char *query;
-  uint32_t limit;
-} SearchQuery;
+typedef struct CArrayTransaction {
+  struct Transaction *data;
+  uintptr_t data_len;
+} CArrayTransaction;
 
-typedef enum ResultCArrayFieldElement_Tag {
-  OkCArrayFieldElement,
-  ErrCArrayFieldElement,
-} ResultCArrayFieldElement_Tag;
+typedef struct PageTransaction {
+  struct CArrayTransaction items;
+  struct COptionc_char next_cursor;
+} PageTransaction;
 
-typedef struct ResultCArrayFieldElement {
-  ResultCArrayFieldElement_Tag tag;
+typedef enum COptionPageTransaction_Tag {
+  SomePageTransaction,
+  NonePageTransaction,
+} COptionPageTransaction_Tag;
+
+typedef struct COptionPageTransaction {
+  COptionPageTransaction_Tag tag;
   union {
     struct {
-      struct CArrayFieldElement ok;
-    };
-    struct {
-      struct Error err;
+      struct PageTransaction some;
     };
   };
-} ResultCArrayFieldElement;
+} COptionPageTransaction;
 
-typedef enum ResultFieldElement_Tag {
-  OkFieldElement,
-  ErrFieldElement,
-} ResultFieldElement_Tag;
+typedef enum ResultCOptionPageTransaction_Tag {
+  OkCOptionPageTransaction,
+  ErrCOptionPageTransaction,
+} ResultCOptionPageTransaction_Tag;
 
-typedef struct ResultFieldElement {
-  ResultFieldElement_Tag tag;
+typedef struct ResultCOptionPageTransaction {
+  ResultCOptionPageTransaction_Tag tag;
   union {
     struct {
-      struct FieldElement ok;
+      struct COptionPageTransaction ok;
     };
     struct {
       struct Error err;
     };
   };
-} ResultFieldElement;
+} ResultCOptionPageTransaction;
 
-typedef struct Signature {
-  /**
-   * The `r` value of a signature
-   */
-  struct FieldElement r;
-  /**
-   * The `s` value of a signature
-   */
-  struct FieldElement s;
-} Signature;
+typedef struct CArrayTransactionCall {
+  struct TransactionCall *data;
+  uintptr_t data_len;
+} CArrayTransactionCall;
 
-typedef enum ResultSignature_Tag {
-  OkSignature,
-  ErrSignature,
-} ResultSignature_Tag;
+typedef struct Transaction {
+  struct FieldElement transaction_hash;
+  struct FieldElement sender_address;
+  struct CArrayFieldElement calldata;
+  struct FieldElement max_fee;
+  struct CArrayFieldElement signature;
+  struct FieldElement nonce;
+  uint64_t block_number;
+  const char *transaction_type;
+  uint64_t block_timestamp;
+  struct CArrayTransactionCall calls;
+  struct CArrayFieldElement unique_models;
+} Transaction;
 
-typedef struct ResultSignature {
-  ResultSignature_Tag tag;
+typedef enum COptionTransaction_Tag {
+  SomeTransaction,
+  NoneTransaction,
+} COptionTransaction_Tag;
+
+typedef struct COptionTransaction {
+  COptionTransaction_Tag tag;
   union {
     struct {
-      struct Signature ok;
-    };
-    struct {
-      struct Error err;
+      struct Transaction some;
     };
   };
-} ResultSignature;
+} COptionTransaction;
 
-typedef enum ResultProvider_Tag {
-  OkProvider,
-  ErrProvider,
-} ResultProvider_Tag;
+typedef enum ResultCOptionTransaction_Tag {
+  OkCOptionTransaction,
+  ErrCOptionTransaction,
+} ResultCOptionTransaction_Tag;
 
-typedef struct ResultProvider {
-  ResultProvider_Tag tag;
+typedef struct ResultCOptionTransaction {
+  ResultCOptionTransaction_Tag tag;
   union {
     struct {
-      struct Provider *ok;
+      struct COptionTransaction ok;
     };
     struct {
       struct Error err;
     };
   };
-} ResultProvider;
+} ResultCOptionTransaction;
 
-typedef enum ResultAccount_Tag {
-  OkAccount,
-  ErrAccount,
-} ResultAccount_Tag;
+typedef struct CArrayActivity {
+  struct Activity *data;
+  uintptr_t data_len;
+} CArrayActivity;
 
-typedef struct ResultAccount {
-  ResultAccount_Tag tag;
+typedef struct PageActivity {
+  struct CArrayActivity items;
+  struct COptionc_char next_cursor;
+} PageActivity;
+
+typedef enum COptionPageActivity_Tag {
+  SomePageActivity,
+  NonePageActivity,
+} COptionPageActivity_Tag;
+
+typedef struct COptionPageActivity {
+  COptionPageActivity_Tag tag;
   union {
     struct {
-      struct Account *ok;
+      struct PageActivity some;
+    };
+  };
+} COptionPageActivity;
+
+typedef enum ResultCOptionPageActivity_Tag {
+  OkCOptionPageActivity,
+  ErrCOptionPageActivity,
+} ResultCOptionPageActivity_Tag;
+
+typedef struct ResultCOptionPageActivity {
+  ResultCOptionPageActivity_Tag tag;
+  union {
+    struct {
+      struct COptionPageActivity ok;
     };
     struct {
       struct Error err;
     };
   };
-} ResultAccount;
+} ResultCOptionPageActivity;
 
-typedef struct Call {
-  struct FieldElement to;
-  const char *selector;
-  struct CArrayFieldElement calldata;
-} Call;
+typedef struct CArrayActionCount {
+  struct ActionCount *data;
+  uintptr_t data_len;
+} CArrayActionCount;
 
-/**
- * Block hash, number or tag
- */
-typedef enum BlockId_Tag {
-  Hash,
-  Number,
-  BlockTag_,
-} BlockId_Tag;
+typedef struct Activity {
+  const char *id;
+  struct FieldElement world_address;
+  const char *namespace_;
+  struct FieldElement caller_address;
+  uint64_t session_start;
+  uint64_t session_end;
+  uint32_t action_count;
+  struct CArrayActionCount actions;
+  uint64_t updated_at;
+} Activity;
 
-typedef struct BlockId {
-  BlockId_Tag tag;
+typedef enum COptionActivity_Tag {
+  SomeActivity,
+  NoneActivity,
+} COptionActivity_Tag;
+
+typedef struct COptionActivity {
+  COptionActivity_Tag tag;
   union {
     struct {
-      struct FieldElement hash;
+      struct Activity some;
     };
+  };
+} COptionActivity;
+
+typedef enum ResultCOptionActivity_Tag {
+  OkCOptionActivity,
+  ErrCOptionActivity,
+} ResultCOptionActivity_Tag;
+
+typedef struct ResultCOptionActivity {
+  ResultCOptionActivity_Tag tag;
+  union {
     struct {
-      uint64_t number;
+      struct COptionActivity ok;
     };
     struct {
-      enum BlockTag block_tag;
+      struct Error err;
     };
   };
-} BlockId;
-
-typedef struct Controller {
-  struct FieldElement address;
-  const char *username;
-  uint64_t deployed_at_timestamp;
-} Controller;
-
-typedef struct OrderBy {
-  const char *field;
-  enum OrderDirection direction;
-} OrderBy;
+} ResultCOptionActivity;
 
-typedef struct CArrayModel {
-  struct Model *data;
+typedef struct CArrayAchievement {
+  struct Achievement *data;
   uintptr_t data_len;
-} CArrayModel;
+} CArrayAchievement;
 
-typedef struct World {
-  struct FieldElement world_address;
-  struct CArrayModel models;
-} World;
+typedef struct PageAchievement {
+  struct CArrayAchievement items;
+  struct COptionc_char next_cursor;
+} PageAchievement;
 
-typedef struct TransactionCall {
-  struct FieldElement contract_address;
-  const char *entrypoint;
-  struct CArrayFieldElement calldata;
-  enum CallType call_type;
-  struct FieldElement caller_address;
-} TransactionCall;
+typedef enum COptionPageAchievement_Tag {
+  SomePageAchievement,
+  NonePageAchievement,
+} COptionPageAchievement_Tag;
 
-typedef struct CArrayMember {
-  struct Member *data;
-  uintptr_t data_len;
-} CArrayMember;
+typedef struct COptionPageAchievement {
+  COptionPageAchievement_Tag tag;
+  union {
+    struct {
+      struct PageAchievement some;
+    };
+  };
+} COptionPageAchievement;
 
-typedef struct Struct {
-  const char *name;
-  struct CArrayMember children;
-} Struct;
+typedef enum ResultCOptionPageAchievement_Tag {
+  OkCOptionPageAchievement,
+  ErrCOptionPageAchievement,
+} ResultCOptionPageAchievement_Tag;
+
+typedef struct ResultCOptionPageAchievement {
+  ResultCOptionPageAchievement_Tag tag;
+  union {
+    struct {
+      struct COptionPageAchievement ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultCOptionPageAchievement;
 
 typedef struct CArrayAchievementTask {
   struct AchievementTask *data;
@@ -1279,13 +1506,85 @@ typedef struct Achievement {
   uint64_t updated_at;
 } Achievement;
 
-typedef struct PlayerAchievementStats {
-  uint32_t total_points;
-  uint32_t completed_achievements;
-  uint32_t total_achievements;
-  double completion_percentage;
-  struct COptionu64 last_achievement_at;
-  uint64_t created_at;
+typedef enum COptionAchievement_Tag {
+  SomeAchievement,
+  NoneAchievement,
+} COptionAchievement_Tag;
+
+typedef struct COptionAchievement {
+  COptionAchievement_Tag tag;
+  union {
+    struct {
+      struct Achievement some;
+    };
+  };
+} COptionAchievement;
+
+typedef enum ResultCOptionAchievement_Tag {
+  OkCOptionAchievement,
+  ErrCOptionAchievement,
+} ResultCOptionAchievement_Tag;
+
+typedef struct ResultCOptionAchievement {
+  ResultCOptionAchievement_Tag tag;
+  union {
+    struct {
+      struct COptionAchievement ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultCOptionAchievement;
+
+typedef struct CArrayPlayerAchievementEntry {
+  struct PlayerAchievementEntry *data;
+  uintptr_t data_len;
+} CArrayPlayerAchievementEntry;
+
+typedef struct PagePlayerAchievementEntry {
+  struct CArrayPlayerAchievementEntry items;
+  struct COptionc_char next_cursor;
+} PagePlayerAchievementEntry;
+
+typedef enum COptionPagePlayerAchievementEntry_Tag {
+  SomePagePlayerAchievementEntry,
+  NonePagePlayerAchievementEntry,
+} COptionPagePlayerAchievementEntry_Tag;
+
+typedef struct COptionPagePlayerAchievementEntry {
+  COptionPagePlayerAchievementEntry_Tag tag;
+  union {
+    struct {
+      struct PagePlayerAchievementEntry some;
+    };
+  };
+} COptionPagePlayerAchievementEntry;
+
+typedef enum ResultCOptionPagePlayerAchievementEntry_Tag {
+  OkCOptionPagePlayerAchievementEntry,
+  ErrCOptionPagePlayerAchievementEntry,
+} ResultCOptionPagePlayerAchievementEntry_Tag;
+
+typedef struct ResultCOptionPagePlayerAchievementEntry {
+  ResultCOptionPagePlayerAchievementEntry_Tag tag;
+  union {
+    struct {
+      struct COptionPagePlayerAchievementEntry ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultCOptionPagePlayerAchievementEntry;
+
+typedef struct PlayerAchievementStats {
+  uint32_t total_points;
+  uint32_t completed_achievements;
+  uint32_t total_achievements;
+  double completion_percentage;
+  struct COptionu64 last_achievement_at;
+  uint64_t created_at;
   uint64_t updated_at;
 } PlayerAchievementStats;
 
@@ -1300,36 +1599,272 @@ typedef struct PlayerAchievementEntry {
   struct CArrayPlayerAchievementProgress achievements;
 } PlayerAchievementEntry;
 
-typedef struct ActionCount {
-  const char *action_name;
-  uint32_t count;
-} ActionCount;
+typedef enum COptionPlayerAchievementEntry_Tag {
+  SomePlayerAchievementEntry,
+  NonePlayerAchievementEntry,
+} COptionPlayerAchievementEntry_Tag;
 
-typedef struct AttributeFilter {
-  const char *trait_name;
-  const char *trait_value;
-} AttributeFilter;
+typedef struct COptionPlayerAchievementEntry {
+  COptionPlayerAchievementEntry_Tag tag;
+  union {
+    struct {
+      struct PlayerAchievementEntry some;
+    };
+  };
+} COptionPlayerAchievementEntry;
 
-typedef struct TokenContract {
-  struct FieldElement contract_address;
+typedef enum ResultCOptionPlayerAchievementEntry_Tag {
+  OkCOptionPlayerAchievementEntry,
+  ErrCOptionPlayerAchievementEntry,
+} ResultCOptionPlayerAchievementEntry_Tag;
+
+typedef struct ResultCOptionPlayerAchievementEntry {
+  ResultCOptionPlayerAchievementEntry_Tag tag;
+  union {
+    struct {
+      struct COptionPlayerAchievementEntry ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultCOptionPlayerAchievementEntry;
+
+typedef struct CArrayAggregationEntry {
+  struct AggregationEntry *data;
+  uintptr_t data_len;
+} CArrayAggregationEntry;
+
+typedef struct PageAggregationEntry {
+  struct CArrayAggregationEntry items;
+  struct COptionc_char next_cursor;
+} PageAggregationEntry;
+
+typedef enum COptionPageAggregationEntry_Tag {
+  SomePageAggregationEntry,
+  NonePageAggregationEntry,
+} COptionPageAggregationEntry_Tag;
+
+typedef struct COptionPageAggregationEntry {
+  COptionPageAggregationEntry_Tag tag;
+  union {
+    struct {
+      struct PageAggregationEntry some;
+    };
+  };
+} COptionPageAggregationEntry;
+
+typedef enum ResultCOptionPageAggregationEntry_Tag {
+  OkCOptionPageAggregationEntry,
+  ErrCOptionPageAggregationEntry,
+} ResultCOptionPageAggregationEntry_Tag;
+
+typedef struct ResultCOptionPageAggregationEntry {
+  ResultCOptionPageAggregationEntry_Tag tag;
+  union {
+    struct {
+      struct COptionPageAggregationEntry ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultCOptionPageAggregationEntry;
+
+typedef struct AggregationEntry {
+  const char *id;
+  const char *aggregator_id;
+  const char *entity_id;
+  struct U256 value;
+  const char *display_value;
+  uint64_t position;
+  const char *model_id;
+  uint64_t created_at;
+  uint64_t updated_at;
+} AggregationEntry;
+
+typedef enum COptionAggregationEntry_Tag {
+  SomeAggregationEntry,
+  NoneAggregationEntry,
+} COptionAggregationEntry_Tag;
+
+typedef struct COptionAggregationEntry {
+  COptionAggregationEntry_Tag tag;
+  union {
+    struct {
+      struct AggregationEntry some;
+    };
+  };
+} COptionAggregationEntry;
+
+typedef enum ResultCOptionAggregationEntry_Tag {
+  OkCOptionAggregationEntry,
+  ErrCOptionAggregationEntry,
+} ResultCOptionAggregationEntry_Tag;
+
+typedef struct ResultCOptionAggregationEntry {
+  ResultCOptionAggregationEntry_Tag tag;
+  union {
+    struct {
+      struct COptionAggregationEntry ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultCOptionAggregationEntry;
+
+typedef struct CArrayWorld {
+  struct World *data;
+  uintptr_t data_len;
+} CArrayWorld;
+
+typedef enum ResultCArrayWorld_Tag {
+  OkCArrayWorld,
+  ErrCArrayWorld,
+} ResultCArrayWorld_Tag;
+
+typedef struct ResultCArrayWorld {
+  ResultCArrayWorld_Tag tag;
+  union {
+    struct {
+      struct CArrayWorld ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultCArrayWorld;
+
+typedef enum ResultWorldSchema_Tag {
+  OkWorldSchema,
+  ErrWorldSchema,
+} ResultWorldSchema_Tag;
+
+typedef struct ResultWorldSchema {
+  ResultWorldSchema_Tag tag;
+  union {
+    struct {
+      struct WorldSchema *ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultWorldSchema;
+
+typedef struct CArrayModelSchema {
+  struct ModelSchema *data;
+  uintptr_t data_len;
+} CArrayModelSchema;
+
+typedef struct CArrayMemberSchema {
+  struct MemberSchema *data;
+  uintptr_t data_len;
+} CArrayMemberSchema;
+
+/**
+ * Model or event registered in a world, along with the class declaring it
+ */
+typedef struct ModelSchema {
+  const char *tag;
+  const char *namespace_;
   const char *name;
-  const char *symbol;
-  uint8_t decimals;
-  const char *metadata;
-  const char *token_metadata;
-  struct COptionU256 total_supply;
-} TokenContract;
+  struct FieldElement selector;
+  struct FieldElement class_hash;
+  struct FieldElement contract_address;
+  uint32_t packed_size;
+  uint32_t unpacked_size;
+  bool use_legacy_store;
+  /**
+   * Whether this is an event rather than a model
+   */
+  bool is_event;
+  /**
+   * Key members, in the order they make up the id of an entity
+   */
+  struct CArrayMemberSchema keys;
+  struct CArrayMemberSchema values;
+} ModelSchema;
 
-typedef struct CArraySearchMatch {
-  struct SearchMatch *data;
+typedef enum COptionModelSchema_Tag {
+  SomeModelSchema,
+  NoneModelSchema,
+} COptionModelSchema_Tag;
+
+typedef struct COptionModelSchema {
+  COptionModelSchema_Tag tag;
+  union {
+    struct {
+      struct ModelSchema some;
+    };
+  };
+} COptionModelSchema;
+
+typedef enum ResultPageTransaction_Tag {
+  OkPageTransaction,
+  ErrPageTransaction,
+} ResultPageTransaction_Tag;
+
+typedef struct ResultPageTransaction {
+  ResultPageTransaction_Tag tag;
+  union {
+    struct {
+      struct PageTransaction ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultPageTransaction;
+
+typedef enum ResultSubscription_Tag {
+  OkSubscription,
+  ErrSubscription,
+} ResultSubscription_Tag;
+
+typedef struct ResultSubscription {
+  ResultSubscription_Tag tag;
+  union {
+    struct {
+      struct Subscription *ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultSubscription;
+
+typedef enum EntityChange_Tag {
+  Created,
+  Updated,
+  Deleted,
+} EntityChange_Tag;
+
+typedef struct EntityChange {
+  EntityChange_Tag tag;
+  union {
+    struct {
+      struct Entity created;
+    };
+    struct {
+      struct Entity updated;
+    };
+    struct {
+      struct Entity deleted;
+    };
+  };
+} EntityChange;
+
+typedef struct CArrayMember {
+  struct Member *data;
   uintptr_t data_len;
-} CArraySearchMatch;
+} CArrayMember;
 
-typedef struct TableSearchResults {
-  const char *table;
-  uint32_t count;
-  struct CArraySearchMatch matches;
-} TableSearchResults;
+typedef struct Struct {
+  const char *name;
+  struct CArrayMember children;
+} Struct;
 
 typedef struct CArrayEnumOption {
   struct EnumOption *data;
@@ -1389,795 +1924,2972 @@ typedef struct Ty {
   };
 } Ty;
 
-typedef struct Model {
-  struct FieldElement world_address;
-  struct Ty schema;
-  const char *namespace_;
-  const char *name;
-  struct FieldElement selector;
-  uint32_t packed_size;
-  uint32_t unpacked_size;
-  struct FieldElement class_hash;
-  struct FieldElement contract_address;
-  const char *layout;
-  bool use_legacy_store;
-} Model;
+typedef enum COptionTy_Tag {
+  SomeTy,
+  NoneTy,
+} COptionTy_Tag;
 
-typedef struct Member {
-  const char *name;
-  struct Ty *ty;
-  bool key;
-} Member;
+typedef struct COptionTy {
+  COptionTy_Tag tag;
+  union {
+    struct {
+      struct Ty some;
+    };
+  };
+} COptionTy;
 
-typedef struct AchievementTask {
+typedef struct FieldChange {
+  struct FieldElement hashed_keys;
+  enum ChangeKind kind;
+  const char *model;
+  const char *path;
+  struct COptionTy old_value;
+  struct COptionTy new_value;
+} FieldChange;
+
+typedef enum Resultbool_Tag {
+  Okbool,
+  Errbool,
+} Resultbool_Tag;
+
+typedef struct Resultbool {
+  Resultbool_Tag tag;
+  union {
+    struct {
+      bool ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} Resultbool;
+
+typedef enum ResultPageAggregationEntry_Tag {
+  OkPageAggregationEntry,
+  ErrPageAggregationEntry,
+} ResultPageAggregationEntry_Tag;
+
+typedef struct ResultPageAggregationEntry {
+  ResultPageAggregationEntry_Tag tag;
+  union {
+    struct {
+      struct PageAggregationEntry ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultPageAggregationEntry;
+
+typedef enum ResultPageAchievement_Tag {
+  OkPageAchievement,
+  ErrPageAchievement,
+} ResultPageAchievement_Tag;
+
+typedef struct ResultPageAchievement {
+  ResultPageAchievement_Tag tag;
+  union {
+    struct {
+      struct PageAchievement ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultPageAchievement;
+
+typedef enum ResultPagePlayerAchievementEntry_Tag {
+  OkPagePlayerAchievementEntry,
+  ErrPagePlayerAchievementEntry,
+} ResultPagePlayerAchievementEntry_Tag;
+
+typedef struct ResultPagePlayerAchievementEntry {
+  ResultPagePlayerAchievementEntry_Tag tag;
+  union {
+    struct {
+      struct PagePlayerAchievementEntry ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultPagePlayerAchievementEntry;
+
+typedef struct AchievementProgression {
+  const char *id;
+  const char *achievement_id;
   const char *task_id;
-  const char *description;
-  uint32_t total;
-  uint32_t total_completions;
-  double completion_rate;
+  struct FieldElement world_address;
+  const char *namespace_;
+  struct FieldElement player_id;
+  uint32_t count;
+  bool completed;
+  struct COptionu64 completed_at;
   uint64_t created_at;
-} AchievementTask;
+  uint64_t updated_at;
+} AchievementProgression;
 
-typedef struct CArrayTaskProgress {
-  struct TaskProgress *data;
+typedef enum ResultPageActivity_Tag {
+  OkPageActivity,
+  ErrPageActivity,
+} ResultPageActivity_Tag;
+
+typedef struct ResultPageActivity {
+  ResultPageActivity_Tag tag;
+  union {
+    struct {
+      struct PageActivity ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultPageActivity;
+
+typedef struct Event {
+  struct CArrayFieldElement keys;
+  struct CArrayFieldElement data;
+  struct FieldElement transaction_hash;
+} Event;
+
+typedef struct CArrayTypeChange {
+  struct TypeChange *data;
   uintptr_t data_len;
-} CArrayTaskProgress;
+} CArrayTypeChange;
 
-typedef struct PlayerAchievementProgress {
-  struct Achievement achievement;
-  struct CArrayTaskProgress task_progress;
-  bool completed;
-  double progress_percentage;
-} PlayerAchievementProgress;
+/**
+ * Model registered or upgraded in a world
+ */
+typedef struct SchemaUpgrade {
+  struct FieldElement world_address;
+  /**
+   * Schema before the upgrade, none when the model was just registered
+   */
+  struct COptionModelSchema old_schema;
+  struct ModelSchema new_schema;
+  /**
+   * Structural changes of the schema, empty when only the class of the model changed
+   */
+  struct CArrayTypeChange changes;
+} SchemaUpgrade;
 
-typedef struct CArraySearchMatchField {
-  struct SearchMatchField *data;
+typedef enum ResultPageToken_Tag {
+  OkPageToken,
+  ErrPageToken,
+} ResultPageToken_Tag;
+
+typedef struct ResultPageToken {
+  ResultPageToken_Tag tag;
+  union {
+    struct {
+      struct PageToken ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultPageToken;
+
+typedef enum ResultPageTokenBalance_Tag {
+  OkPageTokenBalance,
+  ErrPageTokenBalance,
+} ResultPageTokenBalance_Tag;
+
+typedef struct ResultPageTokenBalance {
+  ResultPageTokenBalance_Tag tag;
+  union {
+    struct {
+      struct PageTokenBalance ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultPageTokenBalance;
+
+typedef struct CArrayTokenContract {
+  struct TokenContract *data;
   uintptr_t data_len;
-} CArraySearchMatchField;
+} CArrayTokenContract;
 
-typedef enum COptionf64_Tag {
-  Somef64,
-  Nonef64,
-} COptionf64_Tag;
+typedef struct PageTokenContract {
+  struct CArrayTokenContract items;
+  struct COptionc_char next_cursor;
+} PageTokenContract;
+
+typedef enum ResultPageTokenContract_Tag {
+  OkPageTokenContract,
+  ErrPageTokenContract,
+} ResultPageTokenContract_Tag;
+
+typedef struct ResultPageTokenContract {
+  ResultPageTokenContract_Tag tag;
+  union {
+    struct {
+      struct PageTokenContract ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultPageTokenContract;
+
+typedef struct CArrayContractType {
+  enum ContractType *data;
+  uintptr_t data_len;
+} CArrayContractType;
+
+typedef struct TokenContractQuery {
+  struct CArrayFieldElement contract_addresses;
+  struct CArrayContractType contract_types;
+  struct Pagination pagination;
+} TokenContractQuery;
+
+typedef struct CArrayContract {
+  struct Contract *data;
+  uintptr_t data_len;
+} CArrayContract;
+
+typedef enum ResultCArrayContract_Tag {
+  OkCArrayContract,
+  ErrCArrayContract,
+} ResultCArrayContract_Tag;
+
+typedef struct ResultCArrayContract {
+  ResultCArrayContract_Tag tag;
+  union {
+    struct {
+      struct CArrayContract ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultCArrayContract;
+
+typedef struct ContractQuery {
+  struct CArrayFieldElement contract_addresses;
+  struct CArrayContractType contract_types;
+} ContractQuery;
+
+typedef enum ResultPageTokenTransfer_Tag {
+  OkPageTokenTransfer,
+  ErrPageTokenTransfer,
+} ResultPageTokenTransfer_Tag;
+
+typedef struct ResultPageTokenTransfer {
+  ResultPageTokenTransfer_Tag tag;
+  union {
+    struct {
+      struct PageTokenTransfer ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultPageTokenTransfer;
+
+typedef enum COptionFieldElement_Tag {
+  SomeFieldElement,
+  NoneFieldElement,
+} COptionFieldElement_Tag;
+
+typedef struct COptionFieldElement {
+  COptionFieldElement_Tag tag;
+  union {
+    struct {
+      struct FieldElement some;
+    };
+  };
+} COptionFieldElement;
+
+typedef struct Contract {
+  struct FieldElement contract_address;
+  enum ContractType contract_type;
+  struct COptionu64 head;
+  struct COptionu64 tps;
+  struct COptionu64 last_block_timestamp;
+  struct COptionFieldElement last_pending_block_tx;
+  uint64_t updated_at;
+  uint64_t created_at;
+} Contract;
+
+typedef struct CArrayTableSearchResults {
+  struct TableSearchResults *data;
+  uintptr_t data_len;
+} CArrayTableSearchResults;
+
+typedef struct SearchResponse {
+  uint32_t total;
+  struct CArrayTableSearchResults results;
+} SearchResponse;
+
+typedef enum ResultSearchResponse_Tag {
+  OkSearchResponse,
+  ErrSearchResponse,
+} ResultSearchResponse_Tag;
+
+typedef struct ResultSearchResponse {
+  ResultSearchResponse_Tag tag;
+  union {
+    struct {
+      struct SearchResponse ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultSearchResponse;
+
+typedef struct SearchQuery {
+  const char *query;
+  uint32_t limit;
+} SearchQuery;
+
+typedef enum ResultCArrayFieldElement_Tag {
+  OkCArrayFieldElement,
+  ErrCArrayFieldElement,
+} ResultCArrayFieldElement_Tag;
+
+typedef struct ResultCArrayFieldElement {
+  ResultCArrayFieldElement_Tag tag;
+  union {
+    struct {
+      struct CArrayFieldElement ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultCArrayFieldElement;
+
+typedef enum ResultFieldElement_Tag {
+  OkFieldElement,
+  ErrFieldElement,
+} ResultFieldElement_Tag;
+
+typedef struct ResultFieldElement {
+  ResultFieldElement_Tag tag;
+  union {
+    struct {
+      struct FieldElement ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultFieldElement;
+
+typedef enum COptionKeysClause_Tag {
+  SomeKeysClause,
+  NoneKeysClause,
+} COptionKeysClause_Tag;
+
+typedef struct COptionKeysClause {
+  COptionKeysClause_Tag tag;
+  union {
+    struct {
+      struct KeysClause some;
+    };
+  };
+} COptionKeysClause;
+
+typedef enum ResultTy_Tag {
+  OkTy,
+  ErrTy,
+} ResultTy_Tag;
+
+typedef struct ResultTy {
+  ResultTy_Tag tag;
+  union {
+    struct {
+      struct Ty ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultTy;
+
+typedef enum Resultu32_Tag {
+  Oku32,
+  Erru32,
+} Resultu32_Tag;
+
+typedef struct Resultu32 {
+  Resultu32_Tag tag;
+  union {
+    struct {
+      uint32_t ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} Resultu32;
+
+typedef enum ResultTyBuilder_Tag {
+  OkTyBuilder,
+  ErrTyBuilder,
+} ResultTyBuilder_Tag;
+
+typedef struct ResultTyBuilder {
+  ResultTyBuilder_Tag tag;
+  union {
+    struct {
+      struct TyBuilder *ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultTyBuilder;
+
+typedef enum ResultClause_Tag {
+  OkClause,
+  ErrClause,
+} ResultClause_Tag;
+
+typedef struct ResultClause {
+  ResultClause_Tag tag;
+  union {
+    struct {
+      struct Clause ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultClause;
+
+typedef enum ResultQuery_Tag {
+  OkQuery,
+  ErrQuery,
+} ResultQuery_Tag;
+
+typedef struct ResultQuery {
+  ResultQuery_Tag tag;
+  union {
+    struct {
+      struct Query ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultQuery;
+
+typedef struct CArrayu8 {
+  uint8_t *data;
+  uintptr_t data_len;
+} CArrayu8;
+
+typedef enum ResultCArrayu8_Tag {
+  OkCArrayu8,
+  ErrCArrayu8,
+} ResultCArrayu8_Tag;
+
+typedef struct ResultCArrayu8 {
+  ResultCArrayu8_Tag tag;
+  union {
+    struct {
+      struct CArrayu8 ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultCArrayu8;
+
+typedef struct Signature {
+  /**
+   * The `r` value of a signature
+   */
+  struct FieldElement r;
+  /**
+   * The `s` value of a signature
+   */
+  struct FieldElement s;
+} Signature;
+
+typedef enum ResultSignature_Tag {
+  OkSignature,
+  ErrSignature,
+} ResultSignature_Tag;
+
+typedef struct ResultSignature {
+  ResultSignature_Tag tag;
+  union {
+    struct {
+      struct Signature ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultSignature;
+
+typedef enum ResultProvider_Tag {
+  OkProvider,
+  ErrProvider,
+} ResultProvider_Tag;
+
+typedef struct ResultProvider {
+  ResultProvider_Tag tag;
+  union {
+    struct {
+      struct Provider *ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultProvider;
+
+typedef enum ResultAccount_Tag {
+  OkAccount,
+  ErrAccount,
+} ResultAccount_Tag;
+
+typedef struct ResultAccount {
+  ResultAccount_Tag tag;
+  union {
+    struct {
+      struct Account *ok;
+    };
+    struct {
+      struct Error err;
+    };
+  };
+} ResultAccount;
+
+typedef struct Call {
+  struct FieldElement to;
+  const char *selector;
+  struct CArrayFieldElement calldata;
+} Call;
+
+/**
+ * Block hash, number or tag
+ */
+typedef enum BlockId_Tag {
+  Hash,
+  Number,
+  BlockTag_,
+} BlockId_Tag;
+
+typedef struct BlockId {
+  BlockId_Tag tag;
+  union {
+    struct {
+      struct FieldElement hash;
+    };
+    struct {
+      uint64_t number;
+    };
+    struct {
+      enum BlockTag block_tag;
+    };
+  };
+} BlockId;
+
+typedef enum SubscriptionState_Tag {
+  Connecting,
+  Live,
+  Reconnecting,
+  Closed,
+} SubscriptionState_Tag;
+
+typedef struct Reconnecting_Body {
+  uint32_t attempt;
+  uint64_t delay_ms;
+} Reconnecting_Body;
+
+typedef struct Closed_Body {
+  const char *reason;
+} Closed_Body;
+
+typedef struct SubscriptionState {
+  SubscriptionState_Tag tag;
+  union {
+    Reconnecting_Body reconnecting;
+    Closed_Body closed;
+  };
+} SubscriptionState;
+
+typedef struct DeliveryPolicy {
+  struct COptionu64 buffer_size;
+  enum Overflow overflow;
+  bool coalesce;
+  struct COptionu64 min_interval_ms;
+} DeliveryPolicy;
+
+typedef struct DeliveryStats {
+  uint64_t delivered;
+  uint64_t dropped;
+  uint64_t coalesced;
+} DeliveryStats;
+
+typedef struct EndpointStatus {
+  const char *url;
+  bool active;
+  bool healthy;
+  struct COptionu64 head;
+  struct COptionc_char last_error;
+} EndpointStatus;
+
+typedef struct Diagnostic {
+  /**
+   * Offending clause in the filter syntax, none for problems outside the clause
+   */
+  struct COptionc_char clause;
+  const char *message;
+} Diagnostic;
+
+typedef struct OrderBy {
+  const char *field;
+  enum OrderDirection direction;
+} OrderBy;
+
+/**
+ * Entity looked up by id, `entity` being none when Torii doesn't know it
+ */
+typedef struct EntityLookup {
+  struct FieldElement id;
+  struct COptionEntity entity;
+} EntityLookup;
+
+/**
+ * Value of a model set by a transaction. The block and transaction are unknown when Torii's
+ * SQL endpoint is unavailable.
+ */
+typedef struct EntityVersion {
+  struct COptionu64 block_number;
+  struct COptionFieldElement transaction_hash;
+  uint64_t timestamp;
+  struct Struct model;
+} EntityVersion;
+
+typedef struct AttributeFilter {
+  const char *trait_name;
+  const char *trait_value;
+} AttributeFilter;
+
+typedef struct TransactionCall {
+  struct FieldElement contract_address;
+  const char *entrypoint;
+  struct CArrayFieldElement calldata;
+  enum CallType call_type;
+  struct FieldElement caller_address;
+} TransactionCall;
+
+typedef struct ActionCount {
+  const char *action_name;
+  uint32_t count;
+} ActionCount;
+
+typedef struct AchievementTask {
+  const char *task_id;
+  const char *description;
+  uint32_t total;
+  uint32_t total_completions;
+  double completion_rate;
+  uint64_t created_at;
+} AchievementTask;
+
+typedef struct CArrayTaskProgress {
+  struct TaskProgress *data;
+  uintptr_t data_len;
+} CArrayTaskProgress;
+
+typedef struct PlayerAchievementProgress {
+  struct Achievement achievement;
+  struct CArrayTaskProgress task_progress;
+  bool completed;
+  double progress_percentage;
+} PlayerAchievementProgress;
+
+typedef struct CArrayModel {
+  struct Model *data;
+  uintptr_t data_len;
+} CArrayModel;
+
+typedef struct World {
+  struct FieldElement world_address;
+  struct CArrayModel models;
+} World;
+
+/**
+ * Member of a model, with a description of its type
+ */
+typedef struct MemberSchema {
+  const char *name;
+  /**
+   * Cairo name of the type, e.g. `u32`, `Array<felt252>` or the name of a struct or enum
+   */
+  const char *type_name;
+  enum TypeKind kind;
+  struct Ty ty;
+} MemberSchema;
+
+typedef struct Member {
+  const char *name;
+  struct Ty *ty;
+  bool key;
+} Member;
+
+typedef struct EnumOption {
+  const char *name;
+  struct Ty *ty;
+} EnumOption;
+
+/**
+ * Structural difference between two versions of a model schema
+ */
+typedef struct TypeChange {
+  /**
+   * Path of the member or variant, e.g. `vec.x`, `dir::Left`, `pair.1` or `items[]`
+   */
+  const char *path;
+  enum TypeChangeKind kind;
+  /**
+   * Type before the change, none when added
+   */
+  struct COptionTy old_type;
+  /**
+   * Type after the change, none when removed
+   */
+  struct COptionTy new_type;
+} TypeChange;
+
+typedef struct TokenContract {
+  struct FieldElement contract_address;
+  const char *name;
+  const char *symbol;
+  uint8_t decimals;
+  const char *metadata;
+  const char *token_metadata;
+  struct COptionU256 total_supply;
+} TokenContract;
+
+typedef struct CArraySearchMatch {
+  struct SearchMatch *data;
+  uintptr_t data_len;
+} CArraySearchMatch;
+
+typedef struct TableSearchResults {
+  const char *table;
+  uint32_t count;
+  struct CArraySearchMatch matches;
+} TableSearchResults;
+
+typedef struct TaskProgress {
+  const char *task_id;
+  uint32_t count;
+  bool completed;
+} TaskProgress;
+
+typedef struct Model {
+  struct FieldElement world_address;
+  struct Ty schema;
+  const char *namespace_;
+  const char *name;
+  struct FieldElement selector;
+  uint32_t packed_size;
+  uint32_t unpacked_size;
+  struct FieldElement class_hash;
+  struct FieldElement contract_address;
+  const char *layout;
+  bool use_legacy_store;
+} Model;
+
+typedef struct CArraySearchMatchField {
+  struct SearchMatchField *data;
+  uintptr_t data_len;
+} CArraySearchMatchField;
+
+typedef enum COptionf64_Tag {
+  Somef64,
+  Nonef64,
+} COptionf64_Tag;
+
+typedef struct COptionf64 {
+  COptionf64_Tag tag;
+  union {
+    struct {
+      double some;
+    };
+  };
+} COptionf64;
+
+typedef struct SearchMatch {
+  const char *id;
+  struct CArraySearchMatchField fields;
+  struct COptionf64 score;
+} SearchMatch;
+
+typedef struct SearchMatchField {
+  const char *key;
+  const char *value;
+} SearchMatchField;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Creates a new Torii client instance
+ *
+ * # Parameters
+ * * `torii_url` - URL of the Torii server
+ * * `libp2p_relay_url` - URL of the libp2p relay server
+ *
+ * # Returns
+ * Result containing pointer to new ToriiClient instance or error
+ */
+struct ResultToriiClient client_new(const char *torii_url);
+
+/**
+ * Creates a new Torii client instance from a full configuration
+ *
+ * When `fallback_urls` are provided, queries and subscriptions fail over to the healthiest
+ * replica whenever the active endpoint stops responding or falls behind.
+ *
+ * # Parameters
+ * * `config` - Client configuration (fallback endpoints, timeouts, message size and retry
+ *   policy). Optional fields left as `None` use the library defaults.
+ *
+ * # Returns
+ * Result containing pointer to new ToriiClient instance or error
+ */
+struct ResultToriiClient client_new_with_config(struct ClientConfig config);
+
+/**
+ * Creates a new Torii client that records its session to a file
+ *
+ * Every query response and subscription message is written to the file along with the
+ * time it came in, so that the session can be played back with `client_new_replay`.
+ *
+ * # Parameters
+ * * `config` - Client configuration, see `client_new_with_config`
+ * * `path` - Path of the recording, replaced if it exists
+ *
+ * # Returns
+ * Result containing pointer to new ToriiClient instance or error
+ */
+struct ResultToriiClient client_new_recording(struct ClientConfig config, const char *path);
+
+/**
+ * Creates a Torii client playing back a session recorded by `client_new_recording`
+ *
+ * Queries get the recorded responses and subscriptions the recorded messages, without a
+ * network. The replay is deterministic as long as the same queries are made and the
+ * subscriptions are opened in the same order as in the recorded session.
+ *
+ * # Parameters
+ * * `config` - Client configuration, which should multiplex subscriptions and cache
+ *   queries like the recorded client did. Its URLs aren't used.
+ * * `path` - Path of the recording
+ * * `speed` - Playback speed, 1 being the original speed and infinity playing messages
+ *   without delays
+ *
+ * # Returns
+ * Result containing pointer to new ToriiClient instance or error
+ */
+struct ResultToriiClient client_new_replay(struct ClientConfig config,
+                                           const char *path,
+                                           double speed);
+
+/**
+ * Sets a logger callback function for the client
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `logger` - Callback function that takes a C string parameter
+ */
+void client_set_logger(struct ToriiClient *client, void (*logger)(const char*));
+
+/**
+ * Probes every endpoint of the client and returns their health
+ *
+ * Switches to a healthier replica if the active endpoint is down or lagging behind.
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ *
+ * # Returns
+ * Result containing array of EndpointStatus or error
+ */
+struct ResultCArrayEndpointStatus client_endpoints(struct ToriiClient *client);
+
+/**
+ * Returns the statistics of the client's query cache
+ *
+ * All counters are zero when the client was created without a cache.
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ *
+ * # Returns
+ * CacheStats of the query cache
+ */
+struct CacheStats client_cache_stats(struct ToriiClient *client);
+
+/**
+ * Drops every result of the client's query cache
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ */
+void client_cache_clear(struct ToriiClient *client);
+
+/**
+ * Checks a query against the model schemas of the client's worlds
+ *
+ * Reports unknown models, unresolved member paths, operators that don't apply to a member,
+ * values that can't match it and key counts that differ from the model's.
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - Query to check
+ *
+ * # Returns
+ * Result containing the diagnostics, empty when the query is valid, or error
+ */
+struct ResultCArrayDiagnostic client_validate_query(struct ToriiClient *client, struct Query query);
+
+/**
+ * Publishes a message to the network
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `message` - JSON string containing typed data message
+ * * `signature_felts` - Array of field elements containing signature
+ * * `signature_felts_len` - Length of signature array
+ *
+ * # Returns
+ * Result containing byte array or error
+ */
+struct Resultc_char client_publish_message(struct ToriiClient *client, struct Message message);
+
+/**
+ * Publishes multiple messages to the network
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `messages` - Array of Message structs
+ * * `messages_len` - Length of messages array
+ *
+ * # Returns
+ * Result containing array of message IDs or error
+ */
+struct ResultCArrayc_char client_publish_message_batch(struct ToriiClient *client,
+                                                       const struct Message *messages,
+                                                       uintptr_t messages_len);
+
+/**
+ * Retrieves controllers for the given contract addresses
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `contract_addresses` - Array of contract addresses. If empty, all controllers will be
+ *   returned.
+ *
+ * # Returns
+ * Result containing controllers or error
+ */
+struct ResultPageController client_controllers(struct ToriiClient *client,
+                                               struct ControllerQuery query);
+
+/**
+ * Queries entities matching given criteria
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - Query parameters
+ *
+ * # Returns
+ * Result containing array of matching entities or error
+ */
+struct ResultPageEntity client_entities(struct ToriiClient *client, struct Query query);
+
+/**
+ * Queries entities matching given criteria, only keeping the selected models and members
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - Query parameters
+ * * `selectors` - Array of model tags and member paths to keep, e.g. `ns-Stats.health`,
+ *   every model and member being kept when empty
+ * * `selectors_len` - Length of selectors array
+ *
+ * # Returns
+ * Result containing array of matching entities or error
+ */
+struct ResultPageEntity client_entities_projected(struct ToriiClient *client,
+                                                  struct Query query,
+                                                  const char *const *selectors,
+                                                  uintptr_t selectors_len);
+
+/**
+ * Retrieves event messages matching the given query
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - Query parameters
+ * * `historical` - Whether to include historical messages
+ *
+ * # Returns
+ * Result containing array of matching event message entities or error
+ */
+struct ResultPageEntity client_event_messages(struct ToriiClient *client, struct Query query);
+
+/**
+ * Retrieves event messages matching the given query, only keeping the selected models
+ * and members
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - Query parameters
+ * * `selectors` - Array of model tags and member paths to keep, e.g. `ns-Stats.health`,
+ *   every model and member being kept when empty
+ * * `selectors_len` - Length of selectors array
+ *
+ * # Returns
+ * Result containing array of matching event message entities or error
+ */
+struct ResultPageEntity client_event_messages_projected(struct ToriiClient *client,
+                                                        struct Query query,
+                                                        const char *const *selectors,
+                                                        uintptr_t selectors_len);
+
+/**
+ * Looks up entities by id, i.e. hashed keys
+ *
+ * Ids are requested in chunks running concurrently, sized so that any number of them fits
+ * in the configured message size. An id known to several worlds comes with the entity of
+ * the first one listed.
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `world_addresses` - Array of world addresses to look entities up in, all when empty
+ * * `world_addresses_len` - Length of world addresses array
+ * * `ids` - Array of entity ids
+ * * `ids_len` - Length of ids array
+ *
+ * # Returns
+ * Result containing an EntityLookup per id, in order, or error
+ */
+struct ResultCArrayEntityLookup client_entities_by_ids(struct ToriiClient *client,
+                                                       const struct FieldElement *world_addresses,
+                                                       uintptr_t world_addresses_len,
+                                                       const struct FieldElement *ids,
+                                                       uintptr_t ids_len);
+
+/**
+ * Looks up a model of entities by keys
+ *
+ * Entities without the model are reported as missing. Keys are requested in chunks
+ * running concurrently, so that any number of them fits in the message size.
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `world_addresses` - Array of world addresses to look entities up in, all when empty
+ * * `world_addresses_len` - Length of world addresses array
+ * * `model` - Tag of the model, e.g. `ns-Position`
+ * * `keys` - Array of the keys of each entity
+ * * `keys_len` - Length of keys array
+ *
+ * # Returns
+ * Result containing an EntityLookup per keys, in order, or error
+ */
+struct ResultCArrayEntityLookup client_entities_by_keys(struct ToriiClient *client,
+                                                        const struct FieldElement *world_addresses,
+                                                        uintptr_t world_addresses_len,
+                                                        const char *model,
+                                                        const struct CArrayFieldElement *keys,
+                                                        uintptr_t keys_len);
+
+/**
+ * Gets every version of a model of an entity, in the order they were set
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `world_addresses` - Array of world addresses, every world when empty
+ * * `world_addresses_len` - Length of world addresses array
+ * * `hashed_keys` - Id of the entity
+ * * `model` - Tag of the model
+ * * `range` - Inclusive range of blocks or timestamps to keep
+ *
+ * # Returns
+ * Result containing the versions, with their block and transaction when Torii's SQL
+ * endpoint is available, or error
+ */
+struct ResultCArrayEntityVersion client_entity_history(struct ToriiClient *client,
+                                                       const struct FieldElement *world_addresses,
+                                                       uintptr_t world_addresses_len,
+                                                       struct FieldElement hashed_keys,
+                                                       const char *model,
+                                                       struct HistoryRange range);
+
+/**
+ * Rebuilds entities as they were at a block or time, from their history
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `world_address` - Address of the world of the entities
+ * * `ids` - Array of entity ids
+ * * `ids_len` - Length of ids array
+ * * `at` - Block or timestamp to rebuild the entities at
+ *
+ * # Returns
+ * Result containing the entities that existed at that point, each with the models set
+ * by then, or error
+ */
+struct ResultCArrayEntity client_entities_at(struct ToriiClient *client,
+                                             struct FieldElement world_address,
+                                             const struct FieldElement *ids,
+                                             uintptr_t ids_len,
+                                             struct At at);
+
+/**
+ * Creates an iterator over every page of entities matching a query
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+ * * `prefetch` - Whether to fetch the next page in the background once a page is returned
+ *
+ * # Returns
+ * Pointer to EntityIterator, freed with `entity_iterator_free`
+ */
+struct EntityIterator *client_entities_iter(struct ToriiClient *client,
+                                            struct Query query,
+                                            bool prefetch);
+
+/**
+ * Creates an iterator over every page of entities matching a query, only keeping the
+ * selected models and members
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+ * * `prefetch` - Whether to fetch the next page in the background once a page is returned
+ * * `selectors` - Array of model tags and member paths to keep, e.g. `ns-Stats.health`,
+ *   every model and member being kept when empty
+ * * `selectors_len` - Length of selectors array
+ *
+ * # Returns
+ * Pointer to EntityIterator, freed with `entity_iterator_free`
+ */
+struct EntityIterator *client_entities_iter_projected(struct ToriiClient *client,
+                                                      struct Query query,
+                                                      bool prefetch,
+                                                      const char *const *selectors,
+                                                      uintptr_t selectors_len);
+
+/**
+ * Creates an iterator over every page of event messages matching a query
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+ * * `prefetch` - Whether to fetch the next page in the background once a page is returned
+ *
+ * # Returns
+ * Pointer to EntityIterator, freed with `entity_iterator_free`
+ */
+struct EntityIterator *client_event_messages_iter(struct ToriiClient *client,
+                                                  struct Query query,
+                                                  bool prefetch);
+
+/**
+ * Creates an iterator over every page of event messages matching a query, only keeping the
+ * selected models and members
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+ * * `prefetch` - Whether to fetch the next page in the background once a page is returned
+ * * `selectors` - Array of model tags and member paths to keep, e.g. `ns-Stats.health`,
+ *   every model and member being kept when empty
+ * * `selectors_len` - Length of selectors array
+ *
+ * # Returns
+ * Pointer to EntityIterator, freed with `entity_iterator_free`
+ */
+struct EntityIterator *client_event_messages_iter_projected(struct ToriiClient *client,
+                                                            struct Query query,
+                                                            bool prefetch,
+                                                            const char *const *selectors,
+                                                            uintptr_t selectors_len);
+
+/**
+ * Creates an iterator over every page of controllers matching a query
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+ * * `prefetch` - Whether to fetch the next page in the background once a page is returned
+ *
+ * # Returns
+ * Pointer to ControllerIterator, freed with `controller_iterator_free`
+ */
+struct ControllerIterator *client_controllers_iter(struct ToriiClient *client,
+                                                   struct ControllerQuery query,
+                                                   bool prefetch);
+
+/**
+ * Creates an iterator over every page of tokens matching a query
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+ * * `prefetch` - Whether to fetch the next page in the background once a page is returned
+ *
+ * # Returns
+ * Pointer to TokenIterator, freed with `token_iterator_free`
+ */
+struct TokenIterator *client_tokens_iter(struct ToriiClient *client,
+                                         struct TokenQuery query,
+                                         bool prefetch);
+
+/**
+ * Creates an iterator over every page of token balances matching a query
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+ * * `prefetch` - Whether to fetch the next page in the background once a page is returned
+ *
+ * # Returns
+ * Pointer to TokenBalanceIterator, freed with `token_balance_iterator_free`
+ */
+struct TokenBalanceIterator *client_token_balances_iter(struct ToriiClient *client,
+                                                        struct TokenBalanceQuery query,
+                                                        bool prefetch);
+
+/**
+ * Creates an iterator over every page of token transfers matching a query
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+ * * `prefetch` - Whether to fetch the next page in the background once a page is returned
+ *
+ * # Returns
+ * Pointer to TokenTransferIterator, freed with `token_transfer_iterator_free`
+ */
+struct TokenTransferIterator *client_token_transfers_iter(struct ToriiClient *client,
+                                                          struct TokenTransferQuery query,
+                                                          bool prefetch);
+
+/**
+ * Creates an iterator over every page of transactions matching a query
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+ * * `prefetch` - Whether to fetch the next page in the background once a page is returned
+ *
+ * # Returns
+ * Pointer to TransactionIterator, freed with `transaction_iterator_free`
+ */
+struct TransactionIterator *client_transactions_iter(struct ToriiClient *client,
+                                                     struct TransactionQuery query,
+                                                     bool prefetch);
+
+/**
+ * Creates an iterator over every page of activities matching a query
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+ * * `prefetch` - Whether to fetch the next page in the background once a page is returned
+ *
+ * # Returns
+ * Pointer to ActivityIterator, freed with `activity_iterator_free`
+ */
+struct ActivityIterator *client_activities_iter(struct ToriiClient *client,
+                                                struct ActivityQuery query,
+                                                bool prefetch);
+
+/**
+ * Creates an iterator over every page of achievements matching a query
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+ * * `prefetch` - Whether to fetch the next page in the background once a page is returned
+ *
+ * # Returns
+ * Pointer to AchievementIterator, freed with `achievement_iterator_free`
+ */
+struct AchievementIterator *client_achievements_iter(struct ToriiClient *client,
+                                                     struct AchievementQuery query,
+                                                     bool prefetch);
+
+/**
+ * Creates an iterator over every page of player achievements matching a query
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+ * * `prefetch` - Whether to fetch the next page in the background once a page is returned
+ *
+ * # Returns
+ * Pointer to PlayerAchievementIterator, freed with `player_achievement_iterator_free`
+ */
+struct PlayerAchievementIterator *client_player_achievements_iter(struct ToriiClient *client,
+                                                                  struct PlayerAchievementQuery query,
+                                                                  bool prefetch);
+
+/**
+ * Creates an iterator over every page of aggregation entries matching a query
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+ * * `prefetch` - Whether to fetch the next page in the background once a page is returned
+ *
+ * # Returns
+ * Pointer to AggregationIterator, freed with `aggregation_iterator_free`
+ */
+struct AggregationIterator *client_aggregations_iter(struct ToriiClient *client,
+                                                     struct AggregationQuery query,
+                                                     bool prefetch);
+
+/**
+ * Fetches the page of entities after the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to EntityIterator
+ *
+ * # Returns
+ * Result containing the page, none once every page was returned, or error
+ */
+struct ResultCOptionPageEntity entity_iterator_next_page(struct EntityIterator *iterator);
+
+/**
+ * Fetches again the page of entities before the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to EntityIterator
+ *
+ * # Returns
+ * Result containing the page, none when on the first page, or error
+ */
+struct ResultCOptionPageEntity entity_iterator_previous_page(struct EntityIterator *iterator);
+
+/**
+ * Returns the next item of an iterator over entities, fetching the next page when needed
+ *
+ * # Parameters
+ * * `iterator` - Pointer to EntityIterator
+ *
+ * # Returns
+ * Result containing the item, none once every item was returned, or error
+ */
+struct ResultCOptionEntity entity_iterator_next_item(struct EntityIterator *iterator);
+
+/**
+ * Fetches the page of controllers after the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to ControllerIterator
+ *
+ * # Returns
+ * Result containing the page, none once every page was returned, or error
+ */
+struct ResultCOptionPageController controller_iterator_next_page(struct ControllerIterator *iterator);
+
+/**
+ * Fetches again the page of controllers before the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to ControllerIterator
+ *
+ * # Returns
+ * Result containing the page, none when on the first page, or error
+ */
+struct ResultCOptionPageController controller_iterator_previous_page(struct ControllerIterator *iterator);
+
+/**
+ * Returns the next item of an iterator over controllers, fetching the next page when needed
+ *
+ * # Parameters
+ * * `iterator` - Pointer to ControllerIterator
+ *
+ * # Returns
+ * Result containing the item, none once every item was returned, or error
+ */
+struct ResultCOptionController controller_iterator_next_item(struct ControllerIterator *iterator);
+
+/**
+ * Fetches the page of tokens after the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to TokenIterator
+ *
+ * # Returns
+ * Result containing the page, none once every page was returned, or error
+ */
+struct ResultCOptionPageToken token_iterator_next_page(struct TokenIterator *iterator);
+
+/**
+ * Fetches again the page of tokens before the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to TokenIterator
+ *
+ * # Returns
+ * Result containing the page, none when on the first page, or error
+ */
+struct ResultCOptionPageToken token_iterator_previous_page(struct TokenIterator *iterator);
+
+/**
+ * Returns the next item of an iterator over tokens, fetching the next page when needed
+ *
+ * # Parameters
+ * * `iterator` - Pointer to TokenIterator
+ *
+ * # Returns
+ * Result containing the item, none once every item was returned, or error
+ */
+struct ResultCOptionToken token_iterator_next_item(struct TokenIterator *iterator);
+
+/**
+ * Fetches the page of token balances after the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to TokenBalanceIterator
+ *
+ * # Returns
+ * Result containing the page, none once every page was returned, or error
+ */
+struct ResultCOptionPageTokenBalance token_balance_iterator_next_page(struct TokenBalanceIterator *iterator);
+
+/**
+ * Fetches again the page of token balances before the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to TokenBalanceIterator
+ *
+ * # Returns
+ * Result containing the page, none when on the first page, or error
+ */
+struct ResultCOptionPageTokenBalance token_balance_iterator_previous_page(struct TokenBalanceIterator *iterator);
+
+/**
+ * Returns the next item of an iterator over token balances, fetching the next page when needed
+ *
+ * # Parameters
+ * * `iterator` - Pointer to TokenBalanceIterator
+ *
+ * # Returns
+ * Result containing the item, none once every item was returned, or error
+ */
+struct ResultCOptionTokenBalance token_balance_iterator_next_item(struct TokenBalanceIterator *iterator);
+
+/**
+ * Fetches the page of token transfers after the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to TokenTransferIterator
+ *
+ * # Returns
+ * Result containing the page, none once every page was returned, or error
+ */
+struct ResultCOptionPageTokenTransfer token_transfer_iterator_next_page(struct TokenTransferIterator *iterator);
+
+/**
+ * Fetches again the page of token transfers before the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to TokenTransferIterator
+ *
+ * # Returns
+ * Result containing the page, none when on the first page, or error
+ */
+struct ResultCOptionPageTokenTransfer token_transfer_iterator_previous_page(struct TokenTransferIterator *iterator);
+
+/**
+ * Returns the next item of an iterator over token transfers, fetching the next page when needed
+ *
+ * # Parameters
+ * * `iterator` - Pointer to TokenTransferIterator
+ *
+ * # Returns
+ * Result containing the item, none once every item was returned, or error
+ */
+struct ResultCOptionTokenTransfer token_transfer_iterator_next_item(struct TokenTransferIterator *iterator);
+
+/**
+ * Fetches the page of transactions after the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to TransactionIterator
+ *
+ * # Returns
+ * Result containing the page, none once every page was returned, or error
+ */
+struct ResultCOptionPageTransaction transaction_iterator_next_page(struct TransactionIterator *iterator);
+
+/**
+ * Fetches again the page of transactions before the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to TransactionIterator
+ *
+ * # Returns
+ * Result containing the page, none when on the first page, or error
+ */
+struct ResultCOptionPageTransaction transaction_iterator_previous_page(struct TransactionIterator *iterator);
+
+/**
+ * Returns the next item of an iterator over transactions, fetching the next page when needed
+ *
+ * # Parameters
+ * * `iterator` - Pointer to TransactionIterator
+ *
+ * # Returns
+ * Result containing the item, none once every item was returned, or error
+ */
+struct ResultCOptionTransaction transaction_iterator_next_item(struct TransactionIterator *iterator);
+
+/**
+ * Fetches the page of activities after the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to ActivityIterator
+ *
+ * # Returns
+ * Result containing the page, none once every page was returned, or error
+ */
+struct ResultCOptionPageActivity activity_iterator_next_page(struct ActivityIterator *iterator);
+
+/**
+ * Fetches again the page of activities before the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to ActivityIterator
+ *
+ * # Returns
+ * Result containing the page, none when on the first page, or error
+ */
+struct ResultCOptionPageActivity activity_iterator_previous_page(struct ActivityIterator *iterator);
+
+/**
+ * Returns the next item of an iterator over activities, fetching the next page when needed
+ *
+ * # Parameters
+ * * `iterator` - Pointer to ActivityIterator
+ *
+ * # Returns
+ * Result containing the item, none once every item was returned, or error
+ */
+struct ResultCOptionActivity activity_iterator_next_item(struct ActivityIterator *iterator);
+
+/**
+ * Fetches the page of achievements after the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to AchievementIterator
+ *
+ * # Returns
+ * Result containing the page, none once every page was returned, or error
+ */
+struct ResultCOptionPageAchievement achievement_iterator_next_page(struct AchievementIterator *iterator);
+
+/**
+ * Fetches again the page of achievements before the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to AchievementIterator
+ *
+ * # Returns
+ * Result containing the page, none when on the first page, or error
+ */
+struct ResultCOptionPageAchievement achievement_iterator_previous_page(struct AchievementIterator *iterator);
+
+/**
+ * Returns the next item of an iterator over achievements, fetching the next page when needed
+ *
+ * # Parameters
+ * * `iterator` - Pointer to AchievementIterator
+ *
+ * # Returns
+ * Result containing the item, none once every item was returned, or error
+ */
+struct ResultCOptionAchievement achievement_iterator_next_item(struct AchievementIterator *iterator);
+
+/**
+ * Fetches the page of player achievements after the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to PlayerAchievementIterator
+ *
+ * # Returns
+ * Result containing the page, none once every page was returned, or error
+ */
+struct ResultCOptionPagePlayerAchievementEntry player_achievement_iterator_next_page(struct PlayerAchievementIterator *iterator);
+
+/**
+ * Fetches again the page of player achievements before the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to PlayerAchievementIterator
+ *
+ * # Returns
+ * Result containing the page, none when on the first page, or error
+ */
+struct ResultCOptionPagePlayerAchievementEntry player_achievement_iterator_previous_page(struct PlayerAchievementIterator *iterator);
+
+/**
+ * Returns the next item of an iterator over player achievements, fetching the next page when needed
+ *
+ * # Parameters
+ * * `iterator` - Pointer to PlayerAchievementIterator
+ *
+ * # Returns
+ * Result containing the item, none once every item was returned, or error
+ */
+struct ResultCOptionPlayerAchievementEntry player_achievement_iterator_next_item(struct PlayerAchievementIterator *iterator);
+
+/**
+ * Fetches the page of aggregation entries after the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to AggregationIterator
+ *
+ * # Returns
+ * Result containing the page, none once every page was returned, or error
+ */
+struct ResultCOptionPageAggregationEntry aggregation_iterator_next_page(struct AggregationIterator *iterator);
+
+/**
+ * Fetches again the page of aggregation entries before the last one returned by an iterator
+ *
+ * # Parameters
+ * * `iterator` - Pointer to AggregationIterator
+ *
+ * # Returns
+ * Result containing the page, none when on the first page, or error
+ */
+struct ResultCOptionPageAggregationEntry aggregation_iterator_previous_page(struct AggregationIterator *iterator);
+
+/**
+ * Returns the next item of an iterator over aggregation entries, fetching the next page when needed
+ *
+ * # Parameters
+ * * `iterator` - Pointer to AggregationIterator
+ *
+ * # Returns
+ * Result containing the item, none once every item was returned, or error
+ */
+struct ResultCOptionAggregationEntry aggregation_iterator_next_item(struct AggregationIterator *iterator);
+
+/**
+ * Gets the world metadata for the client
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ *
+ * # Returns
+ * World structure containing world information
+ */
+struct ResultCArrayWorld client_worlds(struct ToriiClient *client,
+                                       const struct FieldElement *world_addresses,
+                                       uintptr_t world_addresses_len);
+
+/**
+ * Gets the schema of a world, to list its namespaces and models and describe their members
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `world_address` - Address of the world
+ *
+ * # Returns
+ * Result containing pointer to the WorldSchema, to free with world_schema_free, or error
+ */
+struct ResultWorldSchema client_world_schema(struct ToriiClient *client,
+                                             struct FieldElement world_address);
+
+/**
+ * Gets the namespaces of a world having at least one model, sorted
+ *
+ * # Parameters
+ * * `schema` - Pointer to WorldSchema instance
+ *
+ * # Returns
+ * Array of namespaces
+ */
+struct CArrayc_char world_schema_namespaces(const struct WorldSchema *schema);
+
+/**
+ * Gets the models and events of a world, sorted by tag
+ *
+ * # Parameters
+ * * `schema` - Pointer to WorldSchema instance
+ * * `namespace` - Namespace to list the models of, all namespaces when null
+ *
+ * # Returns
+ * Array of model schemas
+ */
+struct CArrayModelSchema world_schema_models(const struct WorldSchema *schema,
+                                             const char *namespace_);
+
+/**
+ * Looks up a model of a world by tag
+ *
+ * # Parameters
+ * * `schema` - Pointer to WorldSchema instance
+ * * `tag` - Tag of the model, e.g. `ns-Position`
+ *
+ * # Returns
+ * The model schema, none if the world has no such model
+ */
+struct COptionModelSchema world_schema_model(const struct WorldSchema *schema, const char *tag);
+
+/**
+ * Looks up a model of a world by selector
+ *
+ * # Parameters
+ * * `schema` - Pointer to WorldSchema instance
+ * * `selector` - Selector of the model, the Poseidon hash of its namespace and name
+ *
+ * # Returns
+ * The model schema, none if the world has no such model
+ */
+struct COptionModelSchema world_schema_model_by_selector(const struct WorldSchema *schema,
+                                                         struct FieldElement selector);
+
+/**
+ * Retrieves transactions matching the given query
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - Query parameters
+ *
+ * # Returns
+ * Result containing array of matching transactions or error
+ */
+struct ResultPageTransaction client_transactions(struct ToriiClient *client,
+                                                 struct TransactionQuery query);
+
+/**
+ * Subscribes to transaction updates
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `filter` - Filter parameters
+ * * `callback` - Function called when updates occur
+ *
+ * # Returns
+ * Result containing pointer to Subscription or error
+ */
+struct ResultSubscription client_on_transaction(struct ToriiClient *client,
+                                                struct COptionTransactionFilter filter,
+                                                void (*callback)(struct Transaction));
+
+/**
+ * Subscribes to entity state updates
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `clauses` - Array of entity key clauses to filter updates
+ * * `clauses_len` - Length of clauses array
+ * * `callback` - Function called when updates occur
+ *
+ * # Returns
+ * Result containing pointer to Subscription or error
+ */
+struct ResultSubscription client_on_entity_state_update(struct ToriiClient *client,
+                                                        struct COptionClause clause,
+                                                        const struct FieldElement *world_addresses,
+                                                        uintptr_t world_addresses_len,
+                                                        void (*callback)(struct Entity));
+
+/**
+ * Subscribes to entity updates and dispatches their models to the handlers of a
+ * ModelDispatcher
+ *
+ * Handlers can be added to and removed from the dispatcher while the subscription is live.
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `clause` - Optional clause to filter updates
+ * * `world_addresses` - Array of world addresses to filter updates
+ * * `world_addresses_len` - Length of world addresses array
+ * * `dispatcher` - Pointer to the ModelDispatcher holding the handlers
+ *
+ * # Returns
+ * Result containing pointer to Subscription or error
+ */
+struct ResultSubscription client_on_entity_dispatch(struct ToriiClient *client,
+                                                    struct COptionClause clause,
+                                                    const struct FieldElement *world_addresses,
+                                                    uintptr_t world_addresses_len,
+                                                    struct ModelDispatcher *dispatcher);
+
+/**
+ * Subscribes to entity updates with the predictions of an OptimisticStore applied
+ *
+ * The callback receives the entity as it should be shown: its confirmed state with the
+ * pending predictions of the store on top.
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `clause` - Optional clause to filter updates
+ * * `world_addresses` - Array of world addresses to filter updates
+ * * `world_addresses_len` - Length of world addresses array
+ * * `store` - Pointer to the OptimisticStore holding the predictions
+ * * `callback` - Function called when updates occur
+ *
+ * # Returns
+ * Result containing pointer to Subscription or error
+ */
+struct ResultSubscription client_on_entity_optimistic_update(struct ToriiClient *client,
+                                                             struct COptionClause clause,
+                                                             const struct FieldElement *world_addresses,
+                                                             uintptr_t world_addresses_len,
+                                                             struct OptimisticStore *store,
+                                                             void (*callback)(struct Entity));
+
+/**
+ * Subscribes to entity updates, tagged as creations, updates or deletions
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `clause` - Optional clause to filter updates
+ * * `world_addresses` - Array of world addresses to filter updates
+ * * `world_addresses_len` - Length of world addresses array
+ * * `callback` - Function called when updates occur
+ *
+ * # Returns
+ * Result containing pointer to Subscription or error
+ */
+struct ResultSubscription client_on_entity_changed(struct ToriiClient *client,
+                                                   struct COptionClause clause,
+                                                   const struct FieldElement *world_addresses,
+                                                   uintptr_t world_addresses_len,
+                                                   void (*callback)(struct EntityChange));
+
+/**
+ * Subscribes to entity updates, reported as field level changes
+ *
+ * Each update is compared with the previous state of the entity, and the callback is
+ * called once per changed member, added or removed model.
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `clause` - Optional clause to filter updates
+ * * `world_addresses` - Array of world addresses to filter updates
+ * * `world_addresses_len` - Length of world addresses array
+ * * `callback` - Function called for each change
+ *
+ * # Returns
+ * Result containing pointer to Subscription or error
+ */
+struct ResultSubscription client_on_entity_diff(struct ToriiClient *client,
+                                                struct COptionClause clause,
+                                                const struct FieldElement *world_addresses,
+                                                uintptr_t world_addresses_len,
+                                                void (*callback)(struct FieldChange));
+
+/**
+ * Updates an existing entity subscription with new clauses
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `subscription` - Pointer to existing Subscription
+ * * `clauses` - New array of entity key clauses
+ * * `clauses_len` - Length of new clauses array
+ *
+ * # Returns
+ * Result containing success boolean or error
+ */
+struct Resultbool client_update_entity_subscription(struct ToriiClient *client,
+                                                    struct Subscription *subscription,
+                                                    struct COptionClause clause,
+                                                    const struct FieldElement *world_addresses,
+                                                    uintptr_t world_addresses_len);
+
+/**
+ * Retrieves aggregations (leaderboards, stats, rankings) matching query parameter
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - AggregationQuery containing aggregator_ids, entity_ids, and pagination
+ *
+ * # Returns
+ * Result containing Page of AggregationEntry or error
+ */
+struct ResultPageAggregationEntry client_aggregations(struct ToriiClient *client,
+                                                      struct AggregationQuery query);
+
+/**
+ * Subscribes to aggregation updates (leaderboards, stats, rankings)
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `aggregator_ids` - Array of aggregator IDs to subscribe to
+ * * `aggregator_ids_len` - Length of aggregator_ids array
+ * * `entity_ids` - Array of entity IDs to subscribe to
+ * * `entity_ids_len` - Length of entity_ids array
+ * * `callback` - Function called when updates occur
+ *
+ * # Returns
+ * Result containing pointer to Subscription or error
+ */
+struct ResultSubscription client_on_aggregation_update(struct ToriiClient *client,
+                                                       const char *const *aggregator_ids,
+                                                       uintptr_t aggregator_ids_len,
+                                                       const char *const *entity_ids,
+                                                       uintptr_t entity_ids_len,
+                                                       void (*callback)(struct AggregationEntry));
+
+/**
+ * Updates an existing aggregation subscription with new parameters
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `subscription` - Pointer to existing Subscription
+ * * `aggregator_ids` - Array of aggregator IDs to subscribe to
+ * * `aggregator_ids_len` - Length of aggregator_ids array
+ * * `entity_ids` - Array of entity IDs to subscribe to
+ * * `entity_ids_len` - Length of entity_ids array
+ *
+ * # Returns
+ * Result containing success boolean or error
+ */
+struct Resultbool client_update_aggregation_subscription(struct ToriiClient *client,
+                                                         struct Subscription *subscription,
+                                                         const char *const *aggregator_ids,
+                                                         uintptr_t aggregator_ids_len,
+                                                         const char *const *entity_ids,
+                                                         uintptr_t entity_ids_len);
+
+/**
+ * Retrieves achievements matching query parameter
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - AchievementQuery containing world_addresses, namespaces, hidden filter, and
+ *   pagination
+ *
+ * # Returns
+ * Result containing Page of Achievement or error
+ */
+struct ResultPageAchievement client_achievements(struct ToriiClient *client,
+                                                 struct AchievementQuery query);
+
+/**
+ * Retrieves player achievement data matching query parameter
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - PlayerAchievementQuery containing world_addresses, namespaces, player_addresses,
+ *   and pagination
+ *
+ * # Returns
+ * Result containing Page of PlayerAchievementEntry or error
+ */
+struct ResultPagePlayerAchievementEntry client_player_achievements(struct ToriiClient *client,
+                                                                   struct PlayerAchievementQuery query);
+
+/**
+ * Subscribes to achievement progression updates
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `world_addresses` - Array of world addresses to subscribe to
+ * * `world_addresses_len` - Length of world_addresses array
+ * * `namespaces` - Array of namespaces to subscribe to
+ * * `namespaces_len` - Length of namespaces array
+ * * `player_addresses` - Array of player addresses to subscribe to
+ * * `player_addresses_len` - Length of player_addresses array
+ * * `achievement_ids` - Array of achievement IDs to subscribe to
+ * * `achievement_ids_len` - Length of achievement_ids array
+ * * `callback` - Function called when updates occur
+ *
+ * # Returns
+ * Result containing pointer to Subscription or error
+ */
+struct ResultSubscription client_on_achievement_progression_update(struct ToriiClient *client,
+                                                                   const struct FieldElement *world_addresses,
+                                                                   uintptr_t world_addresses_len,
+                                                                   const char *const *namespaces,
+                                                                   uintptr_t namespaces_len,
+                                                                   const struct FieldElement *player_addresses,
+                                                                   uintptr_t player_addresses_len,
+                                                                   const char *const *achievement_ids,
+                                                                   uintptr_t achievement_ids_len,
+                                                                   void (*callback)(struct AchievementProgression));
+
+/**
+ * Updates an existing achievement progression subscription with new parameters
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `subscription` - Pointer to existing Subscription
+ * * `world_addresses` - Array of world addresses to subscribe to
+ * * `world_addresses_len` - Length of world_addresses array
+ * * `namespaces` - Array of namespaces to subscribe to
+ * * `namespaces_len` - Length of namespaces array
+ * * `player_addresses` - Array of player addresses to subscribe to
+ * * `player_addresses_len` - Length of player_addresses array
+ * * `achievement_ids` - Array of achievement IDs to subscribe to
+ * * `achievement_ids_len` - Length of achievement_ids array
+ *
+ * # Returns
+ * Result containing success boolean or error
+ */
+struct Resultbool client_update_achievement_progression_subscription(struct ToriiClient *client,
+                                                                     struct Subscription *subscription,
+                                                                     const struct FieldElement *world_addresses,
+                                                                     uintptr_t world_addresses_len,
+                                                                     const char *const *namespaces,
+                                                                     uintptr_t namespaces_len,
+                                                                     const struct FieldElement *player_addresses,
+                                                                     uintptr_t player_addresses_len,
+                                                                     const char *const *achievement_ids,
+                                                                     uintptr_t achievement_ids_len);
+
+/**
+ * Retrieves activities (user session tracking) matching query parameter
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - ActivityQuery containing world_addresses, namespaces, caller_addresses, and
+ *   pagination
+ *
+ * # Returns
+ * Result containing Page of Activity or error
+ */
+struct ResultPageActivity client_activities(struct ToriiClient *client, struct ActivityQuery query);
+
+/**
+ * Subscribes to activity updates (user session tracking)
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `world_addresses` - Array of world addresses to subscribe to
+ * * `world_addresses_len` - Length of world_addresses array
+ * * `namespaces` - Array of namespaces to subscribe to
+ * * `namespaces_len` - Length of namespaces array
+ * * `caller_addresses` - Array of caller addresses to subscribe to
+ * * `caller_addresses_len` - Length of caller_addresses array
+ * * `callback` - Function called when updates occur
+ *
+ * # Returns
+ * Result containing pointer to Subscription or error
+ */
+struct ResultSubscription client_on_activity_update(struct ToriiClient *client,
+                                                    const struct FieldElement *world_addresses,
+                                                    uintptr_t world_addresses_len,
+                                                    const char *const *namespaces,
+                                                    uintptr_t namespaces_len,
+                                                    const struct FieldElement *caller_addresses,
+                                                    uintptr_t caller_addresses_len,
+                                                    void (*callback)(struct Activity));
+
+/**
+ * Updates an existing activity subscription with new parameters
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `subscription` - Pointer to existing Subscription
+ * * `world_addresses` - Array of world addresses to subscribe to
+ * * `world_addresses_len` - Length of world_addresses array
+ * * `namespaces` - Array of namespaces to subscribe to
+ * * `namespaces_len` - Length of namespaces array
+ * * `caller_addresses` - Array of caller addresses to subscribe to
+ * * `caller_addresses_len` - Length of caller_addresses array
+ *
+ * # Returns
+ * Result containing success boolean or error
+ */
+struct Resultbool client_update_activity_subscription(struct ToriiClient *client,
+                                                      struct Subscription *subscription,
+                                                      const struct FieldElement *world_addresses,
+                                                      uintptr_t world_addresses_len,
+                                                      const char *const *namespaces,
+                                                      uintptr_t namespaces_len,
+                                                      const struct FieldElement *caller_addresses,
+                                                      uintptr_t caller_addresses_len);
+
+/**
+ * Subscribes to event message updates
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `clauses` - Array of entity key clauses to filter updates
+ * * `clauses_len` - Length of clauses array
+ * * `callback` - Function called when updates occur
+ *
+ * # Returns
+ * Result containing pointer to Subscription or error
+ */
+struct ResultSubscription client_on_event_message_update(struct ToriiClient *client,
+                                                         struct COptionClause clause,
+                                                         const struct FieldElement *world_addresses,
+                                                         uintptr_t world_addresses_len,
+                                                         void (*callback)(struct Entity));
+
+/**
+ * Subscribes to event message updates and dispatches their models to the handlers of a
+ * ModelDispatcher
+ *
+ * Handlers can be added to and removed from the dispatcher while the subscription is live.
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `clause` - Optional clause to filter updates
+ * * `world_addresses` - Array of world addresses to filter updates
+ * * `world_addresses_len` - Length of world addresses array
+ * * `dispatcher` - Pointer to the ModelDispatcher holding the handlers
+ *
+ * # Returns
+ * Result containing pointer to Subscription or error
+ */
+struct ResultSubscription client_on_event_message_dispatch(struct ToriiClient *client,
+                                                           struct COptionClause clause,
+                                                           const struct FieldElement *world_addresses,
+                                                           uintptr_t world_addresses_len,
+                                                           struct ModelDispatcher *dispatcher);
+
+/**
+ * Updates an existing event message subscription
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `subscription` - Pointer to existing Subscription
+ * * `clauses` - New array of entity key clauses
+ * * `clauses_len` - Length of new clauses array
+ *
+ * # Returns
+ * Result containing success boolean or error
+ */
+struct Resultbool client_update_event_message_subscription(struct ToriiClient *client,
+                                                           struct Subscription *subscription,
+                                                           struct COptionClause clause,
+                                                           const struct FieldElement *world_addresses,
+                                                           uintptr_t world_addresses_len);
+
+/**
+ * Subscribes to Starknet events
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `clauses` - Array of entity key clauses to filter events
+ * * `clauses_len` - Length of clauses array
+ * * `callback` - Function called when events occur
+ *
+ * # Returns
+ * Result containing pointer to Subscription or error
+ */
+struct ResultSubscription client_on_starknet_event(struct ToriiClient *client,
+                                                   const struct KeysClause *clauses,
+                                                   uintptr_t clauses_len,
+                                                   void (*callback)(struct Event));
+
+/**
+ * Watches a world for models and events registered or upgraded while the client runs
+ *
+ * Registration and upgrade events of the world trigger a refresh of its schema, as does
+ * every connection of the subscription. The cached query results of the world and the
+ * clause validator are dropped whenever a model changed
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `world_address` - Address of the world to watch
+ * * `store` - Pointer to an OptimisticStore whose predictions of upgraded models are
+ *   discarded, or null
+ * * `callback` - Function called with each registered or upgraded model, along with the
+ *   changes of its schema
+ *
+ * # Returns
+ * Result containing pointer to Subscription or error
+ */
+struct ResultSubscription client_on_schema_upgrade(struct ToriiClient *client,
+                                                   struct FieldElement world_address,
+                                                   struct OptimisticStore *store,
+                                                   void (*callback)(struct SchemaUpgrade));
+
+/**
+ * Retrieves token information for given contract addresses
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `contract_addresses` - Array of contract addresses
+ * * `contract_addresses_len` - Length of addresses array
+ * * `token_ids` - Array of token ids
+ * * `token_ids_len` - Length of token ids array
+ * * `limit` - Maximum number of tokens to return
+ * * `cursor` - Cursor to start from
+ *
+ * # Returns
+ * Result containing array of Token information or error
+ */
+struct ResultPageToken client_tokens(struct ToriiClient *client, struct TokenQuery query);
+
+/**
+ * Subscribes to token updates
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `contract_addresses` - Array of contract addresses
+ * * `callback` - Function called when updates occur
+ *
+ * # Returns
+ * Result containing pointer to Subscription or error
+ */
+struct ResultSubscription client_on_token_update(struct ToriiClient *client,
+                                                 const struct FieldElement *contract_addresses,
+                                                 uintptr_t contract_addresses_len,
+                                                 const struct U256 *token_ids,
+                                                 uintptr_t token_ids_len,
+                                                 void (*callback)(struct Token));
 
-typedef struct COptionf64 {
-  COptionf64_Tag tag;
-  union {
-    struct {
-      double some;
-    };
-  };
-} COptionf64;
+/**
+ * Gets token balances for given accounts and contracts
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `contract_addresses` - Array of contract addresses
+ * * `contract_addresses_len` - Length of contract addresses array
+ * * `account_addresses` - Array of account addresses
+ * * `account_addresses_len` - Length of account addresses array
+ * * `token_ids` - Array of token ids
+ * * `token_ids_len` - Length of token ids array
+ * * `limit` - Maximum number of token balances to return
+ * * `cursor` - Cursor to start from
+ *
+ * # Returns
+ * Result containing array of TokenBalance information or error
+ */
+struct ResultPageTokenBalance client_token_balances(struct ToriiClient *client,
+                                                    struct TokenBalanceQuery query);
 
-typedef struct SearchMatch {
-  const char *id;
-  struct CArraySearchMatchField fields;
-  struct COptionf64 score;
-} SearchMatch;
+/**
+ * Gets token collections for given accounts and contracts
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `contract_addresses` - Array of contract addresses
+ * * `contract_addresses_len` - Length of contract addresses array
+ * * `account_addresses` - Array of account addresses
+ * * `account_addresses_len` - Length of account addresses array
+ * * `token_ids` - Array of token ids
+ * * `token_ids_len` - Length of token ids array
+ * * `limit` - Maximum number of token balances to return
+ * * `cursor` - Cursor to start from
+ *
+ * # Returns
+ * Result containing array of TokenBalance information or error
+ */
+struct ResultPageTokenContract client_token_contracts(struct ToriiClient *client,
+                                                      struct TokenContractQuery query);
 
-typedef struct EnumOption {
-  const char *name;
-  struct Ty *ty;
-} EnumOption;
+/**
+ * Gets contracts matching the given query
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - ContractQuery parameters
+ *
+ * # Returns
+ * Result containing array of Contract information or error
+ */
+struct ResultCArrayContract client_contracts(struct ToriiClient *client,
+                                             struct ContractQuery query);
 
-typedef struct TaskProgress {
-  const char *task_id;
-  uint32_t count;
-  bool completed;
-} TaskProgress;
+/**
+ * Retrieves token transfers matching the given query
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `query` - TokenTransferQuery parameters
+ *
+ * # Returns
+ * Result containing array of TokenTransfer information or error
+ */
+struct ResultPageTokenTransfer client_token_transfers(struct ToriiClient *client,
+                                                      struct TokenTransferQuery query);
 
-typedef struct SearchMatchField {
-  const char *key;
-  const char *value;
-} SearchMatchField;
+/**
+ * Subscribes to contract updates
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `contract_address` - Optional contract address to filter updates
+ * * `callback` - Function called when updates occur
+ *
+ * # Returns
+ * Result containing pointer to Subscription or error
+ */
+struct ResultSubscription on_contract_update(struct ToriiClient *client,
+                                             const struct FieldElement *contract_address,
+                                             void (*callback)(struct Contract));
 
-#ifdef __cplusplus
-extern "C" {
-#endif // __cplusplus
+/**
+ * Subscribes to token balance updates
+ *
+ * # Parameters
+ * * `client` - Pointer to ToriiClient instance
+ * * `contract_addresses` - Array of contract addresses to filter (empty for all)
+ * * `contract_addresses_len` - Length of contract addresses array
+ * * `account_addresses` - Array of account addresses to filter (empty for all)
+ * * `account_addresses_len` - Length of account addresses array
+ * * `callback` - Function called when updates occur
+ *
+ * # Returns
+ * Result containing pointer to Subscription or error
+ */
+struct ResultSubscription client_on_token_balance_update(struct ToriiClient *client,
+                                                         const struct FieldElement *contract_addresses,
+                                                         uintptr_t contract_addresses_len,
+                                                         const struct FieldElement *account_addresses,
+                                                         uintptr_t account_addresses_len,
+                                                         const struct U256 *token_ids,
+                                                         uintptr_t token_ids_len,
+                                                         void (*callback)(struct TokenBalance));
 
 /**
- * Creates a new Torii client instance
+ * Updates an existing token balance subscription
  *
  * # Parameters
- * * `torii_url` - URL of the Torii server
- * * `libp2p_relay_url` - URL of the libp2p relay server
+ * * `client` - Pointer to ToriiClient instance
+ * * `subscription` - Pointer to existing Subscription
+ * * `contract_addresses` - Array of contract addresses to filter (empty for all)
+ * * `contract_addresses_len` - Length of contract addresses array
+ * * `account_addresses` - Array of account addresses to filter (empty for all)
+ * * `account_addresses_len` - Length of account addresses array
  *
  * # Returns
- * Result containing pointer to new ToriiClient instance or error
+ * Result containing success boolean or error
  */
-struct ResultToriiClient client_new(const char *torii_url);
+struct Resultbool client_update_token_balance_subscription(struct ToriiClient *client,
+                                                           struct Subscription *subscription,
+                                                           const struct FieldElement *contract_addresses,
+                                                           uintptr_t contract_addresses_len,
+                                                           const struct FieldElement *account_addresses,
+                                                           uintptr_t account_addresses_len,
+                                                           const struct U256 *token_ids,
+                                                           uintptr_t token_ids_len);
 
 /**
- * Sets a logger callback function for the client
+ * Subscribes to token transfer updates
  *
  * # Parameters
  * * `client` - Pointer to ToriiClient instance
- * * `logger` - Callback function that takes a C string parameter
+ * * `contract_addresses` - Array of contract addresses to filter (empty for all)
+ * * `contract_addresses_len` - Length of contract addresses array
+ * * `account_addresses` - Array of account addresses to filter (empty for all)
+ * * `account_addresses_len` - Length of account addresses array
+ * * `token_ids` - Array of token IDs to filter (empty for all)
+ * * `token_ids_len` - Length of token IDs array
+ * * `callback` - Function called when updates occur
+ *
+ * # Returns
+ * Result containing pointer to Subscription or error
  */
-void client_set_logger(struct ToriiClient *client, void (*logger)(const char*));
+struct ResultSubscription client_on_token_transfer_update(struct ToriiClient *client,
+                                                          const struct FieldElement *contract_addresses,
+                                                          uintptr_t contract_addresses_len,
+                                                          const struct FieldElement *account_addresses,
+                                                          uintptr_t account_addresses_len,
+                                                          const struct U256 *token_ids,
+                                                          uintptr_t token_ids_len,
+                                                          void (*callback)(struct TokenTransfer));
 
 /**
- * Publishes a message to the network
+ * Updates an existing token transfer subscription
  *
  * # Parameters
  * * `client` - Pointer to ToriiClient instance
- * * `message` - JSON string containing typed data message
- * * `signature_felts` - Array of field elements containing signature
- * * `signature_felts_len` - Length of signature array
+ * * `subscription` - Pointer to existing Subscription
+ * * `contract_addresses` - Array of contract addresses to filter (empty for all)
+ * * `contract_addresses_len` - Length of contract addresses array
+ * * `account_addresses` - Array of account addresses to filter (empty for all)
+ * * `account_addresses_len` - Length of account addresses array
+ * * `token_ids` - Array of token IDs to filter (empty for all)
+ * * `token_ids_len` - Length of token IDs array
  *
  * # Returns
- * Result containing byte array or error
+ * Result containing success boolean or error
  */
-struct Resultc_char client_publish_message(struct ToriiClient *client, struct Message message);
+struct Resultbool client_update_token_transfer_subscription(struct ToriiClient *client,
+                                                            struct Subscription *subscription,
+                                                            const struct FieldElement *contract_addresses,
+                                                            uintptr_t contract_addresses_len,
+                                                            const struct FieldElement *account_addresses,
+                                                            uintptr_t account_addresses_len,
+                                                            const struct U256 *token_ids,
+                                                            uintptr_t token_ids_len);
 
 /**
- * Publishes multiple messages to the network
+ * Performs a full-text search across indexed entities using FTS5
  *
  * # Parameters
  * * `client` - Pointer to ToriiClient instance
- * * `messages` - Array of Message structs
- * * `messages_len` - Length of messages array
+ * * `query` - Search query containing the search text and limit
  *
  * # Returns
- * Result containing array of message IDs or error
+ * Result containing SearchResponse with results grouped by table or error
  */
-struct ResultCArrayc_char client_publish_message_batch(struct ToriiClient *client,
-                                                       const struct Message *messages,
-                                                       uintptr_t messages_len);
+struct ResultSearchResponse client_search(struct ToriiClient *client, struct SearchQuery query);
 
 /**
- * Retrieves controllers for the given contract addresses
+ * Serializes a string into a byte array
+ *
+ * # Parameters
+ * * `str` - String to serialize
+ *
+ * # Returns
+ * Result containing array of FieldElements or error
+ */
+struct ResultCArrayFieldElement bytearray_serialize(const char *str);
+
+/**
+ * Deserializes field elements into a string
+ *
+ * # Parameters
+ * * `felts` - Array of field elements
+ * * `felts_len` - Length of field elements array
+ *
+ * # Returns
+ * Result containing pointer to C string or error
+ */
+struct Resultc_char bytearray_deserialize(const struct FieldElement *felts, uintptr_t felts_len);
+
+/**
+ * Computes Poseidon hash of field elements
+ *
+ * # Parameters
+ * * `felts` - Array of field elements
+ * * `felts_len` - Length of array
+ *
+ * # Returns
+ * FieldElement containing the hash result
+ */
+struct FieldElement poseidon_hash(const struct FieldElement *felts, uintptr_t felts_len);
+
+/**
+ * Gets selector from name string
+ *
+ * # Parameters
+ * * `name` - Name to compute selector from
+ *
+ * # Returns
+ * Result containing FieldElement selector or error
+ */
+struct ResultFieldElement get_selector_from_name(const char *name);
+
+/**
+ * Gets selector from tag string
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `contract_addresses` - Array of contract addresses. If empty, all controllers will be
- *   returned.
+ * * `tag` - Tag to compute selector from
  *
  * # Returns
- * Result containing controllers or error
+ * FieldElement containing the computed selector
  */
-struct ResultPageController client_controllers(struct ToriiClient *client,
-                                               struct ControllerQuery query);
+struct FieldElement get_selector_from_tag(const char *tag);
 
 /**
- * Queries entities matching given criteria
- *
- * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `query` - Query parameters
+ * Creates a ModelDispatcher, routing entity updates to handlers registered by model
  *
  * # Returns
- * Result containing array of matching entities or error
+ * Pointer to the new ModelDispatcher
  */
-struct ResultPageEntity client_entities(struct ToriiClient *client, struct Query query);
+struct ModelDispatcher *model_dispatcher_new(void);
 
 /**
- * Retrieves event messages matching the given query
+ * Registers a handler called with the hashed keys of the entity and the model whenever
+ * the given model of an entity is updated
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `query` - Query parameters
- * * `historical` - Whether to include historical messages
+ * * `dispatcher` - Pointer to ModelDispatcher instance
+ * * `model` - Tag of the model, e.g. `ns-Position`
+ * * `keys` - Optional clause the model keys have to match
+ * * `callback` - Function called with matching models
  *
  * # Returns
- * Result containing array of matching event message entities or error
+ * Id of the handler, to remove it
  */
-struct ResultPageEntity client_event_messages(struct ToriiClient *client, struct Query query);
+uint64_t model_dispatcher_add(struct ModelDispatcher *dispatcher,
+                              const char *model,
+                              struct COptionKeysClause keys,
+                              void (*callback)(struct FieldElement, struct Struct));
 
 /**
- * Gets the world metadata for the client
+ * Removes a handler from a ModelDispatcher
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
+ * * `dispatcher` - Pointer to ModelDispatcher instance
+ * * `handler_id` - Id returned by model_dispatcher_add
  *
  * # Returns
- * World structure containing world information
+ * false if there was no handler with this id
  */
-struct ResultCArrayWorld client_worlds(struct ToriiClient *client,
-                                       const struct FieldElement *world_addresses,
-                                       uintptr_t world_addresses_len);
+bool model_dispatcher_remove(struct ModelDispatcher *dispatcher, uint64_t handler_id);
 
 /**
- * Retrieves transactions matching the given query
+ * Creates an OptimisticStore, applying predicted model changes to entity updates until
+ * their transaction settles
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `query` - Query parameters
+ * * `callback` - Function called with entities whose state changed because of a
+ *   prediction, or because a prediction was rolled back or reconciled
  *
  * # Returns
- * Result containing array of matching transactions or error
+ * Pointer to the new OptimisticStore
  */
-struct ResultPageTransaction client_transactions(struct ToriiClient *client,
-                                                 struct TransactionQuery query);
+struct OptimisticStore *optimistic_store_new(void (*callback)(struct Entity));
 
 /**
- * Subscribes to transaction updates
+ * Predicts that a transaction sets the models of an entity. The models are applied to
+ * the entity until Torii reports them, or the transaction succeeded and the entity was
+ * updated, or the transaction reverts
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `filter` - Filter parameters
- * * `callback` - Function called when updates occur
- *
- * # Returns
- * Result containing pointer to Subscription or error
+ * * `store` - Pointer to OptimisticStore instance
+ * * `transaction_hash` - Hash of the transaction, as returned by account_execute_raw
+ * * `entity` - Pointer to the entity holding the predicted models
  */
-struct ResultSubscription client_on_transaction(struct ToriiClient *client,
-                                                struct COptionTransactionFilter filter,
-                                                void (*callback)(struct Transaction));
+void optimistic_store_predict(struct OptimisticStore *store,
+                              struct FieldElement transaction_hash,
+                              const struct Entity *entity);
 
 /**
- * Subscribes to entity state updates
+ * Records the outcome of a transaction, for transactions not watched with
+ * optimistic_store_watch. Predictions of a reverted transaction are rolled back
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `clauses` - Array of entity key clauses to filter updates
- * * `clauses_len` - Length of clauses array
- * * `callback` - Function called when updates occur
+ * * `store` - Pointer to OptimisticStore instance
+ * * `transaction_hash` - Hash of the transaction
+ * * `succeeded` - Whether the transaction succeeded
+ */
+void optimistic_store_settle(struct OptimisticStore *store,
+                             struct FieldElement transaction_hash,
+                             bool succeeded);
+
+/**
+ * Waits in the background for the receipt of a transaction and settles its predictions.
+ * Predictions are kept pending if the receipt can't be fetched, to be settled with
+ * optimistic_store_settle
  *
- * # Returns
- * Result containing pointer to Subscription or error
+ * # Parameters
+ * * `store` - Pointer to OptimisticStore instance
+ * * `provider` - Pointer to Provider
+ * * `transaction_hash` - Hash of the transaction
  */
-struct ResultSubscription client_on_entity_state_update(struct ToriiClient *client,
-                                                        struct COptionClause clause,
-                                                        const struct FieldElement *world_addresses,
-                                                        uintptr_t world_addresses_len,
-                                                        void (*callback)(struct Entity));
+void optimistic_store_watch(struct OptimisticStore *store,
+                            struct Provider *provider,
+                            struct FieldElement transaction_hash);
 
 /**
- * Updates an existing entity subscription with new clauses
+ * Checks whether an entity update reports the deletion of the entity, i.e. it has no
+ * models
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `subscription` - Pointer to existing Subscription
- * * `clauses` - New array of entity key clauses
- * * `clauses_len` - Length of new clauses array
+ * * `entity` - Pointer to the entity received from a subscription
  *
  * # Returns
- * Result containing success boolean or error
+ * true if the entity was deleted
  */
-struct Resultbool client_update_entity_subscription(struct ToriiClient *client,
-                                                    struct Subscription *subscription,
-                                                    struct COptionClause clause,
-                                                    const struct FieldElement *world_addresses,
-                                                    uintptr_t world_addresses_len);
+bool entity_is_deleted(const struct Entity *entity);
 
 /**
- * Retrieves aggregations (leaderboards, stats, rankings) matching query parameter
+ * Checks whether an entity matches a clause, with the same semantics as Torii's queries
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `query` - AggregationQuery containing aggregator_ids, entity_ids, and pagination
+ * * `entity` - Pointer to the entity
+ * * `clause` - Clause to evaluate
  *
  * # Returns
- * Result containing Page of AggregationEntry or error
+ * true if Torii would return the entity for the clause
  */
-struct ResultPageAggregationEntry client_aggregations(struct ToriiClient *client,
-                                                      struct AggregationQuery query);
+bool entity_matches_clause(const struct Entity *entity, struct Clause clause);
 
 /**
- * Subscribes to aggregation updates (leaderboards, stats, rankings)
+ * Gets the type and value at a path within a type, e.g. a member of a model. Models of
+ * entities are accessed by wrapping them in a Ty of the Struct_ variant
+ *
+ * Paths are made of struct members and tuple indices separated by dots, enum variants
+ * following `::` and array indices in brackets, e.g. `vec.x`, `dir::Left`, `pair.1` or
+ * `items[2].x`. A variant only resolves when it is the selected one
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `aggregator_ids` - Array of aggregator IDs to subscribe to
- * * `aggregator_ids_len` - Length of aggregator_ids array
- * * `entity_ids` - Array of entity IDs to subscribe to
- * * `entity_ids_len` - Length of entity_ids array
- * * `callback` - Function called when updates occur
+ * * `ty` - Pointer to the type, e.g. a model
+ * * `path` - Path as C string, e.g. `vec.x`
  *
  * # Returns
- * Result containing pointer to Subscription or error
+ * Result containing the type at the path, or an error naming the part of the path that
+ * doesn't resolve
  */
-struct ResultSubscription client_on_aggregation_update(struct ToriiClient *client,
-                                                       const char *const *aggregator_ids,
-                                                       uintptr_t aggregator_ids_len,
-                                                       const char *const *entity_ids,
-                                                       uintptr_t entity_ids_len,
-                                                       void (*callback)(struct AggregationEntry));
+struct ResultTy ty_get(const struct Ty *ty, const char *path);
 
 /**
- * Updates an existing aggregation subscription with new parameters
+ * Gets the u32 at a path within a type, see ty_get
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `subscription` - Pointer to existing Subscription
- * * `aggregator_ids` - Array of aggregator IDs to subscribe to
- * * `aggregator_ids_len` - Length of aggregator_ids array
- * * `entity_ids` - Array of entity IDs to subscribe to
- * * `entity_ids_len` - Length of entity_ids array
+ * * `ty` - Pointer to the type, e.g. a model
+ * * `path` - Path as C string, e.g. `vec.x`
  *
  * # Returns
- * Result containing success boolean or error
+ * Result containing the value, or an error if the path doesn't resolve to a u32 with a value
  */
-struct Resultbool client_update_aggregation_subscription(struct ToriiClient *client,
-                                                         struct Subscription *subscription,
-                                                         const char *const *aggregator_ids,
-                                                         uintptr_t aggregator_ids_len,
-                                                         const char *const *entity_ids,
-                                                         uintptr_t entity_ids_len);
+struct Resultu32 ty_get_u32(const struct Ty *ty, const char *path);
 
 /**
- * Retrieves achievements matching query parameter
+ * Gets the felt252, ContractAddress, ClassHash or EthAddress at a path within a type, see
+ * ty_get
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `query` - AchievementQuery containing world_addresses, namespaces, hidden filter, and
- *   pagination
+ * * `ty` - Pointer to the type, e.g. a model
+ * * `path` - Path as C string, e.g. `vec.x`
  *
  * # Returns
- * Result containing Page of Achievement or error
+ * Result containing the felt, or an error if the path doesn't resolve to one with a value
  */
-struct ResultPageAchievement client_achievements(struct ToriiClient *client,
-                                                 struct AchievementQuery query);
+struct ResultFieldElement ty_get_felt(const struct Ty *ty, const char *path);
 
 /**
- * Retrieves player achievement data matching query parameter
+ * Gets the u256 at a path within a type as a 0x-prefixed hex string, see ty_get
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `query` - PlayerAchievementQuery containing world_addresses, namespaces, player_addresses,
- *   and pagination
+ * * `ty` - Pointer to the type, e.g. a model
+ * * `path` - Path as C string, e.g. `vec.x`
  *
  * # Returns
- * Result containing Page of PlayerAchievementEntry or error
+ * Result containing the hex string, or an error if the path doesn't resolve to a u256 with a value
  */
-struct ResultPagePlayerAchievementEntry client_player_achievements(struct ToriiClient *client,
-                                                                   struct PlayerAchievementQuery query);
+struct Resultc_char ty_get_u256(const struct Ty *ty, const char *path);
 
 /**
- * Subscribes to achievement progression updates
+ * Gets the bool at a path within a type, see ty_get
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `world_addresses` - Array of world addresses to subscribe to
- * * `world_addresses_len` - Length of world_addresses array
- * * `namespaces` - Array of namespaces to subscribe to
- * * `namespaces_len` - Length of namespaces array
- * * `player_addresses` - Array of player addresses to subscribe to
- * * `player_addresses_len` - Length of player_addresses array
- * * `achievement_ids` - Array of achievement IDs to subscribe to
- * * `achievement_ids_len` - Length of achievement_ids array
- * * `callback` - Function called when updates occur
+ * * `ty` - Pointer to the type, e.g. a model
+ * * `path` - Path as C string, e.g. `vec.x`
  *
  * # Returns
- * Result containing pointer to Subscription or error
+ * Result containing the value, or an error if the path doesn't resolve to a bool with a value
  */
-struct ResultSubscription client_on_achievement_progression_update(struct ToriiClient *client,
-                                                                   const struct FieldElement *world_addresses,
-                                                                   uintptr_t world_addresses_len,
-                                                                   const char *const *namespaces,
-                                                                   uintptr_t namespaces_len,
-                                                                   const struct FieldElement *player_addresses,
-                                                                   uintptr_t player_addresses_len,
-                                                                   const char *const *achievement_ids,
-                                                                   uintptr_t achievement_ids_len,
-                                                                   void (*callback)(struct AchievementProgression));
+struct Resultbool ty_get_bool(const struct Ty *ty, const char *path);
 
 /**
- * Updates an existing achievement progression subscription with new parameters
+ * Gets the ByteArray at a path within a type as a string, see ty_get
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `subscription` - Pointer to existing Subscription
- * * `world_addresses` - Array of world addresses to subscribe to
- * * `world_addresses_len` - Length of world_addresses array
- * * `namespaces` - Array of namespaces to subscribe to
- * * `namespaces_len` - Length of namespaces array
- * * `player_addresses` - Array of player addresses to subscribe to
- * * `player_addresses_len` - Length of player_addresses array
- * * `achievement_ids` - Array of achievement IDs to subscribe to
- * * `achievement_ids_len` - Length of achievement_ids array
+ * * `ty` - Pointer to the type, e.g. a model
+ * * `path` - Path as C string, e.g. `vec.x`
  *
  * # Returns
- * Result containing success boolean or error
+ * Result containing the string, or an error if the path doesn't resolve to a ByteArray or
+ * the string holds a NUL byte
  */
-struct Resultbool client_update_achievement_progression_subscription(struct ToriiClient *client,
-                                                                     struct Subscription *subscription,
-                                                                     const struct FieldElement *world_addresses,
-                                                                     uintptr_t world_addresses_len,
-                                                                     const char *const *namespaces,
-                                                                     uintptr_t namespaces_len,
-                                                                     const struct FieldElement *player_addresses,
-                                                                     uintptr_t player_addresses_len,
-                                                                     const char *const *achievement_ids,
-                                                                     uintptr_t achievement_ids_len);
+struct Resultc_char ty_get_byte_array(const struct Ty *ty, const char *path);
 
 /**
- * Retrieves activities (user session tracking) matching query parameter
+ * Gets the name of the selected variant of the enum at a path within a type, see ty_get
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `query` - ActivityQuery containing world_addresses, namespaces, caller_addresses, and
- *   pagination
+ * * `ty` - Pointer to the type, e.g. a model
+ * * `path` - Path as C string, e.g. `vec.x`
  *
  * # Returns
- * Result containing Page of Activity or error
+ * Result containing the variant name, or an error if the path doesn't resolve to an enum with a value
  */
-struct ResultPageActivity client_activities(struct ToriiClient *client, struct ActivityQuery query);
+struct Resultc_char ty_get_variant(const struct Ty *ty,
+                                   const char *path);
 
 /**
- * Subscribes to activity updates (user session tracking)
+ * Creates a builder of a value from its schema
+ *
+ * The value starts from defaults: zero for primitives, the first variant of enums, an empty
+ * string for byte arrays, no elements for arrays and default elements for fixed size arrays.
+ * Values are set by path as in ty_get and checked against the schema. A variant in a path
+ * selects it, and the index following the last element of an array appends an element. A
+ * setter that fails leaves the value unchanged
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `world_addresses` - Array of world addresses to subscribe to
- * * `world_addresses_len` - Length of world_addresses array
- * * `namespaces` - Array of namespaces to subscribe to
- * * `namespaces_len` - Length of namespaces array
- * * `caller_addresses` - Array of caller addresses to subscribe to
- * * `caller_addresses_len` - Length of caller_addresses array
- * * `callback` - Function called when updates occur
+ * * `schema` - Pointer to the type to build a value of, e.g. the schema of a model
  *
  * # Returns
- * Result containing pointer to Subscription or error
+ * Pointer to the TyBuilder, to free with ty_builder_free
  */
-struct ResultSubscription client_on_activity_update(struct ToriiClient *client,
-                                                    const struct FieldElement *world_addresses,
-                                                    uintptr_t world_addresses_len,
-                                                    const char *const *namespaces,
-                                                    uintptr_t namespaces_len,
-                                                    const struct FieldElement *caller_addresses,
-                                                    uintptr_t caller_addresses_len,
-                                                    void (*callback)(struct Activity));
+struct TyBuilder *ty_builder_new(const struct Ty *schema);
 
 /**
- * Updates an existing activity subscription with new parameters
+ * Creates a builder of a model of a world, see ty_builder_new
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `subscription` - Pointer to existing Subscription
- * * `world_addresses` - Array of world addresses to subscribe to
- * * `world_addresses_len` - Length of world_addresses array
- * * `namespaces` - Array of namespaces to subscribe to
- * * `namespaces_len` - Length of namespaces array
- * * `caller_addresses` - Array of caller addresses to subscribe to
- * * `caller_addresses_len` - Length of caller_addresses array
+ * * `schema` - Pointer to WorldSchema instance
+ * * `tag` - Tag of the model, e.g. `ns-Position`
  *
  * # Returns
- * Result containing success boolean or error
+ * Result containing pointer to the TyBuilder, to free with ty_builder_free, or an error if
+ * the world has no such model
  */
-struct Resultbool client_update_activity_subscription(struct ToriiClient *client,
-                                                      struct Subscription *subscription,
-                                                      const struct FieldElement *world_addresses,
-                                                      uintptr_t world_addresses_len,
-                                                      const char *const *namespaces,
-                                                      uintptr_t namespaces_len,
-                                                      const struct FieldElement *caller_addresses,
-                                                      uintptr_t caller_addresses_len);
+struct ResultTyBuilder world_schema_builder(const struct WorldSchema *schema, const char *tag);
 
 /**
- * Subscribes to event message updates
+ * Sets the value at a path of a builder, which must have the type of the schema at that path
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `clauses` - Array of entity key clauses to filter updates
- * * `clauses_len` - Length of clauses array
- * * `callback` - Function called when updates occur
+ * * `builder` - Pointer to TyBuilder instance
+ * * `path` - Path as C string, e.g. `vec.x`
+ * * `value` - Pointer to the value
  *
  * # Returns
- * Result containing pointer to Subscription or error
+ * Result containing true, or an error if the path or the value doesn't match the schema
  */
-struct ResultSubscription client_on_event_message_update(struct ToriiClient *client,
-                                                         struct COptionClause clause,
-                                                         const struct FieldElement *world_addresses,
-                                                         uintptr_t world_addresses_len,
-                                                         void (*callback)(struct Entity));
+struct Resultbool ty_builder_set(struct TyBuilder *builder,
+                                 const char *path,
+                                 const struct Ty *value);
 
 /**
- * Updates an existing event message subscription
+ * Sets the u32 at a path of a builder, see ty_builder_set
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `subscription` - Pointer to existing Subscription
- * * `clauses` - New array of entity key clauses
- * * `clauses_len` - Length of new clauses array
+ * * `builder` - Pointer to TyBuilder instance
+ * * `path` - Path as C string, e.g. `vec.x`
+ * * `value` - Value to set
  *
  * # Returns
- * Result containing success boolean or error
+ * Result containing true, or an error if the path doesn't resolve to a u32
  */
-struct Resultbool client_update_event_message_subscription(struct ToriiClient *client,
-                                                           struct Subscription *subscription,
-                                                           struct COptionClause clause,
-                                                           const struct FieldElement *world_addresses,
-                                                           uintptr_t world_addresses_len);
+struct Resultbool ty_builder_set_u32(struct TyBuilder *builder, const char *path, uint32_t value);
 
 /**
- * Subscribes to Starknet events
+ * Sets the felt252, ContractAddress, ClassHash or EthAddress at a path of a builder, see
+ * ty_builder_set
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `clauses` - Array of entity key clauses to filter events
- * * `clauses_len` - Length of clauses array
- * * `callback` - Function called when events occur
+ * * `builder` - Pointer to TyBuilder instance
+ * * `path` - Path as C string, e.g. `player`
+ * * `value` - Felt to set
  *
  * # Returns
- * Result containing pointer to Subscription or error
+ * Result containing true, or an error if the path doesn't resolve to a felt
  */
-struct ResultSubscription client_on_starknet_event(struct ToriiClient *client,
-                                                   const struct KeysClause *clauses,
-                                                   uintptr_t clauses_len,
-                                                   void (*callback)(struct Event));
+struct Resultbool ty_builder_set_felt(struct TyBuilder *builder,
+                                      const char *path,
+                                      struct FieldElement value);
 
 /**
- * Retrieves token information for given contract addresses
+ * Sets the bool at a path of a builder, see ty_builder_set
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `contract_addresses` - Array of contract addresses
- * * `contract_addresses_len` - Length of addresses array
- * * `token_ids` - Array of token ids
- * * `token_ids_len` - Length of token ids array
- * * `limit` - Maximum number of tokens to return
- * * `cursor` - Cursor to start from
+ * * `builder` - Pointer to TyBuilder instance
+ * * `path` - Path as C string, e.g. `alive`
+ * * `value` - Value to set
  *
  * # Returns
- * Result containing array of Token information or error
+ * Result containing true, or an error if the path doesn't resolve to a bool
  */
-struct ResultPageToken client_tokens(struct ToriiClient *client, struct TokenQuery query);
+struct Resultbool ty_builder_set_bool(struct TyBuilder *builder, const char *path, bool value);
 
 /**
- * Subscribes to token updates
- *
- * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `contract_addresses` - Array of contract addresses
- * * `callback` - Function called when updates occur
+ * Sets the ByteArray at a path of a builder, see ty_builder_set
+ *
+ * # Parameters
+ * * `builder` - Pointer to TyBuilder instance
+ * * `path` - Path as C string, e.g. `name`
+ * * `value` - String to set
  *
  * # Returns
- * Result containing pointer to Subscription or error
+ * Result containing true, or an error if the path doesn't resolve to a ByteArray
  */
-struct ResultSubscription client_on_token_update(struct ToriiClient *client,
-                                                 const struct FieldElement *contract_addresses,
-                                                 uintptr_t contract_addresses_len,
-                                                 const struct U256 *token_ids,
-                                                 uintptr_t token_ids_len,
-                                                 void (*callback)(struct Token));
+struct Resultbool ty_builder_set_byte_array(struct TyBuilder *builder,
+                                            const char *path,
+                                            const char *value);
 
 /**
- * Gets token balances for given accounts and contracts
+ * Selects a variant of the enum at a path of a builder, keeping the value of the variant
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `contract_addresses` - Array of contract addresses
- * * `contract_addresses_len` - Length of contract addresses array
- * * `account_addresses` - Array of account addresses
- * * `account_addresses_len` - Length of account addresses array
- * * `token_ids` - Array of token ids
- * * `token_ids_len` - Length of token ids array
- * * `limit` - Maximum number of token balances to return
- * * `cursor` - Cursor to start from
+ * * `builder` - Pointer to TyBuilder instance
+ * * `path` - Path as C string, e.g. `dir`
+ * * `variant` - Name of the variant
  *
  * # Returns
- * Result containing array of TokenBalance information or error
+ * Result containing true, or an error if the path doesn't resolve to an enum with the variant
  */
-struct ResultPageTokenBalance client_token_balances(struct ToriiClient *client,
-                                                    struct TokenBalanceQuery query);
+struct Resultbool ty_builder_set_variant(struct TyBuilder *builder,
+                                         const char *path,
+                                         const char *variant);
 
 /**
- * Gets token collections for given accounts and contracts
+ * Sets the primitive or ByteArray at a path of a builder from text, see ty_builder_set
+ *
+ * Numbers are decimal or 0x-prefixed hex, e.g. the strings of ty_get_u256, and booleans are
+ * `true` or `false`
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `contract_addresses` - Array of contract addresses
- * * `contract_addresses_len` - Length of contract addresses array
- * * `account_addresses` - Array of account addresses
- * * `account_addresses_len` - Length of account addresses array
- * * `token_ids` - Array of token ids
- * * `token_ids_len` - Length of token ids array
- * * `limit` - Maximum number of token balances to return
- * * `cursor` - Cursor to start from
+ * * `builder` - Pointer to TyBuilder instance
+ * * `path` - Path as C string, e.g. `score`
+ * * `text` - Value as C string
  *
  * # Returns
- * Result containing array of TokenBalance information or error
+ * Result containing true, or an error if the text isn't a valid value of the type at the path
  */
-struct ResultPageTokenContract client_token_contracts(struct ToriiClient *client,
-                                                      struct TokenContractQuery query);
+struct Resultbool ty_builder_set_str(struct TyBuilder *builder, const char *path, const char *text);
 
 /**
- * Gets contracts matching the given query
+ * Gets the value built so far
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `query` - ContractQuery parameters
+ * * `builder` - Pointer to TyBuilder instance
  *
  * # Returns
- * Result containing array of Contract information or error
+ * The value, a model being a Ty of the Struct_ variant
  */
-struct ResultCArrayContract client_contracts(struct ToriiClient *client,
-                                             struct ContractQuery query);
+struct Ty ty_builder_build(const struct TyBuilder *builder);
 
 /**
- * Retrieves token transfers matching the given query
+ * Gets the value built so far as JSON, e.g. the message of an offchain message
+ *
+ * Structs are objects, unit variants their name and other variants an object of their name
+ * and value, tuples and arrays are arrays and byte arrays strings
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `query` - TokenTransferQuery parameters
+ * * `builder` - Pointer to TyBuilder instance
  *
  * # Returns
- * Result containing array of TokenTransfer information or error
+ * Result containing the JSON as C string, or an error if a primitive has no value
  */
-struct ResultPageTokenTransfer client_token_transfers(struct ToriiClient *client,
-                                                      struct TokenTransferQuery query);
+struct Resultc_char ty_builder_to_json(const struct TyBuilder *builder);
 
 /**
- * Subscribes to contract updates
+ * Serializes the value built so far as Cairo calldata, e.g. an argument of a call
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `contract_address` - Optional contract address to filter updates
- * * `callback` - Function called when updates occur
+ * * `builder` - Pointer to TyBuilder instance
  *
  * # Returns
- * Result containing pointer to Subscription or error
+ * Result containing array of FieldElements or error
  */
-struct ResultSubscription on_contract_update(struct ToriiClient *client,
-                                             const struct FieldElement *contract_address,
-                                             void (*callback)(struct Contract));
+struct ResultCArrayFieldElement ty_builder_calldata(const struct TyBuilder *builder);
 
 /**
- * Subscribes to token balance updates
+ * Parses a textual filter into a clause
+ *
+ * Filters such as `ns-Position.x > 10 and keys(ns-Position) = [0x1, *]` combine member
+ * comparisons, keys and hashed keys clauses with `and`, `or` and parentheses.
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `contract_addresses` - Array of contract addresses to filter (empty for all)
- * * `contract_addresses_len` - Length of contract addresses array
- * * `account_addresses` - Array of account addresses to filter (empty for all)
- * * `account_addresses_len` - Length of account addresses array
- * * `callback` - Function called when updates occur
+ * * `filter` - Filter as C string
  *
  * # Returns
- * Result containing pointer to Subscription or error
+ * Result containing the clause or an error with the line and column of the failure
  */
-struct ResultSubscription client_on_token_balance_update(struct ToriiClient *client,
-                                                         const struct FieldElement *contract_addresses,
-                                                         uintptr_t contract_addresses_len,
-                                                         const struct FieldElement *account_addresses,
-                                                         uintptr_t account_addresses_len,
-                                                         const struct U256 *token_ids,
-                                                         uintptr_t token_ids_len,
-                                                         void (*callback)(struct TokenBalance));
+struct ResultClause clause_parse(const char *filter);
 
 /**
- * Updates an existing token balance subscription
+ * Prints a clause as a textual filter, which `clause_parse` turns back into the clause
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `subscription` - Pointer to existing Subscription
- * * `contract_addresses` - Array of contract addresses to filter (empty for all)
- * * `contract_addresses_len` - Length of contract addresses array
- * * `account_addresses` - Array of account addresses to filter (empty for all)
- * * `account_addresses_len` - Length of account addresses array
+ * * `clause` - Clause to print
  *
  * # Returns
- * Result containing success boolean or error
+ * Pointer to C string containing the filter
  */
-struct Resultbool client_update_token_balance_subscription(struct ToriiClient *client,
-                                                           struct Subscription *subscription,
-                                                           const struct FieldElement *contract_addresses,
-                                                           uintptr_t contract_addresses_len,
-                                                           const struct FieldElement *account_addresses,
-                                                           uintptr_t account_addresses_len,
-                                                           const struct U256 *token_ids,
-                                                           uintptr_t token_ids_len);
+const char *clause_to_string(struct Clause clause);
 
 /**
- * Subscribes to token transfer updates
+ * Encodes a query as canonical JSON, shared by every binding
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `contract_addresses` - Array of contract addresses to filter (empty for all)
- * * `contract_addresses_len` - Length of contract addresses array
- * * `account_addresses` - Array of account addresses to filter (empty for all)
- * * `account_addresses_len` - Length of account addresses array
- * * `token_ids` - Array of token IDs to filter (empty for all)
- * * `token_ids_len` - Length of token IDs array
- * * `callback` - Function called when updates occur
+ * * `query` - Query to encode
  *
  * # Returns
- * Result containing pointer to Subscription or error
+ * Result containing pointer to C string or error
  */
-struct ResultSubscription client_on_token_transfer_update(struct ToriiClient *client,
-                                                          const struct FieldElement *contract_addresses,
-                                                          uintptr_t contract_addresses_len,
-                                                          const struct FieldElement *account_addresses,
-                                                          uintptr_t account_addresses_len,
-                                                          const struct U256 *token_ids,
-                                                          uintptr_t token_ids_len,
-                                                          void (*callback)(struct TokenTransfer));
+struct Resultc_char query_to_json(struct Query query);
 
 /**
- * Updates an existing token transfer subscription
+ * Decodes a query from its canonical JSON encoding
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `subscription` - Pointer to existing Subscription
- * * `contract_addresses` - Array of contract addresses to filter (empty for all)
- * * `contract_addresses_len` - Length of contract addresses array
- * * `account_addresses` - Array of account addresses to filter (empty for all)
- * * `account_addresses_len` - Length of account addresses array
- * * `token_ids` - Array of token IDs to filter (empty for all)
- * * `token_ids_len` - Length of token IDs array
+ * * `json` - JSON string of the query
  *
  * # Returns
- * Result containing success boolean or error
+ * Result containing the query or error
  */
-struct Resultbool client_update_token_transfer_subscription(struct ToriiClient *client,
-                                                            struct Subscription *subscription,
-                                                            const struct FieldElement *contract_addresses,
-                                                            uintptr_t contract_addresses_len,
-                                                            const struct FieldElement *account_addresses,
-                                                            uintptr_t account_addresses_len,
-                                                            const struct U256 *token_ids,
-                                                            uintptr_t token_ids_len);
+struct ResultQuery query_from_json(const char *json);
 
 /**
- * Performs a full-text search across indexed entities using FTS5
+ * Encodes a query in the compact binary encoding, shared by every binding
  *
  * # Parameters
- * * `client` - Pointer to ToriiClient instance
- * * `query` - Search query containing the search text and limit
+ * * `query` - Query to encode
  *
  * # Returns
- * Result containing SearchResponse with results grouped by table or error
+ * Result containing the encoded bytes or error
  */
-struct ResultSearchResponse client_search(struct ToriiClient *client, struct SearchQuery query);
+struct ResultCArrayu8 query_to_bytes(struct Query query);
 
 /**
- * Serializes a string into a byte array
+ * Decodes a query from its compact binary encoding
  *
  * # Parameters
- * * `str` - String to serialize
+ * * `bytes` - Encoded query
+ * * `bytes_len` - Length of the encoded query
  *
  * # Returns
- * Result containing array of FieldElements or error
+ * Result containing the query or error
  */
-struct ResultCArrayFieldElement bytearray_serialize(const char *str);
+struct ResultQuery query_from_bytes(const uint8_t *bytes, uintptr_t bytes_len);
 
 /**
- * Deserializes field elements into a string
+ * Encodes a clause as canonical JSON, shared by every binding
  *
  * # Parameters
- * * `felts` - Array of field elements
- * * `felts_len` - Length of field elements array
+ * * `clause` - Clause to encode
  *
  * # Returns
  * Result containing pointer to C string or error
  */
-struct Resultc_char bytearray_deserialize(const struct FieldElement *felts, uintptr_t felts_len);
+struct Resultc_char clause_to_json(struct Clause clause);
 
 /**
- * Computes Poseidon hash of field elements
+ * Decodes a clause from its canonical JSON encoding
  *
  * # Parameters
- * * `felts` - Array of field elements
- * * `felts_len` - Length of array
+ * * `json` - JSON string of the clause
  *
  * # Returns
- * FieldElement containing the hash result
+ * Result containing the clause or error
  */
-struct FieldElement poseidon_hash(const struct FieldElement *felts, uintptr_t felts_len);
+struct ResultClause clause_from_json(const char *json);
 
 /**
- * Gets selector from name string
+ * Encodes a clause in the compact binary encoding, shared by every binding
  *
  * # Parameters
- * * `name` - Name to compute selector from
+ * * `clause` - Clause to encode
  *
  * # Returns
- * Result containing FieldElement selector or error
+ * Result containing the encoded bytes or error
  */
-struct ResultFieldElement get_selector_from_name(const char *name);
+struct ResultCArrayu8 clause_to_bytes(struct Clause clause);
 
 /**
- * Gets selector from tag string
+ * Decodes a clause from its compact binary encoding
  *
  * # Parameters
- * * `tag` - Tag to compute selector from
+ * * `bytes` - Encoded clause
+ * * `bytes_len` - Length of the encoded clause
  *
  * # Returns
- * FieldElement containing the computed selector
+ * Result containing the clause or error
  */
-struct FieldElement get_selector_from_tag(const char *tag);
+struct ResultClause clause_from_bytes(const uint8_t *bytes, uintptr_t bytes_len);
 
 /**
  * Computes Starknet keccak hash of bytes
@@ -2423,6 +5135,72 @@ struct FieldElement hash_get_contract_address(struct FieldElement class_hash,
  */
 void subscription_cancel(struct Subscription *subscription);
 
+/**
+ * Gets the connection state of a subscription
+ *
+ * # Parameters
+ * * `subscription` - Pointer to Subscription
+ *
+ * # Returns
+ * Current SubscriptionState
+ */
+struct SubscriptionState subscription_state(const struct Subscription *subscription);
+
+/**
+ * Registers a callback notified of every connection state transition of a subscription
+ *
+ * The callback is immediately called with the current state and keeps being called after
+ * the subscription is cancelled, until it reports the `Closed` state.
+ *
+ * # Parameters
+ * * `subscription` - Pointer to Subscription
+ * * `callback` - Function called with each new SubscriptionState
+ */
+void subscription_on_status(struct Subscription *subscription,
+                            void (*callback)(struct SubscriptionState));
+
+/**
+ * Sets how a subscription's updates are buffered, coalesced and rate limited before
+ * reaching its callback
+ *
+ * Coalescing only applies to entity and event message subscriptions.
+ *
+ * # Parameters
+ * * `subscription` - Pointer to Subscription
+ * * `policy` - DeliveryPolicy applied to the updates received from now on
+ */
+void subscription_set_delivery_policy(struct Subscription *subscription,
+                                      struct DeliveryPolicy policy);
+
+/**
+ * Sets the models and members kept from the updates of an entity or event message
+ * subscription, e.g. `ns-Position` or `ns-Stats.health`
+ *
+ * Updates are projected as they are delivered, changes, diffs and predictions being
+ * computed from whole updates, and skipped when nothing is left of them. Other
+ * subscriptions ignore the projection.
+ *
+ * # Parameters
+ * * `subscription` - Pointer to Subscription
+ * * `selectors` - Array of model tags and member paths, every model and member being kept
+ *   when empty
+ * * `selectors_len` - Length of selectors array
+ */
+void subscription_set_projection(struct Subscription *subscription,
+                                 const char *const *selectors,
+                                 uintptr_t selectors_len);
+
+/**
+ * Gets the number of delivered, dropped and coalesced updates of a subscription
+ *
+ * # Parameters
+ * * `subscription` - Pointer to Subscription
+ *
+ * # Returns
+ * DeliveryStats of the subscription
+ */
+struct DeliveryStats subscription_delivery_stats(const struct Subscription *subscription);
+
 /**
  * Frees a ToriiClient instance
  *
@@ -2431,6 +5209,120 @@ void subscription_cancel(struct Subscription *subscription);
  */
 void client_free(struct ToriiClient *t);
 
+/**
+ * Frees a ModelDispatcher instance. Subscriptions using it keep dispatching until they are
+ * cancelled.
+ *
+ * # Parameters
+ * * `dispatcher` - Pointer to ModelDispatcher to free
+ */
+void model_dispatcher_free(struct ModelDispatcher *dispatcher);
+
+/**
+ * Frees an OptimisticStore instance. Subscriptions and watches using it keep applying its
+ * predictions until they are cancelled or settled.
+ *
+ * # Parameters
+ * * `store` - Pointer to OptimisticStore to free
+ */
+void optimistic_store_free(struct OptimisticStore *store);
+
+/**
+ * Frees a WorldSchema instance
+ *
+ * # Parameters
+ * * `schema` - Pointer to WorldSchema to free
+ */
+void world_schema_free(struct WorldSchema *schema);
+
+/**
+ * Frees a TyBuilder instance
+ *
+ * # Parameters
+ * * `builder` - Pointer to TyBuilder to free
+ */
+void ty_builder_free(struct TyBuilder *builder);
+
+/**
+ * Frees an EntityIterator instance
+ *
+ * # Parameters
+ * * `iterator` - Pointer to EntityIterator to free
+ */
+void entity_iterator_free(struct EntityIterator *iterator);
+
+/**
+ * Frees an ControllerIterator instance
+ *
+ * # Parameters
+ * * `iterator` - Pointer to ControllerIterator to free
+ */
+void controller_iterator_free(struct ControllerIterator *iterator);
+
+/**
+ * Frees an TokenIterator instance
+ *
+ * # Parameters
+ * * `iterator` - Pointer to TokenIterator to free
+ */
+void token_iterator_free(struct TokenIterator *iterator);
+
+/**
+ * Frees an TokenBalanceIterator instance
+ *
+ * # Parameters
+ * * `iterator` - Pointer to TokenBalanceIterator to free
+ */
+void token_balance_iterator_free(struct TokenBalanceIterator *iterator);
+
+/**
+ * Frees an TokenTransferIterator instance
+ *
+ * # Parameters
+ * * `iterator` - Pointer to TokenTransferIterator to free
+ */
+void token_transfer_iterator_free(struct TokenTransferIterator *iterator);
+
+/**
+ * Frees an TransactionIterator instance
+ *
+ * # Parameters
+ * * `iterator` - Pointer to TransactionIterator to free
+ */
+void transaction_iterator_free(struct TransactionIterator *iterator);
+
+/**
+ * Frees an ActivityIterator instance
+ *
+ * # Parameters
+ * * `iterator` - Pointer to ActivityIterator to free
+ */
+void activity_iterator_free(struct ActivityIterator *iterator);
+
+/**
+ * Frees an AchievementIterator instance
+ *
+ * # Parameters
+ * * `iterator` - Pointer to AchievementIterator to free
+ */
+void achievement_iterator_free(struct AchievementIterator *iterator);
+
+/**
+ * Frees an PlayerAchievementIterator instance
+ *
+ * # Parameters
+ * * `iterator` - Pointer to PlayerAchievementIterator to free
+ */
+void player_achievement_iterator_free(struct PlayerAchievementIterator *iterator);
+
+/**
+ * Frees an AggregationIterator instance
+ *
+ * # Parameters
+ * * `iterator` - Pointer to AggregationIterator to free
+ */
+void aggregation_iterator_free(struct AggregationIterator *iterator);
+
 /**
  * Frees a Provider instance
  *
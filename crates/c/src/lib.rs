@@ -1,18 +1,40 @@
 mod types;
 
 use std::ffi::{c_void, CStr, CString};
+use std::fmt::Display;
+use std::future::Future;
 use std::ops::Deref;
 use std::os::raw::c_char;
 use std::sync::Arc;
-use std::time::Duration;
 
 use cainome::cairo_serde::{self, ByteArray, CairoSerde};
 use crypto_bigint::U256;
+use dojo_core::batch;
+use dojo_core::cache::{CacheKey, Lookup, Scope};
+use dojo_core::change::{is_deleted, EntityClassifier};
+use dojo_core::codec::Codec;
+use dojo_core::connection::Connection;
 use dojo_core::constants;
-use dojo_core::utils::watch_tx;
+use dojo_core::delivery::{self, Coalesce, DeliveryControl};
+use dojo_core::diff::EntityDiffer;
+use dojo_core::dispatch::Dispatcher;
+use dojo_core::history::{self, Timelines};
+use dojo_core::mux::{EntityFilter, Lease, Mux, TokenBalanceFilter, Topic};
+use dojo_core::optimistic::Optimistic;
+use dojo_core::pagination::{Paged, Paginator};
+use dojo_core::polling::EntitySource;
+use dojo_core::projection::Projection;
+use dojo_core::replay::{Recorder, Replay};
+use dojo_core::subscription::{
+    invalidate_token_balances, subscribe, subscribe_entities, without_id, Handle, Live, Subscribed,
+};
+use dojo_core::upgrade::{upgrade_clauses, SchemaWatcher};
+use dojo_core::utils::{watch_tx, watch_tx_result};
 use dojo_world::contracts::naming::compute_selector_from_tag;
-use futures::FutureExt;
+use futures::StreamExt;
 use lazy_static::lazy_static;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use starknet::accounts::{
     Account as StarknetAccount, ConnectedAccount, ExecutionEncoding, SingleOwnerAccount,
 };
@@ -22,24 +44,28 @@ use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::{JsonRpcClient, Provider as _};
 use starknet::signers::{LocalWallet, SigningKey, VerifyingKey};
 use starknet_crypto::{poseidon_hash_many, Felt};
-use stream_cancel::{StreamExt as _, Tripwire};
+use stream_cancel::Tripwire;
 use tokio::runtime::Runtime;
-use tokio::sync::oneshot;
 use tokio::time::sleep;
-use tokio_stream::StreamExt;
 use torii_client::Client as TClient;
 use torii_proto::Message;
 use types::{
     Achievement, AchievementProgression, Activity, AggregationEntry, BlockId, CArray, COption,
-    Call, Clause, Contract, Controller, Entity, Error, Event, KeysClause, Page,
-    PlayerAchievementEntry, Query, Result, Signature, Struct, Token, TokenBalance, TokenContract,
-    TokenTransfer, TokenTransferQuery, ToriiClient, Ty, World,
+    Call, Clause, ClientConfig, Contract, Controller, EndpointStatus, Entity, Error, Event,
+    KeysClause, Page, PlayerAchievementEntry, Query, Result, Signature, Struct, Token,
+    TokenBalance, TokenContract, TokenTransfer, TokenTransferQuery, ToriiClient, Ty, World,
 };
 
 use crate::types::{
-    Account, AchievementQuery, ActivityQuery, AggregationQuery, ContractQuery, ControllerQuery,
-    PlayerAchievementQuery, Provider, Subscription, TokenBalanceQuery, TokenContractQuery,
-    TokenQuery, Transaction, TransactionFilter, TransactionQuery,
+    c_string, Account, AchievementIterator, AchievementQuery, ActivityIterator, ActivityQuery,
+    AggregationIterator, AggregationQuery, At, CacheStats, ContractQuery, ControllerIterator,
+    ControllerQuery, DeliveryPolicy, DeliveryStats, Diagnostic, EntityChange, EntityIterator,
+    EntityLookup, EntityVersion, FetchPage, FieldChange, HistoryRange, ModelDispatcher,
+    ModelSchema, OptimisticStore, PageIterator, PlayerAchievementIterator, PlayerAchievementQuery,
+    Provider, SchemaUpgrade, Sink, Subscription, SubscriptionState, SubscriptionStream,
+    TokenBalanceIterator, TokenBalanceQuery, TokenContractQuery, TokenIterator, TokenQuery,
+    TokenTransferIterator, Transaction, TransactionFilter, TransactionIterator, TransactionQuery,
+    TyBuilder, WorldSchema,
 };
 
 lazy_static! {
@@ -47,6 +73,437 @@ lazy_static! {
         Arc::new(Runtime::new().expect("Failed to create Tokio runtime"));
 }
 
+/// Blocks on a Torii request, bounded by the client's configured request timeout
+///
+/// A request that reached Torii but went unanswered triggers a health probe of the active
+/// endpoint, so that the next one is routed to a healthy replica when the client was configured
+/// with fallback endpoints. Errors raised before sending, e.g. by validation, don't probe.
+fn block_on_request<F, T, E>(client: &ToriiClient, future: F) -> std::result::Result<T, Error>
+where
+    F: Future<Output = std::result::Result<T, E>>,
+    E: Display,
+{
+    block_on_connection(&client.inner, future)
+}
+
+/// Connects to the endpoints of a configuration, bounded by its connect timeout
+fn connect(config: dojo_core::config::ClientConfig) -> std::result::Result<Connection, Error> {
+    let torii_url = config.torii_url.clone();
+    let connect_timeout = config.connect_timeout;
+    let client_future = Connection::connect(config);
+
+    let connection = match connect_timeout {
+        Some(timeout) => match RUNTIME.block_on(tokio::time::timeout(timeout, client_future)) {
+            Ok(connection) => connection,
+            Err(_) => {
+                return Err(Error {
+                    message: c_string(format!(
+                        "Timed out connecting to {} after {}ms",
+                        torii_url,
+                        timeout.as_millis()
+                    )),
+                });
+            }
+        },
+        None => RUNTIME.block_on(client_future),
+    };
+
+    connection.map_err(error)
+}
+
+/// Error of anything displayable, e.g. `anyhow` errors which don't implement `std::error::Error`
+fn error(e: impl Display) -> Error {
+    Error { message: c_string(e.to_string()) }
+}
+
+/// Reads the value at a path of a type with `get`, see [`dojo_core::access::get`]
+unsafe fn access<T>(
+    ty: *const Ty,
+    path: *const c_char,
+    get: impl FnOnce(&dojo_types::schema::Ty, &str) -> anyhow::Result<T>,
+) -> std::result::Result<T, Error> {
+    let ty: dojo_types::schema::Ty = unsafe { (*ty).clone() }.into();
+    let path = unsafe { CStr::from_ptr(path).to_string_lossy() };
+
+    get(&ty, &path).map_err(error)
+}
+
+/// Sets the value at a path of a builder with `set`, see [`dojo_core::builder::TyBuilder`]
+unsafe fn set_at(
+    builder: *mut TyBuilder,
+    path: *const c_char,
+    set: impl FnOnce(&mut dojo_core::builder::TyBuilder, &str) -> anyhow::Result<()>,
+) -> Result<bool> {
+    let path = unsafe { CStr::from_ptr(path).to_string_lossy() };
+    match set(unsafe { &mut (*builder).0 }, &path) {
+        Ok(()) => Result::Ok(true),
+        Err(e) => Result::Err(error(e)),
+    }
+}
+
+/// Same as [`block_on_request`], for handles that keep the connection of their client
+fn block_on_connection<F, T, E>(connection: &Connection, future: F) -> std::result::Result<T, Error>
+where
+    F: Future<Output = std::result::Result<T, E>>,
+    E: Display,
+{
+    let result = match connection.config().request_timeout {
+        Some(timeout) => match RUNTIME.block_on(tokio::time::timeout(timeout, future)) {
+            Ok(result) => result.map_err(error),
+            Err(_) => Err(Error {
+                message: c_string(format!("Request timed out after {}ms", timeout.as_millis())),
+            }),
+        },
+        None => RUNTIME.block_on(future).map_err(error),
+    };
+
+    if result.is_err() {
+        RUNTIME.block_on(connection.pool().recover_from_request());
+    }
+
+    result
+}
+
+/// Creates an iterator over the pages of a query, fetched with `fetch` from the active endpoint
+///
+/// Pages are requested as `kind` requests, see [`Connection::exchange`].
+fn page_iterator<Q, T, F, Fut, E>(
+    client: &ToriiClient,
+    kind: &'static str,
+    query: Q,
+    prefetch: bool,
+    fetch: F,
+) -> PageIterator<Q, T>
+where
+    Q: Paged + Serialize + Send + 'static,
+    T: Serialize + DeserializeOwned + Send + 'static,
+    F: Fn(Arc<torii_client::Client>, Q) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = std::result::Result<torii_proto::Page<T>, E>> + Send + 'static,
+    anyhow::Error: From<E>,
+{
+    let connection = client.inner.clone();
+    let fetch_connection = connection.clone();
+    let fetch = Arc::new(fetch);
+    let fetch: FetchPage<Q, T> = Box::new(move |query| {
+        let (connection, fetch) = (fetch_connection.clone(), fetch.clone());
+        let key = CacheKey::new(kind, &query);
+        Box::pin(async move { connection.exchange(key, |inner| fetch(inner, query)).await })
+    });
+
+    PageIterator { connection, paginator: Paginator::new(query, fetch), prefetch }
+}
+
+/// Fetches the history of entities from the active endpoint, see [`history::fetch_timelines`]
+fn fetch_timelines<'a>(
+    client: &'a ToriiClient,
+    world_addresses: &'a [Felt],
+    ids: &'a [Felt],
+    models: Vec<String>,
+) -> impl Future<Output = anyhow::Result<Timelines>> + 'a {
+    let connection = &client.inner;
+    history::fetch_timelines(
+        world_addresses,
+        ids,
+        models,
+        move |query| {
+            let key = CacheKey::normalized("entities", &query);
+            connection.exchange(key, |inner| async move { inner.entities(query).await })
+        },
+        move |query| {
+            let key = CacheKey::new("sql", &query);
+            connection.exchange(key, |inner| async move { inner.sql(query).await })
+        },
+    )
+}
+
+impl<Q: Paged, T: Send + 'static> PageIterator<Q, T> {
+    fn next_page<U: From<T>>(&mut self) -> Result<COption<Page<U>>> {
+        let page = block_on_connection(&self.connection, self.paginator.next_page());
+        self.prefetch();
+        match page {
+            Ok(page) => Result::Ok(page.into()),
+            Err(e) => Result::Err(e),
+        }
+    }
+
+    fn previous_page<U: From<T>>(&mut self) -> Result<COption<Page<U>>> {
+        let page = block_on_connection(&self.connection, self.paginator.previous_page());
+        self.prefetch();
+        match page {
+            Ok(page) => Result::Ok(page.into()),
+            Err(e) => Result::Err(e),
+        }
+    }
+
+    fn next_item<U: From<T>>(&mut self) -> Result<COption<U>> {
+        let item = block_on_connection(&self.connection, self.paginator.next_item());
+        self.prefetch();
+        match item {
+            Ok(item) => Result::Ok(item.into()),
+            Err(e) => Result::Err(e),
+        }
+    }
+
+    fn prefetch(&mut self) {
+        if self.prefetch {
+            if let Some(task) = self.paginator.prefetch() {
+                RUNTIME.spawn(task);
+            }
+        }
+    }
+}
+
+/// Runs a query through the client's [`QueryCache`] when one is configured
+///
+/// Expired results are served right away when the cache revalidates them in the background.
+fn cached_request<T, F, Fut, E>(
+    client: &ToriiClient,
+    key: CacheKey,
+    scope: impl FnOnce(&T) -> Scope + Send + 'static,
+    fetch: F,
+) -> std::result::Result<T, Error>
+where
+    T: Clone + Serialize + DeserializeOwned + Send + Sync + 'static,
+    F: FnOnce(Arc<torii_client::Client>) -> Fut + Send + 'static,
+    Fut: Future<Output = std::result::Result<T, E>> + Send + 'static,
+    anyhow::Error: From<E>,
+{
+    let Some(cache) = client.inner.cache() else {
+        return block_on_request(client, client.inner.exchange(key, fetch));
+    };
+
+    match cache.lookup::<T>(&key) {
+        Lookup::Fresh(value) | Lookup::Stale(value, None) => Ok(value),
+        Lookup::Stale(value, Some(ticket)) => {
+            let (cache, connection) = (cache.clone(), client.inner.clone());
+            RUNTIME.spawn(async move {
+                if let Ok(fresh) = connection.exchange(key, fetch).await {
+                    cache.store(ticket, scope(&fresh), fresh);
+                }
+            });
+            Ok(value)
+        }
+        Lookup::Miss(ticket) => {
+            let value = block_on_request(client, client.inner.exchange(key, fetch))?;
+            cache.store(ticket, scope(&value), value.clone());
+            Ok(value)
+        }
+    }
+}
+
+/// Builds the projection of model tags and member paths, `None` when there are none
+unsafe fn projection(selectors: *const *const c_char, selectors_len: usize) -> Option<Projection> {
+    let selectors = unsafe { std::slice::from_raw_parts(selectors, selectors_len) };
+    let selectors = selectors
+        .iter()
+        .map(|selector| unsafe { CStr::from_ptr(*selector).to_string_lossy().into_owned() })
+        .collect::<Vec<_>>();
+    Some(Projection::new(&selectors)).filter(|projection| !projection.is_empty())
+}
+
+/// Queries entities or event messages through the cache, keeping what the projection selects
+fn query_entities(
+    client: &ToriiClient,
+    source: EntitySource,
+    mut query: torii_proto::Query,
+    projection: Option<Projection>,
+) -> Result<Page<Entity>> {
+    if let Some(projection) = &projection {
+        projection.apply_query(&mut query);
+    }
+    let validation = client.inner.validate_clause(query.clause.as_ref(), &query.world_addresses);
+    if let Err(e) = RUNTIME.block_on(validation) {
+        return Result::Err(error(e));
+    }
+    let kind = match source {
+        EntitySource::Entities => "entities",
+        EntitySource::EventMessages => "event_messages",
+    };
+    let key = CacheKey::normalized(kind, &query);
+    let scope_query = query.clone();
+    let entities = cached_request(
+        client,
+        key,
+        move |page| Scope::entities(source, &scope_query, page),
+        move |inner| {
+            let query = query.clone();
+            async move {
+                match source {
+                    EntitySource::Entities => inner.entities(query).await,
+                    EntitySource::EventMessages => inner.event_messages(query).await,
+                }
+            }
+        },
+    );
+
+    match entities {
+        Ok(mut entities) => {
+            if let Some(projection) = &projection {
+                projection.apply_page(&mut entities);
+            }
+            Result::Ok(entities.into())
+        }
+        Err(e) => Result::Err(e.into()),
+    }
+}
+
+/// Creates an iterator over the pages of entities or event messages, keeping what the
+/// projection selects
+fn entity_iterator(
+    client: &ToriiClient,
+    source: EntitySource,
+    mut query: torii_proto::Query,
+    prefetch: bool,
+    projection: Option<Projection>,
+) -> *mut EntityIterator {
+    if let Some(projection) = &projection {
+        projection.apply_query(&mut query);
+    }
+    let kind = match source {
+        EntitySource::Entities => "entities",
+        EntitySource::EventMessages => "event_messages",
+    };
+    let iterator = page_iterator(client, kind, query, prefetch, move |client, query| {
+        let projection = projection.clone();
+        async move {
+            let mut page = match source {
+                EntitySource::Entities => client.entities(query).await?,
+                EntitySource::EventMessages => client.event_messages(query).await?,
+            };
+            if let Some(projection) = &projection {
+                projection.apply_page(&mut page);
+            }
+            anyhow::Ok(page)
+        }
+    });
+    Box::into_raw(Box::new(EntityIterator { inner: iterator }))
+}
+
+/// Spawns a subscription with a stream of its own and waits for its first stream
+fn spawn_subscription<Q, D, R>(
+    subscribed: Subscribed<Q, D, R>,
+    message: &str,
+) -> Result<*mut Subscription>
+where
+    Q: Clone + Send + 'static,
+    D: Future<Output = ()> + Send + 'static,
+    R: Future<Output = ()> + Send + 'static,
+{
+    let Subscribed { handle, deliver, run } = subscribed;
+    RUNTIME.spawn(deliver);
+    RUNTIME.spawn(run);
+    if let Err(e) = RUNTIME.block_on(handle.established()) {
+        return Result::Err(error(format!("{message}: {e}")));
+    }
+
+    let Handle { trigger, status, delivery, live } = handle;
+    let stream = SubscriptionStream::Own(Box::new(live));
+    Result::Ok(Box::into_raw(Box::new(Subscription { trigger, status, delivery, stream })))
+}
+
+/// Completes a subscription sharing a multiplexed stream, once the stream is live
+fn shared_subscription<T: Topic, S>(
+    lease: Lease<T, S>,
+    delivery: DeliveryControl,
+    message: &str,
+) -> Result<*mut Subscription>
+where
+    Lease<T, S>: Send + 'static,
+{
+    if !RUNTIME.block_on(lease.status.established()) {
+        return Result::Err(error(message));
+    }
+
+    // The lease leaves the stream once dropped, there is nothing to trigger
+    let (trigger, _) = Tripwire::new();
+    let status = lease.status.clone();
+    let stream = SubscriptionStream::Shared { id: lease.id, lease: Box::new(lease) };
+    Result::Ok(Box::into_raw(Box::new(Subscription { trigger, status, delivery, stream })))
+}
+
+/// Subscribes to the entities or event messages of `source` matching a filter, sharing the
+/// stream of `mux` when multiplexing is enabled
+///
+/// `control` is the delivery handle `on_item` projects its updates with.
+fn entity_subscription<T>(
+    client: &ToriiClient,
+    mux: &Mux<T, Sink<torii_proto::schema::Entity>>,
+    source: EntitySource,
+    filter: EntityFilter,
+    control: DeliveryControl,
+    on_item: impl FnMut(torii_proto::schema::Entity) + Send + 'static,
+    message: &str,
+) -> Result<*mut Subscription>
+where
+    T: Topic<Query = EntityFilter, Item = torii_proto::schema::Entity>,
+    Lease<T, Sink<torii_proto::schema::Entity>>: Send + 'static,
+{
+    let connection = client.inner.clone();
+    let validation = connection.validate_clause(filter.clause.as_ref(), &filter.world_addresses);
+    if let Err(e) = RUNTIME.block_on(validation) {
+        return Result::Err(error(e));
+    }
+
+    if connection.config().multiplex && mux.shareable(&filter) {
+        let (on_item, delivery, deliver) =
+            delivery::channel_with(control, Some(Coalesce::ENTITIES), sleep, on_item);
+        RUNTIME.spawn(deliver);
+        let (lease, task) = mux.join(connection, sleep, filter, Box::new(on_item));
+        if let Some(task) = task {
+            RUNTIME.spawn(task);
+        }
+        return shared_subscription(lease, delivery, message);
+    }
+
+    spawn_subscription(
+        subscribe_entities(connection, source, filter, sleep, control, on_item),
+        message,
+    )
+}
+
+/// Changes the query of a subscription with a stream of its own, `update` sending it to the
+/// stream currently serving the subscription, see [`Live::update`]
+fn update_own<Q, F, Fut, R, E>(
+    client: &ToriiClient,
+    subscription: &Subscription,
+    query: Q,
+    update: F,
+) -> Result<bool>
+where
+    Q: Clone + 'static,
+    F: FnOnce(u64, Q) -> Fut,
+    Fut: Future<Output = std::result::Result<R, E>>,
+    E: Display,
+{
+    let live = match &subscription.stream {
+        SubscriptionStream::Own(live) => live.downcast_ref::<Live<Q>>(),
+        SubscriptionStream::Shared { .. } => None,
+    };
+    let Some(live) = live else {
+        return Result::Err(error("Subscription cannot be updated with this query"));
+    };
+
+    match block_on_request(client, live.update(query, update)) {
+        Ok(_) => Result::Ok(true),
+        Err(e) => Result::Err(e),
+    }
+}
+
+/// Changes the query of a subscription sharing a multiplexed stream
+fn update_shared<T: Topic, S: FnMut(T::Item)>(
+    mux: &Mux<T, S>,
+    id: u64,
+    query: T::Query,
+) -> Result<bool> {
+    if !mux.shareable(&query) {
+        return Result::Err(Error {
+            message: c_string("Query cannot be served by a shared stream"),
+        });
+    }
+
+    Result::Ok(mux.update(id, query))
+}
+
 #[allow(clippy::missing_safety_doc)]
 mod ffi {
     use super::*;
@@ -61,14 +518,102 @@ mod ffi {
     #[no_mangle]
     pub unsafe extern "C" fn client_new(torii_url: *const c_char) -> Result<*mut ToriiClient> {
         let torii_url = unsafe { CStr::from_ptr(torii_url).to_string_lossy().into_owned() };
-        let client_future = TClient::new(torii_url);
+        let client_future = TClient::new(torii_url.clone());
 
         let client = match RUNTIME.block_on(client_future) {
             Ok(client) => client,
             Err(e) => return Result::Err(e.into()),
         };
 
-        Result::Ok(Box::into_raw(Box::new(ToriiClient { inner: client, logger: None })))
+        Result::Ok(Box::into_raw(Box::new(ToriiClient::new(Arc::new(Connection::from_client(
+            client,
+            dojo_core::config::ClientConfig::new(torii_url),
+        ))))))
+    }
+
+    /// Creates a new Torii client instance from a full configuration
+    ///
+    /// When `fallback_urls` are provided, queries and subscriptions fail over to the healthiest
+    /// replica whenever the active endpoint stops responding or falls behind.
+    ///
+    /// # Parameters
+    /// * `config` - Client configuration (fallback endpoints, timeouts, message size and retry
+    ///   policy). Optional fields left as `None` use the library defaults.
+    ///
+    /// # Returns
+    /// Result containing pointer to new ToriiClient instance or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_new_with_config(
+        config: ClientConfig,
+    ) -> Result<*mut ToriiClient> {
+        match connect(config.into()) {
+            Ok(connection) => {
+                Result::Ok(Box::into_raw(Box::new(ToriiClient::new(Arc::new(connection)))))
+            }
+            Err(e) => Result::Err(e),
+        }
+    }
+
+    /// Creates a new Torii client that records its session to a file
+    ///
+    /// Every query response and subscription message is written to the file along with the
+    /// time it came in, so that the session can be played back with `client_new_replay`.
+    ///
+    /// # Parameters
+    /// * `config` - Client configuration, see `client_new_with_config`
+    /// * `path` - Path of the recording, replaced if it exists
+    ///
+    /// # Returns
+    /// Result containing pointer to new ToriiClient instance or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_new_recording(
+        config: ClientConfig,
+        path: *const c_char,
+    ) -> Result<*mut ToriiClient> {
+        let path = unsafe { CStr::from_ptr(path).to_string_lossy().into_owned() };
+        let recorder = match Recorder::create(path) {
+            Ok(recorder) => recorder,
+            Err(e) => return Result::Err(error(e)),
+        };
+
+        match connect(config.into()) {
+            Ok(connection) => {
+                let connection = Arc::new(connection.with_recorder(recorder));
+                Result::Ok(Box::into_raw(Box::new(ToriiClient::new(connection))))
+            }
+            Err(e) => Result::Err(e),
+        }
+    }
+
+    /// Creates a Torii client playing back a session recorded by `client_new_recording`
+    ///
+    /// Queries get the recorded responses and subscriptions the recorded messages, without a
+    /// network. The replay is deterministic as long as the same queries are made and the
+    /// subscriptions are opened in the same order as in the recorded session.
+    ///
+    /// # Parameters
+    /// * `config` - Client configuration, which should multiplex subscriptions and cache
+    ///   queries like the recorded client did. Its URLs aren't used.
+    /// * `path` - Path of the recording
+    /// * `speed` - Playback speed, 1 being the original speed and infinity playing messages
+    ///   without delays
+    ///
+    /// # Returns
+    /// Result containing pointer to new ToriiClient instance or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_new_replay(
+        config: ClientConfig,
+        path: *const c_char,
+        speed: f64,
+    ) -> Result<*mut ToriiClient> {
+        let path = unsafe { CStr::from_ptr(path).to_string_lossy().into_owned() };
+        match Replay::open(path, speed) {
+            Ok(replay) => {
+                let connection = Arc::new(Connection::replay(config.into(), replay));
+                Result::Ok(Box::into_raw(Box::new(ToriiClient::new(connection))))
+            }
+            Err(e) => Result::Err(error(e)),
+        }
     }
 
     /// Sets a logger callback function for the client
@@ -86,6 +631,85 @@ mod ffi {
         }
     }
 
+    /// Probes every endpoint of the client and returns their health
+    ///
+    /// Switches to a healthier replica if the active endpoint is down or lagging behind.
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    ///
+    /// # Returns
+    /// Result containing array of EndpointStatus or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_endpoints(
+        client: *mut ToriiClient,
+    ) -> Result<CArray<EndpointStatus>> {
+        let connection = unsafe { &(*client).inner };
+        RUNTIME.block_on(connection.pool().recover());
+
+        let statuses = connection
+            .pool()
+            .statuses()
+            .into_iter()
+            .map(|s| s.into())
+            .collect::<Vec<EndpointStatus>>();
+        Result::Ok(statuses.into())
+    }
+
+    /// Returns the statistics of the client's query cache
+    ///
+    /// All counters are zero when the client was created without a cache.
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    ///
+    /// # Returns
+    /// CacheStats of the query cache
+    #[no_mangle]
+    pub unsafe extern "C" fn client_cache_stats(client: *mut ToriiClient) -> CacheStats {
+        let connection = unsafe { &(*client).inner };
+        connection.cache().map(|cache| cache.stats().into()).unwrap_or_default()
+    }
+
+    /// Drops every result of the client's query cache
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    #[no_mangle]
+    pub unsafe extern "C" fn client_cache_clear(client: *mut ToriiClient) {
+        let connection = unsafe { &(*client).inner };
+        if let Some(cache) = connection.cache() {
+            cache.clear();
+        }
+    }
+
+    /// Checks a query against the model schemas of the client's worlds
+    ///
+    /// Reports unknown models, unresolved member paths, operators that don't apply to a member,
+    /// values that can't match it and key counts that differ from the model's.
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `query` - Query to check
+    ///
+    /// # Returns
+    /// Result containing the diagnostics, empty when the query is valid, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_validate_query(
+        client: *mut ToriiClient,
+        query: Query,
+    ) -> Result<CArray<Diagnostic>> {
+        let query: torii_proto::Query = query.into();
+        let connection = unsafe { &(*client).inner };
+        match RUNTIME.block_on(connection.validator()) {
+            Ok(validator) => {
+                let diagnostics = validator.validate_query(&query);
+                Result::Ok(diagnostics.into_iter().map(Into::into).collect::<Vec<_>>().into())
+            }
+            Err(e) => Result::Err(error(e)),
+        }
+    }
+
     /// Publishes a message to the network
     ///
     /// # Parameters
@@ -101,10 +725,14 @@ mod ffi {
         client: *mut ToriiClient,
         message: types::Message,
     ) -> Result<*const c_char> {
-        let client_future = unsafe { (*client).inner.publish_message(message.into()) };
-
-        match RUNTIME.block_on(client_future) {
-            Ok(data) => Result::Ok(CString::new(data).unwrap().into_raw() as *const c_char),
+        let message: Message = message.into();
+        let key = CacheKey::new("publish_message", &message);
+        let client_future = (*client)
+            .inner
+            .exchange(key, |inner| async move { inner.publish_message(message).await });
+
+        match block_on_request(&*client, client_future) {
+            Ok(data) => Result::Ok(c_string(data) as *const c_char),
             Err(e) => Result::Err(e.into()),
         }
     }
@@ -126,14 +754,15 @@ mod ffi {
     ) -> Result<CArray<*const c_char>> {
         let messages = unsafe { std::slice::from_raw_parts(messages, messages_len) };
         let messages: Vec<Message> = messages.iter().cloned().map(|msg| msg.into()).collect();
-        let client_future = unsafe { (*client).inner.publish_message_batch(messages) };
+        let key = CacheKey::new("publish_message_batch", &messages);
+        let client_future = (*client)
+            .inner
+            .exchange(key, |inner| async move { inner.publish_message_batch(messages).await });
 
-        match RUNTIME.block_on(client_future) {
+        match block_on_request(&*client, client_future) {
             Ok(message_ids) => {
-                let ids: Vec<*const c_char> = message_ids
-                    .into_iter()
-                    .map(|id| CString::new(id).unwrap().into_raw() as *const c_char)
-                    .collect();
+                let ids: Vec<*const c_char> =
+                    message_ids.into_iter().map(|id| c_string(id) as *const c_char).collect();
                 Result::Ok(ids.into())
             }
             Err(e) => Result::Err(e.into()),
@@ -154,10 +783,19 @@ mod ffi {
         client: *mut ToriiClient,
         query: ControllerQuery,
     ) -> Result<Page<Controller>> {
-        let query = query.into();
-        let controllers_future = unsafe { (*client).inner.controllers(query) };
+        let query: torii_proto::ControllerQuery = query.into();
+        let key = CacheKey::new("controllers", &query);
+        let controllers = cached_request(
+            &*client,
+            key,
+            |_| Scope::Unscoped,
+            move |inner| {
+                let query = query.clone();
+                async move { inner.controllers(query).await }
+            },
+        );
 
-        match RUNTIME.block_on(controllers_future) {
+        match controllers {
             Ok(controllers) => Result::Ok(controllers.into()),
             Err(e) => Result::Err(e.into()),
         }
@@ -176,13 +814,29 @@ mod ffi {
         client: *mut ToriiClient,
         query: Query,
     ) -> Result<Page<Entity>> {
-        let query = query.clone().into();
-        let entities_future = unsafe { (*client).inner.entities(query) };
+        query_entities(&*client, EntitySource::Entities, query.into(), None)
+    }
 
-        match RUNTIME.block_on(entities_future) {
-            Ok(entities) => Result::Ok(entities.into()),
-            Err(e) => Result::Err(e.into()),
-        }
+    /// Queries entities matching given criteria, only keeping the selected models and members
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `query` - Query parameters
+    /// * `selectors` - Array of model tags and member paths to keep, e.g. `ns-Stats.health`,
+    ///   every model and member being kept when empty
+    /// * `selectors_len` - Length of selectors array
+    ///
+    /// # Returns
+    /// Result containing array of matching entities or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_entities_projected(
+        client: *mut ToriiClient,
+        query: Query,
+        selectors: *const *const c_char,
+        selectors_len: usize,
+    ) -> Result<Page<Entity>> {
+        let projection = projection(selectors, selectors_len);
+        query_entities(&*client, EntitySource::Entities, query.into(), projection)
     }
 
     /// Retrieves event messages matching the given query
@@ -199,1824 +853,3473 @@ mod ffi {
         client: *mut ToriiClient,
         query: Query,
     ) -> Result<Page<Entity>> {
-        let query = query.clone().into();
-        let event_messages_future = unsafe { (*client).inner.event_messages(query) };
-
-        match RUNTIME.block_on(event_messages_future) {
-            Ok(event_messages) => Result::Ok(event_messages.into()),
-            Err(e) => Result::Err(e.into()),
-        }
+        query_entities(&*client, EntitySource::EventMessages, query.into(), None)
     }
 
-    /// Gets the world metadata for the client
+    /// Retrieves event messages matching the given query, only keeping the selected models
+    /// and members
     ///
     /// # Parameters
     /// * `client` - Pointer to ToriiClient instance
+    /// * `query` - Query parameters
+    /// * `selectors` - Array of model tags and member paths to keep, e.g. `ns-Stats.health`,
+    ///   every model and member being kept when empty
+    /// * `selectors_len` - Length of selectors array
     ///
     /// # Returns
-    /// World structure containing world information
+    /// Result containing array of matching event message entities or error
     #[no_mangle]
-    pub unsafe extern "C" fn client_worlds(
+    pub unsafe extern "C" fn client_event_messages_projected(
         client: *mut ToriiClient,
-        world_addresses: *const types::FieldElement,
-        world_addresses_len: usize,
-    ) -> Result<CArray<World>> {
-        let world_addresses =
-            unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
-        let world_addresses = world_addresses.iter().map(|addr| addr.clone().into()).collect();
-        let metadata_future = unsafe { (*client).inner.worlds(world_addresses) };
-        match RUNTIME.block_on(metadata_future) {
-            Ok(metadata) => Result::Ok(metadata.into()),
-            Err(e) => Result::Err(e.into()),
-        }
+        query: Query,
+        selectors: *const *const c_char,
+        selectors_len: usize,
+    ) -> Result<Page<Entity>> {
+        let projection = projection(selectors, selectors_len);
+        query_entities(&*client, EntitySource::EventMessages, query.into(), projection)
     }
 
-    /// Retrieves transactions matching the given query
+    /// Looks up entities by id, i.e. hashed keys
+    ///
+    /// Ids are requested in chunks running concurrently, sized so that any number of them fits
+    /// in the configured message size. An id known to several worlds comes with the entity of
+    /// the first one listed.
     ///
     /// # Parameters
     /// * `client` - Pointer to ToriiClient instance
-    /// * `query` - Query parameters
+    /// * `world_addresses` - Array of world addresses to look entities up in, all when empty
+    /// * `world_addresses_len` - Length of world addresses array
+    /// * `ids` - Array of entity ids
+    /// * `ids_len` - Length of ids array
     ///
     /// # Returns
-    /// Result containing array of matching transactions or error
+    /// Result containing an EntityLookup per id, in order, or error
     #[no_mangle]
-    pub unsafe extern "C" fn client_transactions(
+    pub unsafe extern "C" fn client_entities_by_ids(
         client: *mut ToriiClient,
-        query: TransactionQuery,
-    ) -> Result<Page<Transaction>> {
-        let query = query.into();
-        let transactions_future = unsafe { (*client).inner.transactions(query) };
+        world_addresses: *const types::FieldElement,
+        world_addresses_len: usize,
+        ids: *const types::FieldElement,
+        ids_len: usize,
+    ) -> Result<CArray<EntityLookup>> {
+        let world_addresses =
+            unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
+        let world_addresses =
+            world_addresses.iter().map(|addr| addr.clone().into()).collect::<Vec<Felt>>();
+        let ids = unsafe { std::slice::from_raw_parts(ids, ids_len) };
+        let ids = ids.iter().map(|id| id.clone().into()).collect::<Vec<Felt>>();
+
+        let connection = &(*client).inner;
+        let max_message_size = connection.config().max_message_size;
+        let lookup = batch::entities_by_ids(
+            &world_addresses,
+            &ids,
+            Vec::new(),
+            max_message_size,
+            move |query| {
+                let key = CacheKey::normalized("entities", &query);
+                connection.exchange(key, |inner| async move { inner.entities(query).await })
+            },
+        );
 
-        match RUNTIME.block_on(transactions_future) {
-            Ok(transactions) => Result::Ok(transactions.into()),
-            Err(e) => Result::Err(e.into()),
+        match block_on_request(&*client, lookup) {
+            Ok(lookup) => Result::Ok(lookup.into()),
+            Err(e) => Result::Err(e),
         }
     }
 
-    /// Subscribes to transaction updates
+    /// Looks up a model of entities by keys
+    ///
+    /// Entities without the model are reported as missing. Keys are requested in chunks
+    /// running concurrently, so that any number of them fits in the message size.
     ///
     /// # Parameters
     /// * `client` - Pointer to ToriiClient instance
-    /// * `filter` - Filter parameters
-    /// * `callback` - Function called when updates occur
+    /// * `world_addresses` - Array of world addresses to look entities up in, all when empty
+    /// * `world_addresses_len` - Length of world addresses array
+    /// * `model` - Tag of the model, e.g. `ns-Position`
+    /// * `keys` - Array of the keys of each entity
+    /// * `keys_len` - Length of keys array
     ///
     /// # Returns
-    /// Result containing pointer to Subscription or error
+    /// Result containing an EntityLookup per keys, in order, or error
     #[no_mangle]
-    pub unsafe extern "C" fn client_on_transaction(
+    pub unsafe extern "C" fn client_entities_by_keys(
         client: *mut ToriiClient,
-        filter: COption<TransactionFilter>,
-        callback: unsafe extern "C" fn(Transaction),
-    ) -> Result<*mut Subscription> {
-        let client = Arc::new(unsafe { &*client });
-        let (trigger, tripwire) = Tripwire::new();
-
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-
-        let filter: Option<torii_proto::TransactionFilter> = filter.map(|f| f.into()).into();
-
-        // Spawn a new thread to handle the stream and reconnections
-        let client_clone = client.clone();
-        RUNTIME.spawn(async move {
-            let mut backoff = Duration::from_secs(1);
-            let max_backoff = Duration::from_secs(60);
-
-            loop {
-                let rcv = client_clone.inner.on_transaction(filter.clone()).await;
-                if let Ok(rcv) = rcv {
-                    backoff = Duration::from_secs(1); // Reset backoff on successful connection
-
-                    let mut rcv = rcv.take_until_if(tripwire.clone());
-
-                    while let Some(Ok(transaction)) = rcv.next().await {
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(0).expect("Failed to send subscription ID");
-                        } else {
-                            callback(transaction.into());
-                        }
-                    }
-                }
-
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
-                }
-                sleep(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
-            }
-        });
-
-        let subscription_id = match RUNTIME.block_on(sub_id_rx) {
-            Ok(id) => id,
-            Err(_) => {
-                return Result::Err(Error {
-                    message: CString::new("Failed to establish entity subscription")
-                        .unwrap()
-                        .into_raw(),
-                });
-            }
-        };
+        world_addresses: *const types::FieldElement,
+        world_addresses_len: usize,
+        model: *const c_char,
+        keys: *const CArray<types::FieldElement>,
+        keys_len: usize,
+    ) -> Result<CArray<EntityLookup>> {
+        let world_addresses =
+            unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
+        let world_addresses =
+            world_addresses.iter().map(|addr| addr.clone().into()).collect::<Vec<Felt>>();
+        let model = unsafe { CStr::from_ptr(model).to_string_lossy().into_owned() };
+        let keys = unsafe { std::slice::from_raw_parts(keys, keys_len) };
+        let keys = keys.iter().map(|keys| keys.clone().into()).collect::<Vec<Vec<Felt>>>();
+
+        let connection = &(*client).inner;
+        let max_message_size = connection.config().max_message_size;
+        let lookup = batch::entities_by_keys(
+            &world_addresses,
+            &model,
+            &keys,
+            max_message_size,
+            move |query| {
+                let key = CacheKey::normalized("entities", &query);
+                connection.exchange(key, |inner| async move { inner.entities(query).await })
+            },
+        );
 
-        let subscription = Subscription { id: subscription_id, trigger };
-        Result::Ok(Box::into_raw(Box::new(subscription)))
+        match block_on_request(&*client, lookup) {
+            Ok(lookup) => Result::Ok(lookup.into()),
+            Err(e) => Result::Err(e),
+        }
     }
 
-    /// Subscribes to entity state updates
+    /// Gets every version of a model of an entity, in the order they were set
     ///
     /// # Parameters
     /// * `client` - Pointer to ToriiClient instance
-    /// * `clauses` - Array of entity key clauses to filter updates
-    /// * `clauses_len` - Length of clauses array
-    /// * `callback` - Function called when updates occur
+    /// * `world_addresses` - Array of world addresses, every world when empty
+    /// * `world_addresses_len` - Length of world addresses array
+    /// * `hashed_keys` - Id of the entity
+    /// * `model` - Tag of the model
+    /// * `range` - Inclusive range of blocks or timestamps to keep
     ///
     /// # Returns
-    /// Result containing pointer to Subscription or error
+    /// Result containing the versions, with their block and transaction, or error
     #[no_mangle]
-    pub unsafe extern "C" fn client_on_entity_state_update(
+    pub unsafe extern "C" fn client_entity_history(
         client: *mut ToriiClient,
-        clause: COption<Clause>,
         world_addresses: *const types::FieldElement,
         world_addresses_len: usize,
-        callback: unsafe extern "C" fn(Entity),
-    ) -> Result<*mut Subscription> {
-        let client = Arc::new(unsafe { &*client });
-        let (trigger, tripwire) = Tripwire::new();
-
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-
-        let clause: Option<torii_proto::Clause> = clause.map(|c| c.into()).into();
+        hashed_keys: types::FieldElement,
+        model: *const c_char,
+        range: HistoryRange,
+    ) -> Result<CArray<EntityVersion>> {
         let world_addresses =
             unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
         let world_addresses =
             world_addresses.iter().map(|addr| addr.clone().into()).collect::<Vec<Felt>>();
-        // Spawn a new thread to handle the stream and reconnections
-        let client_clone = client.clone();
-        RUNTIME.spawn(async move {
-            let mut backoff = Duration::from_secs(1);
-            let max_backoff = Duration::from_secs(60);
-
-            loop {
-                let rcv = client_clone
-                    .inner
-                    .on_entity_updated(clause.clone(), world_addresses.clone())
-                    .await;
-                if let Ok(rcv) = rcv {
-                    backoff = Duration::from_secs(1); // Reset backoff on successful connection
-
-                    let mut rcv = rcv.take_until_if(tripwire.clone());
-
-                    while let Some(Ok((id, entity))) = rcv.next().await {
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(id).expect("Failed to send subscription ID");
-                        } else {
-                            callback(entity.into());
-                        }
-                    }
-                }
-
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
-                }
-                sleep(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
-            }
-        });
-
-        let subscription_id = match RUNTIME.block_on(sub_id_rx) {
-            Ok(id) => id,
-            Err(_) => {
-                return Result::Err(Error {
-                    message: CString::new("Failed to establish entity subscription")
-                        .unwrap()
-                        .into_raw(),
-                });
+        let hashed_keys: Felt = hashed_keys.into();
+        let model = unsafe { CStr::from_ptr(model).to_string_lossy().into_owned() };
+
+        let timelines =
+            fetch_timelines(&*client, &world_addresses, &[hashed_keys], vec![model.clone()]);
+        match block_on_request(&*client, timelines) {
+            Ok(mut timelines) => {
+                let versions = timelines.remove(&(hashed_keys, model)).unwrap_or_default();
+                Result::Ok(history::within(versions, range.into()).into())
             }
-        };
-
-        let subscription = Subscription { id: subscription_id, trigger };
-        Result::Ok(Box::into_raw(Box::new(subscription)))
+            Err(e) => Result::Err(e),
+        }
     }
 
-    /// Updates an existing entity subscription with new clauses
+    /// Rebuilds entities as they were at a block or time, from their history
     ///
     /// # Parameters
     /// * `client` - Pointer to ToriiClient instance
-    /// * `subscription` - Pointer to existing Subscription
-    /// * `clauses` - New array of entity key clauses
-    /// * `clauses_len` - Length of new clauses array
+    /// * `world_address` - Address of the world of the entities
+    /// * `ids` - Array of entity ids
+    /// * `ids_len` - Length of ids array
+    /// * `at` - Block or timestamp to rebuild the entities at
     ///
     /// # Returns
-    /// Result containing success boolean or error
+    /// Result containing the entities that existed at that point, each with the models set
+    /// by then, or error
     #[no_mangle]
-    pub unsafe extern "C" fn client_update_entity_subscription(
+    pub unsafe extern "C" fn client_entities_at(
         client: *mut ToriiClient,
-        subscription: *mut Subscription,
-        clause: COption<Clause>,
-        world_addresses: *const types::FieldElement,
-        world_addresses_len: usize,
-    ) -> Result<bool> {
-        let clause: Option<torii_proto::Clause> = clause.map(|c| c.into()).into();
-        let world_addresses =
-            unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
-        let world_addresses = world_addresses.iter().map(|addr| addr.clone().into()).collect();
-        match RUNTIME.block_on((*client).inner.update_entity_subscription(
-            (*subscription).id,
-            clause,
-            world_addresses,
-        )) {
-            Ok(_) => Result::Ok(true),
-            Err(e) => Result::Err(e.into()),
+        world_address: types::FieldElement,
+        ids: *const types::FieldElement,
+        ids_len: usize,
+        at: At,
+    ) -> Result<CArray<Entity>> {
+        let world_address: Felt = world_address.into();
+        let ids = unsafe { std::slice::from_raw_parts(ids, ids_len) };
+        let ids = ids.iter().map(|id| id.clone().into()).collect::<Vec<Felt>>();
+
+        let timelines = fetch_timelines(&*client, &[world_address], &ids, vec![]);
+        match block_on_request(&*client, timelines) {
+            Ok(timelines) => {
+                let entities = history::state_at(world_address, timelines, at.into());
+                Result::Ok(entities.into())
+            }
+            Err(e) => Result::Err(e),
         }
     }
 
-    /// Retrieves aggregations (leaderboards, stats, rankings) matching query parameter
+    /// Creates an iterator over every page of entities matching a query
     ///
     /// # Parameters
     /// * `client` - Pointer to ToriiClient instance
-    /// * `query` - AggregationQuery containing aggregator_ids, entity_ids, and pagination
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
     ///
     /// # Returns
-    /// Result containing Page of AggregationEntry or error
+    /// Pointer to EntityIterator, freed with `entity_iterator_free`
     #[no_mangle]
-    pub unsafe extern "C" fn client_aggregations(
+    pub unsafe extern "C" fn client_entities_iter(
         client: *mut ToriiClient,
-        query: AggregationQuery,
-    ) -> Result<Page<AggregationEntry>> {
-        let query = query.into();
-        let aggregations_future = unsafe { (*client).inner.aggregations(query) };
-
-        match RUNTIME.block_on(aggregations_future) {
-            Ok(aggregations) => Result::Ok(aggregations.into()),
-            Err(e) => Result::Err(e.into()),
-        }
+        query: Query,
+        prefetch: bool,
+    ) -> *mut EntityIterator {
+        entity_iterator(&*client, EntitySource::Entities, query.into(), prefetch, None)
     }
 
-    /// Subscribes to aggregation updates (leaderboards, stats, rankings)
+    /// Creates an iterator over every page of entities matching a query, only keeping the
+    /// selected models and members
     ///
     /// # Parameters
     /// * `client` - Pointer to ToriiClient instance
-    /// * `aggregator_ids` - Array of aggregator IDs to subscribe to
-    /// * `aggregator_ids_len` - Length of aggregator_ids array
-    /// * `entity_ids` - Array of entity IDs to subscribe to
-    /// * `entity_ids_len` - Length of entity_ids array
-    /// * `callback` - Function called when updates occur
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
+    /// * `selectors` - Array of model tags and member paths to keep, e.g. `ns-Stats.health`,
+    ///   every model and member being kept when empty
+    /// * `selectors_len` - Length of selectors array
     ///
     /// # Returns
-    /// Result containing pointer to Subscription or error
+    /// Pointer to EntityIterator, freed with `entity_iterator_free`
     #[no_mangle]
-    pub unsafe extern "C" fn client_on_aggregation_update(
+    pub unsafe extern "C" fn client_entities_iter_projected(
         client: *mut ToriiClient,
-        aggregator_ids: *const *const c_char,
-        aggregator_ids_len: usize,
-        entity_ids: *const *const c_char,
-        entity_ids_len: usize,
-        callback: unsafe extern "C" fn(AggregationEntry),
-    ) -> Result<*mut Subscription> {
-        let client = Arc::new(unsafe { &*client });
-
-        // Convert aggregator_ids array to Vec<String> if not empty
-        let aggregator_ids = if aggregator_ids.is_null() || aggregator_ids_len == 0 {
-            Vec::new()
-        } else {
-            let ids = unsafe { std::slice::from_raw_parts(aggregator_ids, aggregator_ids_len) };
-            ids.iter()
-                .map(|id| unsafe { CStr::from_ptr(*id).to_string_lossy().to_string() })
-                .collect::<Vec<String>>()
-        };
-
-        let entity_ids = if entity_ids.is_null() || entity_ids_len == 0 {
-            Vec::new()
-        } else {
-            let ids = unsafe { std::slice::from_raw_parts(entity_ids, entity_ids_len) };
-            ids.iter()
-                .map(|id| unsafe { CStr::from_ptr(*id).to_string_lossy().to_string() })
-                .collect::<Vec<String>>()
-        };
-
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-        let (trigger, tripwire) = Tripwire::new();
+        query: Query,
+        prefetch: bool,
+        selectors: *const *const c_char,
+        selectors_len: usize,
+    ) -> *mut EntityIterator {
+        let projection = projection(selectors, selectors_len);
+        entity_iterator(&*client, EntitySource::Entities, query.into(), prefetch, projection)
+    }
 
-        // Spawn a new thread to handle the stream and reconnections
-        let client_clone = client.clone();
-        RUNTIME.spawn(async move {
-            let mut backoff = Duration::from_secs(1);
-            let max_backoff = Duration::from_secs(60);
-
-            loop {
-                let rcv = client_clone
-                    .inner
-                    .on_aggregation_updated(aggregator_ids.clone(), entity_ids.clone())
-                    .await;
-
-                if let Ok(rcv) = rcv {
-                    backoff = Duration::from_secs(1); // Reset backoff on successful connection
-
-                    let mut rcv = rcv.take_until_if(tripwire.clone());
-
-                    while let Some(Ok((id, aggregation_entry))) = rcv.next().await {
-                        // Our first message will be the subscription ID
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(id).expect("Failed to send subscription ID");
-                        } else {
-                            let aggregation_entry: AggregationEntry = aggregation_entry.into();
-                            callback(aggregation_entry);
-                        }
-                    }
-                }
+    /// Creates an iterator over every page of event messages matching a query
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
+    ///
+    /// # Returns
+    /// Pointer to EntityIterator, freed with `entity_iterator_free`
+    #[no_mangle]
+    pub unsafe extern "C" fn client_event_messages_iter(
+        client: *mut ToriiClient,
+        query: Query,
+        prefetch: bool,
+    ) -> *mut EntityIterator {
+        entity_iterator(&*client, EntitySource::EventMessages, query.into(), prefetch, None)
+    }
 
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
-                }
-                sleep(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
-            }
-        });
+    /// Creates an iterator over every page of event messages matching a query, only keeping the
+    /// selected models and members
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
+    /// * `selectors` - Array of model tags and member paths to keep, e.g. `ns-Stats.health`,
+    ///   every model and member being kept when empty
+    /// * `selectors_len` - Length of selectors array
+    ///
+    /// # Returns
+    /// Pointer to EntityIterator, freed with `entity_iterator_free`
+    #[no_mangle]
+    pub unsafe extern "C" fn client_event_messages_iter_projected(
+        client: *mut ToriiClient,
+        query: Query,
+        prefetch: bool,
+        selectors: *const *const c_char,
+        selectors_len: usize,
+    ) -> *mut EntityIterator {
+        let projection = projection(selectors, selectors_len);
+        entity_iterator(&*client, EntitySource::EventMessages, query.into(), prefetch, projection)
+    }
 
-        let subscription_id = match RUNTIME.block_on(sub_id_rx) {
-            Ok(id) => id,
-            Err(_) => {
-                return Result::Err(Error {
-                    message: CString::new("Failed to establish aggregation subscription")
-                        .unwrap()
-                        .into_raw(),
-                });
-            }
-        };
+    /// Creates an iterator over every page of controllers matching a query
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
+    ///
+    /// # Returns
+    /// Pointer to ControllerIterator, freed with `controller_iterator_free`
+    #[no_mangle]
+    pub unsafe extern "C" fn client_controllers_iter(
+        client: *mut ToriiClient,
+        query: ControllerQuery,
+        prefetch: bool,
+    ) -> *mut ControllerIterator {
+        let query: torii_proto::ControllerQuery = query.into();
+        let iterator =
+            page_iterator(&*client, "controllers", query, prefetch, |client, query| async move {
+                client.controllers(query).await
+            });
+        Box::into_raw(Box::new(ControllerIterator { inner: iterator }))
+    }
 
-        let subscription = Subscription { id: subscription_id, trigger };
+    /// Creates an iterator over every page of tokens matching a query
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
+    ///
+    /// # Returns
+    /// Pointer to TokenIterator, freed with `token_iterator_free`
+    #[no_mangle]
+    pub unsafe extern "C" fn client_tokens_iter(
+        client: *mut ToriiClient,
+        query: TokenQuery,
+        prefetch: bool,
+    ) -> *mut TokenIterator {
+        let query: torii_proto::TokenQuery = query.into();
+        let iterator =
+            page_iterator(&*client, "tokens", query, prefetch, |client, query| async move {
+                client.tokens(query).await
+            });
+        Box::into_raw(Box::new(TokenIterator { inner: iterator }))
+    }
 
-        Result::Ok(Box::into_raw(Box::new(subscription)))
+    /// Creates an iterator over every page of token balances matching a query
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
+    ///
+    /// # Returns
+    /// Pointer to TokenBalanceIterator, freed with `token_balance_iterator_free`
+    #[no_mangle]
+    pub unsafe extern "C" fn client_token_balances_iter(
+        client: *mut ToriiClient,
+        query: TokenBalanceQuery,
+        prefetch: bool,
+    ) -> *mut TokenBalanceIterator {
+        let query: torii_proto::TokenBalanceQuery = query.into();
+        let iterator = page_iterator(
+            &*client,
+            "token_balances",
+            query,
+            prefetch,
+            |client, query| async move { client.token_balances(query).await },
+        );
+        Box::into_raw(Box::new(TokenBalanceIterator { inner: iterator }))
     }
 
-    /// Updates an existing aggregation subscription with new parameters
+    /// Creates an iterator over every page of token transfers matching a query
     ///
     /// # Parameters
     /// * `client` - Pointer to ToriiClient instance
-    /// * `subscription` - Pointer to existing Subscription
-    /// * `aggregator_ids` - Array of aggregator IDs to subscribe to
-    /// * `aggregator_ids_len` - Length of aggregator_ids array
-    /// * `entity_ids` - Array of entity IDs to subscribe to
-    /// * `entity_ids_len` - Length of entity_ids array
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
     ///
     /// # Returns
-    /// Result containing success boolean or error
+    /// Pointer to TokenTransferIterator, freed with `token_transfer_iterator_free`
     #[no_mangle]
-    pub unsafe extern "C" fn client_update_aggregation_subscription(
+    pub unsafe extern "C" fn client_token_transfers_iter(
         client: *mut ToriiClient,
-        subscription: *mut Subscription,
-        aggregator_ids: *const *const c_char,
-        aggregator_ids_len: usize,
-        entity_ids: *const *const c_char,
-        entity_ids_len: usize,
-    ) -> Result<bool> {
-        // Convert aggregator_ids array to Vec<String> if not empty
-        let aggregator_ids = if aggregator_ids.is_null() || aggregator_ids_len == 0 {
-            Vec::new()
-        } else {
-            let ids = unsafe { std::slice::from_raw_parts(aggregator_ids, aggregator_ids_len) };
-            ids.iter()
-                .map(|id| unsafe { CStr::from_ptr(*id).to_string_lossy().to_string() })
-                .collect::<Vec<String>>()
-        };
+        query: TokenTransferQuery,
+        prefetch: bool,
+    ) -> *mut TokenTransferIterator {
+        let query: torii_proto::TokenTransferQuery = query.into();
+        let iterator = page_iterator(
+            &*client,
+            "token_transfers",
+            query,
+            prefetch,
+            |client, query| async move { client.token_transfers(query).await },
+        );
+        Box::into_raw(Box::new(TokenTransferIterator { inner: iterator }))
+    }
 
-        let entity_ids = if entity_ids.is_null() || entity_ids_len == 0 {
-            Vec::new()
-        } else {
-            let ids = unsafe { std::slice::from_raw_parts(entity_ids, entity_ids_len) };
-            ids.iter()
-                .map(|id| unsafe { CStr::from_ptr(*id).to_string_lossy().to_string() })
-                .collect::<Vec<String>>()
-        };
+    /// Creates an iterator over every page of transactions matching a query
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
+    ///
+    /// # Returns
+    /// Pointer to TransactionIterator, freed with `transaction_iterator_free`
+    #[no_mangle]
+    pub unsafe extern "C" fn client_transactions_iter(
+        client: *mut ToriiClient,
+        query: TransactionQuery,
+        prefetch: bool,
+    ) -> *mut TransactionIterator {
+        let query: torii_proto::TransactionQuery = query.into();
+        let iterator =
+            page_iterator(&*client, "transactions", query, prefetch, |client, query| async move {
+                client.transactions(query).await
+            });
+        Box::into_raw(Box::new(TransactionIterator { inner: iterator }))
+    }
 
-        match RUNTIME.block_on((*client).inner.update_aggregation_subscription(
-            (*subscription).id,
-            aggregator_ids,
-            entity_ids,
-        )) {
-            Ok(_) => Result::Ok(true),
-            Err(e) => Result::Err(e.into()),
-        }
+    /// Creates an iterator over every page of activities matching a query
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
+    ///
+    /// # Returns
+    /// Pointer to ActivityIterator, freed with `activity_iterator_free`
+    #[no_mangle]
+    pub unsafe extern "C" fn client_activities_iter(
+        client: *mut ToriiClient,
+        query: ActivityQuery,
+        prefetch: bool,
+    ) -> *mut ActivityIterator {
+        let query: torii_proto::ActivityQuery = query.into();
+        let iterator =
+            page_iterator(&*client, "activities", query, prefetch, |client, query| async move {
+                client.activities(query).await
+            });
+        Box::into_raw(Box::new(ActivityIterator { inner: iterator }))
     }
 
-    /// Retrieves achievements matching query parameter
+    /// Creates an iterator over every page of achievements matching a query
     ///
     /// # Parameters
     /// * `client` - Pointer to ToriiClient instance
-    /// * `query` - AchievementQuery containing world_addresses, namespaces, hidden filter, and
-    ///   pagination
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
     ///
     /// # Returns
-    /// Result containing Page of Achievement or error
+    /// Pointer to AchievementIterator, freed with `achievement_iterator_free`
     #[no_mangle]
-    pub unsafe extern "C" fn client_achievements(
+    pub unsafe extern "C" fn client_achievements_iter(
         client: *mut ToriiClient,
         query: AchievementQuery,
-    ) -> Result<Page<Achievement>> {
-        let query = query.into();
-        let achievements_future = unsafe { (*client).inner.achievements(query) };
-
-        match RUNTIME.block_on(achievements_future) {
-            Ok(achievements) => Result::Ok(achievements.into()),
-            Err(e) => Result::Err(e.into()),
-        }
+        prefetch: bool,
+    ) -> *mut AchievementIterator {
+        let query: torii_proto::AchievementQuery = query.into();
+        let iterator =
+            page_iterator(&*client, "achievements", query, prefetch, |client, query| async move {
+                client.achievements(query).await
+            });
+        Box::into_raw(Box::new(AchievementIterator { inner: iterator }))
     }
 
-    /// Retrieves player achievement data matching query parameter
+    /// Creates an iterator over every page of player achievements matching a query
     ///
     /// # Parameters
     /// * `client` - Pointer to ToriiClient instance
-    /// * `query` - PlayerAchievementQuery containing world_addresses, namespaces, player_addresses,
-    ///   and pagination
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
     ///
     /// # Returns
-    /// Result containing Page of PlayerAchievementEntry or error
+    /// Pointer to PlayerAchievementIterator, freed with `player_achievement_iterator_free`
     #[no_mangle]
-    pub unsafe extern "C" fn client_player_achievements(
+    pub unsafe extern "C" fn client_player_achievements_iter(
         client: *mut ToriiClient,
         query: PlayerAchievementQuery,
-    ) -> Result<Page<PlayerAchievementEntry>> {
-        let query = query.into();
-        let player_achievements_future = unsafe { (*client).inner.player_achievements(query) };
-
-        match RUNTIME.block_on(player_achievements_future) {
-            Ok(player_achievements) => Result::Ok(player_achievements.into()),
-            Err(e) => Result::Err(e.into()),
-        }
+        prefetch: bool,
+    ) -> *mut PlayerAchievementIterator {
+        let query: torii_proto::PlayerAchievementQuery = query.into();
+        let iterator = page_iterator(
+            &*client,
+            "player_achievements",
+            query,
+            prefetch,
+            |client, query| async move { client.player_achievements(query).await },
+        );
+        Box::into_raw(Box::new(PlayerAchievementIterator { inner: iterator }))
     }
 
-    /// Subscribes to achievement progression updates
+    /// Creates an iterator over every page of aggregation entries matching a query
     ///
     /// # Parameters
     /// * `client` - Pointer to ToriiClient instance
-    /// * `world_addresses` - Array of world addresses to subscribe to
-    /// * `world_addresses_len` - Length of world_addresses array
-    /// * `namespaces` - Array of namespaces to subscribe to
-    /// * `namespaces_len` - Length of namespaces array
-    /// * `player_addresses` - Array of player addresses to subscribe to
-    /// * `player_addresses_len` - Length of player_addresses array
-    /// * `achievement_ids` - Array of achievement IDs to subscribe to
-    /// * `achievement_ids_len` - Length of achievement_ids array
-    /// * `callback` - Function called when updates occur
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
     ///
     /// # Returns
-    /// Result containing pointer to Subscription or error
+    /// Pointer to AggregationIterator, freed with `aggregation_iterator_free`
     #[no_mangle]
-    pub unsafe extern "C" fn client_on_achievement_progression_update(
+    pub unsafe extern "C" fn client_aggregations_iter(
         client: *mut ToriiClient,
-        world_addresses: *const types::FieldElement,
-        world_addresses_len: usize,
-        namespaces: *const *const c_char,
-        namespaces_len: usize,
-        player_addresses: *const types::FieldElement,
-        player_addresses_len: usize,
-        achievement_ids: *const *const c_char,
-        achievement_ids_len: usize,
-        callback: unsafe extern "C" fn(AchievementProgression),
-    ) -> Result<*mut Subscription> {
-        let client = Arc::new(unsafe { &*client });
+        query: AggregationQuery,
+        prefetch: bool,
+    ) -> *mut AggregationIterator {
+        let query: torii_proto::AggregationQuery = query.into();
+        let iterator =
+            page_iterator(&*client, "aggregations", query, prefetch, |client, query| async move {
+                client.aggregations(query).await
+            });
+        Box::into_raw(Box::new(AggregationIterator { inner: iterator }))
+    }
 
-        // Convert world_addresses array to Vec<Felt> if not empty
-        let world_addresses = if world_addresses.is_null() || world_addresses_len == 0 {
-            Vec::new()
-        } else {
-            let addrs = unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
-            addrs.iter().map(|addr| addr.clone().into()).collect::<Vec<Felt>>()
-        };
+    /// Fetches the page of entities after the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to EntityIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none once every page was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn entity_iterator_next_page(
+        iterator: *mut EntityIterator,
+    ) -> Result<COption<Page<Entity>>> {
+        unsafe { (*iterator).inner.next_page() }
+    }
 
-        // Convert namespaces array to Vec<String> if not empty
-        let namespaces = if namespaces.is_null() || namespaces_len == 0 {
+    /// Fetches again the page of entities before the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to EntityIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none when on the first page, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn entity_iterator_previous_page(
+        iterator: *mut EntityIterator,
+    ) -> Result<COption<Page<Entity>>> {
+        unsafe { (*iterator).inner.previous_page() }
+    }
+
+    /// Returns the next item of an iterator over entities, fetching the next page when needed
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to EntityIterator
+    ///
+    /// # Returns
+    /// Result containing the item, none once every item was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn entity_iterator_next_item(
+        iterator: *mut EntityIterator,
+    ) -> Result<COption<Entity>> {
+        unsafe { (*iterator).inner.next_item() }
+    }
+
+    /// Fetches the page of controllers after the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to ControllerIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none once every page was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn controller_iterator_next_page(
+        iterator: *mut ControllerIterator,
+    ) -> Result<COption<Page<Controller>>> {
+        unsafe { (*iterator).inner.next_page() }
+    }
+
+    /// Fetches again the page of controllers before the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to ControllerIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none when on the first page, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn controller_iterator_previous_page(
+        iterator: *mut ControllerIterator,
+    ) -> Result<COption<Page<Controller>>> {
+        unsafe { (*iterator).inner.previous_page() }
+    }
+
+    /// Returns the next item of an iterator over controllers, fetching the next page when needed
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to ControllerIterator
+    ///
+    /// # Returns
+    /// Result containing the item, none once every item was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn controller_iterator_next_item(
+        iterator: *mut ControllerIterator,
+    ) -> Result<COption<Controller>> {
+        unsafe { (*iterator).inner.next_item() }
+    }
+
+    /// Fetches the page of tokens after the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to TokenIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none once every page was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn token_iterator_next_page(
+        iterator: *mut TokenIterator,
+    ) -> Result<COption<Page<Token>>> {
+        unsafe { (*iterator).inner.next_page() }
+    }
+
+    /// Fetches again the page of tokens before the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to TokenIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none when on the first page, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn token_iterator_previous_page(
+        iterator: *mut TokenIterator,
+    ) -> Result<COption<Page<Token>>> {
+        unsafe { (*iterator).inner.previous_page() }
+    }
+
+    /// Returns the next item of an iterator over tokens, fetching the next page when needed
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to TokenIterator
+    ///
+    /// # Returns
+    /// Result containing the item, none once every item was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn token_iterator_next_item(
+        iterator: *mut TokenIterator,
+    ) -> Result<COption<Token>> {
+        unsafe { (*iterator).inner.next_item() }
+    }
+
+    /// Fetches the page of token balances after the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to TokenBalanceIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none once every page was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn token_balance_iterator_next_page(
+        iterator: *mut TokenBalanceIterator,
+    ) -> Result<COption<Page<TokenBalance>>> {
+        unsafe { (*iterator).inner.next_page() }
+    }
+
+    /// Fetches again the page of token balances before the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to TokenBalanceIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none when on the first page, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn token_balance_iterator_previous_page(
+        iterator: *mut TokenBalanceIterator,
+    ) -> Result<COption<Page<TokenBalance>>> {
+        unsafe { (*iterator).inner.previous_page() }
+    }
+
+    /// Returns the next item of an iterator over token balances, fetching the next page when needed
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to TokenBalanceIterator
+    ///
+    /// # Returns
+    /// Result containing the item, none once every item was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn token_balance_iterator_next_item(
+        iterator: *mut TokenBalanceIterator,
+    ) -> Result<COption<TokenBalance>> {
+        unsafe { (*iterator).inner.next_item() }
+    }
+
+    /// Fetches the page of token transfers after the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to TokenTransferIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none once every page was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn token_transfer_iterator_next_page(
+        iterator: *mut TokenTransferIterator,
+    ) -> Result<COption<Page<TokenTransfer>>> {
+        unsafe { (*iterator).inner.next_page() }
+    }
+
+    /// Fetches again the page of token transfers before the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to TokenTransferIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none when on the first page, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn token_transfer_iterator_previous_page(
+        iterator: *mut TokenTransferIterator,
+    ) -> Result<COption<Page<TokenTransfer>>> {
+        unsafe { (*iterator).inner.previous_page() }
+    }
+
+    /// Returns the next item of an iterator over token transfers, fetching the next page when needed
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to TokenTransferIterator
+    ///
+    /// # Returns
+    /// Result containing the item, none once every item was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn token_transfer_iterator_next_item(
+        iterator: *mut TokenTransferIterator,
+    ) -> Result<COption<TokenTransfer>> {
+        unsafe { (*iterator).inner.next_item() }
+    }
+
+    /// Fetches the page of transactions after the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to TransactionIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none once every page was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn transaction_iterator_next_page(
+        iterator: *mut TransactionIterator,
+    ) -> Result<COption<Page<Transaction>>> {
+        unsafe { (*iterator).inner.next_page() }
+    }
+
+    /// Fetches again the page of transactions before the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to TransactionIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none when on the first page, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn transaction_iterator_previous_page(
+        iterator: *mut TransactionIterator,
+    ) -> Result<COption<Page<Transaction>>> {
+        unsafe { (*iterator).inner.previous_page() }
+    }
+
+    /// Returns the next item of an iterator over transactions, fetching the next page when needed
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to TransactionIterator
+    ///
+    /// # Returns
+    /// Result containing the item, none once every item was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn transaction_iterator_next_item(
+        iterator: *mut TransactionIterator,
+    ) -> Result<COption<Transaction>> {
+        unsafe { (*iterator).inner.next_item() }
+    }
+
+    /// Fetches the page of activities after the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to ActivityIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none once every page was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn activity_iterator_next_page(
+        iterator: *mut ActivityIterator,
+    ) -> Result<COption<Page<Activity>>> {
+        unsafe { (*iterator).inner.next_page() }
+    }
+
+    /// Fetches again the page of activities before the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to ActivityIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none when on the first page, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn activity_iterator_previous_page(
+        iterator: *mut ActivityIterator,
+    ) -> Result<COption<Page<Activity>>> {
+        unsafe { (*iterator).inner.previous_page() }
+    }
+
+    /// Returns the next item of an iterator over activities, fetching the next page when needed
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to ActivityIterator
+    ///
+    /// # Returns
+    /// Result containing the item, none once every item was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn activity_iterator_next_item(
+        iterator: *mut ActivityIterator,
+    ) -> Result<COption<Activity>> {
+        unsafe { (*iterator).inner.next_item() }
+    }
+
+    /// Fetches the page of achievements after the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to AchievementIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none once every page was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn achievement_iterator_next_page(
+        iterator: *mut AchievementIterator,
+    ) -> Result<COption<Page<Achievement>>> {
+        unsafe { (*iterator).inner.next_page() }
+    }
+
+    /// Fetches again the page of achievements before the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to AchievementIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none when on the first page, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn achievement_iterator_previous_page(
+        iterator: *mut AchievementIterator,
+    ) -> Result<COption<Page<Achievement>>> {
+        unsafe { (*iterator).inner.previous_page() }
+    }
+
+    /// Returns the next item of an iterator over achievements, fetching the next page when needed
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to AchievementIterator
+    ///
+    /// # Returns
+    /// Result containing the item, none once every item was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn achievement_iterator_next_item(
+        iterator: *mut AchievementIterator,
+    ) -> Result<COption<Achievement>> {
+        unsafe { (*iterator).inner.next_item() }
+    }
+
+    /// Fetches the page of player achievements after the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to PlayerAchievementIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none once every page was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn player_achievement_iterator_next_page(
+        iterator: *mut PlayerAchievementIterator,
+    ) -> Result<COption<Page<PlayerAchievementEntry>>> {
+        unsafe { (*iterator).inner.next_page() }
+    }
+
+    /// Fetches again the page of player achievements before the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to PlayerAchievementIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none when on the first page, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn player_achievement_iterator_previous_page(
+        iterator: *mut PlayerAchievementIterator,
+    ) -> Result<COption<Page<PlayerAchievementEntry>>> {
+        unsafe { (*iterator).inner.previous_page() }
+    }
+
+    /// Returns the next item of an iterator over player achievements, fetching the next page when needed
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to PlayerAchievementIterator
+    ///
+    /// # Returns
+    /// Result containing the item, none once every item was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn player_achievement_iterator_next_item(
+        iterator: *mut PlayerAchievementIterator,
+    ) -> Result<COption<PlayerAchievementEntry>> {
+        unsafe { (*iterator).inner.next_item() }
+    }
+
+    /// Fetches the page of aggregation entries after the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to AggregationIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none once every page was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn aggregation_iterator_next_page(
+        iterator: *mut AggregationIterator,
+    ) -> Result<COption<Page<AggregationEntry>>> {
+        unsafe { (*iterator).inner.next_page() }
+    }
+
+    /// Fetches again the page of aggregation entries before the last one returned by an iterator
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to AggregationIterator
+    ///
+    /// # Returns
+    /// Result containing the page, none when on the first page, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn aggregation_iterator_previous_page(
+        iterator: *mut AggregationIterator,
+    ) -> Result<COption<Page<AggregationEntry>>> {
+        unsafe { (*iterator).inner.previous_page() }
+    }
+
+    /// Returns the next item of an iterator over aggregation entries, fetching the next page when needed
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to AggregationIterator
+    ///
+    /// # Returns
+    /// Result containing the item, none once every item was returned, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn aggregation_iterator_next_item(
+        iterator: *mut AggregationIterator,
+    ) -> Result<COption<AggregationEntry>> {
+        unsafe { (*iterator).inner.next_item() }
+    }
+
+    /// Gets the world metadata for the client
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    ///
+    /// # Returns
+    /// World structure containing world information
+    #[no_mangle]
+    pub unsafe extern "C" fn client_worlds(
+        client: *mut ToriiClient,
+        world_addresses: *const types::FieldElement,
+        world_addresses_len: usize,
+    ) -> Result<CArray<World>> {
+        let world_addresses =
+            unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
+        let world_addresses =
+            world_addresses.iter().map(|addr| addr.clone().into()).collect::<Vec<Felt>>();
+        let key = CacheKey::normalized("worlds", &world_addresses);
+        let metadata_future = (*client)
+            .inner
+            .exchange(key, |inner| async move { inner.worlds(world_addresses).await });
+        match block_on_request(&*client, metadata_future) {
+            Ok(metadata) => Result::Ok(metadata.into()),
+            Err(e) => Result::Err(e.into()),
+        }
+    }
+
+    /// Gets the schema of a world, to list its namespaces and models and describe their members
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `world_address` - Address of the world
+    ///
+    /// # Returns
+    /// Result containing pointer to the WorldSchema, to free with world_schema_free, or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_world_schema(
+        client: *mut ToriiClient,
+        world_address: types::FieldElement,
+    ) -> Result<*mut WorldSchema> {
+        let world_addresses = vec![Felt::from(world_address)];
+        let key = CacheKey::normalized("worlds", &world_addresses);
+        let worlds = (*client)
+            .inner
+            .exchange(key, |inner| async move { inner.worlds(world_addresses).await });
+        match block_on_request(&*client, worlds) {
+            Ok(worlds) => match worlds.first() {
+                Some(world) => Result::Ok(Box::into_raw(Box::new(WorldSchema(
+                    dojo_core::schema::WorldSchema::new(world),
+                )))),
+                None => Result::Err(Error { message: c_string("World not found") }),
+            },
+            Err(e) => Result::Err(e),
+        }
+    }
+
+    /// Gets the namespaces of a world having at least one model, sorted
+    ///
+    /// # Parameters
+    /// * `schema` - Pointer to WorldSchema instance
+    ///
+    /// # Returns
+    /// Array of namespaces
+    #[no_mangle]
+    pub unsafe extern "C" fn world_schema_namespaces(
+        schema: *const WorldSchema,
+    ) -> CArray<*const c_char> {
+        let namespaces = unsafe { (*schema).0.namespaces() };
+        namespaces
+            .into_iter()
+            .map(|namespace| c_string(namespace) as *const c_char)
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    /// Gets the models and events of a world, sorted by tag
+    ///
+    /// # Parameters
+    /// * `schema` - Pointer to WorldSchema instance
+    /// * `namespace` - Namespace to list the models of, all namespaces when null
+    ///
+    /// # Returns
+    /// Array of model schemas
+    #[no_mangle]
+    pub unsafe extern "C" fn world_schema_models(
+        schema: *const WorldSchema,
+        namespace: *const c_char,
+    ) -> CArray<ModelSchema> {
+        let schema = unsafe { &(*schema).0 };
+        let models = if namespace.is_null() {
+            schema.models().iter().collect::<Vec<_>>()
+        } else {
+            let namespace = unsafe { CStr::from_ptr(namespace).to_string_lossy() };
+            schema.models_in(&namespace)
+        };
+        models.into_iter().cloned().collect::<Vec<_>>().into()
+    }
+
+    /// Looks up a model of a world by tag
+    ///
+    /// # Parameters
+    /// * `schema` - Pointer to WorldSchema instance
+    /// * `tag` - Tag of the model, e.g. `ns-Position`
+    ///
+    /// # Returns
+    /// The model schema, none if the world has no such model
+    #[no_mangle]
+    pub unsafe extern "C" fn world_schema_model(
+        schema: *const WorldSchema,
+        tag: *const c_char,
+    ) -> COption<ModelSchema> {
+        let tag = unsafe { CStr::from_ptr(tag).to_string_lossy() };
+        unsafe { (*schema).0.model(&tag) }.cloned().into()
+    }
+
+    /// Looks up a model of a world by selector
+    ///
+    /// # Parameters
+    /// * `schema` - Pointer to WorldSchema instance
+    /// * `selector` - Selector of the model, the Poseidon hash of its namespace and name
+    ///
+    /// # Returns
+    /// The model schema, none if the world has no such model
+    #[no_mangle]
+    pub unsafe extern "C" fn world_schema_model_by_selector(
+        schema: *const WorldSchema,
+        selector: types::FieldElement,
+    ) -> COption<ModelSchema> {
+        unsafe { (*schema).0.model_by_selector(selector.into()) }.cloned().into()
+    }
+
+    /// Retrieves transactions matching the given query
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `query` - Query parameters
+    ///
+    /// # Returns
+    /// Result containing array of matching transactions or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_transactions(
+        client: *mut ToriiClient,
+        query: TransactionQuery,
+    ) -> Result<Page<Transaction>> {
+        let query: torii_proto::TransactionQuery = query.into();
+        let key = CacheKey::new("transactions", &query);
+        let transactions_future =
+            (*client).inner.exchange(key, |inner| async move { inner.transactions(query).await });
+
+        match block_on_request(&*client, transactions_future) {
+            Ok(transactions) => Result::Ok(transactions.into()),
+            Err(e) => Result::Err(e.into()),
+        }
+    }
+
+    /// Subscribes to transaction updates
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `filter` - Filter parameters
+    /// * `callback` - Function called when updates occur
+    ///
+    /// # Returns
+    /// Result containing pointer to Subscription or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_on_transaction(
+        client: *mut ToriiClient,
+        filter: COption<TransactionFilter>,
+        callback: unsafe extern "C" fn(Transaction),
+    ) -> Result<*mut Subscription> {
+        let filter: Option<torii_proto::TransactionFilter> = filter.map(|f| f.into()).into();
+
+        let subscribed = subscribe(
+            (*client).inner.clone(),
+            filter,
+            |client: Arc<TClient>, filter| async move {
+                client.on_transaction(filter).await.map(without_id)
+            },
+            sleep,
+            move |transaction: torii_proto::Transaction| callback(transaction.into()),
+        );
+        spawn_subscription(subscribed, "Failed to establish transaction subscription")
+    }
+
+    /// Subscribes to entity state updates
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `clauses` - Array of entity key clauses to filter updates
+    /// * `clauses_len` - Length of clauses array
+    /// * `callback` - Function called when updates occur
+    ///
+    /// # Returns
+    /// Result containing pointer to Subscription or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_on_entity_state_update(
+        client: *mut ToriiClient,
+        clause: COption<Clause>,
+        world_addresses: *const types::FieldElement,
+        world_addresses_len: usize,
+        callback: unsafe extern "C" fn(Entity),
+    ) -> Result<*mut Subscription> {
+        let clause: Option<torii_proto::Clause> = clause.map(|c| c.into()).into();
+        let world_addresses =
+            unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
+        let world_addresses = world_addresses.iter().map(|addr| addr.clone().into()).collect();
+        let filter = EntityFilter { clause, world_addresses };
+        let control = DeliveryControl::default();
+        let projection = control.clone();
+        entity_subscription(
+            &*client,
+            &(*client).entities,
+            EntitySource::Entities,
+            filter,
+            control,
+            move |mut entity| {
+                if projection.project(&mut entity) {
+                    callback(entity.into());
+                }
+            },
+            "Failed to establish entity subscription",
+        )
+    }
+
+    /// Subscribes to entity updates and dispatches their models to the handlers of a
+    /// ModelDispatcher
+    ///
+    /// Handlers can be added to and removed from the dispatcher while the subscription is live.
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `clause` - Optional clause to filter updates
+    /// * `world_addresses` - Array of world addresses to filter updates
+    /// * `world_addresses_len` - Length of world addresses array
+    /// * `dispatcher` - Pointer to the ModelDispatcher holding the handlers
+    ///
+    /// # Returns
+    /// Result containing pointer to Subscription or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_on_entity_dispatch(
+        client: *mut ToriiClient,
+        clause: COption<Clause>,
+        world_addresses: *const types::FieldElement,
+        world_addresses_len: usize,
+        dispatcher: *mut ModelDispatcher,
+    ) -> Result<*mut Subscription> {
+        let clause: Option<torii_proto::Clause> = clause.map(|c| c.into()).into();
+        let world_addresses =
+            unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
+        let world_addresses = world_addresses.iter().map(|addr| addr.clone().into()).collect();
+        let filter = EntityFilter { clause, world_addresses };
+        let dispatcher = unsafe { (*dispatcher).0.clone() };
+        let control = DeliveryControl::default();
+        let projection = control.clone();
+        entity_subscription(
+            &*client,
+            &(*client).entities,
+            EntitySource::Entities,
+            filter,
+            control,
+            move |entity| {
+                dispatcher.dispatch(&entity, |handler, model| {
+                    let mut model = model.clone();
+                    if projection.project(&mut model) {
+                        handler(entity.hashed_keys.into(), model.into());
+                    }
+                });
+            },
+            "Failed to establish entity subscription",
+        )
+    }
+
+    /// Subscribes to entity updates with the predictions of an OptimisticStore applied
+    ///
+    /// The callback receives the entity as it should be shown: its confirmed state with the
+    /// pending predictions of the store on top.
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `clause` - Optional clause to filter updates
+    /// * `world_addresses` - Array of world addresses to filter updates
+    /// * `world_addresses_len` - Length of world addresses array
+    /// * `store` - Pointer to the OptimisticStore holding the predictions
+    /// * `callback` - Function called when updates occur
+    ///
+    /// # Returns
+    /// Result containing pointer to Subscription or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_on_entity_optimistic_update(
+        client: *mut ToriiClient,
+        clause: COption<Clause>,
+        world_addresses: *const types::FieldElement,
+        world_addresses_len: usize,
+        store: *mut OptimisticStore,
+        callback: unsafe extern "C" fn(Entity),
+    ) -> Result<*mut Subscription> {
+        let clause: Option<torii_proto::Clause> = clause.map(|c| c.into()).into();
+        let world_addresses =
+            unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
+        let world_addresses = world_addresses.iter().map(|addr| addr.clone().into()).collect();
+        let filter = EntityFilter { clause, world_addresses };
+        let optimistic = unsafe { (*store).inner.clone() };
+        let control = DeliveryControl::default();
+        let projection = control.clone();
+        entity_subscription(
+            &*client,
+            &(*client).entities,
+            EntitySource::Entities,
+            filter,
+            control,
+            move |entity| {
+                let mut entity = optimistic.confirm(entity);
+                if projection.project(&mut entity) {
+                    callback(entity.into());
+                }
+            },
+            "Failed to establish entity subscription",
+        )
+    }
+
+    /// Subscribes to entity updates, tagged as creations, updates or deletions
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `clause` - Optional clause to filter updates
+    /// * `world_addresses` - Array of world addresses to filter updates
+    /// * `world_addresses_len` - Length of world addresses array
+    /// * `callback` - Function called when updates occur
+    ///
+    /// # Returns
+    /// Result containing pointer to Subscription or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_on_entity_changed(
+        client: *mut ToriiClient,
+        clause: COption<Clause>,
+        world_addresses: *const types::FieldElement,
+        world_addresses_len: usize,
+        callback: unsafe extern "C" fn(EntityChange),
+    ) -> Result<*mut Subscription> {
+        let clause: Option<torii_proto::Clause> = clause.map(|c| c.into()).into();
+        let world_addresses =
+            unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
+        let world_addresses = world_addresses.iter().map(|addr| addr.clone().into()).collect();
+        let filter = EntityFilter { clause, world_addresses };
+        let mut classifier = EntityClassifier::new();
+        let control = DeliveryControl::default();
+        let projection = control.clone();
+        entity_subscription(
+            &*client,
+            &(*client).entities,
+            EntitySource::Entities,
+            filter,
+            control,
+            move |entity| {
+                let mut change = classifier.classify(entity);
+                if projection.project(&mut change) {
+                    callback(change.into());
+                }
+            },
+            "Failed to establish entity subscription",
+        )
+    }
+
+    /// Subscribes to entity updates, reported as field level changes
+    ///
+    /// Each update is compared with the previous state of the entity, and the callback is
+    /// called once per changed member, added or removed model.
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `clause` - Optional clause to filter updates
+    /// * `world_addresses` - Array of world addresses to filter updates
+    /// * `world_addresses_len` - Length of world addresses array
+    /// * `callback` - Function called for each change
+    ///
+    /// # Returns
+    /// Result containing pointer to Subscription or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_on_entity_diff(
+        client: *mut ToriiClient,
+        clause: COption<Clause>,
+        world_addresses: *const types::FieldElement,
+        world_addresses_len: usize,
+        callback: unsafe extern "C" fn(FieldChange),
+    ) -> Result<*mut Subscription> {
+        let clause: Option<torii_proto::Clause> = clause.map(|c| c.into()).into();
+        let world_addresses =
+            unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
+        let world_addresses = world_addresses.iter().map(|addr| addr.clone().into()).collect();
+        let filter = EntityFilter { clause, world_addresses };
+        let mut differ = EntityDiffer::new();
+        let (upgrades, mut seen) = ((*client).inner.clone(), (*client).inner.upgrades().count());
+        let control = DeliveryControl::default();
+        let projection = control.clone();
+        entity_subscription(
+            &*client,
+            &(*client).entities,
+            EntitySource::Entities,
+            filter,
+            control,
+            move |entity| {
+                // Upgraded models are set afresh rather than diffed across schemas
+                for model in upgrades.upgrades().since(&mut seen) {
+                    differ.forget_model(&model);
+                }
+                for mut change in differ.diff(&entity) {
+                    if projection.project(&mut change) {
+                        callback(change.into());
+                    }
+                }
+            },
+            "Failed to establish entity diff subscription",
+        )
+    }
+
+    /// Updates an existing entity subscription with new clauses
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `subscription` - Pointer to existing Subscription
+    /// * `clauses` - New array of entity key clauses
+    /// * `clauses_len` - Length of new clauses array
+    ///
+    /// # Returns
+    /// Result containing success boolean or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_update_entity_subscription(
+        client: *mut ToriiClient,
+        subscription: *mut Subscription,
+        clause: COption<Clause>,
+        world_addresses: *const types::FieldElement,
+        world_addresses_len: usize,
+    ) -> Result<bool> {
+        let clause: Option<torii_proto::Clause> = clause.map(|c| c.into()).into();
+        let world_addresses =
+            unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
+        let world_addresses = world_addresses.iter().map(|addr| addr.clone().into()).collect();
+        let filter = EntityFilter { clause, world_addresses };
+        if let SubscriptionStream::Shared { id, .. } = (*subscription).stream {
+            return update_shared(&(*client).entities, id, filter);
+        }
+        let connection = &(*client).inner;
+        update_own(&*client, &*subscription, filter, |id, filter| {
+            let EntityFilter { clause, world_addresses } = filter;
+            let key = CacheKey::new("update_entity_subscription", &(id, &clause, &world_addresses));
+            connection.exchange(key, |inner| async move {
+                inner.update_entity_subscription(id, clause, world_addresses).await
+            })
+        })
+    }
+
+    /// Retrieves aggregations (leaderboards, stats, rankings) matching query parameter
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `query` - AggregationQuery containing aggregator_ids, entity_ids, and pagination
+    ///
+    /// # Returns
+    /// Result containing Page of AggregationEntry or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_aggregations(
+        client: *mut ToriiClient,
+        query: AggregationQuery,
+    ) -> Result<Page<AggregationEntry>> {
+        let query: torii_proto::AggregationQuery = query.into();
+        let key = CacheKey::new("aggregations", &query);
+        let aggregations_future =
+            (*client).inner.exchange(key, |inner| async move { inner.aggregations(query).await });
+
+        match block_on_request(&*client, aggregations_future) {
+            Ok(aggregations) => Result::Ok(aggregations.into()),
+            Err(e) => Result::Err(e.into()),
+        }
+    }
+
+    /// Subscribes to aggregation updates (leaderboards, stats, rankings)
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `aggregator_ids` - Array of aggregator IDs to subscribe to
+    /// * `aggregator_ids_len` - Length of aggregator_ids array
+    /// * `entity_ids` - Array of entity IDs to subscribe to
+    /// * `entity_ids_len` - Length of entity_ids array
+    /// * `callback` - Function called when updates occur
+    ///
+    /// # Returns
+    /// Result containing pointer to Subscription or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_on_aggregation_update(
+        client: *mut ToriiClient,
+        aggregator_ids: *const *const c_char,
+        aggregator_ids_len: usize,
+        entity_ids: *const *const c_char,
+        entity_ids_len: usize,
+        callback: unsafe extern "C" fn(AggregationEntry),
+    ) -> Result<*mut Subscription> {
+        // Convert aggregator_ids array to Vec<String> if not empty
+        let aggregator_ids = if aggregator_ids.is_null() || aggregator_ids_len == 0 {
+            Vec::new()
+        } else {
+            let ids = unsafe { std::slice::from_raw_parts(aggregator_ids, aggregator_ids_len) };
+            ids.iter()
+                .map(|id| unsafe { CStr::from_ptr(*id).to_string_lossy().to_string() })
+                .collect::<Vec<String>>()
+        };
+
+        let entity_ids = if entity_ids.is_null() || entity_ids_len == 0 {
+            Vec::new()
+        } else {
+            let ids = unsafe { std::slice::from_raw_parts(entity_ids, entity_ids_len) };
+            ids.iter()
+                .map(|id| unsafe { CStr::from_ptr(*id).to_string_lossy().to_string() })
+                .collect::<Vec<String>>()
+        };
+
+        let subscribed = subscribe(
+            (*client).inner.clone(),
+            (aggregator_ids, entity_ids),
+            |client: Arc<TClient>, (aggregator_ids, entity_ids)| async move {
+                client.on_aggregation_updated(aggregator_ids, entity_ids).await
+            },
+            sleep,
+            move |entry: torii_proto::AggregationEntry| callback(entry.into()),
+        );
+        spawn_subscription(subscribed, "Failed to establish aggregation subscription")
+    }
+
+    /// Updates an existing aggregation subscription with new parameters
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `subscription` - Pointer to existing Subscription
+    /// * `aggregator_ids` - Array of aggregator IDs to subscribe to
+    /// * `aggregator_ids_len` - Length of aggregator_ids array
+    /// * `entity_ids` - Array of entity IDs to subscribe to
+    /// * `entity_ids_len` - Length of entity_ids array
+    ///
+    /// # Returns
+    /// Result containing success boolean or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_update_aggregation_subscription(
+        client: *mut ToriiClient,
+        subscription: *mut Subscription,
+        aggregator_ids: *const *const c_char,
+        aggregator_ids_len: usize,
+        entity_ids: *const *const c_char,
+        entity_ids_len: usize,
+    ) -> Result<bool> {
+        // Convert aggregator_ids array to Vec<String> if not empty
+        let aggregator_ids = if aggregator_ids.is_null() || aggregator_ids_len == 0 {
+            Vec::new()
+        } else {
+            let ids = unsafe { std::slice::from_raw_parts(aggregator_ids, aggregator_ids_len) };
+            ids.iter()
+                .map(|id| unsafe { CStr::from_ptr(*id).to_string_lossy().to_string() })
+                .collect::<Vec<String>>()
+        };
+
+        let entity_ids = if entity_ids.is_null() || entity_ids_len == 0 {
+            Vec::new()
+        } else {
+            let ids = unsafe { std::slice::from_raw_parts(entity_ids, entity_ids_len) };
+            ids.iter()
+                .map(|id| unsafe { CStr::from_ptr(*id).to_string_lossy().to_string() })
+                .collect::<Vec<String>>()
+        };
+
+        let connection = &(*client).inner;
+        let query = (aggregator_ids, entity_ids);
+        update_own(&*client, &*subscription, query, |id, (aggregator_ids, entity_ids)| {
+            let key = CacheKey::new(
+                "update_aggregation_subscription",
+                &(id, &aggregator_ids, &entity_ids),
+            );
+            connection.exchange(key, |inner| async move {
+                inner.update_aggregation_subscription(id, aggregator_ids, entity_ids).await
+            })
+        })
+    }
+
+    /// Retrieves achievements matching query parameter
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `query` - AchievementQuery containing world_addresses, namespaces, hidden filter, and
+    ///   pagination
+    ///
+    /// # Returns
+    /// Result containing Page of Achievement or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_achievements(
+        client: *mut ToriiClient,
+        query: AchievementQuery,
+    ) -> Result<Page<Achievement>> {
+        let query: torii_proto::AchievementQuery = query.into();
+        let key = CacheKey::new("achievements", &query);
+        let achievements_future =
+            (*client).inner.exchange(key, |inner| async move { inner.achievements(query).await });
+
+        match block_on_request(&*client, achievements_future) {
+            Ok(achievements) => Result::Ok(achievements.into()),
+            Err(e) => Result::Err(e.into()),
+        }
+    }
+
+    /// Retrieves player achievement data matching query parameter
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `query` - PlayerAchievementQuery containing world_addresses, namespaces, player_addresses,
+    ///   and pagination
+    ///
+    /// # Returns
+    /// Result containing Page of PlayerAchievementEntry or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_player_achievements(
+        client: *mut ToriiClient,
+        query: PlayerAchievementQuery,
+    ) -> Result<Page<PlayerAchievementEntry>> {
+        let query: torii_proto::PlayerAchievementQuery = query.into();
+        let key = CacheKey::new("player_achievements", &query);
+        let player_achievements_future = (*client)
+            .inner
+            .exchange(key, |inner| async move { inner.player_achievements(query).await });
+
+        match block_on_request(&*client, player_achievements_future) {
+            Ok(player_achievements) => Result::Ok(player_achievements.into()),
+            Err(e) => Result::Err(e.into()),
+        }
+    }
+
+    /// Subscribes to achievement progression updates
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `world_addresses` - Array of world addresses to subscribe to
+    /// * `world_addresses_len` - Length of world_addresses array
+    /// * `namespaces` - Array of namespaces to subscribe to
+    /// * `namespaces_len` - Length of namespaces array
+    /// * `player_addresses` - Array of player addresses to subscribe to
+    /// * `player_addresses_len` - Length of player_addresses array
+    /// * `achievement_ids` - Array of achievement IDs to subscribe to
+    /// * `achievement_ids_len` - Length of achievement_ids array
+    /// * `callback` - Function called when updates occur
+    ///
+    /// # Returns
+    /// Result containing pointer to Subscription or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_on_achievement_progression_update(
+        client: *mut ToriiClient,
+        world_addresses: *const types::FieldElement,
+        world_addresses_len: usize,
+        namespaces: *const *const c_char,
+        namespaces_len: usize,
+        player_addresses: *const types::FieldElement,
+        player_addresses_len: usize,
+        achievement_ids: *const *const c_char,
+        achievement_ids_len: usize,
+        callback: unsafe extern "C" fn(AchievementProgression),
+    ) -> Result<*mut Subscription> {
+        // Convert world_addresses array to Vec<Felt> if not empty
+        let world_addresses = if world_addresses.is_null() || world_addresses_len == 0 {
+            Vec::new()
+        } else {
+            let addrs = unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
+            addrs.iter().map(|addr| addr.clone().into()).collect::<Vec<Felt>>()
+        };
+
+        // Convert namespaces array to Vec<String> if not empty
+        let namespaces = if namespaces.is_null() || namespaces_len == 0 {
+            Vec::new()
+        } else {
+            let ns = unsafe { std::slice::from_raw_parts(namespaces, namespaces_len) };
+            ns.iter()
+                .map(|n| unsafe { CStr::from_ptr(*n).to_string_lossy().to_string() })
+                .collect::<Vec<String>>()
+        };
+
+        // Convert player_addresses array to Vec<Felt> if not empty
+        let player_addresses = if player_addresses.is_null() || player_addresses_len == 0 {
+            Vec::new()
+        } else {
+            let addrs =
+                unsafe { std::slice::from_raw_parts(player_addresses, player_addresses_len) };
+            addrs.iter().map(|addr| addr.clone().into()).collect::<Vec<Felt>>()
+        };
+
+        // Convert achievement_ids array to Vec<String> if not empty
+        let achievement_ids = if achievement_ids.is_null() || achievement_ids_len == 0 {
+            Vec::new()
+        } else {
+            let ids = unsafe { std::slice::from_raw_parts(achievement_ids, achievement_ids_len) };
+            ids.iter()
+                .map(|id| unsafe { CStr::from_ptr(*id).to_string_lossy().to_string() })
+                .collect::<Vec<String>>()
+        };
+
+        let subscribed = subscribe(
+            (*client).inner.clone(),
+            (world_addresses, namespaces, player_addresses, achievement_ids),
+            |client: Arc<TClient>, query| async move {
+                let (world_addresses, namespaces, player_addresses, achievement_ids) = query;
+                client
+                    .on_achievement_progression_updated(
+                        world_addresses,
+                        namespaces,
+                        player_addresses,
+                        achievement_ids,
+                    )
+                    .await
+            },
+            sleep,
+            move |progression: torii_proto::AchievementProgression| callback(progression.into()),
+        );
+        spawn_subscription(subscribed, "Failed to establish achievement progression subscription")
+    }
+
+    /// Updates an existing achievement progression subscription with new parameters
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `subscription` - Pointer to existing Subscription
+    /// * `world_addresses` - Array of world addresses to subscribe to
+    /// * `world_addresses_len` - Length of world_addresses array
+    /// * `namespaces` - Array of namespaces to subscribe to
+    /// * `namespaces_len` - Length of namespaces array
+    /// * `player_addresses` - Array of player addresses to subscribe to
+    /// * `player_addresses_len` - Length of player_addresses array
+    /// * `achievement_ids` - Array of achievement IDs to subscribe to
+    /// * `achievement_ids_len` - Length of achievement_ids array
+    ///
+    /// # Returns
+    /// Result containing success boolean or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_update_achievement_progression_subscription(
+        client: *mut ToriiClient,
+        subscription: *mut Subscription,
+        world_addresses: *const types::FieldElement,
+        world_addresses_len: usize,
+        namespaces: *const *const c_char,
+        namespaces_len: usize,
+        player_addresses: *const types::FieldElement,
+        player_addresses_len: usize,
+        achievement_ids: *const *const c_char,
+        achievement_ids_len: usize,
+    ) -> Result<bool> {
+        // Convert world_addresses array to Vec<Felt> if not empty
+        let world_addresses = if world_addresses.is_null() || world_addresses_len == 0 {
+            Vec::new()
+        } else {
+            let addrs = unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
+            addrs.iter().map(|addr| addr.clone().into()).collect::<Vec<Felt>>()
+        };
+
+        // Convert namespaces array to Vec<String> if not empty
+        let namespaces = if namespaces.is_null() || namespaces_len == 0 {
+            Vec::new()
+        } else {
+            let ns = unsafe { std::slice::from_raw_parts(namespaces, namespaces_len) };
+            ns.iter()
+                .map(|n| unsafe { CStr::from_ptr(*n).to_string_lossy().to_string() })
+                .collect::<Vec<String>>()
+        };
+
+        // Convert player_addresses array to Vec<Felt> if not empty
+        let player_addresses = if player_addresses.is_null() || player_addresses_len == 0 {
+            Vec::new()
+        } else {
+            let addrs =
+                unsafe { std::slice::from_raw_parts(player_addresses, player_addresses_len) };
+            addrs.iter().map(|addr| addr.clone().into()).collect::<Vec<Felt>>()
+        };
+
+        // Convert achievement_ids array to Vec<String> if not empty
+        let achievement_ids = if achievement_ids.is_null() || achievement_ids_len == 0 {
+            Vec::new()
+        } else {
+            let ids = unsafe { std::slice::from_raw_parts(achievement_ids, achievement_ids_len) };
+            ids.iter()
+                .map(|id| unsafe { CStr::from_ptr(*id).to_string_lossy().to_string() })
+                .collect::<Vec<String>>()
+        };
+
+        let connection = &(*client).inner;
+        let query = (world_addresses, namespaces, player_addresses, achievement_ids);
+        update_own(&*client, &*subscription, query, |id, query| {
+            let (world_addresses, namespaces, player_addresses, achievement_ids) = query;
+            let key = CacheKey::new(
+                "update_achievement_progression_subscription",
+                &(id, &world_addresses, &namespaces, &player_addresses, &achievement_ids),
+            );
+            connection.exchange(key, |inner| async move {
+                inner
+                    .update_achievement_progression_subscription(
+                        id,
+                        world_addresses,
+                        namespaces,
+                        player_addresses,
+                        achievement_ids,
+                    )
+                    .await
+            })
+        })
+    }
+
+    /// Retrieves activities (user session tracking) matching query parameter
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `query` - ActivityQuery containing world_addresses, namespaces, caller_addresses, and
+    ///   pagination
+    ///
+    /// # Returns
+    /// Result containing Page of Activity or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_activities(
+        client: *mut ToriiClient,
+        query: ActivityQuery,
+    ) -> Result<Page<Activity>> {
+        let query: torii_proto::ActivityQuery = query.into();
+        let key = CacheKey::new("activities", &query);
+        let activities_future =
+            (*client).inner.exchange(key, |inner| async move { inner.activities(query).await });
+
+        match block_on_request(&*client, activities_future) {
+            Ok(activities) => Result::Ok(activities.into()),
+            Err(e) => Result::Err(e.into()),
+        }
+    }
+
+    /// Subscribes to activity updates (user session tracking)
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `world_addresses` - Array of world addresses to subscribe to
+    /// * `world_addresses_len` - Length of world_addresses array
+    /// * `namespaces` - Array of namespaces to subscribe to
+    /// * `namespaces_len` - Length of namespaces array
+    /// * `caller_addresses` - Array of caller addresses to subscribe to
+    /// * `caller_addresses_len` - Length of caller_addresses array
+    /// * `callback` - Function called when updates occur
+    ///
+    /// # Returns
+    /// Result containing pointer to Subscription or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_on_activity_update(
+        client: *mut ToriiClient,
+        world_addresses: *const types::FieldElement,
+        world_addresses_len: usize,
+        namespaces: *const *const c_char,
+        namespaces_len: usize,
+        caller_addresses: *const types::FieldElement,
+        caller_addresses_len: usize,
+        callback: unsafe extern "C" fn(Activity),
+    ) -> Result<*mut Subscription> {
+        // Convert world addresses array to Vec<Felt> if not empty
+        let world_addresses = if world_addresses.is_null() || world_addresses_len == 0 {
+            Vec::new()
+        } else {
+            let addresses =
+                unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
+            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
+        };
+
+        // Convert namespaces array to Vec<String> if not empty
+        let namespaces = if namespaces.is_null() || namespaces_len == 0 {
             Vec::new()
         } else {
             let ns = unsafe { std::slice::from_raw_parts(namespaces, namespaces_len) };
             ns.iter()
-                .map(|n| unsafe { CStr::from_ptr(*n).to_string_lossy().to_string() })
+                .map(|ns| unsafe { CStr::from_ptr(*ns).to_string_lossy().to_string() })
                 .collect::<Vec<String>>()
         };
 
-        // Convert player_addresses array to Vec<Felt> if not empty
-        let player_addresses = if player_addresses.is_null() || player_addresses_len == 0 {
+        // Convert caller addresses array to Vec<Felt> if not empty
+        let caller_addresses = if caller_addresses.is_null() || caller_addresses_len == 0 {
             Vec::new()
         } else {
-            let addrs =
-                unsafe { std::slice::from_raw_parts(player_addresses, player_addresses_len) };
-            addrs.iter().map(|addr| addr.clone().into()).collect::<Vec<Felt>>()
+            let addresses =
+                unsafe { std::slice::from_raw_parts(caller_addresses, caller_addresses_len) };
+            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
         };
 
-        // Convert achievement_ids array to Vec<String> if not empty
-        let achievement_ids = if achievement_ids.is_null() || achievement_ids_len == 0 {
+        let subscribed = subscribe(
+            (*client).inner.clone(),
+            (world_addresses, namespaces, caller_addresses),
+            |client: Arc<TClient>, (world_addresses, namespaces, caller_addresses)| async move {
+                client.on_activity_updated(world_addresses, namespaces, caller_addresses).await
+            },
+            sleep,
+            move |activity: torii_proto::Activity| callback(activity.into()),
+        );
+        spawn_subscription(subscribed, "Failed to establish activity subscription")
+    }
+
+    /// Updates an existing activity subscription with new parameters
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `subscription` - Pointer to existing Subscription
+    /// * `world_addresses` - Array of world addresses to subscribe to
+    /// * `world_addresses_len` - Length of world_addresses array
+    /// * `namespaces` - Array of namespaces to subscribe to
+    /// * `namespaces_len` - Length of namespaces array
+    /// * `caller_addresses` - Array of caller addresses to subscribe to
+    /// * `caller_addresses_len` - Length of caller_addresses array
+    ///
+    /// # Returns
+    /// Result containing success boolean or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_update_activity_subscription(
+        client: *mut ToriiClient,
+        subscription: *mut Subscription,
+        world_addresses: *const types::FieldElement,
+        world_addresses_len: usize,
+        namespaces: *const *const c_char,
+        namespaces_len: usize,
+        caller_addresses: *const types::FieldElement,
+        caller_addresses_len: usize,
+    ) -> Result<bool> {
+        // Convert world addresses array to Vec<Felt> if not empty
+        let world_addresses = if world_addresses.is_null() || world_addresses_len == 0 {
             Vec::new()
         } else {
-            let ids = unsafe { std::slice::from_raw_parts(achievement_ids, achievement_ids_len) };
-            ids.iter()
-                .map(|id| unsafe { CStr::from_ptr(*id).to_string_lossy().to_string() })
+            let addresses =
+                unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
+            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
+        };
+
+        // Convert namespaces array to Vec<String> if not empty
+        let namespaces = if namespaces.is_null() || namespaces_len == 0 {
+            Vec::new()
+        } else {
+            let ns = unsafe { std::slice::from_raw_parts(namespaces, namespaces_len) };
+            ns.iter()
+                .map(|ns| unsafe { CStr::from_ptr(*ns).to_string_lossy().to_string() })
                 .collect::<Vec<String>>()
         };
 
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-        let (trigger, tripwire) = Tripwire::new();
+        // Convert caller addresses array to Vec<Felt> if not empty
+        let caller_addresses = if caller_addresses.is_null() || caller_addresses_len == 0 {
+            Vec::new()
+        } else {
+            let addresses =
+                unsafe { std::slice::from_raw_parts(caller_addresses, caller_addresses_len) };
+            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
+        };
 
-        // Spawn a new thread to handle the stream and reconnections
-        let client_clone = client.clone();
-        RUNTIME.spawn(async move {
-            let mut backoff = Duration::from_secs(1);
-            let max_backoff = Duration::from_secs(60);
+        let connection = &(*client).inner;
+        let query = (world_addresses, namespaces, caller_addresses);
+        update_own(&*client, &*subscription, query, |id, query| {
+            let (world_addresses, namespaces, caller_addresses) = query;
+            let key = CacheKey::new(
+                "update_activity_subscription",
+                &(id, &world_addresses, &namespaces, &caller_addresses),
+            );
+            connection.exchange(key, |inner| async move {
+                inner
+                    .update_activity_subscription(id, world_addresses, namespaces, caller_addresses)
+                    .await
+            })
+        })
+    }
 
-            loop {
-                let rcv = client_clone
-                    .inner
-                    .on_achievement_progression_updated(
-                        world_addresses.clone(),
-                        namespaces.clone(),
-                        player_addresses.clone(),
-                        achievement_ids.clone(),
-                    )
-                    .await;
+    /// Subscribes to event message updates
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `clauses` - Array of entity key clauses to filter updates
+    /// * `clauses_len` - Length of clauses array
+    /// * `callback` - Function called when updates occur
+    ///
+    /// # Returns
+    /// Result containing pointer to Subscription or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_on_event_message_update(
+        client: *mut ToriiClient,
+        clause: COption<Clause>,
+        world_addresses: *const types::FieldElement,
+        world_addresses_len: usize,
+        callback: unsafe extern "C" fn(Entity),
+    ) -> Result<*mut Subscription> {
+        let clause: Option<torii_proto::Clause> = clause.map(|c| c.into()).into();
+        let world_addresses =
+            unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
+        let world_addresses = world_addresses.iter().map(|addr| addr.clone().into()).collect();
+        let filter = EntityFilter { clause, world_addresses };
+        let control = DeliveryControl::default();
+        let projection = control.clone();
+        entity_subscription(
+            &*client,
+            &(*client).event_messages,
+            EntitySource::EventMessages,
+            filter,
+            control,
+            move |mut entity| {
+                if projection.project(&mut entity) {
+                    callback(entity.into());
+                }
+            },
+            "Failed to establish event message subscription",
+        )
+    }
+
+    /// Subscribes to event message updates and dispatches their models to the handlers of a
+    /// ModelDispatcher
+    ///
+    /// Handlers can be added to and removed from the dispatcher while the subscription is live.
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `clause` - Optional clause to filter updates
+    /// * `world_addresses` - Array of world addresses to filter updates
+    /// * `world_addresses_len` - Length of world addresses array
+    /// * `dispatcher` - Pointer to the ModelDispatcher holding the handlers
+    ///
+    /// # Returns
+    /// Result containing pointer to Subscription or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_on_event_message_dispatch(
+        client: *mut ToriiClient,
+        clause: COption<Clause>,
+        world_addresses: *const types::FieldElement,
+        world_addresses_len: usize,
+        dispatcher: *mut ModelDispatcher,
+    ) -> Result<*mut Subscription> {
+        let clause: Option<torii_proto::Clause> = clause.map(|c| c.into()).into();
+        let world_addresses =
+            unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
+        let world_addresses = world_addresses.iter().map(|addr| addr.clone().into()).collect();
+        let filter = EntityFilter { clause, world_addresses };
+        let dispatcher = unsafe { (*dispatcher).0.clone() };
+        let control = DeliveryControl::default();
+        let projection = control.clone();
+        entity_subscription(
+            &*client,
+            &(*client).event_messages,
+            EntitySource::EventMessages,
+            filter,
+            control,
+            move |entity| {
+                dispatcher.dispatch(&entity, |handler, model| {
+                    let mut model = model.clone();
+                    if projection.project(&mut model) {
+                        handler(entity.hashed_keys.into(), model.into());
+                    }
+                });
+            },
+            "Failed to establish event message subscription",
+        )
+    }
+
+    /// Updates an existing event message subscription
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `subscription` - Pointer to existing Subscription
+    /// * `clauses` - New array of entity key clauses
+    /// * `clauses_len` - Length of new clauses array
+    ///
+    /// # Returns
+    /// Result containing success boolean or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_update_event_message_subscription(
+        client: *mut ToriiClient,
+        subscription: *mut Subscription,
+        clause: COption<Clause>,
+        world_addresses: *const types::FieldElement,
+        world_addresses_len: usize,
+    ) -> Result<bool> {
+        let clause: Option<torii_proto::Clause> = clause.map(|c| c.into()).into();
+        let world_addresses =
+            unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
+        let world_addresses = world_addresses.iter().map(|addr| addr.clone().into()).collect();
+        let filter = EntityFilter { clause, world_addresses };
+        if let SubscriptionStream::Shared { id, .. } = (*subscription).stream {
+            return update_shared(&(*client).event_messages, id, filter);
+        }
+        let connection = &(*client).inner;
+        update_own(&*client, &*subscription, filter, |id, filter| {
+            let EntityFilter { clause, world_addresses } = filter;
+            let key = CacheKey::new(
+                "update_event_message_subscription",
+                &(id, &clause, &world_addresses),
+            );
+            connection.exchange(key, |inner| async move {
+                inner.update_event_message_subscription(id, clause, world_addresses).await
+            })
+        })
+    }
+
+    /// Subscribes to Starknet events
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `clauses` - Array of entity key clauses to filter events
+    /// * `clauses_len` - Length of clauses array
+    /// * `callback` - Function called when events occur
+    ///
+    /// # Returns
+    /// Result containing pointer to Subscription or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_on_starknet_event(
+        client: *mut ToriiClient,
+        clauses: *const KeysClause,
+        clauses_len: usize,
+        callback: unsafe extern "C" fn(Event),
+    ) -> Result<*mut Subscription> {
+        let clauses = if clauses.is_null() || clauses_len == 0 {
+            Vec::new()
+        } else {
+            let clauses = unsafe { std::slice::from_raw_parts(clauses, clauses_len) };
+            clauses.iter().map(|c| c.clone().into()).collect::<Vec<_>>()
+        };
 
-                if let Ok(rcv) = rcv {
-                    backoff = Duration::from_secs(1); // Reset backoff on successful connection
+        let subscribed = subscribe(
+            (*client).inner.clone(),
+            clauses,
+            |client: Arc<TClient>, clauses| async move {
+                client.on_starknet_event(clauses).await.map(without_id)
+            },
+            sleep,
+            move |event: torii_proto::Event| callback(event.into()),
+        );
+        spawn_subscription(subscribed, "Failed to establish event subscription")
+    }
 
-                    let mut rcv = rcv.take_until_if(tripwire.clone());
+    /// Watches a world for models and events registered or upgraded while the client runs
+    ///
+    /// Registration and upgrade events of the world trigger a refresh of its schema, as does
+    /// every connection of the subscription. The cached query results of the world and the
+    /// clause validator are dropped whenever a model changed
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `world_address` - Address of the world to watch
+    /// * `store` - Pointer to an OptimisticStore whose predictions of upgraded models are
+    ///   discarded, or null
+    /// * `callback` - Function called with each registered or upgraded model, along with the
+    ///   changes of its schema
+    ///
+    /// # Returns
+    /// Result containing pointer to Subscription or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_on_schema_upgrade(
+        client: *mut ToriiClient,
+        world_address: types::FieldElement,
+        store: *mut OptimisticStore,
+        callback: unsafe extern "C" fn(SchemaUpgrade),
+    ) -> Result<*mut Subscription> {
+        let connection = unsafe { (*client).inner.clone() };
+        let watcher = Arc::new(SchemaWatcher::new(world_address.into()));
+        // Records the schema the upgrades are compared with
+        if let Err(e) = block_on_connection(&connection, watcher.refresh(&connection)) {
+            return Result::Err(e);
+        }
 
-                    while let Some(Ok((id, progression))) = rcv.next().await {
-                        // Our first message will be the subscription ID
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(id).expect("Failed to send subscription ID");
-                        } else {
-                            let progression: AchievementProgression = progression.into();
-                            callback(progression);
+        let store =
+            (!store.is_null()).then(|| unsafe { ((*store).inner.clone(), (*store).callback) });
+        let refresh = {
+            let connection = connection.clone();
+            move || {
+                let (connection, watcher, store) =
+                    (connection.clone(), watcher.clone(), store.clone());
+                RUNTIME.spawn(async move {
+                    let Ok(upgrades) = watcher.refresh(&connection).await else { return };
+                    for upgrade in upgrades {
+                        if let Some((optimistic, on_view)) = &store {
+                            for view in optimistic.discard_model(&upgrade.new.tag) {
+                                on_view(view.into());
+                            }
                         }
+                        callback(upgrade.into());
                     }
-                }
+                });
+            }
+        };
 
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
+        let on_event = refresh.clone();
+        let subscribed = subscribe(
+            connection,
+            (),
+            |client: Arc<TClient>, ()| async move {
+                client.on_starknet_event(upgrade_clauses()).await.map(without_id)
+            },
+            sleep,
+            move |_: torii_proto::Event| on_event(),
+        );
+        // Upgrades may have landed before the stream was live, or while reconnecting
+        let mut states = subscribed.handle.status.watch();
+        RUNTIME.spawn(async move {
+            while let Some(state) = states.next().await {
+                if state == dojo_core::status::SubscriptionState::Live {
+                    refresh();
                 }
-                sleep(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
             }
         });
+        spawn_subscription(subscribed, "Failed to establish schema upgrade subscription")
+    }
 
-        let subscription_id = match RUNTIME.block_on(sub_id_rx) {
-            Ok(id) => id,
-            Err(_) => {
-                return Result::Err(Error {
-                    message: CString::new(
-                        "Failed to establish achievement progression subscription",
-                    )
-                    .unwrap()
-                    .into_raw(),
-                });
-            }
+    /// Retrieves token information for given contract addresses
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `contract_addresses` - Array of contract addresses
+    /// * `contract_addresses_len` - Length of addresses array
+    /// * `token_ids` - Array of token ids
+    /// * `token_ids_len` - Length of token ids array
+    /// * `limit` - Maximum number of tokens to return
+    /// * `cursor` - Cursor to start from
+    ///
+    /// # Returns
+    /// Result containing array of Token information or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_tokens(
+        client: *mut ToriiClient,
+        query: TokenQuery,
+    ) -> Result<Page<Token>> {
+        let query: torii_proto::TokenQuery = query.into();
+        let key = CacheKey::normalized("tokens", &query);
+        let scope = Scope::tokens(&query);
+        let tokens = cached_request(
+            &*client,
+            key,
+            move |_| scope,
+            move |inner| {
+                let query = query.clone();
+                async move { inner.tokens(query).await }
+            },
+        );
+
+        match tokens {
+            Ok(tokens) => Result::Ok(tokens.into()),
+            Err(e) => Result::Err(e.into()),
+        }
+    }
+
+    /// Subscribes to token updates
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `contract_addresses` - Array of contract addresses
+    /// * `callback` - Function called when updates occur
+    ///
+    /// # Returns
+    /// Result containing pointer to Subscription or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_on_token_update(
+        client: *mut ToriiClient,
+        contract_addresses: *const types::FieldElement,
+        contract_addresses_len: usize,
+        token_ids: *const types::U256,
+        token_ids_len: usize,
+        callback: unsafe extern "C" fn(Token),
+    ) -> Result<*mut Subscription> {
+        // Convert contract addresses array to Vec<Felt> if not empty
+        let contract_addresses = if contract_addresses.is_null() || contract_addresses_len == 0 {
+            Vec::new()
+        } else {
+            let addresses =
+                unsafe { std::slice::from_raw_parts(contract_addresses, contract_addresses_len) };
+            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
         };
 
-        let subscription = Subscription { id: subscription_id, trigger };
+        let token_ids = if token_ids.is_null() || token_ids_len == 0 {
+            Vec::new()
+        } else {
+            let ids = unsafe { std::slice::from_raw_parts(token_ids, token_ids_len) };
+            ids.iter().map(|f| f.clone().into()).collect::<Vec<U256>>()
+        };
 
-        Result::Ok(Box::into_raw(Box::new(subscription)))
+        let subscribed = subscribe(
+            (*client).inner.clone(),
+            (contract_addresses, token_ids),
+            |client: Arc<TClient>, (contract_addresses, token_ids)| async move {
+                client.on_token_updated(contract_addresses, token_ids).await
+            },
+            sleep,
+            move |token: torii_proto::Token| callback(token.into()),
+        );
+        spawn_subscription(subscribed, "Failed to establish token subscription")
     }
 
-    /// Updates an existing achievement progression subscription with new parameters
+    /// Gets token balances for given accounts and contracts
     ///
     /// # Parameters
     /// * `client` - Pointer to ToriiClient instance
-    /// * `subscription` - Pointer to existing Subscription
-    /// * `world_addresses` - Array of world addresses to subscribe to
-    /// * `world_addresses_len` - Length of world_addresses array
-    /// * `namespaces` - Array of namespaces to subscribe to
-    /// * `namespaces_len` - Length of namespaces array
-    /// * `player_addresses` - Array of player addresses to subscribe to
-    /// * `player_addresses_len` - Length of player_addresses array
-    /// * `achievement_ids` - Array of achievement IDs to subscribe to
-    /// * `achievement_ids_len` - Length of achievement_ids array
+    /// * `contract_addresses` - Array of contract addresses
+    /// * `contract_addresses_len` - Length of contract addresses array
+    /// * `account_addresses` - Array of account addresses
+    /// * `account_addresses_len` - Length of account addresses array
+    /// * `token_ids` - Array of token ids
+    /// * `token_ids_len` - Length of token ids array
+    /// * `limit` - Maximum number of token balances to return
+    /// * `cursor` - Cursor to start from
     ///
     /// # Returns
-    /// Result containing success boolean or error
+    /// Result containing array of TokenBalance information or error
     #[no_mangle]
-    pub unsafe extern "C" fn client_update_achievement_progression_subscription(
+    pub unsafe extern "C" fn client_token_balances(
         client: *mut ToriiClient,
-        subscription: *mut Subscription,
-        world_addresses: *const types::FieldElement,
-        world_addresses_len: usize,
-        namespaces: *const *const c_char,
-        namespaces_len: usize,
-        player_addresses: *const types::FieldElement,
-        player_addresses_len: usize,
-        achievement_ids: *const *const c_char,
-        achievement_ids_len: usize,
-    ) -> Result<bool> {
-        // Convert world_addresses array to Vec<Felt> if not empty
-        let world_addresses = if world_addresses.is_null() || world_addresses_len == 0 {
-            Vec::new()
-        } else {
-            let addrs = unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
-            addrs.iter().map(|addr| addr.clone().into()).collect::<Vec<Felt>>()
-        };
+        query: TokenBalanceQuery,
+    ) -> Result<Page<TokenBalance>> {
+        let query: torii_proto::TokenBalanceQuery = query.into();
+        let key = CacheKey::normalized("token_balances", &query);
+        let scope = Scope::token_balances(&query);
+        let token_balances = cached_request(
+            &*client,
+            key,
+            move |_| scope,
+            move |inner| {
+                let query = query.clone();
+                async move { inner.token_balances(query).await }
+            },
+        );
 
-        // Convert namespaces array to Vec<String> if not empty
-        let namespaces = if namespaces.is_null() || namespaces_len == 0 {
-            Vec::new()
-        } else {
-            let ns = unsafe { std::slice::from_raw_parts(namespaces, namespaces_len) };
-            ns.iter()
-                .map(|n| unsafe { CStr::from_ptr(*n).to_string_lossy().to_string() })
-                .collect::<Vec<String>>()
-        };
+        match token_balances {
+            Ok(token_balances) => Result::Ok(token_balances.into()),
+            Err(e) => Result::Err(e.into()),
+        }
+    }
 
-        // Convert player_addresses array to Vec<Felt> if not empty
-        let player_addresses = if player_addresses.is_null() || player_addresses_len == 0 {
-            Vec::new()
-        } else {
-            let addrs =
-                unsafe { std::slice::from_raw_parts(player_addresses, player_addresses_len) };
-            addrs.iter().map(|addr| addr.clone().into()).collect::<Vec<Felt>>()
-        };
+    /// Gets token collections for given accounts and contracts
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `contract_addresses` - Array of contract addresses
+    /// * `contract_addresses_len` - Length of contract addresses array
+    /// * `account_addresses` - Array of account addresses
+    /// * `account_addresses_len` - Length of account addresses array
+    /// * `token_ids` - Array of token ids
+    /// * `token_ids_len` - Length of token ids array
+    /// * `limit` - Maximum number of token balances to return
+    /// * `cursor` - Cursor to start from
+    ///
+    /// # Returns
+    /// Result containing array of TokenBalance information or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_token_contracts(
+        client: *mut ToriiClient,
+        query: TokenContractQuery,
+    ) -> Result<Page<TokenContract>> {
+        let query: torii_proto::TokenContractQuery = query.into();
+        let key = CacheKey::new("token_contracts", &query);
+        let token_contracts_future = (*client)
+            .inner
+            .exchange(key, |inner| async move { inner.token_contracts(query).await });
 
-        // Convert achievement_ids array to Vec<String> if not empty
-        let achievement_ids = if achievement_ids.is_null() || achievement_ids_len == 0 {
-            Vec::new()
-        } else {
-            let ids = unsafe { std::slice::from_raw_parts(achievement_ids, achievement_ids_len) };
-            ids.iter()
-                .map(|id| unsafe { CStr::from_ptr(*id).to_string_lossy().to_string() })
-                .collect::<Vec<String>>()
-        };
+        match block_on_request(&*client, token_contracts_future) {
+            Ok(contracts) => Result::Ok(contracts.into()),
+            Err(e) => Result::Err(e.into()),
+        }
+    }
 
-        match RUNTIME.block_on((*client).inner.update_achievement_progression_subscription(
-            (*subscription).id,
-            world_addresses,
-            namespaces,
-            player_addresses,
-            achievement_ids,
-        )) {
-            Ok(_) => Result::Ok(true),
+    /// Gets contracts matching the given query
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `query` - ContractQuery parameters
+    ///
+    /// # Returns
+    /// Result containing array of Contract information or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_contracts(
+        client: *mut ToriiClient,
+        query: ContractQuery,
+    ) -> Result<CArray<Contract>> {
+        let query: torii_proto::ContractQuery = query.into();
+        let key = CacheKey::new("contracts", &query);
+        let contracts_future =
+            (*client).inner.exchange(key, |inner| async move { inner.contracts(query).await });
+
+        match block_on_request(&*client, contracts_future) {
+            Ok(contracts) => Result::Ok(contracts.into()),
             Err(e) => Result::Err(e.into()),
         }
     }
 
-    /// Retrieves activities (user session tracking) matching query parameter
+    /// Retrieves token transfers matching the given query
     ///
     /// # Parameters
     /// * `client` - Pointer to ToriiClient instance
-    /// * `query` - ActivityQuery containing world_addresses, namespaces, caller_addresses, and
-    ///   pagination
+    /// * `query` - TokenTransferQuery parameters
     ///
     /// # Returns
-    /// Result containing Page of Activity or error
+    /// Result containing array of TokenTransfer information or error
     #[no_mangle]
-    pub unsafe extern "C" fn client_activities(
+    pub unsafe extern "C" fn client_token_transfers(
         client: *mut ToriiClient,
-        query: ActivityQuery,
-    ) -> Result<Page<Activity>> {
-        let query = query.into();
-        let activities_future = unsafe { (*client).inner.activities(query) };
+        query: TokenTransferQuery,
+    ) -> Result<Page<TokenTransfer>> {
+        let query: torii_proto::TokenTransferQuery = query.into();
+        let key = CacheKey::new("token_transfers", &query);
+        let token_transfers_future = (*client)
+            .inner
+            .exchange(key, |inner| async move { inner.token_transfers(query).await });
 
-        match RUNTIME.block_on(activities_future) {
-            Ok(activities) => Result::Ok(activities.into()),
+        match block_on_request(&*client, token_transfers_future) {
+            Ok(transfers) => Result::Ok(transfers.into()),
             Err(e) => Result::Err(e.into()),
         }
     }
 
-    /// Subscribes to activity updates (user session tracking)
+    /// Subscribes to contract updates
     ///
     /// # Parameters
     /// * `client` - Pointer to ToriiClient instance
-    /// * `world_addresses` - Array of world addresses to subscribe to
-    /// * `world_addresses_len` - Length of world_addresses array
-    /// * `namespaces` - Array of namespaces to subscribe to
-    /// * `namespaces_len` - Length of namespaces array
-    /// * `caller_addresses` - Array of caller addresses to subscribe to
-    /// * `caller_addresses_len` - Length of caller_addresses array
+    /// * `contract_address` - Optional contract address to filter updates
     /// * `callback` - Function called when updates occur
     ///
     /// # Returns
     /// Result containing pointer to Subscription or error
     #[no_mangle]
-    pub unsafe extern "C" fn client_on_activity_update(
+    pub unsafe extern "C" fn on_contract_update(
         client: *mut ToriiClient,
-        world_addresses: *const types::FieldElement,
-        world_addresses_len: usize,
-        namespaces: *const *const c_char,
-        namespaces_len: usize,
-        caller_addresses: *const types::FieldElement,
-        caller_addresses_len: usize,
-        callback: unsafe extern "C" fn(Activity),
+        contract_address: *const types::FieldElement,
+        callback: unsafe extern "C" fn(Contract),
     ) -> Result<*mut Subscription> {
-        let client = Arc::new(unsafe { &*client });
+        let contract_address = if contract_address.is_null() {
+            None
+        } else {
+            Some(unsafe { (*contract_address).clone().into() })
+        };
 
-        // Convert world addresses array to Vec<Felt> if not empty
-        let world_addresses = if world_addresses.is_null() || world_addresses_len == 0 {
+        let subscribed = subscribe(
+            (*client).inner.clone(),
+            contract_address,
+            |client: Arc<TClient>, contract_address| async move {
+                client.on_contract_updated(contract_address).await.map(without_id)
+            },
+            sleep,
+            move |contract: torii_proto::Contract| callback(contract.into()),
+        );
+        spawn_subscription(subscribed, "Failed to establish contract subscription")
+    }
+
+    /// Subscribes to token balance updates
+    ///
+    /// # Parameters
+    /// * `client` - Pointer to ToriiClient instance
+    /// * `contract_addresses` - Array of contract addresses to filter (empty for all)
+    /// * `contract_addresses_len` - Length of contract addresses array
+    /// * `account_addresses` - Array of account addresses to filter (empty for all)
+    /// * `account_addresses_len` - Length of account addresses array
+    /// * `callback` - Function called when updates occur
+    ///
+    /// # Returns
+    /// Result containing pointer to Subscription or error
+    #[no_mangle]
+    pub unsafe extern "C" fn client_on_token_balance_update(
+        client: *mut ToriiClient,
+        contract_addresses: *const types::FieldElement,
+        contract_addresses_len: usize,
+        account_addresses: *const types::FieldElement,
+        account_addresses_len: usize,
+        token_ids: *const types::U256,
+        token_ids_len: usize,
+        callback: unsafe extern "C" fn(TokenBalance),
+    ) -> Result<*mut Subscription> {
+        // Convert account addresses array to Vec<Felt> if not empty
+        let account_addresses = if account_addresses.is_null() || account_addresses_len == 0 {
             Vec::new()
         } else {
             let addresses =
-                unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
+                unsafe { std::slice::from_raw_parts(account_addresses, account_addresses_len) };
             addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
         };
 
-        // Convert namespaces array to Vec<String> if not empty
-        let namespaces = if namespaces.is_null() || namespaces_len == 0 {
+        // Convert contract addresses array to Vec<Felt> if not empty
+        let contract_addresses = if contract_addresses.is_null() || contract_addresses_len == 0 {
             Vec::new()
         } else {
-            let ns = unsafe { std::slice::from_raw_parts(namespaces, namespaces_len) };
-            ns.iter()
-                .map(|ns| unsafe { CStr::from_ptr(*ns).to_string_lossy().to_string() })
-                .collect::<Vec<String>>()
+            let addresses =
+                unsafe { std::slice::from_raw_parts(contract_addresses, contract_addresses_len) };
+            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
         };
 
-        // Convert caller addresses array to Vec<Felt> if not empty
-        let caller_addresses = if caller_addresses.is_null() || caller_addresses_len == 0 {
+        let token_ids = if token_ids.is_null() || token_ids_len == 0 {
             Vec::new()
         } else {
-            let addresses =
-                unsafe { std::slice::from_raw_parts(caller_addresses, caller_addresses_len) };
-            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
+            let ids = unsafe { std::slice::from_raw_parts(token_ids, token_ids_len) };
+            ids.iter().map(|f| f.clone().into()).collect::<Vec<U256>>()
         };
 
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-        let (trigger, tripwire) = Tripwire::new();
-
-        // Spawn a new thread to handle the stream and reconnections
-        let client_clone = client.clone();
-        RUNTIME.spawn(async move {
-            let mut backoff = Duration::from_secs(1);
-            let max_backoff = Duration::from_secs(60);
-
-            loop {
-                let rcv = client_clone
-                    .inner
-                    .on_activity_updated(
-                        world_addresses.clone(),
-                        namespaces.clone(),
-                        caller_addresses.clone(),
-                    )
-                    .await;
-
-                if let Ok(rcv) = rcv {
-                    backoff = Duration::from_secs(1); // Reset backoff on successful connection
-
-                    let mut rcv = rcv.take_until_if(tripwire.clone());
-
-                    while let Some(Ok((id, activity))) = rcv.next().await {
-                        // Our first message will be the subscription ID
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(id).expect("Failed to send subscription ID");
-                        } else {
-                            let activity: Activity = activity.into();
-                            callback(activity);
-                        }
-                    }
-                }
-
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
-                }
-                sleep(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
-            }
-        });
-
-        let subscription_id = match RUNTIME.block_on(sub_id_rx) {
-            Ok(id) => id,
-            Err(_) => {
-                return Result::Err(Error {
-                    message: CString::new("Failed to establish activity subscription")
-                        .unwrap()
-                        .into_raw(),
-                });
+        let filter = TokenBalanceFilter { contract_addresses, account_addresses, token_ids };
+        let connection = (*client).inner.clone();
+        let on_item = move |balance: torii_proto::TokenBalance| callback(balance.into());
+        if connection.config().multiplex && (*client).token_balances.shareable(&filter) {
+            let (on_item, delivery, deliver) = delivery::channel(None, sleep, on_item);
+            RUNTIME.spawn(deliver);
+            let (lease, task) =
+                (*client).token_balances.join(connection, sleep, filter, Box::new(on_item));
+            if let Some(task) = task {
+                RUNTIME.spawn(task);
             }
-        };
-
-        let subscription = Subscription { id: subscription_id, trigger };
+            return shared_subscription(
+                lease,
+                delivery,
+                "Failed to establish token balance subscription",
+            );
+        }
 
-        Result::Ok(Box::into_raw(Box::new(subscription)))
+        let on_item = invalidate_token_balances(&connection, on_item);
+        let subscribed = subscribe(
+            connection,
+            filter,
+            |client: Arc<TClient>, filter| async move {
+                let TokenBalanceFilter { contract_addresses, account_addresses, token_ids } =
+                    filter;
+                client
+                    .on_token_balance_updated(contract_addresses, account_addresses, token_ids)
+                    .await
+            },
+            sleep,
+            on_item,
+        );
+        spawn_subscription(subscribed, "Failed to establish token balance subscription")
     }
 
-    /// Updates an existing activity subscription with new parameters
+    /// Updates an existing token balance subscription
     ///
     /// # Parameters
     /// * `client` - Pointer to ToriiClient instance
     /// * `subscription` - Pointer to existing Subscription
-    /// * `world_addresses` - Array of world addresses to subscribe to
-    /// * `world_addresses_len` - Length of world_addresses array
-    /// * `namespaces` - Array of namespaces to subscribe to
-    /// * `namespaces_len` - Length of namespaces array
-    /// * `caller_addresses` - Array of caller addresses to subscribe to
-    /// * `caller_addresses_len` - Length of caller_addresses array
+    /// * `contract_addresses` - Array of contract addresses to filter (empty for all)
+    /// * `contract_addresses_len` - Length of contract addresses array
+    /// * `account_addresses` - Array of account addresses to filter (empty for all)
+    /// * `account_addresses_len` - Length of account addresses array
     ///
     /// # Returns
     /// Result containing success boolean or error
     #[no_mangle]
-    pub unsafe extern "C" fn client_update_activity_subscription(
+    pub unsafe extern "C" fn client_update_token_balance_subscription(
         client: *mut ToriiClient,
         subscription: *mut Subscription,
-        world_addresses: *const types::FieldElement,
-        world_addresses_len: usize,
-        namespaces: *const *const c_char,
-        namespaces_len: usize,
-        caller_addresses: *const types::FieldElement,
-        caller_addresses_len: usize,
+        contract_addresses: *const types::FieldElement,
+        contract_addresses_len: usize,
+        account_addresses: *const types::FieldElement,
+        account_addresses_len: usize,
+        token_ids: *const types::U256,
+        token_ids_len: usize,
     ) -> Result<bool> {
-        // Convert world addresses array to Vec<Felt> if not empty
-        let world_addresses = if world_addresses.is_null() || world_addresses_len == 0 {
+        let contract_addresses = if contract_addresses.is_null() || contract_addresses_len == 0 {
             Vec::new()
         } else {
             let addresses =
-                unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
+                unsafe { std::slice::from_raw_parts(contract_addresses, contract_addresses_len) };
             addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
         };
 
-        // Convert namespaces array to Vec<String> if not empty
-        let namespaces = if namespaces.is_null() || namespaces_len == 0 {
+        let account_addresses = if account_addresses.is_null() || account_addresses_len == 0 {
             Vec::new()
         } else {
-            let ns = unsafe { std::slice::from_raw_parts(namespaces, namespaces_len) };
-            ns.iter()
-                .map(|ns| unsafe { CStr::from_ptr(*ns).to_string_lossy().to_string() })
-                .collect::<Vec<String>>()
+            let addresses =
+                unsafe { std::slice::from_raw_parts(account_addresses, account_addresses_len) };
+            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
         };
 
-        // Convert caller addresses array to Vec<Felt> if not empty
-        let caller_addresses = if caller_addresses.is_null() || caller_addresses_len == 0 {
+        let token_ids = if token_ids.is_null() || token_ids_len == 0 {
             Vec::new()
         } else {
-            let addresses =
-                unsafe { std::slice::from_raw_parts(caller_addresses, caller_addresses_len) };
-            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
+            let ids = unsafe { std::slice::from_raw_parts(token_ids, token_ids_len) };
+            ids.iter().map(|f| f.clone().into()).collect::<Vec<U256>>()
         };
 
-        match RUNTIME.block_on((*client).inner.update_activity_subscription(
-            (*subscription).id,
-            world_addresses,
-            namespaces,
-            caller_addresses,
-        )) {
-            Ok(_) => Result::Ok(true),
-            Err(e) => Result::Err(e.into()),
+        let filter = TokenBalanceFilter { contract_addresses, account_addresses, token_ids };
+        if let SubscriptionStream::Shared { id, .. } = (*subscription).stream {
+            return update_shared(&(*client).token_balances, id, filter);
         }
+        let connection = &(*client).inner;
+        update_own(&*client, &*subscription, filter, |id, filter| {
+            let TokenBalanceFilter { contract_addresses, account_addresses, token_ids } = filter;
+            let key = CacheKey::new(
+                "update_token_balance_subscription",
+                &(id, &contract_addresses, &account_addresses, &token_ids),
+            );
+            connection.exchange(key, |inner| async move {
+                inner
+                    .update_token_balance_subscription(
+                        id,
+                        contract_addresses,
+                        account_addresses,
+                        token_ids,
+                    )
+                    .await
+            })
+        })
     }
 
-    /// Subscribes to event message updates
+    /// Subscribes to token transfer updates
     ///
     /// # Parameters
     /// * `client` - Pointer to ToriiClient instance
-    /// * `clauses` - Array of entity key clauses to filter updates
-    /// * `clauses_len` - Length of clauses array
+    /// * `contract_addresses` - Array of contract addresses to filter (empty for all)
+    /// * `contract_addresses_len` - Length of contract addresses array
+    /// * `account_addresses` - Array of account addresses to filter (empty for all)
+    /// * `account_addresses_len` - Length of account addresses array
+    /// * `token_ids` - Array of token IDs to filter (empty for all)
+    /// * `token_ids_len` - Length of token IDs array
     /// * `callback` - Function called when updates occur
     ///
     /// # Returns
     /// Result containing pointer to Subscription or error
     #[no_mangle]
-    pub unsafe extern "C" fn client_on_event_message_update(
+    pub unsafe extern "C" fn client_on_token_transfer_update(
         client: *mut ToriiClient,
-        clause: COption<Clause>,
-        world_addresses: *const types::FieldElement,
-        world_addresses_len: usize,
-        callback: unsafe extern "C" fn(Entity),
+        contract_addresses: *const types::FieldElement,
+        contract_addresses_len: usize,
+        account_addresses: *const types::FieldElement,
+        account_addresses_len: usize,
+        token_ids: *const types::U256,
+        token_ids_len: usize,
+        callback: unsafe extern "C" fn(TokenTransfer),
     ) -> Result<*mut Subscription> {
-        let client = Arc::new(unsafe { &*client });
-        let clause: Option<torii_proto::Clause> = clause.map(|c| c.into()).into();
-        let world_addresses =
-            unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
-        let world_addresses =
-            world_addresses.iter().map(|addr| addr.clone().into()).collect::<Vec<Felt>>();
-        let (trigger, tripwire) = Tripwire::new();
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-
-        // Spawn a new thread to handle the stream and reconnections
-        let client_clone = client.clone();
-        RUNTIME.spawn(async move {
-            let mut backoff = Duration::from_secs(1);
-            let max_backoff = Duration::from_secs(60);
-
-            loop {
-                let rcv = client_clone
-                    .inner
-                    .on_event_message_updated(clause.clone(), world_addresses.clone())
-                    .await;
-                if let Ok(rcv) = rcv {
-                    backoff = Duration::from_secs(1); // Reset backoff on successful connection
-
-                    let mut rcv = rcv.take_until_if(tripwire.clone());
-
-                    while let Some(Ok((id, entity))) = rcv.next().await {
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(id).expect("Failed to send subscription ID");
-                        } else {
-                            callback(entity.into());
-                        }
-                    }
-                }
+        // Convert account addresses array to Vec<Felt> if not empty
+        let account_addresses = if account_addresses.is_null() || account_addresses_len == 0 {
+            Vec::new()
+        } else {
+            let addresses =
+                unsafe { std::slice::from_raw_parts(account_addresses, account_addresses_len) };
+            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
+        };
 
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
-                }
-                sleep(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
-            }
-        });
+        // Convert contract addresses array to Vec<Felt> if not empty
+        let contract_addresses = if contract_addresses.is_null() || contract_addresses_len == 0 {
+            Vec::new()
+        } else {
+            let addresses =
+                unsafe { std::slice::from_raw_parts(contract_addresses, contract_addresses_len) };
+            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
+        };
 
-        let subscription_id = match RUNTIME.block_on(sub_id_rx) {
-            Ok(id) => id,
-            Err(_) => {
-                return Result::Err(Error {
-                    message: CString::new("Failed to establish event message subscription")
-                        .unwrap()
-                        .into_raw(),
-                });
-            }
+        let token_ids = if token_ids.is_null() || token_ids_len == 0 {
+            Vec::new()
+        } else {
+            let ids = unsafe { std::slice::from_raw_parts(token_ids, token_ids_len) };
+            ids.iter().map(|f| f.clone().into()).collect::<Vec<U256>>()
         };
 
-        let subscription = Subscription { id: subscription_id, trigger };
-        Result::Ok(Box::into_raw(Box::new(subscription)))
+        let subscribed = subscribe(
+            (*client).inner.clone(),
+            (contract_addresses, account_addresses, token_ids),
+            |client: Arc<TClient>, (contract_addresses, account_addresses, token_ids)| async move {
+                client
+                    .on_token_transfer_updated(contract_addresses, account_addresses, token_ids)
+                    .await
+                    .map(without_id)
+            },
+            sleep,
+            move |transfer: torii_proto::TokenTransfer| callback(transfer.into()),
+        );
+        spawn_subscription(subscribed, "Failed to establish token transfer subscription")
     }
 
-    /// Updates an existing event message subscription
+    /// Updates an existing token transfer subscription
     ///
     /// # Parameters
     /// * `client` - Pointer to ToriiClient instance
     /// * `subscription` - Pointer to existing Subscription
-    /// * `clauses` - New array of entity key clauses
-    /// * `clauses_len` - Length of new clauses array
+    /// * `contract_addresses` - Array of contract addresses to filter (empty for all)
+    /// * `contract_addresses_len` - Length of contract addresses array
+    /// * `account_addresses` - Array of account addresses to filter (empty for all)
+    /// * `account_addresses_len` - Length of account addresses array
+    /// * `token_ids` - Array of token IDs to filter (empty for all)
+    /// * `token_ids_len` - Length of token IDs array
     ///
     /// # Returns
     /// Result containing success boolean or error
     #[no_mangle]
-    pub unsafe extern "C" fn client_update_event_message_subscription(
+    pub unsafe extern "C" fn client_update_token_transfer_subscription(
         client: *mut ToriiClient,
         subscription: *mut Subscription,
-        clause: COption<Clause>,
-        world_addresses: *const types::FieldElement,
-        world_addresses_len: usize,
+        contract_addresses: *const types::FieldElement,
+        contract_addresses_len: usize,
+        account_addresses: *const types::FieldElement,
+        account_addresses_len: usize,
+        token_ids: *const types::U256,
+        token_ids_len: usize,
     ) -> Result<bool> {
-        let clause: Option<torii_proto::Clause> = clause.map(|c| c.into()).into();
-        let world_addresses =
-            unsafe { std::slice::from_raw_parts(world_addresses, world_addresses_len) };
-        let world_addresses = world_addresses.iter().map(|addr| addr.clone().into()).collect();
-        match RUNTIME.block_on((*client).inner.update_event_message_subscription(
-            (*subscription).id,
-            clause,
-            world_addresses,
-        )) {
-            Ok(_) => Result::Ok(true),
-            Err(e) => Result::Err(e.into()),
-        }
-    }
-
-    /// Subscribes to Starknet events
-    ///
-    /// # Parameters
-    /// * `client` - Pointer to ToriiClient instance
-    /// * `clauses` - Array of entity key clauses to filter events
-    /// * `clauses_len` - Length of clauses array
-    /// * `callback` - Function called when events occur
-    ///
-    /// # Returns
-    /// Result containing pointer to Subscription or error
-    #[no_mangle]
-    pub unsafe extern "C" fn client_on_starknet_event(
-        client: *mut ToriiClient,
-        clauses: *const KeysClause,
-        clauses_len: usize,
-        callback: unsafe extern "C" fn(Event),
-    ) -> Result<*mut Subscription> {
-        let client = Arc::new(unsafe { &*client });
-        let clauses = if clauses.is_null() || clauses_len == 0 {
+        let contract_addresses = if contract_addresses.is_null() || contract_addresses_len == 0 {
             Vec::new()
         } else {
-            let clauses = unsafe { std::slice::from_raw_parts(clauses, clauses_len) };
-            clauses.iter().map(|c| c.clone().into()).collect::<Vec<_>>()
+            let addresses =
+                unsafe { std::slice::from_raw_parts(contract_addresses, contract_addresses_len) };
+            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
         };
 
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-        let (trigger, tripwire) = Tripwire::new();
-
-        // Spawn a new thread to handle the stream and reconnections
-        let client_clone = client.clone();
-        RUNTIME.spawn(async move {
-            let mut backoff = Duration::from_secs(1);
-            let max_backoff = Duration::from_secs(60);
-
-            loop {
-                let rcv = client_clone.inner.on_starknet_event(clauses.clone()).await;
-
-                if let Ok(rcv) = rcv {
-                    backoff = Duration::from_secs(1); // Reset backoff on successful connection
-
-                    let mut rcv = rcv.take_until_if(tripwire.clone());
-
-                    while let Some(Ok(event)) = rcv.next().await {
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(0).expect("Failed to send subscription ID");
-                        } else {
-                            let event: Event = event.into();
-                            callback(event);
-                        }
-                    }
-                }
-
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
-                }
-                sleep(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
-            }
-        });
-
-        let subscription_id = match RUNTIME.block_on(sub_id_rx) {
-            Ok(id) => id,
-            Err(_) => {
-                return Result::Err(Error {
-                    message: CString::new("Failed to establish event subscription")
-                        .unwrap()
-                        .into_raw(),
-                });
-            }
+        let account_addresses = if account_addresses.is_null() || account_addresses_len == 0 {
+            Vec::new()
+        } else {
+            let addresses =
+                unsafe { std::slice::from_raw_parts(account_addresses, account_addresses_len) };
+            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
         };
 
-        let subscription = Subscription { id: subscription_id, trigger };
+        let token_ids = if token_ids.is_null() || token_ids_len == 0 {
+            Vec::new()
+        } else {
+            let ids = unsafe { std::slice::from_raw_parts(token_ids, token_ids_len) };
+            ids.iter().map(|f| f.clone().into()).collect::<Vec<U256>>()
+        };
 
-        Result::Ok(Box::into_raw(Box::new(subscription)))
+        let connection = &(*client).inner;
+        let query = (contract_addresses, account_addresses, token_ids);
+        update_own(&*client, &*subscription, query, |id, query| {
+            let (contract_addresses, account_addresses, token_ids) = query;
+            let key = CacheKey::new(
+                "update_token_transfer_subscription",
+                &(id, &contract_addresses, &account_addresses, &token_ids),
+            );
+            connection.exchange(key, |inner| async move {
+                inner
+                    .update_token_transfer_subscription(
+                        id,
+                        contract_addresses,
+                        account_addresses,
+                        token_ids,
+                    )
+                    .await
+            })
+        })
     }
 
-    /// Retrieves token information for given contract addresses
+    /// Performs a full-text search across indexed entities using FTS5
     ///
     /// # Parameters
     /// * `client` - Pointer to ToriiClient instance
-    /// * `contract_addresses` - Array of contract addresses
-    /// * `contract_addresses_len` - Length of addresses array
-    /// * `token_ids` - Array of token ids
-    /// * `token_ids_len` - Length of token ids array
-    /// * `limit` - Maximum number of tokens to return
-    /// * `cursor` - Cursor to start from
+    /// * `query` - Search query containing the search text and limit
     ///
     /// # Returns
-    /// Result containing array of Token information or error
+    /// Result containing SearchResponse with results grouped by table or error
     #[no_mangle]
-    pub unsafe extern "C" fn client_tokens(
+    pub unsafe extern "C" fn client_search(
         client: *mut ToriiClient,
-        query: TokenQuery,
-    ) -> Result<Page<Token>> {
-        let query = query.into();
-        let tokens_future = unsafe { (*client).inner.tokens(query) };
+        query: types::SearchQuery,
+    ) -> Result<types::SearchResponse> {
+        let query: torii_proto::SearchQuery = query.into();
+        let key = CacheKey::new("search", &query);
+        let search_future =
+            (*client).inner.exchange(key, |inner| async move { inner.search(query).await });
 
-        match RUNTIME.block_on(tokens_future) {
-            Ok(tokens) => Result::Ok(tokens.into()),
+        match block_on_request(&*client, search_future) {
+            Ok(response) => Result::Ok(response.into()),
             Err(e) => Result::Err(e.into()),
         }
     }
 
-    /// Subscribes to token updates
+    /// Serializes a string into a byte array
     ///
     /// # Parameters
-    /// * `client` - Pointer to ToriiClient instance
-    /// * `contract_addresses` - Array of contract addresses
-    /// * `callback` - Function called when updates occur
+    /// * `str` - String to serialize
     ///
     /// # Returns
-    /// Result containing pointer to Subscription or error
+    /// Result containing array of FieldElements or error
     #[no_mangle]
-    pub unsafe extern "C" fn client_on_token_update(
-        client: *mut ToriiClient,
-        contract_addresses: *const types::FieldElement,
-        contract_addresses_len: usize,
-        token_ids: *const types::U256,
-        token_ids_len: usize,
-        callback: unsafe extern "C" fn(Token),
-    ) -> Result<*mut Subscription> {
-        let client = Arc::new(unsafe { &*client });
-
-        // Convert contract addresses array to Vec<Felt> if not empty
-        let contract_addresses = if contract_addresses.is_null() || contract_addresses_len == 0 {
-            Vec::new()
-        } else {
-            let addresses =
-                unsafe { std::slice::from_raw_parts(contract_addresses, contract_addresses_len) };
-            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
-        };
-
-        let token_ids = if token_ids.is_null() || token_ids_len == 0 {
-            Vec::new()
-        } else {
-            let ids = unsafe { std::slice::from_raw_parts(token_ids, token_ids_len) };
-            ids.iter().map(|f| f.clone().into()).collect::<Vec<U256>>()
+    pub unsafe extern "C" fn bytearray_serialize(
+        str: *const c_char,
+    ) -> Result<CArray<types::FieldElement>> {
+        let str = unsafe { CStr::from_ptr(str).to_string_lossy().into_owned() };
+        let bytearray = match ByteArray::from_string(str.as_str()) {
+            Ok(bytearray) => bytearray,
+            Err(e) => return Result::Err(e.into()),
         };
 
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-        let (trigger, tripwire) = Tripwire::new();
-
-        // Spawn a new thread to handle the stream and reconnections
-        let client_clone = client.clone();
-        RUNTIME.spawn(async move {
-            let mut backoff = Duration::from_secs(1);
-            let max_backoff = Duration::from_secs(60);
-
-            loop {
-                let rcv = client_clone
-                    .inner
-                    .on_token_updated(contract_addresses.clone(), token_ids.clone())
-                    .await;
-
-                if let Ok(rcv) = rcv {
-                    backoff = Duration::from_secs(1); // Reset backoff on successful connection
-
-                    let mut rcv = rcv.take_until_if(tripwire.clone());
-
-                    while let Some(Ok((id, token))) = rcv.next().await {
-                        // Our first message will be the subscription ID
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(id).expect("Failed to send subscription ID");
-                        } else {
-                            let token: Token = token.into();
-                            callback(token);
-                        }
-                    }
-                }
-
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
-                }
-                sleep(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
-            }
-        });
+        let felts = cairo_serde::ByteArray::cairo_serialize(&bytearray);
+        let felts = felts.into_iter().map(|f| f.into()).collect::<Vec<types::FieldElement>>();
+        Result::Ok(felts.into())
+    }
 
-        let subscription_id = match RUNTIME.block_on(sub_id_rx) {
-            Ok(id) => id,
-            Err(_) => {
-                return Result::Err(Error {
-                    message: CString::new("Failed to establish token subscription")
-                        .unwrap()
-                        .into_raw(),
-                });
-            }
+    /// Deserializes field elements into a string
+    ///
+    /// # Parameters
+    /// * `felts` - Array of field elements
+    /// * `felts_len` - Length of field elements array
+    ///
+    /// # Returns
+    /// Result containing pointer to C string or error
+    #[no_mangle]
+    pub unsafe extern "C" fn bytearray_deserialize(
+        felts: *const types::FieldElement,
+        felts_len: usize,
+    ) -> Result<*const c_char> {
+        let felts = unsafe { std::slice::from_raw_parts(felts, felts_len) };
+        let felts = felts.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>();
+        let bytearray = match cairo_serde::ByteArray::cairo_deserialize(&felts, 0) {
+            Ok(bytearray) => bytearray,
+            Err(e) => return Result::Err(e.into()),
         };
 
-        let subscription = Subscription { id: subscription_id, trigger };
+        let bytearray = match bytearray.to_string() {
+            Ok(bytearray) => bytearray,
+            Err(e) => return Result::Err(e.into()),
+        };
 
-        Result::Ok(Box::into_raw(Box::new(subscription)))
+        Result::Ok(c_string(bytearray))
     }
 
-    /// Gets token balances for given accounts and contracts
+    /// Computes Poseidon hash of field elements
     ///
     /// # Parameters
-    /// * `client` - Pointer to ToriiClient instance
-    /// * `contract_addresses` - Array of contract addresses
-    /// * `contract_addresses_len` - Length of contract addresses array
-    /// * `account_addresses` - Array of account addresses
-    /// * `account_addresses_len` - Length of account addresses array
-    /// * `token_ids` - Array of token ids
-    /// * `token_ids_len` - Length of token ids array
-    /// * `limit` - Maximum number of token balances to return
-    /// * `cursor` - Cursor to start from
+    /// * `felts` - Array of field elements
+    /// * `felts_len` - Length of array
     ///
     /// # Returns
-    /// Result containing array of TokenBalance information or error
+    /// FieldElement containing the hash result
     #[no_mangle]
-    pub unsafe extern "C" fn client_token_balances(
-        client: *mut ToriiClient,
-        query: TokenBalanceQuery,
-    ) -> Result<Page<TokenBalance>> {
-        let query = query.into();
-        let token_balances_future = unsafe { (*client).inner.token_balances(query) };
+    pub unsafe extern "C" fn poseidon_hash(
+        felts: *const types::FieldElement,
+        felts_len: usize,
+    ) -> types::FieldElement {
+        let felts = unsafe { std::slice::from_raw_parts(felts, felts_len) };
+        let felts = felts.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>();
 
-        match RUNTIME.block_on(token_balances_future) {
-            Ok(token_balances) => Result::Ok(token_balances.into()),
-            Err(e) => Result::Err(e.into()),
-        }
+        poseidon_hash_many(&felts).into()
     }
 
-    /// Gets token collections for given accounts and contracts
+    /// Gets selector from name string
     ///
     /// # Parameters
-    /// * `client` - Pointer to ToriiClient instance
-    /// * `contract_addresses` - Array of contract addresses
-    /// * `contract_addresses_len` - Length of contract addresses array
-    /// * `account_addresses` - Array of account addresses
-    /// * `account_addresses_len` - Length of account addresses array
-    /// * `token_ids` - Array of token ids
-    /// * `token_ids_len` - Length of token ids array
-    /// * `limit` - Maximum number of token balances to return
-    /// * `cursor` - Cursor to start from
+    /// * `name` - Name to compute selector from
     ///
     /// # Returns
-    /// Result containing array of TokenBalance information or error
+    /// Result containing FieldElement selector or error
     #[no_mangle]
-    pub unsafe extern "C" fn client_token_contracts(
-        client: *mut ToriiClient,
-        query: TokenContractQuery,
-    ) -> Result<Page<TokenContract>> {
-        let query = query.into();
-        let token_contracts_future = unsafe { (*client).inner.token_contracts(query) };
+    pub unsafe extern "C" fn get_selector_from_name(
+        name: *const c_char,
+    ) -> Result<types::FieldElement> {
+        let name = unsafe { CStr::from_ptr(name).to_string_lossy().into_owned() };
+        let selector = match starknet::core::utils::get_selector_from_name(name.as_str()) {
+            Ok(selector) => selector,
+            Err(e) => return Result::Err(e.into()),
+        };
 
-        match RUNTIME.block_on(token_contracts_future) {
-            Ok(contracts) => Result::Ok(contracts.into()),
-            Err(e) => Result::Err(e.into()),
-        }
+        Result::Ok(selector.into())
     }
 
-    /// Gets contracts matching the given query
+    /// Gets selector from tag string
     ///
     /// # Parameters
-    /// * `client` - Pointer to ToriiClient instance
-    /// * `query` - ContractQuery parameters
+    /// * `tag` - Tag to compute selector from
     ///
     /// # Returns
-    /// Result containing array of Contract information or error
+    /// FieldElement containing the computed selector
     #[no_mangle]
-    pub unsafe extern "C" fn client_contracts(
-        client: *mut ToriiClient,
-        query: ContractQuery,
-    ) -> Result<CArray<Contract>> {
-        let query: torii_proto::ContractQuery = query.into();
-        let contracts_future = unsafe { (*client).inner.contracts(query) };
+    pub unsafe extern "C" fn get_selector_from_tag(tag: *const c_char) -> types::FieldElement {
+        let tag = unsafe { CStr::from_ptr(tag).to_string_lossy().into_owned() };
+        let selector = compute_selector_from_tag(tag.as_str());
 
-        match RUNTIME.block_on(contracts_future) {
-            Ok(contracts) => Result::Ok(contracts.into()),
-            Err(e) => Result::Err(e.into()),
-        }
+        selector.into()
     }
 
-    /// Retrieves token transfers matching the given query
+    /// Creates a ModelDispatcher, routing entity updates to handlers registered by model
+    ///
+    /// # Returns
+    /// Pointer to the new ModelDispatcher
+    #[no_mangle]
+    pub unsafe extern "C" fn model_dispatcher_new() -> *mut ModelDispatcher {
+        Box::into_raw(Box::new(ModelDispatcher(Dispatcher::new())))
+    }
+
+    /// Registers a handler called with the hashed keys of the entity and the model whenever
+    /// the given model of an entity is updated
     ///
     /// # Parameters
-    /// * `client` - Pointer to ToriiClient instance
-    /// * `query` - TokenTransferQuery parameters
+    /// * `dispatcher` - Pointer to ModelDispatcher instance
+    /// * `model` - Tag of the model, e.g. `ns-Position`
+    /// * `keys` - Optional clause the model keys have to match
+    /// * `callback` - Function called with matching models
     ///
     /// # Returns
-    /// Result containing array of TokenTransfer information or error
+    /// Id of the handler, to remove it
     #[no_mangle]
-    pub unsafe extern "C" fn client_token_transfers(
-        client: *mut ToriiClient,
-        query: TokenTransferQuery,
-    ) -> Result<Page<TokenTransfer>> {
-        let query = query.into();
-        let token_transfers_future = unsafe { (*client).inner.token_transfers(query) };
-
-        match RUNTIME.block_on(token_transfers_future) {
-            Ok(transfers) => Result::Ok(transfers.into()),
-            Err(e) => Result::Err(e.into()),
-        }
+    pub unsafe extern "C" fn model_dispatcher_add(
+        dispatcher: *mut ModelDispatcher,
+        model: *const c_char,
+        keys: COption<KeysClause>,
+        callback: unsafe extern "C" fn(types::FieldElement, Struct),
+    ) -> u64 {
+        let model = unsafe { CStr::from_ptr(model).to_string_lossy().into_owned() };
+        let keys: Option<torii_proto::KeysClause> = keys.map(|k| k.into()).into();
+
+        unsafe { (*dispatcher).0.register(model, keys, callback) }
     }
 
-    /// Subscribes to contract updates
+    /// Removes a handler from a ModelDispatcher
     ///
     /// # Parameters
-    /// * `client` - Pointer to ToriiClient instance
-    /// * `contract_address` - Optional contract address to filter updates
-    /// * `callback` - Function called when updates occur
+    /// * `dispatcher` - Pointer to ModelDispatcher instance
+    /// * `handler_id` - Id returned by model_dispatcher_add
     ///
     /// # Returns
-    /// Result containing pointer to Subscription or error
+    /// false if there was no handler with this id
     #[no_mangle]
-    pub unsafe extern "C" fn on_contract_update(
-        client: *mut ToriiClient,
-        contract_address: *const types::FieldElement,
-        callback: unsafe extern "C" fn(Contract),
-    ) -> Result<*mut Subscription> {
-        let client = Arc::new(unsafe { &*client });
-        let contract_address = if contract_address.is_null() {
-            None
-        } else {
-            Some(unsafe { (*contract_address).clone().into() })
-        };
+    pub unsafe extern "C" fn model_dispatcher_remove(
+        dispatcher: *mut ModelDispatcher,
+        handler_id: u64,
+    ) -> bool {
+        unsafe { (*dispatcher).0.unregister(handler_id) }
+    }
 
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-        let (trigger, tripwire) = Tripwire::new();
+    /// Creates an OptimisticStore, applying predicted model changes to entity updates until
+    /// their transaction settles
+    ///
+    /// # Parameters
+    /// * `callback` - Function called with entities whose state changed because of a
+    ///   prediction, or because a prediction was rolled back or reconciled
+    ///
+    /// # Returns
+    /// Pointer to the new OptimisticStore
+    #[no_mangle]
+    pub unsafe extern "C" fn optimistic_store_new(
+        callback: unsafe extern "C" fn(Entity),
+    ) -> *mut OptimisticStore {
+        Box::into_raw(Box::new(OptimisticStore { inner: Optimistic::new(), callback }))
+    }
 
-        // Spawn a new thread to handle the stream and reconnections
-        let client_clone = client.clone();
-        RUNTIME.spawn(async move {
-            let mut backoff = Duration::from_secs(1);
-            let max_backoff = Duration::from_secs(60);
+    /// Predicts that a transaction sets the models of an entity. The models are applied to
+    /// the entity until Torii reports them, or the transaction succeeded and the entity was
+    /// updated, or the transaction reverts
+    ///
+    /// # Parameters
+    /// * `store` - Pointer to OptimisticStore instance
+    /// * `transaction_hash` - Hash of the transaction, as returned by account_execute_raw
+    /// * `entity` - Pointer to the entity holding the predicted models
+    #[no_mangle]
+    pub unsafe extern "C" fn optimistic_store_predict(
+        store: *mut OptimisticStore,
+        transaction_hash: types::FieldElement,
+        entity: *const Entity,
+    ) {
+        let entity: torii_proto::schema::Entity = unsafe { (*entity).clone() }.into();
+        let view = unsafe { (*store).inner.predict(transaction_hash.into(), entity) };
 
-            loop {
-                let rcv = client_clone.inner.on_contract_updated(contract_address).await;
-                if let Ok(rcv) = rcv {
-                    backoff = Duration::from_secs(1); // Reset backoff on successful connection
+        unsafe { ((*store).callback)(view.into()) }
+    }
 
-                    let mut rcv = rcv.take_until_if(tripwire.clone());
+    /// Records the outcome of a transaction, for transactions not watched with
+    /// optimistic_store_watch. Predictions of a reverted transaction are rolled back
+    ///
+    /// # Parameters
+    /// * `store` - Pointer to OptimisticStore instance
+    /// * `transaction_hash` - Hash of the transaction
+    /// * `succeeded` - Whether the transaction succeeded
+    #[no_mangle]
+    pub unsafe extern "C" fn optimistic_store_settle(
+        store: *mut OptimisticStore,
+        transaction_hash: types::FieldElement,
+        succeeded: bool,
+    ) {
+        let callback = unsafe { (*store).callback };
+        for view in unsafe { (*store).inner.settle(transaction_hash.into(), succeeded) } {
+            callback(view.into());
+        }
+    }
 
-                    while let Some(Ok(update)) = rcv.next().await {
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(0).expect("Failed to send subscription ID");
-                        } else {
-                            callback(update.into());
-                        }
-                    }
-                }
+    /// Waits in the background for the receipt of a transaction and settles its predictions.
+    /// Predictions are kept pending if the receipt can't be fetched, to be settled with
+    /// optimistic_store_settle
+    ///
+    /// # Parameters
+    /// * `store` - Pointer to OptimisticStore instance
+    /// * `provider` - Pointer to Provider
+    /// * `transaction_hash` - Hash of the transaction
+    #[no_mangle]
+    pub unsafe extern "C" fn optimistic_store_watch(
+        store: *mut OptimisticStore,
+        provider: *mut Provider,
+        transaction_hash: types::FieldElement,
+    ) {
+        let (optimistic, callback) = unsafe { ((*store).inner.clone(), (*store).callback) };
+        let provider = unsafe { (*provider).0.clone() };
+        let transaction_hash = transaction_hash.into();
 
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
-                }
-                sleep(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
+        RUNTIME.spawn(async move {
+            // An unreachable provider says nothing about the transaction
+            let Ok(succeeded) = watch_tx_result(provider, transaction_hash, sleep).await else {
+                return;
+            };
+            for view in optimistic.settle(transaction_hash, succeeded) {
+                callback(view.into());
             }
         });
-
-        let subscription_id = match RUNTIME.block_on(sub_id_rx) {
-            Ok(id) => id,
-            Err(_) => {
-                return Result::Err(Error {
-                    message: CString::new("Failed to establish contract subscription")
-                        .unwrap()
-                        .into_raw(),
-                });
-            }
-        };
-
-        let subscription = Subscription { id: subscription_id, trigger };
-
-        Result::Ok(Box::into_raw(Box::new(subscription)))
     }
 
-    /// Subscribes to token balance updates
+    /// Checks whether an entity update reports the deletion of the entity, i.e. it has no
+    /// models
     ///
     /// # Parameters
-    /// * `client` - Pointer to ToriiClient instance
-    /// * `contract_addresses` - Array of contract addresses to filter (empty for all)
-    /// * `contract_addresses_len` - Length of contract addresses array
-    /// * `account_addresses` - Array of account addresses to filter (empty for all)
-    /// * `account_addresses_len` - Length of account addresses array
-    /// * `callback` - Function called when updates occur
+    /// * `entity` - Pointer to the entity received from a subscription
     ///
     /// # Returns
-    /// Result containing pointer to Subscription or error
+    /// true if the entity was deleted
     #[no_mangle]
-    pub unsafe extern "C" fn client_on_token_balance_update(
-        client: *mut ToriiClient,
-        contract_addresses: *const types::FieldElement,
-        contract_addresses_len: usize,
-        account_addresses: *const types::FieldElement,
-        account_addresses_len: usize,
-        token_ids: *const types::U256,
-        token_ids_len: usize,
-        callback: unsafe extern "C" fn(TokenBalance),
-    ) -> Result<*mut Subscription> {
-        let client = Arc::new(unsafe { &*client });
-
-        // Convert account addresses array to Vec<Felt> if not empty
-        let account_addresses = if account_addresses.is_null() || account_addresses_len == 0 {
-            Vec::new()
-        } else {
-            let addresses =
-                unsafe { std::slice::from_raw_parts(account_addresses, account_addresses_len) };
-            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
-        };
-
-        // Convert contract addresses array to Vec<Felt> if not empty
-        let contract_addresses = if contract_addresses.is_null() || contract_addresses_len == 0 {
-            Vec::new()
-        } else {
-            let addresses =
-                unsafe { std::slice::from_raw_parts(contract_addresses, contract_addresses_len) };
-            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
-        };
-
-        let token_ids = if token_ids.is_null() || token_ids_len == 0 {
-            Vec::new()
-        } else {
-            let ids = unsafe { std::slice::from_raw_parts(token_ids, token_ids_len) };
-            ids.iter().map(|f| f.clone().into()).collect::<Vec<U256>>()
-        };
+    pub unsafe extern "C" fn entity_is_deleted(entity: *const Entity) -> bool {
+        let entity: torii_proto::schema::Entity = unsafe { (*entity).clone() }.into();
 
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-        let (trigger, tripwire) = Tripwire::new();
-
-        // Spawn a new thread to handle the stream and reconnections
-        let client_clone = client.clone();
-        RUNTIME.spawn(async move {
-            let mut backoff = Duration::from_secs(1);
-            let max_backoff = Duration::from_secs(60);
-
-            loop {
-                let rcv = client_clone
-                    .inner
-                    .on_token_balance_updated(
-                        contract_addresses.clone(),
-                        account_addresses.clone(),
-                        token_ids.clone(),
-                    )
-                    .await;
+        is_deleted(&entity)
+    }
 
-                if let Ok(rcv) = rcv {
-                    backoff = Duration::from_secs(1); // Reset backoff on successful connection
+    /// Checks whether an entity matches a clause, with the same semantics as Torii's queries
+    ///
+    /// # Parameters
+    /// * `entity` - Pointer to the entity
+    /// * `clause` - Clause to evaluate
+    ///
+    /// # Returns
+    /// true if Torii would return the entity for the clause
+    #[no_mangle]
+    pub unsafe extern "C" fn entity_matches_clause(entity: *const Entity, clause: Clause) -> bool {
+        let entity: torii_proto::schema::Entity = unsafe { (*entity).clone() }.into();
 
-                    let mut rcv = rcv.take_until_if(tripwire.clone());
+        dojo_core::clause::matches(&clause.into(), &entity)
+    }
 
-                    while let Some(Ok((id, balance))) = rcv.next().await {
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(id).expect("Failed to send subscription ID");
-                        } else {
-                            let balance: TokenBalance = balance.into();
-                            callback(balance);
-                        }
-                    }
-                }
+    /// Gets the type and value at a path within a type, e.g. a member of a model. Models of
+    /// entities are accessed by wrapping them in a Ty of the Struct_ variant
+    ///
+    /// Paths are made of struct members and tuple indices separated by dots, enum variants
+    /// following `::` and array indices in brackets, e.g. `vec.x`, `dir::Left`, `pair.1` or
+    /// `items[2].x`. A variant only resolves when it is the selected one
+    ///
+    /// # Parameters
+    /// * `ty` - Pointer to the type, e.g. a model
+    /// * `path` - Path as C string, e.g. `vec.x`
+    ///
+    /// # Returns
+    /// Result containing the type at the path, or an error naming the part of the path that
+    /// doesn't resolve
+    #[no_mangle]
+    pub unsafe extern "C" fn ty_get(ty: *const Ty, path: *const c_char) -> Result<Ty> {
+        match unsafe { access(ty, path, |ty, path| dojo_core::access::get(ty, path).cloned()) } {
+            Ok(value) => Result::Ok(value.into()),
+            Err(e) => Result::Err(e),
+        }
+    }
 
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
-                }
-                sleep(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
-            }
-        });
+    /// Gets the u32 at a path within a type, see ty_get
+    ///
+    /// # Parameters
+    /// * `ty` - Pointer to the type, e.g. a model
+    /// * `path` - Path as C string, e.g. `vec.x`
+    ///
+    /// # Returns
+    /// Result containing the value, or an error if the path doesn't resolve to a u32 with a value
+    #[no_mangle]
+    pub unsafe extern "C" fn ty_get_u32(ty: *const Ty, path: *const c_char) -> Result<u32> {
+        match unsafe { access(ty, path, dojo_core::access::get_u32) } {
+            Ok(value) => Result::Ok(value),
+            Err(e) => Result::Err(e),
+        }
+    }
 
-        let subscription_id = match RUNTIME.block_on(sub_id_rx) {
-            Ok(id) => id,
-            Err(_) => {
-                return Result::Err(Error {
-                    message: CString::new("Failed to establish token balance subscription")
-                        .unwrap()
-                        .into_raw(),
-                });
-            }
-        };
+    /// Gets the felt252, ContractAddress, ClassHash or EthAddress at a path within a type, see
+    /// ty_get
+    ///
+    /// # Parameters
+    /// * `ty` - Pointer to the type, e.g. a model
+    /// * `path` - Path as C string, e.g. `vec.x`
+    ///
+    /// # Returns
+    /// Result containing the felt, or an error if the path doesn't resolve to one with a value
+    #[no_mangle]
+    pub unsafe extern "C" fn ty_get_felt(
+        ty: *const Ty,
+        path: *const c_char,
+    ) -> Result<types::FieldElement> {
+        match unsafe { access(ty, path, dojo_core::access::get_felt) } {
+            Ok(value) => Result::Ok(value.into()),
+            Err(e) => Result::Err(e),
+        }
+    }
 
-        let subscription = Subscription { id: subscription_id, trigger };
+    /// Gets the u256 at a path within a type as a 0x-prefixed hex string, see ty_get
+    ///
+    /// # Parameters
+    /// * `ty` - Pointer to the type, e.g. a model
+    /// * `path` - Path as C string, e.g. `vec.x`
+    ///
+    /// # Returns
+    /// Result containing the hex string, or an error if the path doesn't resolve to a u256 with a value
+    #[no_mangle]
+    pub unsafe extern "C" fn ty_get_u256(
+        ty: *const Ty,
+        path: *const c_char,
+    ) -> Result<*const c_char> {
+        match unsafe { access(ty, path, dojo_core::access::get_u256) } {
+            Ok(value) => Result::Ok(c_string(format!("0x{value:x}"))),
+            Err(e) => Result::Err(e),
+        }
+    }
 
-        Result::Ok(Box::into_raw(Box::new(subscription)))
+    /// Gets the bool at a path within a type, see ty_get
+    ///
+    /// # Parameters
+    /// * `ty` - Pointer to the type, e.g. a model
+    /// * `path` - Path as C string, e.g. `vec.x`
+    ///
+    /// # Returns
+    /// Result containing the value, or an error if the path doesn't resolve to a bool with a value
+    #[no_mangle]
+    pub unsafe extern "C" fn ty_get_bool(ty: *const Ty, path: *const c_char) -> Result<bool> {
+        match unsafe { access(ty, path, dojo_core::access::get_bool) } {
+            Ok(value) => Result::Ok(value),
+            Err(e) => Result::Err(e),
+        }
     }
 
-    /// Updates an existing token balance subscription
+    /// Gets the ByteArray at a path within a type as a string, see ty_get
     ///
     /// # Parameters
-    /// * `client` - Pointer to ToriiClient instance
-    /// * `subscription` - Pointer to existing Subscription
-    /// * `contract_addresses` - Array of contract addresses to filter (empty for all)
-    /// * `contract_addresses_len` - Length of contract addresses array
-    /// * `account_addresses` - Array of account addresses to filter (empty for all)
-    /// * `account_addresses_len` - Length of account addresses array
+    /// * `ty` - Pointer to the type, e.g. a model
+    /// * `path` - Path as C string, e.g. `vec.x`
     ///
     /// # Returns
-    /// Result containing success boolean or error
+    /// Result containing the string, without its NUL bytes, or an error if the path doesn't
+    /// resolve to a ByteArray
     #[no_mangle]
-    pub unsafe extern "C" fn client_update_token_balance_subscription(
-        client: *mut ToriiClient,
-        subscription: *mut Subscription,
-        contract_addresses: *const types::FieldElement,
-        contract_addresses_len: usize,
-        account_addresses: *const types::FieldElement,
-        account_addresses_len: usize,
-        token_ids: *const types::U256,
-        token_ids_len: usize,
-    ) -> Result<bool> {
-        let contract_addresses = if contract_addresses.is_null() || contract_addresses_len == 0 {
-            Vec::new()
-        } else {
-            let addresses =
-                unsafe { std::slice::from_raw_parts(contract_addresses, contract_addresses_len) };
-            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
-        };
+    pub unsafe extern "C" fn ty_get_byte_array(
+        ty: *const Ty,
+        path: *const c_char,
+    ) -> Result<*const c_char> {
+        match unsafe { access(ty, path, dojo_core::access::get_byte_array) } {
+            Ok(value) => Result::Ok(c_string(value)),
+            Err(e) => Result::Err(e),
+        }
+    }
 
-        let account_addresses = if account_addresses.is_null() || account_addresses_len == 0 {
-            Vec::new()
-        } else {
-            let addresses =
-                unsafe { std::slice::from_raw_parts(account_addresses, account_addresses_len) };
-            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
-        };
+    /// Gets the name of the selected variant of the enum at a path within a type, see ty_get
+    ///
+    /// # Parameters
+    /// * `ty` - Pointer to the type, e.g. a model
+    /// * `path` - Path as C string, e.g. `vec.x`
+    ///
+    /// # Returns
+    /// Result containing the variant name, or an error if the path doesn't resolve to an enum with a value
+    #[no_mangle]
+    pub unsafe extern "C" fn ty_get_variant(
+        ty: *const Ty,
+        path: *const c_char,
+    ) -> Result<*const c_char> {
+        match unsafe { access(ty, path, dojo_core::access::get_variant) } {
+            Ok(value) => Result::Ok(c_string(value)),
+            Err(e) => Result::Err(e),
+        }
+    }
 
-        let token_ids = if token_ids.is_null() || token_ids_len == 0 {
-            Vec::new()
-        } else {
-            let ids = unsafe { std::slice::from_raw_parts(token_ids, token_ids_len) };
-            ids.iter().map(|f| f.clone().into()).collect::<Vec<U256>>()
-        };
+    /// Creates a builder of a value from its schema
+    ///
+    /// The value starts from defaults: zero for primitives, the first variant of enums, an empty
+    /// string for byte arrays, no elements for arrays and default elements for fixed size arrays.
+    /// Values are set by path as in ty_get and checked against the schema. A variant in a path
+    /// selects it, and the index following the last element of an array appends an element. A
+    /// setter that fails leaves the value unchanged
+    ///
+    /// # Parameters
+    /// * `schema` - Pointer to the type to build a value of, e.g. the schema of a model
+    ///
+    /// # Returns
+    /// Pointer to the TyBuilder, to free with ty_builder_free
+    #[no_mangle]
+    pub unsafe extern "C" fn ty_builder_new(schema: *const Ty) -> *mut TyBuilder {
+        let schema: dojo_types::schema::Ty = unsafe { (*schema).clone() }.into();
+        Box::into_raw(Box::new(TyBuilder(dojo_core::builder::TyBuilder::new(&schema))))
+    }
 
-        match RUNTIME.block_on((*client).inner.update_token_balance_subscription(
-            (*subscription).id,
-            contract_addresses,
-            account_addresses,
-            token_ids,
-        )) {
-            Ok(_) => Result::Ok(true),
-            Err(e) => Result::Err(e.into()),
+    /// Creates a builder of a model of a world, see ty_builder_new
+    ///
+    /// # Parameters
+    /// * `schema` - Pointer to WorldSchema instance
+    /// * `tag` - Tag of the model, e.g. `ns-Position`
+    ///
+    /// # Returns
+    /// Result containing pointer to the TyBuilder, to free with ty_builder_free, or an error if
+    /// the world has no such model
+    #[no_mangle]
+    pub unsafe extern "C" fn world_schema_builder(
+        schema: *const WorldSchema,
+        tag: *const c_char,
+    ) -> Result<*mut TyBuilder> {
+        let tag = unsafe { CStr::from_ptr(tag).to_string_lossy() };
+        match unsafe { (*schema).0.model(&tag) } {
+            Some(model) => Result::Ok(Box::into_raw(Box::new(TyBuilder(
+                dojo_core::builder::TyBuilder::new(&model.schema),
+            )))),
+            None => Result::Err(error(format!("World has no model `{tag}`"))),
         }
     }
 
-    /// Subscribes to token transfer updates
+    /// Sets the value at a path of a builder, which must have the type of the schema at that path
     ///
     /// # Parameters
-    /// * `client` - Pointer to ToriiClient instance
-    /// * `contract_addresses` - Array of contract addresses to filter (empty for all)
-    /// * `contract_addresses_len` - Length of contract addresses array
-    /// * `account_addresses` - Array of account addresses to filter (empty for all)
-    /// * `account_addresses_len` - Length of account addresses array
-    /// * `token_ids` - Array of token IDs to filter (empty for all)
-    /// * `token_ids_len` - Length of token IDs array
-    /// * `callback` - Function called when updates occur
+    /// * `builder` - Pointer to TyBuilder instance
+    /// * `path` - Path as C string, e.g. `vec.x`
+    /// * `value` - Pointer to the value
     ///
     /// # Returns
-    /// Result containing pointer to Subscription or error
+    /// Result containing true, or an error if the path or the value doesn't match the schema
     #[no_mangle]
-    pub unsafe extern "C" fn client_on_token_transfer_update(
-        client: *mut ToriiClient,
-        contract_addresses: *const types::FieldElement,
-        contract_addresses_len: usize,
-        account_addresses: *const types::FieldElement,
-        account_addresses_len: usize,
-        token_ids: *const types::U256,
-        token_ids_len: usize,
-        callback: unsafe extern "C" fn(TokenTransfer),
-    ) -> Result<*mut Subscription> {
-        let client = Arc::new(unsafe { &*client });
+    pub unsafe extern "C" fn ty_builder_set(
+        builder: *mut TyBuilder,
+        path: *const c_char,
+        value: *const Ty,
+    ) -> Result<bool> {
+        let value: dojo_types::schema::Ty = unsafe { (*value).clone() }.into();
+        unsafe { set_at(builder, path, |builder, path| builder.set(path, value)) }
+    }
 
-        // Convert account addresses array to Vec<Felt> if not empty
-        let account_addresses = if account_addresses.is_null() || account_addresses_len == 0 {
-            Vec::new()
-        } else {
-            let addresses =
-                unsafe { std::slice::from_raw_parts(account_addresses, account_addresses_len) };
-            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
-        };
+    /// Sets the u32 at a path of a builder, see ty_builder_set
+    ///
+    /// # Parameters
+    /// * `builder` - Pointer to TyBuilder instance
+    /// * `path` - Path as C string, e.g. `vec.x`
+    /// * `value` - Value to set
+    ///
+    /// # Returns
+    /// Result containing true, or an error if the path doesn't resolve to a u32
+    #[no_mangle]
+    pub unsafe extern "C" fn ty_builder_set_u32(
+        builder: *mut TyBuilder,
+        path: *const c_char,
+        value: u32,
+    ) -> Result<bool> {
+        unsafe { set_at(builder, path, |builder, path| builder.set_u32(path, value)) }
+    }
 
-        // Convert contract addresses array to Vec<Felt> if not empty
-        let contract_addresses = if contract_addresses.is_null() || contract_addresses_len == 0 {
-            Vec::new()
-        } else {
-            let addresses =
-                unsafe { std::slice::from_raw_parts(contract_addresses, contract_addresses_len) };
-            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
-        };
+    /// Sets the felt252, ContractAddress, ClassHash or EthAddress at a path of a builder, see
+    /// ty_builder_set
+    ///
+    /// # Parameters
+    /// * `builder` - Pointer to TyBuilder instance
+    /// * `path` - Path as C string, e.g. `player`
+    /// * `value` - Felt to set
+    ///
+    /// # Returns
+    /// Result containing true, or an error if the path doesn't resolve to a felt
+    #[no_mangle]
+    pub unsafe extern "C" fn ty_builder_set_felt(
+        builder: *mut TyBuilder,
+        path: *const c_char,
+        value: types::FieldElement,
+    ) -> Result<bool> {
+        unsafe { set_at(builder, path, |builder, path| builder.set_felt(path, value.into())) }
+    }
 
-        let token_ids = if token_ids.is_null() || token_ids_len == 0 {
-            Vec::new()
-        } else {
-            let ids = unsafe { std::slice::from_raw_parts(token_ids, token_ids_len) };
-            ids.iter().map(|f| f.clone().into()).collect::<Vec<U256>>()
-        };
+    /// Sets the bool at a path of a builder, see ty_builder_set
+    ///
+    /// # Parameters
+    /// * `builder` - Pointer to TyBuilder instance
+    /// * `path` - Path as C string, e.g. `alive`
+    /// * `value` - Value to set
+    ///
+    /// # Returns
+    /// Result containing true, or an error if the path doesn't resolve to a bool
+    #[no_mangle]
+    pub unsafe extern "C" fn ty_builder_set_bool(
+        builder: *mut TyBuilder,
+        path: *const c_char,
+        value: bool,
+    ) -> Result<bool> {
+        unsafe { set_at(builder, path, |builder, path| builder.set_bool(path, value)) }
+    }
 
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-        let (trigger, tripwire) = Tripwire::new();
+    /// Sets the ByteArray at a path of a builder, see ty_builder_set
+    ///
+    /// # Parameters
+    /// * `builder` - Pointer to TyBuilder instance
+    /// * `path` - Path as C string, e.g. `name`
+    /// * `value` - String to set
+    ///
+    /// # Returns
+    /// Result containing true, or an error if the path doesn't resolve to a ByteArray
+    #[no_mangle]
+    pub unsafe extern "C" fn ty_builder_set_byte_array(
+        builder: *mut TyBuilder,
+        path: *const c_char,
+        value: *const c_char,
+    ) -> Result<bool> {
+        let value = unsafe { CStr::from_ptr(value).to_string_lossy() };
+        unsafe { set_at(builder, path, |builder, path| builder.set_byte_array(path, &value)) }
+    }
 
-        // Spawn a new thread to handle the stream and reconnections
-        let client_clone = client.clone();
-        RUNTIME.spawn(async move {
-            let mut backoff = Duration::from_secs(1);
-            let max_backoff = Duration::from_secs(60);
-
-            loop {
-                let rcv = client_clone
-                    .inner
-                    .on_token_transfer_updated(
-                        contract_addresses.clone(),
-                        account_addresses.clone(),
-                        token_ids.clone(),
-                    )
-                    .await;
+    /// Selects a variant of the enum at a path of a builder, keeping the value of the variant
+    ///
+    /// # Parameters
+    /// * `builder` - Pointer to TyBuilder instance
+    /// * `path` - Path as C string, e.g. `dir`
+    /// * `variant` - Name of the variant
+    ///
+    /// # Returns
+    /// Result containing true, or an error if the path doesn't resolve to an enum with the variant
+    #[no_mangle]
+    pub unsafe extern "C" fn ty_builder_set_variant(
+        builder: *mut TyBuilder,
+        path: *const c_char,
+        variant: *const c_char,
+    ) -> Result<bool> {
+        let variant = unsafe { CStr::from_ptr(variant).to_string_lossy() };
+        unsafe { set_at(builder, path, |builder, path| builder.set_variant(path, &variant)) }
+    }
 
-                if let Ok(rcv) = rcv {
-                    backoff = Duration::from_secs(1); // Reset backoff on successful connection
+    /// Sets the primitive or ByteArray at a path of a builder from text, see ty_builder_set
+    ///
+    /// Numbers are decimal or 0x-prefixed hex, e.g. the strings of ty_get_u256, and booleans are
+    /// `true` or `false`
+    ///
+    /// # Parameters
+    /// * `builder` - Pointer to TyBuilder instance
+    /// * `path` - Path as C string, e.g. `score`
+    /// * `text` - Value as C string
+    ///
+    /// # Returns
+    /// Result containing true, or an error if the text isn't a valid value of the type at the path
+    #[no_mangle]
+    pub unsafe extern "C" fn ty_builder_set_str(
+        builder: *mut TyBuilder,
+        path: *const c_char,
+        text: *const c_char,
+    ) -> Result<bool> {
+        let text = unsafe { CStr::from_ptr(text).to_string_lossy() };
+        unsafe { set_at(builder, path, |builder, path| builder.set_from_str(path, &text)) }
+    }
 
-                    let mut rcv = rcv.take_until_if(tripwire.clone());
+    /// Gets the value built so far
+    ///
+    /// # Parameters
+    /// * `builder` - Pointer to TyBuilder instance
+    ///
+    /// # Returns
+    /// The value, a model being a Ty of the Struct_ variant
+    #[no_mangle]
+    pub unsafe extern "C" fn ty_builder_build(builder: *const TyBuilder) -> Ty {
+        unsafe { (*builder).0.ty() }.clone().into()
+    }
 
-                    while let Some(Ok(transfer)) = rcv.next().await {
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(0).expect("Failed to send subscription ID");
-                        } else {
-                            let transfer: types::TokenTransfer = transfer.into();
-                            callback(transfer);
-                        }
-                    }
-                }
+    /// Gets the value built so far as JSON, e.g. the message of an offchain message
+    ///
+    /// Structs are objects, unit variants their name and other variants an object of their name
+    /// and value, tuples and arrays are arrays and byte arrays strings
+    ///
+    /// # Parameters
+    /// * `builder` - Pointer to TyBuilder instance
+    ///
+    /// # Returns
+    /// Result containing the JSON as C string, or an error if a primitive has no value
+    #[no_mangle]
+    pub unsafe extern "C" fn ty_builder_to_json(
+        builder: *const TyBuilder,
+    ) -> Result<*const c_char> {
+        match unsafe { (*builder).0.to_json() } {
+            Ok(json) => Result::Ok(c_string(json.to_string())),
+            Err(e) => Result::Err(error(e)),
+        }
+    }
 
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
-                }
-                sleep(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
-            }
-        });
+    /// Serializes the value built so far as Cairo calldata, e.g. an argument of a call
+    ///
+    /// # Parameters
+    /// * `builder` - Pointer to TyBuilder instance
+    ///
+    /// # Returns
+    /// Result containing array of FieldElements or error
+    #[no_mangle]
+    pub unsafe extern "C" fn ty_builder_calldata(
+        builder: *const TyBuilder,
+    ) -> Result<CArray<types::FieldElement>> {
+        match unsafe { (*builder).0.calldata() } {
+            Ok(felts) => Result::Ok(
+                felts.into_iter().map(|f| f.into()).collect::<Vec<types::FieldElement>>().into(),
+            ),
+            Err(e) => Result::Err(error(e)),
+        }
+    }
 
-        let subscription_id = match RUNTIME.block_on(sub_id_rx) {
-            Ok(id) => id,
-            Err(_) => {
-                return Result::Err(Error {
-                    message: CString::new("Failed to establish token transfer subscription")
-                        .unwrap()
-                        .into_raw(),
-                });
-            }
-        };
+    /// Parses a textual filter into a clause
+    ///
+    /// Filters such as `ns-Position.x > 10 and keys(ns-Position) = [0x1, *]` combine member
+    /// comparisons, keys and hashed keys clauses with `and`, `or` and parentheses.
+    ///
+    /// # Parameters
+    /// * `filter` - Filter as C string
+    ///
+    /// # Returns
+    /// Result containing the clause or an error with the line and column of the failure
+    #[no_mangle]
+    pub unsafe extern "C" fn clause_parse(filter: *const c_char) -> Result<Clause> {
+        let filter = unsafe { CStr::from_ptr(filter).to_string_lossy() };
+
+        match dojo_core::filter::parse(&filter) {
+            Ok(clause) => Result::Ok(clause.into()),
+            Err(e) => Result::Err(Error { message: c_string(e.to_string()) }),
+        }
+    }
 
-        let subscription = Subscription { id: subscription_id, trigger };
+    /// Prints a clause as a textual filter, which `clause_parse` turns back into the clause
+    ///
+    /// # Parameters
+    /// * `clause` - Clause to print
+    ///
+    /// # Returns
+    /// Pointer to C string containing the filter
+    #[no_mangle]
+    pub unsafe extern "C" fn clause_to_string(clause: Clause) -> *const c_char {
+        let filter = dojo_core::filter::print(&clause.into());
 
-        Result::Ok(Box::into_raw(Box::new(subscription)))
+        c_string(filter)
     }
 
-    /// Updates an existing token transfer subscription
+    /// Encodes a query as canonical JSON, shared by every binding
     ///
     /// # Parameters
-    /// * `client` - Pointer to ToriiClient instance
-    /// * `subscription` - Pointer to existing Subscription
-    /// * `contract_addresses` - Array of contract addresses to filter (empty for all)
-    /// * `contract_addresses_len` - Length of contract addresses array
-    /// * `account_addresses` - Array of account addresses to filter (empty for all)
-    /// * `account_addresses_len` - Length of account addresses array
-    /// * `token_ids` - Array of token IDs to filter (empty for all)
-    /// * `token_ids_len` - Length of token IDs array
+    /// * `query` - Query to encode
     ///
     /// # Returns
-    /// Result containing success boolean or error
+    /// Result containing pointer to C string or error
     #[no_mangle]
-    pub unsafe extern "C" fn client_update_token_transfer_subscription(
-        client: *mut ToriiClient,
-        subscription: *mut Subscription,
-        contract_addresses: *const types::FieldElement,
-        contract_addresses_len: usize,
-        account_addresses: *const types::FieldElement,
-        account_addresses_len: usize,
-        token_ids: *const types::U256,
-        token_ids_len: usize,
-    ) -> Result<bool> {
-        let contract_addresses = if contract_addresses.is_null() || contract_addresses_len == 0 {
-            Vec::new()
-        } else {
-            let addresses =
-                unsafe { std::slice::from_raw_parts(contract_addresses, contract_addresses_len) };
-            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
-        };
+    pub unsafe extern "C" fn query_to_json(query: Query) -> Result<*const c_char> {
+        let query: torii_proto::Query = query.into();
 
-        let account_addresses = if account_addresses.is_null() || account_addresses_len == 0 {
-            Vec::new()
-        } else {
-            let addresses =
-                unsafe { std::slice::from_raw_parts(account_addresses, account_addresses_len) };
-            addresses.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>()
-        };
+        match query.to_json() {
+            Ok(json) => Result::Ok(c_string(json)),
+            Err(e) => Result::Err(e.into()),
+        }
+    }
 
-        let token_ids = if token_ids.is_null() || token_ids_len == 0 {
-            Vec::new()
-        } else {
-            let ids = unsafe { std::slice::from_raw_parts(token_ids, token_ids_len) };
-            ids.iter().map(|f| f.clone().into()).collect::<Vec<U256>>()
-        };
+    /// Decodes a query from its canonical JSON encoding
+    ///
+    /// # Parameters
+    /// * `json` - JSON string of the query
+    ///
+    /// # Returns
+    /// Result containing the query or error
+    #[no_mangle]
+    pub unsafe extern "C" fn query_from_json(json: *const c_char) -> Result<Query> {
+        let json = unsafe { CStr::from_ptr(json).to_string_lossy() };
 
-        match RUNTIME.block_on((*client).inner.update_token_transfer_subscription(
-            (*subscription).id,
-            contract_addresses,
-            account_addresses,
-            token_ids,
-        )) {
-            Ok(_) => Result::Ok(true),
+        match torii_proto::Query::from_json(&json) {
+            Ok(query) => Result::Ok(query.into()),
             Err(e) => Result::Err(e.into()),
         }
     }
 
-    /// Performs a full-text search across indexed entities using FTS5
+    /// Encodes a query in the compact binary encoding, shared by every binding
     ///
     /// # Parameters
-    /// * `client` - Pointer to ToriiClient instance
-    /// * `query` - Search query containing the search text and limit
+    /// * `query` - Query to encode
     ///
     /// # Returns
-    /// Result containing SearchResponse with results grouped by table or error
+    /// Result containing the encoded bytes or error
     #[no_mangle]
-    pub unsafe extern "C" fn client_search(
-        client: *mut ToriiClient,
-        query: types::SearchQuery,
-    ) -> Result<types::SearchResponse> {
-        let query = query.into();
-        let search_future = unsafe { (*client).inner.search(query) };
+    pub unsafe extern "C" fn query_to_bytes(query: Query) -> Result<CArray<u8>> {
+        let query: torii_proto::Query = query.into();
 
-        match RUNTIME.block_on(search_future) {
-            Ok(response) => Result::Ok(response.into()),
+        match query.to_bytes() {
+            Ok(bytes) => Result::Ok(bytes.into()),
             Err(e) => Result::Err(e.into()),
         }
     }
 
-    /// Serializes a string into a byte array
+    /// Decodes a query from its compact binary encoding
     ///
     /// # Parameters
-    /// * `str` - String to serialize
+    /// * `bytes` - Encoded query
+    /// * `bytes_len` - Length of the encoded query
     ///
     /// # Returns
-    /// Result containing array of FieldElements or error
+    /// Result containing the query or error
     #[no_mangle]
-    pub unsafe extern "C" fn bytearray_serialize(
-        str: *const c_char,
-    ) -> Result<CArray<types::FieldElement>> {
-        let str = unsafe { CStr::from_ptr(str).to_string_lossy().into_owned() };
-        let bytearray = match ByteArray::from_string(str.as_str()) {
-            Ok(bytearray) => bytearray,
-            Err(e) => return Result::Err(e.into()),
-        };
+    pub unsafe extern "C" fn query_from_bytes(bytes: *const u8, bytes_len: usize) -> Result<Query> {
+        let bytes = unsafe { std::slice::from_raw_parts(bytes, bytes_len) };
 
-        let felts = cairo_serde::ByteArray::cairo_serialize(&bytearray);
-        let felts = felts.into_iter().map(|f| f.into()).collect::<Vec<types::FieldElement>>();
-        Result::Ok(felts.into())
+        match torii_proto::Query::from_bytes(bytes) {
+            Ok(query) => Result::Ok(query.into()),
+            Err(e) => Result::Err(e.into()),
+        }
     }
 
-    /// Deserializes field elements into a string
+    /// Encodes a clause as canonical JSON, shared by every binding
     ///
     /// # Parameters
-    /// * `felts` - Array of field elements
-    /// * `felts_len` - Length of field elements array
+    /// * `clause` - Clause to encode
     ///
     /// # Returns
     /// Result containing pointer to C string or error
     #[no_mangle]
-    pub unsafe extern "C" fn bytearray_deserialize(
-        felts: *const types::FieldElement,
-        felts_len: usize,
-    ) -> Result<*const c_char> {
-        let felts = unsafe { std::slice::from_raw_parts(felts, felts_len) };
-        let felts = felts.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>();
-        let bytearray = match cairo_serde::ByteArray::cairo_deserialize(&felts, 0) {
-            Ok(bytearray) => bytearray,
-            Err(e) => return Result::Err(e.into()),
-        };
-
-        let bytearray = match bytearray.to_string() {
-            Ok(bytearray) => bytearray,
-            Err(e) => return Result::Err(e.into()),
-        };
+    pub unsafe extern "C" fn clause_to_json(clause: Clause) -> Result<*const c_char> {
+        let clause: torii_proto::Clause = clause.into();
 
-        Result::Ok(CString::new(bytearray).unwrap().into_raw())
+        match clause.to_json() {
+            Ok(json) => Result::Ok(c_string(json)),
+            Err(e) => Result::Err(e.into()),
+        }
     }
 
-    /// Computes Poseidon hash of field elements
+    /// Decodes a clause from its canonical JSON encoding
     ///
     /// # Parameters
-    /// * `felts` - Array of field elements
-    /// * `felts_len` - Length of array
+    /// * `json` - JSON string of the clause
     ///
     /// # Returns
-    /// FieldElement containing the hash result
+    /// Result containing the clause or error
     #[no_mangle]
-    pub unsafe extern "C" fn poseidon_hash(
-        felts: *const types::FieldElement,
-        felts_len: usize,
-    ) -> types::FieldElement {
-        let felts = unsafe { std::slice::from_raw_parts(felts, felts_len) };
-        let felts = felts.iter().map(|f| f.clone().into()).collect::<Vec<Felt>>();
+    pub unsafe extern "C" fn clause_from_json(json: *const c_char) -> Result<Clause> {
+        let json = unsafe { CStr::from_ptr(json).to_string_lossy() };
 
-        poseidon_hash_many(&felts).into()
+        match torii_proto::Clause::from_json(&json) {
+            Ok(clause) => Result::Ok(clause.into()),
+            Err(e) => Result::Err(e.into()),
+        }
     }
 
-    /// Gets selector from name string
+    /// Encodes a clause in the compact binary encoding, shared by every binding
     ///
     /// # Parameters
-    /// * `name` - Name to compute selector from
+    /// * `clause` - Clause to encode
     ///
     /// # Returns
-    /// Result containing FieldElement selector or error
+    /// Result containing the encoded bytes or error
     #[no_mangle]
-    pub unsafe extern "C" fn get_selector_from_name(
-        name: *const c_char,
-    ) -> Result<types::FieldElement> {
-        let name = unsafe { CStr::from_ptr(name).to_string_lossy().into_owned() };
-        let selector = match starknet::core::utils::get_selector_from_name(name.as_str()) {
-            Ok(selector) => selector,
-            Err(e) => return Result::Err(e.into()),
-        };
+    pub unsafe extern "C" fn clause_to_bytes(clause: Clause) -> Result<CArray<u8>> {
+        let clause: torii_proto::Clause = clause.into();
 
-        Result::Ok(selector.into())
+        match clause.to_bytes() {
+            Ok(bytes) => Result::Ok(bytes.into()),
+            Err(e) => Result::Err(e.into()),
+        }
     }
 
-    /// Gets selector from tag string
+    /// Decodes a clause from its compact binary encoding
     ///
     /// # Parameters
-    /// * `tag` - Tag to compute selector from
+    /// * `bytes` - Encoded clause
+    /// * `bytes_len` - Length of the encoded clause
     ///
     /// # Returns
-    /// FieldElement containing the computed selector
+    /// Result containing the clause or error
     #[no_mangle]
-    pub unsafe extern "C" fn get_selector_from_tag(tag: *const c_char) -> types::FieldElement {
-        let tag = unsafe { CStr::from_ptr(tag).to_string_lossy().into_owned() };
-        let selector = compute_selector_from_tag(tag.as_str());
+    pub unsafe extern "C" fn clause_from_bytes(
+        bytes: *const u8,
+        bytes_len: usize,
+    ) -> Result<Clause> {
+        let bytes = unsafe { std::slice::from_raw_parts(bytes, bytes_len) };
 
-        selector.into()
+        match torii_proto::Clause::from_bytes(bytes) {
+            Ok(clause) => Result::Ok(clause.into()),
+            Err(e) => Result::Err(e.into()),
+        }
     }
 
     /// Computes Starknet keccak hash of bytes
@@ -2075,7 +4378,7 @@ mod ffi {
             Err(e) => return Result::Err(e.into()),
         };
 
-        Result::Ok(CString::new(str).unwrap().into_raw())
+        Result::Ok(c_string(str))
     }
 
     /// Encodes typed data
@@ -2096,7 +4399,7 @@ mod ffi {
             Ok(typed_data) => typed_data,
             Err(err) => {
                 return Result::Err(Error {
-                    message: CString::new(format!("Invalid typed data: {err}")).unwrap().into_raw(),
+                    message: c_string(format!("Invalid typed data: {err}")),
                 });
             }
         };
@@ -2325,9 +4628,7 @@ mod ffi {
 
         match RUNTIME.block_on(watch_tx(&(*provider).0, result.transaction_hash)) {
             Ok(_) => Result::Ok(Box::into_raw(Box::new(Account(account)))),
-            Err(e) => {
-                Result::Err(Error { message: CString::new(e.to_string()).unwrap().into_raw() })
-            }
+            Err(e) => Result::Err(Error { message: c_string(e.to_string()) }),
         }
     }
 
@@ -2425,9 +4726,7 @@ mod ffi {
         let txn_hash = txn_hash.into();
         match RUNTIME.block_on(watch_tx(&(*rpc).0, txn_hash)) {
             Ok(_) => Result::Ok(true),
-            Err(e) => {
-                Result::Err(Error { message: CString::new(e.to_string()).unwrap().into_raw() })
-            }
+            Err(e) => Result::Err(Error { message: c_string(e.to_string()) }),
         }
     }
 
@@ -2474,11 +4773,98 @@ mod ffi {
         if !subscription.is_null() {
             unsafe {
                 let subscription = Box::from_raw(subscription);
+                // Shared subscriptions leave their stream when the lease is dropped
                 subscription.trigger.cancel();
             }
         }
     }
 
+    /// Gets the connection state of a subscription
+    ///
+    /// # Parameters
+    /// * `subscription` - Pointer to Subscription
+    ///
+    /// # Returns
+    /// Current SubscriptionState
+    #[no_mangle]
+    pub unsafe extern "C" fn subscription_state(
+        subscription: *const Subscription,
+    ) -> SubscriptionState {
+        (*subscription).status.state().into()
+    }
+
+    /// Registers a callback notified of every connection state transition of a subscription
+    ///
+    /// The callback is immediately called with the current state and keeps being called after
+    /// the subscription is cancelled, until it reports the `Closed` state.
+    ///
+    /// # Parameters
+    /// * `subscription` - Pointer to Subscription
+    /// * `callback` - Function called with each new SubscriptionState
+    #[no_mangle]
+    pub unsafe extern "C" fn subscription_on_status(
+        subscription: *mut Subscription,
+        callback: unsafe extern "C" fn(SubscriptionState),
+    ) {
+        let mut states = (*subscription).status.watch();
+        RUNTIME.spawn(async move {
+            while let Some(state) = states.next().await {
+                callback(state.into());
+            }
+        });
+    }
+
+    /// Sets how a subscription's updates are buffered, coalesced and rate limited before
+    /// reaching its callback
+    ///
+    /// Coalescing only applies to entity and event message subscriptions.
+    ///
+    /// # Parameters
+    /// * `subscription` - Pointer to Subscription
+    /// * `policy` - DeliveryPolicy applied to the updates received from now on
+    #[no_mangle]
+    pub unsafe extern "C" fn subscription_set_delivery_policy(
+        subscription: *mut Subscription,
+        policy: DeliveryPolicy,
+    ) {
+        (*subscription).delivery.set_policy(policy.into());
+    }
+
+    /// Sets the models and members kept from the updates of an entity or event message
+    /// subscription, e.g. `ns-Position` or `ns-Stats.health`
+    ///
+    /// Updates are projected as they are delivered, changes, diffs and predictions being
+    /// computed from whole updates, and skipped when nothing is left of them. Other
+    /// subscriptions ignore the projection.
+    ///
+    /// # Parameters
+    /// * `subscription` - Pointer to Subscription
+    /// * `selectors` - Array of model tags and member paths, every model and member being kept
+    ///   when empty
+    /// * `selectors_len` - Length of selectors array
+    #[no_mangle]
+    pub unsafe extern "C" fn subscription_set_projection(
+        subscription: *mut Subscription,
+        selectors: *const *const c_char,
+        selectors_len: usize,
+    ) {
+        (*subscription).delivery.set_projection(projection(selectors, selectors_len));
+    }
+
+    /// Gets the number of delivered, dropped and coalesced updates of a subscription
+    ///
+    /// # Parameters
+    /// * `subscription` - Pointer to Subscription
+    ///
+    /// # Returns
+    /// DeliveryStats of the subscription
+    #[no_mangle]
+    pub unsafe extern "C" fn subscription_delivery_stats(
+        subscription: *const Subscription,
+    ) -> DeliveryStats {
+        (*subscription).delivery.stats().into()
+    }
+
     /// Frees a ToriiClient instance
     ///
     /// # Parameters
@@ -2492,6 +4878,192 @@ mod ffi {
         }
     }
 
+    /// Frees a ModelDispatcher instance. Subscriptions using it keep dispatching until they are
+    /// cancelled.
+    ///
+    /// # Parameters
+    /// * `dispatcher` - Pointer to ModelDispatcher to free
+    #[no_mangle]
+    pub unsafe extern "C" fn model_dispatcher_free(dispatcher: *mut ModelDispatcher) {
+        if !dispatcher.is_null() {
+            unsafe {
+                let _ = Box::from_raw(dispatcher);
+            }
+        }
+    }
+
+    /// Frees an OptimisticStore instance. Subscriptions and watches using it keep applying its
+    /// predictions until they are cancelled or settled.
+    ///
+    /// # Parameters
+    /// * `store` - Pointer to OptimisticStore to free
+    #[no_mangle]
+    pub unsafe extern "C" fn optimistic_store_free(store: *mut OptimisticStore) {
+        if !store.is_null() {
+            unsafe {
+                let _ = Box::from_raw(store);
+            }
+        }
+    }
+
+    /// Frees a WorldSchema instance
+    ///
+    /// # Parameters
+    /// * `schema` - Pointer to WorldSchema to free
+    #[no_mangle]
+    pub unsafe extern "C" fn world_schema_free(schema: *mut WorldSchema) {
+        if !schema.is_null() {
+            unsafe {
+                let _ = Box::from_raw(schema);
+            }
+        }
+    }
+
+    /// Frees a TyBuilder instance
+    ///
+    /// # Parameters
+    /// * `builder` - Pointer to TyBuilder to free
+    #[no_mangle]
+    pub unsafe extern "C" fn ty_builder_free(builder: *mut TyBuilder) {
+        if !builder.is_null() {
+            unsafe {
+                let _ = Box::from_raw(builder);
+            }
+        }
+    }
+
+    /// Frees an EntityIterator instance
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to EntityIterator to free
+    #[no_mangle]
+    pub unsafe extern "C" fn entity_iterator_free(iterator: *mut EntityIterator) {
+        if !iterator.is_null() {
+            unsafe {
+                let _ = Box::from_raw(iterator);
+            }
+        }
+    }
+
+    /// Frees an ControllerIterator instance
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to ControllerIterator to free
+    #[no_mangle]
+    pub unsafe extern "C" fn controller_iterator_free(iterator: *mut ControllerIterator) {
+        if !iterator.is_null() {
+            unsafe {
+                let _ = Box::from_raw(iterator);
+            }
+        }
+    }
+
+    /// Frees an TokenIterator instance
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to TokenIterator to free
+    #[no_mangle]
+    pub unsafe extern "C" fn token_iterator_free(iterator: *mut TokenIterator) {
+        if !iterator.is_null() {
+            unsafe {
+                let _ = Box::from_raw(iterator);
+            }
+        }
+    }
+
+    /// Frees an TokenBalanceIterator instance
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to TokenBalanceIterator to free
+    #[no_mangle]
+    pub unsafe extern "C" fn token_balance_iterator_free(iterator: *mut TokenBalanceIterator) {
+        if !iterator.is_null() {
+            unsafe {
+                let _ = Box::from_raw(iterator);
+            }
+        }
+    }
+
+    /// Frees an TokenTransferIterator instance
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to TokenTransferIterator to free
+    #[no_mangle]
+    pub unsafe extern "C" fn token_transfer_iterator_free(iterator: *mut TokenTransferIterator) {
+        if !iterator.is_null() {
+            unsafe {
+                let _ = Box::from_raw(iterator);
+            }
+        }
+    }
+
+    /// Frees an TransactionIterator instance
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to TransactionIterator to free
+    #[no_mangle]
+    pub unsafe extern "C" fn transaction_iterator_free(iterator: *mut TransactionIterator) {
+        if !iterator.is_null() {
+            unsafe {
+                let _ = Box::from_raw(iterator);
+            }
+        }
+    }
+
+    /// Frees an ActivityIterator instance
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to ActivityIterator to free
+    #[no_mangle]
+    pub unsafe extern "C" fn activity_iterator_free(iterator: *mut ActivityIterator) {
+        if !iterator.is_null() {
+            unsafe {
+                let _ = Box::from_raw(iterator);
+            }
+        }
+    }
+
+    /// Frees an AchievementIterator instance
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to AchievementIterator to free
+    #[no_mangle]
+    pub unsafe extern "C" fn achievement_iterator_free(iterator: *mut AchievementIterator) {
+        if !iterator.is_null() {
+            unsafe {
+                let _ = Box::from_raw(iterator);
+            }
+        }
+    }
+
+    /// Frees an PlayerAchievementIterator instance
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to PlayerAchievementIterator to free
+    #[no_mangle]
+    pub unsafe extern "C" fn player_achievement_iterator_free(
+        iterator: *mut PlayerAchievementIterator,
+    ) {
+        if !iterator.is_null() {
+            unsafe {
+                let _ = Box::from_raw(iterator);
+            }
+        }
+    }
+
+    /// Frees an AggregationIterator instance
+    ///
+    /// # Parameters
+    /// * `iterator` - Pointer to AggregationIterator to free
+    #[no_mangle]
+    pub unsafe extern "C" fn aggregation_iterator_free(iterator: *mut AggregationIterator) {
+        if !iterator.is_null() {
+            unsafe {
+                let _ = Box::from_raw(iterator);
+            }
+        }
+    }
+
     /// Frees a Provider instance
     ///
     /// # Parameters
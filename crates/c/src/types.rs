@@ -1,10 +1,12 @@
 use std::ffi::{c_char, CStr, CString};
+use std::sync::Arc;
 
 use chrono::DateTime;
 use crypto_bigint::Encoding;
+use dojo_core::connection::Connection;
+use dojo_core::mux::{Entities, EventMessages, Mux, TokenBalances};
 use dojo_types::naming::compute_selector_from_names;
 use starknet::core::utils::get_selector_from_name;
-use torii_client::Client;
 
 #[derive(Debug, Clone)]
 #[repr(C)]
@@ -21,10 +23,7 @@ where
         let items = val.items.into_iter().map(|t| t.into()).collect::<Vec<U>>();
         Page {
             items: items.into(),
-            next_cursor: val
-                .next_cursor
-                .map(|c| CString::new(c).unwrap().into_raw() as *const c_char)
-                .into(),
+            next_cursor: val.next_cursor.map(|c| c_string(c) as *const c_char).into(),
         }
     }
 }
@@ -92,7 +91,7 @@ impl From<torii_proto::Controller> for Controller {
     fn from(val: torii_proto::Controller) -> Self {
         Controller {
             address: val.address.into(),
-            username: CString::new(val.username.clone()).unwrap().into_raw(),
+            username: c_string(val.username.clone()),
             deployed_at_timestamp: val.deployed_at.timestamp() as u64,
         }
     }
@@ -115,10 +114,10 @@ impl From<torii_proto::Token> for Token {
         Token {
             token_id: val.token_id.into(),
             contract_address: val.contract_address.into(),
-            name: CString::new(val.name.clone()).unwrap().into_raw(),
-            symbol: CString::new(val.symbol.clone()).unwrap().into_raw(),
+            name: c_string(val.name.clone()),
+            symbol: c_string(val.symbol.clone()),
             decimals: val.decimals,
-            metadata: CString::new(val.metadata.clone()).unwrap().into_raw(),
+            metadata: c_string(val.metadata.clone()),
             total_supply: val.total_supply.into(),
         }
     }
@@ -160,12 +159,12 @@ impl From<torii_proto::TokenContract> for TokenContract {
     fn from(value: torii_proto::TokenContract) -> Self {
         Self {
             contract_address: value.contract_address.into(),
-            name: CString::new(value.name.clone()).unwrap().into_raw(),
-            symbol: CString::new(value.symbol.clone()).unwrap().into_raw(),
+            name: c_string(value.name.clone()),
+            symbol: c_string(value.symbol.clone()),
             decimals: value.decimals,
-            token_metadata: CString::new(value.token_metadata.clone()).unwrap().into_raw(),
+            token_metadata: c_string(value.token_metadata.clone()),
             total_supply: value.total_supply.into(),
-            metadata: CString::new(value.metadata.clone()).unwrap().into_raw(),
+            metadata: c_string(value.metadata.clone()),
         }
     }
 }
@@ -326,9 +325,314 @@ impl From<Call> for starknet::core::types::FunctionCall {
     }
 }
 
+/// Consumer of a multiplexed stream
+pub(crate) type Sink<T> = Box<dyn FnMut(T) + Send>;
+
 pub struct ToriiClient {
-    pub inner: Client,
+    pub inner: Arc<Connection>,
     pub logger: Option<extern "C" fn(*const c_char)>,
+    pub(crate) entities: Mux<Entities, Sink<torii_proto::schema::Entity>>,
+    pub(crate) event_messages: Mux<EventMessages, Sink<torii_proto::schema::Entity>>,
+    pub(crate) token_balances: Mux<TokenBalances, Sink<torii_proto::TokenBalance>>,
+}
+
+impl ToriiClient {
+    pub fn new(inner: Arc<Connection>) -> Self {
+        Self {
+            inner,
+            logger: None,
+            entities: Mux::new(Entities),
+            event_messages: Mux::new(EventMessages),
+            token_balances: Mux::new(TokenBalances),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct ClientConfig {
+    pub torii_url: *const c_char,
+    pub fallback_urls: CArray<*const c_char>,
+    pub connect_timeout_ms: COption<u64>,
+    pub request_timeout_ms: COption<u64>,
+    pub max_message_size: COption<u64>,
+    pub retry: COption<RetryPolicy>,
+    pub polling: COption<PollingConfig>,
+    pub multiplex: bool,
+    pub cache: COption<CacheConfig>,
+    pub validate_queries: bool,
+}
+
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct RetryPolicy {
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    pub multiplier: u32,
+    pub max_attempts: COption<u32>,
+}
+
+impl From<ClientConfig> for dojo_core::config::ClientConfig {
+    fn from(val: ClientConfig) -> Self {
+        let torii_url = unsafe { CStr::from_ptr(val.torii_url).to_string_lossy().to_string() };
+        let fallback_urls: Vec<*const c_char> = val.fallback_urls.into();
+        let mut config = dojo_core::config::ClientConfig::new(torii_url);
+
+        config.fallback_urls = fallback_urls
+            .into_iter()
+            .map(|url| unsafe { CStr::from_ptr(url).to_string_lossy().to_string() })
+            .collect();
+
+        config.connect_timeout =
+            Option::<u64>::from(val.connect_timeout_ms).map(std::time::Duration::from_millis);
+        config.request_timeout =
+            Option::<u64>::from(val.request_timeout_ms).map(std::time::Duration::from_millis);
+        if let COption::Some(max_message_size) = val.max_message_size {
+            config.max_message_size = max_message_size as usize;
+        }
+        if let COption::Some(retry) = val.retry {
+            config.retry = retry.into();
+        }
+        config.polling = Option::<PollingConfig>::from(val.polling).map(Into::into);
+        config.multiplex = val.multiplex;
+        config.cache = Option::<CacheConfig>::from(val.cache).map(Into::into);
+        config.validate_queries = val.validate_queries;
+
+        config
+    }
+}
+
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct PollingConfig {
+    pub interval_ms: u64,
+    pub after_failures: u32,
+    pub page_size: u32,
+}
+
+impl From<PollingConfig> for dojo_core::config::PollingConfig {
+    fn from(val: PollingConfig) -> Self {
+        dojo_core::config::PollingConfig {
+            interval: std::time::Duration::from_millis(val.interval_ms),
+            after_failures: val.after_failures,
+            page_size: val.page_size,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct CacheConfig {
+    pub ttl_ms: u64,
+    pub max_entries: u64,
+    pub stale_while_revalidate: bool,
+}
+
+impl From<CacheConfig> for dojo_core::cache::CacheConfig {
+    fn from(val: CacheConfig) -> Self {
+        dojo_core::cache::CacheConfig {
+            ttl: std::time::Duration::from_millis(val.ttl_ms),
+            max_entries: val.max_entries as usize,
+            stale_while_revalidate: val.stale_while_revalidate,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[repr(C)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub stale_hits: u64,
+    pub misses: u64,
+    pub invalidations: u64,
+    pub evictions: u64,
+    pub entries: u64,
+}
+
+impl From<dojo_core::cache::CacheStats> for CacheStats {
+    fn from(val: dojo_core::cache::CacheStats) -> Self {
+        CacheStats {
+            hits: val.hits,
+            stale_hits: val.stale_hits,
+            misses: val.misses,
+            invalidations: val.invalidations,
+            evictions: val.evictions,
+            entries: val.entries,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct Diagnostic {
+    /// Offending clause in the filter syntax, none for problems outside the clause
+    pub clause: COption<*const c_char>,
+    pub message: *const c_char,
+}
+
+impl From<dojo_core::validate::Diagnostic> for Diagnostic {
+    fn from(val: dojo_core::validate::Diagnostic) -> Self {
+        Diagnostic {
+            clause: val.clause.map(|c| c_string(c) as *const c_char).into(),
+            message: c_string(val.message),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub enum SubscriptionState {
+    Connecting,
+    Live,
+    Reconnecting { attempt: u32, delay_ms: u64 },
+    Closed { reason: *const c_char },
+}
+
+impl From<dojo_core::status::SubscriptionState> for SubscriptionState {
+    fn from(val: dojo_core::status::SubscriptionState) -> Self {
+        match val {
+            dojo_core::status::SubscriptionState::Connecting => SubscriptionState::Connecting,
+            dojo_core::status::SubscriptionState::Live => SubscriptionState::Live,
+            dojo_core::status::SubscriptionState::Reconnecting { attempt, delay } => {
+                SubscriptionState::Reconnecting { attempt, delay_ms: delay.as_millis() as u64 }
+            }
+            dojo_core::status::SubscriptionState::Closed { reason } => {
+                SubscriptionState::Closed { reason: c_string(reason) }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub enum Overflow {
+    DropOldest,
+    DropNewest,
+    Wait,
+}
+
+impl From<Overflow> for dojo_core::delivery::Overflow {
+    fn from(val: Overflow) -> Self {
+        match val {
+            Overflow::DropOldest => dojo_core::delivery::Overflow::DropOldest,
+            Overflow::DropNewest => dojo_core::delivery::Overflow::DropNewest,
+            Overflow::Wait => dojo_core::delivery::Overflow::Wait,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct DeliveryPolicy {
+    pub buffer_size: COption<u64>,
+    pub overflow: Overflow,
+    pub coalesce: bool,
+    pub min_interval_ms: COption<u64>,
+}
+
+impl From<DeliveryPolicy> for dojo_core::delivery::DeliveryPolicy {
+    fn from(val: DeliveryPolicy) -> Self {
+        dojo_core::delivery::DeliveryPolicy {
+            buffer_size: Option::<u64>::from(val.buffer_size).map(|size| size as usize),
+            overflow: val.overflow.into(),
+            coalesce: val.coalesce,
+            min_interval: Option::<u64>::from(val.min_interval_ms)
+                .map(std::time::Duration::from_millis),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct DeliveryStats {
+    pub delivered: u64,
+    pub dropped: u64,
+    pub coalesced: u64,
+}
+
+impl From<dojo_core::delivery::DeliveryStats> for DeliveryStats {
+    fn from(val: dojo_core::delivery::DeliveryStats) -> Self {
+        DeliveryStats { delivered: val.delivered, dropped: val.dropped, coalesced: val.coalesced }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub enum ChangeKind {
+    EntityCreated,
+    ModelAdded,
+    MemberUpdated,
+    ModelRemoved,
+    EntityDeleted,
+}
+
+impl From<dojo_core::diff::ChangeKind> for ChangeKind {
+    fn from(val: dojo_core::diff::ChangeKind) -> Self {
+        match val {
+            dojo_core::diff::ChangeKind::EntityCreated => ChangeKind::EntityCreated,
+            dojo_core::diff::ChangeKind::ModelAdded => ChangeKind::ModelAdded,
+            dojo_core::diff::ChangeKind::MemberUpdated => ChangeKind::MemberUpdated,
+            dojo_core::diff::ChangeKind::ModelRemoved => ChangeKind::ModelRemoved,
+            dojo_core::diff::ChangeKind::EntityDeleted => ChangeKind::EntityDeleted,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct FieldChange {
+    pub hashed_keys: FieldElement,
+    pub kind: ChangeKind,
+    pub model: *const c_char,
+    pub path: *const c_char,
+    pub old_value: COption<Ty>,
+    pub new_value: COption<Ty>,
+}
+
+impl From<dojo_core::diff::FieldChange> for FieldChange {
+    fn from(val: dojo_core::diff::FieldChange) -> Self {
+        FieldChange {
+            hashed_keys: val.hashed_keys.into(),
+            kind: val.kind.into(),
+            model: c_string(val.model),
+            path: c_string(val.path),
+            old_value: val.old.into(),
+            new_value: val.new.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct EndpointStatus {
+    pub url: *const c_char,
+    pub active: bool,
+    pub healthy: bool,
+    pub head: COption<u64>,
+    pub last_error: COption<*const c_char>,
+}
+
+impl From<dojo_core::failover::EndpointStatus> for EndpointStatus {
+    fn from(val: dojo_core::failover::EndpointStatus) -> Self {
+        EndpointStatus {
+            url: c_string(val.url),
+            active: val.active,
+            healthy: val.health.healthy,
+            head: val.health.head.into(),
+            last_error: val.health.last_error.map(|e| c_string(e) as *const c_char).into(),
+        }
+    }
+}
+
+impl From<RetryPolicy> for dojo_core::config::RetryPolicy {
+    fn from(val: RetryPolicy) -> Self {
+        dojo_core::config::RetryPolicy {
+            initial_backoff: std::time::Duration::from_millis(val.initial_backoff_ms),
+            max_backoff: std::time::Duration::from_millis(val.max_backoff_ms),
+            multiplier: val.multiplier,
+            max_attempts: val.max_attempts.into(),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -383,11 +687,7 @@ pub struct StringVec(Vec<String>);
 
 impl From<StringVec> for CArray<*const c_char> {
     fn from(val: StringVec) -> Self {
-        let vec = val
-            .0
-            .into_iter()
-            .map(|s| CString::new(s).unwrap().into_raw() as *const c_char)
-            .collect::<Vec<_>>();
+        let vec = val.0.into_iter().map(|s| c_string(s) as *const c_char).collect::<Vec<_>>();
 
         vec.into()
     }
@@ -412,13 +712,20 @@ pub struct Error {
     pub message: *mut c_char,
 }
 
+/// Copies text into a C string owned by the caller, dropping the NUL bytes C strings can't hold.
+pub fn c_string(text: impl Into<Vec<u8>>) -> *mut c_char {
+    let mut bytes = text.into();
+    bytes.retain(|&byte| byte != 0);
+    CString::new(bytes).expect("NUL bytes were dropped").into_raw()
+}
+
 // Implement conversion from std::error::Error to Error
 impl<T> From<T> for Error
 where
     T: std::error::Error,
 {
     fn from(val: T) -> Self {
-        Error { message: CString::new(val.to_string()).unwrap().into_raw() }
+        Error { message: c_string(val.to_string()) }
     }
 }
 
@@ -571,17 +878,14 @@ pub struct TokenTransfer {
 impl From<torii_proto::TokenTransfer> for TokenTransfer {
     fn from(val: torii_proto::TokenTransfer) -> Self {
         TokenTransfer {
-            id: CString::new(val.id.clone()).unwrap().into_raw(),
+            id: c_string(val.id.clone()),
             contract_address: val.contract_address.into(),
             from_address: val.from_address.into(),
             to_address: val.to_address.into(),
             amount: val.amount.into(),
             token_id: val.token_id.into(),
             executed_at: val.executed_at.timestamp() as u64,
-            event_id: val
-                .event_id
-                .map(|e| CString::new(e).unwrap().into_raw() as *const c_char)
-                .into(),
+            event_id: val.event_id.map(|e| c_string(e) as *const c_char).into(),
         }
     }
 }
@@ -696,7 +1000,7 @@ impl From<torii_proto::Transaction> for Transaction {
             signature: val.signature.into(),
             nonce: val.nonce.into(),
             block_number: val.block_number,
-            transaction_type: CString::new(val.transaction_type).unwrap().into_raw(),
+            transaction_type: c_string(val.transaction_type),
             block_timestamp: val.block_timestamp.timestamp() as u64,
             calls: val.calls.into(),
             unique_models: val.unique_models.into(),
@@ -734,7 +1038,7 @@ impl From<torii_proto::TransactionCall> for TransactionCall {
     fn from(val: torii_proto::TransactionCall) -> Self {
         TransactionCall {
             contract_address: val.contract_address.into(),
-            entrypoint: CString::new(val.entrypoint).unwrap().into_raw(),
+            entrypoint: c_string(val.entrypoint),
             calldata: val.calldata.into(),
             call_type: val.call_type.into(),
             caller_address: val.caller_address.into(),
@@ -779,7 +1083,7 @@ impl From<Pagination> for torii_proto::Pagination {
 impl From<torii_proto::Pagination> for Pagination {
     fn from(val: torii_proto::Pagination) -> Self {
         Pagination {
-            cursor: val.cursor.map(|c| CString::new(c).unwrap().into_raw() as *const c_char).into(),
+            cursor: val.cursor.map(|c| c_string(c) as *const c_char).into(),
             limit: val.limit.into(),
             direction: val.direction.into(),
             order_by: val.order_by.into(),
@@ -829,10 +1133,7 @@ impl From<OrderBy> for torii_proto::OrderBy {
 
 impl From<torii_proto::OrderBy> for OrderBy {
     fn from(val: torii_proto::OrderBy) -> Self {
-        OrderBy {
-            field: CString::new(val.field.clone()).unwrap().into_raw(),
-            direction: val.direction.into(),
-        }
+        OrderBy { field: c_string(val.field.clone()), direction: val.direction.into() }
     }
 }
 
@@ -919,7 +1220,7 @@ impl From<torii_proto::MemberValue> for MemberValue {
                 MemberValue::PrimitiveValue(primitive.into())
             }
             torii_proto::MemberValue::String(string) => {
-                MemberValue::String(CString::new(string.clone()).unwrap().into_raw())
+                MemberValue::String(c_string(string.clone()))
             }
             torii_proto::MemberValue::List(list) => {
                 let values = list.into_iter().map(|v| v.into()).collect::<Vec<MemberValue>>();
@@ -1031,6 +1332,100 @@ impl From<torii_proto::schema::Entity> for Entity {
     }
 }
 
+/// Entity looked up by id, `entity` being none when Torii doesn't know it
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct EntityLookup {
+    pub id: FieldElement,
+    pub entity: COption<Entity>,
+}
+
+impl From<(Felt, Option<torii_proto::schema::Entity>)> for EntityLookup {
+    fn from((id, entity): (Felt, Option<torii_proto::schema::Entity>)) -> Self {
+        EntityLookup { id: id.into(), entity: entity.into() }
+    }
+}
+
+/// Point in the history of a world, a timestamp being in seconds
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub enum At {
+    Block(u64),
+    Timestamp(u64),
+}
+
+impl From<At> for dojo_core::history::At {
+    fn from(val: At) -> Self {
+        match val {
+            At::Block(block) => dojo_core::history::At::Block(block),
+            At::Timestamp(timestamp) => dojo_core::history::At::Timestamp(timestamp),
+        }
+    }
+}
+
+/// Inclusive bounds of an entity history, unbounded when none
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct HistoryRange {
+    pub from: COption<At>,
+    pub to: COption<At>,
+}
+
+impl From<HistoryRange> for dojo_core::history::HistoryRange {
+    fn from(val: HistoryRange) -> Self {
+        dojo_core::history::HistoryRange {
+            from: Option::<At>::from(val.from).map(Into::into),
+            to: Option::<At>::from(val.to).map(Into::into),
+        }
+    }
+}
+
+/// Value of a model set by a transaction. The block and transaction are unknown when its
+/// event couldn't be told apart from others of the same entity and time.
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct EntityVersion {
+    pub block_number: COption<u64>,
+    pub transaction_hash: COption<FieldElement>,
+    pub timestamp: u64,
+    pub model: Struct,
+}
+
+impl From<dojo_core::history::Version> for EntityVersion {
+    fn from(val: dojo_core::history::Version) -> Self {
+        EntityVersion {
+            block_number: val.block_number.into(),
+            transaction_hash: val.transaction_hash.into(),
+            timestamp: val.timestamp,
+            model: val.model.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub enum EntityChange {
+    Created(Entity),
+    Updated(Entity),
+    Deleted(Entity),
+}
+
+impl From<dojo_core::change::EntityChange> for EntityChange {
+    fn from(val: dojo_core::change::EntityChange) -> Self {
+        match val {
+            dojo_core::change::EntityChange::Created(entity) => {
+                EntityChange::Created(entity.into())
+            }
+            dojo_core::change::EntityChange::Updated(entity) => {
+                EntityChange::Updated(entity.into())
+            }
+            dojo_core::change::EntityChange::Deleted(entity) => {
+                EntityChange::Deleted(entity.into())
+            }
+        }
+    }
+}
+
 impl From<ValueType> for torii_proto::ValueType {
     fn from(value: ValueType) -> Self {
         match value {
@@ -1053,7 +1448,7 @@ impl From<torii_proto::ValueType> for ValueType {
     fn from(value: torii_proto::ValueType) -> Self {
         match value {
             torii_proto::ValueType::String(v) => {
-                let v = CString::new(v.clone()).unwrap().into_raw();
+                let v = c_string(v.clone());
                 ValueType::String(v)
             }
             torii_proto::ValueType::Int(v) => ValueType::Int(v),
@@ -1103,7 +1498,7 @@ impl From<dojo_types::schema::Ty> for Ty {
                 Ty::FixedSizeArray_(FixedSizeArray { array: ty.into(), size })
             }
             dojo_types::schema::Ty::ByteArray(array) => {
-                let array = CString::new(array.clone()).unwrap().into_raw();
+                let array = c_string(array.clone());
                 Ty::ByteArray(array)
             }
         }
@@ -1158,7 +1553,7 @@ impl From<dojo_types::schema::Enum> for Enum {
         let options = value.options.into_iter().map(|o| o.into()).collect::<Vec<EnumOption>>();
 
         Enum {
-            name: CString::new(value.name.clone()).unwrap().into_raw(),
+            name: c_string(value.name.clone()),
             option: value.option.unwrap_or(0),
             options: options.into(),
         }
@@ -1184,7 +1579,7 @@ impl From<EnumOption> for dojo_types::schema::EnumOption {
 impl From<dojo_types::schema::EnumOption> for EnumOption {
     fn from(value: dojo_types::schema::EnumOption) -> Self {
         EnumOption {
-            name: CString::new(value.name.clone()).unwrap().into_raw(),
+            name: c_string(value.name.clone()),
             ty: Box::into_raw(Box::new(value.ty.into())),
         }
     }
@@ -1213,10 +1608,7 @@ impl From<dojo_types::schema::Struct> for Struct {
     fn from(value: dojo_types::schema::Struct) -> Self {
         let children = value.children.into_iter().map(|c| c.into()).collect::<Vec<Member>>();
 
-        Struct {
-            name: CString::new(value.name.clone()).unwrap().into_raw(),
-            children: children.into(),
-        }
+        Struct { name: c_string(value.name.clone()), children: children.into() }
     }
 }
 
@@ -1241,7 +1633,7 @@ impl From<Member> for dojo_types::schema::Member {
 impl From<dojo_types::schema::Member> for Member {
     fn from(value: dojo_types::schema::Member) -> Self {
         Member {
-            name: CString::new(value.name.clone()).unwrap().into_raw(),
+            name: c_string(value.name.clone()),
             ty: Box::into_raw(Box::new(value.ty.into())),
             key: value.key,
         }
@@ -1471,8 +1863,8 @@ impl From<MemberClause> for torii_proto::MemberClause {
 impl From<torii_proto::MemberClause> for MemberClause {
     fn from(val: torii_proto::MemberClause) -> Self {
         MemberClause {
-            model: CString::new(val.model.clone()).unwrap().into_raw(),
-            member: CString::new(val.member.clone()).unwrap().into_raw(),
+            model: c_string(val.model.clone()),
+            member: c_string(val.member.clone()),
             operator: val.operator.into(),
             value: val.value.into(),
         }
@@ -1618,13 +2010,13 @@ pub struct Model {
 impl From<torii_proto::Model> for Model {
     fn from(value: torii_proto::Model) -> Self {
         let layout = serde_json::to_string(&value.layout).unwrap();
-        let layout = CString::new(layout).unwrap().into_raw();
+        let layout = c_string(layout);
 
         Model {
             world_address: value.world_address.into(),
             schema: value.schema.into(),
-            name: CString::new(value.name.clone()).unwrap().into_raw(),
-            namespace: CString::new(value.namespace.clone()).unwrap().into_raw(),
+            name: c_string(value.name.clone()),
+            namespace: c_string(value.namespace.clone()),
             selector: value.selector.into(),
             packed_size: value.packed_size,
             unpacked_size: value.unpacked_size,
@@ -1659,6 +2051,168 @@ impl From<Model> for torii_proto::Model {
     }
 }
 
+/// Shape of a type. Variants are suffixed so as not to clash with the type names of the header
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub enum TypeKind {
+    PrimitiveType,
+    StructType,
+    EnumType,
+    TupleType,
+    ArrayType,
+    FixedSizeArrayType,
+    ByteArrayType,
+}
+
+impl From<dojo_core::schema::TypeKind> for TypeKind {
+    fn from(val: dojo_core::schema::TypeKind) -> Self {
+        match val {
+            dojo_core::schema::TypeKind::Primitive => TypeKind::PrimitiveType,
+            dojo_core::schema::TypeKind::Struct => TypeKind::StructType,
+            dojo_core::schema::TypeKind::Enum => TypeKind::EnumType,
+            dojo_core::schema::TypeKind::Tuple => TypeKind::TupleType,
+            dojo_core::schema::TypeKind::Array => TypeKind::ArrayType,
+            dojo_core::schema::TypeKind::FixedSizeArray => TypeKind::FixedSizeArrayType,
+            dojo_core::schema::TypeKind::ByteArray => TypeKind::ByteArrayType,
+        }
+    }
+}
+
+/// Member of a model, with a description of its type
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct MemberSchema {
+    pub name: *const c_char,
+    /// Cairo name of the type, e.g. `u32`, `Array<felt252>` or the name of a struct or enum
+    pub type_name: *const c_char,
+    pub kind: TypeKind,
+    pub ty: Ty,
+}
+
+impl From<dojo_core::schema::MemberSchema> for MemberSchema {
+    fn from(val: dojo_core::schema::MemberSchema) -> Self {
+        MemberSchema {
+            name: c_string(val.name),
+            type_name: c_string(val.type_name),
+            kind: val.kind.into(),
+            ty: val.ty.into(),
+        }
+    }
+}
+
+/// Model or event registered in a world, along with the class declaring it
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct ModelSchema {
+    pub tag: *const c_char,
+    pub namespace: *const c_char,
+    pub name: *const c_char,
+    pub selector: FieldElement,
+    pub class_hash: FieldElement,
+    pub contract_address: FieldElement,
+    pub packed_size: u32,
+    pub unpacked_size: u32,
+    pub use_legacy_store: bool,
+    /// Whether this is an event rather than a model
+    pub is_event: bool,
+    /// Key members, in the order they make up the id of an entity
+    pub keys: CArray<MemberSchema>,
+    pub values: CArray<MemberSchema>,
+}
+
+impl From<dojo_core::schema::ModelSchema> for ModelSchema {
+    fn from(val: dojo_core::schema::ModelSchema) -> Self {
+        ModelSchema {
+            tag: c_string(val.tag),
+            namespace: c_string(val.namespace),
+            name: c_string(val.name),
+            selector: val.selector.into(),
+            class_hash: val.class_hash.into(),
+            contract_address: val.contract_address.into(),
+            packed_size: val.packed_size,
+            unpacked_size: val.unpacked_size,
+            use_legacy_store: val.use_legacy_store,
+            is_event: val.is_event,
+            keys: val.keys.into(),
+            values: val.values.into(),
+        }
+    }
+}
+
+/// Models of a world, looked up by tag or selector
+pub struct WorldSchema(pub(crate) dojo_core::schema::WorldSchema);
+
+/// Value of a type being built from its schema, e.g. a model to publish in a message
+pub struct TyBuilder(pub(crate) dojo_core::builder::TyBuilder);
+
+/// How a part of a model schema changed
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub enum TypeChangeKind {
+    Added,
+    Removed,
+    Changed,
+    KeyChanged,
+}
+
+impl From<dojo_core::upgrade::TypeChangeKind> for TypeChangeKind {
+    fn from(val: dojo_core::upgrade::TypeChangeKind) -> Self {
+        match val {
+            dojo_core::upgrade::TypeChangeKind::Added => TypeChangeKind::Added,
+            dojo_core::upgrade::TypeChangeKind::Removed => TypeChangeKind::Removed,
+            dojo_core::upgrade::TypeChangeKind::Changed => TypeChangeKind::Changed,
+            dojo_core::upgrade::TypeChangeKind::KeyChanged => TypeChangeKind::KeyChanged,
+        }
+    }
+}
+
+/// Structural difference between two versions of a model schema
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct TypeChange {
+    /// Path of the member or variant, e.g. `vec.x`, `dir::Left`, `pair.1` or `items[]`
+    pub path: *const c_char,
+    pub kind: TypeChangeKind,
+    /// Type before the change, none when added
+    pub old_type: COption<Ty>,
+    /// Type after the change, none when removed
+    pub new_type: COption<Ty>,
+}
+
+impl From<dojo_core::upgrade::TypeChange> for TypeChange {
+    fn from(val: dojo_core::upgrade::TypeChange) -> Self {
+        TypeChange {
+            path: c_string(val.path),
+            kind: val.kind.into(),
+            old_type: val.old.into(),
+            new_type: val.new.into(),
+        }
+    }
+}
+
+/// Model registered or upgraded in a world
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct SchemaUpgrade {
+    pub world_address: FieldElement,
+    /// Schema before the upgrade, none when the model was just registered
+    pub old_schema: COption<ModelSchema>,
+    pub new_schema: ModelSchema,
+    /// Structural changes of the schema, empty when only the class of the model changed
+    pub changes: CArray<TypeChange>,
+}
+
+impl From<dojo_core::upgrade::SchemaUpgrade> for SchemaUpgrade {
+    fn from(val: dojo_core::upgrade::SchemaUpgrade) -> Self {
+        SchemaUpgrade {
+            world_address: val.world_address.into(),
+            old_schema: val.old.into(),
+            new_schema: val.new.into(),
+            changes: val.changes.into(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 #[repr(C)]
 pub struct Event {
@@ -1785,13 +2339,13 @@ pub struct AggregationEntry {
 impl From<torii_proto::AggregationEntry> for AggregationEntry {
     fn from(val: torii_proto::AggregationEntry) -> Self {
         AggregationEntry {
-            id: CString::new(val.id.clone()).unwrap().into_raw(),
-            aggregator_id: CString::new(val.aggregator_id.clone()).unwrap().into_raw(),
-            entity_id: CString::new(val.entity_id.clone()).unwrap().into_raw(),
+            id: c_string(val.id.clone()),
+            aggregator_id: c_string(val.aggregator_id.clone()),
+            entity_id: c_string(val.entity_id.clone()),
             value: val.value.into(),
-            display_value: CString::new(val.display_value.clone()).unwrap().into_raw(),
+            display_value: c_string(val.display_value.clone()),
             position: val.position,
-            model_id: CString::new(val.model_id.clone()).unwrap().into_raw(),
+            model_id: c_string(val.model_id.clone()),
             created_at: val.created_at.timestamp() as u64,
             updated_at: val.updated_at.timestamp() as u64,
         }
@@ -1824,16 +2378,13 @@ impl From<torii_proto::Activity> for Activity {
         let actions: Vec<ActionCount> = val
             .actions
             .into_iter()
-            .map(|(name, count)| ActionCount {
-                action_name: CString::new(name).unwrap().into_raw(),
-                count,
-            })
+            .map(|(name, count)| ActionCount { action_name: c_string(name), count })
             .collect();
 
         Activity {
-            id: CString::new(val.id).unwrap().into_raw(),
+            id: c_string(val.id),
             world_address: val.world_address.into(),
-            namespace: CString::new(val.namespace).unwrap().into_raw(),
+            namespace: c_string(val.namespace),
             caller_address: val.caller_address.into(),
             session_start: val.session_start.timestamp() as u64,
             session_end: val.session_end.timestamp() as u64,
@@ -1925,21 +2476,21 @@ impl From<torii_proto::Achievement> for Achievement {
         let tasks: Vec<AchievementTask> = val.tasks.into_iter().map(|t| t.into()).collect();
 
         Achievement {
-            id: CString::new(val.id).unwrap().into_raw(),
+            id: c_string(val.id),
             world_address: val.world_address.into(),
-            namespace: CString::new(val.namespace).unwrap().into_raw(),
-            entity_id: CString::new(val.entity_id).unwrap().into_raw(),
+            namespace: c_string(val.namespace),
+            entity_id: c_string(val.entity_id),
             hidden: val.hidden,
             index: val.index,
             points: val.points,
-            start: CString::new(val.start).unwrap().into_raw(),
-            end: CString::new(val.end).unwrap().into_raw(),
-            group: CString::new(val.group).unwrap().into_raw(),
-            icon: CString::new(val.icon).unwrap().into_raw(),
-            title: CString::new(val.title).unwrap().into_raw(),
-            description: CString::new(val.description).unwrap().into_raw(),
+            start: c_string(val.start),
+            end: c_string(val.end),
+            group: c_string(val.group),
+            icon: c_string(val.icon),
+            title: c_string(val.title),
+            description: c_string(val.description),
             tasks: tasks.into(),
-            data: CString::new(val.data.unwrap_or_default()).unwrap().into_raw(),
+            data: c_string(val.data.unwrap_or_default()),
             total_completions: val.total_completions,
             completion_rate: val.completion_rate,
             created_at: val.created_at.timestamp() as u64,
@@ -1962,8 +2513,8 @@ pub struct AchievementTask {
 impl From<torii_proto::AchievementTask> for AchievementTask {
     fn from(val: torii_proto::AchievementTask) -> Self {
         AchievementTask {
-            task_id: CString::new(val.task_id).unwrap().into_raw(),
-            description: CString::new(val.description).unwrap().into_raw(),
+            task_id: c_string(val.task_id),
+            description: c_string(val.description),
             total: val.total,
             total_completions: val.total_completions,
             completion_rate: val.completion_rate,
@@ -2052,11 +2603,7 @@ pub struct TaskProgress {
 
 impl From<torii_proto::TaskProgress> for TaskProgress {
     fn from(val: torii_proto::TaskProgress) -> Self {
-        TaskProgress {
-            task_id: CString::new(val.task_id).unwrap().into_raw(),
-            count: val.count,
-            completed: val.completed,
-        }
+        TaskProgress { task_id: c_string(val.task_id), count: val.count, completed: val.completed }
     }
 }
 
@@ -2079,11 +2626,11 @@ pub struct AchievementProgression {
 impl From<torii_proto::AchievementProgression> for AchievementProgression {
     fn from(val: torii_proto::AchievementProgression) -> Self {
         AchievementProgression {
-            id: CString::new(val.id).unwrap().into_raw(),
-            achievement_id: CString::new(val.achievement_id).unwrap().into_raw(),
-            task_id: CString::new(val.task_id).unwrap().into_raw(),
+            id: c_string(val.id),
+            achievement_id: c_string(val.achievement_id),
+            task_id: c_string(val.task_id),
             world_address: val.world_address.into(),
-            namespace: CString::new(val.namespace).unwrap().into_raw(),
+            namespace: c_string(val.namespace),
             player_id: val.player_id.into(),
             count: val.count,
             completed: val.completed,
@@ -2129,16 +2676,9 @@ impl From<torii_proto::SearchMatch> for SearchMatch {
         let fields: Vec<SearchMatchField> = val
             .fields
             .into_iter()
-            .map(|(k, v)| SearchMatchField {
-                key: CString::new(k).unwrap().into_raw(),
-                value: CString::new(v).unwrap().into_raw(),
-            })
+            .map(|(k, v)| SearchMatchField { key: c_string(k), value: c_string(v) })
             .collect();
-        Self {
-            id: CString::new(val.id).unwrap().into_raw(),
-            fields: fields.into(),
-            score: val.score.into(),
-        }
+        Self { id: c_string(val.id), fields: fields.into(), score: val.score.into() }
     }
 }
 
@@ -2152,11 +2692,7 @@ pub struct TableSearchResults {
 
 impl From<torii_proto::TableSearchResults> for TableSearchResults {
     fn from(val: torii_proto::TableSearchResults) -> Self {
-        Self {
-            table: CString::new(val.table).unwrap().into_raw(),
-            count: val.count,
-            matches: val.matches.into(),
-        }
+        Self { table: c_string(val.table), count: val.count, matches: val.matches.into() }
     }
 }
 
@@ -2190,6 +2726,82 @@ pub struct Account(
 );
 
 pub struct Subscription {
-    pub id: u64,
     pub(crate) trigger: stream_cancel::Trigger,
+    pub(crate) status: dojo_core::status::SubscriptionStatus,
+    pub(crate) delivery: dojo_core::delivery::DeliveryControl,
+    pub(crate) stream: SubscriptionStream,
+}
+
+/// Stream serving a subscription
+pub(crate) enum SubscriptionStream {
+    /// Stream of its own, following the `Live` id and query of the subscription
+    Own(Box<dyn std::any::Any + Send>),
+    /// Membership of a multiplexed stream, see `ClientConfig::multiplex`
+    Shared { id: u64, lease: Box<dyn std::any::Any + Send> },
+}
+
+pub struct ModelDispatcher(
+    pub(crate) dojo_core::dispatch::Dispatcher<unsafe extern "C" fn(FieldElement, Struct)>,
+);
+
+pub struct OptimisticStore {
+    pub(crate) inner: dojo_core::optimistic::Optimistic,
+    /// Called with entities whose state changed because of a prediction or its settlement
+    pub(crate) callback: unsafe extern "C" fn(Entity),
+}
+
+/// Fetches the page of a query from the active endpoint
+pub(crate) type FetchPage<Q, T> = Box<
+    dyn Fn(Q) -> futures::future::BoxFuture<'static, anyhow::Result<torii_proto::Page<T>>>
+        + Send
+        + Sync,
+>;
+
+/// Walks the pages of a query, see [`dojo_core::pagination::Paginator`]
+pub struct PageIterator<Q, T> {
+    pub(crate) connection: Arc<Connection>,
+    pub(crate) paginator: dojo_core::pagination::Paginator<Q, T, FetchPage<Q, T>>,
+    /// Fetches the next page in the background once a page is returned
+    pub(crate) prefetch: bool,
+}
+
+pub struct EntityIterator {
+    pub(crate) inner: PageIterator<torii_proto::Query, torii_proto::schema::Entity>,
+}
+
+pub struct ControllerIterator {
+    pub(crate) inner: PageIterator<torii_proto::ControllerQuery, torii_proto::Controller>,
+}
+
+pub struct TokenIterator {
+    pub(crate) inner: PageIterator<torii_proto::TokenQuery, torii_proto::Token>,
+}
+
+pub struct TokenBalanceIterator {
+    pub(crate) inner: PageIterator<torii_proto::TokenBalanceQuery, torii_proto::TokenBalance>,
+}
+
+pub struct TokenTransferIterator {
+    pub(crate) inner: PageIterator<torii_proto::TokenTransferQuery, torii_proto::TokenTransfer>,
+}
+
+pub struct TransactionIterator {
+    pub(crate) inner: PageIterator<torii_proto::TransactionQuery, torii_proto::Transaction>,
+}
+
+pub struct ActivityIterator {
+    pub(crate) inner: PageIterator<torii_proto::ActivityQuery, torii_proto::Activity>,
+}
+
+pub struct AchievementIterator {
+    pub(crate) inner: PageIterator<torii_proto::AchievementQuery, torii_proto::Achievement>,
+}
+
+pub struct PlayerAchievementIterator {
+    pub(crate) inner:
+        PageIterator<torii_proto::PlayerAchievementQuery, torii_proto::PlayerAchievementEntry>,
+}
+
+pub struct AggregationIterator {
+    pub(crate) inner: PageIterator<torii_proto::AggregationQuery, torii_proto::AggregationEntry>,
 }
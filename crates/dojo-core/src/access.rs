@@ -0,0 +1,287 @@
+use anyhow::{anyhow, Error, Result};
+use crypto_bigint::U256;
+use dojo_types::primitive::Primitive;
+use dojo_types::schema::Ty;
+use starknet_crypto::Felt;
+
+use crate::validate::{names, plural};
+
+/// Step of a path, see [`get`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Step<'a> {
+    /// Member of a struct, or index of a tuple
+    Member(&'a str),
+    /// Variant of an enum, which must be the selected one
+    Variant(&'a str),
+    /// Element of an array
+    Index(usize),
+}
+
+pub(crate) fn steps(path: &str) -> Result<Vec<Step<'_>>> {
+    let mut steps = Vec::new();
+    if path.is_empty() {
+        return Ok(steps);
+    }
+
+    for segment in path.split('.') {
+        let (head, mut indices) = segment.split_at(segment.find('[').unwrap_or(segment.len()));
+        if segment.is_empty() {
+            return Err(anyhow!("empty segment in `{path}`"));
+        }
+
+        let mut parts = head.split("::");
+        let member = parts.next().unwrap_or_default();
+        if !member.is_empty() {
+            steps.push(Step::Member(member));
+        }
+        for variant in parts {
+            if variant.is_empty() {
+                return Err(anyhow!("empty variant in `{path}`"));
+            }
+            steps.push(Step::Variant(variant));
+        }
+
+        while !indices.is_empty() {
+            let end = indices
+                .find(']')
+                .filter(|_| indices.starts_with('['))
+                .ok_or_else(|| anyhow!("malformed index `{indices}` in `{path}`"))?;
+            let index = indices[1..end]
+                .parse()
+                .map_err(|_| anyhow!("`{}` is not an index in `{path}`", &indices[1..end]))?;
+            steps.push(Step::Index(index));
+            indices = &indices[end + 1..];
+        }
+    }
+
+    Ok(steps)
+}
+
+/// Describes the value at a path in errors.
+pub(crate) fn subject(path: &str) -> String {
+    if path.is_empty() {
+        "the value".to_string()
+    } else {
+        format!("`{path}`")
+    }
+}
+
+/// Type or value at a path within a type, e.g. a member of a model.
+///
+/// Paths are made of struct members and tuple indices separated by dots, enum variants
+/// following `::` and array indices in brackets, e.g. `vec.x`, `dir::Left`, `pair.1` or
+/// `items[2].x`. A variant only resolves when it is the selected one. An empty path is the
+/// type itself.
+pub fn get<'a>(ty: &'a Ty, path: &str) -> Result<&'a Ty> {
+    let mut ty = ty;
+    let mut at = String::new();
+
+    for step in steps(path)? {
+        ty = match (step, ty) {
+            (Step::Member(name), Ty::Struct(s)) => {
+                &s.children
+                    .iter()
+                    .find(|member| member.name == name)
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "{} has no member `{name}`, expected one of {}",
+                            subject(&at),
+                            names(s.children.iter().map(|member| member.name.as_str()))
+                        )
+                    })?
+                    .ty
+            }
+            (Step::Member(name), Ty::Tuple(values)) => {
+                name.parse::<usize>().ok().and_then(|index| values.get(index)).ok_or_else(|| {
+                    anyhow!(
+                        "{} is a tuple of {} element{}, `{name}` is not an index",
+                        subject(&at),
+                        values.len(),
+                        plural(values.len())
+                    )
+                })?
+            }
+            (Step::Member(_), ty) => {
+                return Err(anyhow!("{} is a {}, it has no members", subject(&at), ty.name()));
+            }
+            (Step::Variant(name), Ty::Enum(e)) => {
+                if !e.options.iter().any(|option| option.name == name) {
+                    return Err(anyhow!(
+                        "{} has no variant `{name}`, expected one of {}",
+                        subject(&at),
+                        names(e.options.iter().map(|option| option.name.as_str()))
+                    ));
+                }
+                match e.option.and_then(|i| e.options.get(usize::from(i))) {
+                    Some(option) if option.name == name => &option.ty,
+                    Some(option) => {
+                        return Err(anyhow!("{} is `{}`, not `{name}`", subject(&at), option.name));
+                    }
+                    None => return Err(anyhow!("{} has no value", subject(&at))),
+                }
+            }
+            (Step::Variant(_), ty) => {
+                return Err(anyhow!("{} is a {}, not an enum", subject(&at), ty.name()));
+            }
+            (Step::Index(index), Ty::Array(values) | Ty::FixedSizeArray((values, _))) => {
+                values.get(index).ok_or_else(|| {
+                    anyhow!(
+                        "index {index} is out of bounds, {} has {} element{}",
+                        subject(&at),
+                        values.len(),
+                        plural(values.len())
+                    )
+                })?
+            }
+            (Step::Index(_), ty) => {
+                return Err(anyhow!("{} is a {}, not an array", subject(&at), ty.name()));
+            }
+        };
+
+        at = join(&at, step);
+    }
+
+    Ok(ty)
+}
+
+/// Path of the value reached by a step from the value at `at`.
+pub(crate) fn join(at: &str, step: Step<'_>) -> String {
+    match step {
+        Step::Member(name) if at.is_empty() => name.to_string(),
+        Step::Member(name) => format!("{at}.{name}"),
+        Step::Variant(name) => format!("{at}::{name}"),
+        Step::Index(index) => format!("{at}[{index}]"),
+    }
+}
+
+pub(crate) fn mismatch(path: &str, ty: &Ty, expected: &str) -> Error {
+    anyhow!("{} is a {}, not a {expected}", subject(path), ty.name())
+}
+
+pub(crate) fn unset(path: &str) -> Error {
+    anyhow!("{} has no value", subject(path))
+}
+
+pub fn get_u32(ty: &Ty, path: &str) -> Result<u32> {
+    match get(ty, path)? {
+        Ty::Primitive(Primitive::U32(value)) => value.ok_or_else(|| unset(path)),
+        ty => Err(mismatch(path, ty, "u32")),
+    }
+}
+
+/// Felt of a `felt252`, `ContractAddress`, `ClassHash` or `EthAddress`.
+pub fn get_felt(ty: &Ty, path: &str) -> Result<Felt> {
+    match get(ty, path)? {
+        Ty::Primitive(
+            Primitive::Felt252(value)
+            | Primitive::ContractAddress(value)
+            | Primitive::ClassHash(value)
+            | Primitive::EthAddress(value),
+        ) => value.ok_or_else(|| unset(path)),
+        ty => Err(mismatch(path, ty, "felt252")),
+    }
+}
+
+pub fn get_u256(ty: &Ty, path: &str) -> Result<U256> {
+    match get(ty, path)? {
+        Ty::Primitive(Primitive::U256(value)) => value.ok_or_else(|| unset(path)),
+        ty => Err(mismatch(path, ty, "u256")),
+    }
+}
+
+pub fn get_bool(ty: &Ty, path: &str) -> Result<bool> {
+    match get(ty, path)? {
+        Ty::Primitive(Primitive::Bool(value)) => value.ok_or_else(|| unset(path)),
+        ty => Err(mismatch(path, ty, "bool")),
+    }
+}
+
+pub fn get_byte_array(ty: &Ty, path: &str) -> Result<String> {
+    match get(ty, path)? {
+        Ty::ByteArray(value) => Ok(value.clone()),
+        ty => Err(mismatch(path, ty, "ByteArray")),
+    }
+}
+
+/// Name of the selected variant of an enum.
+pub fn get_variant(ty: &Ty, path: &str) -> Result<String> {
+    match get(ty, path)? {
+        Ty::Enum(e) => e
+            .option
+            .and_then(|i| e.options.get(usize::from(i)))
+            .map(|option| option.name.clone())
+            .ok_or_else(|| unset(path)),
+        ty => Err(mismatch(path, ty, "enum")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_types::schema::{Enum, EnumOption, Member, Struct};
+
+    use super::*;
+
+    fn member(name: &str, ty: Ty) -> Member {
+        Member { name: name.to_string(), ty, key: false }
+    }
+
+    fn model() -> Ty {
+        let vec = Ty::Struct(Struct {
+            name: "Vec2".to_string(),
+            children: vec![
+                member("x", Ty::Primitive(Primitive::U32(Some(3)))),
+                member("y", Ty::Primitive(Primitive::U32(None))),
+            ],
+        });
+        let dir = Ty::Enum(Enum {
+            name: "Direction".to_string(),
+            option: Some(1),
+            options: vec![
+                EnumOption { name: "Left".to_string(), ty: Ty::Tuple(vec![]) },
+                EnumOption {
+                    name: "Jump".to_string(),
+                    ty: Ty::Primitive(Primitive::Bool(Some(true))),
+                },
+            ],
+        });
+        Ty::Struct(Struct {
+            name: "ns-Position".to_string(),
+            children: vec![
+                member("player", Ty::Primitive(Primitive::ContractAddress(Some(Felt::TWO)))),
+                member("vec", vec.clone()),
+                member("dir", dir),
+                member("pair", Ty::Tuple(vec![Ty::ByteArray("name".to_string()), vec.clone()])),
+                member("path", Ty::Array(vec![vec.clone(), vec])),
+                member("score", Ty::Primitive(Primitive::U256(Some(U256::from_u8(7))))),
+            ],
+        })
+    }
+
+    #[test]
+    fn gets_values_by_path() {
+        let model = model();
+
+        assert_eq!(get_u32(&model, "vec.x").unwrap(), 3);
+        assert_eq!(get_felt(&model, "player").unwrap(), Felt::TWO);
+        assert!(get_bool(&model, "dir::Jump").unwrap());
+        assert_eq!(get_variant(&model, "dir").unwrap(), "Jump");
+        assert_eq!(get_byte_array(&model, "pair.0").unwrap(), "name");
+        assert_eq!(get_u32(&model, "pair.1.x").unwrap(), 3);
+        assert_eq!(get_u32(&model, "path[1].x").unwrap(), 3);
+        assert_eq!(get_u256(&model, "score").unwrap(), U256::from_u8(7));
+        assert_eq!(get(&model, "").unwrap(), &model);
+
+        let error = |path| get(&model, path).unwrap_err().to_string();
+        assert_eq!(error("vec.z"), "`vec` has no member `z`, expected one of `x`, `y`");
+        assert_eq!(error("dir::Left"), "`dir` is `Jump`, not `Left`");
+        assert_eq!(error("dir::Up"), "`dir` has no variant `Up`, expected one of `Left`, `Jump`");
+        assert_eq!(error("path[2]"), "index 2 is out of bounds, `path` has 2 elements");
+        assert_eq!(error("vec[0]"), "`vec` is a Vec2, not an array");
+        assert_eq!(error("vec..x"), "empty segment in `vec..x`");
+        assert_eq!(get_u32(&model, "vec.y").unwrap_err().to_string(), "`vec.y` has no value");
+        assert_eq!(
+            get_felt(&model, "vec.x").unwrap_err().to_string(),
+            "`vec.x` is a u32, not a felt252"
+        );
+    }
+}
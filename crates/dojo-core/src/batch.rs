@@ -0,0 +1,227 @@
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+
+use anyhow::Result;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use starknet_crypto::{poseidon_hash_many, Felt};
+use torii_proto::schema::Entity;
+use torii_proto::{Clause, Page, Pagination, PaginationDirection, Query};
+
+/// Maximum number of entities requested at once.
+pub const CHUNK_SIZE: usize = 100;
+
+/// Maximum number of chunks requested concurrently.
+pub const CONCURRENCY: usize = 4;
+
+/// Bytes budgeted per entity of a response, enough for a few models and their members.
+const ENTITY_SIZE: usize = 16 * 1024;
+
+/// Entities looked up by id, in the order of the requested ids. Entities Torii doesn't know
+/// come with `None`.
+pub type Lookup = Vec<(Felt, Option<Entity>)>;
+
+/// Number of ids requested at once so that responses fit in `max_message_size`, see
+/// [`ClientConfig::max_message_size`](crate::config::ClientConfig::max_message_size).
+pub fn chunk_size(max_message_size: usize) -> usize {
+    (max_message_size / ENTITY_SIZE).clamp(1, CHUNK_SIZE)
+}
+
+/// Fetches entities by hashed keys, in chunks of ids sized from `max_message_size` and
+/// running concurrently.
+///
+/// `fetch` requests a query from the active endpoint. Only the given models are returned, or
+/// every model when empty. An id may match an entity in each of the worlds, so every page of a
+/// chunk is fetched, and an id known to several worlds comes with the entity of the first one
+/// listed.
+pub async fn entities_by_ids<F, Fut>(
+    world_addresses: &[Felt],
+    ids: &[Felt],
+    models: Vec<String>,
+    max_message_size: usize,
+    fetch: F,
+) -> Result<Lookup>
+where
+    F: Fn(Query) -> Fut,
+    Fut: Future<Output = Result<Page<Entity>>>,
+{
+    let mut seen = HashSet::new();
+    let unique = ids.iter().copied().filter(|id| seen.insert(*id)).collect::<Vec<_>>();
+    let queries = unique.chunks(chunk_size(max_message_size)).map(|chunk| Query {
+        world_addresses: world_addresses.to_vec(),
+        pagination: Pagination {
+            cursor: None,
+            limit: Some(chunk.len() as u32),
+            direction: PaginationDirection::Forward,
+            order_by: vec![],
+        },
+        clause: Some(Clause::HashedKeys(chunk.to_vec())),
+        no_hashed_keys: false,
+        models: models.clone(),
+        historical: false,
+    });
+    let fetch_all = |mut query: Query| {
+        let fetch = &fetch;
+        async move {
+            let mut entities = Vec::new();
+            loop {
+                let page = fetch(query.clone()).await?;
+                let last = page.items.is_empty();
+                entities.extend(page.items);
+                match page.next_cursor {
+                    Some(cursor) if !last => query.pagination.cursor = Some(cursor),
+                    _ => return Ok::<_, anyhow::Error>(entities),
+                }
+            }
+        }
+    };
+
+    let chunks = stream::iter(queries)
+        .map(fetch_all)
+        .buffer_unordered(CONCURRENCY)
+        .try_collect::<Vec<_>>()
+        .await?;
+    let rank =
+        |entity: &Entity| world_addresses.iter().position(|world| *world == entity.world_address);
+    let mut found = HashMap::<Felt, Entity>::new();
+    for entity in chunks.into_iter().flatten() {
+        let kept = found.get(&entity.hashed_keys);
+        if kept.is_none_or(|kept| rank(kept) > rank(&entity)) {
+            found.insert(entity.hashed_keys, entity);
+        }
+    }
+
+    Ok(ids.iter().map(|id| (*id, found.get(id).cloned())).collect())
+}
+
+/// Fetches a model of entities by keys, the id of an entity being the Poseidon hash of its
+/// keys. Entities without the model come with `None`, like unknown entities.
+pub async fn entities_by_keys<F, Fut>(
+    world_addresses: &[Felt],
+    model: &str,
+    keys: &[Vec<Felt>],
+    max_message_size: usize,
+    fetch: F,
+) -> Result<Lookup>
+where
+    F: Fn(Query) -> Fut,
+    Fut: Future<Output = Result<Page<Entity>>>,
+{
+    let ids = keys.iter().map(|keys| poseidon_hash_many(keys)).collect::<Vec<_>>();
+    let models = vec![model.to_string()];
+    let mut lookup =
+        entities_by_ids(world_addresses, &ids, models, max_message_size, fetch).await?;
+    for (_, entity) in &mut lookup {
+        if entity.as_ref().is_some_and(|e| !e.models.iter().any(|m| m.name == model)) {
+            *entity = None;
+        }
+    }
+    Ok(lookup)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use dojo_types::schema::Struct;
+    use futures::executor::block_on;
+
+    use super::*;
+
+    const MAX_MESSAGE_SIZE: usize = crate::config::DEFAULT_MAX_MESSAGE_SIZE;
+
+    fn is_even(id: &Felt) -> bool {
+        id.to_bytes_be()[31] % 2 == 0
+    }
+
+    fn entity(world_address: Felt, hashed_keys: Felt) -> Entity {
+        Entity {
+            world_address,
+            hashed_keys,
+            models: vec![Struct { name: "ns-Position".to_string(), children: vec![] }],
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            executed_at: Default::default(),
+        }
+    }
+
+    /// Entities with an even id exist in every world and have a `ns-Position` model. Pages
+    /// hold at most `limit` entities, the last world listed coming first.
+    fn fetch(
+        requests: Arc<Mutex<usize>>,
+    ) -> impl Fn(Query) -> futures::future::Ready<Result<Page<Entity>>> {
+        move |query| {
+            *requests.lock().unwrap() += 1;
+            let Some(Clause::HashedKeys(ids)) = query.clause else { panic!() };
+            let limit = query.pagination.limit.unwrap() as usize;
+            assert!(ids.len() <= limit);
+            let worlds = match query.world_addresses.as_slice() {
+                [] => vec![Felt::ONE],
+                worlds => worlds.to_vec(),
+            };
+            let items = ids
+                .into_iter()
+                .filter(|id| is_even(id))
+                .flat_map(|hashed_keys| {
+                    worlds
+                        .iter()
+                        .rev()
+                        .map(move |world_address| entity(*world_address, hashed_keys))
+                })
+                .collect::<Vec<_>>();
+
+            let offset = query.pagination.cursor.map_or(0, |cursor| cursor.parse().unwrap());
+            let end = (offset + limit).min(items.len());
+            let next_cursor = (end < items.len()).then(|| end.to_string());
+            let items = items[offset..end].to_vec();
+            futures::future::ready(Ok(Page { items, next_cursor }))
+        }
+    }
+
+    #[test]
+    fn looks_up_entities_in_chunks() {
+        let requests = Arc::new(Mutex::new(0));
+        let mut ids = (0..250u64).map(Felt::from).collect::<Vec<_>>();
+        ids.push(Felt::from(4u8));
+
+        let lookup =
+            block_on(entities_by_ids(&[], &ids, vec![], MAX_MESSAGE_SIZE, fetch(requests.clone())))
+                .unwrap();
+        assert_eq!(*requests.lock().unwrap(), 3);
+        assert_eq!(lookup.len(), 251);
+        for (id, entity) in &lookup {
+            assert_eq!(entity.as_ref().map(|e| e.hashed_keys), is_even(id).then_some(*id));
+        }
+
+        // Entities found without the requested model are misses
+        let keys = vec![vec![Felt::ONE], vec![Felt::TWO]];
+        let lookup =
+            block_on(entities_by_keys(&[], "ns-Moves", &keys, MAX_MESSAGE_SIZE, fetch(requests)))
+                .unwrap();
+        assert_eq!(lookup[0].0, poseidon_hash_many(&[Felt::ONE]));
+        assert!(lookup.iter().all(|(_, entity)| entity.is_none()));
+    }
+
+    #[test]
+    fn sizes_chunks_and_pages_through_worlds() {
+        assert_eq!(chunk_size(MAX_MESSAGE_SIZE), CHUNK_SIZE);
+        assert_eq!(chunk_size(10 * ENTITY_SIZE), 10);
+        assert_eq!(chunk_size(0), 1);
+
+        // Even ids are known to every world, so each chunk of 10 ids matches 15 entities
+        let requests = Arc::new(Mutex::new(0));
+        let worlds = [Felt::THREE, Felt::TWO, Felt::ONE];
+        let ids = (0..20u64).map(Felt::from).collect::<Vec<_>>();
+        let lookup = block_on(entities_by_ids(
+            &worlds,
+            &ids,
+            vec![],
+            10 * ENTITY_SIZE,
+            fetch(requests.clone()),
+        ))
+        .unwrap();
+        assert_eq!(*requests.lock().unwrap(), 4);
+        for (id, entity) in &lookup {
+            assert_eq!(entity.as_ref().map(|e| e.world_address), is_even(id).then_some(worlds[0]));
+        }
+    }
+}
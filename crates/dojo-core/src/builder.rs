@@ -0,0 +1,549 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use crypto_bigint::{CheckedAdd, CheckedMul, U256};
+use dojo_types::primitive::Primitive;
+use dojo_types::schema::{Enum, EnumOption, Member, Struct, Ty};
+use serde_json::{Map, Value};
+use starknet_crypto::Felt;
+
+use crate::access::{join, mismatch, steps, subject, unset, Step};
+use crate::clause::with_default;
+use crate::validate::{names, plural};
+
+/// Value of a type built from its schema, e.g. a model to publish in an offchain message or
+/// an argument of a call.
+///
+/// The value starts from defaults: zero for primitives not set in the schema, the first
+/// variant of enums, an empty string for byte arrays, no elements for arrays and default
+/// elements for fixed size arrays. Values are set by path as in [`get`](crate::access::get)
+/// and checked against the schema. A variant in a path selects it, and the index following
+/// the last element of an array appends an element. A setter that fails leaves the value
+/// unchanged.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TyBuilder {
+    schema: Ty,
+    value: Ty,
+}
+
+impl TyBuilder {
+    pub fn new(schema: &Ty) -> Self {
+        Self { schema: schema.clone(), value: default_value(schema) }
+    }
+
+    pub fn schema(&self) -> &Ty {
+        &self.schema
+    }
+
+    /// Value built so far.
+    pub fn ty(&self) -> &Ty {
+        &self.value
+    }
+
+    pub fn build(self) -> Ty {
+        self.value
+    }
+
+    /// Sets the value at a path, which must have the type of the schema at that path.
+    pub fn set(&mut self, path: &str, value: Ty) -> Result<()> {
+        self.update(path, |schema, slot| {
+            check(path, schema, &value, true)?;
+            *slot = value;
+            Ok(())
+        })
+    }
+
+    pub fn set_u32(&mut self, path: &str, value: u32) -> Result<()> {
+        self.set(path, Ty::Primitive(Primitive::U32(Some(value))))
+    }
+
+    /// Sets a `felt252`, `ContractAddress`, `ClassHash` or `EthAddress`.
+    pub fn set_felt(&mut self, path: &str, value: Felt) -> Result<()> {
+        self.update(path, |schema, slot| {
+            *slot = Ty::Primitive(match schema {
+                Ty::Primitive(Primitive::Felt252(_)) => Primitive::Felt252(Some(value)),
+                Ty::Primitive(Primitive::ContractAddress(_)) => {
+                    Primitive::ContractAddress(Some(value))
+                }
+                Ty::Primitive(Primitive::ClassHash(_)) => Primitive::ClassHash(Some(value)),
+                Ty::Primitive(Primitive::EthAddress(_)) => Primitive::EthAddress(Some(value)),
+                ty => return Err(mismatch(path, ty, "felt252")),
+            });
+            Ok(())
+        })
+    }
+
+    pub fn set_u256(&mut self, path: &str, value: U256) -> Result<()> {
+        self.set(path, Ty::Primitive(Primitive::U256(Some(value))))
+    }
+
+    pub fn set_bool(&mut self, path: &str, value: bool) -> Result<()> {
+        self.set(path, Ty::Primitive(Primitive::Bool(Some(value))))
+    }
+
+    pub fn set_byte_array(&mut self, path: &str, value: &str) -> Result<()> {
+        self.set(path, Ty::ByteArray(value.to_string()))
+    }
+
+    /// Selects a variant of the enum at a path, keeping the value of the variant.
+    pub fn set_variant(&mut self, path: &str, variant: &str) -> Result<()> {
+        self.update(&format!("{path}::{variant}"), |_, _| Ok(()))
+    }
+
+    /// Sets a primitive or a byte array from text: decimal or `0x` hexadecimal for numbers,
+    /// `true` or `false` for booleans.
+    pub fn set_from_str(&mut self, path: &str, text: &str) -> Result<()> {
+        self.update(path, |schema, slot| {
+            *slot = parse(path, schema, text)?;
+            Ok(())
+        })
+    }
+
+    /// Value as JSON: structs as objects, unit variants as their name and other variants as
+    /// an object of their name and value, tuples and arrays as arrays, byte arrays as strings
+    /// and primitives as in the models of Torii.
+    pub fn to_json(&self) -> Result<Value> {
+        to_json("", &self.value)
+    }
+
+    /// Value serialized as Cairo calldata.
+    pub fn calldata(&self) -> Result<Vec<Felt>> {
+        self.value.serialize().map_err(|e| anyhow!("failed to serialize value: {e}"))
+    }
+
+    fn update(&mut self, path: &str, f: impl FnOnce(&Ty, &mut Ty) -> Result<()>) -> Result<()> {
+        let mut value = self.value.clone();
+        let (schema, slot) = slot(&self.schema, &mut value, path)?;
+        f(schema, slot)?;
+        self.value = value;
+        Ok(())
+    }
+}
+
+fn default_value(schema: &Ty) -> Ty {
+    match schema {
+        Ty::Primitive(primitive) => Ty::Primitive(with_default(primitive)),
+        Ty::Struct(s) => Ty::Struct(Struct {
+            name: s.name.clone(),
+            children: s
+                .children
+                .iter()
+                .map(|member| Member {
+                    name: member.name.clone(),
+                    ty: default_value(&member.ty),
+                    key: member.key,
+                })
+                .collect(),
+        }),
+        Ty::Enum(e) => Ty::Enum(Enum {
+            name: e.name.clone(),
+            option: (!e.options.is_empty()).then_some(0),
+            options: e
+                .options
+                .iter()
+                .map(|option| EnumOption {
+                    name: option.name.clone(),
+                    ty: default_value(&option.ty),
+                })
+                .collect(),
+        }),
+        Ty::Tuple(values) => Ty::Tuple(values.iter().map(default_value).collect()),
+        Ty::Array(_) => Ty::Array(vec![]),
+        Ty::FixedSizeArray((values, size)) => Ty::FixedSizeArray((
+            values.first().map(|ty| vec![default_value(ty); *size as usize]).unwrap_or_default(),
+            *size,
+        )),
+        Ty::ByteArray(_) => Ty::ByteArray(String::new()),
+    }
+}
+
+/// Schema and value at a path, selecting the variants and appending the elements of arrays on
+/// the way.
+fn slot<'a>(schema: &'a Ty, value: &'a mut Ty, path: &str) -> Result<(&'a Ty, &'a mut Ty)> {
+    let (mut schema, mut value) = (schema, value);
+    let mut at = String::new();
+
+    for step in steps(path)? {
+        (schema, value) = match (step, schema, value) {
+            (Step::Member(name), Ty::Struct(s), Ty::Struct(v)) => {
+                let i =
+                    s.children.iter().position(|member| member.name == name).ok_or_else(|| {
+                        anyhow!(
+                            "{} has no member `{name}`, expected one of {}",
+                            subject(&at),
+                            names(s.children.iter().map(|member| member.name.as_str()))
+                        )
+                    })?;
+                (&s.children[i].ty, &mut v.children[i].ty)
+            }
+            (Step::Member(name), Ty::Tuple(s), Ty::Tuple(v)) => {
+                let i = name.parse::<usize>().ok().filter(|i| *i < s.len()).ok_or_else(|| {
+                    anyhow!(
+                        "{} is a tuple of {} element{}, `{name}` is not an index",
+                        subject(&at),
+                        s.len(),
+                        plural(s.len())
+                    )
+                })?;
+                (&s[i], &mut v[i])
+            }
+            (Step::Member(_), ty, _) => {
+                return Err(anyhow!("{} is a {}, it has no members", subject(&at), ty.name()));
+            }
+            (Step::Variant(name), Ty::Enum(s), Ty::Enum(v)) => {
+                let i =
+                    s.options.iter().position(|option| option.name == name).ok_or_else(|| {
+                        anyhow!(
+                            "{} has no variant `{name}`, expected one of {}",
+                            subject(&at),
+                            names(s.options.iter().map(|option| option.name.as_str()))
+                        )
+                    })?;
+                v.option = Some(i as u8);
+                (&s.options[i].ty, &mut v.options[i].ty)
+            }
+            (Step::Variant(_), ty, _) => {
+                return Err(anyhow!("{} is a {}, not an enum", subject(&at), ty.name()));
+            }
+            (Step::Index(index), Ty::Array(s), Ty::Array(v)) => {
+                let template =
+                    s.first().ok_or_else(|| anyhow!("{} has no element type", subject(&at)))?;
+                match index.cmp(&v.len()) {
+                    Ordering::Less => {}
+                    Ordering::Equal => v.push(default_value(template)),
+                    Ordering::Greater => {
+                        return Err(anyhow!(
+                            "index {index} is out of bounds, {} has {} element{}",
+                            subject(&at),
+                            v.len(),
+                            plural(v.len())
+                        ));
+                    }
+                }
+                (template, &mut v[index])
+            }
+            (Step::Index(index), Ty::FixedSizeArray((s, _)), Ty::FixedSizeArray((v, _))) => {
+                let template =
+                    s.first().ok_or_else(|| anyhow!("{} has no element type", subject(&at)))?;
+                if index >= v.len() {
+                    return Err(anyhow!(
+                        "index {index} is out of bounds, {} has {} element{}",
+                        subject(&at),
+                        v.len(),
+                        plural(v.len())
+                    ));
+                }
+                (template, &mut v[index])
+            }
+            (Step::Index(_), ty, _) => {
+                return Err(anyhow!("{} is a {}, not an array", subject(&at), ty.name()));
+            }
+        };
+
+        at = join(&at, step);
+    }
+
+    Ok((schema, value))
+}
+
+/// Checks that a value has the type of a schema. Primitives must have a value when
+/// `required`, which only holds for the selected variant of enums.
+fn check(path: &str, schema: &Ty, value: &Ty, required: bool) -> Result<()> {
+    match (schema, value) {
+        (Ty::Primitive(_), Ty::Primitive(primitive)) if schema.name() == value.name() => {
+            if required && with_default(primitive) != *primitive {
+                return Err(unset(path));
+            }
+        }
+        (Ty::Struct(s), Ty::Struct(v)) if s.name == v.name => {
+            let expected = s.children.iter().map(|member| member.name.as_str());
+            if !expected.clone().eq(v.children.iter().map(|member| member.name.as_str())) {
+                return Err(anyhow!(
+                    "{} has members {}, expected {}",
+                    subject(path),
+                    names(v.children.iter().map(|member| member.name.as_str())),
+                    names(expected)
+                ));
+            }
+            for (s, v) in s.children.iter().zip(&v.children) {
+                check(&join(path, Step::Member(&s.name)), &s.ty, &v.ty, required)?;
+            }
+        }
+        (Ty::Enum(s), Ty::Enum(v)) if s.name == v.name => {
+            let expected = s.options.iter().map(|option| option.name.as_str());
+            if !expected.clone().eq(v.options.iter().map(|option| option.name.as_str())) {
+                return Err(anyhow!(
+                    "{} has variants {}, expected {}",
+                    subject(path),
+                    names(v.options.iter().map(|option| option.name.as_str())),
+                    names(expected)
+                ));
+            }
+            let selected = v.option.map(usize::from).filter(|i| *i < v.options.len());
+            if required && selected.is_none() {
+                return Err(unset(path));
+            }
+            for (i, (s, v)) in s.options.iter().zip(&v.options).enumerate() {
+                let path = join(path, Step::Variant(&s.name));
+                check(&path, &s.ty, &v.ty, required && selected == Some(i))?;
+            }
+        }
+        (Ty::Tuple(s), Ty::Tuple(v)) => {
+            if s.len() != v.len() {
+                return Err(anyhow!(
+                    "{} is a tuple of {} element{}, expected {}",
+                    subject(path),
+                    v.len(),
+                    plural(v.len()),
+                    s.len()
+                ));
+            }
+            for (i, (s, v)) in s.iter().zip(v).enumerate() {
+                check(&join(path, Step::Member(&i.to_string())), s, v, required)?;
+            }
+        }
+        (Ty::Array(s), Ty::Array(v)) | (Ty::FixedSizeArray((s, _)), Ty::FixedSizeArray((v, _))) => {
+            if let Ty::FixedSizeArray((_, size)) = schema {
+                if v.len() != *size as usize {
+                    return Err(anyhow!(
+                        "{} has {} element{}, expected {size}",
+                        subject(path),
+                        v.len(),
+                        plural(v.len())
+                    ));
+                }
+            }
+            for (i, v) in v.iter().enumerate() {
+                let path = join(path, Step::Index(i));
+                let template = s.first().ok_or_else(|| mismatch(&path, v, "element"))?;
+                check(&path, template, v, required)?;
+            }
+        }
+        (Ty::ByteArray(_), Ty::ByteArray(_)) => {}
+        _ => return Err(mismatch(path, value, &schema.name())),
+    }
+    Ok(())
+}
+
+fn parse(path: &str, schema: &Ty, text: &str) -> Result<Ty> {
+    let invalid = || anyhow!("`{text}` is not a valid {} for {}", schema.name(), subject(path));
+    let primitive = match schema {
+        Ty::Primitive(primitive) => primitive,
+        Ty::ByteArray(_) => return Ok(Ty::ByteArray(text.to_string())),
+        ty => return Err(mismatch(path, ty, "primitive")),
+    };
+
+    Ok(Ty::Primitive(match primitive {
+        Primitive::I8(_) => Primitive::I8(Some(int(text).ok_or_else(invalid)?)),
+        Primitive::I16(_) => Primitive::I16(Some(int(text).ok_or_else(invalid)?)),
+        Primitive::I32(_) => Primitive::I32(Some(int(text).ok_or_else(invalid)?)),
+        Primitive::I64(_) => Primitive::I64(Some(int(text).ok_or_else(invalid)?)),
+        Primitive::I128(_) => Primitive::I128(Some(int(text).ok_or_else(invalid)?)),
+        Primitive::U8(_) => Primitive::U8(Some(int(text).ok_or_else(invalid)?)),
+        Primitive::U16(_) => Primitive::U16(Some(int(text).ok_or_else(invalid)?)),
+        Primitive::U32(_) => Primitive::U32(Some(int(text).ok_or_else(invalid)?)),
+        Primitive::U64(_) => Primitive::U64(Some(int(text).ok_or_else(invalid)?)),
+        Primitive::U128(_) => Primitive::U128(Some(uint(text).ok_or_else(invalid)?)),
+        Primitive::U256(_) => Primitive::U256(Some(u256(text).ok_or_else(invalid)?)),
+        Primitive::Bool(_) => Primitive::Bool(Some(text.parse().map_err(|_| invalid())?)),
+        Primitive::Felt252(_) => Primitive::Felt252(Some(felt(text).ok_or_else(invalid)?)),
+        Primitive::ClassHash(_) => Primitive::ClassHash(Some(felt(text).ok_or_else(invalid)?)),
+        Primitive::ContractAddress(_) => {
+            Primitive::ContractAddress(Some(felt(text).ok_or_else(invalid)?))
+        }
+        Primitive::EthAddress(_) => Primitive::EthAddress(Some(felt(text).ok_or_else(invalid)?)),
+    }))
+}
+
+fn int<T: TryFrom<i128>>(text: &str) -> Option<T> {
+    let value = match text.strip_prefix("0x") {
+        Some(hex) => i128::from_str_radix(hex, 16).ok()?,
+        None => text.parse().ok()?,
+    };
+    T::try_from(value).ok()
+}
+
+fn uint(text: &str) -> Option<u128> {
+    match text.strip_prefix("0x") {
+        Some(hex) => u128::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+fn u256(text: &str) -> Option<U256> {
+    match text.strip_prefix("0x") {
+        Some(hex) if !hex.is_empty() && hex.len() <= 64 => hex
+            .bytes()
+            .all(|b| b.is_ascii_hexdigit())
+            .then(|| U256::from_be_hex(&format!("{hex:0>64}"))),
+        Some(_) => None,
+        None => u256_from_dec(text),
+    }
+}
+
+/// Decimal digits as a `u256`, `None` if there are none or they overflow it.
+pub(crate) fn u256_from_dec(digits: &str) -> Option<U256> {
+    if digits.is_empty() {
+        return None;
+    }
+    digits.bytes().try_fold(U256::ZERO, |value, digit| {
+        let digit = U256::from_u8(char::from(digit).to_digit(10)? as u8);
+        let value = Option::<U256>::from(value.checked_mul(&U256::from_u8(10)))?;
+        Option::from(value.checked_add(&digit))
+    })
+}
+
+fn felt(text: &str) -> Option<Felt> {
+    Felt::from_str(text).ok()
+}
+
+fn to_json(path: &str, ty: &Ty) -> Result<Value> {
+    Ok(match ty {
+        Ty::Primitive(primitive) => {
+            if with_default(primitive) != *primitive {
+                return Err(unset(path));
+            }
+            primitive.to_json_value().map_err(|e| anyhow!("{}: {e}", subject(path)))?
+        }
+        Ty::Struct(s) => Value::Object(
+            s.children
+                .iter()
+                .map(|member| {
+                    let value = to_json(&join(path, Step::Member(&member.name)), &member.ty)?;
+                    Ok((member.name.clone(), value))
+                })
+                .collect::<Result<Map<_, _>>>()?,
+        ),
+        Ty::Enum(e) => {
+            let option =
+                e.option.and_then(|i| e.options.get(usize::from(i))).ok_or_else(|| unset(path))?;
+            match &option.ty {
+                Ty::Tuple(values) if values.is_empty() => Value::String(option.name.clone()),
+                ty => {
+                    let value = to_json(&join(path, Step::Variant(&option.name)), ty)?;
+                    Value::Object(Map::from_iter([(option.name.clone(), value)]))
+                }
+            }
+        }
+        Ty::Tuple(values) => Value::Array(
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, ty)| to_json(&join(path, Step::Member(&i.to_string())), ty))
+                .collect::<Result<_>>()?,
+        ),
+        Ty::Array(values) | Ty::FixedSizeArray((values, _)) => Value::Array(
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, ty)| to_json(&join(path, Step::Index(i)), ty))
+                .collect::<Result<_>>()?,
+        ),
+        Ty::ByteArray(value) => Value::String(value.clone()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn member(name: &str, ty: Ty) -> Member {
+        Member { name: name.to_string(), ty, key: false }
+    }
+
+    fn schema() -> Ty {
+        let vec = Ty::Struct(Struct {
+            name: "Vec2".to_string(),
+            children: vec![
+                member("x", Ty::Primitive(Primitive::U32(None))),
+                member("y", Ty::Primitive(Primitive::U32(None))),
+            ],
+        });
+        let dir = Ty::Enum(Enum {
+            name: "Direction".to_string(),
+            option: None,
+            options: vec![
+                EnumOption { name: "Left".to_string(), ty: Ty::Tuple(vec![]) },
+                EnumOption { name: "Jump".to_string(), ty: Ty::Primitive(Primitive::U8(None)) },
+            ],
+        });
+        Ty::Struct(Struct {
+            name: "ns-Position".to_string(),
+            children: vec![
+                member("player", Ty::Primitive(Primitive::ContractAddress(None))),
+                member("vec", vec.clone()),
+                member("dir", dir),
+                member("path", Ty::Array(vec![vec])),
+                member("name", Ty::ByteArray(String::new())),
+            ],
+        })
+    }
+
+    #[test]
+    fn builds_values_from_schema() {
+        let mut builder = TyBuilder::new(&schema());
+        let json = builder.to_json().unwrap();
+        assert_eq!(
+            (&json["dir"], &json["path"], &json["name"]),
+            (&json!("Left"), &json!([]), &json!(""))
+        );
+
+        builder.set_felt("player", Felt::TWO).unwrap();
+        builder.set_u32("vec.x", 3).unwrap();
+        builder.set_from_str("dir::Jump", "0x10").unwrap();
+        builder.set_u32("path[0].y", 5).unwrap();
+        builder.set_byte_array("name", "dojo").unwrap();
+        assert_eq!(crate::access::get_u32(builder.ty(), "path[0].y").unwrap(), 5);
+        assert_eq!(crate::access::get_variant(builder.ty(), "dir").unwrap(), "Jump");
+
+        let error = |result: Result<()>| result.unwrap_err().to_string();
+        let mut failed = builder.clone();
+        assert_eq!(
+            error(failed.set_u32("path[2].x", 1)),
+            "index 2 is out of bounds, `path` has 1 element"
+        );
+        assert_eq!(error(failed.set_u32("player", 1)), "`player` is a u32, not a ContractAddress");
+        assert_eq!(error(failed.set_felt("vec.x", Felt::ONE)), "`vec.x` is a u32, not a felt252");
+        assert_eq!(
+            error(failed.set_variant("dir", "Up")),
+            "`dir` has no variant `Up`, expected one of `Left`, `Jump`"
+        );
+        assert_eq!(
+            error(failed.set_from_str("dir::Jump", "256")),
+            "`256` is not a valid u8 for `dir::Jump`"
+        );
+        assert_eq!(
+            error(failed.set("vec.x", Ty::Primitive(Primitive::U32(None)))),
+            "`vec.x` has no value"
+        );
+        assert_eq!(failed, builder);
+
+        builder.set_variant("dir", "Left").unwrap();
+        builder.set_variant("dir", "Jump").unwrap();
+        assert_eq!(
+            crate::access::get(builder.ty(), "dir::Jump").unwrap(),
+            &Ty::Primitive(Primitive::U8(Some(16)))
+        );
+        let json = builder.to_json().unwrap();
+        assert_eq!(json["dir"]["Jump"], json!(16));
+        assert_eq!(json["path"].as_array().map(Vec::len), Some(1));
+        assert_eq!(json["name"], json!("dojo"));
+    }
+
+    #[test]
+    fn parses_u256_over_the_full_range() {
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(u256(max), Some(U256::MAX));
+        assert_eq!(u256(&format!("{max}0")), None);
+        assert_eq!(u256(&max.replace("935", "936")), None);
+        assert_eq!(
+            u256("340282366920938463463374607431768211456"),
+            Some(U256::ONE.shl_vartime(128))
+        );
+        assert_eq!(u256("0x10"), Some(U256::from_u8(16)));
+        assert_eq!((u256(""), u256("-1"), u256("1e3")), (None, None, None));
+    }
+}
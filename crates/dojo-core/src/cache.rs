@@ -0,0 +1,560 @@
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use instant::Instant;
+use serde::Serialize;
+use starknet_crypto::Felt;
+use torii_proto::schema::Entity;
+use torii_proto::{Clause, Page, Query, TokenBalance, TokenBalanceQuery, TokenQuery};
+
+use crate::codec::canonical_json;
+use crate::mux::{self, EntityFilter, TokenBalanceFilter, Topic};
+use crate::polling::EntitySource;
+
+/// Subscription updates kept while queries are in flight, to tell whether their results are
+/// already outdated when they arrive.
+const UPDATE_LOG_LIMIT: usize = 256;
+
+/// Opt-in cache of query results, see [`QueryCache`].
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    /// How long a result is served without asking Torii again
+    pub ttl: Duration,
+    /// Maximum number of cached results. The least recently used one is evicted first.
+    pub max_entries: usize,
+    /// Serves expired results while they are refreshed in the background
+    pub stale_while_revalidate: bool,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self { ttl: Duration::from_secs(30), max_entries: 256, stale_while_revalidate: false }
+    }
+}
+
+impl CacheConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.ttl.is_zero() {
+            return Err(anyhow!("cache ttl must be greater than zero"));
+        }
+        if self.max_entries == 0 {
+            return Err(anyhow!("cache size must be greater than zero"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Counters of a [`QueryCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups served from a fresh result
+    pub hits: u64,
+    /// Lookups served from an expired result, see [`CacheConfig::stale_while_revalidate`]
+    pub stale_hits: u64,
+    /// Lookups that had to query Torii
+    pub misses: u64,
+    /// Results dropped because a subscription update changed them
+    pub invalidations: u64,
+    /// Results dropped to stay within [`CacheConfig::max_entries`]
+    pub evictions: u64,
+    /// Results currently cached
+    pub entries: u64,
+}
+
+/// Identifies a query: its kind and every field of it, pagination included, as
+/// [`canonical_json`] so that keys stay the same across builds and recordings.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey(String);
+
+impl CacheKey {
+    pub fn new(kind: &str, query: &impl Serialize) -> Self {
+        let query = canonical_json(query).expect("queries serialize to JSON");
+        Self(format!("{kind}:{query}"))
+    }
+
+    /// Key of a query with its unordered lists sorted, so that queries only listing the same
+    /// worlds, models or addresses in another order share a result.
+    pub fn normalized<Q: Normalize>(kind: &str, query: &Q) -> Self {
+        let mut query = query.clone();
+        query.normalize();
+        Self::new(kind, &query)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Queries with lists whose order doesn't change their results, see [`CacheKey::normalized`].
+pub trait Normalize: Clone + Serialize {
+    /// Sorts and deduplicates the unordered lists. Positional ones, like the keys of a
+    /// [`KeysClause`](torii_proto::KeysClause), are left as is.
+    fn normalize(&mut self);
+}
+
+impl Normalize for Vec<Felt> {
+    fn normalize(&mut self) {
+        self.sort_unstable();
+        self.dedup();
+    }
+}
+
+impl Normalize for Vec<String> {
+    fn normalize(&mut self) {
+        self.sort_unstable();
+        self.dedup();
+    }
+}
+
+impl Normalize for Clause {
+    fn normalize(&mut self) {
+        match self {
+            Clause::HashedKeys(hashed_keys) => hashed_keys.normalize(),
+            Clause::Keys(keys) => keys.models.normalize(),
+            Clause::Member(_) => {}
+            Clause::Composite(composite) => {
+                composite.clauses.iter_mut().for_each(Clause::normalize);
+                // Clauses have no order of their own, their normalized form gives them one.
+                composite.clauses.sort_by_cached_key(|clause| CacheKey::new("", clause).0);
+            }
+        }
+    }
+}
+
+impl Normalize for Query {
+    fn normalize(&mut self) {
+        self.world_addresses.normalize();
+        self.models.normalize();
+        if let Some(clause) = &mut self.clause {
+            clause.normalize();
+        }
+    }
+}
+
+impl Normalize for TokenQuery {
+    fn normalize(&mut self) {
+        self.contract_addresses.normalize();
+        self.token_ids.sort_unstable();
+        self.token_ids.dedup();
+    }
+}
+
+impl Normalize for TokenBalanceQuery {
+    fn normalize(&mut self) {
+        self.contract_addresses.normalize();
+        self.account_addresses.normalize();
+        self.token_ids.sort_unstable();
+        self.token_ids.dedup();
+    }
+}
+
+/// The subscription updates that invalidate a cached result.
+#[derive(Debug, Clone)]
+pub enum Scope {
+    /// Entity or event message updates matching the filter, or of an entity in the result.
+    /// Every update of the worlds when the hashed keys of the result are unknown.
+    Entities { source: EntitySource, filter: EntityFilter, hashed_keys: Option<Vec<Felt>> },
+    /// Token balance updates of these tokens
+    Tokens(TokenBalanceFilter),
+    /// Token balance updates matching the filter
+    TokenBalances(TokenBalanceFilter),
+    /// Nothing, the result only expires
+    Unscoped,
+}
+
+impl Scope {
+    pub fn entities(source: EntitySource, query: &Query, page: &Page<Entity>) -> Self {
+        let filter = EntityFilter {
+            clause: query.clause.clone(),
+            world_addresses: query.world_addresses.clone(),
+        };
+        let hashed_keys = (!query.no_hashed_keys)
+            .then(|| page.items.iter().map(|entity| entity.hashed_keys).collect());
+        Scope::Entities { source, filter, hashed_keys }
+    }
+
+    pub fn tokens(query: &TokenQuery) -> Self {
+        Scope::Tokens(TokenBalanceFilter {
+            contract_addresses: query.contract_addresses.clone(),
+            account_addresses: Vec::new(),
+            token_ids: query.token_ids.clone(),
+        })
+    }
+
+    pub fn token_balances(query: &TokenBalanceQuery) -> Self {
+        Scope::TokenBalances(TokenBalanceFilter {
+            contract_addresses: query.contract_addresses.clone(),
+            account_addresses: query.account_addresses.clone(),
+            token_ids: query.token_ids.clone(),
+        })
+    }
+
+    fn affected_by(&self, update: &Update) -> bool {
+        match (self, update) {
+            (Scope::Entities { source, filter, hashed_keys }, Update::Entity(from, entity)) => {
+                let in_world = filter.world_addresses.is_empty()
+                    || filter.world_addresses.contains(&entity.world_address);
+                source == from
+                    && in_world
+                    && hashed_keys.as_ref().is_none_or(|hashed_keys| {
+                        hashed_keys.contains(&entity.hashed_keys)
+                            || mux::Entities.matches(filter, entity)
+                    })
+            }
+            (
+                Scope::Tokens(filter) | Scope::TokenBalances(filter),
+                Update::TokenBalance(balance),
+            ) => mux::TokenBalances.matches(filter, balance),
+            (Scope::Entities { filter, .. }, Update::World(world_address)) => {
+                filter.world_addresses.is_empty() || filter.world_addresses.contains(world_address)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Result of [`QueryCache::lookup`].
+pub enum Lookup<T> {
+    /// A result younger than the TTL
+    Fresh(T),
+    /// An expired result. The first lookup after expiry also gets a ticket to refresh it.
+    Stale(T, Option<Ticket>),
+    /// Nothing usable, the result has to be fetched and stored with the ticket
+    Miss(Ticket),
+}
+
+/// Permission to store the result of a query fetched after a [`QueryCache::lookup`].
+///
+/// Dropping it without storing a result lets the next lookup fetch the query again.
+pub struct Ticket {
+    state: Arc<Mutex<State>>,
+    key: CacheKey,
+    epoch: u64,
+}
+
+impl Drop for Ticket {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.in_flight -= 1;
+        if state.in_flight == 0 {
+            state.log.clear();
+        }
+        if let Some(entry) = state.entries.get_mut(&self.key) {
+            entry.revalidating = false;
+        }
+    }
+}
+
+enum Update {
+    Entity(EntitySource, Entity),
+    TokenBalance(TokenBalance),
+    /// The schema of a world changed
+    World(Felt),
+}
+
+struct Entry {
+    value: Arc<dyn Any + Send + Sync>,
+    scope: Scope,
+    stored_at: Instant,
+    used: u64,
+    revalidating: bool,
+}
+
+#[derive(Default)]
+struct State {
+    entries: HashMap<CacheKey, Entry>,
+    /// Lookup counter, ordering entries by last use
+    clock: u64,
+    /// Number of updates seen
+    epoch: u64,
+    /// Updates seen while tickets were out, with their epoch
+    log: VecDeque<(u64, Update)>,
+    in_flight: usize,
+    stats: CacheStats,
+}
+
+impl State {
+    fn ticket(&mut self, state: &Arc<Mutex<State>>, key: CacheKey) -> Ticket {
+        self.in_flight += 1;
+        Ticket { state: state.clone(), key, epoch: self.epoch }
+    }
+
+    fn invalidate(&mut self, update: Update) {
+        self.epoch += 1;
+        let len = self.entries.len();
+        self.entries.retain(|_, entry| !entry.scope.affected_by(&update));
+        self.stats.invalidations += (len - self.entries.len()) as u64;
+
+        if self.in_flight > 0 {
+            if self.log.len() == UPDATE_LOG_LIMIT {
+                self.log.pop_front();
+            }
+            self.log.push_back((self.epoch, update));
+        }
+    }
+}
+
+/// Results of Torii queries, kept until they expire or a subscription update changes them.
+///
+/// Queries are identified by a [`CacheKey`], and cached along with the [`Scope`] of updates
+/// that invalidate them. Entity subscriptions invalidate entity and event message results,
+/// token balance subscriptions invalidate token and token balance results, so results are only
+/// kept precise while a matching subscription is live. Results of queries still in flight are
+/// not stored when an update already invalidated them.
+#[derive(Clone)]
+pub struct QueryCache {
+    config: CacheConfig,
+    state: Arc<Mutex<State>>,
+}
+
+impl QueryCache {
+    pub fn new(config: CacheConfig) -> Self {
+        Self { config, state: Arc::new(Mutex::new(State::default())) }
+    }
+
+    pub fn config(&self) -> &CacheConfig {
+        &self.config
+    }
+
+    pub fn lookup<T: Clone + 'static>(&self, key: &CacheKey) -> Lookup<T> {
+        let mut state = self.state.lock().unwrap();
+        state.clock += 1;
+        let clock = state.clock;
+
+        let entry = state.entries.get_mut(key);
+        let cached = entry.and_then(|entry| {
+            let value = entry.value.downcast_ref::<T>()?.clone();
+            let fresh = entry.stored_at.elapsed() < self.config.ttl;
+            if !fresh && !self.config.stale_while_revalidate {
+                return None;
+            }
+            entry.used = clock;
+            let revalidate = !fresh && !entry.revalidating;
+            if revalidate {
+                entry.revalidating = true;
+            }
+            Some((value, fresh, revalidate))
+        });
+
+        match cached {
+            Some((value, true, _)) => {
+                state.stats.hits += 1;
+                Lookup::Fresh(value)
+            }
+            Some((value, false, revalidate)) => {
+                state.stats.stale_hits += 1;
+                let ticket = revalidate.then(|| state.ticket(&self.state, key.clone()));
+                Lookup::Stale(value, ticket)
+            }
+            None => {
+                state.stats.misses += 1;
+                state.entries.remove(key);
+                Lookup::Miss(state.ticket(&self.state, key.clone()))
+            }
+        }
+    }
+
+    /// Stores the result fetched with a ticket, unless an update seen since the lookup
+    /// invalidates it.
+    pub fn store<T: Send + Sync + 'static>(&self, ticket: Ticket, scope: Scope, value: T) {
+        let mut state = self.state.lock().unwrap();
+        let missed = state.epoch - ticket.epoch;
+        let outdated = missed > state.log.len() as u64
+            || state
+                .log
+                .iter()
+                .filter(|(epoch, _)| *epoch > ticket.epoch)
+                .any(|(_, update)| scope.affected_by(update));
+
+        if !outdated {
+            state.clock += 1;
+            let entry = Entry {
+                value: Arc::new(value),
+                scope,
+                stored_at: Instant::now(),
+                used: state.clock,
+                revalidating: false,
+            };
+            state.entries.insert(ticket.key.clone(), entry);
+
+            while state.entries.len() > self.config.max_entries {
+                let lru = state.entries.iter().min_by_key(|(_, entry)| entry.used);
+                let Some(key) = lru.map(|(key, _)| key.clone()) else { break };
+                state.entries.remove(&key);
+                state.stats.evictions += 1;
+            }
+        }
+
+        drop(state);
+        drop(ticket);
+    }
+
+    pub fn invalidate_entity(&self, source: EntitySource, entity: &Entity) {
+        self.state.lock().unwrap().invalidate(Update::Entity(source, entity.clone()));
+    }
+
+    pub fn invalidate_token_balance(&self, balance: &TokenBalance) {
+        self.state.lock().unwrap().invalidate(Update::TokenBalance(balance.clone()));
+    }
+
+    /// Drops the entity and event message results of a world, e.g. when one of its models was
+    /// upgraded.
+    pub fn invalidate_world(&self, world_address: Felt) {
+        self.state.lock().unwrap().invalidate(Update::World(world_address));
+    }
+
+    pub fn clear(&self) {
+        self.state.lock().unwrap().entries.clear();
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let state = self.state.lock().unwrap();
+        CacheStats { entries: state.entries.len() as u64, ..state.stats }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use torii_proto::{
+        CompositeClause, KeysClause, LogicalOperator, Pagination, PaginationDirection,
+        PatternMatching,
+    };
+
+    use super::*;
+
+    fn entity(hashed_keys: Felt) -> Entity {
+        Entity {
+            world_address: Felt::ZERO,
+            hashed_keys,
+            models: vec![],
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            executed_at: Default::default(),
+        }
+    }
+
+    fn scope(hashed_keys: Vec<Felt>) -> Scope {
+        Scope::Entities {
+            source: EntitySource::Entities,
+            filter: EntityFilter {
+                clause: Some(torii_proto::Clause::Keys(KeysClause {
+                    keys: vec![Some(Felt::ONE)],
+                    pattern_matching: PatternMatching::FixedLen,
+                    models: vec![],
+                })),
+                world_addresses: vec![],
+            },
+            hashed_keys: Some(hashed_keys),
+        }
+    }
+
+    #[test]
+    fn invalidates_results_containing_updated_entities() {
+        let cache = QueryCache::new(CacheConfig::default());
+        let key = CacheKey::new("entities", &1);
+        let Lookup::Miss(ticket) = cache.lookup::<u32>(&key) else { panic!("expected a miss") };
+        cache.store(ticket, scope(vec![Felt::TWO]), 7u32);
+        assert!(matches!(cache.lookup::<u32>(&key), Lookup::Fresh(7)));
+
+        cache.invalidate_entity(EntitySource::EventMessages, &entity(Felt::TWO));
+        assert!(matches!(cache.lookup::<u32>(&key), Lookup::Fresh(7)));
+
+        cache.invalidate_entity(EntitySource::Entities, &entity(Felt::TWO));
+        assert!(matches!(cache.lookup::<u32>(&key), Lookup::Miss(_)));
+        assert_eq!(
+            cache.stats(),
+            CacheStats { hits: 2, misses: 2, invalidations: 1, ..Default::default() }
+        );
+    }
+
+    #[test]
+    fn drops_results_outdated_while_in_flight() {
+        let cache = QueryCache::new(CacheConfig::default());
+        let key = CacheKey::new("entities", &1);
+        let Lookup::Miss(ticket) = cache.lookup::<u32>(&key) else { panic!("expected a miss") };
+
+        cache.invalidate_entity(EntitySource::Entities, &entity(Felt::TWO));
+        cache.store(ticket, scope(vec![Felt::TWO]), 7u32);
+        assert!(matches!(cache.lookup::<u32>(&key), Lookup::Miss(_)));
+    }
+
+    #[test]
+    fn evicts_least_recently_used_results() {
+        let cache = QueryCache::new(CacheConfig { max_entries: 2, ..Default::default() });
+        let store = |query: u32| {
+            let key = CacheKey::new("tokens", &query);
+            if let Lookup::Miss(ticket) = cache.lookup::<u32>(&key) {
+                cache.store(ticket, Scope::Unscoped, query);
+            }
+        };
+        store(1);
+        store(2);
+        store(1);
+        store(3);
+
+        assert!(matches!(cache.lookup::<u32>(&CacheKey::new("tokens", &1)), Lookup::Fresh(1)));
+        assert!(matches!(cache.lookup::<u32>(&CacheKey::new("tokens", &2)), Lookup::Miss(_)));
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[test]
+    fn normalizes_unordered_lists() {
+        let query = |world_addresses: Vec<Felt>, models: Vec<&str>, hashed_keys: Vec<Felt>| Query {
+            world_addresses,
+            pagination: Pagination {
+                cursor: None,
+                limit: None,
+                direction: PaginationDirection::Forward,
+                order_by: vec![],
+            },
+            clause: Some(torii_proto::Clause::Composite(CompositeClause {
+                operator: LogicalOperator::Or,
+                clauses: vec![
+                    torii_proto::Clause::HashedKeys(hashed_keys),
+                    torii_proto::Clause::Keys(KeysClause {
+                        keys: vec![Some(Felt::ONE), Some(Felt::TWO)],
+                        pattern_matching: PatternMatching::FixedLen,
+                        models: vec![],
+                    }),
+                ],
+            })),
+            no_hashed_keys: false,
+            models: models.into_iter().map(String::from).collect(),
+            historical: false,
+        };
+
+        let key = CacheKey::normalized(
+            "entities",
+            &query(vec![Felt::ONE, Felt::TWO], vec!["ns-A", "ns-B"], vec![Felt::ONE, Felt::TWO]),
+        );
+        let reordered = CacheKey::normalized(
+            "entities",
+            &query(vec![Felt::TWO, Felt::ONE], vec!["ns-B", "ns-A"], vec![Felt::TWO, Felt::ONE]),
+        );
+        assert_eq!(key, reordered);
+
+        let mut swapped = query(vec![Felt::ONE], vec![], vec![]);
+        let Some(torii_proto::Clause::Composite(composite)) = &mut swapped.clause else {
+            unreachable!()
+        };
+        composite.clauses.reverse();
+        assert_eq!(
+            CacheKey::normalized("entities", &swapped),
+            CacheKey::normalized("entities", &query(vec![Felt::ONE], vec![], vec![])),
+        );
+
+        let Some(torii_proto::Clause::Composite(composite)) = &mut swapped.clause else {
+            unreachable!()
+        };
+        let torii_proto::Clause::Keys(keys) = &mut composite.clauses[0] else { unreachable!() };
+        keys.keys.reverse();
+        assert_ne!(
+            CacheKey::normalized("entities", &swapped),
+            CacheKey::normalized("entities", &query(vec![Felt::ONE], vec![], vec![])),
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::HashSet;
+
+use dojo_types::primitive::Primitive;
+use dojo_types::schema::{Struct, Ty};
+use starknet_crypto::Felt;
+use torii_proto::schema::Entity;
+
+/// Whether an entity update created, updated or deleted the entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityChangeKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+/// An entity update tagged with its [`EntityChangeKind`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntityChange {
+    Created(Entity),
+    Updated(Entity),
+    Deleted(Entity),
+}
+
+impl EntityChange {
+    /// Classifies an entity update from its payload alone.
+    ///
+    /// See [`is_deleted`] for deletions. Other updates are creations when the entity was
+    /// created at the time of the update, which Torii tracks with a second precision: an
+    /// entity updated within the second it was created is reported as created again. Use an
+    /// [`EntityClassifier`] to classify the updates of a subscription.
+    pub fn classify(entity: Entity) -> Self {
+        if is_deleted(&entity) {
+            EntityChange::Deleted(entity)
+        } else if entity.created_at == entity.updated_at {
+            EntityChange::Created(entity)
+        } else {
+            EntityChange::Updated(entity)
+        }
+    }
+
+    pub fn kind(&self) -> EntityChangeKind {
+        match self {
+            EntityChange::Created(_) => EntityChangeKind::Created,
+            EntityChange::Updated(_) => EntityChangeKind::Updated,
+            EntityChange::Deleted(_) => EntityChangeKind::Deleted,
+        }
+    }
+
+    pub fn entity(&self) -> &Entity {
+        match self {
+            EntityChange::Created(entity)
+            | EntityChange::Updated(entity)
+            | EntityChange::Deleted(entity) => entity,
+        }
+    }
+
+    pub fn into_entity(self) -> Entity {
+        match self {
+            EntityChange::Created(entity)
+            | EntityChange::Updated(entity)
+            | EntityChange::Deleted(entity) => entity,
+        }
+    }
+}
+
+/// Applies an entity update on top of a known state of the entity.
+///
+/// Torii updates only carry the models that changed: the models of `update` replace those of
+/// the same name and the others are kept. A deletion replaces the whole state.
+pub fn merge_update(entity: &mut Entity, update: Entity) {
+    if is_deleted(&update) {
+        *entity = update;
+        return;
+    }
+
+    let models = std::mem::take(&mut entity.models);
+    let mut merged = Entity { models, ..update };
+    for model in update.models {
+        match merged.models.iter_mut().find(|m| m.name == model.name) {
+            Some(slot) => *slot = model,
+            None => merged.models.push(model),
+        }
+    }
+    *entity = merged;
+}
+
+/// Classifies the updates of a subscription, remembering the entities it has seen so that only
+/// the first update of an entity can report its creation.
+#[derive(Debug, Default)]
+pub struct EntityClassifier {
+    seen: HashSet<Felt>,
+}
+
+impl EntityClassifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Classifies an update like [`EntityChange::classify`], except that updates of an entity
+    /// already seen since its creation are never creations.
+    pub fn classify(&mut self, entity: Entity) -> EntityChange {
+        match EntityChange::classify(entity) {
+            EntityChange::Deleted(entity) => {
+                self.seen.remove(&entity.hashed_keys);
+                EntityChange::Deleted(entity)
+            }
+            EntityChange::Created(entity) if !self.seen.insert(entity.hashed_keys) => {
+                EntityChange::Updated(entity)
+            }
+            change => {
+                self.seen.insert(change.entity().hashed_keys);
+                change
+            }
+        }
+    }
+}
+
+/// Whether an entity update reports the deletion of the entity.
+///
+/// Torii sends a deleted entity without models. Models whose values are all zero are not
+/// deletions: a model may legitimately be written with zero values, and updates only carry the
+/// models that changed.
+pub fn is_deleted(entity: &Entity) -> bool {
+    entity.models.is_empty()
+}
+
+/// Whether a model carried by an entity update reports its deletion from an entity that keeps
+/// other models.
+///
+/// Torii only learns the entity a model was deleted from, not its keys or values, and sends the
+/// schema of the model without any value set. A written model always has values, zeroes
+/// included.
+pub fn is_model_deleted(model: &Struct) -> bool {
+    model.children.iter().all(|member| is_unset(&member.ty))
+}
+
+fn is_unset(ty: &Ty) -> bool {
+    match ty {
+        Ty::Primitive(primitive) => match primitive {
+            Primitive::I8(value) => value.is_none(),
+            Primitive::I16(value) => value.is_none(),
+            Primitive::I32(value) => value.is_none(),
+            Primitive::I64(value) => value.is_none(),
+            Primitive::I128(value) => value.is_none(),
+            Primitive::U8(value) => value.is_none(),
+            Primitive::U16(value) => value.is_none(),
+            Primitive::U32(value) => value.is_none(),
+            Primitive::U64(value) => value.is_none(),
+            Primitive::U128(value) => value.is_none(),
+            Primitive::U256(value) => value.is_none(),
+            Primitive::Bool(value) => value.is_none(),
+            Primitive::Felt252(value)
+            | Primitive::ClassHash(value)
+            | Primitive::ContractAddress(value)
+            | Primitive::EthAddress(value) => value.is_none(),
+        },
+        Ty::Struct(s) => s.children.iter().all(|member| is_unset(&member.ty)),
+        Ty::Enum(e) => e.option.is_none(),
+        // Schemas hold the type of the elements of arrays as their single element
+        Ty::Tuple(values) | Ty::Array(values) | Ty::FixedSizeArray((values, _)) => {
+            values.iter().all(is_unset)
+        }
+        Ty::ByteArray(value) => value.is_empty(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+    use dojo_types::schema::{EnumOption, Member};
+
+    use super::*;
+
+    fn member(name: &str, key: bool, ty: Ty) -> Member {
+        Member { name: name.to_string(), ty, key }
+    }
+
+    fn position(player: u64, x: u32, moved: bool) -> Struct {
+        Struct {
+            name: "ns-Position".to_string(),
+            children: vec![
+                member(
+                    "player",
+                    true,
+                    Ty::Primitive(Primitive::ContractAddress(Some(player.into()))),
+                ),
+                member("x", false, Ty::Primitive(Primitive::U32(Some(x)))),
+                member("moved", false, Ty::Primitive(Primitive::Bool(Some(moved)))),
+                member(
+                    "direction",
+                    false,
+                    Ty::Enum(dojo_types::schema::Enum {
+                        name: "Direction".to_string(),
+                        option: Some(0),
+                        options: vec![
+                            EnumOption { name: "None".to_string(), ty: Ty::Tuple(vec![]) },
+                            EnumOption { name: "Left".to_string(), ty: Ty::Tuple(vec![]) },
+                        ],
+                    }),
+                ),
+            ],
+        }
+    }
+
+    fn entity(models: Vec<Struct>, created_at: i64, updated_at: i64) -> Entity {
+        Entity {
+            world_address: Felt::ONE,
+            hashed_keys: Felt::TWO,
+            models,
+            created_at: DateTime::from_timestamp(created_at, 0).unwrap(),
+            updated_at: DateTime::from_timestamp(updated_at, 0).unwrap(),
+            executed_at: DateTime::from_timestamp(updated_at, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn classifies_entity_updates() {
+        let created = EntityChange::classify(entity(vec![position(1, 3, true)], 10, 10));
+        assert_eq!(created.kind(), EntityChangeKind::Created);
+
+        let updated = EntityChange::classify(entity(vec![position(1, 4, false)], 10, 12));
+        assert_eq!(updated.kind(), EntityChangeKind::Updated);
+
+        let zeroed = EntityChange::classify(entity(vec![position(1, 0, false)], 10, 13));
+        assert_eq!(zeroed.kind(), EntityChangeKind::Updated);
+
+        let empty = EntityChange::classify(entity(vec![], 10, 14));
+        assert_eq!(empty.kind(), EntityChangeKind::Deleted);
+    }
+
+    #[test]
+    fn reports_creation_once() {
+        let mut classifier = EntityClassifier::new();
+        let mut kind = |models, created_at, updated_at| {
+            classifier.classify(entity(models, created_at, updated_at)).kind()
+        };
+
+        assert_eq!(kind(vec![position(1, 3, true)], 10, 10), EntityChangeKind::Created);
+        // Updated within the second it was created
+        assert_eq!(kind(vec![position(1, 4, true)], 10, 10), EntityChangeKind::Updated);
+        assert_eq!(kind(vec![], 10, 11), EntityChangeKind::Deleted);
+        assert_eq!(kind(vec![position(1, 0, false)], 12, 12), EntityChangeKind::Created);
+    }
+}
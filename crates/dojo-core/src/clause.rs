@@ -0,0 +1,646 @@
+use std::cmp::Ordering;
+
+use crypto_bigint::{Encoding, U256};
+use dojo_types::primitive::Primitive;
+use dojo_types::schema::{Struct, Ty};
+use starknet_crypto::Felt;
+use torii_proto::schema::Entity;
+use torii_proto::{
+    Clause, ComparisonOperator, KeysClause, LogicalOperator, MemberClause, MemberValue,
+    PatternMatching,
+};
+
+/// Whether an entity matches a clause, evaluated locally with Torii's semantics.
+///
+/// Keys clauses match when one of the entity's models they list, or any of its models when they
+/// list none, has matching keys, see [`matches_keys`]. Member clauses compare the member of a
+/// model the way Torii's SQLite storage does, see [`matches_member`]. Composite clauses
+/// without sub-clauses match every entity, as Torii doesn't filter on them.
+pub fn matches(clause: &Clause, entity: &Entity) -> bool {
+    match clause {
+        Clause::HashedKeys(hashed_keys) => {
+            hashed_keys.is_empty() || hashed_keys.contains(&entity.hashed_keys)
+        }
+        Clause::Keys(keys) => entity
+            .models
+            .iter()
+            .filter(|model| keys.models.is_empty() || keys.models.contains(&model.name))
+            .any(|model| matches_keys(keys, &model_keys(model))),
+        Clause::Member(member) => entity
+            .models
+            .iter()
+            .find(|model| model.name == member.model)
+            .is_some_and(|model| matches_member(member, model)),
+        Clause::Composite(composite) if composite.clauses.is_empty() => true,
+        Clause::Composite(composite) => match composite.operator {
+            LogicalOperator::And => composite.clauses.iter().all(|c| matches(c, entity)),
+            LogicalOperator::Or => composite.clauses.iter().any(|c| matches(c, entity)),
+        },
+    }
+}
+
+/// Whether keys match a [`KeysClause`], with Torii's semantics: unset keys match any value,
+/// fixed length clauses match as many keys as they have, variable length clauses match keys
+/// starting with theirs. A clause without keys stands for a single wildcard.
+pub fn matches_keys(clause: &KeysClause, keys: &[Felt]) -> bool {
+    let pattern: &[Option<Felt>] = if clause.keys.is_empty() { &[None] } else { &clause.keys };
+
+    let len_matches = match clause.pattern_matching {
+        PatternMatching::FixedLen => keys.len() == pattern.len(),
+        PatternMatching::VariableLen => keys.len() >= pattern.len(),
+    };
+
+    len_matches
+        && pattern.iter().zip(keys).all(|(expected, key)| expected.is_none_or(|e| e == *key))
+}
+
+/// Whether a member of the model matches a [`MemberClause`]. The model of the clause isn't
+/// checked.
+///
+/// Torii stores members in SQLite columns: integers up to 64 bits and booleans as `INTEGER`,
+/// wider integers and felts as 0x prefixed, zero padded hex `TEXT`, enums as the name of their
+/// variant and byte arrays as text. Clause values are bound as text, and compared with SQLite's
+/// rules: text is converted to a number when compared with an `INTEGER` column, numbers sort
+/// before text, text compares bytewise, and unset members match no comparison. Negative `i128`
+/// are stored in two's complement and sort after positive ones.
+///
+/// Array operators compare the elements of array members like columns of their type.
+pub fn matches_member(clause: &MemberClause, model: &Struct) -> bool {
+    let Some(ty) = member_ty(model, &clause.member) else {
+        return false;
+    };
+
+    match clause.operator {
+        ComparisonOperator::Eq => compare(ty, &clause.value) == Some(Ordering::Equal),
+        ComparisonOperator::Neq => compare(ty, &clause.value).is_some_and(Ordering::is_ne),
+        ComparisonOperator::Gt => compare(ty, &clause.value) == Some(Ordering::Greater),
+        ComparisonOperator::Gte => compare(ty, &clause.value).is_some_and(Ordering::is_ge),
+        ComparisonOperator::Lt => compare(ty, &clause.value) == Some(Ordering::Less),
+        ComparisonOperator::Lte => compare(ty, &clause.value).is_some_and(Ordering::is_le),
+        ComparisonOperator::In => is_in(ty, &clause.value).unwrap_or(false),
+        ComparisonOperator::NotIn => is_in(ty, &clause.value).is_some_and(|is_in| !is_in),
+        ComparisonOperator::Contains => elements(ty).is_some_and(|elements| {
+            elements.iter().any(|e| compare(e, &clause.value) == Some(Ordering::Equal))
+        }),
+        ComparisonOperator::ContainsAll => elements(ty).is_some_and(|elements| {
+            values(&clause.value)
+                .iter()
+                .all(|value| elements.iter().any(|e| compare(e, value) == Some(Ordering::Equal)))
+        }),
+        ComparisonOperator::ContainsAny => elements(ty).is_some_and(|elements| {
+            values(&clause.value)
+                .iter()
+                .any(|value| elements.iter().any(|e| compare(e, value) == Some(Ordering::Equal)))
+        }),
+        ComparisonOperator::ArrayLengthEq => {
+            array_len_cmp(ty, &clause.value) == Some(Ordering::Equal)
+        }
+        ComparisonOperator::ArrayLengthGt => {
+            array_len_cmp(ty, &clause.value) == Some(Ordering::Greater)
+        }
+        ComparisonOperator::ArrayLengthLt => {
+            array_len_cmp(ty, &clause.value) == Some(Ordering::Less)
+        }
+    }
+}
+
+/// Member of a model at a dot separated path, like the columns of Torii's model tables: nested
+/// struct members are separated by dots (`position.x`), tuple elements are indexed
+/// (`pair.0`) and enum payloads are named after their variant (`direction.Left`), reachable
+/// only while the variant is set.
+pub fn member_ty<'a>(model: &'a Struct, path: &str) -> Option<&'a Ty> {
+    let mut segments = path.split('.');
+    let first = segments.next()?;
+    let mut ty = &model.children.iter().find(|member| member.name == first)?.ty;
+
+    for segment in segments {
+        ty = match ty {
+            Ty::Struct(s) => &s.children.iter().find(|member| member.name == segment)?.ty,
+            Ty::Tuple(values) => values.get(segment.parse::<usize>().ok()?)?,
+            Ty::Enum(e) => {
+                let option = e.options.get(usize::from(e.option?))?;
+                if option.name != segment {
+                    return None;
+                }
+                &option.ty
+            }
+            _ => return None,
+        };
+    }
+
+    Some(ty)
+}
+
+/// Keys of a model, serialized the way the world emits them.
+pub fn model_keys(model: &Struct) -> Vec<Felt> {
+    let mut keys = Vec::new();
+    for member in model.children.iter().filter(|member| member.key) {
+        serialize_ty(&member.ty, &mut keys);
+    }
+    keys
+}
+
+/// A value as stored by SQLite.
+#[derive(Debug, Clone, PartialEq)]
+enum SqlValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+}
+
+/// Compares a member with a clause value the way SQLite compares a column with a bound
+/// parameter. `None` stands for SQL's `NULL` result, which matches nothing.
+fn compare(ty: &Ty, value: &MemberValue) -> Option<Ordering> {
+    let column = column_value(ty);
+    let mut value = bound_value(value)?;
+    if column_is_integer(ty) {
+        value = numeric_affinity(value);
+    }
+
+    match (&column, &value) {
+        (SqlValue::Null, _) | (_, SqlValue::Null) => None,
+        (SqlValue::Integer(a), SqlValue::Integer(b)) => Some(a.cmp(b)),
+        (SqlValue::Integer(a), SqlValue::Real(b)) => (*a as f64).partial_cmp(b),
+        (SqlValue::Real(a), SqlValue::Integer(b)) => a.partial_cmp(&(*b as f64)),
+        (SqlValue::Real(a), SqlValue::Real(b)) => a.partial_cmp(b),
+        (SqlValue::Text(a), SqlValue::Text(b)) => Some(a.as_bytes().cmp(b.as_bytes())),
+        // Numbers sort before text
+        (SqlValue::Text(_), _) => Some(Ordering::Greater),
+        (_, SqlValue::Text(_)) => Some(Ordering::Less),
+    }
+}
+
+/// `IN` over a list, or a single value. `None` when the member is unset.
+fn is_in(ty: &Ty, value: &MemberValue) -> Option<bool> {
+    if column_value(ty) == SqlValue::Null {
+        return None;
+    }
+    Some(values(value).iter().any(|value| compare(ty, value) == Some(Ordering::Equal)))
+}
+
+fn array_len_cmp(ty: &Ty, value: &MemberValue) -> Option<Ordering> {
+    let len = elements(ty)?.len() as i64;
+    match numeric_affinity(bound_value(value)?) {
+        SqlValue::Integer(expected) => Some(len.cmp(&expected)),
+        SqlValue::Real(expected) => (len as f64).partial_cmp(&expected),
+        _ => None,
+    }
+}
+
+fn values(value: &MemberValue) -> &[MemberValue] {
+    match value {
+        MemberValue::List(values) => values,
+        value => std::slice::from_ref(value),
+    }
+}
+
+fn elements(ty: &Ty) -> Option<&[Ty]> {
+    match ty {
+        Ty::Array(values) | Ty::FixedSizeArray((values, _)) => Some(values),
+        _ => None,
+    }
+}
+
+fn column_is_integer(ty: &Ty) -> bool {
+    matches!(
+        ty,
+        Ty::Primitive(
+            Primitive::I8(_)
+                | Primitive::I16(_)
+                | Primitive::I32(_)
+                | Primitive::I64(_)
+                | Primitive::U8(_)
+                | Primitive::U16(_)
+                | Primitive::U32(_)
+                | Primitive::Bool(_)
+        )
+    )
+}
+
+fn column_value(ty: &Ty) -> SqlValue {
+    match ty {
+        Ty::Primitive(primitive) => primitive_value(primitive),
+        Ty::Enum(e) => e
+            .option
+            .and_then(|option| e.options.get(usize::from(option)))
+            .map_or(SqlValue::Null, |option| SqlValue::Text(option.name.clone())),
+        Ty::ByteArray(bytes) => SqlValue::Text(bytes.clone()),
+        Ty::Struct(_) | Ty::Tuple(_) | Ty::Array(_) | Ty::FixedSizeArray(_) => SqlValue::Null,
+    }
+}
+
+/// Clause values are bound as text. Unset primitives are bound as their default value.
+fn bound_value(value: &MemberValue) -> Option<SqlValue> {
+    match value {
+        MemberValue::Primitive(primitive) => {
+            Some(match primitive_value(&with_default(primitive)) {
+                SqlValue::Integer(value) => SqlValue::Text(value.to_string()),
+                value => value,
+            })
+        }
+        MemberValue::String(value) => Some(SqlValue::Text(value.clone())),
+        MemberValue::List(_) => None,
+    }
+}
+
+fn primitive_value(primitive: &Primitive) -> SqlValue {
+    let integer = |value: Option<i64>| value.map_or(SqlValue::Null, SqlValue::Integer);
+    let text = |value: Option<[u8; 32]>| value.map_or(SqlValue::Null, |bytes| hex_text(&bytes));
+
+    match *primitive {
+        Primitive::I8(v) => integer(v.map(i64::from)),
+        Primitive::I16(v) => integer(v.map(i64::from)),
+        Primitive::I32(v) => integer(v.map(i64::from)),
+        Primitive::I64(v) => integer(v),
+        Primitive::U8(v) => integer(v.map(i64::from)),
+        Primitive::U16(v) => integer(v.map(i64::from)),
+        Primitive::U32(v) => integer(v.map(i64::from)),
+        Primitive::Bool(v) => integer(v.map(i64::from)),
+        Primitive::I128(v) => text(v.map(|v| widen(&(v as u128).to_be_bytes()))),
+        Primitive::U64(v) => text(v.map(|v| widen(&v.to_be_bytes()))),
+        Primitive::U128(v) => text(v.map(|v| widen(&v.to_be_bytes()))),
+        Primitive::U256(v) => text(v.map(|v| v.to_be_bytes())),
+        Primitive::Felt252(v)
+        | Primitive::ClassHash(v)
+        | Primitive::ContractAddress(v)
+        | Primitive::EthAddress(v) => text(v.map(|v| v.to_bytes_be())),
+    }
+}
+
+pub(crate) fn with_default(primitive: &Primitive) -> Primitive {
+    match *primitive {
+        Primitive::I8(v) => Primitive::I8(Some(v.unwrap_or_default())),
+        Primitive::I16(v) => Primitive::I16(Some(v.unwrap_or_default())),
+        Primitive::I32(v) => Primitive::I32(Some(v.unwrap_or_default())),
+        Primitive::I64(v) => Primitive::I64(Some(v.unwrap_or_default())),
+        Primitive::I128(v) => Primitive::I128(Some(v.unwrap_or_default())),
+        Primitive::U8(v) => Primitive::U8(Some(v.unwrap_or_default())),
+        Primitive::U16(v) => Primitive::U16(Some(v.unwrap_or_default())),
+        Primitive::U32(v) => Primitive::U32(Some(v.unwrap_or_default())),
+        Primitive::U64(v) => Primitive::U64(Some(v.unwrap_or_default())),
+        Primitive::U128(v) => Primitive::U128(Some(v.unwrap_or_default())),
+        Primitive::U256(v) => Primitive::U256(Some(v.unwrap_or(U256::ZERO))),
+        Primitive::Bool(v) => Primitive::Bool(Some(v.unwrap_or_default())),
+        Primitive::Felt252(v) => Primitive::Felt252(Some(v.unwrap_or_default())),
+        Primitive::ClassHash(v) => Primitive::ClassHash(Some(v.unwrap_or_default())),
+        Primitive::ContractAddress(v) => Primitive::ContractAddress(Some(v.unwrap_or_default())),
+        Primitive::EthAddress(v) => Primitive::EthAddress(Some(v.unwrap_or_default())),
+    }
+}
+
+fn widen(bytes: &[u8]) -> [u8; 32] {
+    let mut wide = [0; 32];
+    wide[32 - bytes.len()..].copy_from_slice(bytes);
+    wide
+}
+
+fn hex_text(bytes: &[u8; 32]) -> SqlValue {
+    let hex = bytes.iter().map(|byte| format!("{byte:02x}")).collect::<String>();
+    SqlValue::Text(format!("0x{hex}"))
+}
+
+/// SQLite's numeric affinity: text holding a number is converted to it.
+fn numeric_affinity(value: SqlValue) -> SqlValue {
+    let SqlValue::Text(text) = &value else {
+        return value;
+    };
+    let text = text.trim();
+
+    if let Ok(integer) = text.parse::<i64>() {
+        return SqlValue::Integer(integer);
+    }
+    // Rust also parses `inf` and `NaN`, which SQLite keeps as text
+    let is_real = text.bytes().all(|b| b.is_ascii_digit() || b"+-.eE".contains(&b));
+    match text.parse::<f64>() {
+        Ok(real) if is_real && real.fract() == 0.0 && real.abs() < i64::MAX as f64 => {
+            SqlValue::Integer(real as i64)
+        }
+        Ok(real) if is_real => SqlValue::Real(real),
+        _ => value,
+    }
+}
+
+fn serialize_ty(ty: &Ty, felts: &mut Vec<Felt>) {
+    match ty {
+        Ty::Primitive(primitive) => serialize_primitive(primitive, felts),
+        Ty::Struct(s) => s.children.iter().for_each(|member| serialize_ty(&member.ty, felts)),
+        Ty::Enum(e) => {
+            let option = e.option.unwrap_or_default();
+            felts.push(option.into());
+            if let Some(option) = e.options.get(usize::from(option)) {
+                serialize_ty(&option.ty, felts);
+            }
+        }
+        Ty::Tuple(values) | Ty::FixedSizeArray((values, _)) => {
+            values.iter().for_each(|value| serialize_ty(value, felts))
+        }
+        Ty::Array(values) => {
+            felts.push(Felt::from(values.len() as u64));
+            values.iter().for_each(|value| serialize_ty(value, felts));
+        }
+        Ty::ByteArray(bytes) => {
+            let chunks = bytes.as_bytes().chunks(31);
+            let pending = chunks.clone().last().filter(|chunk| chunk.len() < 31).unwrap_or(&[]);
+            let words = chunks.filter(|chunk| chunk.len() == 31).collect::<Vec<_>>();
+            felts.push(Felt::from(words.len() as u64));
+            felts.extend(words.iter().map(|word| Felt::from_bytes_be_slice(word)));
+            felts.push(Felt::from_bytes_be_slice(pending));
+            felts.push(Felt::from(pending.len() as u64));
+        }
+    }
+}
+
+fn serialize_primitive(primitive: &Primitive, felts: &mut Vec<Felt>) {
+    let felt = match *primitive {
+        Primitive::I8(v) => Felt::from(v.unwrap_or_default()),
+        Primitive::I16(v) => Felt::from(v.unwrap_or_default()),
+        Primitive::I32(v) => Felt::from(v.unwrap_or_default()),
+        Primitive::I64(v) => Felt::from(v.unwrap_or_default()),
+        Primitive::I128(v) => Felt::from(v.unwrap_or_default()),
+        Primitive::U8(v) => Felt::from(v.unwrap_or_default()),
+        Primitive::U16(v) => Felt::from(v.unwrap_or_default()),
+        Primitive::U32(v) => Felt::from(v.unwrap_or_default()),
+        Primitive::U64(v) => Felt::from(v.unwrap_or_default()),
+        Primitive::U128(v) => Felt::from(v.unwrap_or_default()),
+        Primitive::Bool(v) => Felt::from(u8::from(v.unwrap_or_default())),
+        Primitive::U256(v) => {
+            let bytes = v.unwrap_or(U256::ZERO).to_be_bytes();
+            felts.push(Felt::from_bytes_be_slice(&bytes[16..]));
+            felts.push(Felt::from_bytes_be_slice(&bytes[..16]));
+            return;
+        }
+        Primitive::Felt252(v)
+        | Primitive::ClassHash(v)
+        | Primitive::ContractAddress(v)
+        | Primitive::EthAddress(v) => v.unwrap_or_default(),
+    };
+    felts.push(felt);
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_types::schema::{Enum, EnumOption, Member};
+    use proptest::prelude::*;
+    use torii_proto::CompositeClause;
+
+    use super::*;
+
+    fn member(name: &str, ty: Ty) -> Member {
+        Member { name: name.to_string(), ty, key: false }
+    }
+
+    fn model(children: Vec<Member>) -> Struct {
+        Struct { name: "ns-Model".to_string(), children }
+    }
+
+    fn clause(member: &str, operator: ComparisonOperator, value: MemberValue) -> MemberClause {
+        MemberClause { model: "ns-Model".to_string(), member: member.to_string(), operator, value }
+    }
+
+    fn entity(models: Vec<Struct>) -> Entity {
+        Entity {
+            world_address: Felt::ZERO,
+            hashed_keys: Felt::ONE,
+            models,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            executed_at: Default::default(),
+        }
+    }
+
+    const ORDERING: [(ComparisonOperator, fn(Ordering) -> bool); 6] = [
+        (ComparisonOperator::Eq, Ordering::is_eq),
+        (ComparisonOperator::Neq, Ordering::is_ne),
+        (ComparisonOperator::Gt, Ordering::is_gt),
+        (ComparisonOperator::Gte, Ordering::is_ge),
+        (ComparisonOperator::Lt, Ordering::is_lt),
+        (ComparisonOperator::Lte, Ordering::is_le),
+    ];
+
+    /// Checks every ordering operator of the `value` member against `value`.
+    fn assert_ordering(model: &Struct, value: Primitive, expected: Ordering) {
+        for (operator, holds) in ORDERING {
+            let clause = clause("value", operator.clone(), MemberValue::Primitive(value));
+            assert_eq!(matches_member(&clause, model), holds(expected), "{operator:?}");
+        }
+    }
+
+    #[test]
+    fn matches_keys_like_torii() {
+        let clause = |keys: Vec<Option<Felt>>, pattern_matching| KeysClause {
+            keys,
+            pattern_matching,
+            models: vec![],
+        };
+        let keys = [Felt::ONE, Felt::TWO];
+
+        assert!(matches_keys(
+            &clause(vec![Some(Felt::ONE), None], PatternMatching::FixedLen),
+            &keys
+        ));
+        assert!(!matches_keys(&clause(vec![Some(Felt::ONE)], PatternMatching::FixedLen), &keys));
+        assert!(matches_keys(&clause(vec![Some(Felt::ONE)], PatternMatching::VariableLen), &keys));
+        assert!(!matches_keys(&clause(vec![Some(Felt::TWO)], PatternMatching::VariableLen), &keys));
+        assert!(matches_keys(&clause(vec![], PatternMatching::VariableLen), &keys));
+        assert!(!matches_keys(&clause(vec![], PatternMatching::FixedLen), &keys));
+    }
+
+    #[test]
+    fn matches_keys_per_model_like_torii() {
+        let keyed = |name: &str, keys: &[u64]| Struct {
+            name: name.to_string(),
+            children: keys
+                .iter()
+                .map(|key| Member {
+                    name: format!("key_{key}"),
+                    ty: Ty::Primitive(Primitive::Felt252(Some(Felt::from(*key)))),
+                    key: true,
+                })
+                .chain([member("value", Ty::Primitive(Primitive::U8(Some(0))))])
+                .collect(),
+        };
+        let clause = |keys: &[Option<u64>], pattern_matching, models: &[&str]| {
+            Clause::Keys(KeysClause {
+                keys: keys.iter().map(|key| key.map(Felt::from)).collect(),
+                pattern_matching,
+                models: models.iter().map(|model| model.to_string()).collect(),
+            })
+        };
+        // Keyed by player, and by player and index
+        let player = entity(vec![keyed("ns-Player", &[1]), keyed("ns-Item", &[1, 2])]);
+
+        // Torii matches `^0x1/0x2/$` against the keys of every model the clause lists
+        assert!(matches(&clause(&[Some(1), Some(2)], PatternMatching::FixedLen, &[]), &player));
+        assert!(matches(
+            &clause(&[Some(1), Some(2)], PatternMatching::FixedLen, &["ns-Item"]),
+            &player
+        ));
+        assert!(!matches(
+            &clause(&[Some(1), Some(2)], PatternMatching::FixedLen, &["ns-Player"]),
+            &player
+        ));
+        // `^0x1/$` and `^0x1(/0x[0-9a-fA-F]+)*/$`
+        assert!(matches(&clause(&[Some(1)], PatternMatching::FixedLen, &["ns-Player"]), &player));
+        assert!(!matches(&clause(&[Some(1)], PatternMatching::FixedLen, &["ns-Item"]), &player));
+        assert!(matches(&clause(&[Some(1)], PatternMatching::VariableLen, &["ns-Item"]), &player));
+        // `^0x[0-9a-fA-F]+/0x2/$`
+        assert!(matches(&clause(&[None, Some(2)], PatternMatching::FixedLen, &[]), &player));
+        assert!(!matches(&clause(&[None, Some(3)], PatternMatching::VariableLen, &[]), &player));
+        // Models the entity doesn't have match nothing
+        assert!(!matches(&clause(&[], PatternMatching::VariableLen, &["ns-Other"]), &player));
+        assert!(!matches(&clause(&[], PatternMatching::VariableLen, &[]), &entity(vec![])));
+    }
+
+    #[test]
+    fn matches_members_like_sqlite() {
+        let direction = Ty::Enum(Enum {
+            name: "Direction".to_string(),
+            option: Some(1),
+            options: vec![
+                EnumOption { name: "None".to_string(), ty: Ty::Tuple(vec![]) },
+                EnumOption { name: "Left".to_string(), ty: Ty::Primitive(Primitive::U8(Some(2))) },
+            ],
+        });
+        let model = model(vec![
+            member("x", Ty::Primitive(Primitive::U32(Some(10)))),
+            member("unset", Ty::Primitive(Primitive::U32(None))),
+            member("name", Ty::ByteArray("dojo".to_string())),
+            member("direction", direction),
+            member(
+                "items",
+                Ty::Array(vec![
+                    Ty::Primitive(Primitive::U16(Some(1))),
+                    Ty::Primitive(Primitive::U16(Some(3))),
+                ]),
+            ),
+        ]);
+        let string = |s: &str| MemberValue::String(s.to_string());
+        let u16 = |v| MemberValue::Primitive(Primitive::U16(Some(v)));
+        let check =
+            |member, operator, value| matches_member(&clause(member, operator, value), &model);
+
+        // Text holding a number compares as a number with integer columns, other text sorts last
+        assert!(check("x", ComparisonOperator::Eq, string("10")));
+        assert!(check("x", ComparisonOperator::Eq, string(" 10.0")));
+        assert!(!check("x", ComparisonOperator::Eq, string("0xa")));
+        assert!(check("x", ComparisonOperator::Lt, string("0xa")));
+
+        assert!(!check("unset", ComparisonOperator::Eq, u16(0)));
+        assert!(!check("unset", ComparisonOperator::Neq, u16(0)));
+        assert!(!check("unset", ComparisonOperator::NotIn, MemberValue::List(vec![u16(1)])));
+
+        assert!(check("name", ComparisonOperator::Eq, string("dojo")));
+        assert!(check("name", ComparisonOperator::Gt, string("do")));
+        assert!(check("direction", ComparisonOperator::Eq, string("Left")));
+        assert!(check("direction.Left", ComparisonOperator::Eq, u16(2)));
+        assert!(!check("direction.None", ComparisonOperator::Eq, u16(2)));
+
+        assert!(check("x", ComparisonOperator::In, MemberValue::List(vec![u16(1), u16(10)])));
+        assert!(check("x", ComparisonOperator::NotIn, MemberValue::List(vec![u16(1)])));
+
+        assert!(check("items", ComparisonOperator::Contains, u16(3)));
+        assert!(!check("items", ComparisonOperator::Contains, u16(2)));
+        assert!(check(
+            "items",
+            ComparisonOperator::ContainsAll,
+            MemberValue::List(vec![u16(1), u16(3)])
+        ));
+        assert!(!check(
+            "items",
+            ComparisonOperator::ContainsAll,
+            MemberValue::List(vec![u16(1), u16(2)])
+        ));
+        assert!(check(
+            "items",
+            ComparisonOperator::ContainsAny,
+            MemberValue::List(vec![u16(1), u16(2)])
+        ));
+        assert!(check("items", ComparisonOperator::ArrayLengthEq, string("2")));
+        assert!(check("items", ComparisonOperator::ArrayLengthGt, u16(1)));
+        assert!(!check("items", ComparisonOperator::ArrayLengthLt, u16(2)));
+    }
+
+    #[test]
+    fn matches_composite_clauses() {
+        let entity = entity(vec![model(vec![member("x", Ty::Primitive(Primitive::U8(Some(4))))])]);
+        let x = |operator, value| {
+            Clause::Member(clause(
+                "x",
+                operator,
+                MemberValue::Primitive(Primitive::U8(Some(value))),
+            ))
+        };
+        let composite =
+            |operator, clauses| Clause::Composite(CompositeClause { operator, clauses });
+
+        let gt = x(ComparisonOperator::Gt, 3);
+        let lt = x(ComparisonOperator::Lt, 3);
+        assert!(matches(&composite(LogicalOperator::And, vec![gt.clone()]), &entity));
+        assert!(!matches(&composite(LogicalOperator::And, vec![gt.clone(), lt.clone()]), &entity));
+        assert!(matches(&composite(LogicalOperator::Or, vec![gt, lt.clone()]), &entity));
+        assert!(matches(&composite(LogicalOperator::Or, vec![]), &entity));
+
+        let mut other =
+            clause("x", ComparisonOperator::Gt, MemberValue::Primitive(Primitive::U8(Some(0))));
+        other.model = "ns-Other".to_string();
+        assert!(!matches(&Clause::Member(other), &entity));
+        assert!(matches(&Clause::HashedKeys(vec![]), &entity));
+        assert!(!matches(&Clause::HashedKeys(vec![Felt::TWO]), &entity));
+    }
+
+    proptest! {
+        // Torii stores wide integers as padded hex text, which must sort like the numbers
+        #[test]
+        fn orders_wide_integers_numerically(a: u128, b: u128) {
+            let model = model_with(Primitive::U128(Some(a)));
+            assert_ordering(&model, Primitive::U128(Some(b)), a.cmp(&b));
+
+            let model = model_with(Primitive::U64(Some(a as u64)));
+            assert_ordering(&model, Primitive::U64(Some(b as u64)), (a as u64).cmp(&(b as u64)));
+
+            let (a, b) = (U256::from_u128(a).shl_vartime(100), U256::from_u128(b));
+            let model = model_with(Primitive::U256(Some(a)));
+            assert_ordering(&model, Primitive::U256(Some(b)), a.cmp(&b));
+        }
+
+        #[test]
+        fn orders_felts_numerically(a: [u8; 31], b: [u8; 31]) {
+            let (a, b) = (Felt::from_bytes_be_slice(&a), Felt::from_bytes_be_slice(&b));
+            let model = model_with(Primitive::ContractAddress(Some(a)));
+            assert_ordering(&model, Primitive::ContractAddress(Some(b)), a.cmp(&b));
+        }
+
+        // Small integers are stored as SQLite integers
+        #[test]
+        fn orders_small_integers_numerically(a: i64, b: i64, c: u32, d: u32) {
+            assert_ordering(&model_with(Primitive::I64(Some(a))), Primitive::I64(Some(b)), a.cmp(&b));
+            assert_ordering(&model_with(Primitive::U32(Some(c))), Primitive::U32(Some(d)), c.cmp(&d));
+        }
+
+        // Negative i128 are stored in two's complement text, sorting after positive ones
+        #[test]
+        fn orders_i128_as_twos_complement(a: i128, b: i128) {
+            let model = model_with(Primitive::I128(Some(a)));
+            assert_ordering(&model, Primitive::I128(Some(b)), (a as u128).cmp(&(b as u128)));
+        }
+
+        #[test]
+        fn in_is_any_eq(x: u8, list in proptest::collection::vec(any::<u8>(), 0..8)) {
+            let model = model_with(Primitive::U8(Some(x)));
+            let values = list
+                .into_iter()
+                .map(|v| MemberValue::Primitive(Primitive::U8(Some(v))))
+                .collect::<Vec<_>>();
+            let check = |operator, value| matches_member(&clause("value", operator, value), &model);
+
+            let any_eq = values.iter().any(|value| check(ComparisonOperator::Eq, value.clone()));
+            prop_assert_eq!(check(ComparisonOperator::In, MemberValue::List(values.clone())), any_eq);
+            prop_assert_eq!(check(ComparisonOperator::NotIn, MemberValue::List(values)), !any_eq);
+        }
+    }
+
+    fn model_with(value: Primitive) -> Struct {
+        model(vec![member("value", Ty::Primitive(value))])
+    }
+}
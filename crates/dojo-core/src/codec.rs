@@ -0,0 +1,103 @@
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use torii_proto::{Clause, Query};
+
+/// Leading byte of the binary encoding, bumped when it changes incompatibly.
+pub const BINARY_VERSION: u8 = 1;
+
+/// Canonical encodings of Torii's query types, shared by every binding.
+///
+/// The JSON encoding follows the shape of the `torii_proto` types, fields in declaration order,
+/// field elements as `0x` prefixed hex strings. The binary one is the same data as CBOR, after
+/// a [`BINARY_VERSION`] byte.
+pub trait Codec: Serialize + DeserializeOwned {
+    fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(|e| anyhow!("failed to encode query: {e}"))
+    }
+
+    fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| anyhow!("failed to decode query: {e}"))
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = vec![BINARY_VERSION];
+        ciborium::into_writer(self, &mut bytes)
+            .map_err(|e| anyhow!("failed to encode query: {e}"))?;
+        Ok(bytes)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        match bytes.split_first() {
+            Some((&BINARY_VERSION, data)) => {
+                ciborium::from_reader(data).map_err(|e| anyhow!("failed to decode query: {e}"))
+            }
+            Some((version, _)) => Err(anyhow!("unsupported query encoding version {version}")),
+            None => Err(anyhow!("failed to decode query: no data")),
+        }
+    }
+}
+
+impl Codec for Query {}
+impl Codec for Clause {}
+
+/// JSON encoding with the fields of every object sorted, so that equal values encode the same
+/// whatever the order of their maps. Keys cached results and recorded requests.
+pub fn canonical_json(value: &impl Serialize) -> Result<String> {
+    let mut value = serde_json::to_value(value).map_err(|e| anyhow!("failed to encode: {e}"))?;
+    value.sort_all_objects();
+    Ok(value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use torii_proto::{Pagination, PaginationDirection};
+
+    use super::*;
+    use crate::filter;
+
+    #[test]
+    fn round_trips_queries_and_clauses() {
+        let query = Query {
+            world_addresses: vec![starknet_crypto::Felt::ONE],
+            pagination: Pagination {
+                limit: Some(10),
+                cursor: None,
+                direction: PaginationDirection::Forward,
+                order_by: vec![],
+            },
+            clause: Some(
+                filter::parse("ns-Position.x > 10_u32 and keys(ns-Position) = [0x1, *]").unwrap(),
+            ),
+            no_hashed_keys: false,
+            models: vec!["ns-Position".to_string()],
+            historical: false,
+        };
+
+        let json = query.to_json().unwrap();
+        assert_eq!(Query::from_json(&json).unwrap().to_json().unwrap(), json);
+
+        let bytes = query.to_bytes().unwrap();
+        assert_eq!(bytes[0], BINARY_VERSION);
+        assert_eq!(Query::from_bytes(&bytes).unwrap().to_json().unwrap(), json);
+
+        let mut future = bytes.clone();
+        future[0] = BINARY_VERSION + 1;
+        assert!(Query::from_bytes(&future).is_err());
+        assert!(Query::from_bytes(&[]).is_err());
+
+        let clause = query.clause.unwrap();
+        let json = clause.to_json().unwrap();
+        assert_eq!(Clause::from_json(&json).unwrap().to_json().unwrap(), json);
+        let bytes = clause.to_bytes().unwrap();
+        assert_eq!(Clause::from_bytes(&bytes).unwrap().to_json().unwrap(), json);
+    }
+
+    #[test]
+    fn sorts_objects_in_canonical_json() {
+        let first: std::collections::HashMap<_, _> = [("b", 1), ("a", 2), ("c", 3)].into();
+        let second: std::collections::HashMap<_, _> = [("c", 3), ("a", 2), ("b", 1)].into();
+        assert_eq!(canonical_json(&first).unwrap(), r#"{"a":2,"b":1,"c":3}"#);
+        assert_eq!(canonical_json(&second).unwrap(), canonical_json(&first).unwrap());
+    }
+}
@@ -0,0 +1,193 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+
+use crate::cache::CacheConfig;
+
+/// Default maximum gRPC message size (4MB), matching `torii_client::Client::new`.
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Client configuration shared by every binding.
+///
+/// Each binding exposes its own FFI-friendly mirror of this struct and converts it here, so
+/// defaults and validation stay identical across C, WASM and UniFFI.
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// URL of the Torii server
+    pub torii_url: String,
+    /// Additional Torii replicas to fail over to when `torii_url` becomes unhealthy
+    pub fallback_urls: Vec<String>,
+    /// Maximum time to wait for the initial connection
+    pub connect_timeout: Option<Duration>,
+    /// Maximum time to wait for a single query
+    pub request_timeout: Option<Duration>,
+    /// Maximum size of a decoded gRPC message
+    pub max_message_size: usize,
+    /// Reconnection policy used by subscriptions
+    pub retry: RetryPolicy,
+    /// Polling fallback for entity and event message subscriptions. Disabled when `None`.
+    pub polling: Option<PollingConfig>,
+    /// Shares one stream between subscriptions of the same kind, see [`Mux`](crate::mux::Mux)
+    pub multiplex: bool,
+    /// Caches query results, see [`QueryCache`](crate::cache::QueryCache). Disabled when `None`.
+    pub cache: Option<CacheConfig>,
+    /// Checks entity and event message clauses against the world schemas before sending them,
+    /// see [`Validator`](crate::validate::Validator)
+    pub validate_queries: bool,
+}
+
+impl ClientConfig {
+    pub fn new(torii_url: impl Into<String>) -> Self {
+        Self {
+            torii_url: torii_url.into(),
+            fallback_urls: Vec::new(),
+            connect_timeout: None,
+            request_timeout: None,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            retry: RetryPolicy::default(),
+            polling: None,
+            multiplex: false,
+            cache: None,
+            validate_queries: false,
+        }
+    }
+
+    /// Every configured endpoint, primary first.
+    pub fn endpoints(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.torii_url).chain(self.fallback_urls.iter())
+    }
+
+    /// Checks the configuration for values that can never produce a working client.
+    pub fn validate(&self) -> Result<()> {
+        for torii_url in self.endpoints() {
+            url::Url::parse(torii_url)
+                .map_err(|e| anyhow!("invalid torii url {torii_url}: {e}"))?;
+        }
+
+        if self.max_message_size == 0 {
+            return Err(anyhow!("max message size must be greater than zero"));
+        }
+
+        if let Some(polling) = &self.polling {
+            polling.validate()?;
+        }
+
+        if let Some(cache) = &self.cache {
+            cache.validate()?;
+        }
+
+        self.retry.validate()
+    }
+}
+
+/// Exponential backoff used when a subscription stream has to be re-established.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Delay before the first reconnection attempt
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay between attempts
+    pub max_backoff: Duration,
+    /// Factor applied to the delay after each failed attempt
+    pub multiplier: u32,
+    /// Number of consecutive failed attempts after which the subscription gives up.
+    /// `None` retries forever.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            multiplier: 2,
+            max_attempts: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn validate(&self) -> Result<()> {
+        if self.multiplier == 0 {
+            return Err(anyhow!("retry multiplier must be greater than zero"));
+        }
+        if self.initial_backoff > self.max_backoff {
+            return Err(anyhow!("initial backoff cannot be greater than max backoff"));
+        }
+
+        Ok(())
+    }
+
+    /// Delay to wait before the given (zero based) reconnection attempt.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = self.multiplier.saturating_pow(attempt);
+        std::cmp::min(self.initial_backoff.saturating_mul(factor), self.max_backoff)
+    }
+
+    /// Whether the given number of consecutive failed attempts exhausts the policy.
+    pub fn exhausted(&self, attempts: u32) -> bool {
+        self.max_attempts.is_some_and(|max| attempts >= max)
+    }
+}
+
+/// Polling fallback for networks where long-lived gRPC streams are unavailable.
+///
+/// Entity and event message subscriptions switch to periodically querying Torii once their
+/// stream failed `after_failures` consecutive times, emitting the same updates to callbacks.
+#[derive(Debug, Clone)]
+pub struct PollingConfig {
+    /// Delay between two polls
+    pub interval: Duration,
+    /// Consecutive failed stream attempts before switching to polling. `0` polls right away.
+    pub after_failures: u32,
+    /// Maximum number of entities fetched per request
+    pub page_size: u32,
+}
+
+impl Default for PollingConfig {
+    fn default() -> Self {
+        Self { interval: Duration::from_secs(2), after_failures: 3, page_size: 100 }
+    }
+}
+
+impl PollingConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.interval.is_zero() {
+            return Err(anyhow!("polling interval must be greater than zero"));
+        }
+        if self.page_size == 0 {
+            return Err(anyhow!("polling page size must be greater than zero"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds a Torii client from the given configuration, see [`ClientConfig::validate`].
+pub async fn connect(config: &ClientConfig) -> Result<torii_client::Client> {
+    config.validate()?;
+
+    torii_client::Client::new_with_config(config.torii_url.clone(), config.max_message_size)
+        .await
+        .map_err(|e| anyhow!("failed to connect to {}: {e}", config.torii_url))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refuses_configurations_that_cannot_connect() {
+        let mut config = ClientConfig::new("http://localhost:8080");
+        assert!(config.validate().is_ok());
+
+        config.fallback_urls.push("not a url".to_string());
+        assert!(config.validate().is_err());
+
+        config.fallback_urls.clear();
+        config.max_message_size = 0;
+        assert_eq!(
+            config.validate().unwrap_err().to_string(),
+            "max message size must be greater than zero"
+        );
+    }
+}
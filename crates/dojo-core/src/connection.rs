@@ -0,0 +1,170 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use starknet_crypto::Felt;
+use torii_client::Client;
+use torii_proto::Clause;
+
+use crate::cache::{CacheKey, QueryCache};
+use crate::config::ClientConfig;
+use crate::failover::{EndpointPool, Torii, Transport};
+use crate::replay::{Recorder, Replay, Session};
+use crate::upgrade::Upgrades;
+use crate::validate::Validator;
+
+/// What a client talks to Torii through: the [`EndpointPool`] it fails over between, and the
+/// layers built around it.
+///
+/// Requests go through the session, which records or replays them, then the pool. Their
+/// results are kept by the [`QueryCache`] until subscription updates change them, and clauses
+/// are checked by a [`Validator`] built from the world schemas. Both are dropped when a schema
+/// changes, along with the state of the subscriptions following [`Upgrades`].
+pub struct Connection<T: Transport = Torii> {
+    pool: EndpointPool<T>,
+    session: Option<Session>,
+    cache: Option<QueryCache>,
+    validator: Mutex<Option<Arc<Validator>>>,
+    upgrades: Upgrades,
+}
+
+impl Connection {
+    /// Wraps an already connected client, see [`EndpointPool::from_client`].
+    pub fn from_client(client: Client, config: ClientConfig) -> Self {
+        Self::new(EndpointPool::from_client(client, config))
+    }
+
+    /// Connects to every endpoint of the configuration, see [`EndpointPool::connect`].
+    pub async fn connect(config: ClientConfig) -> Result<Self> {
+        Ok(Self::new(EndpointPool::connect(config).await?))
+    }
+
+    /// Serves requests and subscriptions from a recording instead of Torii, see [`Replay`].
+    ///
+    /// The connection never reaches the network: `config` only sets how the client behaves,
+    /// e.g. whether it multiplexes subscriptions, which should match the recorded session.
+    pub fn replay(config: ClientConfig, replay: Replay) -> Self {
+        let mut connection = Self::new(EndpointPool::unconnected(config));
+        connection.session = Some(Session::Replay(Arc::new(replay)));
+        connection
+    }
+
+    /// Validator built from the worlds of the active endpoint, fetched on first use.
+    pub async fn validator(&self) -> Result<Arc<Validator>> {
+        if let Some(validator) = self.validator.lock().unwrap().clone() {
+            return Ok(validator);
+        }
+
+        let key = CacheKey::normalized("worlds", &Vec::<Felt>::new());
+        let worlds =
+            self.exchange(key, |client| async move { client.worlds(Vec::new()).await }).await?;
+        let validator = Arc::new(Validator::new(&worlds));
+        *self.validator.lock().unwrap() = Some(validator.clone());
+        Ok(validator)
+    }
+
+    /// Checks a clause against the world schemas when [`ClientConfig::validate_queries`] is
+    /// set, failing with every diagnostic.
+    pub async fn validate_clause(
+        &self,
+        clause: Option<&Clause>,
+        world_addresses: &[Felt],
+    ) -> Result<()> {
+        let Some(clause) = clause.filter(|_| self.config().validate_queries) else {
+            return Ok(());
+        };
+
+        let diagnostics = self.validator().await?.validate_clause(clause, world_addresses);
+        if diagnostics.is_empty() {
+            return Ok(());
+        }
+        let diagnostics = diagnostics.iter().map(ToString::to_string).collect::<Vec<_>>();
+        Err(anyhow!("invalid clause: {}", diagnostics.join("; ")))
+    }
+}
+
+impl<T: Transport> Connection<T> {
+    pub fn new(pool: EndpointPool<T>) -> Self {
+        let cache = pool.config().cache.clone().map(QueryCache::new);
+        Self {
+            pool,
+            session: None,
+            cache,
+            validator: Mutex::new(None),
+            upgrades: Upgrades::default(),
+        }
+    }
+
+    /// Records the responses and subscription messages of the session, see [`Recorder`].
+    pub fn with_recorder(mut self, recorder: Recorder) -> Self {
+        self.session = Some(Session::Record(Arc::new(recorder)));
+        self
+    }
+
+    /// Endpoints requests and subscriptions fail over between.
+    pub fn pool(&self) -> &EndpointPool<T> {
+        &self.pool
+    }
+
+    /// Configuration shared by every endpoint, see [`EndpointPool::config`].
+    pub fn config(&self) -> &ClientConfig {
+        self.pool.config()
+    }
+
+    /// Cache of query results, when configured.
+    pub fn cache(&self) -> Option<&QueryCache> {
+        self.cache.as_ref()
+    }
+
+    /// Whether the session is recorded or replays a recorded one.
+    pub fn session(&self) -> Option<&Session> {
+        self.session.as_ref()
+    }
+
+    /// Whether the session replays a recording rather than talking to Torii.
+    pub fn is_replaying(&self) -> bool {
+        matches!(self.session, Some(Session::Replay(_)))
+    }
+
+    /// Models upgraded while the client runs, see [`Connection::invalidate_schema`].
+    pub fn upgrades(&self) -> &Upgrades {
+        &self.upgrades
+    }
+
+    /// Sends a request to the active endpoint, `fetch` being called with its client, see
+    /// [`EndpointPool::request`].
+    ///
+    /// When recording, the response is recorded under `key`. When replaying, the recorded
+    /// response is returned instead and `fetch` isn't called. Every request of the bindings
+    /// goes through here, so that a replayed session never needs a network.
+    pub async fn exchange<R, F, Fut, E>(&self, key: CacheKey, fetch: F) -> Result<R>
+    where
+        R: Serialize + DeserializeOwned,
+        F: FnOnce(Arc<T::Client>) -> Fut,
+        Fut: Future<Output = Result<R, E>>,
+        anyhow::Error: From<E>,
+    {
+        if let Some(Session::Replay(replay)) = &self.session {
+            return replay.response(&key);
+        }
+
+        let result = self.pool.request(fetch).await;
+        if let Some(Session::Record(recorder)) = &self.session {
+            recorder.response(&key, &result);
+        }
+        result
+    }
+
+    /// Drops what was built from the schema of a world: its cached entity results and the
+    /// validator, fetched again on next use. The upgraded models are recorded for the
+    /// subscriptions that built state from them, see [`Upgrades`].
+    pub fn invalidate_schema(&self, world_address: Felt, models: &[String]) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate_world(world_address);
+        }
+        *self.validator.lock().unwrap() = None;
+        self.upgrades.record(models);
+    }
+}
@@ -0,0 +1,68 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+
+use dojo_types::schema::Struct;
+use starknet_crypto::Felt;
+use torii_proto::schema::Entity;
+
+/// Default number of entities whose last delivered state is remembered.
+pub const DEFAULT_DEDUP_CAPACITY: usize = 10_000;
+
+/// Hash of an entity's models, used to tell whether two updates carry the same state.
+pub fn model_hash(models: &[Struct]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    models.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Suppresses entity updates that repeat the state last delivered for the same entity.
+///
+/// Replicas and reconnections can replay updates a consumer has already seen: after a failover
+/// the new endpoint may still be indexing blocks the previous one already streamed. Keys are
+/// forgotten in insertion order once `capacity` entities are tracked.
+#[derive(Debug)]
+pub struct Deduplicator {
+    last: HashMap<Felt, u64>,
+    order: VecDeque<Felt>,
+    capacity: usize,
+}
+
+impl Default for Deduplicator {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEDUP_CAPACITY)
+    }
+}
+
+impl Deduplicator {
+    pub fn new(capacity: usize) -> Self {
+        Self { last: HashMap::new(), order: VecDeque::new(), capacity: capacity.max(1) }
+    }
+
+    /// Records the state of `hashed_keys` and returns whether it differs from the last one seen.
+    pub fn observe(&mut self, hashed_keys: Felt, model_hash: u64) -> bool {
+        match self.last.insert(hashed_keys, model_hash) {
+            Some(previous) => previous != model_hash,
+            None => {
+                self.order.push_back(hashed_keys);
+                if self.order.len() > self.capacity {
+                    if let Some(evicted) = self.order.pop_front() {
+                        self.last.remove(&evicted);
+                    }
+                }
+                true
+            }
+        }
+    }
+
+    /// Records an entity update and returns whether it should be delivered.
+    pub fn observe_entity(&mut self, entity: &Entity) -> bool {
+        self.observe(entity.hashed_keys, model_hash(&entity.models))
+    }
+
+    /// Forgets every tracked entity.
+    pub fn clear(&mut self) {
+        self.last.clear();
+        self.order.clear();
+    }
+}
@@ -0,0 +1,462 @@
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+use std::mem;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::Duration;
+
+use futures::future::poll_fn;
+use futures::task::AtomicWaker;
+use starknet_crypto::Felt;
+use torii_proto::schema::Entity;
+
+use crate::change::merge_update;
+use crate::projection::{Project, Projection};
+
+/// What happens when the buffer of a subscription is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    /// Stops reading the stream until the callback caught up, so that Torii holds the updates
+    /// back instead. A subscription sharing a multiplexed stream can't hold it back for the
+    /// others, and drops its oldest buffered update instead.
+    #[default]
+    Wait,
+    /// Drops the oldest buffered update to make room for the new one
+    DropOldest,
+    /// Drops the new update
+    DropNewest,
+}
+
+/// How subscription updates are handed to a consumer that may not keep up with them.
+///
+/// Updates are buffered between the stream and the callback. The default policy buffers up to
+/// [`DEFAULT_BUFFER_SIZE`] updates, then waits for the callback to catch up. It never drops or
+/// merges anything and delivers updates as soon as the callback is free.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeliveryPolicy {
+    /// Maximum number of updates buffered or being delivered. Unbounded when `None`, in which
+    /// case a callback that can't keep up makes the buffer grow without limit.
+    pub buffer_size: Option<usize>,
+    /// What to drop when the buffer is full
+    pub overflow: Overflow,
+    /// Merges the buffered updates of each entity, by hashed keys, into a single one. Only
+    /// applies to entity and event message subscriptions.
+    pub coalesce: bool,
+    /// Minimum delay between two deliveries, e.g. one frame. Every update buffered in the
+    /// meantime is delivered at once.
+    pub min_interval: Option<Duration>,
+}
+
+/// Updates buffered by the default [`DeliveryPolicy`] before the stream is held back.
+pub const DEFAULT_BUFFER_SIZE: usize = 1024;
+
+impl Default for DeliveryPolicy {
+    fn default() -> Self {
+        Self {
+            buffer_size: Some(DEFAULT_BUFFER_SIZE),
+            overflow: Overflow::default(),
+            coalesce: false,
+            min_interval: None,
+        }
+    }
+}
+
+/// Counters of what happened to a subscription's updates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeliveryStats {
+    /// Updates handed to the callback
+    pub delivered: u64,
+    /// Updates dropped because the buffer was full
+    pub dropped: u64,
+    /// Updates merged into an earlier buffered update of the same entity
+    pub coalesced: u64,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    policy: DeliveryPolicy,
+    stats: DeliveryStats,
+    projection: Option<Arc<Projection>>,
+    /// Updates buffered or being delivered
+    pending: usize,
+}
+
+/// Handle to configure the delivery of a subscription and read its counters.
+#[derive(Debug, Clone, Default)]
+pub struct DeliveryControl {
+    shared: Arc<Mutex<Shared>>,
+    /// Woken when updates were delivered, see [`DeliveryControl::ready`]
+    room: Arc<AtomicWaker>,
+}
+
+impl DeliveryControl {
+    pub fn policy(&self) -> DeliveryPolicy {
+        self.shared.lock().unwrap().policy.clone()
+    }
+
+    /// Replaces the policy. Applies to the updates received from now on.
+    pub fn set_policy(&self, policy: DeliveryPolicy) {
+        self.shared.lock().unwrap().policy = policy;
+        self.room.wake();
+    }
+
+    pub fn stats(&self) -> DeliveryStats {
+        self.shared.lock().unwrap().stats
+    }
+
+    /// Replaces the projection applied to entity updates, see [`DeliveryControl::project`].
+    /// Applies to the updates delivered from now on.
+    pub fn set_projection(&self, projection: Option<Projection>) {
+        self.shared.lock().unwrap().projection = projection.map(Arc::new);
+    }
+
+    /// Applies the projection to what the callback of [`channel_with`] is about to deliver,
+    /// once diffs, predictions and classifications were computed from the whole update.
+    /// Returns false if nothing is left to deliver.
+    pub fn project<T: Project>(&self, item: &mut T) -> bool {
+        let projection = self.shared.lock().unwrap().projection.clone();
+        projection.is_none_or(|projection| item.project(&projection))
+    }
+
+    /// Completes once the buffer has room for another update, right away unless its policy
+    /// [waits](Overflow::Wait). Awaited by the stream loop before every update, see
+    /// [`drive`](crate::subscription::drive).
+    pub async fn ready(&self) {
+        poll_fn(|cx| {
+            self.room.register(cx.waker());
+
+            let shared = self.shared.lock().unwrap();
+            let DeliveryPolicy { buffer_size, overflow, .. } = shared.policy;
+            if overflow == Overflow::Wait && buffer_size.is_some_and(|size| shared.pending >= size)
+            {
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
+        })
+        .await
+    }
+
+    /// Counts delivered updates, making room for as many.
+    fn delivered(&self, count: usize) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.stats.delivered += count as u64;
+        shared.pending -= count;
+        drop(shared);
+
+        self.room.wake();
+    }
+}
+
+/// How updates of the same entity are recognized and merged when coalescing.
+#[derive(Debug)]
+pub struct Coalesce<T> {
+    /// Hashed keys of the entity an update belongs to
+    pub key: fn(&T) -> Felt,
+    /// Merges a later update into the buffered one
+    pub merge: fn(&mut T, T),
+}
+
+impl<T> Clone for Coalesce<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Coalesce<T> {}
+
+impl Coalesce<Entity> {
+    /// Entity and event message updates, which only carry the models that changed and are
+    /// merged by model name, see [`merge_update`].
+    pub const ENTITIES: Self = Self { key: hashed_keys, merge: merge_update };
+}
+
+/// Buffered updates, along with the hashed keys used to coalesce them.
+struct Buffer<T> {
+    items: VecDeque<(Option<Felt>, T)>,
+    keys: HashSet<Felt>,
+}
+
+impl<T> Default for Buffer<T> {
+    fn default() -> Self {
+        Self { items: VecDeque::new(), keys: HashSet::new() }
+    }
+}
+
+impl<T> Buffer<T> {
+    fn push(&mut self, key: Option<Felt>, item: T, merge: fn(&mut T, T), shared: &mut Shared) {
+        let policy = &shared.policy;
+
+        if let Some(key) = key.filter(|_| policy.coalesce) {
+            if self.keys.contains(&key) {
+                if let Some(slot) = self.items.iter_mut().find(|(k, _)| *k == Some(key)) {
+                    merge(&mut slot.1, item);
+                    shared.stats.coalesced += 1;
+                    return;
+                }
+            }
+        }
+
+        if policy.buffer_size.is_some_and(|size| self.items.len() >= size) {
+            shared.stats.dropped += 1;
+            match policy.overflow {
+                Overflow::DropNewest => return,
+                // Only reached by updates that didn't wait, see `Overflow::Wait`
+                Overflow::Wait | Overflow::DropOldest => {
+                    let Some((key, _)) = self.items.pop_front() else {
+                        return;
+                    };
+                    if let Some(key) = key {
+                        self.keys.remove(&key);
+                    }
+                    shared.pending -= 1;
+                }
+            }
+        }
+
+        if let Some(key) = key {
+            self.keys.insert(key);
+        }
+        self.items.push_back((key, item));
+        shared.pending += 1;
+    }
+
+    fn take(&mut self) -> Vec<T> {
+        self.keys.clear();
+        mem::take(&mut self.items).into_iter().map(|(_, item)| item).collect()
+    }
+}
+
+struct Queue<T> {
+    buffer: Mutex<Buffer<T>>,
+    waker: AtomicWaker,
+    closed: AtomicBool,
+}
+
+/// Pushes updates into a subscription's delivery buffer. Closes the buffer when dropped.
+struct Sender<T> {
+    queue: Arc<Queue<T>>,
+    control: DeliveryControl,
+    coalesce: Option<Coalesce<T>>,
+}
+
+impl<T> Sender<T> {
+    fn send(&self, item: T) {
+        let key = self.coalesce.map(|coalesce| (coalesce.key)(&item));
+        let merge = self.coalesce.map_or(replace, |coalesce| coalesce.merge);
+        let mut shared = self.control.shared.lock().unwrap();
+        self.queue.buffer.lock().unwrap().push(key, item, merge, &mut shared);
+        drop(shared);
+
+        self.queue.waker.wake();
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.queue.closed.store(true, Ordering::SeqCst);
+        self.queue.waker.wake();
+    }
+}
+
+/// Key entity and event message updates are coalesced by.
+pub fn hashed_keys(entity: &Entity) -> Felt {
+    entity.hashed_keys
+}
+
+fn replace<T>(buffered: &mut T, item: T) {
+    *buffered = item;
+}
+
+/// Creates the buffer between a subscription and its callback.
+///
+/// Returns the closure to hand updates to, the handle to control the delivery, and the future
+/// delivering buffered updates to `on_item`, to be spawned alongside the subscription. It
+/// completes once the returned closure is dropped and every buffered update is delivered.
+///
+/// `coalesce` tells how updates of the same entity are merged, if the subscription supports it.
+/// `sleep` abstracts over the runtime, like for [`drive`](crate::subscription::drive).
+pub fn channel<T, Z, ZF>(
+    coalesce: Option<Coalesce<T>>,
+    sleep: Z,
+    on_item: impl FnMut(T),
+) -> (impl FnMut(T), DeliveryControl, impl Future<Output = ()>)
+where
+    Z: Fn(Duration) -> ZF,
+    ZF: Future<Output = ()>,
+{
+    channel_with(DeliveryControl::default(), coalesce, sleep, on_item)
+}
+
+/// Creates the buffer between a subscription and its callback, like [`channel`], controlled
+/// by an existing handle, e.g. one `on_item` projects its updates with.
+pub fn channel_with<T, Z, ZF>(
+    control: DeliveryControl,
+    coalesce: Option<Coalesce<T>>,
+    sleep: Z,
+    mut on_item: impl FnMut(T),
+) -> (impl FnMut(T), DeliveryControl, impl Future<Output = ()>)
+where
+    Z: Fn(Duration) -> ZF,
+    ZF: Future<Output = ()>,
+{
+    let queue = Arc::new(Queue {
+        buffer: Mutex::new(Buffer::default()),
+        waker: AtomicWaker::new(),
+        closed: AtomicBool::new(false),
+    });
+    let sender = Sender { queue: queue.clone(), control: control.clone(), coalesce };
+
+    let delivery = control.clone();
+    let deliver = async move {
+        loop {
+            let batch = poll_fn(|cx| {
+                queue.waker.register(cx.waker());
+
+                let batch = queue.buffer.lock().unwrap().take();
+                if !batch.is_empty() {
+                    Poll::Ready(Some(batch))
+                } else if queue.closed.load(Ordering::SeqCst) {
+                    Poll::Ready(None)
+                } else {
+                    Poll::Pending
+                }
+            })
+            .await;
+            let Some(batch) = batch else {
+                break;
+            };
+
+            let count = batch.len();
+            batch.into_iter().for_each(&mut on_item);
+            delivery.delivered(count);
+
+            if let Some(interval) = delivery.policy().min_interval {
+                sleep(interval).await;
+            }
+        }
+    };
+
+    (move |item| sender.send(item), control, deliver)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    use dojo_types::primitive::Primitive;
+    use dojo_types::schema::{Member, Struct, Ty};
+    use futures::executor::LocalPool;
+    use futures::task::LocalSpawnExt;
+
+    use super::*;
+
+    fn push_all(policy: DeliveryPolicy, items: &[(u64, u32)]) -> (Vec<u32>, DeliveryStats) {
+        let mut shared = Shared { policy, ..Default::default() };
+        let mut buffer = Buffer::default();
+        for (key, item) in items {
+            buffer.push(Some(Felt::from(*key)), *item, replace, &mut shared);
+        }
+        (buffer.take(), shared.stats)
+    }
+
+    #[test]
+    fn drops_oldest_when_full() {
+        let policy = DeliveryPolicy {
+            buffer_size: Some(2),
+            overflow: Overflow::DropOldest,
+            ..Default::default()
+        };
+        let (items, stats) = push_all(policy, &[(1, 1), (2, 2), (3, 3)]);
+        assert_eq!(items, vec![2, 3]);
+        assert_eq!(stats.dropped, 1);
+    }
+
+    #[test]
+    fn drops_newest_when_full() {
+        let policy = DeliveryPolicy {
+            buffer_size: Some(2),
+            overflow: Overflow::DropNewest,
+            ..Default::default()
+        };
+        let (items, stats) = push_all(policy, &[(1, 1), (2, 2), (3, 3)]);
+        assert_eq!(items, vec![1, 2]);
+        assert_eq!(stats.dropped, 1);
+    }
+
+    #[test]
+    fn waits_for_a_slow_consumer() {
+        let delivered = Rc::new(RefCell::new(Vec::new()));
+        let sink = delivered.clone();
+        let (mut send, control, deliver) = channel(
+            None,
+            |_| futures::future::ready(()),
+            move |item: u32| sink.borrow_mut().push(item),
+        );
+        control.set_policy(DeliveryPolicy { buffer_size: Some(2), ..Default::default() });
+
+        let mut pool = LocalPool::new();
+        let (sent, pace) = (Rc::new(Cell::new(0)), control.clone());
+        let counted = sent.clone();
+        let produce = async move {
+            for item in 0..10 {
+                pace.ready().await;
+                send(item);
+                counted.set(counted.get() + 1);
+            }
+        };
+        pool.spawner().spawn_local(produce).unwrap();
+
+        // Nothing is delivered yet, so the producer stops once the buffer is full
+        pool.run_until_stalled();
+        assert_eq!(sent.get(), 2);
+
+        pool.spawner().spawn_local(deliver).unwrap();
+        pool.run();
+        assert_eq!(*delivered.borrow(), (0..10).collect::<Vec<_>>());
+        assert_eq!(control.stats(), DeliveryStats { delivered: 10, dropped: 0, coalesced: 0 });
+    }
+
+    #[test]
+    fn coalesces_by_key_in_place() {
+        let policy = DeliveryPolicy { coalesce: true, ..Default::default() };
+        let (items, stats) = push_all(policy, &[(1, 1), (2, 2), (1, 3)]);
+        assert_eq!(items, vec![3, 2]);
+        assert_eq!(stats.coalesced, 1);
+    }
+
+    #[test]
+    fn coalesces_entities_by_model() {
+        let model = |name: &str| Struct {
+            name: name.to_string(),
+            children: vec![Member {
+                name: "value".to_string(),
+                ty: Ty::Primitive(Primitive::U32(Some(1))),
+                key: false,
+            }],
+        };
+        let entity = |models| Entity {
+            world_address: Felt::ZERO,
+            hashed_keys: Felt::ONE,
+            models,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            executed_at: Default::default(),
+        };
+        let policy = DeliveryPolicy { coalesce: true, ..Default::default() };
+        let mut shared = Shared { policy, ..Default::default() };
+        let mut buffer = Buffer::default();
+        let Coalesce { key, merge } = Coalesce::ENTITIES;
+        for update in [entity(vec![model("ns-Position")]), entity(vec![model("ns-Moves")])] {
+            buffer.push(Some(key(&update)), update, merge, &mut shared);
+        }
+
+        let items = buffer.take();
+        assert_eq!(items.len(), 1);
+        let names = items[0].models.iter().map(|m| m.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["ns-Position", "ns-Moves"]);
+        assert_eq!(shared.stats.coalesced, 1);
+    }
+}
@@ -0,0 +1,316 @@
+use std::collections::HashMap;
+
+use dojo_types::schema::{Struct, Ty};
+use starknet_crypto::Felt;
+use torii_proto::schema::Entity;
+
+use crate::change::{is_deleted, is_model_deleted};
+
+/// What a [`FieldChange`] describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// A model of an entity seen for the first time
+    EntityCreated,
+    /// A model newly set on a known entity
+    ModelAdded,
+    /// A member whose value changed
+    MemberUpdated,
+    /// A model deleted from an entity that still has other models
+    ModelRemoved,
+    /// A model of an entity that no longer has any model
+    EntityDeleted,
+}
+
+/// A single change between two states of an entity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub hashed_keys: Felt,
+    pub kind: ChangeKind,
+    /// Name of the model, e.g. `ns-Position`
+    pub model: String,
+    /// Path of the changed member within the model, e.g. `vec.x` or `items[2]`. Empty when the
+    /// whole model changed.
+    pub path: String,
+    /// Previous value, `None` when the model was just set
+    pub old: Option<Ty>,
+    /// New value, `None` when the model was removed
+    pub new: Option<Ty>,
+}
+
+/// Turns entity updates into field level changes, against the last state delivered for each
+/// entity.
+///
+/// Torii updates only carry the models that changed, so each update is merged into the known
+/// state of the entity by model name and models it doesn't carry are left untouched. An update
+/// classified as a deletion by [`is_deleted`] deletes the entity, and models reported deleted by
+/// [`is_model_deleted`] are removed from it. Entities are reported as created the first time
+/// they are seen.
+#[derive(Debug, Default)]
+pub struct EntityDiffer {
+    previous: HashMap<Felt, Vec<Struct>>,
+}
+
+impl EntityDiffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the new state of an entity and returns what changed since the previous one.
+    pub fn diff(&mut self, entity: &Entity) -> Vec<FieldChange> {
+        if is_deleted(entity) {
+            let previous = self.previous.remove(&entity.hashed_keys);
+            return diff_entity(entity.hashed_keys, previous.as_deref(), &[]);
+        }
+
+        let (removed, set): (Vec<_>, Vec<_>) =
+            entity.models.iter().cloned().partition(is_model_deleted);
+        let Some(models) = self.previous.get_mut(&entity.hashed_keys) else {
+            if !set.is_empty() {
+                self.previous.insert(entity.hashed_keys, set.clone());
+            }
+            return diff_entity(entity.hashed_keys, None, &set);
+        };
+
+        // Only the models carried by the update can have changed
+        let carried = |model: &&Struct| set.iter().any(|m| m.name == model.name);
+        let previous = models.iter().filter(carried).cloned().collect::<Vec<_>>();
+        for model in &set {
+            match models.iter_mut().find(|m| m.name == model.name) {
+                Some(slot) => *slot = model.clone(),
+                None => models.push(model.clone()),
+            }
+        }
+        let mut changes = diff_entity(entity.hashed_keys, Some(&previous), &set);
+
+        let removed = removed
+            .iter()
+            .filter_map(|model| {
+                let index = models.iter().position(|m| m.name == model.name)?;
+                Some(models.remove(index))
+            })
+            .collect::<Vec<_>>();
+        let kind =
+            if models.is_empty() { ChangeKind::EntityDeleted } else { ChangeKind::ModelRemoved };
+        if models.is_empty() {
+            self.previous.remove(&entity.hashed_keys);
+        }
+        changes.extend(removed.into_iter().map(|model| FieldChange {
+            hashed_keys: entity.hashed_keys,
+            kind,
+            model: model.name.clone(),
+            path: String::new(),
+            old: Some(Ty::Struct(model)),
+            new: None,
+        }));
+        changes
+    }
+
+    /// Forgets the recorded states of a model, e.g. once upgraded, so that its next update is
+    /// reported as set rather than diffed against the previous schema.
+    pub fn forget_model(&mut self, model: &str) {
+        for models in self.previous.values_mut() {
+            models.retain(|m| m.name != model);
+        }
+    }
+}
+
+/// Changes between two states of an entity. `old` is `None` if the entity wasn't known.
+pub fn diff_entity(hashed_keys: Felt, old: Option<&[Struct]>, new: &[Struct]) -> Vec<FieldChange> {
+    let change = |kind, model: &str, path: String, old: Option<Ty>, new: Option<Ty>| FieldChange {
+        hashed_keys,
+        kind,
+        model: model.to_string(),
+        path,
+        old,
+        new,
+    };
+
+    let Some(old) = old else {
+        return new
+            .iter()
+            .map(|model| {
+                let ty = Some(Ty::Struct(model.clone()));
+                change(ChangeKind::EntityCreated, &model.name, String::new(), None, ty)
+            })
+            .collect();
+    };
+
+    let removed = if new.is_empty() { ChangeKind::EntityDeleted } else { ChangeKind::ModelRemoved };
+    let mut changes = Vec::new();
+
+    for model in old {
+        if !new.iter().any(|m| m.name == model.name) {
+            let ty = Some(Ty::Struct(model.clone()));
+            changes.push(change(removed, &model.name, String::new(), ty, None));
+        }
+    }
+
+    for model in new {
+        match old.iter().find(|m| m.name == model.name) {
+            Some(previous) => {
+                let mut members = Vec::new();
+                diff_ty(
+                    String::new(),
+                    &Ty::Struct(previous.clone()),
+                    &Ty::Struct(model.clone()),
+                    &mut members,
+                );
+                changes.extend(members.into_iter().map(|(path, old, new)| {
+                    change(ChangeKind::MemberUpdated, &model.name, path, Some(old), Some(new))
+                }));
+            }
+            None => {
+                let ty = Some(Ty::Struct(model.clone()));
+                changes.push(change(ChangeKind::ModelAdded, &model.name, String::new(), None, ty));
+            }
+        }
+    }
+
+    changes
+}
+
+/// Collects the `(path, old, new)` values that differ between two types, descending into
+/// structs, tuples and arrays of the same shape.
+pub fn diff_ty(path: String, old: &Ty, new: &Ty, changes: &mut Vec<(String, Ty, Ty)>) {
+    if old == new {
+        return;
+    }
+
+    match (old, new) {
+        (Ty::Struct(old), Ty::Struct(new)) if same_members(old, new) => {
+            for (old, new) in old.children.iter().zip(&new.children) {
+                let path =
+                    if path.is_empty() { old.name.clone() } else { format!("{path}.{}", old.name) };
+                diff_ty(path, &old.ty, &new.ty, changes);
+            }
+        }
+        (Ty::Enum(old_enum), Ty::Enum(new_enum)) if old_enum.option == new_enum.option => {
+            let option = old_enum.option.map(usize::from);
+            match option.and_then(|i| old_enum.options.get(i).zip(new_enum.options.get(i))) {
+                Some((old, new)) => {
+                    diff_ty(format!("{path}.{}", old.name), &old.ty, &new.ty, changes)
+                }
+                None => changes.push((path, old.clone(), new.clone())),
+            }
+        }
+        (Ty::Tuple(old_items), Ty::Tuple(new_items))
+        | (Ty::Array(old_items), Ty::Array(new_items))
+        | (Ty::FixedSizeArray((old_items, _)), Ty::FixedSizeArray((new_items, _)))
+            if old_items.len() == new_items.len() =>
+        {
+            for (i, (old, new)) in old_items.iter().zip(new_items).enumerate() {
+                diff_ty(format!("{path}[{i}]"), old, new, changes);
+            }
+        }
+        _ => changes.push((path, old.clone(), new.clone())),
+    }
+}
+
+fn same_members(old: &Struct, new: &Struct) -> bool {
+    old.name == new.name
+        && old.children.iter().map(|m| &m.name).eq(new.children.iter().map(|m| &m.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_types::primitive::Primitive;
+    use dojo_types::schema::Member;
+
+    use super::*;
+
+    fn position(x: u32, y: u32) -> Struct {
+        let member = |name: &str, value| Member {
+            name: name.to_string(),
+            ty: Ty::Primitive(Primitive::U32(Some(value))),
+            key: false,
+        };
+        Struct { name: "ns-Position".to_string(), children: vec![member("x", x), member("y", y)] }
+    }
+
+    #[test]
+    fn reports_changed_members_only() {
+        let changes = diff_entity(Felt::ONE, Some(&[position(1, 2)]), &[position(3, 2)]);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, ChangeKind::MemberUpdated);
+        assert_eq!(changes[0].path, "x");
+        assert_eq!(changes[0].old, Some(Ty::Primitive(Primitive::U32(Some(1)))));
+        assert_eq!(changes[0].new, Some(Ty::Primitive(Primitive::U32(Some(3)))));
+    }
+
+    #[test]
+    fn tracks_entity_lifecycle() {
+        let mut differ = EntityDiffer::new();
+        let entity = |models| Entity {
+            world_address: Felt::ZERO,
+            hashed_keys: Felt::ONE,
+            models,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            executed_at: Default::default(),
+        };
+
+        let moves = Struct { name: "ns-Moves".to_string(), ..position(0, 0) };
+
+        let kinds = |changes: Vec<FieldChange>| changes.iter().map(|c| c.kind).collect::<Vec<_>>();
+        assert_eq!(kinds(differ.diff(&entity(vec![position(1, 2)]))), [ChangeKind::EntityCreated]);
+        assert_eq!(kinds(differ.diff(&entity(vec![position(1, 2)]))), []);
+        // Updates only carry the models that changed
+        assert_eq!(kinds(differ.diff(&entity(vec![moves]))), [ChangeKind::ModelAdded]);
+        assert_eq!(
+            kinds(differ.diff(&entity(vec![position(0, 0)]))),
+            [ChangeKind::MemberUpdated, ChangeKind::MemberUpdated]
+        );
+        // Upgraded models are set afresh
+        differ.forget_model("ns-Position");
+        assert_eq!(kinds(differ.diff(&entity(vec![position(0, 0)]))), [ChangeKind::ModelAdded]);
+        assert_eq!(
+            kinds(differ.diff(&entity(vec![]))),
+            [ChangeKind::EntityDeleted, ChangeKind::EntityDeleted]
+        );
+    }
+
+    #[test]
+    fn reports_deleted_models() {
+        let mut differ = EntityDiffer::new();
+        let entity = |models| Entity {
+            world_address: Felt::ZERO,
+            hashed_keys: Felt::ONE,
+            models,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            executed_at: Default::default(),
+        };
+        // Torii reports a deleted model with its schema, which holds no value
+        let deleted = |model: Struct| Struct {
+            children: model
+                .children
+                .into_iter()
+                .map(|member| Member { ty: Ty::Primitive(Primitive::U32(None)), ..member })
+                .collect(),
+            ..model
+        };
+        let moves = Struct { name: "ns-Moves".to_string(), ..position(0, 0) };
+        differ.diff(&entity(vec![position(1, 2), moves.clone()]));
+
+        // Zero values are written values
+        let changes = differ.diff(&entity(vec![position(0, 0)]));
+        assert!(changes.iter().all(|change| change.kind == ChangeKind::MemberUpdated));
+
+        let changes = differ.diff(&entity(vec![deleted(position(0, 0))]));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, ChangeKind::ModelRemoved);
+        assert_eq!(changes[0].model, "ns-Position");
+        assert_eq!(changes[0].old, Some(Ty::Struct(position(0, 0))));
+        assert_eq!(changes[0].new, None);
+
+        let changes = differ.diff(&entity(vec![deleted(moves.clone())]));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, ChangeKind::EntityDeleted);
+        assert_eq!(changes[0].old, Some(Ty::Struct(moves.clone())));
+
+        // The entity is new again
+        let changes = differ.diff(&entity(vec![moves]));
+        assert_eq!(changes[0].kind, ChangeKind::EntityCreated);
+    }
+}
@@ -0,0 +1,153 @@
+use std::sync::{Arc, Mutex};
+
+use dojo_types::schema::Struct;
+use torii_proto::schema::Entity;
+use torii_proto::KeysClause;
+
+use crate::clause::{matches_keys, model_keys};
+
+struct Route<H> {
+    id: u64,
+    model: String,
+    keys: Option<KeysClause>,
+    handler: H,
+}
+
+struct Routes<H> {
+    next_id: u64,
+    routes: Vec<Route<H>>,
+}
+
+/// Fans entity updates out to handlers registered by model tag.
+///
+/// A single subscription feeds [`Dispatcher::dispatch`], and every handler registered for a
+/// model of the entity is called with that model, if the model keys match the handler's key
+/// filter. Handlers can be added and removed at any time, including from a handler.
+pub struct Dispatcher<H> {
+    routes: Arc<Mutex<Routes<H>>>,
+}
+
+impl<H> Clone for Dispatcher<H> {
+    fn clone(&self) -> Self {
+        Self { routes: self.routes.clone() }
+    }
+}
+
+impl<H> Default for Dispatcher<H> {
+    fn default() -> Self {
+        Self { routes: Arc::new(Mutex::new(Routes { next_id: 1, routes: Vec::new() })) }
+    }
+}
+
+impl<H: Clone> Dispatcher<H> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a handler for a model tag, e.g. `ns-Position`, and returns its id. When set,
+    /// `keys` is matched against the keys of the model like Torii matches entity keys.
+    pub fn register(&self, model: String, keys: Option<KeysClause>, handler: H) -> u64 {
+        let mut routes = self.routes.lock().unwrap();
+        let id = routes.next_id;
+        routes.next_id += 1;
+        routes.routes.push(Route { id, model, keys, handler });
+        id
+    }
+
+    /// Removes a handler. Returns false if there was no handler with this id.
+    pub fn unregister(&self, id: u64) -> bool {
+        let mut routes = self.routes.lock().unwrap();
+        let len = routes.routes.len();
+        routes.routes.retain(|route| route.id != id);
+        routes.routes.len() != len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.lock().unwrap().routes.is_empty()
+    }
+
+    /// Calls `call` for every handler matching a model of the entity, in registration order.
+    /// Handlers are called without holding the registry lock.
+    pub fn dispatch(&self, entity: &Entity, mut call: impl FnMut(&H, &Struct)) {
+        let matched = {
+            let routes = self.routes.lock().unwrap();
+            let mut matched = Vec::new();
+            for model in &entity.models {
+                let keys = model_keys(model);
+                for route in routes.routes.iter().filter(|route| route.model == model.name) {
+                    if route.keys.as_ref().is_none_or(|clause| matches_keys(clause, &keys)) {
+                        matched.push((route.handler.clone(), model));
+                    }
+                }
+            }
+            matched
+        };
+
+        for (handler, model) in matched {
+            call(&handler, model);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_types::primitive::Primitive;
+    use dojo_types::schema::{Member, Ty};
+    use starknet_crypto::Felt;
+    use torii_proto::PatternMatching;
+
+    use super::*;
+
+    fn position(player: Felt, x: u32) -> Struct {
+        Struct {
+            name: "ns-Position".to_string(),
+            children: vec![
+                Member {
+                    name: "player".to_string(),
+                    ty: Ty::Primitive(Primitive::ContractAddress(Some(player))),
+                    key: true,
+                },
+                Member {
+                    name: "x".to_string(),
+                    ty: Ty::Primitive(Primitive::U32(Some(x))),
+                    key: false,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn dispatches_to_matching_handlers() {
+        let dispatcher = Dispatcher::new();
+        let all = dispatcher.register("ns-Position".to_string(), None, "all");
+        dispatcher.register("ns-Moves".to_string(), None, "moves");
+        dispatcher.register(
+            "ns-Position".to_string(),
+            Some(KeysClause {
+                keys: vec![Some(Felt::TWO)],
+                pattern_matching: PatternMatching::FixedLen,
+                models: vec![],
+            }),
+            "player two",
+        );
+
+        let entity = Entity {
+            world_address: Felt::ZERO,
+            hashed_keys: Felt::ONE,
+            models: vec![position(Felt::TWO, 3)],
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            executed_at: Default::default(),
+        };
+
+        let mut called = Vec::new();
+        dispatcher.dispatch(&entity, |handler, _| called.push(*handler));
+        assert_eq!(called, ["all", "player two"]);
+
+        assert!(dispatcher.unregister(all));
+        assert!(!dispatcher.unregister(all));
+        called.clear();
+        dispatcher.dispatch(&entity, |handler, _| called.push(*handler));
+        assert_eq!(called, ["player two"]);
+    }
+}
@@ -0,0 +1,438 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+use anyhow::{anyhow, Result};
+use futures::future::join_all;
+use torii_client::Client;
+use torii_proto::{ContractQuery, ContractType};
+
+use crate::config::{self, ClientConfig};
+
+/// Number of blocks an endpoint may trail the most advanced healthy replica before queries are
+/// routed away from it.
+pub const DEFAULT_MAX_HEAD_LAG: u64 = 10;
+
+/// Last known health of a Torii endpoint.
+#[derive(Debug, Clone, Default)]
+pub struct EndpointHealth {
+    /// Whether the last probe succeeded
+    pub healthy: bool,
+    /// Latest block indexed for the world contract, if the endpoint reported one
+    pub head: Option<u64>,
+    /// Error returned by the last failed probe or connection attempt
+    pub last_error: Option<String>,
+}
+
+/// Health of an endpoint along with whether it currently serves requests.
+#[derive(Debug, Clone)]
+pub struct EndpointStatus {
+    pub url: String,
+    pub active: bool,
+    pub health: EndpointHealth,
+}
+
+/// How a pool connects to and probes its endpoints.
+///
+/// Every binding uses [`Torii`]. Tests use in-process replicas instead, so that failover can be
+/// exercised without servers.
+pub trait Transport {
+    type Client;
+
+    /// Connects to the endpoint of `config.torii_url`.
+    fn connect(&self, config: &ClientConfig) -> impl Future<Output = Result<Self::Client>>;
+
+    /// Latest block indexed for the world contract, failing if the endpoint doesn't answer.
+    fn head(&self, client: &Self::Client) -> impl Future<Output = Result<Option<u64>>>;
+}
+
+/// Torii endpoints, reached over gRPC. An endpoint is healthy when it answers a world contract
+/// query, whose head is used to compare how far each replica has indexed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Torii;
+
+impl Transport for Torii {
+    type Client = Client;
+
+    async fn connect(&self, config: &ClientConfig) -> Result<Client> {
+        config::connect(config).await
+    }
+
+    async fn head(&self, client: &Client) -> Result<Option<u64>> {
+        let query =
+            ContractQuery { contract_addresses: vec![], contract_types: vec![ContractType::WORLD] };
+        let contracts = client.contracts(query).await?;
+        Ok(contracts.iter().filter_map(|c| c.head).max())
+    }
+}
+
+struct Endpoint<C> {
+    url: String,
+    client: RwLock<Option<Arc<C>>>,
+    health: RwLock<EndpointHealth>,
+}
+
+/// A set of Torii replicas serving the same world.
+///
+/// Requests go to the active endpoint returned by [`EndpointPool::current`]. When a stream
+/// fails, [`EndpointPool::recover`] probes every replica and moves to the healthiest one. When
+/// a request goes unanswered, [`EndpointPool::recover_from_request`] does the same if the
+/// active endpoint doesn't answer a probe. A pool built from a single URL behaves exactly like
+/// a plain client.
+pub struct EndpointPool<T: Transport = Torii> {
+    transport: T,
+    endpoints: Vec<Endpoint<T::Client>>,
+    active: AtomicUsize,
+    generation: AtomicU64,
+    /// Whether a request went unanswered since the last [`EndpointPool::recover_from_request`]
+    unanswered: AtomicBool,
+    max_head_lag: u64,
+    config: ClientConfig,
+}
+
+/// Request sent through [`EndpointPool::request`], marking the pool as having a request
+/// unanswered when dropped before its answer, e.g. on timeout.
+struct Pending<'a> {
+    unanswered: &'a AtomicBool,
+    answered: bool,
+}
+
+impl Drop for Pending<'_> {
+    fn drop(&mut self) {
+        if !self.answered {
+            self.unanswered.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+impl EndpointPool {
+    /// Wraps an already connected client.
+    pub fn from_client(client: Client, config: ClientConfig) -> Self {
+        let endpoint = Endpoint {
+            url: config.torii_url.clone(),
+            client: RwLock::new(Some(Arc::new(client))),
+            health: RwLock::new(EndpointHealth { healthy: true, ..Default::default() }),
+        };
+        Self::new(Torii, vec![endpoint], config)
+    }
+
+    /// Connects to every endpoint of the configuration over gRPC, see
+    /// [`EndpointPool::connect_with`].
+    pub async fn connect(config: ClientConfig) -> Result<Self> {
+        Self::connect_with(Torii, config).await
+    }
+
+    /// Pool of the primary endpoint of `config` that never connects, for sessions that don't
+    /// need a network, e.g. a replayed one. Fallback endpoints are ignored, so that nothing is
+    /// ever probed.
+    pub fn unconnected(config: ClientConfig) -> Self {
+        let endpoint = Endpoint {
+            url: config.torii_url.clone(),
+            client: RwLock::new(None),
+            health: RwLock::new(EndpointHealth { healthy: true, ..Default::default() }),
+        };
+        Self::new(Torii, vec![endpoint], config)
+    }
+}
+
+impl<T: Transport> EndpointPool<T> {
+    fn new(transport: T, endpoints: Vec<Endpoint<T::Client>>, config: ClientConfig) -> Self {
+        Self {
+            transport,
+            endpoints,
+            active: AtomicUsize::new(0),
+            generation: AtomicU64::new(0),
+            unanswered: AtomicBool::new(false),
+            max_head_lag: DEFAULT_MAX_HEAD_LAG,
+            config,
+        }
+    }
+
+    /// Connects to every endpoint of the configuration and activates the healthiest one.
+    ///
+    /// Endpoints that cannot be reached are kept and retried on the next probe. Fails only if
+    /// none of them can be reached.
+    pub async fn connect_with(transport: T, config: ClientConfig) -> Result<Self> {
+        config.validate()?;
+
+        let endpoints = config
+            .endpoints()
+            .map(|url| Endpoint {
+                url: url.clone(),
+                client: RwLock::new(None),
+                health: RwLock::new(EndpointHealth::default()),
+            })
+            .collect();
+        let pool = Self::new(transport, endpoints, config);
+
+        pool.probe().await;
+        if !pool.select() && !pool.read_health(pool.active()).healthy {
+            let errors = pool
+                .endpoints
+                .iter()
+                .map(|e| {
+                    format!("{}: {}", e.url, pool.read_health_of(e).last_error.unwrap_or_default())
+                })
+                .collect::<Vec<_>>();
+            return Err(anyhow!("no healthy torii endpoint ({})", errors.join(", ")));
+        }
+
+        Ok(pool)
+    }
+
+    /// Sets how many blocks the active endpoint may trail the best replica before switching.
+    pub fn with_max_head_lag(mut self, max_head_lag: u64) -> Self {
+        self.max_head_lag = max_head_lag;
+        self
+    }
+
+    /// Configuration shared by every endpoint. `torii_url` is the primary endpoint.
+    pub fn config(&self) -> &ClientConfig {
+        &self.config
+    }
+
+    /// Sends a request to the active endpoint, `fetch` being called with its client.
+    ///
+    /// A request that fails or is dropped before its answer is remembered for
+    /// [`EndpointPool::recover_from_request`].
+    pub async fn request<R, F, Fut, E>(&self, fetch: F) -> Result<R>
+    where
+        F: FnOnce(Arc<T::Client>) -> Fut,
+        Fut: Future<Output = Result<R, E>>,
+        anyhow::Error: From<E>,
+    {
+        let mut pending = Pending { unanswered: &self.unanswered, answered: false };
+        let result = fetch(self.current()?).await.map_err(anyhow::Error::from);
+        pending.answered = result.is_ok();
+        result
+    }
+
+    /// Client of the active endpoint, or of any connected one if the active endpoint isn't
+    /// connected. There is none in an [`EndpointPool::unconnected`] pool.
+    pub fn current(&self) -> Result<Arc<T::Client>> {
+        self.endpoints[self.active()]
+            .client
+            .read()
+            .unwrap()
+            .clone()
+            .or_else(|| self.endpoints.iter().find_map(|e| e.client.read().unwrap().clone()))
+            .ok_or_else(|| anyhow!("no torii endpoint is connected"))
+    }
+
+    /// URL of the active endpoint.
+    pub fn current_url(&self) -> &str {
+        &self.endpoints[self.active()].url
+    }
+
+    /// Incremented every time the active endpoint changes.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// Last known status of every endpoint, without probing.
+    pub fn statuses(&self) -> Vec<EndpointStatus> {
+        let active = self.active();
+        self.endpoints
+            .iter()
+            .enumerate()
+            .map(|(i, e)| EndpointStatus {
+                url: e.url.clone(),
+                active: i == active,
+                health: self.read_health_of(e),
+            })
+            .collect()
+    }
+
+    /// Probes every endpoint concurrently, connecting to the ones that are not connected yet,
+    /// and returns their updated status.
+    pub async fn probe(&self) -> Vec<EndpointStatus> {
+        join_all(self.endpoints.iter().map(|endpoint| self.probe_endpoint(endpoint))).await;
+        self.statuses()
+    }
+
+    /// Probes the endpoints and switches to a healthier one if the active endpoint is down or
+    /// lagging behind. Returns whether the active endpoint changed.
+    pub async fn recover(&self) -> bool {
+        if self.endpoints.len() == 1 {
+            return false;
+        }
+
+        self.probe().await;
+        self.select()
+    }
+
+    /// Called by the bindings after a request failed or timed out. Returns whether the active
+    /// endpoint changed.
+    ///
+    /// Nothing is probed unless a request sent through [`EndpointPool::request`] went
+    /// unanswered since the last call, so that errors raised before sending, e.g. by
+    /// validation, don't probe. The active endpoint is then probed first, and every replica
+    /// only if it doesn't answer, so that a request rejected by a healthy endpoint doesn't
+    /// move the pool.
+    pub async fn recover_from_request(&self) -> bool {
+        if !self.unanswered.swap(false, Ordering::SeqCst) || self.endpoints.len() == 1 {
+            return false;
+        }
+
+        let active = &self.endpoints[self.active()];
+        self.probe_endpoint(active).await;
+        if self.read_health_of(active).healthy {
+            return false;
+        }
+        self.recover().await
+    }
+
+    fn active(&self) -> usize {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    fn read_health(&self, index: usize) -> EndpointHealth {
+        self.read_health_of(&self.endpoints[index])
+    }
+
+    fn read_health_of(&self, endpoint: &Endpoint<T::Client>) -> EndpointHealth {
+        endpoint.health.read().unwrap().clone()
+    }
+
+    fn select(&self) -> bool {
+        let health = (0..self.endpoints.len()).map(|i| self.read_health(i)).collect::<Vec<_>>();
+        let active = self.active();
+
+        match select_endpoint(&health, active, self.max_head_lag) {
+            Some(next) if next != active => {
+                self.active.store(next, Ordering::SeqCst);
+                self.generation.fetch_add(1, Ordering::SeqCst);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    async fn probe_endpoint(&self, endpoint: &Endpoint<T::Client>) {
+        let head = match self.connected(endpoint).await {
+            Ok(client) => self.transport.head(&client).await,
+            Err(e) => Err(e),
+        };
+        let health = match head {
+            Ok(head) => EndpointHealth { healthy: true, head, last_error: None },
+            Err(e) => {
+                EndpointHealth { healthy: false, head: None, last_error: Some(e.to_string()) }
+            }
+        };
+
+        *endpoint.health.write().unwrap() = health;
+    }
+
+    /// Client of an endpoint, connecting to it if it isn't yet.
+    async fn connected(&self, endpoint: &Endpoint<T::Client>) -> Result<Arc<T::Client>> {
+        let client = endpoint.client.read().unwrap().clone();
+        if let Some(client) = client {
+            return Ok(client);
+        }
+
+        let mut endpoint_config = self.config.clone();
+        endpoint_config.torii_url = endpoint.url.clone();
+        let client = Arc::new(self.transport.connect(&endpoint_config).await?);
+        *endpoint.client.write().unwrap() = Some(client.clone());
+        Ok(client)
+    }
+}
+
+/// Picks the endpoint that should serve requests.
+///
+/// The active endpoint is kept as long as it is healthy and within `max_head_lag` blocks of
+/// the most advanced healthy replica, so that replicas indexing at slightly different paces
+/// don't cause constant switching. Otherwise the most advanced healthy replica wins, ties going
+/// to the earliest configured one. Returns `None` if no endpoint is healthy.
+pub fn select_endpoint(
+    health: &[EndpointHealth],
+    active: usize,
+    max_head_lag: u64,
+) -> Option<usize> {
+    let head = |h: &EndpointHealth| h.head.unwrap_or_default();
+
+    let best = health
+        .iter()
+        .enumerate()
+        .filter(|(_, h)| h.healthy)
+        .max_by(|(a, ha), (b, hb)| head(ha).cmp(&head(hb)).then(b.cmp(a)))?;
+
+    match health.get(active) {
+        Some(current)
+            if current.healthy && head(current).saturating_add(max_head_lag) >= head(best.1) =>
+        {
+            Some(active)
+        }
+        _ => Some(best.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::mock::{Server, Servers};
+
+    fn healthy(head: u64) -> EndpointHealth {
+        EndpointHealth { healthy: true, head: Some(head), last_error: None }
+    }
+
+    fn down() -> EndpointHealth {
+        EndpointHealth { healthy: false, head: None, last_error: Some("unreachable".into()) }
+    }
+
+    #[test]
+    fn keeps_active_endpoint_within_lag() {
+        assert_eq!(select_endpoint(&[healthy(95), healthy(100)], 0, 10), Some(0));
+    }
+
+    #[test]
+    fn moves_away_from_lagging_endpoint() {
+        assert_eq!(select_endpoint(&[healthy(80), healthy(100)], 0, 10), Some(1));
+    }
+
+    #[test]
+    fn fails_over_when_active_endpoint_is_down() {
+        assert_eq!(select_endpoint(&[down(), healthy(10), healthy(10)], 0, 10), Some(1));
+    }
+
+    #[test]
+    fn no_endpoint_when_all_are_down() {
+        assert_eq!(select_endpoint(&[down(), down()], 1, 10), None);
+    }
+
+    #[test]
+    fn fails_over_between_replicas() {
+        let (a, b) = (Server::new(100, 1), Server::new(100, 1));
+        let servers = Servers::default();
+        servers.add("http://a", a.clone());
+        servers.add("http://b", b.clone());
+        let mut config = ClientConfig::new("http://a".to_string());
+        config.fallback_urls = vec!["http://b".to_string()];
+        let pool = block_on(EndpointPool::connect_with(servers.clone(), config.clone())).unwrap();
+        assert_eq!(pool.current_url(), "http://a");
+
+        // A request to a replica that went down moves the pool to the other one
+        a.stop();
+        let request = |pool: &EndpointPool<Servers>| {
+            block_on(pool.request(|server| async move { server.head() }))
+        };
+        assert!(request(&pool).is_err());
+        assert!(block_on(pool.recover_from_request()));
+        assert_eq!(pool.current_url(), "http://b");
+        assert_eq!(pool.generation(), 1);
+        assert_eq!(request(&pool).unwrap(), 100);
+        assert!(!block_on(pool.recover_from_request()));
+
+        // Once back, a replica ahead of the active one by more than the lag takes over again
+        a.start();
+        a.set_head(200);
+        assert!(block_on(pool.recover()));
+        assert_eq!(pool.current_url(), "http://a");
+
+        a.stop();
+        b.stop();
+        assert!(block_on(EndpointPool::connect_with(servers, config)).is_err());
+    }
+}
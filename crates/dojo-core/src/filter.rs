@@ -0,0 +1,836 @@
+use std::fmt;
+
+use crypto_bigint::U256;
+use dojo_types::primitive::Primitive;
+use starknet_crypto::Felt;
+use torii_proto::{
+    Clause, ComparisonOperator, CompositeClause, KeysClause, LogicalOperator, MemberClause,
+    MemberValue, PatternMatching,
+};
+
+use crate::builder::u256_from_dec;
+
+/// Error of [`parse`], pointing at the offending part of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    /// Byte offset in the input
+    pub offset: usize,
+    /// Line of the offset, starting at 1
+    pub line: usize,
+    /// Character of the offset in its line, starting at 1
+    pub column: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at line {}, column {}", self.message, self.line, self.column)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a filter into a clause.
+///
+/// Filters combine conditions with `and` and `or`, `and` binding tighter, and parentheses:
+///
+/// ```text
+/// ns-Position.x > 10 and ns-Moves.remaining in [1, 2, 3] and keys(ns-Position) = [0x1, *]
+/// ```
+///
+/// - `model.member op value` compares a member, nested members being separated by dots. The
+///   operators are `=`, `!=`, `>`, `>=`, `<`, `<=`, `in`, `not in`, `contains`,
+///   `contains all` and `contains any`.
+/// - `len(model.member) op value` compares the length of an array with `=`, `>` or `<`.
+/// - `keys(model, ...) = [key, ...]` matches the keys of the models, `*` matching any key.
+///   A trailing `..` also matches entities with more keys.
+/// - `hashed_keys in [0x1, ...]` matches entities by hashed keys.
+/// - `()` matches every entity.
+/// - `all(filter)` and `any(filter)` wrap a filter in an `and` or an `or` of its own, which is
+///   how [`print`] writes composites of a single clause.
+///
+/// Values are strings in double or single quotes, `true`, `false`, lists in brackets and
+/// numbers. Decimal numbers are `i64` and hexadecimal ones `felt252`, other types are
+/// written with a suffix such as `10_u64` or `0x1_contract_address`.
+pub fn parse(input: &str) -> Result<Clause, ParseError> {
+    let mut parser = Parser { input, pos: 0 };
+    let clause = parser.or()?;
+    parser.skip_whitespace();
+    if parser.pos < input.len() {
+        return Err(parser.error("expected `and`, `or` or the end of the filter"));
+    }
+    Ok(clause)
+}
+
+/// Prints a clause as a filter that [`parse`] turns back into an equivalent clause.
+pub fn print(clause: &Clause) -> String {
+    let mut out = String::new();
+    print_clause(clause, &mut out);
+    out
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn or(&mut self) -> Result<Clause, ParseError> {
+        let mut clauses = vec![self.and()?];
+        while self.keyword("or") {
+            clauses.push(self.and()?);
+        }
+        Ok(composite(LogicalOperator::Or, clauses))
+    }
+
+    fn and(&mut self) -> Result<Clause, ParseError> {
+        let mut clauses = vec![self.primary()?];
+        while self.keyword("and") {
+            clauses.push(self.primary()?);
+        }
+        Ok(composite(LogicalOperator::And, clauses))
+    }
+
+    fn primary(&mut self) -> Result<Clause, ParseError> {
+        if self.symbol("(") {
+            if self.symbol(")") {
+                return Ok(Clause::Composite(CompositeClause {
+                    operator: LogicalOperator::And,
+                    clauses: Vec::new(),
+                }));
+            }
+            let clause = self.or()?;
+            self.expect(")")?;
+            return Ok(clause);
+        }
+
+        let start = self.pos;
+        if self.keyword("keys") && self.symbol("(") {
+            return self.keys();
+        }
+        self.pos = start;
+        if self.keyword("len") && self.symbol("(") {
+            return self.array_length();
+        }
+        self.pos = start;
+        for (name, operator) in [("all", LogicalOperator::And), ("any", LogicalOperator::Or)] {
+            if self.keyword(name) && self.symbol("(") {
+                let mut clauses = Vec::new();
+                if !self.symbol(")") {
+                    clauses.push(self.or()?);
+                    self.expect(")")?;
+                }
+                return Ok(Clause::Composite(CompositeClause { operator, clauses }));
+            }
+            self.pos = start;
+        }
+        if self.keyword("hashed_keys") && self.keyword("in") {
+            let hashed_keys = self.list(|p| p.felt())?;
+            return Ok(Clause::HashedKeys(hashed_keys));
+        }
+        self.pos = start;
+
+        let (model, member) = self.member()?;
+        let operator = self.operator()?;
+        let value = self.value()?;
+        Ok(Clause::Member(MemberClause { model, member, operator, value }))
+    }
+
+    /// `keys(models) = [keys]`, after `keys(`
+    fn keys(&mut self) -> Result<Clause, ParseError> {
+        let mut models = Vec::new();
+        if !self.symbol(")") {
+            models.push(self.word("a model name")?);
+            while self.symbol(",") {
+                models.push(self.word("a model name")?);
+            }
+            self.expect(")")?;
+        }
+        self.expect("=")?;
+        self.expect("[")?;
+
+        let mut keys = Vec::new();
+        let mut pattern_matching = PatternMatching::FixedLen;
+        if !self.symbol("]") {
+            loop {
+                if self.symbol("..") {
+                    pattern_matching = PatternMatching::VariableLen;
+                    self.expect("]")?;
+                    break;
+                }
+                keys.push(if self.symbol("*") { None } else { Some(self.felt()?) });
+                if self.symbol("]") {
+                    break;
+                }
+                self.expect(",")?;
+            }
+        }
+        Ok(Clause::Keys(KeysClause { keys, pattern_matching, models }))
+    }
+
+    /// `len(model.member) op value`, after `len(`
+    fn array_length(&mut self) -> Result<Clause, ParseError> {
+        let (model, member) = self.member()?;
+        self.expect(")")?;
+
+        let operator = if self.symbol(">") {
+            ComparisonOperator::ArrayLengthGt
+        } else if self.symbol("<") {
+            ComparisonOperator::ArrayLengthLt
+        } else if self.symbol("==") || self.symbol("=") {
+            ComparisonOperator::ArrayLengthEq
+        } else {
+            return Err(self.error("expected `=`, `>` or `<`"));
+        };
+        let value = self.value()?;
+        Ok(Clause::Member(MemberClause { model, member, operator, value }))
+    }
+
+    fn member(&mut self) -> Result<(String, String), ParseError> {
+        let model = self.word("a model name or `(`")?;
+        self.expect(".")?;
+        let mut member = self.segment()?;
+        while self.input[self.pos..].starts_with('.') {
+            self.pos += 1;
+            member.push('.');
+            member.push_str(&self.segment()?);
+        }
+        Ok((model, member))
+    }
+
+    /// Reads a segment of a member path, a member name or the index of a tuple or array item.
+    fn segment(&mut self) -> Result<String, ParseError> {
+        let rest = &self.input[self.pos..];
+        let len = rest.bytes().take_while(u8::is_ascii_digit).count();
+        if len == 0 {
+            return self.word("a member name");
+        }
+        self.pos += len;
+        Ok(rest[..len].to_string())
+    }
+
+    fn operator(&mut self) -> Result<ComparisonOperator, ParseError> {
+        const SYMBOLS: [(&str, ComparisonOperator); 7] = [
+            ("==", ComparisonOperator::Eq),
+            ("!=", ComparisonOperator::Neq),
+            (">=", ComparisonOperator::Gte),
+            ("<=", ComparisonOperator::Lte),
+            ("=", ComparisonOperator::Eq),
+            (">", ComparisonOperator::Gt),
+            ("<", ComparisonOperator::Lt),
+        ];
+        if let Some((_, operator)) = SYMBOLS.into_iter().find(|(symbol, _)| self.symbol(symbol)) {
+            return Ok(operator);
+        }
+
+        if self.keyword("in") {
+            Ok(ComparisonOperator::In)
+        } else if self.keyword("not") {
+            if !self.keyword("in") {
+                return Err(self.error("expected `in`"));
+            }
+            Ok(ComparisonOperator::NotIn)
+        } else if self.keyword("contains") {
+            Ok(if self.keyword("all") {
+                ComparisonOperator::ContainsAll
+            } else if self.keyword("any") {
+                ComparisonOperator::ContainsAny
+            } else {
+                ComparisonOperator::Contains
+            })
+        } else {
+            Err(self.error("expected a comparison operator"))
+        }
+    }
+
+    fn value(&mut self) -> Result<MemberValue, ParseError> {
+        self.skip_whitespace();
+        let rest = &self.input[self.pos..];
+        if rest.starts_with('[') {
+            return self.list(Self::value).map(MemberValue::List);
+        }
+        if rest.starts_with(['"', '\'']) {
+            return self.string().map(MemberValue::String);
+        }
+        if self.keyword("true") {
+            return Ok(MemberValue::Primitive(Primitive::Bool(Some(true))));
+        }
+        if self.keyword("false") {
+            return Ok(MemberValue::Primitive(Primitive::Bool(Some(false))));
+        }
+        self.number().map(MemberValue::Primitive)
+    }
+
+    fn list<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        self.expect("[")?;
+        let mut items = Vec::new();
+        if self.symbol("]") {
+            return Ok(items);
+        }
+        loop {
+            items.push(item(self)?);
+            if self.symbol("]") {
+                return Ok(items);
+            }
+            self.expect(",")?;
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        let start = self.pos;
+        let mut chars = self.input[start..].char_indices();
+        let Some((_, quote)) = chars.next() else {
+            return Err(self.error("expected a string"));
+        };
+
+        let mut value = String::new();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                c if c == quote => {
+                    self.pos = start + i + c.len_utf8();
+                    return Ok(value);
+                }
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((_, c @ ('\\' | '"' | '\''))) => value.push(c),
+                    Some((j, _)) => {
+                        self.pos = start + j;
+                        return Err(self.error("unknown escape sequence"));
+                    }
+                    None => break,
+                },
+                c => value.push(c),
+            }
+        }
+        self.pos = start;
+        Err(self.error("unterminated string"))
+    }
+
+    fn felt(&mut self) -> Result<Felt, ParseError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        match self.number()? {
+            Primitive::Felt252(Some(felt)) => Ok(felt),
+            Primitive::I64(Some(value)) if value >= 0 => Ok(Felt::from(value)),
+            _ => {
+                self.pos = start;
+                Err(self.error("expected a felt"))
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Primitive, ParseError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let rest = &self.input[start..];
+
+        let sign = usize::from(rest.starts_with('-'));
+        let hex = rest[sign..].starts_with("0x") || rest[sign..].starts_with("0X");
+        let digits_start = sign + if hex { 2 } else { 0 };
+        let digits_len = rest[digits_start..]
+            .bytes()
+            .take_while(|b| if hex { b.is_ascii_hexdigit() } else { b.is_ascii_digit() })
+            .count();
+        if digits_len == 0 {
+            return Err(self.error("expected a value"));
+        }
+        let digits_end = digits_start + digits_len;
+        let digits = format!("{}{}", &rest[..sign], &rest[digits_start..digits_end]);
+
+        let suffix_start = digits_end + usize::from(rest[digits_end..].starts_with('_'));
+        let suffix_len = rest[suffix_start..]
+            .bytes()
+            .take_while(|b| b.is_ascii_alphanumeric() || *b == b'_')
+            .count();
+        if hex && suffix_start == digits_end && suffix_len > 0 {
+            self.pos = start + digits_end;
+            return Err(self.error("expected `_` before the type of a hexadecimal number"));
+        }
+        let suffix = &rest[suffix_start..suffix_start + suffix_len];
+
+        let primitive = number(&digits, if hex { 16 } else { 10 }, suffix);
+        self.pos = match primitive {
+            Ok(_) => start + suffix_start + suffix_len,
+            Err(Invalid::Suffix) => start + suffix_start,
+            Err(Invalid::Number) => start,
+        };
+        primitive.map_err(|invalid| match invalid {
+            Invalid::Suffix => self.error(&format!("unknown number type `{suffix}`")),
+            Invalid::Number => {
+                let ty = match suffix {
+                    "" if hex => "felt252",
+                    "" => "i64",
+                    suffix => suffix,
+                };
+                self.error(&format!("invalid {ty} number"))
+            }
+        })
+    }
+
+    /// Reads an identifier, model names also containing dashes.
+    fn word(&mut self, expected: &str) -> Result<String, ParseError> {
+        self.skip_whitespace();
+        let rest = &self.input[self.pos..];
+        let len = word_len(rest);
+        if len == 0 {
+            return Err(self.error(&format!("expected {expected}")));
+        }
+        self.pos += len;
+        Ok(rest[..len].to_string())
+    }
+
+    /// Consumes a case insensitive keyword, not followed by other identifier characters.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        self.skip_whitespace();
+        let rest = &self.input[self.pos..];
+        let len = word_len(rest);
+        let matched = rest[..len].eq_ignore_ascii_case(keyword);
+        if matched {
+            self.pos += len;
+        }
+        matched
+    }
+
+    fn symbol(&mut self, symbol: &str) -> bool {
+        self.skip_whitespace();
+        let matched = self.input[self.pos..].starts_with(symbol);
+        if matched {
+            self.pos += symbol.len();
+        }
+        matched
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), ParseError> {
+        if self.symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{symbol}`")))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        let before = &self.input[..self.pos];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        ParseError {
+            message: message.to_string(),
+            offset: self.pos,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+fn composite(operator: LogicalOperator, mut clauses: Vec<Clause>) -> Clause {
+    match clauses.len() {
+        1 => clauses.remove(0),
+        _ => Clause::Composite(CompositeClause { operator, clauses }),
+    }
+}
+
+fn word_len(input: &str) -> usize {
+    if !input.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        return 0;
+    }
+    input.bytes().take_while(|b| b.is_ascii_alphanumeric() || b"_-".contains(b)).count()
+}
+
+enum Invalid {
+    Suffix,
+    Number,
+}
+
+/// Parses signed digits in the given radix as the primitive named by the suffix.
+fn number(digits: &str, radix: u32, suffix: &str) -> Result<Primitive, Invalid> {
+    macro_rules! int {
+        ($variant:ident, $ty:ty) => {
+            <$ty>::from_str_radix(digits, radix)
+                .map(|value| Primitive::$variant(Some(value)))
+                .map_err(|_| Invalid::Number)
+        };
+    }
+    let felt = || -> Result<Felt, Invalid> {
+        let felt = match radix {
+            16 => Felt::from_hex(&format!("0x{digits}")),
+            _ => Felt::from_dec_str(digits),
+        };
+        felt.map_err(|_| Invalid::Number)
+    };
+
+    match suffix {
+        "" if radix == 16 => felt().map(|felt| Primitive::Felt252(Some(felt))),
+        "" | "i64" => int!(I64, i64),
+        "i8" => int!(I8, i8),
+        "i16" => int!(I16, i16),
+        "i32" => int!(I32, i32),
+        "i128" => int!(I128, i128),
+        "u8" => int!(U8, u8),
+        "u16" => int!(U16, u16),
+        "u32" => int!(U32, u32),
+        "u64" => int!(U64, u64),
+        "u128" => int!(U128, u128),
+        "u256" if radix == 16 && digits.len() <= 64 && !digits.starts_with('-') => {
+            Ok(Primitive::U256(Some(U256::from_be_hex(&format!("{digits:0>64}")))))
+        }
+        "u256" if radix == 10 => {
+            u256_from_dec(digits).map(|value| Primitive::U256(Some(value))).ok_or(Invalid::Number)
+        }
+        "u256" => Err(Invalid::Number),
+        "felt252" => felt().map(|felt| Primitive::Felt252(Some(felt))),
+        "class_hash" => felt().map(|felt| Primitive::ClassHash(Some(felt))),
+        "contract_address" => felt().map(|felt| Primitive::ContractAddress(Some(felt))),
+        "eth_address" => felt().map(|felt| Primitive::EthAddress(Some(felt))),
+        _ => Err(Invalid::Suffix),
+    }
+}
+
+fn print_clause(clause: &Clause, out: &mut String) {
+    match clause {
+        Clause::HashedKeys(hashed_keys) => {
+            let hashed_keys = hashed_keys.iter().map(|key| format!("{key:#x}"));
+            out.push_str(&format!(
+                "hashed_keys in [{}]",
+                hashed_keys.collect::<Vec<_>>().join(", ")
+            ));
+        }
+        Clause::Keys(keys) => {
+            let mut patterns = keys
+                .keys
+                .iter()
+                .map(|key| key.map_or("*".to_string(), |key| format!("{key:#x}")))
+                .collect::<Vec<_>>();
+            if keys.pattern_matching == PatternMatching::VariableLen {
+                patterns.push("..".to_string());
+            }
+            out.push_str(&format!("keys({}) = [{}]", keys.models.join(", "), patterns.join(", ")));
+        }
+        Clause::Member(member) => {
+            let path = format!("{}.{}", member.model, member.member);
+            let (path, operator) = match member.operator {
+                ComparisonOperator::Eq => (path, "="),
+                ComparisonOperator::Neq => (path, "!="),
+                ComparisonOperator::Gt => (path, ">"),
+                ComparisonOperator::Gte => (path, ">="),
+                ComparisonOperator::Lt => (path, "<"),
+                ComparisonOperator::Lte => (path, "<="),
+                ComparisonOperator::In => (path, "in"),
+                ComparisonOperator::NotIn => (path, "not in"),
+                ComparisonOperator::Contains => (path, "contains"),
+                ComparisonOperator::ContainsAll => (path, "contains all"),
+                ComparisonOperator::ContainsAny => (path, "contains any"),
+                ComparisonOperator::ArrayLengthEq => (format!("len({path})"), "="),
+                ComparisonOperator::ArrayLengthGt => (format!("len({path})"), ">"),
+                ComparisonOperator::ArrayLengthLt => (format!("len({path})"), "<"),
+            };
+            out.push_str(&format!("{path} {operator} "));
+            print_value(&member.value, out);
+        }
+        Clause::Composite(composite)
+            if composite.clauses.is_empty() && composite.operator == LogicalOperator::And =>
+        {
+            out.push_str("()")
+        }
+        // Parentheses alone would give back the clause itself
+        Clause::Composite(composite) if composite.clauses.len() < 2 => {
+            out.push_str(match composite.operator {
+                LogicalOperator::And => "all(",
+                LogicalOperator::Or => "any(",
+            });
+            if let Some(clause) = composite.clauses.first() {
+                print_clause(clause, out);
+            }
+            out.push(')');
+        }
+        Clause::Composite(composite) => {
+            let separator = match composite.operator {
+                LogicalOperator::And => " and ",
+                LogicalOperator::Or => " or ",
+            };
+            for (i, clause) in composite.clauses.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(separator);
+                }
+                // `and` binds tighter than `or`, other nested composites keep their grouping
+                let grouped = match clause {
+                    Clause::Composite(nested) if nested.clauses.len() > 1 => {
+                        composite.operator == LogicalOperator::And
+                            || nested.operator == LogicalOperator::Or
+                    }
+                    _ => false,
+                };
+                if grouped {
+                    out.push('(');
+                    print_clause(clause, out);
+                    out.push(')');
+                } else {
+                    print_clause(clause, out);
+                }
+            }
+        }
+    }
+}
+
+fn print_value(value: &MemberValue, out: &mut String) {
+    match value {
+        MemberValue::Primitive(primitive) => out.push_str(&print_primitive(primitive)),
+        MemberValue::String(value) => {
+            out.push('"');
+            for c in value.chars() {
+                match c {
+                    '\n' => out.push_str("\\n"),
+                    '\t' => out.push_str("\\t"),
+                    '\\' | '"' => {
+                        out.push('\\');
+                        out.push(c);
+                    }
+                    c => out.push(c),
+                }
+            }
+            out.push('"');
+        }
+        MemberValue::List(values) => {
+            out.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                print_value(value, out);
+            }
+            out.push(']');
+        }
+    }
+}
+
+/// Unset primitives are printed as their default value, which is how Torii compares them.
+fn print_primitive(primitive: &Primitive) -> String {
+    let felt =
+        |value: Option<Felt>, suffix: &str| format!("{:#x}{suffix}", value.unwrap_or_default());
+
+    match *primitive {
+        Primitive::I8(v) => format!("{}_i8", v.unwrap_or_default()),
+        Primitive::I16(v) => format!("{}_i16", v.unwrap_or_default()),
+        Primitive::I32(v) => format!("{}_i32", v.unwrap_or_default()),
+        Primitive::I64(v) => v.unwrap_or_default().to_string(),
+        Primitive::I128(v) => format!("{}_i128", v.unwrap_or_default()),
+        Primitive::U8(v) => format!("{}_u8", v.unwrap_or_default()),
+        Primitive::U16(v) => format!("{}_u16", v.unwrap_or_default()),
+        Primitive::U32(v) => format!("{}_u32", v.unwrap_or_default()),
+        Primitive::U64(v) => format!("{}_u64", v.unwrap_or_default()),
+        Primitive::U128(v) => format!("{}_u128", v.unwrap_or_default()),
+        Primitive::U256(v) => {
+            let hex = format!("{:x}", v.unwrap_or(U256::ZERO));
+            let digits = hex.trim_start_matches('0');
+            format!("0x{}_u256", if digits.is_empty() { "0" } else { digits })
+        }
+        Primitive::Bool(v) => v.unwrap_or_default().to_string(),
+        Primitive::Felt252(v) => felt(v, ""),
+        Primitive::ClassHash(v) => felt(v, "_class_hash"),
+        Primitive::ContractAddress(v) => felt(v, "_contract_address"),
+        Primitive::EthAddress(v) => felt(v, "_eth_address"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn member(
+        model: &str,
+        member: &str,
+        operator: ComparisonOperator,
+        value: MemberValue,
+    ) -> Clause {
+        Clause::Member(MemberClause {
+            model: model.to_string(),
+            member: member.to_string(),
+            operator,
+            value,
+        })
+    }
+
+    #[test]
+    fn parses_filters() {
+        let clause = parse(
+            "ns-Position.x > 10 and ns-Moves.remaining in [1, 2, 3] and keys(ns-Position) = [0x1, *]",
+        )
+        .unwrap();
+        let int = |v| MemberValue::Primitive(Primitive::I64(Some(v)));
+
+        assert_eq!(
+            clause,
+            Clause::Composite(CompositeClause {
+                operator: LogicalOperator::And,
+                clauses: vec![
+                    member("ns-Position", "x", ComparisonOperator::Gt, int(10)),
+                    member(
+                        "ns-Moves",
+                        "remaining",
+                        ComparisonOperator::In,
+                        MemberValue::List(vec![int(1), int(2), int(3)]),
+                    ),
+                    Clause::Keys(KeysClause {
+                        keys: vec![Some(Felt::ONE), None],
+                        pattern_matching: PatternMatching::FixedLen,
+                        models: vec!["ns-Position".to_string()],
+                    }),
+                ],
+            })
+        );
+
+        let clause =
+            parse("len(ns-Inventory.items) > 2_u32 OR ns-Player.vec.owner != 0x2_contract_address");
+        assert_eq!(
+            clause.unwrap(),
+            Clause::Composite(CompositeClause {
+                operator: LogicalOperator::Or,
+                clauses: vec![
+                    member(
+                        "ns-Inventory",
+                        "items",
+                        ComparisonOperator::ArrayLengthGt,
+                        MemberValue::Primitive(Primitive::U32(Some(2))),
+                    ),
+                    member(
+                        "ns-Player",
+                        "vec.owner",
+                        ComparisonOperator::Neq,
+                        MemberValue::Primitive(Primitive::ContractAddress(Some(Felt::TWO))),
+                    ),
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn prints_parsable_filters() {
+        let filters = [
+            "ns-Position.x > 10 and (ns-Moves.remaining in [1, 2_u8] or ns-Moves.can_move = true)",
+            "keys(ns-Position, ns-Moves) = [0x1, *, ..] or hashed_keys in [0xabc]",
+            "ns-Player.name not in [\"a \\\"b\\\"\", 'c'] and len(ns-Player.items) = 0_u32",
+            "ns-Player.items contains any [-1_i128, 0xff_u256, 7_u64] and keys() = [..]",
+            "(ns-A.a = 1 and ns-A.b = 2) and ()",
+            "ns-M.pair.0 = 1 and ns-M.grid.1.2 != 0x3",
+            "all(ns-A.a = 1) or any() or any(ns-A.a = 1 and all())",
+        ];
+
+        for filter in filters {
+            let clause = parse(filter).unwrap();
+            assert_eq!(parse(&print(&clause)).unwrap(), clause, "{filter}");
+        }
+        assert_eq!(print(&parse(filters[0]).unwrap()), filters[0]);
+    }
+
+    #[test]
+    fn reports_error_positions() {
+        let error = parse("ns-Position.x > 10 and\n  ns-Position.y >< 3").unwrap_err();
+        assert_eq!((error.line, error.column, error.offset), (2, 18, 40));
+        assert_eq!(error.message, "expected a value");
+
+        let error = parse("ns-Position.x = 300_u8").unwrap_err();
+        assert_eq!(error.to_string(), "invalid u8 number at line 1, column 17");
+
+        let error = parse("ns-Position.x = 3_u7").unwrap_err();
+        assert_eq!(error.to_string(), "unknown number type `u7` at line 1, column 19");
+
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(
+            parse(&format!("ns-A.x = {max}_u256")).unwrap(),
+            member(
+                "ns-A",
+                "x",
+                ComparisonOperator::Eq,
+                MemberValue::Primitive(Primitive::U256(Some(U256::MAX)))
+            )
+        );
+        assert!(parse(&format!("ns-A.x = {max}0_u256")).is_err());
+
+        assert!(parse("(ns-Position.x = 3").is_err());
+        assert!(parse("ns-Position.x = 3 ns-Position.y = 4").is_err());
+    }
+
+    fn primitive() -> impl Strategy<Value = Primitive> {
+        prop_oneof![
+            any::<i64>().prop_map(|v| Primitive::I64(Some(v))),
+            any::<i128>().prop_map(|v| Primitive::I128(Some(v))),
+            any::<u8>().prop_map(|v| Primitive::U8(Some(v))),
+            any::<u128>().prop_map(|v| Primitive::U128(Some(v))),
+            any::<[u8; 32]>().prop_map(|v| Primitive::U256(Some(U256::from_be_slice(&v)))),
+            any::<bool>().prop_map(|v| Primitive::Bool(Some(v))),
+            any::<[u8; 31]>().prop_map(|v| Primitive::Felt252(Some(Felt::from_bytes_be_slice(&v)))),
+            any::<[u8; 20]>()
+                .prop_map(|v| Primitive::ContractAddress(Some(Felt::from_bytes_be_slice(&v)))),
+        ]
+    }
+
+    fn value() -> impl Strategy<Value = MemberValue> {
+        let value = prop_oneof![
+            primitive().prop_map(MemberValue::Primitive),
+            "\\PC{0,8}".prop_map(MemberValue::String),
+        ];
+        prop_oneof![
+            value.clone(),
+            proptest::collection::vec(value, 0..3).prop_map(MemberValue::List),
+        ]
+    }
+
+    fn leaf() -> impl Strategy<Value = Clause> {
+        let felt = any::<[u8; 31]>().prop_map(|v| Felt::from_bytes_be_slice(&v));
+        let models = proptest::collection::vec(prop_oneof![Just("ns-A"), Just("ns-B")], 0..3)
+            .prop_map(|models| models.into_iter().map(String::from).collect());
+        let operator = prop_oneof![
+            Just(ComparisonOperator::Eq),
+            Just(ComparisonOperator::Neq),
+            Just(ComparisonOperator::Gte),
+            Just(ComparisonOperator::Lt),
+            Just(ComparisonOperator::In),
+            Just(ComparisonOperator::NotIn),
+            Just(ComparisonOperator::ContainsAll),
+            Just(ComparisonOperator::ArrayLengthGt),
+        ];
+        prop_oneof![
+            (prop_oneof![Just("x"), Just("pair.0"), Just("grid.1.2")], operator, value())
+                .prop_map(|(path, operator, value)| member("ns-A", path, operator, value)),
+            (
+                proptest::collection::vec(proptest::option::of(felt.clone()), 0..3),
+                any::<bool>(),
+                models,
+            )
+                .prop_map(|(keys, variable, models)| Clause::Keys(KeysClause {
+                    keys,
+                    pattern_matching: if variable {
+                        PatternMatching::VariableLen
+                    } else {
+                        PatternMatching::FixedLen
+                    },
+                    models,
+                })),
+            proptest::collection::vec(felt, 0..3).prop_map(Clause::HashedKeys),
+        ]
+    }
+
+    fn clause() -> impl Strategy<Value = Clause> {
+        leaf().prop_recursive(3, 16, 3, |inner| {
+            let operator = prop_oneof![Just(LogicalOperator::And), Just(LogicalOperator::Or)];
+            (operator, proptest::collection::vec(inner, 0..3)).prop_map(|(operator, clauses)| {
+                Clause::Composite(CompositeClause { operator, clauses })
+            })
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn round_trips_printed_filters(clause in clause()) {
+            prop_assert_eq!(parse(&print(&clause)), Ok(clause));
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+use std::future::Future;
+
+use anyhow::Result;
+use dojo_types::naming::compute_selector_from_tag;
+use dojo_types::schema::Struct;
+use starknet_crypto::Felt;
+use torii_proto::schema::Entity;
+use torii_proto::{Clause, Page, Pagination, PaginationDirection, Query, SqlRow, SqlValue};
+
+use crate::pagination::Paginator;
+
+/// Point in the history of a world.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum At {
+    Block(u64),
+    /// Unix timestamp in seconds
+    Timestamp(u64),
+}
+
+/// Bounds of a timeline, both inclusive. Unbounded when `None`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HistoryRange {
+    pub from: Option<At>,
+    pub to: Option<At>,
+}
+
+/// Value of a model set by a transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Version {
+    /// Block of the transaction, unknown when no event of the version was found
+    pub block_number: Option<u64>,
+    /// Hash of the transaction, unknown when no single event of the version was found
+    pub transaction_hash: Option<Felt>,
+    /// Unix timestamp in seconds of the block
+    pub timestamp: u64,
+    pub model: Struct,
+}
+
+impl Version {
+    /// Whether the version was set at or before `at`. Versions of an unknown block are never
+    /// placed relative to a block.
+    fn is_at_or_before(&self, at: At) -> bool {
+        match at {
+            At::Block(block) => self.block_number.is_some_and(|number| number <= block),
+            At::Timestamp(timestamp) => self.timestamp <= timestamp,
+        }
+    }
+
+    fn is_at_or_after(&self, at: At) -> bool {
+        match at {
+            At::Block(block) => self.block_number.is_some_and(|number| number >= block),
+            At::Timestamp(timestamp) => self.timestamp >= timestamp,
+        }
+    }
+}
+
+/// Versions of each model of each entity, keyed by entity id and model tag.
+pub type Timelines = HashMap<(Felt, String), Vec<Version>>;
+
+/// Historical query of every version of the models of entities, all models when `models` is
+/// empty.
+pub fn history_query(world_addresses: &[Felt], ids: &[Felt], models: Vec<String>) -> Query {
+    Query {
+        world_addresses: world_addresses.to_vec(),
+        pagination: Pagination {
+            cursor: None,
+            limit: None,
+            direction: PaginationDirection::Forward,
+            order_by: vec![],
+        },
+        clause: Some(Clause::HashedKeys(ids.to_vec())),
+        no_hashed_keys: false,
+        models,
+        historical: true,
+    }
+}
+
+/// SQL query of the events that set the versions of entities, which carry their block and
+/// transaction. Gets the id, model selector, event id and execution time of each version.
+pub fn history_sql(ids: &[Felt]) -> String {
+    let ids = ids.iter().map(|id| format!("'{id:#x}'")).collect::<Vec<_>>().join(", ");
+    format!(
+        "SELECT id, model_id, event_id, executed_at FROM entities_historical WHERE id IN \
+         ({ids}) ORDER BY executed_at, event_id"
+    )
+}
+
+/// Versions of each model of each entity, in the order they were set.
+///
+/// Torii's historical entities don't carry the event that set them, so each version is joined
+/// with the `events`, the rows of [`history_sql`], of its entity and model executed at the
+/// same time, whose event id gives its block and transaction. A transaction is only set when
+/// a single one matches, e.g. not when several of a block set the model.
+pub fn timelines(entities: Vec<Entity>, events: &[SqlRow]) -> Timelines {
+    let mut timelines = Timelines::new();
+    for entity in entities {
+        let timestamp = entity.executed_at.timestamp().max(0) as u64;
+        for model in entity.models {
+            timelines.entry((entity.hashed_keys, model.name.clone())).or_default().push(Version {
+                block_number: None,
+                transaction_hash: None,
+                timestamp,
+                model,
+            });
+        }
+    }
+    for versions in timelines.values_mut() {
+        versions.sort_by_key(|version| version.timestamp);
+    }
+
+    let mut executed = HashMap::<_, Vec<(u64, Felt)>>::new();
+    for row in events {
+        if let Some((key, event)) = event(row) {
+            executed.entry(key).or_default().push(event);
+        }
+    }
+    for ((id, model), versions) in &mut timelines {
+        let model = compute_selector_from_tag(model);
+        for version in versions {
+            let Some(events) = executed.get(&(*id, model, version.timestamp)) else {
+                continue;
+            };
+            let (block_number, transaction_hash) = events[0];
+            if events.iter().all(|(block, _)| *block == block_number) {
+                version.block_number = Some(block_number);
+            }
+            if events.iter().all(|event| *event == (block_number, transaction_hash)) {
+                version.transaction_hash = Some(transaction_hash);
+            }
+        }
+    }
+
+    timelines
+}
+
+/// Entity, model selector and execution time of a row of [`history_sql`], with the block and
+/// transaction hash of its event. Event ids read `block:transaction:index`, in hex.
+fn event(row: &SqlRow) -> Option<((Felt, Felt, u64), (u64, Felt))> {
+    let text = |column: &str| {
+        row.fields.iter().find_map(|(name, value)| match value {
+            SqlValue::Text(text) if name == column => Some(text.as_str()),
+            _ => None,
+        })
+    };
+
+    let mut event_id = text("event_id")?.split(':');
+    let block = event_id.next()?;
+    let block = u64::from_str_radix(block.trim_start_matches("0x"), 16).ok()?;
+    let transaction_hash = Felt::from_hex(event_id.next()?).ok()?;
+    let id = Felt::from_hex(text("id")?).ok()?;
+    let model = Felt::from_hex(text("model_id")?).ok()?;
+    let executed_at = timestamp(text("executed_at")?)?;
+    Some(((id, model, executed_at), (block, transaction_hash)))
+}
+
+/// Unix timestamp in seconds of an SQL datetime, as stored by Torii or in RFC 3339.
+fn timestamp(text: &str) -> Option<u64> {
+    let time = chrono::DateTime::parse_from_rfc3339(text)
+        .map(|time| time.naive_utc())
+        .or_else(|_| chrono::NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S"))
+        .ok()?;
+    Some(time.and_utc().timestamp().max(0) as u64)
+}
+
+/// Versions of a timeline within a range.
+pub fn within(versions: Vec<Version>, range: HistoryRange) -> Vec<Version> {
+    versions
+        .into_iter()
+        .filter(|version| range.from.is_none_or(|from| version.is_at_or_after(from)))
+        .filter(|version| range.to.is_none_or(|to| version.is_at_or_before(to)))
+        .collect()
+}
+
+/// Entities as they were at a point in history, each with the latest version of its models
+/// set at or before it. Entities that didn't exist yet are left out.
+pub fn state_at(world_address: Felt, timelines: Timelines, at: At) -> Vec<Entity> {
+    let mut entities = HashMap::<Felt, Entity>::new();
+    for ((id, _), versions) in timelines {
+        let Some(version) = versions.into_iter().rev().find(|version| version.is_at_or_before(at))
+        else {
+            continue;
+        };
+
+        let executed_at =
+            chrono::DateTime::from_timestamp(version.timestamp as i64, 0).unwrap_or_default();
+        let entity = entities.entry(id).or_insert_with(|| Entity {
+            world_address,
+            hashed_keys: id,
+            models: Vec::new(),
+            created_at: executed_at,
+            updated_at: executed_at,
+            executed_at,
+        });
+        entity.created_at = entity.created_at.min(executed_at);
+        entity.updated_at = entity.updated_at.max(executed_at);
+        entity.executed_at = entity.updated_at;
+        entity.models.push(version.model);
+    }
+
+    let mut entities = entities.into_values().collect::<Vec<_>>();
+    for entity in &mut entities {
+        entity.models.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+    entities
+}
+
+/// Fetches every version of the models of entities, along with their block and transaction.
+///
+/// `fetch` requests a page of entities and `sql` runs an SQL query, both on the active
+/// endpoint.
+pub async fn fetch_timelines<F, Fut, S, SFut>(
+    world_addresses: &[Felt],
+    ids: &[Felt],
+    models: Vec<String>,
+    fetch: F,
+    sql: S,
+) -> Result<Timelines>
+where
+    F: Fn(Query) -> Fut,
+    Fut: Future<Output = Result<Page<Entity>>>,
+    S: FnOnce(String) -> SFut,
+    SFut: Future<Output = Result<Vec<SqlRow>>>,
+{
+    let mut paginator = Paginator::new(history_query(world_addresses, ids, models), fetch);
+    let mut entities = Vec::new();
+    while let Some(page) = paginator.next_page().await? {
+        entities.extend(page.items);
+    }
+
+    let events = sql(history_sql(ids)).await?;
+    Ok(timelines(entities, &events))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+    use dojo_types::primitive::Primitive;
+    use dojo_types::schema::{Member, Ty};
+
+    use super::*;
+
+    fn version(id: Felt, name: &str, x: u32, timestamp: i64) -> Entity {
+        let executed_at = DateTime::from_timestamp(timestamp, 0).unwrap();
+        Entity {
+            world_address: Felt::ONE,
+            hashed_keys: id,
+            models: vec![Struct {
+                name: name.to_string(),
+                children: vec![Member {
+                    name: "x".to_string(),
+                    ty: Ty::Primitive(Primitive::U32(Some(x))),
+                    key: false,
+                }],
+            }],
+            created_at: executed_at,
+            updated_at: executed_at,
+            executed_at,
+        }
+    }
+
+    fn event(id: Felt, model: &str, timestamp: i64, block: u64, transaction_hash: u64) -> SqlRow {
+        let text = |name: &str, value: String| (name.to_string(), SqlValue::Text(value));
+        let executed_at = DateTime::from_timestamp(timestamp, 0).unwrap();
+        SqlRow {
+            fields: [
+                text("id", format!("{id:#x}")),
+                text("model_id", format!("{:#x}", compute_selector_from_tag(model))),
+                text("event_id", format!("{block:#064x}:{transaction_hash:#x}:0x0000")),
+                text("executed_at", executed_at.format("%Y-%m-%d %H:%M:%S").to_string()),
+            ]
+            .into_iter()
+            .collect(),
+        }
+    }
+
+    #[test]
+    fn rebuilds_timelines_and_state() {
+        let versions = vec![
+            version(Felt::TWO, "ns-Position", 2, 20),
+            version(Felt::TWO, "ns-Position", 1, 10),
+            version(Felt::TWO, "ns-Moves", 5, 15),
+            version(Felt::TWO, "ns-Moves", 6, 16),
+        ];
+        let events = vec![
+            event(Felt::TWO, "ns-Position", 20, 200, 0xb),
+            event(Felt::TWO, "ns-Position", 10, 100, 0xa),
+            // A version indexed after the query ran is joined with nothing
+            event(Felt::TWO, "ns-Position", 30, 300, 0xe),
+            event(Felt::TWO, "ns-Moves", 15, 150, 0xc),
+            // Transactions of the same block executed at the same time
+            event(Felt::TWO, "ns-Moves", 16, 160, 0xd),
+            event(Felt::TWO, "ns-Moves", 16, 160, 0xf),
+        ];
+        let timelines = timelines(versions, &events);
+
+        let position = &timelines[&(Felt::TWO, "ns-Position".to_string())];
+        assert_eq!(
+            position.iter().map(|v| (v.block_number, v.timestamp)).collect::<Vec<_>>(),
+            [(Some(100), 10), (Some(200), 20)]
+        );
+        assert_eq!(position[1].transaction_hash, Some(Felt::from(0xbu8)));
+        let moves = &timelines[&(Felt::TWO, "ns-Moves".to_string())];
+        assert_eq!(moves[0].transaction_hash, Some(Felt::from(0xcu8)));
+        assert_eq!((moves[1].block_number, moves[1].transaction_hash), (Some(160), None));
+
+        let range = HistoryRange { from: Some(At::Block(150)), to: None };
+        assert_eq!(within(position.clone(), range).len(), 1);
+
+        let state = state_at(Felt::ONE, timelines.clone(), At::Timestamp(12));
+        assert_eq!(state[0].models.len(), 1);
+        assert_eq!(state[0].models[0].children[0].ty, Ty::Primitive(Primitive::U32(Some(1))));
+        assert_eq!(state_at(Felt::ONE, timelines.clone(), At::Timestamp(15))[0].models.len(), 2);
+        assert!(state_at(Felt::ONE, timelines, At::Timestamp(5)).is_empty());
+    }
+
+    #[test]
+    fn fails_when_events_are_unavailable() {
+        let fetch = |_: Query| async {
+            Ok(Page { items: vec![version(Felt::TWO, "ns-Position", 1, 10)], next_cursor: None })
+        };
+        let sql = |_: String| async { Err::<Vec<SqlRow>, _>(anyhow::anyhow!("sql is disabled")) };
+        let fetched = fetch_timelines(&[Felt::ONE], &[Felt::TWO], vec![], fetch, sql);
+        assert!(futures::executor::block_on(fetched).is_err());
+    }
+}
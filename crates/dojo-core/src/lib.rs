@@ -1,5 +1,34 @@
+pub mod access;
+pub mod batch;
+pub mod builder;
+pub mod cache;
+pub mod change;
+pub mod clause;
+pub mod codec;
+pub mod config;
+pub mod connection;
 pub mod constants;
+pub mod dedup;
+pub mod delivery;
+pub mod diff;
+pub mod dispatch;
+pub mod failover;
+pub mod filter;
+pub mod history;
+#[cfg(test)]
+mod mock;
+pub mod mux;
+pub mod optimistic;
+pub mod pagination;
+pub mod polling;
+pub mod projection;
+pub mod replay;
+pub mod schema;
+pub mod status;
+pub mod subscription;
+pub mod upgrade;
 pub mod utils;
+pub mod validate;
 
 // Re-export commonly used types for convenience
 pub use utils::watch_tx;
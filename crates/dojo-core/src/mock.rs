@@ -0,0 +1,115 @@
+//! In-process Torii replicas for tests, reached through [`Servers`].
+//!
+//! A replica serves streams of `u32` items, each subscribed with the `u32` item it wants, and
+//! can be stopped and restarted to exercise failover.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+
+use crate::config::ClientConfig;
+use crate::failover::Transport;
+
+/// Items of a stream, the first one carrying the subscription id.
+pub type Items = UnboundedReceiver<Result<(u64, u32), ()>>;
+
+struct Stream {
+    query: u32,
+    sender: UnboundedSender<Result<(u64, u32), ()>>,
+}
+
+struct State {
+    up: bool,
+    head: u64,
+    next_id: u64,
+    streams: HashMap<u64, Stream>,
+}
+
+/// A replica, cloned into every client connected to it.
+#[derive(Clone)]
+pub struct Server(Arc<Mutex<State>>);
+
+impl Server {
+    /// A running replica assigning subscription ids from `first_id`.
+    pub fn new(head: u64, first_id: u64) -> Self {
+        let state = State { up: true, head, next_id: first_id, streams: HashMap::new() };
+        Self(Arc::new(Mutex::new(state)))
+    }
+
+    /// Stops answering and closes every stream.
+    pub fn stop(&self) {
+        let mut state = self.0.lock().unwrap();
+        state.up = false;
+        state.streams.clear();
+    }
+
+    pub fn start(&self) {
+        self.0.lock().unwrap().up = true;
+    }
+
+    pub fn set_head(&self, head: u64) {
+        self.0.lock().unwrap().head = head;
+    }
+
+    pub fn head(&self) -> Result<u64> {
+        let state = self.0.lock().unwrap();
+        state.up.then_some(state.head).ok_or_else(|| anyhow!("server is down"))
+    }
+
+    /// Opens a stream of the items equal to `query`.
+    pub fn subscribe(&self, query: u32) -> Result<Items> {
+        self.head()?;
+        let mut state = self.0.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+
+        let (sender, receiver) = unbounded();
+        let _ = sender.unbounded_send(Ok((id, 0)));
+        state.streams.insert(id, Stream { query, sender });
+        Ok(receiver)
+    }
+
+    /// Changes the query of the stream with the given subscription id.
+    pub fn update(&self, id: u64, query: u32) -> Result<()> {
+        self.head()?;
+        let mut state = self.0.lock().unwrap();
+        let stream = state.streams.get_mut(&id).ok_or_else(|| anyhow!("unknown stream {id}"))?;
+        stream.query = query;
+        Ok(())
+    }
+
+    /// Sends an item to the streams subscribed to it.
+    pub fn publish(&self, item: u32) {
+        let state = self.0.lock().unwrap();
+        for (id, stream) in state.streams.iter().filter(|(_, stream)| stream.query == item) {
+            let _ = stream.sender.unbounded_send(Ok((*id, item)));
+        }
+    }
+}
+
+/// Replicas by URL.
+#[derive(Clone, Default)]
+pub struct Servers(Arc<Mutex<HashMap<String, Server>>>);
+
+impl Servers {
+    pub fn add(&self, url: &str, server: Server) {
+        self.0.lock().unwrap().insert(url.to_string(), server);
+    }
+}
+
+impl Transport for Servers {
+    type Client = Server;
+
+    async fn connect(&self, config: &ClientConfig) -> Result<Server> {
+        let server = self.0.lock().unwrap().get(&config.torii_url).cloned();
+        let server = server.ok_or_else(|| anyhow!("no server at {}", config.torii_url))?;
+        server.head()?;
+        Ok(server)
+    }
+
+    async fn head(&self, server: &Server) -> Result<Option<u64>> {
+        server.head().map(Some)
+    }
+}
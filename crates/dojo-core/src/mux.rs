@@ -0,0 +1,942 @@
+use std::collections::HashSet;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crypto_bigint::U256;
+use futures::channel::mpsc;
+use futures::StreamExt;
+use starknet_crypto::Felt;
+use stream_cancel::{Trigger, Tripwire};
+use torii_client::Client;
+use torii_proto::schema::Entity;
+use torii_proto::{Clause, CompositeClause, LogicalOperator, TokenBalance};
+
+use crate::change::is_deleted;
+use crate::clause;
+use crate::connection::Connection;
+use crate::polling::EntitySource;
+use crate::status::{SubscriptionState, SubscriptionStatus};
+use crate::subscription::{drive, drive_entities, invalidate_token_balances, CANCELLED};
+
+/// A kind of Torii subscription whose consumers can share a single stream.
+///
+/// The stream serves a query covering every consumer, and each item is handed to the consumers
+/// whose own query matches it.
+pub trait Topic: Copy + 'static {
+    type Query: Clone + PartialEq;
+    type Item: Clone;
+
+    /// Whether items can be matched against `query` locally, which consumers need to share the
+    /// stream.
+    fn shareable(self, query: &Self::Query) -> bool;
+
+    /// A query matching every item matched by one of `queries`.
+    fn widen(self, queries: &[&Self::Query]) -> Self::Query;
+
+    fn matches(self, query: &Self::Query, item: &Self::Item) -> bool;
+
+    /// Hashed keys of the entity an item updates, along with whether the item deletes it.
+    /// Deletions carry no models to match queries against, so they are only handed to the
+    /// consumers that were handed the entity.
+    fn entity(self, _item: &Self::Item) -> Option<(Felt, bool)> {
+        None
+    }
+
+    /// Drives the shared stream, see [`drive`]. Every connection uses the query returned by
+    /// `query` at the time. The stream isn't held back for a slow consumer, which would hold it
+    /// back for the others, see [`Overflow::Wait`](crate::delivery::Overflow::Wait).
+    #[allow(clippy::too_many_arguments)]
+    fn drive<Z, ZF>(
+        self,
+        connection: Arc<Connection>,
+        query: impl Fn() -> Self::Query,
+        sleep: Z,
+        tripwire: Tripwire,
+        status: SubscriptionStatus,
+        on_connected: impl FnMut(u64),
+        on_item: impl FnMut(Self::Item),
+    ) -> impl Future<Output = ()>
+    where
+        Z: Fn(Duration) -> ZF,
+        ZF: Future<Output = ()>;
+
+    /// Changes the query served by the stream with the given subscription id. Returns whether
+    /// the server accepted it.
+    fn update(self, client: Arc<Client>, id: u64, query: Self::Query)
+        -> impl Future<Output = bool>;
+}
+
+/// Query of an entity or event message subscription.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EntityFilter {
+    pub clause: Option<Clause>,
+    pub world_addresses: Vec<Felt>,
+}
+
+/// Query of a token balance subscription. Empty lists match everything.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TokenBalanceFilter {
+    pub contract_addresses: Vec<Felt>,
+    pub account_addresses: Vec<Felt>,
+    pub token_ids: Vec<U256>,
+}
+
+/// Entity updates, see [`torii_client::Client::on_entity_updated`].
+#[derive(Debug, Clone, Copy)]
+pub struct Entities;
+
+/// Event message updates, see [`torii_client::Client::on_event_message_updated`].
+#[derive(Debug, Clone, Copy)]
+pub struct EventMessages;
+
+/// Token balance updates, see [`torii_client::Client::on_token_balance_updated`].
+#[derive(Debug, Clone, Copy)]
+pub struct TokenBalances;
+
+impl Topic for Entities {
+    type Query = EntityFilter;
+    type Item = Entity;
+
+    fn shareable(self, _query: &EntityFilter) -> bool {
+        true
+    }
+
+    fn widen(self, queries: &[&EntityFilter]) -> EntityFilter {
+        widen_entities(queries)
+    }
+
+    fn matches(self, query: &EntityFilter, item: &Entity) -> bool {
+        matches_entity(query, item)
+    }
+
+    fn entity(self, item: &Entity) -> Option<(Felt, bool)> {
+        Some((item.hashed_keys, is_deleted(item)))
+    }
+
+    fn drive<Z, ZF>(
+        self,
+        connection: Arc<Connection>,
+        query: impl Fn() -> EntityFilter,
+        sleep: Z,
+        tripwire: Tripwire,
+        status: SubscriptionStatus,
+        on_connected: impl FnMut(u64),
+        on_item: impl FnMut(Entity),
+    ) -> impl Future<Output = ()>
+    where
+        Z: Fn(Duration) -> ZF,
+        ZF: Future<Output = ()>,
+    {
+        let source = EntitySource::Entities;
+        drive_entities(
+            connection,
+            source,
+            query,
+            sleep,
+            tripwire,
+            status,
+            None,
+            on_connected,
+            on_item,
+        )
+    }
+
+    async fn update(self, client: Arc<Client>, id: u64, query: EntityFilter) -> bool {
+        client.update_entity_subscription(id, query.clause, query.world_addresses).await.is_ok()
+    }
+}
+
+impl Topic for EventMessages {
+    type Query = EntityFilter;
+    type Item = Entity;
+
+    fn shareable(self, query: &EntityFilter) -> bool {
+        Entities.shareable(query)
+    }
+
+    fn widen(self, queries: &[&EntityFilter]) -> EntityFilter {
+        widen_entities(queries)
+    }
+
+    fn matches(self, query: &EntityFilter, item: &Entity) -> bool {
+        matches_entity(query, item)
+    }
+
+    fn entity(self, item: &Entity) -> Option<(Felt, bool)> {
+        Entities.entity(item)
+    }
+
+    fn drive<Z, ZF>(
+        self,
+        connection: Arc<Connection>,
+        query: impl Fn() -> EntityFilter,
+        sleep: Z,
+        tripwire: Tripwire,
+        status: SubscriptionStatus,
+        on_connected: impl FnMut(u64),
+        on_item: impl FnMut(Entity),
+    ) -> impl Future<Output = ()>
+    where
+        Z: Fn(Duration) -> ZF,
+        ZF: Future<Output = ()>,
+    {
+        let source = EntitySource::EventMessages;
+        drive_entities(
+            connection,
+            source,
+            query,
+            sleep,
+            tripwire,
+            status,
+            None,
+            on_connected,
+            on_item,
+        )
+    }
+
+    async fn update(self, client: Arc<Client>, id: u64, query: EntityFilter) -> bool {
+        client
+            .update_event_message_subscription(id, query.clause, query.world_addresses)
+            .await
+            .is_ok()
+    }
+}
+
+impl Topic for TokenBalances {
+    type Query = TokenBalanceFilter;
+    type Item = TokenBalance;
+
+    fn shareable(self, _query: &TokenBalanceFilter) -> bool {
+        true
+    }
+
+    fn widen(self, queries: &[&TokenBalanceFilter]) -> TokenBalanceFilter {
+        TokenBalanceFilter {
+            contract_addresses: union(queries.iter().map(|q| &q.contract_addresses)),
+            account_addresses: union(queries.iter().map(|q| &q.account_addresses)),
+            token_ids: union(queries.iter().map(|q| &q.token_ids)),
+        }
+    }
+
+    fn matches(self, query: &TokenBalanceFilter, item: &TokenBalance) -> bool {
+        let contains = |list: &[Felt], value| list.is_empty() || list.contains(value);
+        contains(&query.contract_addresses, &item.contract_address)
+            && contains(&query.account_addresses, &item.account_address)
+            && (query.token_ids.is_empty()
+                || item.token_id.is_some_and(|id| query.token_ids.contains(&id)))
+    }
+
+    fn drive<Z, ZF>(
+        self,
+        connection: Arc<Connection>,
+        query: impl Fn() -> TokenBalanceFilter,
+        sleep: Z,
+        tripwire: Tripwire,
+        status: SubscriptionStatus,
+        on_connected: impl FnMut(u64),
+        on_item: impl FnMut(TokenBalance),
+    ) -> impl Future<Output = ()>
+    where
+        Z: Fn(Duration) -> ZF,
+        ZF: Future<Output = ()>,
+    {
+        let connect = move |client: Arc<Client>| {
+            let TokenBalanceFilter { contract_addresses, account_addresses, token_ids } = query();
+            async move {
+                client
+                    .on_token_balance_updated(contract_addresses, account_addresses, token_ids)
+                    .await
+            }
+        };
+        let on_item = invalidate_token_balances(&connection, on_item);
+        drive(connection, connect, sleep, tripwire, status, None, on_connected, on_item)
+    }
+
+    async fn update(self, client: Arc<Client>, id: u64, query: TokenBalanceFilter) -> bool {
+        client
+            .update_token_balance_subscription(
+                id,
+                query.contract_addresses,
+                query.account_addresses,
+                query.token_ids,
+            )
+            .await
+            .is_ok()
+    }
+}
+
+fn widen_entities(queries: &[&EntityFilter]) -> EntityFilter {
+    let mut clauses: Vec<Clause> = Vec::new();
+    for query in queries {
+        match &query.clause {
+            // One consumer wants every entity
+            None => {
+                clauses.clear();
+                break;
+            }
+            Some(clause) if !clauses.contains(clause) => clauses.push(clause.clone()),
+            Some(_) => {}
+        }
+    }
+
+    let clause = match clauses.len() {
+        0 => None,
+        1 => clauses.pop(),
+        _ => Some(Clause::Composite(CompositeClause { operator: LogicalOperator::Or, clauses })),
+    };
+    EntityFilter { clause, world_addresses: union(queries.iter().map(|q| &q.world_addresses)) }
+}
+
+fn matches_entity(query: &EntityFilter, entity: &Entity) -> bool {
+    if !query.world_addresses.is_empty() && !query.world_addresses.contains(&entity.world_address) {
+        return false;
+    }
+    query.clause.as_ref().is_none_or(|clause| matches_clause(clause, entity))
+}
+
+fn matches_clause(clause: &Clause, entity: &Entity) -> bool {
+    match clause {
+        Clause::Composite(composite) if !composite.clauses.is_empty() => match composite.operator {
+            LogicalOperator::And => composite.clauses.iter().all(|c| matches_clause(c, entity)),
+            LogicalOperator::Or => composite.clauses.iter().any(|c| matches_clause(c, entity)),
+        },
+        clause => clause::matches(clause, entity),
+    }
+}
+
+/// Union of lists where an empty list stands for everything.
+fn union<'a, T: Clone + PartialEq + 'a>(lists: impl Iterator<Item = &'a Vec<T>>) -> Vec<T> {
+    let mut union = Vec::new();
+    for list in lists {
+        if list.is_empty() {
+            return Vec::new();
+        }
+        for value in list {
+            if !union.contains(value) {
+                union.push(value.clone());
+            }
+        }
+    }
+    union
+}
+
+struct Consumer<T: Topic, S> {
+    id: u64,
+    /// Stream the consumer is attached to
+    generation: u64,
+    query: T::Query,
+    status: SubscriptionStatus,
+    /// Called without holding the state, so that it can join, update or leave the mux
+    sink: Arc<Mutex<S>>,
+    /// Entities handed to the consumer and still matching its query, see [`Topic::entity`]
+    sent: HashSet<Felt>,
+}
+
+struct Shared<T: Topic> {
+    generation: u64,
+    /// Cancels the stream when dropped
+    _trigger: Trigger,
+    status: SubscriptionStatus,
+    /// Subscription id assigned by the server to the current connection
+    server_id: Option<u64>,
+    /// Query the server is currently serving
+    serving: T::Query,
+    /// Whether models were upgraded since the server subscribed to `serving`
+    stale: bool,
+    /// Notifies the stream task that the consumers changed
+    changes: mpsc::UnboundedSender<()>,
+}
+
+struct State<T: Topic, S> {
+    next_id: u64,
+    generation: u64,
+    consumers: Vec<Consumer<T, S>>,
+    stream: Option<Shared<T>>,
+}
+
+impl<T: Topic, S> State<T, S> {
+    fn shared(&mut self, generation: u64) -> Option<&mut Shared<T>> {
+        self.stream.as_mut().filter(|shared| shared.generation == generation)
+    }
+
+    fn widened(&self, topic: T, generation: u64) -> T::Query {
+        let queries = self
+            .consumers
+            .iter()
+            .filter(|consumer| consumer.generation == generation)
+            .map(|consumer| &consumer.query)
+            .collect::<Vec<_>>();
+        topic.widen(&queries)
+    }
+
+    /// Sinks of the consumers an item of a stream concerns, to be handed the item once the state
+    /// is released.
+    fn concerned(&mut self, topic: T, generation: u64, item: &T::Item) -> Vec<Arc<Mutex<S>>> {
+        let entity = topic.entity(item);
+        let mut sinks = Vec::new();
+        for consumer in self.consumers.iter_mut().filter(|c| c.generation == generation) {
+            let concerned = match entity {
+                Some((hashed_keys, true)) => consumer.sent.remove(&hashed_keys),
+                Some((hashed_keys, false)) => {
+                    let matched = topic.matches(&consumer.query, item);
+                    // Entities leaving the query of the consumer are forgotten
+                    if matched {
+                        consumer.sent.insert(hashed_keys);
+                    } else {
+                        consumer.sent.remove(&hashed_keys);
+                    }
+                    matched
+                }
+                None => topic.matches(&consumer.query, item),
+            };
+            if concerned {
+                sinks.push(consumer.sink.clone());
+            }
+        }
+        sinks
+    }
+}
+
+/// Hands an item of a stream to the consumers it concerns, calling their sinks once the state is
+/// released.
+fn deliver<T: Topic, S: FnMut(T::Item)>(
+    state: &Mutex<State<T, S>>,
+    topic: T,
+    generation: u64,
+    item: T::Item,
+) {
+    let sinks = state.lock().unwrap().concerned(topic, generation, &item);
+    for sink in sinks {
+        (sink.lock().unwrap())(item.clone());
+    }
+}
+
+/// A consumer of a [`Mux`]. Dropping it unsubscribes the consumer, and closes the stream when it
+/// was the last one.
+pub struct Lease<T: Topic, S> {
+    mux: Mux<T, S>,
+    pub id: u64,
+    pub status: SubscriptionStatus,
+}
+
+impl<T: Topic, S> Drop for Lease<T, S> {
+    fn drop(&mut self) {
+        self.mux.leave(self.id);
+    }
+}
+
+/// Multiplexes the subscriptions of a [`Topic`] onto a single Torii stream.
+///
+/// The first consumer opens the stream with its own query. Every consumer joining or leaving
+/// afterwards widens or narrows the query of the stream with the topic's `update_*_subscription`
+/// call, retried until the server accepts it, and the stream is closed along with its last
+/// consumer. Items are matched against the query of every consumer and handed to the `sink` of
+/// those they match, deletions going to the consumers that were handed the entity since it last
+/// matched their query. Once models
+/// are upgraded, see [`Upgrades`](crate::upgrade::Upgrades), the query is sent to the server again.
+///
+/// Consumers share the connection state of the stream, mirrored into their own
+/// [`SubscriptionStatus`].
+pub struct Mux<T: Topic, S> {
+    topic: T,
+    state: Arc<Mutex<State<T, S>>>,
+}
+
+impl<T: Topic, S> Clone for Mux<T, S> {
+    fn clone(&self) -> Self {
+        Self { topic: self.topic, state: self.state.clone() }
+    }
+}
+
+impl<T: Topic, S: FnMut(T::Item)> Mux<T, S> {
+    pub fn new(topic: T) -> Self {
+        let state = State { next_id: 1, generation: 0, consumers: Vec::new(), stream: None };
+        Self { topic, state: Arc::new(Mutex::new(state)) }
+    }
+
+    pub fn shareable(&self, query: &T::Query) -> bool {
+        self.topic.shareable(query)
+    }
+
+    /// Adds a consumer. When no stream is running, also returns the task driving a new one,
+    /// which must be spawned.
+    pub fn join<Z, ZF>(
+        &self,
+        connection: Arc<Connection>,
+        sleep: Z,
+        query: T::Query,
+        sink: S,
+    ) -> (Lease<T, S>, Option<impl Future<Output = ()>>)
+    where
+        Z: Fn(Duration) -> ZF,
+        ZF: Future<Output = ()>,
+    {
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        let status = SubscriptionStatus::new();
+
+        let started = match &state.stream {
+            Some(shared) => {
+                status.set(shared.status.state());
+                let _ = shared.changes.unbounded_send(());
+                None
+            }
+            None => {
+                state.generation += 1;
+                let (trigger, tripwire) = Tripwire::new();
+                let (changes, changed) = mpsc::unbounded();
+                let shared_status = SubscriptionStatus::new();
+                state.stream = Some(Shared {
+                    generation: state.generation,
+                    _trigger: trigger,
+                    status: shared_status.clone(),
+                    server_id: None,
+                    serving: query.clone(),
+                    stale: false,
+                    changes,
+                });
+                Some((tripwire, shared_status, changed))
+            }
+        };
+
+        let generation = state.generation;
+        state.consumers.push(Consumer {
+            id,
+            generation,
+            query,
+            status: status.clone(),
+            sink: Arc::new(Mutex::new(sink)),
+            sent: HashSet::new(),
+        });
+        drop(state);
+
+        let task = started.map(|(tripwire, status, changed)| {
+            self.clone().run(generation, connection, sleep, tripwire, status, changed)
+        });
+        (Lease { mux: self.clone(), id, status }, task)
+    }
+
+    /// Changes the query of a consumer. Returns false if there is no such consumer.
+    pub fn update(&self, id: u64, query: T::Query) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let Some(consumer) = state.consumers.iter_mut().find(|consumer| consumer.id == id) else {
+            return false;
+        };
+        consumer.query = query;
+        let generation = consumer.generation;
+        if let Some(shared) = state.shared(generation) {
+            let _ = shared.changes.unbounded_send(());
+        }
+        true
+    }
+
+    fn leave(&self, id: u64) {
+        let mut state = self.state.lock().unwrap();
+        let Some(index) = state.consumers.iter().position(|consumer| consumer.id == id) else {
+            return;
+        };
+        let consumer = state.consumers.remove(index);
+        consumer.status.set(SubscriptionState::Closed { reason: CANCELLED.to_string() });
+
+        let generation = consumer.generation;
+        if state.consumers.iter().any(|consumer| consumer.generation == generation) {
+            if let Some(shared) = state.shared(generation) {
+                let _ = shared.changes.unbounded_send(());
+            }
+        } else if state.shared(generation).is_some() {
+            // Dropping the trigger closes the stream
+            state.stream = None;
+        }
+    }
+
+    async fn run<Z, ZF>(
+        self,
+        generation: u64,
+        connection: Arc<Connection>,
+        sleep: Z,
+        tripwire: Tripwire,
+        status: SubscriptionStatus,
+        mut changed: mpsc::UnboundedReceiver<()>,
+    ) where
+        Z: Fn(Duration) -> ZF,
+        ZF: Future<Output = ()>,
+    {
+        let Self { topic, state } = self;
+
+        // Also what entity streams catch up with after reconnecting
+        let query = {
+            let state = state.clone();
+            move || {
+                let mut state = state.lock().unwrap();
+                let query = state.widened(topic, generation);
+                if let Some(shared) = state.shared(generation) {
+                    shared.serving = query.clone();
+                    shared.stale = false;
+                }
+                query
+            }
+        };
+        let on_connected = {
+            let state = state.clone();
+            move |id| {
+                if let Some(shared) = state.lock().unwrap().shared(generation) {
+                    shared.server_id = Some(id);
+                    // Consumers may have changed while connecting
+                    let _ = shared.changes.unbounded_send(());
+                }
+            }
+        };
+        let on_item = {
+            let state = state.clone();
+            let connection = connection.clone();
+            let mut upgrades = connection.upgrades().count();
+            move |item: T::Item| {
+                // The server filters with the schema it subscribed with, have it subscribe again
+                if !connection.upgrades().since(&mut upgrades).is_empty() {
+                    if let Some(shared) = state.lock().unwrap().shared(generation) {
+                        shared.stale = true;
+                        let _ = shared.changes.unbounded_send(());
+                    }
+                }
+                deliver(&state, topic, generation, item);
+            }
+        };
+        let driver = topic.drive(
+            connection.clone(),
+            query,
+            &sleep,
+            tripwire,
+            status.clone(),
+            on_connected,
+            on_item,
+        );
+
+        let mirror = async {
+            let mut states = status.watch();
+            while let Some(current) = states.next().await {
+                let state = state.lock().unwrap();
+                for consumer in state.consumers.iter().filter(|c| c.generation == generation) {
+                    consumer.status.set(current.clone());
+                }
+            }
+        };
+
+        let updater = async {
+            let retry = connection.config().retry.clone();
+            while changed.next().await.is_some() {
+                // Retries with backoff until the server serves the consumers' query, or the
+                // stream reconnects with it
+                let mut attempt = 0;
+                loop {
+                    let update = {
+                        let mut state = state.lock().unwrap();
+                        let query = state.widened(topic, generation);
+                        match state.shared(generation) {
+                            // Polling streams have no server side subscription to update
+                            Some(shared)
+                                if (shared.stale || shared.serving != query)
+                                    && shared.server_id != Some(0) =>
+                            {
+                                shared.server_id.map(|id| (id, query))
+                            }
+                            _ => None,
+                        }
+                    };
+                    // Replayed streams already carry the updates of the recorded session
+                    let Some((id, query)) = update.filter(|_| !connection.is_replaying()) else {
+                        break;
+                    };
+
+                    let updated = match connection.pool().current() {
+                        Ok(client) => topic.update(client, id, query.clone()).await,
+                        Err(_) => false,
+                    };
+                    if updated {
+                        if let Some(shared) = state.lock().unwrap().shared(generation) {
+                            if shared.server_id == Some(id) {
+                                shared.serving = query;
+                                shared.stale = false;
+                            }
+                        }
+                        break;
+                    }
+
+                    sleep(retry.backoff(attempt)).await;
+                    attempt += 1;
+                }
+            }
+        };
+
+        futures::future::select(pin!(updater), pin!(futures::future::join(driver, mirror))).await;
+
+        // The stream is gone, detach the consumers it was serving
+        let mut state = state.lock().unwrap();
+        state.consumers.retain(|consumer| consumer.generation != generation);
+        if state.shared(generation).is_some() {
+            state.stream = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use dojo_types::primitive::Primitive;
+    use dojo_types::schema::{Member, Struct, Ty};
+    use futures::executor::LocalPool;
+    use futures::task::LocalSpawnExt;
+
+    use super::*;
+    use crate::config::ClientConfig;
+    use crate::replay::{Buffer, Recorder, Replay};
+
+    type Received = Arc<Mutex<Vec<(u64, Felt)>>>;
+    type Sink = Box<dyn FnMut(Entity)>;
+
+    fn connection() -> Arc<Connection> {
+        let buffer = Buffer::default();
+        Recorder::new(buffer.clone(), 0);
+        let replay = Replay::parse(&buffer.contents(), f64::INFINITY).unwrap();
+        Arc::new(Connection::replay(ClientConfig::new("http://localhost:8080"), replay))
+    }
+
+    fn join(
+        mux: &Mux<Entities, Sink>,
+        name: u64,
+        query: EntityFilter,
+        received: &Received,
+    ) -> (Lease<Entities, Sink>, Option<impl Future<Output = ()>>) {
+        let received = received.clone();
+        let sink: Sink = Box::new(move |entity: Entity| {
+            received.lock().unwrap().push((name, entity.hashed_keys));
+        });
+        mux.join(connection(), |_| futures::future::ready(()), query, sink)
+    }
+
+    fn entity(hashed_keys: Felt, models: &[&str]) -> Entity {
+        let model = |name: &&str| Struct {
+            name: name.to_string(),
+            children: vec![Member {
+                name: "value".to_string(),
+                ty: Ty::Primitive(Primitive::U32(Some(1))),
+                key: false,
+            }],
+        };
+        Entity {
+            world_address: Felt::ONE,
+            hashed_keys,
+            models: models.iter().map(model).collect(),
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            executed_at: Default::default(),
+        }
+    }
+
+    #[test]
+    fn attaches_and_detaches_consumers() {
+        let mux = Mux::new(Entities);
+        let received = Received::default();
+        let hashed = |key| EntityFilter {
+            clause: Some(Clause::HashedKeys(vec![key])),
+            world_addresses: vec![],
+        };
+
+        // Only the first consumer opens a stream
+        let (first, task) = join(&mux, 1, hashed(Felt::ONE), &received);
+        assert!(task.is_some());
+        let (second, task) = join(&mux, 2, hashed(Felt::TWO), &received);
+        assert!(task.is_none());
+        {
+            let state = mux.state.lock().unwrap();
+            assert_eq!(state.consumers.len(), 2);
+            assert_eq!(
+                state.widened(Entities, state.generation),
+                Entities.widen(&[&hashed(Felt::ONE), &hashed(Felt::TWO)])
+            );
+        }
+
+        drop(first);
+        assert_eq!(mux.state.lock().unwrap().consumers.len(), 1);
+        assert!(mux.state.lock().unwrap().stream.is_some());
+        // The stream closes along with its last consumer
+        drop(second);
+        let state = mux.state.lock().unwrap();
+        assert!(state.consumers.is_empty());
+        assert!(state.stream.is_none());
+    }
+
+    #[test]
+    fn routes_updates_and_deletions() {
+        let mux = Mux::new(Entities);
+        let received = Received::default();
+        let _all = join(&mux, 1, EntityFilter::default(), &received);
+        let two = EntityFilter {
+            clause: Some(Clause::HashedKeys(vec![Felt::TWO])),
+            ..Default::default()
+        };
+        let _two = join(&mux, 2, two, &received);
+        let position = EntityFilter {
+            clause: Some(crate::filter::parse("ns-Position.value = 1_u32").unwrap()),
+            ..Default::default()
+        };
+        let _position = join(&mux, 3, position, &received);
+
+        let generation = mux.state.lock().unwrap().generation;
+        let deliver = |item| deliver(&mux.state, Entities, generation, item);
+        deliver(entity(Felt::ONE, &["ns-Position"]));
+        deliver(entity(Felt::TWO, &["ns-Moves"]));
+        // The entity leaves the query of the third consumer, which forgets it
+        deliver(entity(Felt::ONE, &["ns-Moves"]));
+        assert!(mux.state.lock().unwrap().consumers[2].sent.is_empty());
+        // Deletions only reach the consumers that were handed the entity
+        deliver(entity(Felt::ONE, &[]));
+        deliver(entity(Felt::THREE, &[]));
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            [
+                (1, Felt::ONE),
+                (3, Felt::ONE),
+                (1, Felt::TWO),
+                (2, Felt::TWO),
+                (1, Felt::ONE),
+                (1, Felt::ONE)
+            ]
+        );
+        assert!(mux.state.lock().unwrap().consumers.iter().all(|c| c.sent.len() <= 1));
+    }
+
+    #[test]
+    fn calls_sinks_without_holding_the_state() {
+        let mux = Mux::new(Entities);
+        let updated = Arc::new(Mutex::new(false));
+        let sink: Sink = {
+            let (mux, updated) = (mux.clone(), updated.clone());
+            Box::new(move |_: Entity| {
+                *updated.lock().unwrap() = mux.update(1, EntityFilter::default())
+            })
+        };
+        let _lease =
+            mux.join(connection(), |_| futures::future::ready(()), Default::default(), sink);
+
+        let generation = mux.state.lock().unwrap().generation;
+        deliver(&mux.state, Entities, generation, entity(Felt::ONE, &["ns-Position"]));
+        assert!(*updated.lock().unwrap());
+    }
+
+    thread_local! {
+        static CAUGHT_UP: RefCell<Vec<EntityFilter>> = Default::default();
+    }
+
+    /// Records the query it streams, which entity streams also catch up with.
+    #[derive(Clone, Copy)]
+    struct CatchUp;
+
+    impl Topic for CatchUp {
+        type Query = EntityFilter;
+        type Item = Entity;
+
+        fn shareable(self, _query: &EntityFilter) -> bool {
+            true
+        }
+
+        fn widen(self, queries: &[&EntityFilter]) -> EntityFilter {
+            Entities.widen(queries)
+        }
+
+        fn matches(self, query: &EntityFilter, item: &Entity) -> bool {
+            Entities.matches(query, item)
+        }
+
+        async fn drive<Z, ZF>(
+            self,
+            _connection: Arc<Connection>,
+            query: impl Fn() -> EntityFilter,
+            _sleep: Z,
+            _tripwire: Tripwire,
+            _status: SubscriptionStatus,
+            _on_connected: impl FnMut(u64),
+            _on_item: impl FnMut(Entity),
+        ) where
+            Z: Fn(Duration) -> ZF,
+            ZF: Future<Output = ()>,
+        {
+            CAUGHT_UP.with(|caught_up| caught_up.borrow_mut().push(query()));
+        }
+
+        async fn update(self, _client: Arc<Client>, _id: u64, _query: EntityFilter) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn catches_up_with_the_union_of_the_consumers() {
+        let mux = Mux::<_, Sink>::new(CatchUp);
+        let sleep = |_: Duration| futures::future::ready(());
+        let world = |world| EntityFilter { clause: None, world_addresses: vec![world] };
+        let (_first, task) =
+            mux.join(connection(), sleep, world(Felt::ONE), Box::new(|_: Entity| {}));
+        let (_second, _) =
+            mux.join(connection(), sleep, world(Felt::TWO), Box::new(|_: Entity| {}));
+
+        let mut tasks = LocalPool::new();
+        tasks.spawner().spawn_local(task.unwrap()).unwrap();
+        tasks.run_until_stalled();
+        let caught_up = CAUGHT_UP.with(|caught_up| caught_up.take());
+        assert_eq!(
+            caught_up,
+            [EntityFilter { clause: None, world_addresses: vec![Felt::ONE, Felt::TWO] }]
+        );
+    }
+
+    #[test]
+    fn widens_token_balance_filters() {
+        let filter = |contracts: Vec<Felt>, accounts: Vec<Felt>| TokenBalanceFilter {
+            contract_addresses: contracts,
+            account_addresses: accounts,
+            token_ids: Vec::new(),
+        };
+        let a = filter(vec![Felt::ONE], vec![Felt::TWO]);
+        let b = filter(vec![Felt::ONE, Felt::THREE], vec![]);
+
+        let widened = TokenBalances.widen(&[&a, &b]);
+        assert_eq!(widened, filter(vec![Felt::ONE, Felt::THREE], vec![]));
+
+        let balance = TokenBalance {
+            balance: U256::ZERO,
+            account_address: Felt::THREE,
+            contract_address: Felt::ONE,
+            token_id: None,
+        };
+        assert!(TokenBalances.matches(&widened, &balance));
+        assert!(TokenBalances.matches(&b, &balance));
+        assert!(!TokenBalances.matches(&a, &balance));
+    }
+
+    #[test]
+    fn widens_entity_filters() {
+        let hashed = |keys: Vec<Felt>| EntityFilter {
+            clause: Some(Clause::HashedKeys(keys)),
+            world_addresses: vec![Felt::ONE],
+        };
+        let a = hashed(vec![Felt::ONE]);
+        let b = hashed(vec![Felt::TWO]);
+
+        let widened = Entities.widen(&[&a, &b, &a]);
+        assert_eq!(
+            widened.clause,
+            Some(Clause::Composite(CompositeClause {
+                operator: LogicalOperator::Or,
+                clauses: vec![a.clause.clone().unwrap(), b.clause.clone().unwrap()],
+            }))
+        );
+        assert_eq!(widened.world_addresses, [Felt::ONE]);
+
+        let all = EntityFilter::default();
+        assert_eq!(Entities.widen(&[&a, &all]), all);
+    }
+}
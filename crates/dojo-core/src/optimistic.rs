@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use dojo_types::schema::{Enum, Member, Struct, Ty};
+use starknet_crypto::Felt;
+use torii_proto::schema::Entity;
+
+use crate::change::{is_deleted, merge_update};
+
+struct Prediction {
+    transaction_hash: Felt,
+    world_address: Felt,
+    hashed_keys: Felt,
+    model: Struct,
+    /// The transaction succeeded
+    accepted: bool,
+}
+
+#[derive(Default)]
+struct State {
+    confirmed: HashMap<Felt, Entity>,
+    predictions: Vec<Prediction>,
+}
+
+impl State {
+    /// Confirmed state of an entity with its pending predictions applied in order.
+    fn view(&self, hashed_keys: Felt, world_address: Felt) -> Entity {
+        let mut entity = self.confirmed.get(&hashed_keys).cloned().unwrap_or_else(|| Entity {
+            world_address,
+            hashed_keys,
+            models: Vec::new(),
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            executed_at: Default::default(),
+        });
+
+        for prediction in self.predictions.iter().filter(|p| p.hashed_keys == hashed_keys) {
+            match entity.models.iter_mut().find(|model| model.name == prediction.model.name) {
+                Some(model) => *model = prediction.model.clone(),
+                None => entity.models.push(prediction.model.clone()),
+            }
+        }
+        entity
+    }
+}
+
+/// Entity state with predicted model changes layered on top until their transaction settles.
+///
+/// Predictions are made with [`Optimistic::predict`] right after sending a transaction, and
+/// confirmed entity updates are fed through [`Optimistic::confirm`], which returns the state to
+/// show. A prediction is dropped once Torii reports the predicted model, or once the
+/// transaction succeeded and an update of the entity arrived after its receipt, updates
+/// arriving before it having possibly been made by other transactions. It is rolled back when
+/// the transaction reverts, see [`Optimistic::settle`].
+///
+/// Confirmed updates only carry the models that changed and are merged into the confirmed
+/// state of the entity by model name. Entities never confirmed roll back to no models.
+/// Predicted models are compared with reported ones by value, regardless of the order of their
+/// members or of the enum variants they hold besides the selected one, which bindings may not
+/// preserve.
+#[derive(Clone, Default)]
+pub struct Optimistic {
+    state: Arc<Mutex<State>>,
+}
+
+impl Optimistic {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Predicts that the transaction will set the models of `entity`, and returns the entity
+    /// as it should now be shown.
+    pub fn predict(&self, transaction_hash: Felt, entity: Entity) -> Entity {
+        let mut state = self.state.lock().unwrap();
+        for model in entity.models {
+            state.predictions.push(Prediction {
+                transaction_hash,
+                world_address: entity.world_address,
+                hashed_keys: entity.hashed_keys,
+                model,
+                accepted: false,
+            });
+        }
+        state.view(entity.hashed_keys, entity.world_address)
+    }
+
+    /// Applies a confirmed entity update, reconciling the predictions it settles, and returns
+    /// the entity as it should now be shown.
+    pub fn confirm(&self, entity: Entity) -> Entity {
+        let mut state = self.state.lock().unwrap();
+        let (hashed_keys, world_address) = (entity.hashed_keys, entity.world_address);
+
+        state.predictions.retain_mut(|prediction| {
+            if prediction.hashed_keys != hashed_keys {
+                return true;
+            }
+            let reported = entity.models.iter().any(|model| same_model(model, &prediction.model));
+            !reported && !prediction.accepted
+        });
+
+        if is_deleted(&entity) {
+            state.confirmed.remove(&hashed_keys);
+        } else if let Some(confirmed) = state.confirmed.get_mut(&hashed_keys) {
+            merge_update(confirmed, entity);
+        } else {
+            state.confirmed.insert(hashed_keys, entity);
+        }
+        state.view(hashed_keys, world_address)
+    }
+
+    /// Records the outcome of a transaction: predictions of a reverted transaction are rolled
+    /// back, those of a successful one are kept until the next update of the entity. Returns
+    /// the entities whose state changed, as they should now be shown.
+    pub fn settle(&self, transaction_hash: Felt, succeeded: bool) -> Vec<Entity> {
+        let mut state = self.state.lock().unwrap();
+        let mut settled = Vec::new();
+
+        state.predictions.retain_mut(|prediction| {
+            if prediction.transaction_hash != transaction_hash {
+                return true;
+            }
+            prediction.accepted = succeeded;
+            if !succeeded && !settled.contains(&(prediction.hashed_keys, prediction.world_address))
+            {
+                settled.push((prediction.hashed_keys, prediction.world_address));
+            }
+            succeeded
+        });
+
+        settled
+            .into_iter()
+            .map(|(hashed_keys, world_address)| state.view(hashed_keys, world_address))
+            .collect()
+    }
+
+    /// Drops the predictions of a model, e.g. once it was upgraded and predictions no longer
+    /// match its schema. Returns the entities whose state changed, as they should now be shown.
+    pub fn discard_model(&self, tag: &str) -> Vec<Entity> {
+        let mut state = self.state.lock().unwrap();
+        let mut discarded = Vec::new();
+
+        state.predictions.retain(|prediction| {
+            if prediction.model.name != tag {
+                return true;
+            }
+            if !discarded.contains(&(prediction.hashed_keys, prediction.world_address)) {
+                discarded.push((prediction.hashed_keys, prediction.world_address));
+            }
+            false
+        });
+
+        discarded
+            .into_iter()
+            .map(|(hashed_keys, world_address)| state.view(hashed_keys, world_address))
+            .collect()
+    }
+
+    /// Whether predictions of the transaction are still applied.
+    pub fn is_pending(&self, transaction_hash: Felt) -> bool {
+        let state = self.state.lock().unwrap();
+        state.predictions.iter().any(|prediction| prediction.transaction_hash == transaction_hash)
+    }
+}
+
+/// Whether a reported model holds the predicted values, see [`Optimistic`].
+fn same_model(reported: &Struct, predicted: &Struct) -> bool {
+    reported.name == predicted.name && same_members(&reported.children, &predicted.children)
+}
+
+fn same_members(a: &[Member], b: &[Member]) -> bool {
+    a.len() == b.len()
+        && a.iter().all(|m| b.iter().any(|n| n.name == m.name && same_value(&m.ty, &n.ty)))
+}
+
+fn same_value(a: &Ty, b: &Ty) -> bool {
+    match (a, b) {
+        (Ty::Struct(a), Ty::Struct(b)) => same_members(&a.children, &b.children),
+        (Ty::Enum(a), Ty::Enum(b)) => {
+            let selected = |e: &Enum| e.option.and_then(|i| e.options.get(usize::from(i)));
+            match (selected(a), selected(b)) {
+                (Some(a), Some(b)) => a.name == b.name && same_value(&a.ty, &b.ty),
+                (a, b) => a.is_none() && b.is_none(),
+            }
+        }
+        (Ty::Tuple(a), Ty::Tuple(b))
+        | (Ty::Array(a), Ty::Array(b))
+        | (Ty::FixedSizeArray((a, _)), Ty::FixedSizeArray((b, _))) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same_value(a, b))
+        }
+        (a, b) => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_types::primitive::Primitive;
+
+    use super::*;
+
+    fn position(x: u32) -> Struct {
+        Struct {
+            name: "ns-Position".to_string(),
+            children: vec![Member {
+                name: "x".to_string(),
+                ty: Ty::Primitive(Primitive::U32(Some(x))),
+                key: false,
+            }],
+        }
+    }
+
+    fn entity(models: Vec<Struct>) -> Entity {
+        Entity {
+            world_address: Felt::ONE,
+            hashed_keys: Felt::TWO,
+            models,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            executed_at: Default::default(),
+        }
+    }
+
+    #[test]
+    fn reconciles_and_rolls_back_predictions() {
+        let optimistic = Optimistic::new();
+        let (tx_a, tx_b) = (Felt::from(10), Felt::from(11));
+        optimistic.confirm(entity(vec![position(1)]));
+
+        assert_eq!(optimistic.predict(tx_a, entity(vec![position(2)])).models, [position(2)]);
+        assert_eq!(optimistic.predict(tx_b, entity(vec![position(3)])).models, [position(3)]);
+
+        // An unrelated update keeps the predictions on top
+        assert_eq!(optimistic.confirm(entity(vec![position(1)])).models, [position(3)]);
+
+        // The first transaction reverts, the second one is still predicted
+        let settled = optimistic.settle(tx_a, false);
+        assert_eq!(settled.len(), 1);
+        assert_eq!(settled[0].models, [position(3)]);
+        assert!(!optimistic.is_pending(tx_a));
+
+        // Torii reports the predicted model
+        assert_eq!(optimistic.confirm(entity(vec![position(3)])).models, [position(3)]);
+        assert!(!optimistic.is_pending(tx_b));
+    }
+
+    #[test]
+    fn drops_accepted_predictions_on_update() {
+        let optimistic = Optimistic::new();
+        let tx = Felt::from(10);
+        optimistic.predict(tx, entity(vec![position(2)]));
+
+        assert!(optimistic.settle(tx, true).is_empty());
+        assert!(optimistic.is_pending(tx));
+
+        // The transaction did something else than predicted
+        assert_eq!(optimistic.confirm(entity(vec![position(4)])).models, [position(4)]);
+        assert!(!optimistic.is_pending(tx));
+    }
+
+    #[test]
+    fn keeps_predictions_through_updates_preceding_the_receipt() {
+        let optimistic = Optimistic::new();
+        let tx = Felt::from(10);
+        optimistic.confirm(entity(vec![position(1)]));
+        optimistic.predict(tx, entity(vec![position(2)]));
+
+        // Another transaction updated the entity before ours was accepted
+        assert_eq!(optimistic.confirm(entity(vec![position(5)])).models, [position(2)]);
+        assert!(optimistic.settle(tx, true).is_empty());
+        assert!(optimistic.is_pending(tx));
+
+        // Our update arrives after the receipt
+        assert_eq!(optimistic.confirm(entity(vec![position(6)])).models, [position(6)]);
+        assert!(!optimistic.is_pending(tx));
+    }
+
+    #[test]
+    fn merges_partial_updates_and_compares_by_value() {
+        let optimistic = Optimistic::new();
+        let moves = Struct { name: "ns-Moves".to_string(), ..position(5) };
+        optimistic.confirm(entity(vec![position(1), moves.clone()]));
+
+        // Updates only carry the models that changed
+        assert_eq!(optimistic.confirm(entity(vec![position(2)])).models, [position(2), moves]);
+
+        let tx = Felt::from(10);
+        let mut predicted = position(3);
+        predicted.children.push(Member {
+            name: "y".to_string(),
+            ty: Ty::Primitive(Primitive::U32(Some(4))),
+            key: false,
+        });
+        optimistic.predict(tx, entity(vec![predicted.clone()]));
+
+        // Torii reports the predicted values with members in another order
+        let mut reported = predicted;
+        reported.children.reverse();
+        optimistic.confirm(entity(vec![reported]));
+        assert!(!optimistic.is_pending(tx));
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::VecDeque;
+use std::future::Future;
+
+use anyhow::Result;
+use futures::channel::oneshot;
+use torii_proto::{
+    AchievementQuery, ActivityQuery, AggregationQuery, ControllerQuery, Page, Pagination,
+    PlayerAchievementQuery, Query, TokenBalanceQuery, TokenQuery, TokenTransferQuery,
+    TransactionQuery,
+};
+
+/// Queries whose results come in pages.
+pub trait Paged: Clone {
+    fn pagination(&mut self) -> &mut Pagination;
+}
+
+impl Paged for Query {
+    fn pagination(&mut self) -> &mut Pagination {
+        &mut self.pagination
+    }
+}
+
+impl Paged for TokenQuery {
+    fn pagination(&mut self) -> &mut Pagination {
+        &mut self.pagination
+    }
+}
+
+impl Paged for TokenBalanceQuery {
+    fn pagination(&mut self) -> &mut Pagination {
+        &mut self.pagination
+    }
+}
+
+impl Paged for TokenTransferQuery {
+    fn pagination(&mut self) -> &mut Pagination {
+        &mut self.pagination
+    }
+}
+
+impl Paged for TransactionQuery {
+    fn pagination(&mut self) -> &mut Pagination {
+        &mut self.pagination
+    }
+}
+
+impl Paged for ControllerQuery {
+    fn pagination(&mut self) -> &mut Pagination {
+        &mut self.pagination
+    }
+}
+
+impl Paged for ActivityQuery {
+    fn pagination(&mut self) -> &mut Pagination {
+        &mut self.pagination
+    }
+}
+
+impl Paged for AchievementQuery {
+    fn pagination(&mut self) -> &mut Pagination {
+        &mut self.pagination
+    }
+}
+
+impl Paged for PlayerAchievementQuery {
+    fn pagination(&mut self) -> &mut Pagination {
+        &mut self.pagination
+    }
+}
+
+impl Paged for AggregationQuery {
+    fn pagination(&mut self) -> &mut Pagination {
+        &mut self.pagination
+    }
+}
+
+/// Walks the pages of a query by following their cursors.
+///
+/// `fetch` requests the page of a query, usually from the active endpoint. Pages are returned
+/// by [`Paginator::next_page`], or item by item by [`Paginator::next_item`], until a page comes
+/// without a next cursor. The cursors of the pages returned so far are kept, so that
+/// [`Paginator::previous_page`] walks back through them.
+///
+/// The paginator doesn't spawn anything: [`Paginator::prefetch`] returns a task fetching the
+/// next page, which the next call to [`Paginator::next_page`] waits for instead of fetching it
+/// again.
+pub struct Paginator<Q, T, F> {
+    query: Q,
+    fetch: F,
+    /// Cursor of each page seen so far, the first one being the cursor of the query
+    cursors: Vec<Option<String>>,
+    /// Index of the last page returned
+    position: Option<usize>,
+    /// Number of pages, known once the last one was fetched
+    len: Option<usize>,
+    /// Items of the current page not returned by `next_item` yet
+    items: VecDeque<T>,
+    prefetched: Option<(usize, oneshot::Receiver<Result<Page<T>>>)>,
+}
+
+impl<Q, T, F, Fut> Paginator<Q, T, F>
+where
+    Q: Paged,
+    F: Fn(Q) -> Fut,
+    Fut: Future<Output = Result<Page<T>>>,
+{
+    pub fn new(mut query: Q, fetch: F) -> Self {
+        let cursor = query.pagination().cursor.clone();
+        Self {
+            query,
+            fetch,
+            cursors: vec![cursor],
+            position: None,
+            len: None,
+            items: VecDeque::new(),
+            prefetched: None,
+        }
+    }
+
+    /// Page after the last one returned, `None` once every page was returned. Items of the
+    /// current page not returned by [`Paginator::next_item`] are skipped.
+    pub async fn next_page(&mut self) -> Result<Option<Page<T>>> {
+        let index = self.position.map_or(0, |position| position + 1);
+        if self.len.is_some_and(|len| index >= len) {
+            return Ok(None);
+        }
+
+        let page = self.fetch_page(index).await?;
+        self.position = Some(index);
+        self.items.clear();
+        Ok(Some(page))
+    }
+
+    /// Page before the last one returned, `None` when on the first page. The page is fetched
+    /// again, so it reflects the current state of the data.
+    pub async fn previous_page(&mut self) -> Result<Option<Page<T>>> {
+        let Some(index) = self.position.and_then(|position| position.checked_sub(1)) else {
+            return Ok(None);
+        };
+
+        let page = self.fetch_page(index).await?;
+        self.position = Some(index);
+        self.items.clear();
+        Ok(Some(page))
+    }
+
+    /// Next item of the current page, moving to the next page once it is exhausted. `None`
+    /// once every item was returned.
+    pub async fn next_item(&mut self) -> Result<Option<T>> {
+        loop {
+            if let Some(item) = self.items.pop_front() {
+                return Ok(Some(item));
+            }
+            match self.next_page().await? {
+                Some(page) => self.items.extend(page.items),
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Starts fetching the page after the current one, returning the task to spawn. `None`
+    /// when there is no next page or it is already being fetched.
+    pub fn prefetch(&mut self) -> Option<impl Future<Output = ()>> {
+        let index = self.position.map_or(0, |position| position + 1);
+        if self.len.is_some_and(|len| index >= len)
+            || index >= self.cursors.len()
+            || self.prefetched.as_ref().is_some_and(|(prefetched, _)| *prefetched == index)
+        {
+            return None;
+        }
+
+        let (tx, rx) = oneshot::channel();
+        let page = (self.fetch)(self.query_at(index));
+        self.prefetched = Some((index, rx));
+        Some(async move {
+            let _ = tx.send(page.await);
+        })
+    }
+
+    fn query_at(&self, index: usize) -> Q {
+        let mut query = self.query.clone();
+        query.pagination().cursor = self.cursors[index].clone();
+        query
+    }
+
+    async fn fetch_page(&mut self, index: usize) -> Result<Page<T>> {
+        let prefetched = match self.prefetched.take() {
+            Some((prefetched, rx)) if prefetched == index => rx.await.ok(),
+            _ => None,
+        };
+        let page = match prefetched {
+            Some(page) => page?,
+            None => (self.fetch)(self.query_at(index)).await?,
+        };
+
+        match page.next_cursor.as_ref().filter(|cursor| !cursor.is_empty()) {
+            Some(cursor) if self.cursors.len() == index + 1 => {
+                self.cursors.push(Some(cursor.clone()))
+            }
+            Some(_) => {}
+            None => self.len = Some(index + 1),
+        }
+        Ok(page)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use futures::executor::block_on;
+    use torii_proto::PaginationDirection;
+
+    use super::*;
+
+    fn query() -> TokenQuery {
+        TokenQuery {
+            contract_addresses: vec![],
+            token_ids: vec![],
+            attribute_filters: vec![],
+            pagination: Pagination {
+                cursor: None,
+                limit: Some(2),
+                direction: PaginationDirection::Forward,
+                order_by: vec![],
+            },
+        }
+    }
+
+    /// Pages of two numbers out of five, the cursor being the first number of the page.
+    fn fetch(
+        requests: Arc<Mutex<Vec<Option<String>>>>,
+    ) -> impl Fn(TokenQuery) -> futures::future::Ready<Result<Page<u32>>> {
+        move |mut query| {
+            let cursor = query.pagination().cursor.clone();
+            requests.lock().unwrap().push(cursor.clone());
+            let start = cursor.map_or(0, |cursor| cursor.parse().unwrap());
+            let items = (start..5).take(2).collect::<Vec<_>>();
+            let next_cursor = (start + 2 < 5).then(|| (start + 2).to_string());
+            futures::future::ready(Ok(Page { items, next_cursor }))
+        }
+    }
+
+    #[test]
+    fn walks_pages_both_ways() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let mut paginator = Paginator::new(query(), fetch(requests.clone()));
+
+        block_on(async {
+            let mut items = Vec::new();
+            while let Some(item) = paginator.next_item().await.unwrap() {
+                items.push(item);
+            }
+            assert_eq!(items, [0, 1, 2, 3, 4]);
+            assert!(paginator.next_page().await.unwrap().is_none());
+
+            let page = paginator.previous_page().await.unwrap().unwrap();
+            assert_eq!(page.items, [2, 3]);
+            let page = paginator.previous_page().await.unwrap().unwrap();
+            assert_eq!(page.items, [0, 1]);
+            assert!(paginator.previous_page().await.unwrap().is_none());
+
+            paginator.prefetch().unwrap().await;
+            assert!(paginator.prefetch().is_none());
+            let page = paginator.next_page().await.unwrap().unwrap();
+            assert_eq!(page.items, [2, 3]);
+        });
+
+        let requests = requests.lock().unwrap();
+        let cursors = requests.iter().map(|cursor| cursor.as_deref()).collect::<Vec<_>>();
+        assert_eq!(cursors, [None, Some("2"), Some("4"), Some("2"), None, Some("2")]);
+    }
+}
@@ -0,0 +1,214 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use starknet_crypto::Felt;
+use torii_client::Client;
+use torii_proto::schema::Entity;
+use torii_proto::{Clause, OrderBy, OrderDirection, Pagination, PaginationDirection, Query};
+
+use crate::config::PollingConfig;
+use crate::dedup::Deduplicator;
+use crate::subscription::Resume;
+
+/// Field entities are ordered by, most recently updated first.
+const UPDATED_AT: &str = "updated_at";
+
+/// What a [`Poller`] queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntitySource {
+    Entities,
+    EventMessages,
+}
+
+/// Fetches the entity or event message updates a subscription hasn't seen yet.
+///
+/// Pages are requested most recently updated first, so each poll pages through results until
+/// it reaches entities older than the latest update seen so far. The first poll delivers the
+/// updates made since the poller was created, so that a subscription falling back to polling
+/// right away doesn't miss them.
+///
+/// Used to catch up after a reconnection and to emulate a subscription by polling when streams
+/// are unavailable.
+#[derive(Debug)]
+pub struct Poller {
+    source: EntitySource,
+    clause: Option<Clause>,
+    world_addresses: Vec<Felt>,
+    page_size: u32,
+    watermark: DateTime<Utc>,
+    /// Cleared while updates between the watermark and the stream are still to be fetched
+    caught_up: bool,
+    dedup: Deduplicator,
+}
+
+impl Poller {
+    pub fn new(source: EntitySource, clause: Option<Clause>, world_addresses: Vec<Felt>) -> Self {
+        Self {
+            source,
+            clause,
+            world_addresses,
+            page_size: PollingConfig::default().page_size,
+            watermark: Utc::now(),
+            caught_up: true,
+            dedup: Deduplicator::default(),
+        }
+    }
+
+    /// Sets the maximum number of entities fetched per request.
+    pub fn with_page_size(mut self, page_size: u32) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    pub fn source(&self) -> EntitySource {
+        self.source
+    }
+
+    /// Changes what is polled, e.g. once the subscription was updated. Updates keep being
+    /// fetched from the same watermark.
+    pub fn set_filter(&mut self, clause: Option<Clause>, world_addresses: Vec<Felt>) {
+        self.clause = clause;
+        self.world_addresses = world_addresses;
+    }
+
+    /// Fetches the entities updated since the previous poll, oldest update first.
+    pub async fn poll(&mut self, client: &Client) -> Result<Vec<Entity>> {
+        let mut updates = Vec::new();
+        let mut cursor = None;
+
+        loop {
+            let page = self.fetch(client, cursor).await?;
+            let caught_up = self.collect(page.items, &mut updates);
+
+            match page.next_cursor {
+                Some(next) if !caught_up => cursor = Some(next),
+                _ => break,
+            }
+        }
+
+        if let Some(latest) = updates.iter().map(|e| e.updated_at).max() {
+            self.advance(latest);
+        }
+        self.caught_up = true;
+
+        updates.reverse();
+        updates.retain(|entity| self.dedup.observe_entity(entity));
+
+        Ok(updates)
+    }
+
+    /// Moves the entities of a page updated since the watermark to `updates`, returning whether
+    /// the page reached older ones.
+    fn collect(&self, page: Vec<Entity>, updates: &mut Vec<Entity>) -> bool {
+        for entity in page {
+            // Entities updated within the same second as the watermark are fetched again
+            // and filtered out by the deduplicator if their state didn't change.
+            if entity.updated_at < self.watermark {
+                return true;
+            }
+            updates.push(entity);
+        }
+        false
+    }
+
+    fn advance(&mut self, updated_at: DateTime<Utc>) {
+        self.watermark = self.watermark.max(updated_at);
+    }
+
+    /// Query of a page, ordered by update time so that polling can stop at the watermark.
+    fn query(&self, cursor: Option<String>) -> Query {
+        Query {
+            world_addresses: self.world_addresses.clone(),
+            pagination: Pagination {
+                cursor,
+                limit: Some(self.page_size),
+                direction: PaginationDirection::Forward,
+                order_by: vec![OrderBy {
+                    field: UPDATED_AT.to_string(),
+                    direction: OrderDirection::Desc,
+                }],
+            },
+            clause: self.clause.clone(),
+            no_hashed_keys: false,
+            models: vec![],
+            historical: false,
+        }
+    }
+
+    async fn fetch(
+        &self,
+        client: &Client,
+        cursor: Option<String>,
+    ) -> Result<torii_proto::Page<Entity>> {
+        let query = self.query(cursor);
+        let page = match self.source {
+            EntitySource::Entities => client.entities(query).await?,
+            EntitySource::EventMessages => client.event_messages(query).await?,
+        };
+
+        Ok(page)
+    }
+}
+
+impl Resume<Entity> for Poller {
+    fn observe(&mut self, entity: &Entity) -> bool {
+        if self.caught_up {
+            self.advance(entity.updated_at);
+        }
+        self.dedup.observe_entity(entity)
+    }
+
+    /// Fetches the updates missed since the watermark, stopping at the first older entity of
+    /// pages ordered by latest update.
+    async fn resume(&mut self, client: &Client) -> Vec<Entity> {
+        match self.poll(client).await {
+            Ok(entities) => entities,
+            Err(_) => {
+                // Keep the watermark so that the next reconnection fetches the missed range
+                self.caught_up = false;
+                Vec::new()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_pages_by_latest_update() {
+        let poller = Poller::new(EntitySource::Entities, None, vec![]).with_page_size(50);
+        let query = poller.query(Some("cursor".to_string()));
+
+        assert_eq!(query.pagination.limit, Some(50));
+        assert_eq!(query.pagination.cursor.as_deref(), Some("cursor"));
+        assert_eq!(query.pagination.order_by.len(), 1);
+        assert_eq!(query.pagination.order_by[0].field, UPDATED_AT);
+        assert!(matches!(query.pagination.order_by[0].direction, OrderDirection::Desc));
+    }
+
+    #[test]
+    fn first_poll_keeps_updates_since_creation() {
+        let poller = Poller::new(EntitySource::Entities, None, vec![]);
+        let entity = |hashed_keys, updated_at| Entity {
+            world_address: Felt::ONE,
+            hashed_keys,
+            models: vec![],
+            created_at: updated_at,
+            updated_at,
+            executed_at: updated_at,
+        };
+        let later = poller.watermark + chrono::Duration::seconds(1);
+        let earlier = poller.watermark - chrono::Duration::hours(1);
+
+        let mut updates = Vec::new();
+        let page = vec![entity(Felt::ONE, later), entity(Felt::TWO, earlier)];
+        assert!(poller.collect(page, &mut updates));
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].hashed_keys, Felt::ONE);
+
+        let mut updates = Vec::new();
+        assert!(!poller.collect(vec![entity(Felt::TWO, later)], &mut updates));
+        assert_eq!(updates.len(), 1);
+    }
+}
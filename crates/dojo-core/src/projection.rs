@@ -0,0 +1,312 @@
+use std::collections::BTreeMap;
+
+use dojo_types::schema::{Struct, Ty};
+use torii_proto::schema::Entity;
+use torii_proto::{Page, Query};
+
+use crate::change::EntityChange;
+use crate::diff::FieldChange;
+
+/// Members kept by a projection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Fields {
+    All,
+    Members(BTreeMap<String, Fields>),
+}
+
+impl Fields {
+    fn insert(&mut self, path: &[&str]) {
+        let Some((member, rest)) = path.split_first() else {
+            *self = Fields::All;
+            return;
+        };
+        let Fields::Members(members) = self else {
+            return;
+        };
+        let fields =
+            members.entry(member.to_string()).or_insert_with(|| Fields::Members(BTreeMap::new()));
+        fields.insert(rest);
+    }
+
+    /// Fields kept of the member at the path, `None` if it isn't projected.
+    fn at(&self, path: &[&str]) -> Option<&Fields> {
+        let Fields::Members(members) = self else {
+            return Some(self);
+        };
+        match path.split_first() {
+            Some((member, rest)) => members.get(*member)?.at(rest),
+            None => Some(self),
+        }
+    }
+
+    fn retain(&self, model: &mut Struct) {
+        let Fields::Members(members) = self else {
+            return;
+        };
+        model.children.retain_mut(|member| match members.get(&member.name) {
+            Some(fields) => {
+                if let Ty::Struct(inner) = &mut member.ty {
+                    fields.retain(inner);
+                }
+                true
+            }
+            None => false,
+        });
+    }
+}
+
+/// Models, and optionally members of those models, to keep from entities.
+///
+/// A projection is built from selectors: a model tag like `ns-Position` keeps the whole model,
+/// while `ns-Stats.health` or `ns-Stats.inventory.gold` only keep the given member, walking
+/// through nested structs. Members that aren't structs are kept whole, whatever comes after
+/// them in the path. Like the models of a query, unknown models and members match nothing.
+///
+/// Torii filters entities by model, so [`Projection::apply_query`] narrows the models of a
+/// query, and members are dropped client-side by [`Projection::apply`]. Subscriptions project
+/// what they deliver last, see [`Project`], so that diffs, predictions and classifications
+/// are computed from whole updates.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Projection {
+    models: BTreeMap<String, Fields>,
+}
+
+impl Projection {
+    pub fn new<S: AsRef<str>>(selectors: &[S]) -> Self {
+        let mut projection = Self::default();
+        for selector in selectors {
+            let path = selector
+                .as_ref()
+                .split('.')
+                .map(str::trim)
+                .filter(|segment| !segment.is_empty())
+                .collect::<Vec<_>>();
+            let Some((model, members)) = path.split_first() else {
+                continue;
+            };
+
+            projection
+                .models
+                .entry(model.to_string())
+                .or_insert_with(|| Fields::Members(BTreeMap::new()))
+                .insert(members);
+        }
+        projection
+    }
+
+    /// Whether the projection keeps nothing, i.e. it was built without selectors.
+    pub fn is_empty(&self) -> bool {
+        self.models.is_empty()
+    }
+
+    /// Tags of the projected models.
+    pub fn models(&self) -> Vec<String> {
+        self.models.keys().cloned().collect()
+    }
+
+    /// Drops the models and members of an entity that aren't projected. Returns false if the
+    /// entity had models but none of them is projected, i.e. the entity can be skipped.
+    pub fn apply(&self, entity: &mut Entity) -> bool {
+        if entity.models.is_empty() {
+            return true;
+        }
+
+        entity.models.retain_mut(|model| match self.models.get(&model.name) {
+            Some(fields) => {
+                fields.retain(model);
+                true
+            }
+            None => false,
+        });
+        !entity.models.is_empty()
+    }
+
+    /// Drops the members of a model that aren't projected. Returns false if the model isn't.
+    pub fn apply_model(&self, model: &mut Struct) -> bool {
+        let Some(fields) = self.models.get(&model.name) else {
+            return false;
+        };
+        fields.retain(model);
+        true
+    }
+
+    /// Returns false if the change is about a member that isn't projected, and otherwise
+    /// drops the members of its values that aren't. Changes within arrays are kept whole, like
+    /// the arrays themselves.
+    pub fn apply_change(&self, change: &mut FieldChange) -> bool {
+        let Some(fields) = self.models.get(&change.model) else {
+            return false;
+        };
+
+        let mut path = Vec::new();
+        let mut indexed = false;
+        for segment in change.path.split('.').filter(|segment| !segment.is_empty()) {
+            let (member, index) = segment.split_once('[').unwrap_or((segment, ""));
+            path.push(member);
+            if !index.is_empty() {
+                indexed = true;
+                break;
+            }
+        }
+
+        let Some(fields) = fields.at(&path) else {
+            return false;
+        };
+        if !indexed {
+            for value in [&mut change.old, &mut change.new].into_iter().flatten() {
+                if let Ty::Struct(value) = value {
+                    fields.retain(value);
+                }
+            }
+        }
+        true
+    }
+
+    /// Applies the projection to the entities of a page, skipping the ones left without models.
+    pub fn apply_page(&self, page: &mut Page<Entity>) {
+        page.items.retain_mut(|entity| self.apply(entity));
+    }
+
+    /// Only requests the projected models, unless the query already restricts them.
+    pub fn apply_query(&self, query: &mut Query) {
+        if query.models.is_empty() {
+            query.models = self.models();
+        }
+    }
+}
+
+/// Updates a subscription delivers, projected just before being handed to the callback.
+pub trait Project {
+    /// Drops what the projection doesn't keep. Returns false if nothing is left to deliver.
+    fn project(&mut self, projection: &Projection) -> bool;
+}
+
+impl Project for Entity {
+    fn project(&mut self, projection: &Projection) -> bool {
+        projection.apply(self)
+    }
+}
+
+impl Project for EntityChange {
+    fn project(&mut self, projection: &Projection) -> bool {
+        match self {
+            EntityChange::Created(entity)
+            | EntityChange::Updated(entity)
+            | EntityChange::Deleted(entity) => projection.apply(entity),
+        }
+    }
+}
+
+/// A model handed to a dispatcher handler.
+impl Project for Struct {
+    fn project(&mut self, projection: &Projection) -> bool {
+        projection.apply_model(self)
+    }
+}
+
+impl Project for FieldChange {
+    fn project(&mut self, projection: &Projection) -> bool {
+        projection.apply_change(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_types::primitive::Primitive;
+    use dojo_types::schema::Member;
+    use starknet_crypto::Felt;
+
+    use super::*;
+    use crate::diff::ChangeKind;
+
+    fn member(name: &str, ty: Ty) -> Member {
+        Member { name: name.to_string(), ty, key: false }
+    }
+
+    fn model(name: &str, children: Vec<Member>) -> Struct {
+        Struct { name: name.to_string(), children }
+    }
+
+    fn player() -> Entity {
+        let u32 = |value| Ty::Primitive(Primitive::U32(Some(value)));
+        Entity {
+            world_address: Felt::ONE,
+            hashed_keys: Felt::TWO,
+            models: vec![
+                model("ns-Position", vec![member("x", u32(1)), member("y", u32(2))]),
+                model(
+                    "ns-Stats",
+                    vec![
+                        member("health", u32(3)),
+                        member(
+                            "inventory",
+                            Ty::Struct(model(
+                                "Inventory",
+                                vec![member("gold", u32(4)), member("wood", u32(5))],
+                            )),
+                        ),
+                    ],
+                ),
+            ],
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            executed_at: Default::default(),
+        }
+    }
+
+    fn names(model: &Struct) -> Vec<&str> {
+        model.children.iter().map(|member| member.name.as_str()).collect()
+    }
+
+    #[test]
+    fn keeps_projected_members() {
+        let projection = Projection::new(&["ns-Stats.inventory.gold", "ns-Stats.health"]);
+        let mut entity = player();
+        assert!(projection.apply(&mut entity));
+
+        assert_eq!(entity.models.len(), 1);
+        assert_eq!(names(&entity.models[0]), ["health", "inventory"]);
+        let Ty::Struct(inventory) = &entity.models[0].children[1].ty else { panic!() };
+        assert_eq!(names(inventory), ["gold"]);
+
+        // A whole model wins over its members, whatever the order
+        let projection = Projection::new(&["ns-Position.x", "ns-Position"]);
+        let mut entity = player();
+        assert!(projection.apply(&mut entity));
+        assert_eq!(names(&entity.models[0]), ["x", "y"]);
+
+        let projection = Projection::new(&["ns-Position..y "]);
+        assert!(projection.apply(&mut entity));
+        assert_eq!(names(&entity.models[0]), ["y"]);
+
+        assert!(!Projection::new(&["ns-Moves"]).apply(&mut entity));
+        assert!(Projection::new(&["", "."]).is_empty());
+    }
+
+    #[test]
+    fn keeps_changes_of_projected_members() {
+        let projection = Projection::new(&["ns-Stats.inventory.gold", "ns-Position"]);
+        let change = |model: &str, path: &str, new: Option<Ty>| FieldChange {
+            hashed_keys: Felt::TWO,
+            kind: ChangeKind::MemberUpdated,
+            model: model.to_string(),
+            path: path.to_string(),
+            old: None,
+            new,
+        };
+
+        assert!(change("ns-Position", "x", None).project(&projection));
+        assert!(change("ns-Stats", "inventory.gold", None).project(&projection));
+        assert!(!change("ns-Stats", "inventory.wood", None).project(&projection));
+        assert!(!change("ns-Stats", "health", None).project(&projection));
+        assert!(!change("ns-Moves", "", None).project(&projection));
+
+        let stats = player().models.remove(1);
+        let mut created = change("ns-Stats", "", Some(Ty::Struct(stats)));
+        assert!(created.project(&projection));
+        let Some(Ty::Struct(stats)) = &created.new else { panic!() };
+        assert_eq!(names(stats), ["inventory"]);
+        let Ty::Struct(inventory) = &stats.children[0].ty else { panic!() };
+        assert_eq!(names(inventory), ["gold"]);
+    }
+}
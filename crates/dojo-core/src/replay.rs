@@ -0,0 +1,415 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt::Display;
+use std::future::Future;
+use std::io::Write;
+use std::pin::pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use futures::FutureExt;
+use instant::Instant;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use stream_cancel::Tripwire;
+
+use crate::cache::CacheKey;
+use crate::status::{SubscriptionState, SubscriptionStatus};
+use crate::subscription::CANCELLED;
+
+/// Version of the recording format, bumped when it changes incompatibly. Version 2 identifies
+/// requests by the canonical JSON of their queries rather than their debug output.
+pub const RECORDING_VERSION: u32 = 2;
+
+/// Line of a recording. Times are in milliseconds since the session started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Entry {
+    /// First line, `started_at` being a Unix timestamp in milliseconds
+    Session { version: u32, started_at: u64 },
+    /// Response of a request, or the error it failed with
+    Response { at: u64, request: String, result: std::result::Result<Value, String> },
+    /// Subscription opened by the session, numbered in the order they were opened
+    Subscribed { at: u64, subscription: u64 },
+    /// Id assigned by Torii to a stream of a subscription
+    Connected { at: u64, subscription: u64, id: u64 },
+    /// Item delivered by a subscription
+    Message { at: u64, subscription: u64, item: Value },
+}
+
+/// How a client session talks to Torii, see [`Connection::exchange`].
+///
+/// [`Connection::exchange`]: crate::connection::Connection::exchange
+#[derive(Clone)]
+pub enum Session {
+    /// Requests and subscriptions go to Torii and are recorded
+    Record(Arc<Recorder>),
+    /// Requests and subscriptions are served from a recording, without a network
+    Replay(Arc<Replay>),
+}
+
+/// Records the responses and subscription messages of a client session.
+///
+/// A recording holds a JSON document per line: a header with the time the session started,
+/// then every response and subscription message with the time it came in. Requests are
+/// identified by their [`CacheKey`], which doesn't depend on how the query types are laid out
+/// in memory, subscriptions by the order they were opened in. Recordings go to a file, or to a
+/// [`Buffer`] where there is none, to be played back with [`Replay::parse`]. Failing to write
+/// is ignored, so that recording never gets in the way of the session.
+pub struct Recorder {
+    start: Instant,
+    subscriptions: AtomicU64,
+    sink: Mutex<Box<dyn Write + Send>>,
+}
+
+impl Recorder {
+    /// Records a session started at `started_at`, a Unix timestamp in milliseconds, to `sink`.
+    pub fn new(sink: impl Write + Send + 'static, started_at: u64) -> Self {
+        let recorder = Self {
+            start: Instant::now(),
+            subscriptions: AtomicU64::new(0),
+            sink: Mutex::new(Box::new(sink)),
+        };
+        recorder.write(&Entry::Session { version: RECORDING_VERSION, started_at });
+        recorder
+    }
+
+    /// Records a session starting now to a file, replacing it if it exists.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn create(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = std::fs::File::create(path)
+            .map_err(|e| anyhow!("failed to create recording {}: {e}", path.display()))?;
+        let started_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        Ok(Self::new(std::io::LineWriter::new(file), started_at))
+    }
+
+    /// Records the response of a request, or the error it failed with.
+    pub fn response<T: Serialize, E: Display>(&self, request: &CacheKey, result: &Result<T, E>) {
+        let result = match result {
+            Ok(response) => serde_json::to_value(response).map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        let request = request.as_str().to_string();
+        self.write(&Entry::Response { at: self.elapsed(), request, result });
+    }
+
+    /// Numbers a new subscription, whose stream is then recorded with [`Recorder::connected`]
+    /// and [`Recorder::message`].
+    pub fn subscribe(&self) -> u64 {
+        let subscription = self.subscriptions.fetch_add(1, Ordering::SeqCst);
+        self.write(&Entry::Subscribed { at: self.elapsed(), subscription });
+        subscription
+    }
+
+    pub fn connected(&self, subscription: u64, id: u64) {
+        self.write(&Entry::Connected { at: self.elapsed(), subscription, id });
+    }
+
+    pub fn message<T: Serialize>(&self, subscription: u64, item: &T) {
+        if let Ok(item) = serde_json::to_value(item) {
+            self.write(&Entry::Message { at: self.elapsed(), subscription, item });
+        }
+    }
+
+    fn elapsed(&self) -> u64 {
+        self.start.elapsed().as_millis() as u64
+    }
+
+    fn write(&self, entry: &Entry) {
+        let Ok(mut line) = serde_json::to_vec(entry) else {
+            return;
+        };
+        line.push(b'\n');
+        let _ = self.sink.lock().unwrap().write_all(&line);
+    }
+}
+
+/// In-memory sink of a recording, for platforms without a filesystem. Clones share their
+/// contents, which can be read while a recorder writes to one of them.
+#[derive(Clone, Default)]
+pub struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Buffer {
+    /// Recording written so far.
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Wraps the callbacks of a subscription so that its stream is recorded, when there is a
+/// recorder.
+pub(crate) fn recorded<T: Serialize>(
+    recorder: Option<&Arc<Recorder>>,
+    mut on_connected: impl FnMut(u64),
+    mut on_item: impl FnMut(T),
+) -> (impl FnMut(u64), impl FnMut(T)) {
+    let recorder = recorder.map(|recorder| (recorder.subscribe(), recorder.clone()));
+    let connected = recorder.clone();
+    (
+        move |id| {
+            if let Some((subscription, recorder)) = &connected {
+                recorder.connected(*subscription, id);
+            }
+            on_connected(id);
+        },
+        move |item| {
+            if let Some((subscription, recorder)) = &recorder {
+                recorder.message(*subscription, &item);
+            }
+            on_item(item);
+        },
+    )
+}
+
+/// Event of a recorded subscription stream.
+enum Event {
+    Connected(u64),
+    Message(Value),
+}
+
+/// Recorded stream of a subscription.
+struct Stream {
+    opened_at: u64,
+    events: Vec<(u64, Event)>,
+}
+
+/// Plays a recording back in place of Torii, see [`Recorder`].
+///
+/// Requests get the recorded responses of the same request in the order they were recorded,
+/// the last one being repeated once they run out. Subscriptions get the stream of the
+/// subscription opened at the same position in the recorded session, with the delays between
+/// its messages divided by `speed`. Replays are deterministic as long as the session makes
+/// the same requests and opens its subscriptions in the same order as the recorded one.
+pub struct Replay {
+    speed: f64,
+    responses: Mutex<HashMap<String, VecDeque<std::result::Result<Value, String>>>>,
+    streams: Mutex<VecDeque<Stream>>,
+}
+
+impl Replay {
+    /// Parses a recording to play back at `speed` times the original speed, infinity playing
+    /// it without delays.
+    pub fn parse(recording: &str, speed: f64) -> Result<Self> {
+        if speed.is_nan() || speed <= 0.0 {
+            return Err(anyhow!("replay speed must be greater than zero"));
+        }
+
+        let mut lines = recording.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+        match lines.next().map(|(_, line)| serde_json::from_str(line)) {
+            Some(Ok(Entry::Session { version: RECORDING_VERSION, .. })) => {}
+            Some(Ok(Entry::Session { version, .. })) => {
+                return Err(anyhow!("unsupported recording version {version}"));
+            }
+            _ => return Err(anyhow!("invalid recording: missing session header")),
+        }
+
+        let mut responses = HashMap::<_, VecDeque<_>>::new();
+        let mut streams = BTreeMap::new();
+        for (index, line) in lines {
+            let entry = serde_json::from_str(line)
+                .map_err(|e| anyhow!("invalid recording at line {}: {e}", index + 1))?;
+            let (subscription, event) = match entry {
+                Entry::Session { .. } => {
+                    return Err(anyhow!("invalid recording at line {}: nested session", index + 1));
+                }
+                Entry::Response { request, result, .. } => {
+                    responses.entry(request).or_default().push_back(result);
+                    continue;
+                }
+                Entry::Subscribed { at, subscription } => {
+                    streams.insert(subscription, Stream { opened_at: at, events: Vec::new() });
+                    continue;
+                }
+                Entry::Connected { at, subscription, id } => {
+                    (subscription, (at, Event::Connected(id)))
+                }
+                Entry::Message { at, subscription, item } => {
+                    (subscription, (at, Event::Message(item)))
+                }
+            };
+            if let Some(stream) = streams.get_mut(&subscription) {
+                stream.events.push(event);
+            }
+        }
+
+        Ok(Self {
+            speed,
+            responses: Mutex::new(responses),
+            streams: Mutex::new(streams.into_values().collect()),
+        })
+    }
+
+    /// Reads a recording from a file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open(path: impl AsRef<std::path::Path>, speed: f64) -> Result<Self> {
+        let path = path.as_ref();
+        let recording = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("failed to read recording {}: {e}", path.display()))?;
+        Self::parse(&recording, speed)
+    }
+
+    /// Recorded response of a request, or the error it failed with.
+    pub fn response<T: DeserializeOwned>(&self, request: &CacheKey) -> Result<T> {
+        let result = {
+            let mut responses = self.responses.lock().unwrap();
+            let results = responses
+                .get_mut(request.as_str())
+                .ok_or_else(|| anyhow!("no recorded response for {}", request.as_str()))?;
+            match results.len() {
+                1 => results[0].clone(),
+                _ => results.pop_front().unwrap(),
+            }
+        };
+
+        serde_json::from_value(result.map_err(|e| anyhow!(e))?)
+            .map_err(|e| anyhow!("recorded response doesn't match {}: {e}", request.as_str()))
+    }
+
+    /// Plays the stream of the next recorded subscription, see
+    /// [`drive`](crate::subscription::drive). The subscription stays live once the stream is
+    /// over, until it is cancelled.
+    pub(crate) async fn drive<T, Z, ZF>(
+        &self,
+        sleep: &Z,
+        tripwire: &Tripwire,
+        status: &SubscriptionStatus,
+        on_connected: &mut impl FnMut(u64),
+        on_item: &mut impl FnMut(T),
+    ) where
+        T: DeserializeOwned,
+        Z: Fn(Duration) -> ZF,
+        ZF: Future<Output = ()>,
+    {
+        let stream = self.streams.lock().unwrap().pop_front();
+        let Some(stream) = stream else {
+            let reason = "no recorded subscription left".to_string();
+            status.set(SubscriptionState::Closed { reason });
+            return;
+        };
+
+        let start = Instant::now();
+        for (at, event) in stream.events {
+            let delay = self.delay(at.saturating_sub(stream.opened_at));
+            if wait(sleep, tripwire, delay.saturating_sub(start.elapsed())).await {
+                status.set(SubscriptionState::Closed { reason: CANCELLED.to_string() });
+                return;
+            }
+
+            match event {
+                Event::Connected(id) => {
+                    on_connected(id);
+                    status.set(SubscriptionState::Live);
+                }
+                Event::Message(item) => match serde_json::from_value(item) {
+                    Ok(item) => on_item(item),
+                    Err(e) => {
+                        let reason =
+                            format!("recorded message doesn't match the subscription: {e}");
+                        status.set(SubscriptionState::Closed { reason });
+                        return;
+                    }
+                },
+            }
+        }
+
+        if !tripwire.clone().await {
+            futures::future::pending::<()>().await;
+        }
+        status.set(SubscriptionState::Closed { reason: CANCELLED.to_string() });
+    }
+
+    fn delay(&self, millis: u64) -> Duration {
+        let secs = Duration::from_millis(millis).as_secs_f64() / self.speed;
+        Duration::try_from_secs_f64(secs).unwrap_or(Duration::MAX)
+    }
+}
+
+/// Waits for `delay` unless the subscription is cancelled first. Returns whether it was.
+async fn wait<Z, ZF>(sleep: &Z, tripwire: &Tripwire, delay: Duration) -> bool
+where
+    Z: Fn(Duration) -> ZF,
+    ZF: Future<Output = ()>,
+{
+    if delay.is_zero() {
+        return tripwire.clone().now_or_never().unwrap_or_default();
+    }
+
+    let mut delay = pin!(sleep(delay).fuse());
+    let mut cancelled = pin!(tripwire.clone().fuse());
+    futures::select! {
+        _ = delay => return false,
+        cancelled = cancelled => if cancelled { return true },
+    }
+    // The trigger was disabled, the subscription can't be cancelled anymore
+    delay.await;
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use stream_cancel::Tripwire;
+
+    use super::*;
+
+    #[test]
+    fn replays_recorded_session() {
+        let buffer = Buffer::default();
+        let recorder = Arc::new(Recorder::new(buffer.clone(), 1_700_000_000_000));
+        let first = CacheKey::new("tokens", &1);
+        recorder.response(&first, &Ok::<_, String>(vec![1, 2]));
+        recorder.response(&first, &Ok::<_, String>(vec![3]));
+        recorder.response(&CacheKey::new("tokens", &2), &Err::<u32, _>("unavailable"));
+
+        let (mut on_connected, mut on_item) = recorded(Some(&recorder), |_| {}, |_: u32| {});
+        on_connected(7);
+        on_item(10);
+        on_item(20);
+
+        let recording = buffer.contents();
+        let replay = Replay::parse(&recording, f64::INFINITY).unwrap();
+        assert_eq!(replay.response::<Vec<u32>>(&first).unwrap(), [1, 2]);
+        assert_eq!(replay.response::<Vec<u32>>(&first).unwrap(), [3]);
+        // The last response is repeated once they run out
+        assert_eq!(replay.response::<Vec<u32>>(&first).unwrap(), [3]);
+        let error = replay.response::<u32>(&CacheKey::new("tokens", &2)).unwrap_err();
+        assert_eq!(error.to_string(), "unavailable");
+        assert!(replay.response::<u32>(&CacheKey::new("tokens", &3)).is_err());
+
+        let (trigger, tripwire) = Tripwire::new();
+        let status = SubscriptionStatus::new();
+        let (mut ids, mut items) = (Vec::new(), Vec::new());
+        let drive = replay.drive(
+            &|_: Duration| futures::future::ready(()),
+            &tripwire,
+            &status,
+            &mut |id| ids.push(id),
+            &mut |item: u32| items.push(item),
+        );
+        // The stream stays live once played, until it is cancelled
+        block_on(futures::future::join(drive, async move { drop(trigger) }));
+        assert_eq!((ids, items), (vec![7], vec![10, 20]));
+        assert_eq!(status.state(), SubscriptionState::Closed { reason: CANCELLED.to_string() });
+
+        assert!(Replay::parse("", 1.0).is_err());
+        assert!(Replay::parse(&recording, 0.0).is_err());
+        let outdated = r#"{"type":"session","version":1,"started_at":0}"#;
+        assert!(Replay::parse(outdated, 1.0).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::{BTreeSet, HashMap};
+
+use dojo_types::schema::{Member, Ty};
+use starknet_crypto::Felt;
+use torii_proto::{Model, World};
+
+/// Shape of a type, see [`MemberSchema`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeKind {
+    Primitive,
+    Struct,
+    Enum,
+    Tuple,
+    Array,
+    FixedSizeArray,
+    ByteArray,
+}
+
+impl From<&Ty> for TypeKind {
+    fn from(ty: &Ty) -> Self {
+        match ty {
+            Ty::Primitive(_) => TypeKind::Primitive,
+            Ty::Struct(_) => TypeKind::Struct,
+            Ty::Enum(_) => TypeKind::Enum,
+            Ty::Tuple(_) => TypeKind::Tuple,
+            Ty::Array(_) => TypeKind::Array,
+            Ty::FixedSizeArray(_) => TypeKind::FixedSizeArray,
+            Ty::ByteArray(_) => TypeKind::ByteArray,
+        }
+    }
+}
+
+/// Member of a model, with a description of its type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemberSchema {
+    pub name: String,
+    /// Cairo name of the type, e.g. `u32`, `Array<felt252>` or the name of a struct or enum
+    pub type_name: String,
+    pub kind: TypeKind,
+    /// Type of the member, without a value
+    pub ty: Ty,
+}
+
+impl From<&Member> for MemberSchema {
+    fn from(member: &Member) -> Self {
+        Self {
+            name: member.name.clone(),
+            type_name: member.ty.name(),
+            kind: TypeKind::from(&member.ty),
+            ty: member.ty.clone(),
+        }
+    }
+}
+
+/// Model registered in a world, along with the class declaring it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelSchema {
+    /// `namespace-name`
+    pub tag: String,
+    pub namespace: String,
+    pub name: String,
+    pub selector: Felt,
+    pub class_hash: Felt,
+    pub contract_address: Felt,
+    pub packed_size: u32,
+    pub unpacked_size: u32,
+    pub use_legacy_store: bool,
+    /// Whether this is an event rather than a model. Events are never stored onchain, so Torii
+    /// registers them without a packed or unpacked size, while models have at least one value.
+    pub is_event: bool,
+    /// Key members, in the order they make up the id of an entity
+    pub keys: Vec<MemberSchema>,
+    pub values: Vec<MemberSchema>,
+    pub schema: Ty,
+}
+
+impl From<&Model> for ModelSchema {
+    fn from(model: &Model) -> Self {
+        let members = match &model.schema {
+            Ty::Struct(schema) => schema.children.as_slice(),
+            _ => &[],
+        };
+        let (keys, values) = members.iter().partition::<Vec<_>, _>(|member| member.key);
+
+        Self {
+            tag: format!("{}-{}", model.namespace, model.name),
+            namespace: model.namespace.clone(),
+            name: model.name.clone(),
+            selector: model.selector,
+            class_hash: model.class_hash,
+            contract_address: model.contract_address,
+            packed_size: model.packed_size,
+            unpacked_size: model.unpacked_size,
+            use_legacy_store: model.use_legacy_store,
+            is_event: model.packed_size == 0 && model.unpacked_size == 0,
+            keys: keys.into_iter().map(MemberSchema::from).collect(),
+            values: values.into_iter().map(MemberSchema::from).collect(),
+            schema: model.schema.clone(),
+        }
+    }
+}
+
+/// Models of a world, for editors and inspectors.
+///
+/// Torii registers the events of a world like its models, so events are listed alongside
+/// models, with the class declaring them, and told apart by [`ModelSchema::is_event`]. Models
+/// are sorted by tag.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldSchema {
+    pub world_address: Felt,
+    models: Vec<ModelSchema>,
+    by_tag: HashMap<String, usize>,
+    by_selector: HashMap<Felt, usize>,
+}
+
+impl WorldSchema {
+    pub fn new(world: &World) -> Self {
+        let mut models = world.models.values().map(ModelSchema::from).collect::<Vec<_>>();
+        models.sort_by(|a, b| a.tag.cmp(&b.tag));
+
+        let by_tag = models.iter().enumerate().map(|(i, model)| (model.tag.clone(), i)).collect();
+        let by_selector = models.iter().enumerate().map(|(i, model)| (model.selector, i)).collect();
+        Self { world_address: world.world_address, models, by_tag, by_selector }
+    }
+
+    /// Namespaces having at least one model, sorted.
+    pub fn namespaces(&self) -> Vec<String> {
+        let namespaces = self.models.iter().map(|model| model.namespace.as_str());
+        namespaces.collect::<BTreeSet<_>>().into_iter().map(str::to_string).collect()
+    }
+
+    pub fn models(&self) -> &[ModelSchema] {
+        &self.models
+    }
+
+    /// Models of a namespace.
+    pub fn models_in(&self, namespace: &str) -> Vec<&ModelSchema> {
+        self.models.iter().filter(|model| model.namespace == namespace).collect()
+    }
+
+    /// Model by tag, e.g. `ns-Position`.
+    pub fn model(&self, tag: &str) -> Option<&ModelSchema> {
+        self.by_tag.get(tag).map(|&i| &self.models[i])
+    }
+
+    /// Model by selector, the Poseidon hash of its namespace and name.
+    pub fn model_by_selector(&self, selector: Felt) -> Option<&ModelSchema> {
+        self.by_selector.get(&selector).map(|&i| &self.models[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_types::naming::compute_selector_from_names;
+    use dojo_types::primitive::Primitive;
+    use dojo_types::schema::Struct;
+    use dojo_world::contracts::abigen::model::Layout;
+
+    use super::*;
+
+    fn model(namespace: &str, name: &str, children: Vec<Member>) -> Model {
+        let size = children.iter().filter(|member| !member.key).count() as u32;
+        Model {
+            world_address: Felt::ONE,
+            schema: Ty::Struct(Struct { name: name.to_string(), children }),
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+            selector: compute_selector_from_names(namespace, name),
+            packed_size: size,
+            unpacked_size: size,
+            class_hash: Felt::TWO,
+            contract_address: Felt::THREE,
+            layout: Layout::Fixed(vec![]),
+            use_legacy_store: false,
+        }
+    }
+
+    fn member(name: &str, ty: Ty, key: bool) -> Member {
+        Member { name: name.to_string(), ty, key }
+    }
+
+    #[test]
+    fn describes_world() {
+        let models = vec![
+            model(
+                "ns",
+                "Position",
+                vec![
+                    member("player", Ty::Primitive(Primitive::ContractAddress(None)), true),
+                    member("x", Ty::Primitive(Primitive::U32(None)), false),
+                    member("y", Ty::Primitive(Primitive::U32(None)), false),
+                ],
+            ),
+            model("ns", "Moves", vec![]),
+            model("other", "Moved", vec![member("path", Ty::Array(vec![]), false)]),
+        ];
+        let world = World {
+            world_address: Felt::ONE,
+            models: models.into_iter().map(|model| (model.selector, model)).collect(),
+        };
+        let schema = WorldSchema::new(&world);
+
+        assert_eq!(schema.namespaces(), ["ns", "other"]);
+        let tags = schema.models().iter().map(|model| model.tag.as_str()).collect::<Vec<_>>();
+        assert_eq!(tags, ["ns-Moves", "ns-Position", "other-Moved"]);
+        assert_eq!(schema.models_in("ns").len(), 2);
+
+        let position = schema.model("ns-Position").unwrap();
+        assert_eq!(position.keys.len(), 1);
+        assert_eq!(position.keys[0].kind, TypeKind::Primitive);
+        assert_eq!(position.values.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(), ["x", "y"]);
+        assert_eq!(position.values[0].type_name, "u32");
+        assert_eq!(position.class_hash, Felt::TWO);
+        assert!(!position.is_event);
+
+        let selector = compute_selector_from_names("other", "Moved");
+        assert_eq!(schema.model_by_selector(selector).unwrap().values[0].kind, TypeKind::Array);
+        assert!(schema.model("ns-Moves").unwrap().is_event);
+        assert!(schema.model("ns-Unknown").is_none());
+    }
+}
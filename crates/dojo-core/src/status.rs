@@ -0,0 +1,113 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::StreamExt;
+
+/// Connection state of a subscription.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum SubscriptionState {
+    /// Establishing the first stream
+    #[default]
+    Connecting,
+    /// Receiving updates, either streamed or polled
+    Live,
+    /// Waiting `delay` before the given (one based) reconnection attempt
+    Reconnecting { attempt: u32, delay: Duration },
+    /// Stopped for good
+    Closed { reason: String },
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    state: SubscriptionState,
+    watchers: Vec<UnboundedSender<SubscriptionState>>,
+}
+
+/// Shared view of a subscription's [`SubscriptionState`].
+///
+/// The subscription driver records transitions while the handle returned to the consumer reads
+/// the current state or watches for transitions.
+#[derive(Debug, Clone, Default)]
+pub struct SubscriptionStatus {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl SubscriptionStatus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current state.
+    pub fn state(&self) -> SubscriptionState {
+        self.inner.lock().unwrap().state.clone()
+    }
+
+    /// Records a transition and notifies watchers. Repeating the current state is a no-op.
+    pub fn set(&self, state: SubscriptionState) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.state == state {
+            return;
+        }
+
+        inner.watchers.retain(|watcher| watcher.unbounded_send(state.clone()).is_ok());
+        if matches!(state, SubscriptionState::Closed { .. }) {
+            inner.watchers.clear(); // Nothing follows a closed state, end the watch streams
+        }
+        inner.state = state;
+    }
+
+    /// Stream of states starting with the current one and followed by every transition. Ends
+    /// once the subscription is closed.
+    pub fn watch(&self) -> UnboundedReceiver<SubscriptionState> {
+        let (tx, rx) = unbounded();
+        let mut inner = self.inner.lock().unwrap();
+
+        let _ = tx.unbounded_send(inner.state.clone());
+        if !matches!(inner.state, SubscriptionState::Closed { .. }) {
+            inner.watchers.push(tx);
+        }
+
+        rx
+    }
+
+    /// Waits until the subscription is live. Returns false if it closes first.
+    pub async fn established(&self) -> bool {
+        let mut states = self.watch();
+        while let Some(state) = states.next().await {
+            match state {
+                SubscriptionState::Live => return true,
+                SubscriptionState::Closed { .. } => return false,
+                _ => {}
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+
+    #[test]
+    fn watch_starts_with_current_state_and_ends_when_closed() {
+        let status = SubscriptionStatus::new();
+        status.set(SubscriptionState::Live);
+
+        let states = status.watch();
+        status.set(SubscriptionState::Live);
+        status.set(SubscriptionState::Reconnecting { attempt: 1, delay: Duration::from_secs(1) });
+        status.set(SubscriptionState::Closed { reason: "cancelled".into() });
+
+        assert_eq!(
+            block_on(states.collect::<Vec<_>>()),
+            vec![
+                SubscriptionState::Live,
+                SubscriptionState::Reconnecting { attempt: 1, delay: Duration::from_secs(1) },
+                SubscriptionState::Closed { reason: "cancelled".into() },
+            ]
+        );
+    }
+}
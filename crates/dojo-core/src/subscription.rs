@@ -0,0 +1,791 @@
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use futures::future::Either;
+use futures::{FutureExt, Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use stream_cancel::{StreamExt as _, Trigger, Tripwire};
+use torii_client::Client;
+use torii_proto::schema::Entity;
+use torii_proto::TokenBalance;
+
+use crate::connection::Connection;
+use crate::delivery::{channel_with, Coalesce, DeliveryControl};
+use crate::failover::Transport;
+use crate::mux::EntityFilter;
+use crate::polling::{EntitySource, Poller};
+use crate::replay::{self, Session};
+use crate::status::{SubscriptionState, SubscriptionStatus};
+
+/// Subscription id and query of the stream serving a subscription, shared by the task driving
+/// it and the handle updating it.
+///
+/// Every (re)connection subscribes with the current query and records the id assigned by the
+/// server, so that updates reach the stream currently serving the subscription and outlive
+/// reconnections, e.g. to another endpoint.
+#[derive(Debug)]
+pub struct Live<Q> {
+    inner: Arc<Mutex<Current<Q>>>,
+}
+
+#[derive(Debug)]
+struct Current<Q> {
+    id: u64,
+    query: Q,
+}
+
+impl<Q> Clone for Live<Q> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone() }
+    }
+}
+
+impl<Q: Clone> Live<Q> {
+    pub fn new(query: Q) -> Self {
+        Self { inner: Arc::new(Mutex::new(Current { id: 0, query })) }
+    }
+
+    /// Id assigned by the server to the current stream, `0` until the first one is established.
+    pub fn id(&self) -> u64 {
+        self.inner.lock().unwrap().id
+    }
+
+    /// Query the next stream subscribes with.
+    pub fn query(&self) -> Q {
+        self.inner.lock().unwrap().query.clone()
+    }
+
+    fn connected(&self, id: u64) {
+        self.inner.lock().unwrap().id = id;
+    }
+
+    /// Changes the query of the subscription, `update` sending it to the current stream.
+    ///
+    /// Streams established from now on subscribe with the new query, unless `update` fails,
+    /// in which case the previous query is kept.
+    pub async fn update<R, E, F, Fut>(&self, query: Q, update: F) -> Result<R, E>
+    where
+        F: FnOnce(u64, Q) -> Fut,
+        Fut: Future<Output = Result<R, E>>,
+    {
+        let (id, previous) = {
+            let mut current = self.inner.lock().unwrap();
+            (current.id, std::mem::replace(&mut current.query, query.clone()))
+        };
+
+        let result = update(id, query).await;
+        if result.is_err() {
+            self.inner.lock().unwrap().query = previous;
+        }
+        result
+    }
+}
+
+/// What the consumer of a subscription controls it with.
+pub struct Handle<Q> {
+    /// Cancels the subscription when triggered or dropped
+    pub trigger: Trigger,
+    pub status: SubscriptionStatus,
+    pub delivery: DeliveryControl,
+    pub live: Live<Q>,
+}
+
+impl<Q: Clone> Handle<Q> {
+    /// Waits until the first stream is established and returns its subscription id. Fails if
+    /// the subscription closes first.
+    pub async fn established(&self) -> Result<u64> {
+        if !self.status.established().await {
+            return Err(anyhow!("subscription closed before its stream was established"));
+        }
+        Ok(self.live.id())
+    }
+}
+
+/// A subscription created by [`subscribe`], which runs once both of its futures are spawned.
+pub struct Subscribed<Q, D, R> {
+    pub handle: Handle<Q>,
+    /// Delivers the buffered updates to the callback, see [`channel_with`]
+    pub deliver: D,
+    /// Drives the stream, see [`drive`]
+    pub run: R,
+}
+
+/// Creates a subscription whose stream is opened by `connect` with its current query.
+///
+/// Updates reach `on_item` through a delivery buffer, see [`channel_with`], and the stream is
+/// driven by [`drive`]. The query is changed through [`Live::update`] on the returned handle.
+pub fn subscribe<P, Q, T, S, E, C, CF, CE, Z, ZF>(
+    connection: Arc<Connection<P>>,
+    query: Q,
+    connect: C,
+    sleep: Z,
+    on_item: impl FnMut(T),
+) -> Subscribed<Q, impl Future<Output = ()>, impl Future<Output = ()>>
+where
+    P: Transport,
+    Q: Clone,
+    T: Serialize + DeserializeOwned,
+    C: Fn(Arc<P::Client>, Q) -> CF,
+    CF: Future<Output = Result<S, CE>>,
+    S: Stream<Item = Result<(u64, T), E>>,
+    Z: Fn(Duration) -> ZF + Clone,
+    ZF: Future<Output = ()>,
+{
+    let (trigger, tripwire) = Tripwire::new();
+    let status = SubscriptionStatus::new();
+    let live = Live::new(query);
+    let (on_item, delivery, deliver) =
+        channel_with(DeliveryControl::default(), None, sleep.clone(), on_item);
+
+    let (following, connected) = (live.clone(), live.clone());
+    let run = drive(
+        connection,
+        move |client| connect(client, following.query()),
+        sleep,
+        tripwire,
+        status.clone(),
+        Some(delivery.clone()),
+        move |id| connected.connected(id),
+        on_item,
+    );
+
+    Subscribed { handle: Handle { trigger, status, delivery, live }, deliver, run }
+}
+
+/// Same as [`subscribe`] for entity and event message subscriptions, driven by
+/// [`drive_entities`] and coalesced as [`Coalesce::ENTITIES`] tells.
+///
+/// `control` is the handle of the delivery, e.g. one `on_item` projects its updates with.
+pub fn subscribe_entities<Z, ZF>(
+    connection: Arc<Connection>,
+    source: EntitySource,
+    filter: EntityFilter,
+    sleep: Z,
+    control: DeliveryControl,
+    on_item: impl FnMut(Entity),
+) -> Subscribed<EntityFilter, impl Future<Output = ()>, impl Future<Output = ()>>
+where
+    Z: Fn(Duration) -> ZF + Clone,
+    ZF: Future<Output = ()>,
+{
+    let (trigger, tripwire) = Tripwire::new();
+    let status = SubscriptionStatus::new();
+    let live = Live::new(filter);
+    let (on_item, delivery, deliver) =
+        channel_with(control, Some(Coalesce::ENTITIES), sleep.clone(), on_item);
+
+    let (following, connected) = (live.clone(), live.clone());
+    let run = drive_entities(
+        connection,
+        source,
+        move || following.query(),
+        sleep,
+        tripwire,
+        status.clone(),
+        Some(delivery.clone()),
+        move |id| connected.connected(id),
+        on_item,
+    );
+
+    Subscribed { handle: Handle { trigger, status, delivery, live }, deliver, run }
+}
+
+/// Drives a Torii subscription until it is cancelled or its retry policy is exhausted.
+///
+/// `connect` opens the stream on the active endpoint. The first message of every stream carries
+/// the subscription id assigned by the server and is handed to `on_connected`; every following
+/// message goes to `on_item`. When the stream ends, the endpoint pool is probed so that the
+/// subscription moves to a healthy replica, then the stream is re-established with the retry
+/// policy of the configuration.
+///
+/// `sleep` abstracts over the runtime so that the same driver works natively and in the browser.
+/// Every connection state transition is recorded in `status`. When `on_item` feeds the buffer
+/// of `pace`, the stream is only read while the buffer has room, see [`DeliveryControl::ready`].
+///
+/// When the session of the connection is recorded, the stream is recorded along with it. When
+/// it replays a recording, the recorded stream is played instead, see
+/// [`Replay`](crate::replay::Replay).
+#[allow(clippy::too_many_arguments)]
+pub async fn drive<P, T, S, E, C, CF, CE, Z, ZF>(
+    connection: Arc<Connection<P>>,
+    connect: C,
+    sleep: Z,
+    tripwire: Tripwire,
+    status: SubscriptionStatus,
+    pace: Option<DeliveryControl>,
+    mut on_connected: impl FnMut(u64),
+    mut on_item: impl FnMut(T),
+) where
+    P: Transport,
+    T: Serialize + DeserializeOwned,
+    C: Fn(Arc<P::Client>) -> CF,
+    CF: Future<Output = Result<S, CE>>,
+    S: Stream<Item = Result<(u64, T), E>>,
+    Z: Fn(Duration) -> ZF,
+    ZF: Future<Output = ()>,
+{
+    let recorder = match connection.session() {
+        Some(Session::Replay(replay)) => {
+            return replay.drive(&sleep, &tripwire, &status, &mut on_connected, &mut on_item).await;
+        }
+        Some(Session::Record(recorder)) => Some(recorder),
+        None => None,
+    };
+    let (mut on_connected, mut on_item) = replay::recorded(recorder, on_connected, on_item);
+
+    let hooks = Hooks {
+        tripwire: &tripwire,
+        status: &status,
+        pace: pace.as_ref(),
+        fallback_after: None,
+        polling: false,
+    };
+    run(&connection, &connect, &sleep, hooks, &mut (), &mut on_connected, &mut on_item).await;
+}
+
+/// Same as [`drive`] for entity and event message subscriptions of `source`, every connection
+/// subscribing with the filter returned by `query` at the time.
+///
+/// Every (re)connection queries the entities matching the filter that were updated since the
+/// last one delivered, so that they reach `on_item` before the live updates, deduplicated by
+/// hashed keys and model hash, see [`Poller`].
+///
+/// Updates invalidate the cached query results they change, see
+/// [`QueryCache`](crate::cache::QueryCache).
+///
+/// When the [`PollingConfig`](crate::config::PollingConfig) is set and the stream keeps
+/// failing, the subscription falls back to polling, trying to stream again after every poll.
+/// `on_connected` receives `0` whenever it starts polling.
+///
+/// Like [`drive`], the stream is recorded or replayed along with the session, polled
+/// updates included.
+#[allow(clippy::too_many_arguments)]
+pub async fn drive_entities<Z, ZF>(
+    connection: Arc<Connection>,
+    source: EntitySource,
+    query: impl Fn() -> EntityFilter,
+    sleep: Z,
+    tripwire: Tripwire,
+    status: SubscriptionStatus,
+    pace: Option<DeliveryControl>,
+    mut on_connected: impl FnMut(u64),
+    mut on_item: impl FnMut(Entity),
+) where
+    Z: Fn(Duration) -> ZF,
+    ZF: Future<Output = ()>,
+{
+    let cache = connection.cache().cloned();
+    let mut on_item = move |entity: Entity| {
+        if let Some(cache) = &cache {
+            cache.invalidate_entity(source, &entity);
+        }
+        on_item(entity);
+    };
+
+    let recorder = match connection.session() {
+        Some(Session::Replay(replay)) => {
+            return replay.drive(&sleep, &tripwire, &status, &mut on_connected, &mut on_item).await;
+        }
+        Some(Session::Record(recorder)) => Some(recorder),
+        None => None,
+    };
+    let (mut on_connected, mut on_item) = replay::recorded(recorder, on_connected, on_item);
+
+    let connect = |client: Arc<Client>| {
+        let EntityFilter { clause, world_addresses } = query();
+        async move {
+            match source {
+                EntitySource::Entities => {
+                    client.on_entity_updated(clause, world_addresses).await.map(Either::Left)
+                }
+                EntitySource::EventMessages => client
+                    .on_event_message_updated(clause, world_addresses)
+                    .await
+                    .map(Either::Right),
+            }
+        }
+    };
+
+    let polling = connection.config().polling.clone();
+    let EntityFilter { clause, world_addresses } = query();
+    let mut poller = Poller::new(source, clause, world_addresses);
+    if let Some(polling) = &polling {
+        poller = poller.with_page_size(polling.page_size);
+    }
+
+    let hooks = Hooks {
+        tripwire: &tripwire,
+        status: &status,
+        pace: pace.as_ref(),
+        fallback_after: polling.as_ref().map(|polling| polling.after_failures),
+        polling: false,
+    };
+    let interval = polling.map(|polling| polling.interval);
+    let mut resume = Following { poller: &mut poller, query: &query };
+    run_or_poll(
+        &connection,
+        &connect,
+        &sleep,
+        hooks,
+        interval,
+        &mut resume,
+        &mut on_connected,
+        &mut on_item,
+    )
+    .await;
+}
+
+/// Wraps the `on_item` of a token balance subscription so that updates invalidate the cached
+/// query results they change, see [`QueryCache`](crate::cache::QueryCache).
+pub fn invalidate_token_balances(
+    connection: &Connection,
+    mut on_item: impl FnMut(TokenBalance),
+) -> impl FnMut(TokenBalance) {
+    let cache = connection.cache().cloned();
+    move |balance| {
+        if let Some(cache) = &cache {
+            cache.invalidate_token_balance(&balance);
+        }
+        on_item(balance);
+    }
+}
+
+/// Adapts streams that don't carry a subscription id to the shape expected by [`drive`].
+pub fn without_id<T, E>(
+    stream: impl Stream<Item = Result<T, E>>,
+) -> impl Stream<Item = Result<(u64, T), E>> {
+    stream.map(|item| item.map(|item| (0, item)))
+}
+
+pub(crate) const CANCELLED: &str = "subscription cancelled";
+const EXHAUSTED: &str = "retry policy exhausted";
+
+/// Lets a subscription recover the items it missed while disconnected, from the client `C` of
+/// the endpoint it reconnected to.
+pub trait Resume<T, C = Client> {
+    /// Records an item received from the stream and returns whether it should be delivered.
+    fn observe(&mut self, item: &T) -> bool;
+
+    /// Fetches the items missed since the last observed one, called every time a stream is
+    /// established. Their delivery precedes the stream's items.
+    fn resume(&mut self, client: &C) -> impl Future<Output = Vec<T>>;
+
+    /// Fetches the items updated since the previous poll, once the subscription fell back to
+    /// polling. Subscriptions that can't poll deliver nothing.
+    fn poll(&mut self, _client: &C) -> impl Future<Output = Result<Vec<T>>> {
+        futures::future::ready(Ok(Vec::new()))
+    }
+}
+
+/// Subscriptions that don't resume.
+impl<T, C> Resume<T, C> for () {
+    fn observe(&mut self, _item: &T) -> bool {
+        true
+    }
+
+    fn resume(&mut self, _client: &C) -> impl Future<Output = Vec<T>> {
+        futures::future::ready(Vec::new())
+    }
+}
+
+/// Resumes with a [`Poller`] following the current filter of the subscription.
+struct Following<'a, Q> {
+    poller: &'a mut Poller,
+    query: &'a Q,
+}
+
+impl<Q: Fn() -> EntityFilter> Resume<Entity> for Following<'_, Q> {
+    fn observe(&mut self, entity: &Entity) -> bool {
+        self.poller.observe(entity)
+    }
+
+    fn resume(&mut self, client: &Client) -> impl Future<Output = Vec<Entity>> {
+        let EntityFilter { clause, world_addresses } = (self.query)();
+        self.poller.set_filter(clause, world_addresses);
+        self.poller.resume(client)
+    }
+
+    fn poll(&mut self, client: &Client) -> impl Future<Output = Result<Vec<Entity>>> {
+        let EntityFilter { clause, world_addresses } = (self.query)();
+        self.poller.set_filter(clause, world_addresses);
+        self.poller.poll(client)
+    }
+}
+
+/// How the stream loop reports to and is controlled by its subscription.
+#[derive(Clone, Copy)]
+struct Hooks<'a> {
+    tripwire: &'a Tripwire,
+    status: &'a SubscriptionStatus,
+    /// Delivery buffer fed by the stream, holding it back while full
+    pace: Option<&'a DeliveryControl>,
+    /// Consecutive failed attempts after which the loop gives up on streaming
+    fallback_after: Option<u32>,
+    /// Set while polling, so that a single failed attempt goes back to polling
+    polling: bool,
+}
+
+/// Why a subscription stopped streaming.
+#[derive(Debug, PartialEq, Eq)]
+enum Ended {
+    /// The subscription was cancelled by its consumer
+    Cancelled,
+    /// The retry policy gave up on reconnecting
+    Exhausted,
+    /// The stream failed as many consecutive times as allowed before falling back
+    Failing,
+}
+
+async fn run<P, T, S, E, C, CF, CE, Z, ZF>(
+    connection: &Connection<P>,
+    connect: &C,
+    sleep: &Z,
+    Hooks { tripwire, status, pace, fallback_after, polling }: Hooks<'_>,
+    resume: &mut impl Resume<T, P::Client>,
+    on_connected: &mut impl FnMut(u64),
+    on_item: &mut impl FnMut(T),
+) -> Ended
+where
+    P: Transport,
+    C: Fn(Arc<P::Client>) -> CF,
+    CF: Future<Output = Result<S, CE>>,
+    S: Stream<Item = Result<(u64, T), E>>,
+    Z: Fn(Duration) -> ZF,
+    ZF: Future<Output = ()>,
+{
+    let retry = connection.config().retry.clone();
+    let mut attempt = 0;
+    // Consecutive streams that ended before delivering their subscription id
+    let mut failures = 0;
+    // Whether a stream was established since the loop started
+    let mut established = false;
+    if fallback_after == Some(0) && !polling {
+        return Ended::Failing;
+    }
+
+    loop {
+        let mut connected = false;
+        if let Ok(client) = connection.pool().current() {
+            if let Ok(stream) = connect(client.clone()).await {
+                let mut stream = pin!(stream.take_until_if(tripwire.clone()));
+                while let Some(Ok((id, item))) = stream.next().await {
+                    if connected {
+                        if resume.observe(&item) {
+                            ready(pace).await;
+                            on_item(item);
+                        }
+                    } else {
+                        connected = true;
+                        attempt = 0; // Reset backoff once the stream delivers
+                        on_connected(id);
+                        // Live updates queue up in the stream while catching up
+                        for item in resume.resume(&client).await {
+                            ready(pace).await;
+                            on_item(item);
+                        }
+                        status.set(SubscriptionState::Live);
+                    }
+                }
+            }
+        }
+        failures = if connected { 0 } else { failures + 1 };
+        established |= connected;
+
+        // If we've reached this point, the stream has ended (possibly due to disconnection)
+        // We'll try to reconnect after a delay, unless the tripwire has been triggered
+        if tripwire.clone().now_or_never().unwrap_or_default() {
+            // Exit the loop if the subscription has been cancelled
+            status.set(SubscriptionState::Closed { reason: CANCELLED.to_string() });
+            return Ended::Cancelled;
+        }
+        let switched = connection.pool().recover().await;
+        let fallback_after = if polling && !established { Some(1) } else { fallback_after };
+        if fallback_after.is_some_and(|after| failures >= after) {
+            return Ended::Failing;
+        }
+        if retry.exhausted(attempt) {
+            // Give up once the retry policy is exhausted
+            status.set(SubscriptionState::Closed { reason: EXHAUSTED.to_string() });
+            return Ended::Exhausted;
+        }
+
+        // When another replica took over, reconnect to it right away, but only on the first
+        // attempt so that replicas failing in turn don't spin
+        let delay = if switched && attempt == 0 { Duration::ZERO } else { retry.backoff(attempt) };
+        status.set(SubscriptionState::Reconnecting { attempt: attempt + 1, delay });
+        let mut backoff = pin!(sleep(delay).fuse());
+        let mut cancelled = pin!(tripwire.clone().fuse());
+        futures::select! {
+            _ = backoff => {}
+            cancelled = cancelled => if cancelled {
+                status.set(SubscriptionState::Closed { reason: CANCELLED.to_string() });
+                return Ended::Cancelled;
+            },
+        }
+        attempt += 1;
+    }
+}
+
+/// Runs the stream loop, falling back to polling every `interval` once the stream keeps failing
+/// and trying to stream again after every poll.
+#[allow(clippy::too_many_arguments)]
+async fn run_or_poll<P, T, S, E, C, CF, CE, Z, ZF>(
+    connection: &Connection<P>,
+    connect: &C,
+    sleep: &Z,
+    mut hooks: Hooks<'_>,
+    interval: Option<Duration>,
+    resume: &mut impl Resume<T, P::Client>,
+    on_connected: &mut impl FnMut(u64),
+    on_item: &mut impl FnMut(T),
+) where
+    P: Transport,
+    C: Fn(Arc<P::Client>) -> CF,
+    CF: Future<Output = Result<S, CE>>,
+    S: Stream<Item = Result<(u64, T), E>>,
+    Z: Fn(Duration) -> ZF,
+    ZF: Future<Output = ()>,
+{
+    let Hooks { tripwire, status, pace, .. } = hooks;
+    // Whether a stream was established since polling last started
+    let streamed = Cell::new(true);
+    let mut on_stream = |id| {
+        streamed.set(true);
+        on_connected(id);
+    };
+
+    loop {
+        let ended = run(connection, connect, sleep, hooks, resume, &mut on_stream, on_item).await;
+        let Some(interval) = interval.filter(|_| ended == Ended::Failing) else {
+            return;
+        };
+
+        if streamed.get() {
+            on_stream(0);
+            streamed.set(false);
+        }
+        status.set(SubscriptionState::Live);
+        let polled = match connection.pool().current() {
+            Ok(client) => resume.poll(&client).await,
+            Err(e) => Err(e),
+        };
+        match polled {
+            Ok(items) => {
+                for item in items {
+                    ready(pace).await;
+                    on_item(item);
+                }
+            }
+            Err(_) => {
+                connection.pool().recover().await;
+            }
+        }
+
+        let mut wait = pin!(sleep(interval).fuse());
+        let mut cancelled = pin!(tripwire.clone().fuse());
+        futures::select! {
+            _ = wait => {}
+            cancelled = cancelled => if cancelled {
+                status.set(SubscriptionState::Closed { reason: CANCELLED.to_string() });
+                return;
+            },
+        }
+        hooks.polling = true;
+    }
+}
+
+/// Waits for the delivery buffer fed by the stream to have room, if any.
+async fn ready(pace: Option<&DeliveryControl>) {
+    if let Some(pace) = pace {
+        pace.ready().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use futures::channel::oneshot;
+    use futures::executor::{block_on, LocalPool};
+    use futures::task::LocalSpawnExt;
+
+    use super::*;
+    use crate::config::ClientConfig;
+    use crate::failover::EndpointPool;
+    use crate::mock::{Server, Servers};
+
+    #[test]
+    fn follows_id_and_query_across_failovers() {
+        let (a, b) = (Server::new(100, 1), Server::new(100, 100));
+        let servers = Servers::default();
+        servers.add("http://a", a.clone());
+        servers.add("http://b", b.clone());
+        let mut config = ClientConfig::new("http://a".to_string());
+        config.fallback_urls = vec!["http://b".to_string()];
+        let pool = block_on(EndpointPool::connect_with(servers, config)).unwrap();
+        let connection = Arc::new(Connection::new(pool));
+
+        let items = Rc::new(RefCell::new(Vec::new()));
+        let received = items.clone();
+        let Subscribed { handle, deliver, run } = subscribe(
+            connection.clone(),
+            1,
+            |server: Arc<Server>, query| async move { server.subscribe(query) },
+            |_| futures::future::ready(()),
+            move |item| received.borrow_mut().push(item),
+        );
+        let mut tasks = LocalPool::new();
+        tasks.spawner().spawn_local(deliver).unwrap();
+        tasks.spawner().spawn_local(run).unwrap();
+        tasks.run_until_stalled();
+        assert_eq!(handle.status.state(), SubscriptionState::Live);
+        assert_eq!(handle.live.id(), 1);
+
+        // The stream moves to the other replica, which assigns it another id
+        a.stop();
+        tasks.run_until_stalled();
+        assert_eq!(handle.status.state(), SubscriptionState::Live);
+        assert_eq!(handle.live.id(), 100);
+
+        // Updates reach the stream of the replica now serving it
+        let update = handle.live.update(2, |id, query| {
+            let server = connection.pool().current();
+            async move { server?.update(id, query) }
+        });
+        block_on(update).unwrap();
+        b.publish(1);
+        b.publish(2);
+        tasks.run_until_stalled();
+        assert_eq!(*items.borrow(), [2]);
+
+        // Failing back subscribes with the updated query
+        a.start();
+        b.stop();
+        tasks.run_until_stalled();
+        assert_eq!(handle.live.id(), 2);
+        a.publish(1);
+        a.publish(2);
+        tasks.run_until_stalled();
+        assert_eq!(*items.borrow(), [2, 2]);
+
+        // A rejected update keeps the query
+        let rejected = handle.live.update(1, move |id, query| async move { b.update(id, query) });
+        assert!(block_on(rejected).is_err());
+        assert_eq!(handle.live.query(), 2);
+
+        handle.trigger.cancel();
+        tasks.run();
+    }
+
+    /// Polls the head of the server.
+    struct Heads;
+
+    impl Resume<u32, Server> for Heads {
+        fn observe(&mut self, _item: &u32) -> bool {
+            true
+        }
+
+        fn resume(&mut self, _server: &Server) -> impl Future<Output = Vec<u32>> {
+            futures::future::ready(Vec::new())
+        }
+
+        fn poll(&mut self, server: &Server) -> impl Future<Output = Result<Vec<u32>>> {
+            futures::future::ready(server.head().map(|head| vec![head as u32]))
+        }
+    }
+
+    #[test]
+    fn retries_streaming_while_polling() {
+        let server = Server::new(100, 1);
+        let servers = Servers::default();
+        servers.add("http://a", server.clone());
+        let config = ClientConfig::new("http://a".to_string());
+        let connection =
+            Connection::new(block_on(EndpointPool::connect_with(servers, config)).unwrap());
+
+        let streaming = Rc::new(Cell::new(false));
+        let ticks = Rc::new(RefCell::new(Vec::<oneshot::Sender<()>>::new()));
+        let ids = Rc::new(RefCell::new(Vec::new()));
+        let items = Rc::new(RefCell::new(Vec::new()));
+        let (trigger, tripwire) = Tripwire::new();
+        let status = SubscriptionStatus::new();
+        let polling = {
+            let (streaming, ticks) = (streaming.clone(), ticks.clone());
+            let (ids, items, status) = (ids.clone(), items.clone(), status.clone());
+            async move {
+                let connect = |server: Arc<Server>| {
+                    let up = streaming.get();
+                    async move {
+                        match up {
+                            true => server.subscribe(7),
+                            false => Err(anyhow!("streaming is unavailable")),
+                        }
+                    }
+                };
+                let sleep = |_: Duration| {
+                    let (tick, ticked) = oneshot::channel();
+                    ticks.borrow_mut().push(tick);
+                    ticked.map(|_| ())
+                };
+                let hooks = Hooks {
+                    tripwire: &tripwire,
+                    status: &status,
+                    pace: None,
+                    fallback_after: Some(1),
+                    polling: false,
+                };
+                run_or_poll(
+                    &connection,
+                    &connect,
+                    &sleep,
+                    hooks,
+                    Some(Duration::from_secs(1)),
+                    &mut Heads,
+                    &mut |id| ids.borrow_mut().push(id),
+                    &mut |item| items.borrow_mut().push(item),
+                )
+                .await;
+            }
+        };
+        let tick = || {
+            for tick in ticks.borrow_mut().drain(..) {
+                let _ = tick.send(());
+            }
+        };
+
+        let mut tasks = LocalPool::new();
+        tasks.spawner().spawn_local(polling).unwrap();
+        tasks.run_until_stalled();
+        // The first poll is delivered
+        assert_eq!(status.state(), SubscriptionState::Live);
+        assert_eq!(*ids.borrow(), [0]);
+        assert_eq!(*items.borrow(), [100]);
+
+        // Streaming is tried once per poll
+        server.set_head(101);
+        tick();
+        tasks.run_until_stalled();
+        assert_eq!(*ids.borrow(), [0]);
+        assert_eq!(*items.borrow(), [100, 101]);
+
+        // and followed again once it works
+        streaming.set(true);
+        tick();
+        tasks.run_until_stalled();
+        assert_eq!(status.state(), SubscriptionState::Live);
+        assert_eq!(*ids.borrow(), [0, 1]);
+        server.publish(7);
+        tasks.run_until_stalled();
+        assert_eq!(*items.borrow(), [100, 101, 7]);
+
+        trigger.cancel();
+        tasks.run();
+        assert_eq!(status.state(), SubscriptionState::Closed { reason: CANCELLED.to_string() });
+    }
+}
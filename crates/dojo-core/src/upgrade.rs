@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use dojo_types::schema::{EnumOption, Member, Ty};
+use futures::lock::Mutex;
+use starknet::core::utils::get_selector_from_name;
+use starknet_crypto::Felt;
+use torii_proto::{KeysClause, PatternMatching};
+
+use crate::cache::CacheKey;
+use crate::connection::Connection;
+use crate::schema::{ModelSchema, WorldSchema};
+
+/// Events of the world contract registering or upgrading a model or an event.
+pub const UPGRADE_EVENTS: [&str; 4] =
+    ["ModelRegistered", "ModelUpgraded", "EventRegistered", "EventUpgraded"];
+
+/// How a part of a schema changed, see [`TypeChange`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeChangeKind {
+    /// A struct member or enum variant was added
+    Added,
+    /// A struct member or enum variant was removed
+    Removed,
+    /// The type changed, e.g. a wider primitive or an array of a fixed size
+    Changed,
+    /// A struct member became a key, or stopped being one
+    KeyChanged,
+}
+
+/// Structural difference between two versions of a type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeChange {
+    /// Path of the member or variant, e.g. `vec.x`, `dir::Left`, `pair.1` or `items[]` for
+    /// the elements of an array. Empty for the type itself.
+    pub path: String,
+    pub kind: TypeChangeKind,
+    /// Type before the change, `None` when added
+    pub old: Option<Ty>,
+    /// Type after the change, `None` when removed
+    pub new: Option<Ty>,
+}
+
+/// Model registered or upgraded since the schema of its world was last seen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaUpgrade {
+    pub world_address: Felt,
+    /// Schema before the upgrade, `None` when the model was just registered
+    pub old: Option<ModelSchema>,
+    pub new: ModelSchema,
+    /// Structural changes of the schema, empty when only the class of the model changed
+    pub changes: Vec<TypeChange>,
+}
+
+/// Structural differences between two versions of a type, ignoring values.
+///
+/// Struct members and enum variants are matched by name, so that reordering them isn't a
+/// change. Tuples of a different length and arrays of a different size are changed as a whole.
+pub fn diff_schema(old: &Ty, new: &Ty) -> Vec<TypeChange> {
+    let mut changes = Vec::new();
+    diff_types(String::new(), old, new, &mut changes);
+    changes
+}
+
+fn diff_types(path: String, old: &Ty, new: &Ty, changes: &mut Vec<TypeChange>) {
+    match (old, new) {
+        (Ty::Struct(old), Ty::Struct(new)) => {
+            diff_named(&path, ".", &old.children, &new.children, member, changes);
+            for old in &old.children {
+                let Some(new) = new.children.iter().find(|m| m.name == old.name) else { continue };
+                if old.key != new.key {
+                    changes.push(TypeChange {
+                        path: join(&path, ".", &old.name),
+                        kind: TypeChangeKind::KeyChanged,
+                        old: Some(old.ty.clone()),
+                        new: Some(new.ty.clone()),
+                    });
+                }
+            }
+        }
+        (Ty::Enum(old), Ty::Enum(new)) => {
+            diff_named(&path, "::", &old.options, &new.options, variant, changes);
+        }
+        (Ty::Tuple(old_items), Ty::Tuple(new_items)) if old_items.len() == new_items.len() => {
+            for (i, (old, new)) in old_items.iter().zip(new_items).enumerate() {
+                diff_types(join(&path, ".", &i.to_string()), old, new, changes);
+            }
+        }
+        (Ty::FixedSizeArray((old_items, old_size)), Ty::FixedSizeArray((new_items, new_size)))
+            if old_size == new_size =>
+        {
+            diff_elements(path, old_items, new_items, changes);
+        }
+        (Ty::Array(old_items), Ty::Array(new_items)) => {
+            diff_elements(path, old_items, new_items, changes);
+        }
+        (Ty::Primitive(_), Ty::Primitive(_)) | (Ty::ByteArray(_), Ty::ByteArray(_))
+            if old.name() == new.name() => {}
+        _ => changes.push(TypeChange {
+            path,
+            kind: TypeChangeKind::Changed,
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+    }
+}
+
+/// Compares the element types of two arrays, the first value of an array in a schema.
+fn diff_elements(path: String, old: &[Ty], new: &[Ty], changes: &mut Vec<TypeChange>) {
+    if let (Some(old), Some(new)) = (old.first(), new.first()) {
+        diff_types(format!("{path}[]"), old, new, changes);
+    }
+}
+
+fn member(member: &Member) -> (&str, &Ty) {
+    (&member.name, &member.ty)
+}
+
+fn variant(option: &EnumOption) -> (&str, &Ty) {
+    (&option.name, &option.ty)
+}
+
+/// Matches the members or variants of two versions of a type by name.
+fn diff_named<T>(
+    path: &str,
+    separator: &str,
+    old: &[T],
+    new: &[T],
+    named: fn(&T) -> (&str, &Ty),
+    changes: &mut Vec<TypeChange>,
+) {
+    let new_by_name = new.iter().map(named).collect::<HashMap<_, _>>();
+    let old_by_name = old.iter().map(named).collect::<HashMap<_, _>>();
+
+    for (name, old) in old.iter().map(named) {
+        let path = join(path, separator, name);
+        match new_by_name.get(name) {
+            Some(new) => diff_types(path, old, new, changes),
+            None => changes.push(TypeChange {
+                path,
+                kind: TypeChangeKind::Removed,
+                old: Some(old.clone()),
+                new: None,
+            }),
+        }
+    }
+    for (name, new) in new.iter().map(named).filter(|(name, _)| !old_by_name.contains_key(name)) {
+        changes.push(TypeChange {
+            path: join(path, separator, name),
+            kind: TypeChangeKind::Added,
+            old: None,
+            new: Some(new.clone()),
+        });
+    }
+}
+
+fn join(path: &str, separator: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{path}{separator}{name}")
+    }
+}
+
+/// Models registered or upgraded between two schemas of a world, sorted by tag.
+///
+/// A model is upgraded when its schema or its class changed. Models can't be removed from a
+/// world, so models missing from `new` are ignored.
+pub fn upgrades(old: &WorldSchema, new: &WorldSchema) -> Vec<SchemaUpgrade> {
+    new.models()
+        .iter()
+        .filter_map(|model| {
+            let old = old.model_by_selector(model.selector);
+            if old
+                .is_some_and(|old| old.schema == model.schema && old.class_hash == model.class_hash)
+            {
+                return None;
+            }
+            Some(SchemaUpgrade {
+                world_address: new.world_address,
+                changes: old.map(|old| diff_schema(&old.schema, &model.schema)).unwrap_or_default(),
+                old: old.cloned(),
+                new: model.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Clauses matching the world events of [`UPGRADE_EVENTS`], whose first key is the selector
+/// of the event.
+pub fn upgrade_clauses() -> Vec<KeysClause> {
+    UPGRADE_EVENTS
+        .iter()
+        .map(|name| KeysClause {
+            keys: vec![Some(get_selector_from_name(name).unwrap())],
+            pattern_matching: PatternMatching::VariableLen,
+            models: vec![],
+        })
+        .collect()
+}
+
+/// Tags of the models upgraded while a client runs, in order, for the subscriptions whose
+/// state was built from their previous schema, e.g. an [`EntityDiffer`].
+///
+/// [`EntityDiffer`]: crate::diff::EntityDiffer
+#[derive(Debug, Default)]
+pub struct Upgrades {
+    models: std::sync::Mutex<Vec<String>>,
+}
+
+impl Upgrades {
+    pub fn record(&self, models: &[String]) {
+        self.models.lock().unwrap().extend_from_slice(models);
+    }
+
+    /// Number of upgrades recorded so far, where to start following [`Upgrades::since`] from.
+    pub fn count(&self) -> usize {
+        self.models.lock().unwrap().len()
+    }
+
+    /// Returns the tags of the models upgraded since `seen` upgrades, and moves `seen` past
+    /// them.
+    pub fn since(&self, seen: &mut usize) -> Vec<String> {
+        let models = self.models.lock().unwrap();
+        let upgraded = models.get(*seen..).unwrap_or_default().to_vec();
+        *seen = models.len();
+        upgraded
+    }
+}
+
+/// Tracks the schema of a world to report the models registered or upgraded.
+///
+/// Bindings call [`SchemaWatcher::refresh`] whenever a Starknet event of
+/// [`upgrade_clauses`] arrives, and whenever the event subscription connects, so that upgrades
+/// missed before it was live or while disconnected are reported too. Torii's events don't carry
+/// the contract that emitted them, so an event of another contract with the same selector only
+/// costs a refresh.
+pub struct SchemaWatcher {
+    world_address: Felt,
+    schema: Mutex<Option<WorldSchema>>,
+}
+
+impl SchemaWatcher {
+    pub fn new(world_address: Felt) -> Self {
+        Self { world_address, schema: Mutex::new(None) }
+    }
+
+    pub fn world_address(&self) -> Felt {
+        self.world_address
+    }
+
+    /// Fetches the schema of the world and returns the models registered or upgraded since the
+    /// last refresh. The first refresh only records the schema.
+    ///
+    /// When models changed, what the connection built from the previous schema is dropped, see
+    /// [`Connection::invalidate_schema`].
+    pub async fn refresh(&self, connection: &Connection) -> Result<Vec<SchemaUpgrade>> {
+        // Held across the request so that concurrent refreshes report each upgrade once
+        let mut schema = self.schema.lock().await;
+
+        let world_address = self.world_address;
+        let key = CacheKey::normalized("worlds", &vec![world_address]);
+        let worlds = connection
+            .exchange(key, |client| async move { client.worlds(vec![world_address]).await })
+            .await?;
+        let Some(world) = worlds.iter().find(|world| world.world_address == world_address) else {
+            return Err(anyhow::anyhow!("world {world_address:#x} not found"));
+        };
+
+        let new = WorldSchema::new(world);
+        let upgrades = schema.as_ref().map(|old| upgrades(old, &new)).unwrap_or_default();
+        if !upgrades.is_empty() {
+            let models = upgrades.iter().map(|upgrade| upgrade.new.tag.clone()).collect::<Vec<_>>();
+            connection.invalidate_schema(world_address, &models);
+        }
+        *schema = Some(new);
+        Ok(upgrades)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_types::primitive::Primitive;
+    use dojo_types::schema::{Enum, Struct};
+
+    use super::*;
+
+    fn member(name: &str, ty: Ty, key: bool) -> Member {
+        Member { name: name.to_string(), ty, key }
+    }
+
+    fn direction(variants: &[&str]) -> Ty {
+        Ty::Enum(Enum {
+            name: "Direction".to_string(),
+            option: None,
+            options: variants
+                .iter()
+                .map(|name| EnumOption { name: name.to_string(), ty: Ty::Tuple(vec![]) })
+                .collect(),
+        })
+    }
+
+    #[test]
+    fn diffs_schemas_structurally() {
+        let old = Ty::Struct(Struct {
+            name: "Position".to_string(),
+            children: vec![
+                member("player", Ty::Primitive(Primitive::ContractAddress(None)), true),
+                member("x", Ty::Primitive(Primitive::U32(None)), false),
+                member("dir", direction(&["Left", "Right"]), false),
+                member("path", Ty::Array(vec![Ty::Primitive(Primitive::U8(None))]), false),
+                member("gone", Ty::ByteArray(String::new()), false),
+            ],
+        });
+        let new = Ty::Struct(Struct {
+            name: "Position".to_string(),
+            children: vec![
+                member("x", Ty::Primitive(Primitive::U64(None)), true),
+                member("player", Ty::Primitive(Primitive::ContractAddress(None)), true),
+                member("dir", direction(&["Left", "Right", "Up"]), false),
+                member("path", Ty::Array(vec![Ty::Primitive(Primitive::U16(None))]), false),
+                member("y", Ty::Primitive(Primitive::U32(None)), false),
+            ],
+        });
+
+        let changes = diff_schema(&old, &new)
+            .into_iter()
+            .map(|change| (change.path, change.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            [
+                ("x".to_string(), TypeChangeKind::Changed),
+                ("dir::Up".to_string(), TypeChangeKind::Added),
+                ("path[]".to_string(), TypeChangeKind::Changed),
+                ("gone".to_string(), TypeChangeKind::Removed),
+                ("y".to_string(), TypeChangeKind::Added),
+                ("x".to_string(), TypeChangeKind::KeyChanged),
+            ]
+        );
+        assert!(diff_schema(&new, &new).is_empty());
+    }
+}
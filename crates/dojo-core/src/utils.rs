@@ -1,5 +1,8 @@
+use std::future::Future;
+use std::time::Duration;
+
 use anyhow::Result;
-use starknet::core::types::StarknetError;
+use starknet::core::types::{ExecutionResult, StarknetError};
 use starknet::providers::{Provider, ProviderError};
 use starknet_crypto::Felt;
 
@@ -25,3 +28,42 @@ where
         }
     }
 }
+
+/// Delay before asking again for a receipt that isn't available yet, doubled every time up to
+/// [`MAX_RECEIPT_DELAY`].
+const RECEIPT_DELAY: Duration = Duration::from_millis(250);
+const MAX_RECEIPT_DELAY: Duration = Duration::from_secs(2);
+
+/// Waits for a transaction like [`watch_tx`], returning whether it succeeded rather than
+/// reverted.
+///
+/// The receipt is requested again after a growing delay, `sleep` abstracting over the runtime
+/// like for [`drive`](crate::subscription::drive).
+pub async fn watch_tx_result<P, Z, ZF>(
+    provider: P,
+    transaction_hash: Felt,
+    sleep: Z,
+) -> Result<bool>
+where
+    P: Provider,
+    ProviderError: 'static,
+    Z: Fn(Duration) -> ZF,
+    ZF: Future<Output = ()>,
+{
+    let mut delay = RECEIPT_DELAY;
+    loop {
+        match provider.get_transaction_receipt(transaction_hash).await {
+            Ok(receipt) => {
+                return Ok(matches!(
+                    receipt.receipt.execution_result(),
+                    ExecutionResult::Succeeded
+                ));
+            }
+            Err(ProviderError::StarknetError(StarknetError::TransactionHashNotFound)) => {}
+            Err(err) => return Err(err.into()),
+        }
+
+        sleep(delay).await;
+        delay = (delay * 2).min(MAX_RECEIPT_DELAY);
+    }
+}
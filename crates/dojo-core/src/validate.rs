@@ -0,0 +1,493 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use dojo_types::primitive::Primitive;
+use dojo_types::schema::{Struct, Ty};
+use starknet_crypto::Felt;
+use torii_proto::{
+    Clause, ComparisonOperator, KeysClause, MemberClause, MemberValue, PatternMatching, Query,
+    World,
+};
+
+use crate::filter;
+
+/// A problem found by a [`Validator`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The offending clause, printed as a [`filter`]. `None` for problems outside the clause.
+    pub clause: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.clause {
+            Some(clause) => write!(f, "`{clause}`: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// Checks queries against the model schemas of worlds before they are sent.
+///
+/// Torii answers clauses naming unknown models or members, or comparing members with values
+/// stored differently, with empty results. The validator reports them instead: unknown model
+/// tags, member paths that don't resolve, operators that don't apply to the member, values
+/// that can never equal a member of its type, and keys clauses with more or fewer keys than
+/// the model has.
+#[derive(Debug, Clone, Default)]
+pub struct Validator {
+    /// Model schemas by tag, for each world
+    worlds: HashMap<Felt, HashMap<String, Struct>>,
+}
+
+impl Validator {
+    pub fn new(worlds: &[World]) -> Self {
+        let mut validator = Self::default();
+        for world in worlds {
+            for model in world.models.values() {
+                if let Ty::Struct(schema) = &model.schema {
+                    let tag = format!("{}-{}", model.namespace, model.name);
+                    validator.add_model(world.world_address, tag, schema.clone());
+                }
+            }
+        }
+        validator
+    }
+
+    /// Adds the schema of a model, identified by its tag (`namespace-name`).
+    pub fn add_model(&mut self, world_address: Felt, tag: impl Into<String>, schema: Struct) {
+        self.worlds.entry(world_address).or_default().insert(tag.into(), schema);
+    }
+
+    /// Checks the models and the clause of a query.
+    pub fn validate_query(&self, query: &Query) -> Vec<Diagnostic> {
+        let mut diagnostics = query
+            .models
+            .iter()
+            .filter(|tag| self.model(tag, &query.world_addresses).is_none())
+            .map(|tag| Diagnostic { clause: None, message: format!("unknown model `{tag}`") })
+            .collect::<Vec<_>>();
+
+        if let Some(clause) = &query.clause {
+            diagnostics.extend(self.validate_clause(clause, &query.world_addresses));
+        }
+        diagnostics
+    }
+
+    /// Checks a clause against the models of the given worlds, or of every world when empty.
+    pub fn validate_clause(&self, clause: &Clause, world_addresses: &[Felt]) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        self.check_clause(clause, world_addresses, &mut diagnostics);
+        diagnostics
+    }
+
+    fn model(&self, tag: &str, world_addresses: &[Felt]) -> Option<&Struct> {
+        self.worlds
+            .iter()
+            .filter(|(address, _)| world_addresses.is_empty() || world_addresses.contains(address))
+            .find_map(|(_, models)| models.get(tag))
+    }
+
+    fn check_clause(
+        &self,
+        clause: &Clause,
+        world_addresses: &[Felt],
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let messages = match clause {
+            Clause::HashedKeys(_) => Vec::new(),
+            Clause::Keys(keys) => self.check_keys(keys, world_addresses),
+            Clause::Member(member) => self.check_member(member, world_addresses),
+            Clause::Composite(composite) => {
+                for clause in &composite.clauses {
+                    self.check_clause(clause, world_addresses, diagnostics);
+                }
+                Vec::new()
+            }
+        };
+
+        if !messages.is_empty() {
+            let printed = filter::print(clause);
+            diagnostics.extend(
+                messages
+                    .into_iter()
+                    .map(|message| Diagnostic { clause: Some(printed.clone()), message }),
+            );
+        }
+    }
+
+    fn check_keys(&self, clause: &KeysClause, world_addresses: &[Felt]) -> Vec<String> {
+        // A clause without keys stands for a single wildcard
+        let len = clause.keys.len().max(1);
+        let mut messages = Vec::new();
+
+        for tag in &clause.models {
+            let Some(model) = self.model(tag, world_addresses) else {
+                messages.push(format!("unknown model `{tag}`"));
+                continue;
+            };
+            let Some(size) = key_size(model) else {
+                continue;
+            };
+
+            match clause.pattern_matching {
+                PatternMatching::FixedLen if len != size => messages.push(format!(
+                    "model `{tag}` has {size} key{}, the clause matches exactly {len}",
+                    plural(size)
+                )),
+                PatternMatching::VariableLen if len > size => messages.push(format!(
+                    "model `{tag}` has {size} key{}, the clause requires at least {len}",
+                    plural(size)
+                )),
+                _ => {}
+            }
+        }
+        messages
+    }
+
+    fn check_member(&self, clause: &MemberClause, world_addresses: &[Felt]) -> Vec<String> {
+        let Some(model) = self.model(&clause.model, world_addresses) else {
+            return vec![format!("unknown model `{}`", clause.model)];
+        };
+        let ty = match schema_member(model, &clause.model, &clause.member) {
+            Ok(ty) => ty,
+            Err(message) => return vec![message],
+        };
+
+        let member = &clause.member;
+        let mut messages = Vec::new();
+        match clause.operator {
+            ComparisonOperator::Eq
+            | ComparisonOperator::Neq
+            | ComparisonOperator::Gt
+            | ComparisonOperator::Gte
+            | ComparisonOperator::Lt
+            | ComparisonOperator::Lte => {
+                if !is_scalar(ty) {
+                    messages.push(format!("`{member}` is a {}, it can't be compared", ty.name()));
+                } else if let MemberValue::List(_) = clause.value {
+                    messages.push("expected a single value, found a list".to_string());
+                } else {
+                    check_value(ty, &clause.value, &mut messages);
+                }
+            }
+            ComparisonOperator::In | ComparisonOperator::NotIn => {
+                if !is_scalar(ty) {
+                    messages.push(format!("`{member}` is a {}, it can't be compared", ty.name()));
+                } else {
+                    check_values(ty, &clause.value, &mut messages);
+                }
+            }
+            ComparisonOperator::Contains => match element(ty) {
+                None => messages.push(not_an_array(member, ty)),
+                Some(_) if matches!(clause.value, MemberValue::List(_)) => {
+                    messages.push("expected a single value, found a list".to_string())
+                }
+                Some(element) => check_value(element, &clause.value, &mut messages),
+            },
+            ComparisonOperator::ContainsAll | ComparisonOperator::ContainsAny => {
+                match element(ty) {
+                    None => messages.push(not_an_array(member, ty)),
+                    Some(element) => check_values(element, &clause.value, &mut messages),
+                }
+            }
+            ComparisonOperator::ArrayLengthEq
+            | ComparisonOperator::ArrayLengthGt
+            | ComparisonOperator::ArrayLengthLt => {
+                if element(ty).is_none() {
+                    messages.push(not_an_array(member, ty));
+                } else if !is_integer_value(&clause.value) {
+                    messages.push(format!(
+                        "array lengths are compared with integers, found {}",
+                        describe(&clause.value)
+                    ));
+                }
+            }
+        }
+        messages
+    }
+}
+
+/// Member of a model schema at a dot separated path. Unlike
+/// [`member_ty`](crate::clause::member_ty), enum payloads are reachable whatever the selected
+/// variant, since schemas have none.
+fn schema_member<'a>(model: &'a Struct, tag: &str, path: &str) -> Result<&'a Ty, String> {
+    let mut segments = path.split('.');
+    let first = segments.next().unwrap_or_default();
+    let mut ty = &model
+        .children
+        .iter()
+        .find(|member| member.name == first)
+        .ok_or_else(|| {
+            format!(
+                "model `{tag}` has no member `{first}`, expected one of {}",
+                names(model.children.iter().map(|member| member.name.as_str()))
+            )
+        })?
+        .ty;
+    let mut parent = first.to_string();
+
+    for segment in segments {
+        ty = match ty {
+            Ty::Struct(s) => {
+                &s.children
+                    .iter()
+                    .find(|member| member.name == segment)
+                    .ok_or_else(|| {
+                        format!(
+                            "`{parent}` has no member `{segment}`, expected one of {}",
+                            names(s.children.iter().map(|member| member.name.as_str()))
+                        )
+                    })?
+                    .ty
+            }
+            Ty::Tuple(values) => {
+                segment.parse::<usize>().ok().and_then(|index| values.get(index)).ok_or_else(
+                    || {
+                        format!(
+                            "`{parent}` is a tuple of {} element{}, `{segment}` is not an index",
+                            values.len(),
+                            plural(values.len())
+                        )
+                    },
+                )?
+            }
+            Ty::Enum(e) => {
+                &e.options
+                    .iter()
+                    .find(|option| option.name == segment)
+                    .ok_or_else(|| {
+                        format!(
+                            "`{parent}` has no variant `{segment}`, expected one of {}",
+                            names(e.options.iter().map(|option| option.name.as_str()))
+                        )
+                    })?
+                    .ty
+            }
+            ty => return Err(format!("`{parent}` is a {}, it has no members", ty.name())),
+        };
+        parent = format!("{parent}.{segment}");
+    }
+
+    Ok(ty)
+}
+
+/// Checks every value of a list, or a single value.
+fn check_values(ty: &Ty, value: &MemberValue, messages: &mut Vec<String>) {
+    match value {
+        MemberValue::List(values) => {
+            for value in values {
+                check_value(ty, value, messages);
+            }
+        }
+        value => check_value(ty, value, messages),
+    }
+}
+
+/// Checks that a value can equal a member of the type, given how Torii stores it and binds the
+/// value, see [`matches_member`](crate::clause::matches_member).
+fn check_value(ty: &Ty, value: &MemberValue, messages: &mut Vec<String>) {
+    let valid = match (ty, value) {
+        (_, MemberValue::List(_)) => {
+            messages.push("lists can't be nested".to_string());
+            return;
+        }
+        (Ty::Primitive(primitive), value) if is_integer_column(primitive) => {
+            is_integer_value(value)
+        }
+        (Ty::Primitive(_), MemberValue::Primitive(primitive)) => !is_integer_column(primitive),
+        (Ty::Primitive(_), MemberValue::String(value)) => is_hex_text(value),
+        (Ty::Enum(e), MemberValue::String(value)) => {
+            if !e.options.iter().any(|option| &option.name == value) {
+                messages.push(format!(
+                    "`{}` has no variant `{value}`, expected one of {}",
+                    e.name,
+                    names(e.options.iter().map(|option| option.name.as_str()))
+                ));
+            }
+            return;
+        }
+        (Ty::ByteArray(_), MemberValue::String(_)) => true,
+        _ => false,
+    };
+
+    if !valid {
+        messages.push(format!("expected a {} value, found {}", ty.name(), describe(value)));
+    }
+}
+
+fn is_scalar(ty: &Ty) -> bool {
+    matches!(ty, Ty::Primitive(_) | Ty::Enum(_) | Ty::ByteArray(_))
+}
+
+fn element(ty: &Ty) -> Option<&Ty> {
+    match ty {
+        Ty::Array(values) | Ty::FixedSizeArray((values, _)) => values.first(),
+        _ => None,
+    }
+}
+
+fn not_an_array(member: &str, ty: &Ty) -> String {
+    format!("`{member}` is a {}, not an array", ty.name())
+}
+
+/// Primitives Torii stores as `INTEGER`, bound as decimal text.
+fn is_integer_column(primitive: &Primitive) -> bool {
+    matches!(
+        primitive,
+        Primitive::I8(_)
+            | Primitive::I16(_)
+            | Primitive::I32(_)
+            | Primitive::I64(_)
+            | Primitive::U8(_)
+            | Primitive::U16(_)
+            | Primitive::U32(_)
+            | Primitive::Bool(_)
+    )
+}
+
+fn is_integer_value(value: &MemberValue) -> bool {
+    match value {
+        MemberValue::Primitive(primitive) => is_integer_column(primitive),
+        MemberValue::String(value) => value.trim().parse::<i64>().is_ok(),
+        MemberValue::List(_) => false,
+    }
+}
+
+/// Text as Torii stores wide integers and felts: 0x prefixed, zero padded lowercase hex.
+fn is_hex_text(value: &str) -> bool {
+    value.strip_prefix("0x").is_some_and(|hex| {
+        hex.len() == 64 && hex.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+    })
+}
+
+fn describe(value: &MemberValue) -> String {
+    match value {
+        MemberValue::Primitive(primitive) => Ty::Primitive(*primitive).name(),
+        MemberValue::String(value) => format!("string {value:?}"),
+        MemberValue::List(_) => "a list".to_string(),
+    }
+}
+
+/// Number of felts the keys of a model serialize to, `None` when it varies.
+fn key_size(model: &Struct) -> Option<usize> {
+    model.children.iter().filter(|member| member.key).map(|member| felt_size(&member.ty)).sum()
+}
+
+fn felt_size(ty: &Ty) -> Option<usize> {
+    match ty {
+        Ty::Primitive(Primitive::U256(_)) => Some(2),
+        Ty::Primitive(_) => Some(1),
+        Ty::Struct(s) => s.children.iter().map(|member| felt_size(&member.ty)).sum(),
+        Ty::Tuple(values) => values.iter().map(felt_size).sum(),
+        Ty::FixedSizeArray((values, len)) => {
+            values.first().and_then(felt_size).map(|size| size * *len as usize)
+        }
+        Ty::Enum(_) | Ty::Array(_) | Ty::ByteArray(_) => None,
+    }
+}
+
+pub(crate) fn names<'a>(names: impl Iterator<Item = &'a str>) -> String {
+    names.map(|name| format!("`{name}`")).collect::<Vec<_>>().join(", ")
+}
+
+pub(crate) fn plural(count: usize) -> &'static str {
+    if count == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_types::schema::{Enum, EnumOption, Member};
+
+    use super::*;
+
+    fn member(name: &str, ty: Ty, key: bool) -> Member {
+        Member { name: name.to_string(), ty, key }
+    }
+
+    fn validator() -> Validator {
+        let direction = Ty::Enum(Enum {
+            name: "Direction".to_string(),
+            option: None,
+            options: vec![
+                EnumOption { name: "Left".to_string(), ty: Ty::Tuple(vec![]) },
+                EnumOption { name: "Right".to_string(), ty: Ty::Tuple(vec![]) },
+            ],
+        });
+        let vec2 = Ty::Struct(Struct {
+            name: "Vec2".to_string(),
+            children: vec![
+                member("x", Ty::Primitive(Primitive::U32(None)), false),
+                member("y", Ty::Primitive(Primitive::U32(None)), false),
+            ],
+        });
+        let schema = Struct {
+            name: "Position".to_string(),
+            children: vec![
+                member("player", Ty::Primitive(Primitive::ContractAddress(None)), true),
+                member("id", Ty::Primitive(Primitive::U256(None)), true),
+                member("vec", vec2, false),
+                member("direction", direction, false),
+                member("score", Ty::Primitive(Primitive::U64(None)), false),
+                member("items", Ty::Array(vec![Ty::Primitive(Primitive::U8(None))]), false),
+            ],
+        };
+
+        let mut validator = Validator::default();
+        validator.add_model(Felt::ONE, "ns-Position", schema);
+        validator
+    }
+
+    fn messages(filter: &str) -> Vec<String> {
+        validator()
+            .validate_clause(&filter::parse(filter).unwrap(), &[])
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    #[test]
+    fn accepts_valid_clauses() {
+        for filter in [
+            "ns-Position.vec.x > 10 and ns-Position.direction = 'Left'",
+            "ns-Position.score >= 10_u64 or ns-Position.player = 0x1_contract_address",
+            "ns-Position.items contains any [1, 2] and len(ns-Position.items) > 1",
+            "keys(ns-Position) = [0x1, *, *] or keys(ns-Position) = [0x1, ..]",
+        ] {
+            assert_eq!(messages(filter), Vec::<String>::new(), "{filter}");
+        }
+    }
+
+    #[test]
+    fn reports_invalid_clauses() {
+        assert_eq!(messages("ns-Positon.vec.x = 1"), ["unknown model `ns-Positon`"]);
+        assert_eq!(
+            messages("ns-Position.vec.z = 1"),
+            ["`vec` has no member `z`, expected one of `x`, `y`"]
+        );
+        assert_eq!(messages("ns-Position.score > 10"), ["expected a u64 value, found i64"]);
+        assert_eq!(
+            messages("ns-Position.direction in ['Up']"),
+            ["`Direction` has no variant `Up`, expected one of `Left`, `Right`"]
+        );
+        assert_eq!(messages("ns-Position.vec = 1"), ["`vec` is a Vec2, it can't be compared"]);
+        assert_eq!(
+            messages("ns-Position.score contains 1_u64"),
+            ["`score` is a u64, not an array"]
+        );
+        assert_eq!(
+            messages("keys(ns-Position) = [0x1, *]"),
+            ["model `ns-Position` has 3 keys, the clause matches exactly 2"]
+        );
+        assert_eq!(messages("keys(ns-Position) = [*, *, *, ..]"), Vec::<String>::new());
+        assert_eq!(
+            messages("keys(ns-Position) = [*, *, *, *, ..]"),
+            ["model `ns-Position` has 3 keys, the clause requires at least 4"]
+        );
+    }
+}
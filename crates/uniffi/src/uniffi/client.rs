@@ -1,10 +1,34 @@
 // Client wrapper for UniFFI - exposes torii_client functionality
 
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, OnceLock};
-
+use std::time::Duration;
+
+use dojo_core::batch;
+use dojo_core::cache::{CacheKey, Lookup, Scope};
+use dojo_core::change::EntityClassifier;
+use dojo_core::connection::Connection;
+use dojo_core::delivery::{self, Coalesce, DeliveryControl};
+use dojo_core::diff::EntityDiffer;
+use dojo_core::dispatch::Dispatcher;
+use dojo_core::history::{self, Timelines};
+use dojo_core::mux::{self, EntityFilter, Lease, Mux, TokenBalanceFilter, Topic};
+use dojo_core::optimistic::Optimistic;
+use dojo_core::pagination::{Paged, Paginator};
+use dojo_core::polling::EntitySource;
+use dojo_core::replay::{Recorder, Replay};
+use dojo_core::status::SubscriptionStatus;
+use dojo_core::subscription::{drive, drive_entities, invalidate_token_balances, without_id};
+use dojo_core::upgrade::{upgrade_clauses, SchemaWatcher};
+use futures::future::BoxFuture;
+use futures::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use stream_cancel::Tripwire;
 use tokio::runtime::Runtime;
+use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
 
 use super::types::*;
@@ -16,12 +40,37 @@ fn runtime() -> &'static Runtime {
     RUNTIME.get_or_init(|| Runtime::new().expect("Failed to create tokio runtime"))
 }
 
+// Awaits a future, failing with a network error once the given timeout elapses
+async fn with_timeout<F: Future>(
+    timeout: Option<Duration>,
+    future: F,
+) -> Result<F::Output, DojoError> {
+    match timeout {
+        Some(timeout) => {
+            tokio::time::timeout(timeout, future).await.map_err(|_| DojoError::NetworkError {
+                message: format!("request timed out after {}ms", timeout.as_millis()),
+            })
+        }
+        None => Ok(future.await),
+    }
+}
+
 // Callback traits for subscriptions
 pub trait EntityUpdateCallback: Send + Sync {
     fn on_update(&self, entity: Entity);
     fn on_error(&self, error: String);
 }
 
+pub trait EntityChangeCallback: Send + Sync {
+    fn on_change(&self, change: EntityChange);
+    fn on_error(&self, error: String);
+}
+
+pub trait EntityDiffCallback: Send + Sync {
+    fn on_change(&self, change: FieldChange);
+    fn on_error(&self, error: String);
+}
+
 pub trait TokenBalanceUpdateCallback: Send + Sync {
     fn on_update(&self, balance: TokenBalance);
     fn on_error(&self, error: String);
@@ -42,54 +91,836 @@ pub trait EventUpdateCallback: Send + Sync {
     fn on_error(&self, error: String);
 }
 
+pub trait SchemaUpgradeCallback: Send + Sync {
+    fn on_upgrade(&self, upgrade: SchemaUpgrade);
+    fn on_error(&self, error: String);
+}
+
+pub trait ModelHandler: Send + Sync {
+    fn on_model(&self, hashed_keys: FieldElement, model: Struct);
+}
+
+pub trait SubscriptionStatusCallback: Send + Sync {
+    fn on_status(&self, state: SubscriptionState);
+}
+
+/// Mirrors the state of a shared stream into a subscription's status, holding on to the lease
+/// until the stream closes or the subscription is cancelled
+async fn follow<T: Topic, S>(
+    lease: Lease<T, S>,
+    status: SubscriptionStatus,
+    mut on_connected: Box<dyn FnMut(u64) + Send>,
+) {
+    let mut states = lease.status.watch();
+    while let Some(state) = states.next().await {
+        if state == dojo_core::status::SubscriptionState::Live {
+            on_connected(lease.id);
+        }
+        status.set(state);
+    }
+}
+
+/// Consumer of a multiplexed stream
+type Sink<T> = Box<dyn FnMut(T) + Send>;
+
+struct SubscriptionHandle {
+    task: JoinHandle<()>,
+    status: SubscriptionStatus,
+    delivery: DeliveryControl,
+}
+
+/// What a subscription driver is handed by [`ToriiClient::spawn_driven`]
+struct Driver<T> {
+    connection: Arc<Connection>,
+    tripwire: Tripwire,
+    status: SubscriptionStatus,
+    /// Delivery buffer `on_item` feeds, see [`drive`]
+    pace: DeliveryControl,
+    on_connected: Box<dyn FnMut(u64) + Send>,
+    on_item: Box<dyn FnMut(T) + Send>,
+}
+
+/// Models of a world, looked up by tag or selector
+pub struct WorldSchema {
+    inner: dojo_core::schema::WorldSchema,
+}
+
+impl WorldSchema {
+    pub fn world_address(&self) -> FieldElement {
+        felt_to_field_element(self.inner.world_address)
+    }
+
+    /// Namespaces having at least one model, sorted
+    pub fn namespaces(&self) -> Vec<String> {
+        self.inner.namespaces()
+    }
+
+    /// Models and events sorted by tag, of a namespace or of every namespace when `None`
+    pub fn models(&self, namespace: Option<String>) -> Vec<ModelSchema> {
+        let models = match namespace {
+            Some(namespace) => self.inner.models_in(&namespace),
+            None => self.inner.models().iter().collect(),
+        };
+        models.into_iter().cloned().map(Into::into).collect()
+    }
+
+    /// Model by tag, e.g. `ns-Position`
+    pub fn model(&self, tag: String) -> Option<ModelSchema> {
+        self.inner.model(&tag).cloned().map(Into::into)
+    }
+
+    /// Model by selector, the Poseidon hash of its namespace and name
+    pub fn model_by_selector(
+        &self,
+        selector: FieldElement,
+    ) -> Result<Option<ModelSchema>, DojoError> {
+        let selector = field_element_to_felt(&selector)?;
+        Ok(self.inner.model_by_selector(selector).cloned().map(Into::into))
+    }
+
+    /// Builder of a model by tag, see [`TyBuilder`]
+    pub fn builder(&self, tag: String) -> Result<Arc<TyBuilder>, DojoError> {
+        let model = self.inner.model(&tag).ok_or_else(|| DojoError::TypeError {
+            message: format!("world has no model `{tag}`"),
+        })?;
+        Ok(Arc::new(TyBuilder::new(model.schema.clone().into())))
+    }
+}
+
+/// Value of a type being built from its schema, e.g. a model to publish in a message.
+///
+/// The value starts from defaults: zero for primitives, the first variant of enums, an empty
+/// string for byte arrays, no elements for arrays and default elements for fixed size arrays.
+/// Values are set by path as in `ty_get` and checked against the schema, failing with a
+/// `TypeError`. A variant in a path selects it, and the index following the last element of
+/// an array appends an element. A setter that fails leaves the value unchanged.
+pub struct TyBuilder {
+    inner: Mutex<dojo_core::builder::TyBuilder>,
+}
+
+impl TyBuilder {
+    pub fn new(schema: Ty) -> Self {
+        Self { inner: Mutex::new(dojo_core::builder::TyBuilder::new(&schema.into())) }
+    }
+
+    fn update(
+        &self,
+        set: impl FnOnce(&mut dojo_core::builder::TyBuilder) -> anyhow::Result<()>,
+    ) -> Result<(), DojoError> {
+        set(&mut self.inner.lock().unwrap())
+            .map_err(|e| DojoError::TypeError { message: e.to_string() })
+    }
+
+    /// Set the value at a path, which must have the type of the schema at that path
+    pub fn set(&self, path: String, value: Ty) -> Result<(), DojoError> {
+        self.update(|builder| builder.set(&path, value.into()))
+    }
+
+    pub fn set_u32(&self, path: String, value: u32) -> Result<(), DojoError> {
+        self.update(|builder| builder.set_u32(&path, value))
+    }
+
+    /// Set the felt252, ContractAddress, ClassHash or EthAddress at a path
+    pub fn set_felt(&self, path: String, value: FieldElement) -> Result<(), DojoError> {
+        let value = field_element_to_felt(&value)?;
+        self.update(|builder| builder.set_felt(&path, value))
+    }
+
+    pub fn set_u256(&self, path: String, value: U256) -> Result<(), DojoError> {
+        let value = uniffi_to_u256(&value)?;
+        self.update(|builder| builder.set_u256(&path, value))
+    }
+
+    pub fn set_bool(&self, path: String, value: bool) -> Result<(), DojoError> {
+        self.update(|builder| builder.set_bool(&path, value))
+    }
+
+    pub fn set_byte_array(&self, path: String, value: String) -> Result<(), DojoError> {
+        self.update(|builder| builder.set_byte_array(&path, &value))
+    }
+
+    /// Select a variant of the enum at a path, keeping the value of the variant
+    pub fn set_variant(&self, path: String, variant: String) -> Result<(), DojoError> {
+        self.update(|builder| builder.set_variant(&path, &variant))
+    }
+
+    /// Set a primitive or byte array at a path from text: decimal or 0x-prefixed hex for
+    /// numbers, `true` or `false` for booleans
+    pub fn set_str(&self, path: String, text: String) -> Result<(), DojoError> {
+        self.update(|builder| builder.set_from_str(&path, &text))
+    }
+
+    /// Value built so far
+    pub fn build(&self) -> Ty {
+        self.inner.lock().unwrap().ty().clone().into()
+    }
+
+    /// Value built so far as JSON, e.g. the message of an offchain message. Structs are
+    /// objects, unit variants their name and other variants an object of their name and value.
+    pub fn to_json(&self) -> Result<String, DojoError> {
+        let json = self.inner.lock().unwrap().to_json();
+        json.map(|json| json.to_string())
+            .map_err(|e| DojoError::TypeError { message: e.to_string() })
+    }
+
+    /// Value built so far serialized as Cairo calldata
+    pub fn calldata(&self) -> Result<Vec<FieldElement>, DojoError> {
+        let felts = self.inner.lock().unwrap().calldata();
+        felts
+            .map(|felts| felts.into_iter().map(felt_to_field_element).collect())
+            .map_err(|e| DojoError::TypeError { message: e.to_string() })
+    }
+}
+
+/// Routes entity updates to handlers registered by model
+pub struct ModelDispatcher {
+    inner: Dispatcher<Arc<dyn ModelHandler>>,
+}
+
+impl ModelDispatcher {
+    pub fn new() -> Self {
+        Self { inner: Dispatcher::new() }
+    }
+
+    /// Register a handler called whenever the given model of an entity is updated, optionally
+    /// only if the model keys match `keys`. Returns the id of the handler.
+    pub fn add_handler(
+        &self,
+        model: String,
+        keys: Option<KeysClause>,
+        handler: Box<dyn ModelHandler>,
+    ) -> u64 {
+        self.inner.register(model, keys.map(|k| k.into()), handler.into())
+    }
+
+    /// Remove a handler. Returns false if there was no handler with this id.
+    pub fn remove_handler(&self, handler_id: u64) -> bool {
+        self.inner.unregister(handler_id)
+    }
+}
+
+impl Default for ModelDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Applies predicted model changes to entity updates until their transaction settles
+pub struct OptimisticStore {
+    inner: Optimistic,
+    callback: Arc<dyn EntityUpdateCallback>,
+}
+
+impl OptimisticStore {
+    /// `callback` is called with entities whose state changed because of a prediction, or
+    /// because a prediction was rolled back or reconciled
+    pub fn new(callback: Box<dyn EntityUpdateCallback>) -> Self {
+        Self { inner: Optimistic::new(), callback: callback.into() }
+    }
+
+    /// Predict that a transaction sets the models of an entity. The models are applied to the
+    /// entity until Torii reports them, or the transaction succeeded and the entity was
+    /// updated, or the transaction reverts.
+    pub fn predict(&self, transaction_hash: FieldElement, entity: Entity) -> Result<(), DojoError> {
+        let transaction_hash = field_element_to_felt(&transaction_hash)?;
+        let view = self.inner.predict(transaction_hash, entity.into());
+        self.callback.on_update(view.into());
+        Ok(())
+    }
+
+    /// Record the outcome of a transaction once its receipt is known. Predictions of a
+    /// reverted transaction are rolled back.
+    pub fn settle(&self, transaction_hash: FieldElement, succeeded: bool) -> Result<(), DojoError> {
+        let transaction_hash = field_element_to_felt(&transaction_hash)?;
+        for view in self.inner.settle(transaction_hash, succeeded) {
+            self.callback.on_update(view.into());
+        }
+        Ok(())
+    }
+}
+
+/// Entities of a batch lookup keyed by id
+fn entity_lookup(lookup: batch::Lookup) -> HashMap<String, Option<Entity>> {
+    lookup
+        .into_iter()
+        .map(|(id, entity)| (felt_to_field_element(id).0, entity.map(Into::into)))
+        .collect()
+}
+
+/// Fetches the page of a query from the active endpoint
+type FetchPage<Q, T> =
+    Box<dyn Fn(Q) -> BoxFuture<'static, anyhow::Result<torii_proto::Page<T>>> + Send + Sync>;
+
+/// Walks the pages of a query for the typed iterators below
+struct PageIter<Q, T> {
+    connection: Arc<Connection>,
+    paginator: Mutex<Paginator<Q, T, FetchPage<Q, T>>>,
+    /// Fetches the next page in the background once a page is returned
+    prefetch: bool,
+}
+
+impl<Q, T> PageIter<Q, T>
+where
+    Q: Paged + Serialize + Send + 'static,
+    T: Serialize + DeserializeOwned + Send + 'static,
+{
+    /// Pages are requested as `kind` requests, see [`Connection::exchange`].
+    fn new<F, Fut, E>(
+        connection: Arc<Connection>,
+        kind: &'static str,
+        query: Q,
+        prefetch: bool,
+        fetch: F,
+    ) -> Self
+    where
+        F: Fn(Arc<torii_client::Client>, Q) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<torii_proto::Page<T>, E>> + Send + 'static,
+        anyhow::Error: From<E>,
+    {
+        let fetch_connection = connection.clone();
+        let fetch = Arc::new(fetch);
+        let fetch: FetchPage<Q, T> = Box::new(move |query| {
+            let (connection, fetch) = (fetch_connection.clone(), fetch.clone());
+            let key = CacheKey::new(kind, &query);
+            Box::pin(async move { connection.exchange(key, |inner| fetch(inner, query)).await })
+        });
+
+        Self { connection, paginator: Mutex::new(Paginator::new(query, fetch)), prefetch }
+    }
+
+    /// Blocks on a step of the paginator, bounded by the configured request timeout
+    fn block_on<R>(&self, future: impl Future<Output = anyhow::Result<R>>) -> Result<R, DojoError> {
+        let result =
+            runtime().block_on(with_timeout(self.connection.config().request_timeout, future));
+        if !matches!(result, Ok(Ok(_))) {
+            runtime().block_on(self.connection.pool().recover_from_request());
+        }
+
+        result?.map_err(|e| DojoError::QueryError { message: e.to_string() })
+    }
+
+    fn prefetch(&self, paginator: &mut Paginator<Q, T, FetchPage<Q, T>>) {
+        if self.prefetch {
+            if let Some(task) = paginator.prefetch() {
+                runtime().spawn(task);
+            }
+        }
+    }
+
+    fn next_page<U: From<T>>(&self) -> Result<Option<(Vec<U>, Option<String>)>, DojoError> {
+        let mut paginator = self.paginator.lock().unwrap();
+        let page = self.block_on(paginator.next_page());
+        self.prefetch(&mut paginator);
+        Ok(page?.map(|page| (page.items.into_iter().map(Into::into).collect(), page.next_cursor)))
+    }
+
+    fn previous_page<U: From<T>>(&self) -> Result<Option<(Vec<U>, Option<String>)>, DojoError> {
+        let mut paginator = self.paginator.lock().unwrap();
+        let page = self.block_on(paginator.previous_page());
+        self.prefetch(&mut paginator);
+        Ok(page?.map(|page| (page.items.into_iter().map(Into::into).collect(), page.next_cursor)))
+    }
+
+    fn next_item<U: From<T>>(&self) -> Result<Option<U>, DojoError> {
+        let mut paginator = self.paginator.lock().unwrap();
+        let item = self.block_on(paginator.next_item());
+        self.prefetch(&mut paginator);
+        Ok(item?.map(Into::into))
+    }
+}
+
+/// Iterator over every page of entities matching a query
+pub struct EntityIterator {
+    inner: PageIter<torii_proto::Query, torii_proto::schema::Entity>,
+}
+
+impl EntityIterator {
+    /// Fetch the page after the last one returned, none once every page was returned
+    pub fn next_page(&self) -> Result<Option<PageEntity>, DojoError> {
+        let page = self.inner.next_page()?;
+        Ok(page.map(|(items, next_cursor)| PageEntity { items, next_cursor }))
+    }
+
+    /// Fetch again the page before the last one returned, none when on the first page
+    pub fn previous_page(&self) -> Result<Option<PageEntity>, DojoError> {
+        let page = self.inner.previous_page()?;
+        Ok(page.map(|(items, next_cursor)| PageEntity { items, next_cursor }))
+    }
+
+    /// Next item, fetching the next page when needed, none once every item was returned
+    pub fn next_item(&self) -> Result<Option<Entity>, DojoError> {
+        self.inner.next_item()
+    }
+}
+
+/// Iterator over every page of controllers matching a query
+pub struct ControllerIterator {
+    inner: PageIter<torii_proto::ControllerQuery, torii_proto::Controller>,
+}
+
+impl ControllerIterator {
+    /// Fetch the page after the last one returned, none once every page was returned
+    pub fn next_page(&self) -> Result<Option<PageController>, DojoError> {
+        let page = self.inner.next_page()?;
+        Ok(page.map(|(items, next_cursor)| PageController { items, next_cursor }))
+    }
+
+    /// Fetch again the page before the last one returned, none when on the first page
+    pub fn previous_page(&self) -> Result<Option<PageController>, DojoError> {
+        let page = self.inner.previous_page()?;
+        Ok(page.map(|(items, next_cursor)| PageController { items, next_cursor }))
+    }
+
+    /// Next item, fetching the next page when needed, none once every item was returned
+    pub fn next_item(&self) -> Result<Option<Controller>, DojoError> {
+        self.inner.next_item()
+    }
+}
+
+/// Iterator over every page of tokens matching a query
+pub struct TokenIterator {
+    inner: PageIter<torii_proto::TokenQuery, torii_proto::Token>,
+}
+
+impl TokenIterator {
+    /// Fetch the page after the last one returned, none once every page was returned
+    pub fn next_page(&self) -> Result<Option<PageToken>, DojoError> {
+        let page = self.inner.next_page()?;
+        Ok(page.map(|(items, next_cursor)| PageToken { items, next_cursor }))
+    }
+
+    /// Fetch again the page before the last one returned, none when on the first page
+    pub fn previous_page(&self) -> Result<Option<PageToken>, DojoError> {
+        let page = self.inner.previous_page()?;
+        Ok(page.map(|(items, next_cursor)| PageToken { items, next_cursor }))
+    }
+
+    /// Next item, fetching the next page when needed, none once every item was returned
+    pub fn next_item(&self) -> Result<Option<Token>, DojoError> {
+        self.inner.next_item()
+    }
+}
+
+/// Iterator over every page of token balances matching a query
+pub struct TokenBalanceIterator {
+    inner: PageIter<torii_proto::TokenBalanceQuery, torii_proto::TokenBalance>,
+}
+
+impl TokenBalanceIterator {
+    /// Fetch the page after the last one returned, none once every page was returned
+    pub fn next_page(&self) -> Result<Option<PageTokenBalance>, DojoError> {
+        let page = self.inner.next_page()?;
+        Ok(page.map(|(items, next_cursor)| PageTokenBalance { items, next_cursor }))
+    }
+
+    /// Fetch again the page before the last one returned, none when on the first page
+    pub fn previous_page(&self) -> Result<Option<PageTokenBalance>, DojoError> {
+        let page = self.inner.previous_page()?;
+        Ok(page.map(|(items, next_cursor)| PageTokenBalance { items, next_cursor }))
+    }
+
+    /// Next item, fetching the next page when needed, none once every item was returned
+    pub fn next_item(&self) -> Result<Option<TokenBalance>, DojoError> {
+        self.inner.next_item()
+    }
+}
+
+/// Iterator over every page of token transfers matching a query
+pub struct TokenTransferIterator {
+    inner: PageIter<torii_proto::TokenTransferQuery, torii_proto::TokenTransfer>,
+}
+
+impl TokenTransferIterator {
+    /// Fetch the page after the last one returned, none once every page was returned
+    pub fn next_page(&self) -> Result<Option<PageTokenTransfer>, DojoError> {
+        let page = self.inner.next_page()?;
+        Ok(page.map(|(items, next_cursor)| PageTokenTransfer { items, next_cursor }))
+    }
+
+    /// Fetch again the page before the last one returned, none when on the first page
+    pub fn previous_page(&self) -> Result<Option<PageTokenTransfer>, DojoError> {
+        let page = self.inner.previous_page()?;
+        Ok(page.map(|(items, next_cursor)| PageTokenTransfer { items, next_cursor }))
+    }
+
+    /// Next item, fetching the next page when needed, none once every item was returned
+    pub fn next_item(&self) -> Result<Option<TokenTransfer>, DojoError> {
+        self.inner.next_item()
+    }
+}
+
+/// Iterator over every page of transactions matching a query
+pub struct TransactionIterator {
+    inner: PageIter<torii_proto::TransactionQuery, torii_proto::Transaction>,
+}
+
+impl TransactionIterator {
+    /// Fetch the page after the last one returned, none once every page was returned
+    pub fn next_page(&self) -> Result<Option<PageTransaction>, DojoError> {
+        let page = self.inner.next_page()?;
+        Ok(page.map(|(items, next_cursor)| PageTransaction { items, next_cursor }))
+    }
+
+    /// Fetch again the page before the last one returned, none when on the first page
+    pub fn previous_page(&self) -> Result<Option<PageTransaction>, DojoError> {
+        let page = self.inner.previous_page()?;
+        Ok(page.map(|(items, next_cursor)| PageTransaction { items, next_cursor }))
+    }
+
+    /// Next item, fetching the next page when needed, none once every item was returned
+    pub fn next_item(&self) -> Result<Option<Transaction>, DojoError> {
+        self.inner.next_item()
+    }
+}
+
+/// Iterator over every page of activities matching a query
+pub struct ActivityIterator {
+    inner: PageIter<torii_proto::ActivityQuery, torii_proto::Activity>,
+}
+
+impl ActivityIterator {
+    /// Fetch the page after the last one returned, none once every page was returned
+    pub fn next_page(&self) -> Result<Option<PageActivity>, DojoError> {
+        let page = self.inner.next_page()?;
+        Ok(page.map(|(items, next_cursor)| PageActivity { items, next_cursor }))
+    }
+
+    /// Fetch again the page before the last one returned, none when on the first page
+    pub fn previous_page(&self) -> Result<Option<PageActivity>, DojoError> {
+        let page = self.inner.previous_page()?;
+        Ok(page.map(|(items, next_cursor)| PageActivity { items, next_cursor }))
+    }
+
+    /// Next item, fetching the next page when needed, none once every item was returned
+    pub fn next_item(&self) -> Result<Option<Activity>, DojoError> {
+        self.inner.next_item()
+    }
+}
+
+/// Iterator over every page of achievements matching a query
+pub struct AchievementIterator {
+    inner: PageIter<torii_proto::AchievementQuery, torii_proto::Achievement>,
+}
+
+impl AchievementIterator {
+    /// Fetch the page after the last one returned, none once every page was returned
+    pub fn next_page(&self) -> Result<Option<PageAchievement>, DojoError> {
+        let page = self.inner.next_page()?;
+        Ok(page.map(|(items, next_cursor)| PageAchievement { items, next_cursor }))
+    }
+
+    /// Fetch again the page before the last one returned, none when on the first page
+    pub fn previous_page(&self) -> Result<Option<PageAchievement>, DojoError> {
+        let page = self.inner.previous_page()?;
+        Ok(page.map(|(items, next_cursor)| PageAchievement { items, next_cursor }))
+    }
+
+    /// Next item, fetching the next page when needed, none once every item was returned
+    pub fn next_item(&self) -> Result<Option<Achievement>, DojoError> {
+        self.inner.next_item()
+    }
+}
+
+/// Iterator over every page of player achievements matching a query
+pub struct PlayerAchievementIterator {
+    inner: PageIter<torii_proto::PlayerAchievementQuery, torii_proto::PlayerAchievementEntry>,
+}
+
+impl PlayerAchievementIterator {
+    /// Fetch the page after the last one returned, none once every page was returned
+    pub fn next_page(&self) -> Result<Option<PagePlayerAchievement>, DojoError> {
+        let page = self.inner.next_page()?;
+        Ok(page.map(|(items, next_cursor)| PagePlayerAchievement { items, next_cursor }))
+    }
+
+    /// Fetch again the page before the last one returned, none when on the first page
+    pub fn previous_page(&self) -> Result<Option<PagePlayerAchievement>, DojoError> {
+        let page = self.inner.previous_page()?;
+        Ok(page.map(|(items, next_cursor)| PagePlayerAchievement { items, next_cursor }))
+    }
+
+    /// Next item, fetching the next page when needed, none once every item was returned
+    pub fn next_item(&self) -> Result<Option<PlayerAchievementEntry>, DojoError> {
+        self.inner.next_item()
+    }
+}
+
+/// Iterator over every page of aggregation entries matching a query
+pub struct AggregationIterator {
+    inner: PageIter<torii_proto::AggregationQuery, torii_proto::AggregationEntry>,
+}
+
+impl AggregationIterator {
+    /// Fetch the page after the last one returned, none once every page was returned
+    pub fn next_page(&self) -> Result<Option<PageAggregationEntry>, DojoError> {
+        let page = self.inner.next_page()?;
+        Ok(page.map(|(items, next_cursor)| PageAggregationEntry { items, next_cursor }))
+    }
+
+    /// Fetch again the page before the last one returned, none when on the first page
+    pub fn previous_page(&self) -> Result<Option<PageAggregationEntry>, DojoError> {
+        let page = self.inner.previous_page()?;
+        Ok(page.map(|(items, next_cursor)| PageAggregationEntry { items, next_cursor }))
+    }
+
+    /// Next item, fetching the next page when needed, none once every item was returned
+    pub fn next_item(&self) -> Result<Option<AggregationEntry>, DojoError> {
+        self.inner.next_item()
+    }
+}
+
 /// Main Dojo client for interacting with the Torii indexer
 pub struct ToriiClient {
-    inner: Arc<torii_client::Client>,
-    subscriptions: Arc<Mutex<HashMap<u64, JoinHandle<()>>>>,
+    inner: Arc<Connection>,
+    subscriptions: Arc<Mutex<HashMap<u64, SubscriptionHandle>>>,
     next_sub_id: Arc<AtomicU64>,
+    entities: Mux<mux::Entities, Sink<torii_proto::schema::Entity>>,
+    token_balances: Mux<mux::TokenBalances, Sink<torii_proto::TokenBalance>>,
 }
 
 impl ToriiClient {
     /// Create a new Torii client with default configuration (4MB max message size)
     pub fn new(torii_url: String) -> Result<Self, DojoError> {
         let client = runtime()
-            .block_on(torii_client::Client::new(torii_url))
+            .block_on(torii_client::Client::new(torii_url.clone()))
             .map_err(|_e| DojoError::ConnectionError)?;
 
-        Ok(Self {
-            inner: Arc::new(client),
-            subscriptions: Arc::new(Mutex::new(HashMap::new())),
-            next_sub_id: Arc::new(AtomicU64::new(0)),
-        })
+        Ok(Self::from_connection(Connection::from_client(
+            client,
+            dojo_core::config::ClientConfig::new(torii_url),
+        )))
     }
 
     /// Create a new Torii client with custom max message size
     pub fn new_with_config(torii_url: String, max_message_size: u64) -> Result<Self, DojoError> {
+        let mut config = dojo_core::config::ClientConfig::new(torii_url.clone());
+        config.max_message_size = max_message_size as usize;
+
         let client = runtime()
             .block_on(torii_client::Client::new_with_config(torii_url, max_message_size as usize))
             .map_err(|_e| DojoError::ConnectionError)?;
 
-        Ok(Self {
-            inner: Arc::new(client),
+        Ok(Self::from_connection(Connection::from_client(client, config)))
+    }
+
+    /// Create a new Torii client from a full configuration (fallback endpoints, timeouts and
+    /// retry policy)
+    ///
+    /// With fallback endpoints, queries and subscriptions fail over to the healthiest replica
+    /// whenever the active endpoint stops responding or falls behind.
+    pub fn from_config(config: ClientConfig) -> Result<Self, DojoError> {
+        let config: dojo_core::config::ClientConfig = config.into();
+        config.validate().map_err(|e| DojoError::ClientError { message: e.to_string() })?;
+
+        let connection = runtime()
+            .block_on(with_timeout(config.connect_timeout, Connection::connect(config.clone())))?
+            .map_err(|_e| DojoError::ConnectionError)?;
+
+        Ok(Self::from_connection(connection))
+    }
+
+    /// Create a new Torii client recording its session to a file, replaced if it exists
+    ///
+    /// Every query response and subscription message is recorded with the time it came in, so
+    /// that the session can be played back with `new_replay`.
+    pub fn new_recording(config: ClientConfig, path: String) -> Result<Self, DojoError> {
+        let recorder = Recorder::create(path)
+            .map_err(|e| DojoError::ClientError { message: e.to_string() })?;
+        let config: dojo_core::config::ClientConfig = config.into();
+        config.validate().map_err(|e| DojoError::ClientError { message: e.to_string() })?;
+
+        let connection = runtime()
+            .block_on(with_timeout(config.connect_timeout, Connection::connect(config.clone())))?
+            .map_err(|_e| DojoError::ConnectionError)?;
+
+        Ok(Self::from_connection(connection.with_recorder(recorder)))
+    }
+
+    /// Create a Torii client playing back a session recorded by `new_recording`, without a
+    /// network
+    ///
+    /// Queries get the recorded responses and subscriptions the recorded messages, `speed`
+    /// times faster than they came in (infinity plays them without delays). The replay is
+    /// deterministic as long as the same queries are made and the subscriptions are opened in
+    /// the same order as in the recorded session, by a client configured alike.
+    pub fn new_replay(config: ClientConfig, path: String, speed: f64) -> Result<Self, DojoError> {
+        let replay = Replay::open(path, speed)
+            .map_err(|e| DojoError::ClientError { message: e.to_string() })?;
+
+        Ok(Self::from_connection(Connection::replay(config.into(), replay)))
+    }
+
+    fn from_connection(connection: Connection) -> Self {
+        Self {
+            inner: Arc::new(connection),
             subscriptions: Arc::new(Mutex::new(HashMap::new())),
             next_sub_id: Arc::new(AtomicU64::new(0)),
-        })
+            entities: Mux::new(mux::Entities),
+            token_balances: Mux::new(mux::TokenBalances),
+        }
+    }
+
+    /// Blocks on a Torii request, bounded by the configured request timeout
+    ///
+    /// A request that reached Torii but went unanswered triggers a health probe of the active
+    /// endpoint, so that the next one is routed to a healthy replica when fallback endpoints
+    /// are configured.
+    fn block_on<F, T, E>(&self, future: F) -> Result<Result<T, E>, DojoError>
+    where
+        F: Future<Output = Result<T, E>>,
+    {
+        let result = runtime().block_on(with_timeout(self.inner.config().request_timeout, future));
+        if !matches!(result, Ok(Ok(_))) {
+            runtime().block_on(self.inner.pool().recover_from_request());
+        }
+
+        result
+    }
+
+    /// Fetches the history of entities from the active endpoint, see
+    /// [`history::fetch_timelines`]
+    fn timelines<'a>(
+        &self,
+        world_addresses: &'a [starknet::core::types::Felt],
+        ids: &'a [starknet::core::types::Felt],
+        models: Vec<String>,
+    ) -> impl Future<Output = anyhow::Result<Timelines>> + 'a {
+        let connection = &self.inner;
+        history::fetch_timelines(
+            world_addresses,
+            ids,
+            models,
+            move |query| {
+                let key = CacheKey::normalized("entities", &query);
+                connection.exchange(key, |inner| async move { inner.entities(query).await })
+            },
+            move |query| {
+                let key = CacheKey::new("sql", &query);
+                connection.exchange(key, |inner| async move { inner.sql(query).await })
+            },
+        )
+    }
+
+    /// Runs a query through the client's [`QueryCache`](dojo_core::cache::QueryCache) when one
+    /// is configured
+    ///
+    /// Expired results are served right away when the cache revalidates them in the background.
+    fn cached<T, F, Fut, E>(
+        &self,
+        key: CacheKey,
+        scope: impl FnOnce(&T) -> Scope + Send + 'static,
+        fetch: F,
+    ) -> Result<anyhow::Result<T>, DojoError>
+    where
+        T: Clone + Serialize + DeserializeOwned + Send + Sync + 'static,
+        F: FnOnce(Arc<torii_client::Client>) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+        anyhow::Error: From<E>,
+    {
+        let Some(cache) = self.inner.cache() else {
+            return self.block_on(self.inner.exchange(key, fetch));
+        };
+
+        match cache.lookup::<T>(&key) {
+            Lookup::Fresh(value) | Lookup::Stale(value, None) => Ok(Ok(value)),
+            Lookup::Stale(value, Some(ticket)) => {
+                let (cache, connection) = (cache.clone(), self.inner.clone());
+                runtime().spawn(async move {
+                    if let Ok(fresh) = connection.exchange(key, fetch).await {
+                        cache.store(ticket, scope(&fresh), fresh);
+                    }
+                });
+                Ok(Ok(value))
+            }
+            Lookup::Miss(ticket) => {
+                let result = self.block_on(self.inner.exchange(key, fetch))?;
+                if let Ok(value) = &result {
+                    cache.store(ticket, scope(value), value.clone());
+                }
+                Ok(result)
+            }
+        }
+    }
+
+    /// Probe every configured endpoint and return their health
+    ///
+    /// Switches to a healthier replica if the active endpoint is down or lagging behind.
+    pub fn endpoints(&self) -> Vec<EndpointStatus> {
+        runtime().block_on(self.inner.pool().recover());
+        self.inner.pool().statuses().into_iter().map(|s| s.into()).collect()
+    }
+
+    /// Statistics of the query cache, all zero when the client was created without one
+    pub fn cache_stats(&self) -> CacheStats {
+        self.inner.cache().map(|cache| cache.stats()).unwrap_or_default().into()
+    }
+
+    /// Check a query against the model schemas of the client's worlds
+    ///
+    /// Reports unknown models, unresolved member paths, operators that don't apply to a member,
+    /// values that can't match it and key counts that differ from the model's.
+    pub fn validate_query(&self, query: Query) -> Result<Vec<Diagnostic>, DojoError> {
+        let validator = runtime().block_on(self.inner.validator())?;
+        let diagnostics = validator.validate_query(&query.into());
+        Ok(diagnostics.into_iter().map(Into::into).collect())
+    }
+
+    /// Get the schema of a world, to list its namespaces and models and describe their members
+    pub fn world_schema(&self, world_address: FieldElement) -> Result<Arc<WorldSchema>, DojoError> {
+        let world_addresses = vec![field_element_to_felt(&world_address)?];
+        let key = CacheKey::normalized("worlds", &world_addresses);
+        let worlds = self
+            .block_on(
+                self.inner
+                    .exchange(key, |inner| async move { inner.worlds(world_addresses).await }),
+            )?
+            .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
+
+        let world = worlds.first().ok_or_else(|| DojoError::QueryError {
+            message: format!("world {} not found", world_address.0),
+        })?;
+        Ok(Arc::new(WorldSchema { inner: dojo_core::schema::WorldSchema::new(world) }))
+    }
+
+    /// Checks a clause against the world schemas when `validate_queries` is configured
+    fn validate_clause(
+        &self,
+        clause: Option<&torii_proto::Clause>,
+        world_addresses: &[starknet::core::types::Felt],
+    ) -> Result<(), DojoError> {
+        runtime()
+            .block_on(self.inner.validate_clause(clause, world_addresses))
+            .map_err(|e| DojoError::QueryError { message: e.to_string() })
+    }
+
+    /// Drop every result of the query cache
+    pub fn clear_cache(&self) {
+        if let Some(cache) = self.inner.cache() {
+            cache.clear();
+        }
     }
 
     /// Publish an offchain message to the world
     /// Returns the entity ID of the published message
     pub fn publish_message(&self, message: Message) -> Result<String, DojoError> {
         let msg: torii_proto::Message = message.into();
-        let inner = self.inner.clone();
-        runtime().block_on(inner.publish_message(msg)).map_err(|_| DojoError::PublishError)
+        let key = CacheKey::new("publish_message", &msg);
+        self.block_on(
+            self.inner.exchange(key, |inner| async move { inner.publish_message(msg).await }),
+        )?
+        .map_err(|_| DojoError::PublishError)
     }
 
     /// Publish multiple offchain messages to the world
     /// Returns the entity IDs of the published messages
     pub fn publish_message_batch(&self, messages: Vec<Message>) -> Result<Vec<String>, DojoError> {
         let msgs: Vec<torii_proto::Message> = messages.into_iter().map(|m| m.into()).collect();
-        let inner = self.inner.clone();
-        runtime().block_on(inner.publish_message_batch(msgs)).map_err(|_| DojoError::PublishError)
+        let key = CacheKey::new("publish_message_batch", &msgs);
+        self.block_on(
+            self.inner
+                .exchange(key, |inner| async move { inner.publish_message_batch(msgs).await }),
+        )?
+        .map_err(|_| DojoError::PublishError)
     }
 
     /// Get world metadata for specified world addresses
@@ -98,9 +929,9 @@ impl ToriiClient {
             world_addresses.iter().map(field_element_to_felt).collect();
         let addrs = addrs?;
 
-        let inner = self.inner.clone();
-        let worlds = runtime()
-            .block_on(inner.worlds(addrs))
+        let key = CacheKey::normalized("worlds", &addrs);
+        let worlds = self
+            .block_on(self.inner.exchange(key, |inner| async move { inner.worlds(addrs).await }))?
             .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
 
         Ok(worlds.into_iter().map(|w| w.into()).collect())
@@ -109,9 +940,16 @@ impl ToriiClient {
     /// Retrieve controllers matching the query
     pub fn controllers(&self, query: ControllerQuery) -> Result<PageController, DojoError> {
         let q: torii_proto::ControllerQuery = query.into();
-        let inner = self.inner.clone();
-        let page = runtime()
-            .block_on(inner.controllers(q))
+        let key = CacheKey::new("controllers", &q);
+        let page = self
+            .cached(
+                key,
+                |_| Scope::Unscoped,
+                move |inner| {
+                    let q = q.clone();
+                    async move { inner.controllers(q).await }
+                },
+            )?
             .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
 
         Ok(PageController {
@@ -123,9 +961,9 @@ impl ToriiClient {
     /// Retrieve contracts matching the query
     pub fn contracts(&self, query: ContractQuery) -> Result<Vec<Contract>, DojoError> {
         let q: torii_proto::ContractQuery = query.into();
-        let inner = self.inner.clone();
-        let contracts = runtime()
-            .block_on(inner.contracts(q))
+        let key = CacheKey::new("contracts", &q);
+        let contracts = self
+            .block_on(self.inner.exchange(key, |inner| async move { inner.contracts(q).await }))?
             .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
 
         Ok(contracts.into_iter().map(|c| c.into()).collect())
@@ -134,9 +972,16 @@ impl ToriiClient {
     /// Retrieve tokens matching the query
     pub fn tokens(&self, query: TokenQuery) -> Result<PageToken, DojoError> {
         let q: torii_proto::TokenQuery = query.into();
-        let inner = self.inner.clone();
-        let page = runtime()
-            .block_on(inner.tokens(q))
+        let (key, scope) = (CacheKey::normalized("tokens", &q), Scope::tokens(&q));
+        let page = self
+            .cached(
+                key,
+                move |_| scope,
+                move |inner| {
+                    let q = q.clone();
+                    async move { inner.tokens(q).await }
+                },
+            )?
             .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
 
         Ok(PageToken {
@@ -148,9 +993,16 @@ impl ToriiClient {
     /// Retrieve token balances
     pub fn token_balances(&self, query: TokenBalanceQuery) -> Result<PageTokenBalance, DojoError> {
         let q: torii_proto::TokenBalanceQuery = query.into();
-        let inner = self.inner.clone();
-        let page = runtime()
-            .block_on(inner.token_balances(q))
+        let (key, scope) = (CacheKey::normalized("token_balances", &q), Scope::token_balances(&q));
+        let page = self
+            .cached(
+                key,
+                move |_| scope,
+                move |inner| {
+                    let q = q.clone();
+                    async move { inner.token_balances(q).await }
+                },
+            )?
             .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
 
         Ok(PageTokenBalance {
@@ -165,9 +1017,11 @@ impl ToriiClient {
         query: TokenContractQuery,
     ) -> Result<PageTokenContract, DojoError> {
         let q: torii_proto::TokenContractQuery = query.into();
-        let inner = self.inner.clone();
-        let page = runtime()
-            .block_on(inner.token_contracts(q))
+        let key = CacheKey::new("token_contracts", &q);
+        let page = self
+            .block_on(
+                self.inner.exchange(key, |inner| async move { inner.token_contracts(q).await }),
+            )?
             .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
 
         Ok(PageTokenContract {
@@ -182,9 +1036,11 @@ impl ToriiClient {
         query: TokenTransferQuery,
     ) -> Result<PageTokenTransfer, DojoError> {
         let q: torii_proto::TokenTransferQuery = query.into();
-        let inner = self.inner.clone();
-        let page = runtime()
-            .block_on(inner.token_transfers(q))
+        let key = CacheKey::new("token_transfers", &q);
+        let page = self
+            .block_on(
+                self.inner.exchange(key, |inner| async move { inner.token_transfers(q).await }),
+            )?
             .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
 
         Ok(PageTokenTransfer {
@@ -196,9 +1052,9 @@ impl ToriiClient {
     /// Retrieve transactions
     pub fn transactions(&self, query: TransactionQuery) -> Result<PageTransaction, DojoError> {
         let q: torii_proto::TransactionQuery = query.into();
-        let inner = self.inner.clone();
-        let page = runtime()
-            .block_on(inner.transactions(q))
+        let key = CacheKey::new("transactions", &q);
+        let page = self
+            .block_on(self.inner.exchange(key, |inner| async move { inner.transactions(q).await }))?
             .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
 
         Ok(PageTransaction {
@@ -210,9 +1066,9 @@ impl ToriiClient {
     /// Retrieve aggregations (leaderboards, stats, rankings)
     pub fn aggregations(&self, query: AggregationQuery) -> Result<PageAggregationEntry, DojoError> {
         let q: torii_proto::AggregationQuery = query.into();
-        let inner = self.inner.clone();
-        let page = runtime()
-            .block_on(inner.aggregations(q))
+        let key = CacheKey::new("aggregations", &q);
+        let page = self
+            .block_on(self.inner.exchange(key, |inner| async move { inner.aggregations(q).await }))?
             .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
 
         Ok(PageAggregationEntry {
@@ -224,9 +1080,9 @@ impl ToriiClient {
     /// Retrieve activities (user session tracking)
     pub fn activities(&self, query: ActivityQuery) -> Result<PageActivity, DojoError> {
         let q: torii_proto::ActivityQuery = query.into();
-        let inner = self.inner.clone();
-        let page = runtime()
-            .block_on(inner.activities(q))
+        let key = CacheKey::new("activities", &q);
+        let page = self
+            .block_on(self.inner.exchange(key, |inner| async move { inner.activities(q).await }))?
             .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
 
         Ok(PageActivity {
@@ -238,9 +1094,9 @@ impl ToriiClient {
     /// Retrieve achievements
     pub fn achievements(&self, query: AchievementQuery) -> Result<PageAchievement, DojoError> {
         let q: torii_proto::AchievementQuery = query.into();
-        let inner = self.inner.clone();
-        let page = runtime()
-            .block_on(inner.achievements(q))
+        let key = CacheKey::new("achievements", &q);
+        let page = self
+            .block_on(self.inner.exchange(key, |inner| async move { inner.achievements(q).await }))?
             .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
 
         Ok(PageAchievement {
@@ -255,9 +1111,11 @@ impl ToriiClient {
         query: PlayerAchievementQuery,
     ) -> Result<PagePlayerAchievement, DojoError> {
         let q: torii_proto::PlayerAchievementQuery = query.into();
-        let inner = self.inner.clone();
-        let page = runtime()
-            .block_on(inner.player_achievements(q))
+        let key = CacheKey::new("player_achievements", &q);
+        let page = self
+            .block_on(
+                self.inner.exchange(key, |inner| async move { inner.player_achievements(q).await }),
+            )?
             .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
 
         Ok(PagePlayerAchievement {
@@ -268,11 +1126,26 @@ impl ToriiClient {
 
     /// Retrieve entities matching the query
     pub fn entities(&self, query: Query) -> Result<PageEntity, DojoError> {
-        let q: torii_proto::Query = query.into();
-        let inner = self.inner.clone();
-        let page = runtime()
-            .block_on(inner.entities(q))
+        let projection = query.projection();
+        let mut q: torii_proto::Query = query.into();
+        if let Some(projection) = &projection {
+            projection.apply_query(&mut q);
+        }
+        self.validate_clause(q.clause.as_ref(), &q.world_addresses)?;
+        let (key, scope_query) = (CacheKey::normalized("entities", &q), q.clone());
+        let mut page = self
+            .cached(
+                key,
+                move |page| Scope::entities(EntitySource::Entities, &scope_query, page),
+                move |inner| {
+                    let q = q.clone();
+                    async move { inner.entities(q).await }
+                },
+            )?
             .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
+        if let Some(projection) = &projection {
+            projection.apply_page(&mut page);
+        }
 
         Ok(PageEntity {
             items: page.items.into_iter().map(|e| e.into()).collect(),
@@ -282,11 +1155,26 @@ impl ToriiClient {
 
     /// Retrieve event messages matching the query
     pub fn event_messages(&self, query: Query) -> Result<PageEntity, DojoError> {
-        let q: torii_proto::Query = query.into();
-        let inner = self.inner.clone();
-        let page = runtime()
-            .block_on(inner.event_messages(q))
+        let projection = query.projection();
+        let mut q: torii_proto::Query = query.into();
+        if let Some(projection) = &projection {
+            projection.apply_query(&mut q);
+        }
+        self.validate_clause(q.clause.as_ref(), &q.world_addresses)?;
+        let (key, scope_query) = (CacheKey::normalized("event_messages", &q), q.clone());
+        let mut page = self
+            .cached(
+                key,
+                move |page| Scope::entities(EntitySource::EventMessages, &scope_query, page),
+                move |inner| {
+                    let q = q.clone();
+                    async move { inner.event_messages(q).await }
+                },
+            )?
             .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
+        if let Some(projection) = &projection {
+            projection.apply_page(&mut page);
+        }
 
         Ok(PageEntity {
             items: page.items.into_iter().map(|e| e.into()).collect(),
@@ -294,12 +1182,311 @@ impl ToriiClient {
         })
     }
 
+    /// Look up entities by id, i.e. hashed keys, requested in chunks running concurrently
+    ///
+    /// Returns the entity of each id, none when Torii doesn't know it.
+    pub fn entities_by_ids(
+        &self,
+        world_addresses: Vec<FieldElement>,
+        ids: Vec<FieldElement>,
+    ) -> Result<HashMap<String, Option<Entity>>, DojoError> {
+        let world_addresses =
+            world_addresses.iter().map(field_element_to_felt).collect::<Result<Vec<_>, _>>()?;
+        let ids = ids.iter().map(field_element_to_felt).collect::<Result<Vec<_>, _>>()?;
+
+        let connection = &self.inner;
+        let max_message_size = connection.config().max_message_size;
+        let lookup = self
+            .block_on(batch::entities_by_ids(
+                &world_addresses,
+                &ids,
+                Vec::new(),
+                max_message_size,
+                |query| {
+                    let key = CacheKey::normalized("entities", &query);
+                    connection.exchange(key, |inner| async move { inner.entities(query).await })
+                },
+            ))?
+            .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
+
+        Ok(entity_lookup(lookup))
+    }
+
+    /// Look up a model of entities by keys, requested in chunks running concurrently
+    ///
+    /// Entities are keyed by id, the Poseidon hash of their keys. Entities without the model
+    /// are reported as missing.
+    pub fn entities_by_keys(
+        &self,
+        world_addresses: Vec<FieldElement>,
+        model: String,
+        keys: Vec<Vec<FieldElement>>,
+    ) -> Result<HashMap<String, Option<Entity>>, DojoError> {
+        let world_addresses =
+            world_addresses.iter().map(field_element_to_felt).collect::<Result<Vec<_>, _>>()?;
+        let keys = keys
+            .iter()
+            .map(|keys| keys.iter().map(field_element_to_felt).collect::<Result<Vec<_>, _>>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let connection = &self.inner;
+        let max_message_size = connection.config().max_message_size;
+        let lookup = self
+            .block_on(batch::entities_by_keys(
+                &world_addresses,
+                &model,
+                &keys,
+                max_message_size,
+                |query| {
+                    let key = CacheKey::normalized("entities", &query);
+                    connection.exchange(key, |inner| async move { inner.entities(query).await })
+                },
+            ))?
+            .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
+
+        Ok(entity_lookup(lookup))
+    }
+
+    /// Get every version of a model of an entity within an inclusive range, in the order they
+    /// were set
+    ///
+    /// Versions come with their block and transaction, read through Torii's SQL endpoint.
+    pub fn entity_history(
+        &self,
+        world_addresses: Vec<FieldElement>,
+        hashed_keys: FieldElement,
+        model: String,
+        range: HistoryRange,
+    ) -> Result<Vec<EntityVersion>, DojoError> {
+        let world_addresses =
+            world_addresses.iter().map(field_element_to_felt).collect::<Result<Vec<_>, _>>()?;
+        let hashed_keys = field_element_to_felt(&hashed_keys)?;
+
+        let mut timelines = self
+            .block_on(self.timelines(&world_addresses, &[hashed_keys], vec![model.clone()]))?
+            .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
+
+        let versions = timelines.remove(&(hashed_keys, model)).unwrap_or_default();
+        Ok(history::within(versions, range.into()).into_iter().map(Into::into).collect())
+    }
+
+    /// Rebuild entities as they were at a block or time from their history, each with the
+    /// models set by then. Entities that didn't exist yet are left out.
+    pub fn entities_at(
+        &self,
+        world_address: FieldElement,
+        ids: Vec<FieldElement>,
+        at: At,
+    ) -> Result<Vec<Entity>, DojoError> {
+        let world_address = field_element_to_felt(&world_address)?;
+        let ids = ids.iter().map(field_element_to_felt).collect::<Result<Vec<_>, _>>()?;
+
+        let timelines = self
+            .block_on(self.timelines(&[world_address], &ids, vec![]))?
+            .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
+
+        Ok(history::state_at(world_address, timelines, at.into())
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Iterate over every page of entities matching the query, optionally fetching the next
+    /// page in the background once a page is returned
+    pub fn entities_iter(&self, query: Query, prefetch: bool) -> Arc<EntityIterator> {
+        let projection = query.projection();
+        let mut q: torii_proto::Query = query.into();
+        if let Some(projection) = &projection {
+            projection.apply_query(&mut q);
+        }
+        let inner = PageIter::new(self.inner.clone(), "entities", q, prefetch, move |client, q| {
+            let projection = projection.clone();
+            async move {
+                let mut page = client.entities(q).await?;
+                if let Some(projection) = &projection {
+                    projection.apply_page(&mut page);
+                }
+                anyhow::Ok(page)
+            }
+        });
+        Arc::new(EntityIterator { inner })
+    }
+
+    /// Iterate over every page of event messages matching the query, optionally fetching the next
+    /// page in the background once a page is returned
+    pub fn event_messages_iter(&self, query: Query, prefetch: bool) -> Arc<EntityIterator> {
+        let projection = query.projection();
+        let mut q: torii_proto::Query = query.into();
+        if let Some(projection) = &projection {
+            projection.apply_query(&mut q);
+        }
+        let inner =
+            PageIter::new(self.inner.clone(), "event_messages", q, prefetch, move |client, q| {
+                let projection = projection.clone();
+                async move {
+                    let mut page = client.event_messages(q).await?;
+                    if let Some(projection) = &projection {
+                        projection.apply_page(&mut page);
+                    }
+                    anyhow::Ok(page)
+                }
+            });
+        Arc::new(EntityIterator { inner })
+    }
+
+    /// Iterate over every page of controllers matching the query, optionally fetching the next
+    /// page in the background once a page is returned
+    pub fn controllers_iter(
+        &self,
+        query: ControllerQuery,
+        prefetch: bool,
+    ) -> Arc<ControllerIterator> {
+        let q: torii_proto::ControllerQuery = query.into();
+        let inner =
+            PageIter::new(self.inner.clone(), "controllers", q, prefetch, |client, q| async move {
+                client.controllers(q).await
+            });
+        Arc::new(ControllerIterator { inner })
+    }
+
+    /// Iterate over every page of tokens matching the query, optionally fetching the next
+    /// page in the background once a page is returned
+    pub fn tokens_iter(&self, query: TokenQuery, prefetch: bool) -> Arc<TokenIterator> {
+        let q: torii_proto::TokenQuery = query.into();
+        let inner =
+            PageIter::new(self.inner.clone(), "tokens", q, prefetch, |client, q| async move {
+                client.tokens(q).await
+            });
+        Arc::new(TokenIterator { inner })
+    }
+
+    /// Iterate over every page of token balances matching the query, optionally fetching the next
+    /// page in the background once a page is returned
+    pub fn token_balances_iter(
+        &self,
+        query: TokenBalanceQuery,
+        prefetch: bool,
+    ) -> Arc<TokenBalanceIterator> {
+        let q: torii_proto::TokenBalanceQuery = query.into();
+        let inner = PageIter::new(
+            self.inner.clone(),
+            "token_balances",
+            q,
+            prefetch,
+            |client, q| async move { client.token_balances(q).await },
+        );
+        Arc::new(TokenBalanceIterator { inner })
+    }
+
+    /// Iterate over every page of token transfers matching the query, optionally fetching the next
+    /// page in the background once a page is returned
+    pub fn token_transfers_iter(
+        &self,
+        query: TokenTransferQuery,
+        prefetch: bool,
+    ) -> Arc<TokenTransferIterator> {
+        let q: torii_proto::TokenTransferQuery = query.into();
+        let inner = PageIter::new(
+            self.inner.clone(),
+            "token_transfers",
+            q,
+            prefetch,
+            |client, q| async move { client.token_transfers(q).await },
+        );
+        Arc::new(TokenTransferIterator { inner })
+    }
+
+    /// Iterate over every page of transactions matching the query, optionally fetching the next
+    /// page in the background once a page is returned
+    pub fn transactions_iter(
+        &self,
+        query: TransactionQuery,
+        prefetch: bool,
+    ) -> Arc<TransactionIterator> {
+        let q: torii_proto::TransactionQuery = query.into();
+        let inner = PageIter::new(
+            self.inner.clone(),
+            "transactions",
+            q,
+            prefetch,
+            |client, q| async move { client.transactions(q).await },
+        );
+        Arc::new(TransactionIterator { inner })
+    }
+
+    /// Iterate over every page of activities matching the query, optionally fetching the next
+    /// page in the background once a page is returned
+    pub fn activities_iter(&self, query: ActivityQuery, prefetch: bool) -> Arc<ActivityIterator> {
+        let q: torii_proto::ActivityQuery = query.into();
+        let inner =
+            PageIter::new(self.inner.clone(), "activities", q, prefetch, |client, q| async move {
+                client.activities(q).await
+            });
+        Arc::new(ActivityIterator { inner })
+    }
+
+    /// Iterate over every page of achievements matching the query, optionally fetching the next
+    /// page in the background once a page is returned
+    pub fn achievements_iter(
+        &self,
+        query: AchievementQuery,
+        prefetch: bool,
+    ) -> Arc<AchievementIterator> {
+        let q: torii_proto::AchievementQuery = query.into();
+        let inner = PageIter::new(
+            self.inner.clone(),
+            "achievements",
+            q,
+            prefetch,
+            |client, q| async move { client.achievements(q).await },
+        );
+        Arc::new(AchievementIterator { inner })
+    }
+
+    /// Iterate over every page of player achievements matching the query, optionally fetching the next
+    /// page in the background once a page is returned
+    pub fn player_achievements_iter(
+        &self,
+        query: PlayerAchievementQuery,
+        prefetch: bool,
+    ) -> Arc<PlayerAchievementIterator> {
+        let q: torii_proto::PlayerAchievementQuery = query.into();
+        let inner = PageIter::new(
+            self.inner.clone(),
+            "player_achievements",
+            q,
+            prefetch,
+            |client, q| async move { client.player_achievements(q).await },
+        );
+        Arc::new(PlayerAchievementIterator { inner })
+    }
+
+    /// Iterate over every page of aggregation entries matching the query, optionally fetching the next
+    /// page in the background once a page is returned
+    pub fn aggregations_iter(
+        &self,
+        query: AggregationQuery,
+        prefetch: bool,
+    ) -> Arc<AggregationIterator> {
+        let q: torii_proto::AggregationQuery = query.into();
+        let inner = PageIter::new(
+            self.inner.clone(),
+            "aggregations",
+            q,
+            prefetch,
+            |client, q| async move { client.aggregations(q).await },
+        );
+        Arc::new(AggregationIterator { inner })
+    }
+
     /// Retrieve raw Starknet events
     pub fn starknet_events(&self, query: EventQuery) -> Result<PageEvent, DojoError> {
         let q: torii_proto::EventQuery = query.try_into()?;
-        let inner = self.inner.clone();
-        let page = runtime()
-            .block_on(inner.starknet_events(q))
+        let key = CacheKey::new("starknet_events", &q);
+        let page = self
+            .block_on(
+                self.inner.exchange(key, |inner| async move { inner.starknet_events(q).await }),
+            )?
             .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
 
         Ok(PageEvent {
@@ -310,9 +1497,9 @@ impl ToriiClient {
 
     /// Execute a SQL query against the Torii database
     pub fn sql(&self, query: String) -> Result<Vec<SqlRow>, DojoError> {
-        let inner = self.inner.clone();
-        let rows = runtime()
-            .block_on(inner.sql(query))
+        let key = CacheKey::new("sql", &query);
+        let rows = self
+            .block_on(self.inner.exchange(key, |inner| async move { inner.sql(query).await }))?
             .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
 
         rows.into_iter().map(|r| r.try_into()).collect()
@@ -326,9 +1513,9 @@ impl ToriiClient {
     /// # Returns
     /// A `SearchResponse` containing results grouped by table with relevance scores
     pub fn search(&self, query: SearchQuery) -> Result<SearchResponse, DojoError> {
-        let inner = self.inner.clone();
-        runtime()
-            .block_on(inner.search(query.into()))
+        let query: torii_proto::SearchQuery = query.into();
+        let key = CacheKey::new("search", &query);
+        self.block_on(self.inner.exchange(key, |inner| async move { inner.search(query).await }))?
             .map(Into::into)
             .map_err(|e| DojoError::QueryError { message: e.to_string() })
     }
@@ -340,40 +1527,166 @@ impl ToriiClient {
         world_addresses: Vec<FieldElement>,
         callback: Box<dyn EntityUpdateCallback>,
     ) -> Result<u64, DojoError> {
-        let sub_id = self.next_sub_id.fetch_add(1, Ordering::SeqCst);
-
         let addrs: Result<Vec<starknet::core::types::Felt>, DojoError> =
             world_addresses.iter().map(field_element_to_felt).collect();
         let addrs = addrs?;
 
-        let clause_proto = clause.map(|c| c.into());
+        let clause_proto: Option<torii_proto::Clause> = clause.map(|c| c.into());
+
+        let callback: Arc<dyn EntityUpdateCallback> = callback.into();
+        let on_closed = callback.clone();
+        let delivery = DeliveryControl::default();
+        let projection = delivery.clone();
+        self.spawn_entities(
+            clause_proto,
+            addrs,
+            delivery,
+            move |mut entity| {
+                if projection.project(&mut entity) {
+                    callback.on_update(entity.into());
+                }
+            },
+            move |reason| on_closed.on_error(reason),
+        )
+    }
 
-        let inner = self.inner.clone();
-        let stream = runtime()
-            .block_on(inner.on_entity_updated(clause_proto, addrs))
-            .map_err(|_| DojoError::SubscriptionError)?;
+    /// Subscribe to entity updates and dispatch their models to the handlers of a
+    /// ModelDispatcher, which can be added and removed while the subscription is live
+    pub fn subscribe_entity_dispatch(
+        &self,
+        clause: Option<Clause>,
+        world_addresses: Vec<FieldElement>,
+        dispatcher: Arc<ModelDispatcher>,
+    ) -> Result<u64, DojoError> {
+        let addrs: Result<Vec<starknet::core::types::Felt>, DojoError> =
+            world_addresses.iter().map(field_element_to_felt).collect();
+        let addrs = addrs?;
 
-        let handle = runtime().spawn(async move {
-            use futures_util::StreamExt;
-            let mut stream = stream;
-            // Skip the first message which contains the subscription ID
-            let _ = stream.next().await;
-
-            while let Some(result) = stream.next().await {
-                match result {
-                    Ok((_id, entity)) => {
-                        callback.on_update(entity.into());
-                    }
-                    Err(e) => {
-                        callback.on_error(e.to_string());
-                        break;
+        let clause_proto: Option<torii_proto::Clause> = clause.map(|c| c.into());
+
+        let dispatcher = dispatcher.inner.clone();
+        let delivery = DeliveryControl::default();
+        let projection = delivery.clone();
+        self.spawn_entities(
+            clause_proto,
+            addrs,
+            delivery,
+            move |entity| {
+                dispatcher.dispatch(&entity, |handler, model| {
+                    let mut model = model.clone();
+                    if projection.project(&mut model) {
+                        handler.on_model(felt_to_field_element(entity.hashed_keys), model.into());
                     }
+                })
+            },
+            // Connection failures are reported through the subscription status
+            |_| {},
+        )
+    }
+
+    /// Subscribe to entity updates with the predictions of an OptimisticStore applied, i.e.
+    /// the confirmed state of each entity with the pending predictions on top
+    pub fn subscribe_entity_optimistic_updates(
+        &self,
+        clause: Option<Clause>,
+        world_addresses: Vec<FieldElement>,
+        store: Arc<OptimisticStore>,
+        callback: Box<dyn EntityUpdateCallback>,
+    ) -> Result<u64, DojoError> {
+        let addrs: Result<Vec<starknet::core::types::Felt>, DojoError> =
+            world_addresses.iter().map(field_element_to_felt).collect();
+        let addrs = addrs?;
+
+        let clause_proto: Option<torii_proto::Clause> = clause.map(|c| c.into());
+
+        let callback: Arc<dyn EntityUpdateCallback> = callback.into();
+        let on_closed = callback.clone();
+        let optimistic = store.inner.clone();
+        let delivery = DeliveryControl::default();
+        let projection = delivery.clone();
+        self.spawn_entities(
+            clause_proto,
+            addrs,
+            delivery,
+            move |entity| {
+                let mut entity = optimistic.confirm(entity);
+                if projection.project(&mut entity) {
+                    callback.on_update(entity.into());
                 }
-            }
-        });
+            },
+            move |reason| on_closed.on_error(reason),
+        )
+    }
 
-        self.subscriptions.lock().unwrap().insert(sub_id, handle);
-        Ok(sub_id)
+    /// Subscribe to entity updates, tagged as creations, updates or deletions
+    pub fn subscribe_entity_changes(
+        &self,
+        clause: Option<Clause>,
+        world_addresses: Vec<FieldElement>,
+        callback: Box<dyn EntityChangeCallback>,
+    ) -> Result<u64, DojoError> {
+        let addrs: Result<Vec<starknet::core::types::Felt>, DojoError> =
+            world_addresses.iter().map(field_element_to_felt).collect();
+        let addrs = addrs?;
+
+        let clause_proto: Option<torii_proto::Clause> = clause.map(|c| c.into());
+
+        let callback: Arc<dyn EntityChangeCallback> = callback.into();
+        let on_closed = callback.clone();
+        let mut classifier = EntityClassifier::new();
+        let delivery = DeliveryControl::default();
+        let projection = delivery.clone();
+        self.spawn_entities(
+            clause_proto,
+            addrs,
+            delivery,
+            move |entity| {
+                let mut change = classifier.classify(entity);
+                if projection.project(&mut change) {
+                    callback.on_change(change.into());
+                }
+            },
+            move |reason| on_closed.on_error(reason),
+        )
+    }
+
+    /// Subscribe to entity updates, reported as field level changes against the previous
+    /// state of each entity
+    pub fn subscribe_entity_diffs(
+        &self,
+        clause: Option<Clause>,
+        world_addresses: Vec<FieldElement>,
+        callback: Box<dyn EntityDiffCallback>,
+    ) -> Result<u64, DojoError> {
+        let addrs: Result<Vec<starknet::core::types::Felt>, DojoError> =
+            world_addresses.iter().map(field_element_to_felt).collect();
+        let addrs = addrs?;
+
+        let clause_proto: Option<torii_proto::Clause> = clause.map(|c| c.into());
+
+        let callback: Arc<dyn EntityDiffCallback> = callback.into();
+        let on_closed = callback.clone();
+        let mut differ = EntityDiffer::new();
+        let (upgrades, mut seen) = (self.inner.clone(), self.inner.upgrades().count());
+        let delivery = DeliveryControl::default();
+        let projection = delivery.clone();
+        self.spawn_entities(
+            clause_proto,
+            addrs,
+            delivery,
+            move |entity| {
+                // Upgraded models are set afresh rather than diffed across schemas
+                for model in upgrades.upgrades().since(&mut seen) {
+                    differ.forget_model(&model);
+                }
+                for mut change in differ.diff(&entity) {
+                    if projection.project(&mut change) {
+                        callback.on_change(change.into());
+                    }
+                }
+            },
+            move |reason| on_closed.on_error(reason),
+        )
     }
 
     /// Subscribe to token balance updates
@@ -384,41 +1697,55 @@ impl ToriiClient {
         token_ids: Vec<U256>,
         callback: Box<dyn TokenBalanceUpdateCallback>,
     ) -> Result<u64, DojoError> {
-        let sub_id = self.next_sub_id.fetch_add(1, Ordering::SeqCst);
-
         let contracts: Result<Vec<starknet::core::types::Felt>, DojoError> =
             contract_addresses.iter().map(field_element_to_felt).collect();
         let accounts: Result<Vec<starknet::core::types::Felt>, DojoError> =
             account_addresses.iter().map(field_element_to_felt).collect();
         let ids: Result<Vec<crypto_bigint::U256>, DojoError> =
             token_ids.iter().map(uniffi_to_u256).collect();
-
-        let inner = self.inner.clone();
-        let stream = runtime()
-            .block_on(inner.on_token_balance_updated(contracts?, accounts?, ids?))
-            .map_err(|_| DojoError::SubscriptionError)?;
-
-        let handle = runtime().spawn(async move {
-            use futures_util::StreamExt;
-            let mut stream = stream;
-            // Skip the first message which contains the subscription ID
-            let _ = stream.next().await;
-
-            while let Some(result) = stream.next().await {
-                match result {
-                    Ok((_id, balance)) => {
-                        callback.on_update(balance.into());
+        let (contracts, accounts, ids) = (contracts?, accounts?, ids?);
+
+        let callback: Arc<dyn TokenBalanceUpdateCallback> = callback.into();
+        let on_closed = callback.clone();
+        let on_item = move |balance: torii_proto::TokenBalance| callback.on_update(balance.into());
+        let on_closed = move |reason| on_closed.on_error(reason);
+
+        let filter = TokenBalanceFilter {
+            contract_addresses: contracts,
+            account_addresses: accounts,
+            token_ids: ids,
+        };
+        if self.multiplexed(&self.token_balances, &filter) {
+            let token_balances = self.token_balances.clone();
+            return self.spawn_driven(
+                DeliveryControl::default(),
+                None,
+                move |Driver { connection, status, on_connected, on_item, .. }| {
+                    let (lease, task) =
+                        token_balances.join(connection, tokio::time::sleep, filter, on_item);
+                    if let Some(task) = task {
+                        runtime().spawn(task);
                     }
-                    Err(e) => {
-                        callback.on_error(e.to_string());
-                        break;
-                    }
-                }
-            }
-        });
+                    follow(lease, status, on_connected)
+                },
+                on_item,
+                on_closed,
+            );
+        }
 
-        self.subscriptions.lock().unwrap().insert(sub_id, handle);
-        Ok(sub_id)
+        self.spawn_subscription(
+            move |client| {
+                let TokenBalanceFilter { contract_addresses, account_addresses, token_ids } =
+                    filter.clone();
+                async move {
+                    client
+                        .on_token_balance_updated(contract_addresses, account_addresses, token_ids)
+                        .await
+                }
+            },
+            invalidate_token_balances(&self.inner, on_item),
+            on_closed,
+        )
     }
 
     /// Subscribe to token updates
@@ -428,39 +1755,22 @@ impl ToriiClient {
         token_ids: Vec<U256>,
         callback: Box<dyn TokenUpdateCallback>,
     ) -> Result<u64, DojoError> {
-        let sub_id = self.next_sub_id.fetch_add(1, Ordering::SeqCst);
-
         let contracts: Result<Vec<starknet::core::types::Felt>, DojoError> =
             contract_addresses.iter().map(field_element_to_felt).collect();
         let ids: Result<Vec<crypto_bigint::U256>, DojoError> =
             token_ids.iter().map(uniffi_to_u256).collect();
-
-        let inner = self.inner.clone();
-        let stream = runtime()
-            .block_on(inner.on_token_updated(contracts?, ids?))
-            .map_err(|_| DojoError::SubscriptionError)?;
-
-        let handle = runtime().spawn(async move {
-            use futures_util::StreamExt;
-            let mut stream = stream;
-            // Skip the first message which contains the subscription ID
-            let _ = stream.next().await;
-
-            while let Some(result) = stream.next().await {
-                match result {
-                    Ok((_id, token)) => {
-                        callback.on_update(token.into());
-                    }
-                    Err(e) => {
-                        callback.on_error(e.to_string());
-                        break;
-                    }
-                }
-            }
-        });
-
-        self.subscriptions.lock().unwrap().insert(sub_id, handle);
-        Ok(sub_id)
+        let (contracts, ids) = (contracts?, ids?);
+
+        let callback: Arc<dyn TokenUpdateCallback> = callback.into();
+        let on_closed = callback.clone();
+        self.spawn_subscription(
+            move |client| {
+                let (contracts, ids) = (contracts.clone(), ids.clone());
+                async move { client.on_token_updated(contracts, ids).await }
+            },
+            move |token| callback.on_update(token.into()),
+            move |reason| on_closed.on_error(reason),
+        )
     }
 
     /// Subscribe to transaction updates
@@ -469,36 +1779,18 @@ impl ToriiClient {
         filter: Option<TransactionFilter>,
         callback: Box<dyn TransactionUpdateCallback>,
     ) -> Result<u64, DojoError> {
-        let sub_id = self.next_sub_id.fetch_add(1, Ordering::SeqCst);
-
-        let filter_proto = filter.map(|f| f.into());
-
-        let inner = self.inner.clone();
-        let stream = runtime()
-            .block_on(inner.on_transaction(filter_proto))
-            .map_err(|_| DojoError::SubscriptionError)?;
-
-        let handle = runtime().spawn(async move {
-            use futures_util::StreamExt;
-            let mut stream = stream;
-            // Skip the first message which contains the subscription ID
-            let _ = stream.next().await;
-
-            while let Some(result) = stream.next().await {
-                match result {
-                    Ok(transaction) => {
-                        callback.on_update(transaction.into());
-                    }
-                    Err(e) => {
-                        callback.on_error(e.to_string());
-                        break;
-                    }
-                }
-            }
-        });
-
-        self.subscriptions.lock().unwrap().insert(sub_id, handle);
-        Ok(sub_id)
+        let filter_proto: Option<torii_proto::TransactionFilter> = filter.map(|f| f.into());
+
+        let callback: Arc<dyn TransactionUpdateCallback> = callback.into();
+        let on_closed = callback.clone();
+        self.spawn_subscription(
+            move |client| {
+                let filter_proto = filter_proto.clone();
+                async move { client.on_transaction(filter_proto).await.map(without_id) }
+            },
+            move |transaction| callback.on_update(transaction.into()),
+            move |reason| on_closed.on_error(reason),
+        )
     }
 
     /// Subscribe to Starknet event updates
@@ -507,43 +1799,318 @@ impl ToriiClient {
         keys: Vec<KeysClause>,
         callback: Box<dyn EventUpdateCallback>,
     ) -> Result<u64, DojoError> {
-        let sub_id = self.next_sub_id.fetch_add(1, Ordering::SeqCst);
-
         let keys_proto: Vec<torii_proto::KeysClause> = keys.into_iter().map(|k| k.into()).collect();
 
-        let inner = self.inner.clone();
-        let stream = runtime()
-            .block_on(inner.on_starknet_event(keys_proto))
-            .map_err(|_| DojoError::SubscriptionError)?;
+        let callback: Arc<dyn EventUpdateCallback> = callback.into();
+        let on_closed = callback.clone();
+        self.spawn_subscription(
+            move |client| {
+                let keys_proto = keys_proto.clone();
+                async move { client.on_starknet_event(keys_proto).await.map(without_id) }
+            },
+            move |event| callback.on_update(event.into()),
+            move |reason| on_closed.on_error(reason),
+        )
+    }
 
-        let handle = runtime().spawn(async move {
-            use futures_util::StreamExt;
-            let mut stream = stream;
-            // Skip the first message which contains the subscription ID
-            let _ = stream.next().await;
-
-            while let Some(result) = stream.next().await {
-                match result {
-                    Ok(event) => {
-                        callback.on_update(event.into());
-                    }
-                    Err(e) => {
-                        callback.on_error(e.to_string());
-                        break;
+    /// Watch a world for models and events registered or upgraded while the client runs.
+    ///
+    /// Registration and upgrade events of the world trigger a refresh of its schema, as does
+    /// every connection of the subscription. The cached query results of the world and the
+    /// clause validator are dropped whenever a model changed, and the predictions of upgraded
+    /// models are discarded from `store`.
+    pub fn subscribe_schema_upgrades(
+        &self,
+        world_address: FieldElement,
+        store: Option<Arc<OptimisticStore>>,
+        callback: Box<dyn SchemaUpgradeCallback>,
+    ) -> Result<u64, DojoError> {
+        let watcher = Arc::new(SchemaWatcher::new(field_element_to_felt(&world_address)?));
+        // Records the schema the upgrades are compared with
+        self.block_on(watcher.refresh(&self.inner))?
+            .map_err(|e| DojoError::QueryError { message: e.to_string() })?;
+
+        let callback: Arc<dyn SchemaUpgradeCallback> = callback.into();
+        let on_closed = callback.clone();
+        let connection = self.inner.clone();
+        let refresh = move || {
+            let (connection, watcher) = (connection.clone(), watcher.clone());
+            let (store, callback) = (store.clone(), callback.clone());
+            runtime().spawn(async move {
+                let Ok(upgrades) = watcher.refresh(&connection).await else { return };
+                for upgrade in upgrades {
+                    if let Some(store) = &store {
+                        for view in store.inner.discard_model(&upgrade.new.tag) {
+                            store.callback.on_update(view.into());
+                        }
                     }
+                    callback.on_upgrade(upgrade.into());
                 }
+            });
+        };
+        let on_event = refresh.clone();
+
+        self.spawn_driven(
+            DeliveryControl::default(),
+            None,
+            move |Driver { connection, tripwire, status, pace, mut on_connected, on_item }| {
+                let on_connected = move |id| {
+                    // Upgrades may have landed before the stream was live, or while reconnecting
+                    refresh();
+                    on_connected(id);
+                };
+                drive(
+                    connection,
+                    |client| async move {
+                        client.on_starknet_event(upgrade_clauses()).await.map(without_id)
+                    },
+                    tokio::time::sleep,
+                    tripwire,
+                    status,
+                    Some(pace),
+                    on_connected,
+                    on_item,
+                )
+            },
+            move |_: torii_proto::Event| on_event(),
+            move |reason| on_closed.on_error(reason),
+        )
+    }
+
+    /// Spawns an entity subscription, sharing the entity stream when multiplexing is enabled
+    ///
+    /// `delivery` controls the subscription, `on_item` projecting what it delivers with it.
+    fn spawn_entities(
+        &self,
+        clause: Option<torii_proto::Clause>,
+        world_addresses: Vec<starknet::core::types::Felt>,
+        delivery: DeliveryControl,
+        on_item: impl FnMut(torii_proto::schema::Entity) + Send + 'static,
+        on_closed: impl FnOnce(String) + Send + 'static,
+    ) -> Result<u64, DojoError> {
+        self.validate_clause(clause.as_ref(), &world_addresses)?;
+        let filter = EntityFilter { clause, world_addresses };
+        if self.multiplexed(&self.entities, &filter) {
+            let entities = self.entities.clone();
+            return self.spawn_driven(
+                delivery,
+                Some(Coalesce::ENTITIES),
+                move |Driver { connection, status, on_connected, on_item, .. }| {
+                    let (lease, task) =
+                        entities.join(connection, tokio::time::sleep, filter, on_item);
+                    if let Some(task) = task {
+                        runtime().spawn(task);
+                    }
+                    follow(lease, status, on_connected)
+                },
+                on_item,
+                on_closed,
+            );
+        }
+
+        self.spawn_driven(
+            delivery,
+            Some(Coalesce::ENTITIES),
+            move |Driver { connection, tripwire, status, pace, on_connected, on_item }| {
+                drive_entities(
+                    connection,
+                    EntitySource::Entities,
+                    move || filter.clone(),
+                    tokio::time::sleep,
+                    tripwire,
+                    status,
+                    Some(pace),
+                    on_connected,
+                    on_item,
+                )
+            },
+            on_item,
+            on_closed,
+        )
+    }
+
+    /// Whether a subscription with this query joins the shared stream of `topic`
+    fn multiplexed<T: Topic, S: FnMut(T::Item)>(
+        &self,
+        topic: &Mux<T, S>,
+        query: &T::Query,
+    ) -> bool {
+        self.inner.config().multiplex && topic.shareable(query)
+    }
+
+    /// Spawns a reconnecting subscription and waits for its first stream to be established
+    ///
+    /// `on_closed` is called if the subscription gives up after exhausting the retry policy.
+    fn spawn_subscription<T, S, E, C, CF, CE>(
+        &self,
+        connect: C,
+        on_item: impl FnMut(T) + Send + 'static,
+        on_closed: impl FnOnce(String) + Send + 'static,
+    ) -> Result<u64, DojoError>
+    where
+        T: Send + 'static,
+        S: Stream<Item = Result<(u64, T), E>> + Send + 'static,
+        E: Send + 'static,
+        C: Fn(Arc<torii_client::Client>) -> CF + Send + 'static,
+        CF: Future<Output = Result<S, CE>> + Send + 'static,
+        CE: Send + 'static,
+    {
+        self.spawn_driven(
+            DeliveryControl::default(),
+            None,
+            move |Driver { connection, tripwire, status, pace, on_connected, on_item }| {
+                drive(
+                    connection,
+                    connect,
+                    tokio::time::sleep,
+                    tripwire,
+                    status,
+                    Some(pace),
+                    on_connected,
+                    on_item,
+                )
+            },
+            on_item,
+            on_closed,
+        )
+    }
+
+    /// Spawns the future returned by `run` and waits until it reports its first connection
+    ///
+    /// Items are delivered to `on_item` according to the subscription's delivery policy,
+    /// coalesced as `coalesce` tells when supported. `delivery` controls them once spawned.
+    fn spawn_driven<T, R, F>(
+        &self,
+        delivery: DeliveryControl,
+        coalesce: Option<Coalesce<T>>,
+        run: R,
+        on_item: impl FnMut(T) + Send + 'static,
+        on_closed: impl FnOnce(String) + Send + 'static,
+    ) -> Result<u64, DojoError>
+    where
+        T: Send + 'static,
+        R: FnOnce(Driver<T>) -> F,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let sub_id = self.next_sub_id.fetch_add(1, Ordering::SeqCst);
+        let (established_tx, established_rx) = oneshot::channel();
+        let mut established_tx = Some(established_tx);
+        let (trigger, tripwire) = Tripwire::new();
+        let status = SubscriptionStatus::new();
+
+        let on_connected = Box::new(move |_id| {
+            if let Some(tx) = established_tx.take() {
+                let _ = tx.send(());
             }
         });
+        let (on_item, delivery, deliver) =
+            delivery::channel_with(delivery, coalesce, tokio::time::sleep, on_item);
+        runtime().spawn(deliver);
+
+        let driver = run(Driver {
+            connection: self.inner.clone(),
+            tripwire,
+            status: status.clone(),
+            pace: delivery.clone(),
+            on_connected,
+            on_item: Box::new(on_item),
+        });
+
+        let handle = runtime().spawn(async move {
+            // Cancelling the subscription aborts this task, dropping the trigger along with it
+            let _trigger = trigger;
+
+            driver.await;
+
+            on_closed("subscription closed after exhausting its retry policy".to_string());
+        });
 
-        self.subscriptions.lock().unwrap().insert(sub_id, handle);
+        if !matches!(self.block_on(established_rx), Ok(Ok(()))) {
+            handle.abort();
+            return Err(DojoError::SubscriptionError);
+        }
+
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .insert(sub_id, SubscriptionHandle { task: handle, status, delivery });
         Ok(sub_id)
     }
 
+    /// Get the connection state of a subscription
+    pub fn subscription_state(&self, subscription_id: u64) -> Result<SubscriptionState, DojoError> {
+        let subs = self.subscriptions.lock().unwrap();
+        let sub = subs.get(&subscription_id).ok_or(DojoError::SubscriptionError)?;
+        Ok(sub.status.state().into())
+    }
+
+    /// Register a callback notified of every connection state transition of a subscription
+    ///
+    /// The callback is immediately called with the current state.
+    pub fn on_subscription_status(
+        &self,
+        subscription_id: u64,
+        callback: Box<dyn SubscriptionStatusCallback>,
+    ) -> Result<(), DojoError> {
+        let mut states = {
+            let subs = self.subscriptions.lock().unwrap();
+            subs.get(&subscription_id).ok_or(DojoError::SubscriptionError)?.status.watch()
+        };
+
+        runtime().spawn(async move {
+            while let Some(state) = states.next().await {
+                callback.on_status(state.into());
+            }
+        });
+        Ok(())
+    }
+
+    /// Set how a subscription's updates are buffered, coalesced and rate limited
+    pub fn set_subscription_delivery_policy(
+        &self,
+        subscription_id: u64,
+        policy: DeliveryPolicy,
+    ) -> Result<(), DojoError> {
+        let subs = self.subscriptions.lock().unwrap();
+        let sub = subs.get(&subscription_id).ok_or(DojoError::SubscriptionError)?;
+        sub.delivery.set_policy(policy.into());
+        Ok(())
+    }
+
+    /// Set the models and members kept from the updates of an entity subscription, e.g.
+    /// `ns-Position` or `ns-Stats.health`, skipping updates left without models
+    ///
+    /// Every model and member is kept when `selectors` is empty.
+    pub fn set_subscription_projection(
+        &self,
+        subscription_id: u64,
+        selectors: Vec<String>,
+    ) -> Result<(), DojoError> {
+        let subs = self.subscriptions.lock().unwrap();
+        let sub = subs.get(&subscription_id).ok_or(DojoError::SubscriptionError)?;
+        let projection = dojo_core::projection::Projection::new(&selectors);
+        sub.delivery.set_projection(Some(projection).filter(|p| !p.is_empty()));
+        Ok(())
+    }
+
+    /// Get the number of delivered, dropped and coalesced updates of a subscription
+    pub fn subscription_delivery_stats(
+        &self,
+        subscription_id: u64,
+    ) -> Result<DeliveryStats, DojoError> {
+        let subs = self.subscriptions.lock().unwrap();
+        let sub = subs.get(&subscription_id).ok_or(DojoError::SubscriptionError)?;
+        Ok(sub.delivery.stats().into())
+    }
+
     /// Cancel a subscription
     pub fn cancel_subscription(&self, subscription_id: u64) -> Result<(), DojoError> {
         let mut subs = self.subscriptions.lock().unwrap();
-        if let Some(handle) = subs.remove(&subscription_id) {
-            handle.abort();
+        if let Some(sub) = subs.remove(&subscription_id) {
+            sub.task.abort();
+            sub.status.set(dojo_core::status::SubscriptionState::Closed {
+                reason: "subscription cancelled".to_string(),
+            });
             Ok(())
         } else {
             Err(DojoError::SubscriptionError)
@@ -0,0 +1,133 @@
+// Client configuration types - ClientConfig, RetryPolicy, PollingConfig, EndpointStatus
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    pub multiplier: u32,
+    pub max_attempts: Option<u32>,
+}
+
+impl From<RetryPolicy> for dojo_core::config::RetryPolicy {
+    fn from(val: RetryPolicy) -> Self {
+        dojo_core::config::RetryPolicy {
+            initial_backoff: Duration::from_millis(val.initial_backoff_ms),
+            max_backoff: Duration::from_millis(val.max_backoff_ms),
+            multiplier: val.multiplier,
+            max_attempts: val.max_attempts,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PollingConfig {
+    pub interval_ms: u64,
+    pub after_failures: u32,
+    pub page_size: u32,
+}
+
+impl From<PollingConfig> for dojo_core::config::PollingConfig {
+    fn from(val: PollingConfig) -> Self {
+        dojo_core::config::PollingConfig {
+            interval: Duration::from_millis(val.interval_ms),
+            after_failures: val.after_failures,
+            page_size: val.page_size,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    pub ttl_ms: u64,
+    pub max_entries: u64,
+    pub stale_while_revalidate: bool,
+}
+
+impl From<CacheConfig> for dojo_core::cache::CacheConfig {
+    fn from(val: CacheConfig) -> Self {
+        dojo_core::cache::CacheConfig {
+            ttl: Duration::from_millis(val.ttl_ms),
+            max_entries: val.max_entries as usize,
+            stale_while_revalidate: val.stale_while_revalidate,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub stale_hits: u64,
+    pub misses: u64,
+    pub invalidations: u64,
+    pub evictions: u64,
+    pub entries: u64,
+}
+
+impl From<dojo_core::cache::CacheStats> for CacheStats {
+    fn from(val: dojo_core::cache::CacheStats) -> Self {
+        CacheStats {
+            hits: val.hits,
+            stale_hits: val.stale_hits,
+            misses: val.misses,
+            invalidations: val.invalidations,
+            evictions: val.evictions,
+            entries: val.entries,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    pub torii_url: String,
+    pub fallback_urls: Vec<String>,
+    pub connect_timeout_ms: Option<u64>,
+    pub request_timeout_ms: Option<u64>,
+    pub max_message_size: Option<u64>,
+    pub retry: Option<RetryPolicy>,
+    pub polling: Option<PollingConfig>,
+    pub multiplex: Option<bool>,
+    pub cache: Option<CacheConfig>,
+    pub validate_queries: Option<bool>,
+}
+
+impl From<ClientConfig> for dojo_core::config::ClientConfig {
+    fn from(val: ClientConfig) -> Self {
+        let mut config = dojo_core::config::ClientConfig::new(val.torii_url);
+        config.fallback_urls = val.fallback_urls;
+        config.connect_timeout = val.connect_timeout_ms.map(Duration::from_millis);
+        config.request_timeout = val.request_timeout_ms.map(Duration::from_millis);
+        if let Some(max_message_size) = val.max_message_size {
+            config.max_message_size = max_message_size as usize;
+        }
+        if let Some(retry) = val.retry {
+            config.retry = retry.into();
+        }
+        config.polling = val.polling.map(Into::into);
+        config.multiplex = val.multiplex.unwrap_or_default();
+        config.cache = val.cache.map(Into::into);
+        config.validate_queries = val.validate_queries.unwrap_or_default();
+        config
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EndpointStatus {
+    pub url: String,
+    pub active: bool,
+    pub healthy: bool,
+    pub head: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+impl From<dojo_core::failover::EndpointStatus> for EndpointStatus {
+    fn from(val: dojo_core::failover::EndpointStatus) -> Self {
+        EndpointStatus {
+            url: val.url,
+            active: val.active,
+            healthy: val.health.healthy,
+            head: val.health.head,
+            last_error: val.health.last_error,
+        }
+    }
+}
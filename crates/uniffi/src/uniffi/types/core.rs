@@ -52,6 +52,10 @@ pub enum DojoError {
     QueryError { message: String },
     #[error("Subscription error")]
     SubscriptionError,
+    #[error("Parse error: {message} at line {line}, column {column}")]
+    ParseError { message: String, line: u64, column: u64 },
+    #[error("Type error: {message}")]
+    TypeError { message: String },
 }
 
 impl From<anyhow::Error> for DojoError {
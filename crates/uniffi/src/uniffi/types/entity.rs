@@ -2,6 +2,7 @@
 use chrono::DateTime;
 
 use super::core::*;
+use super::query::Clause;
 use super::schema::{Struct, Ty};
 
 #[derive(Debug, Clone)]
@@ -40,6 +41,136 @@ impl From<torii_proto::schema::Entity> for Entity {
     }
 }
 
+#[derive(Debug, Clone)]
+pub enum EntityChange {
+    Created { entity: Entity },
+    Updated { entity: Entity },
+    Deleted { entity: Entity },
+}
+
+impl From<dojo_core::change::EntityChange> for EntityChange {
+    fn from(val: dojo_core::change::EntityChange) -> Self {
+        match val {
+            dojo_core::change::EntityChange::Created(entity) => {
+                EntityChange::Created { entity: entity.into() }
+            }
+            dojo_core::change::EntityChange::Updated(entity) => {
+                EntityChange::Updated { entity: entity.into() }
+            }
+            dojo_core::change::EntityChange::Deleted(entity) => {
+                EntityChange::Deleted { entity: entity.into() }
+            }
+        }
+    }
+}
+
+/// Point in the history of a world
+#[derive(Debug, Clone, Copy)]
+pub enum At {
+    Block { number: u64 },
+    Timestamp { seconds: u64 },
+}
+
+impl From<At> for dojo_core::history::At {
+    fn from(val: At) -> Self {
+        match val {
+            At::Block { number } => dojo_core::history::At::Block(number),
+            At::Timestamp { seconds } => dojo_core::history::At::Timestamp(seconds),
+        }
+    }
+}
+
+/// Inclusive bounds of an entity history, unbounded when none
+#[derive(Debug, Clone, Copy)]
+pub struct HistoryRange {
+    pub from: Option<At>,
+    pub to: Option<At>,
+}
+
+impl From<HistoryRange> for dojo_core::history::HistoryRange {
+    fn from(val: HistoryRange) -> Self {
+        dojo_core::history::HistoryRange {
+            from: val.from.map(Into::into),
+            to: val.to.map(Into::into),
+        }
+    }
+}
+
+/// Value of a model set by a transaction. The block and transaction are unknown when its
+/// event couldn't be told apart from others of the same entity and time.
+#[derive(Debug, Clone)]
+pub struct EntityVersion {
+    pub block_number: Option<u64>,
+    pub transaction_hash: Option<FieldElement>,
+    pub timestamp: u64,
+    pub model: Struct,
+}
+
+impl From<dojo_core::history::Version> for EntityVersion {
+    fn from(val: dojo_core::history::Version) -> Self {
+        EntityVersion {
+            block_number: val.block_number,
+            transaction_hash: val.transaction_hash.map(felt_to_field_element),
+            timestamp: val.timestamp,
+            model: val.model.into(),
+        }
+    }
+}
+
+/// Whether an entity update reports the deletion of the entity, i.e. it has no models
+pub fn entity_is_deleted(entity: Entity) -> bool {
+    dojo_core::change::is_deleted(&entity.into())
+}
+
+/// Whether an entity matches a clause, with the same semantics as Torii's queries
+pub fn entity_matches_clause(entity: Entity, clause: Clause) -> bool {
+    dojo_core::clause::matches(&clause.into(), &entity.into())
+}
+
+#[derive(Debug, Clone)]
+pub enum ChangeKind {
+    EntityCreated,
+    ModelAdded,
+    MemberUpdated,
+    ModelRemoved,
+    EntityDeleted,
+}
+
+impl From<dojo_core::diff::ChangeKind> for ChangeKind {
+    fn from(val: dojo_core::diff::ChangeKind) -> Self {
+        match val {
+            dojo_core::diff::ChangeKind::EntityCreated => ChangeKind::EntityCreated,
+            dojo_core::diff::ChangeKind::ModelAdded => ChangeKind::ModelAdded,
+            dojo_core::diff::ChangeKind::MemberUpdated => ChangeKind::MemberUpdated,
+            dojo_core::diff::ChangeKind::ModelRemoved => ChangeKind::ModelRemoved,
+            dojo_core::diff::ChangeKind::EntityDeleted => ChangeKind::EntityDeleted,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FieldChange {
+    pub hashed_keys: FieldElement,
+    pub kind: ChangeKind,
+    pub model: String,
+    pub path: String,
+    pub old_value: Option<Ty>,
+    pub new_value: Option<Ty>,
+}
+
+impl From<dojo_core::diff::FieldChange> for FieldChange {
+    fn from(val: dojo_core::diff::FieldChange) -> Self {
+        FieldChange {
+            hashed_keys: felt_to_field_element(val.hashed_keys),
+            kind: val.kind.into(),
+            model: val.model,
+            path: val.path,
+            old_value: val.old.map(|ty| ty.into()),
+            new_value: val.new.map(|ty| ty.into()),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Model {
     pub world_address: FieldElement,
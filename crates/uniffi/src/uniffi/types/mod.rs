@@ -5,6 +5,7 @@ pub mod core;
 pub mod achievement;
 pub mod activity;
 pub mod aggregation;
+pub mod config;
 pub mod contract;
 pub mod controller;
 pub mod entity;
@@ -12,6 +13,7 @@ pub mod event;
 pub mod query;
 pub mod schema;
 pub mod search;
+pub mod subscription;
 pub mod token;
 pub mod transaction;
 
@@ -21,6 +23,7 @@ pub use core::*;
 pub use achievement::*;
 pub use activity::*;
 pub use aggregation::*;
+pub use config::*;
 pub use contract::*;
 pub use controller::*;
 pub use entity::*;
@@ -28,5 +31,6 @@ pub use event::*;
 pub use query::*;
 pub use schema::*;
 pub use search::*;
+pub use subscription::*;
 pub use token::*;
 pub use transaction::*;
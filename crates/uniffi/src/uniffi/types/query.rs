@@ -1,4 +1,6 @@
 // Query types - Query, Clause, KeysClause, MemberClause, CompositeClause
+use dojo_core::codec::Codec;
+
 use super::core::*;
 use super::schema::MemberValue;
 
@@ -272,6 +274,61 @@ impl From<torii_proto::Clause> for Clause {
     }
 }
 
+/// Parse a textual filter such as `ns-Position.x > 10 and keys(ns-Position) = [0x1, *]` into a
+/// clause
+pub fn clause_parse(filter: String) -> Result<Clause, DojoError> {
+    dojo_core::filter::parse(&filter).map(Into::into).map_err(|e| DojoError::ParseError {
+        message: e.message,
+        line: e.line as u64,
+        column: e.column as u64,
+    })
+}
+
+/// Print a clause as a textual filter that `clause_parse` turns back into the clause
+pub fn clause_to_string(clause: Clause) -> String {
+    dojo_core::filter::print(&clause.into())
+}
+
+/// Encode a clause as canonical JSON, shared by every binding
+pub fn clause_to_json(clause: Clause) -> Result<String, DojoError> {
+    let clause: torii_proto::Clause = clause.into();
+    clause.to_json().map_err(|e| DojoError::SerializationError { message: e.to_string() })
+}
+
+/// Decode a clause from its canonical JSON encoding
+pub fn clause_from_json(json: String) -> Result<Clause, DojoError> {
+    torii_proto::Clause::from_json(&json)
+        .map(Into::into)
+        .map_err(|e| DojoError::SerializationError { message: e.to_string() })
+}
+
+/// Encode a clause in the compact binary encoding, shared by every binding
+pub fn clause_to_bytes(clause: Clause) -> Result<Vec<u8>, DojoError> {
+    let clause: torii_proto::Clause = clause.into();
+    clause.to_bytes().map_err(|e| DojoError::SerializationError { message: e.to_string() })
+}
+
+/// Decode a clause from its compact binary encoding
+pub fn clause_from_bytes(bytes: Vec<u8>) -> Result<Clause, DojoError> {
+    torii_proto::Clause::from_bytes(&bytes)
+        .map(Into::into)
+        .map_err(|e| DojoError::SerializationError { message: e.to_string() })
+}
+
+/// A problem found when validating a query against the world schemas
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    /// Offending clause in the filter syntax, none for problems outside the clause
+    pub clause: Option<String>,
+    pub message: String,
+}
+
+impl From<dojo_core::validate::Diagnostic> for Diagnostic {
+    fn from(val: dojo_core::validate::Diagnostic) -> Self {
+        Diagnostic { clause: val.clause, message: val.message }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Query {
     pub world_addresses: Vec<FieldElement>,
@@ -280,6 +337,18 @@ pub struct Query {
     pub no_hashed_keys: bool,
     pub models: Vec<String>,
     pub historical: bool,
+    /// Model tags and member paths, e.g. `ns-Stats.health`, to keep from the returned entities.
+    /// Every model and member is kept when unset.
+    pub projection: Option<Vec<String>>,
+}
+
+impl Query {
+    /// Projection of the returned entities, `None` when the query doesn't set one
+    pub(crate) fn projection(&self) -> Option<dojo_core::projection::Projection> {
+        let selectors = self.projection.as_deref().unwrap_or_default();
+        Some(dojo_core::projection::Projection::new(selectors))
+            .filter(|projection| !projection.is_empty())
+    }
 }
 
 impl From<Query> for torii_proto::Query {
@@ -299,6 +368,32 @@ impl From<Query> for torii_proto::Query {
     }
 }
 
+/// Encode a query as canonical JSON, shared by every binding
+pub fn query_to_json(query: Query) -> Result<String, DojoError> {
+    let query: torii_proto::Query = query.into();
+    query.to_json().map_err(|e| DojoError::SerializationError { message: e.to_string() })
+}
+
+/// Decode a query from its canonical JSON encoding
+pub fn query_from_json(json: String) -> Result<Query, DojoError> {
+    torii_proto::Query::from_json(&json)
+        .map(Into::into)
+        .map_err(|e| DojoError::SerializationError { message: e.to_string() })
+}
+
+/// Encode a query in the compact binary encoding, shared by every binding
+pub fn query_to_bytes(query: Query) -> Result<Vec<u8>, DojoError> {
+    let query: torii_proto::Query = query.into();
+    query.to_bytes().map_err(|e| DojoError::SerializationError { message: e.to_string() })
+}
+
+/// Decode a query from its compact binary encoding
+pub fn query_from_bytes(bytes: Vec<u8>) -> Result<Query, DojoError> {
+    torii_proto::Query::from_bytes(&bytes)
+        .map(Into::into)
+        .map_err(|e| DojoError::SerializationError { message: e.to_string() })
+}
+
 impl From<torii_proto::Query> for Query {
     fn from(val: torii_proto::Query) -> Self {
         Query {
@@ -308,6 +403,7 @@ impl From<torii_proto::Query> for Query {
             models: val.models,
             no_hashed_keys: val.no_hashed_keys,
             historical: val.historical,
+            projection: None,
         }
     }
 }
@@ -347,3 +347,182 @@ impl From<Value> for torii_proto::Value {
         }
     }
 }
+
+// Path-based accessors
+fn access<T>(
+    ty: Ty,
+    path: &str,
+    get: impl FnOnce(&dojo_types::schema::Ty, &str) -> anyhow::Result<T>,
+) -> Result<T, DojoError> {
+    get(&ty.into(), path).map_err(|e| DojoError::TypeError { message: e.to_string() })
+}
+
+/// Type and value at a path within a type, e.g. `vec.x`, `dir::Left`, `pair.1` or `items[2].x`.
+/// A variant only resolves when it is the selected one.
+pub fn ty_get(ty: Ty, path: String) -> Result<Ty, DojoError> {
+    access(ty, &path, |ty, path| dojo_core::access::get(ty, path).cloned()).map(Into::into)
+}
+
+pub fn ty_get_u32(ty: Ty, path: String) -> Result<u32, DojoError> {
+    access(ty, &path, dojo_core::access::get_u32)
+}
+
+/// Felt of a `felt252`, `ContractAddress`, `ClassHash` or `EthAddress` at a path
+pub fn ty_get_felt(ty: Ty, path: String) -> Result<FieldElement, DojoError> {
+    access(ty, &path, dojo_core::access::get_felt).map(felt_to_field_element)
+}
+
+pub fn ty_get_u256(ty: Ty, path: String) -> Result<U256, DojoError> {
+    access(ty, &path, dojo_core::access::get_u256).map(u256_to_uniffi)
+}
+
+pub fn ty_get_bool(ty: Ty, path: String) -> Result<bool, DojoError> {
+    access(ty, &path, dojo_core::access::get_bool)
+}
+
+pub fn ty_get_byte_array(ty: Ty, path: String) -> Result<String, DojoError> {
+    access(ty, &path, dojo_core::access::get_byte_array)
+}
+
+/// Name of the selected variant of the enum at a path
+pub fn ty_get_variant(ty: Ty, path: String) -> Result<String, DojoError> {
+    access(ty, &path, dojo_core::access::get_variant)
+}
+
+// World schema introspection
+#[derive(Debug, Clone)]
+pub enum TypeKind {
+    Primitive,
+    Struct,
+    Enum,
+    Tuple,
+    Array,
+    FixedSizeArray,
+    ByteArray,
+}
+
+impl From<dojo_core::schema::TypeKind> for TypeKind {
+    fn from(val: dojo_core::schema::TypeKind) -> Self {
+        match val {
+            dojo_core::schema::TypeKind::Primitive => TypeKind::Primitive,
+            dojo_core::schema::TypeKind::Struct => TypeKind::Struct,
+            dojo_core::schema::TypeKind::Enum => TypeKind::Enum,
+            dojo_core::schema::TypeKind::Tuple => TypeKind::Tuple,
+            dojo_core::schema::TypeKind::Array => TypeKind::Array,
+            dojo_core::schema::TypeKind::FixedSizeArray => TypeKind::FixedSizeArray,
+            dojo_core::schema::TypeKind::ByteArray => TypeKind::ByteArray,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MemberSchema {
+    pub name: String,
+    pub type_name: String,
+    pub kind: TypeKind,
+    pub ty: Ty,
+}
+
+impl From<dojo_core::schema::MemberSchema> for MemberSchema {
+    fn from(val: dojo_core::schema::MemberSchema) -> Self {
+        MemberSchema {
+            name: val.name,
+            type_name: val.type_name,
+            kind: val.kind.into(),
+            ty: val.ty.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ModelSchema {
+    pub tag: String,
+    pub namespace: String,
+    pub name: String,
+    pub selector: FieldElement,
+    pub class_hash: FieldElement,
+    pub contract_address: FieldElement,
+    pub packed_size: u32,
+    pub unpacked_size: u32,
+    pub use_legacy_store: bool,
+    pub is_event: bool,
+    pub keys: Vec<MemberSchema>,
+    pub values: Vec<MemberSchema>,
+}
+
+impl From<dojo_core::schema::ModelSchema> for ModelSchema {
+    fn from(val: dojo_core::schema::ModelSchema) -> Self {
+        ModelSchema {
+            tag: val.tag,
+            namespace: val.namespace,
+            name: val.name,
+            selector: felt_to_field_element(val.selector),
+            class_hash: felt_to_field_element(val.class_hash),
+            contract_address: felt_to_field_element(val.contract_address),
+            packed_size: val.packed_size,
+            unpacked_size: val.unpacked_size,
+            use_legacy_store: val.use_legacy_store,
+            is_event: val.is_event,
+            keys: val.keys.into_iter().map(Into::into).collect(),
+            values: val.values.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+// Schema upgrades
+#[derive(Debug, Clone)]
+pub enum TypeChangeKind {
+    Added,
+    Removed,
+    Changed,
+    KeyChanged,
+}
+
+impl From<dojo_core::upgrade::TypeChangeKind> for TypeChangeKind {
+    fn from(val: dojo_core::upgrade::TypeChangeKind) -> Self {
+        match val {
+            dojo_core::upgrade::TypeChangeKind::Added => TypeChangeKind::Added,
+            dojo_core::upgrade::TypeChangeKind::Removed => TypeChangeKind::Removed,
+            dojo_core::upgrade::TypeChangeKind::Changed => TypeChangeKind::Changed,
+            dojo_core::upgrade::TypeChangeKind::KeyChanged => TypeChangeKind::KeyChanged,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TypeChange {
+    pub path: String,
+    pub kind: TypeChangeKind,
+    pub old_type: Option<Ty>,
+    pub new_type: Option<Ty>,
+}
+
+impl From<dojo_core::upgrade::TypeChange> for TypeChange {
+    fn from(val: dojo_core::upgrade::TypeChange) -> Self {
+        TypeChange {
+            path: val.path,
+            kind: val.kind.into(),
+            old_type: val.old.map(Into::into),
+            new_type: val.new.map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SchemaUpgrade {
+    pub world_address: FieldElement,
+    pub old_schema: Option<ModelSchema>,
+    pub new_schema: ModelSchema,
+    pub changes: Vec<TypeChange>,
+}
+
+impl From<dojo_core::upgrade::SchemaUpgrade> for SchemaUpgrade {
+    fn from(val: dojo_core::upgrade::SchemaUpgrade) -> Self {
+        SchemaUpgrade {
+            world_address: felt_to_field_element(val.world_address),
+            old_schema: val.old.map(Into::into),
+            new_schema: val.new.into(),
+            changes: val.changes.into_iter().map(Into::into).collect(),
+        }
+    }
+}
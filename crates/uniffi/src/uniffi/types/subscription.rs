@@ -0,0 +1,74 @@
+// Subscription types - SubscriptionState, DeliveryPolicy, DeliveryStats
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub enum SubscriptionState {
+    Connecting,
+    Live,
+    Reconnecting { attempt: u32, delay_ms: u64 },
+    Closed { reason: String },
+}
+
+impl From<dojo_core::status::SubscriptionState> for SubscriptionState {
+    fn from(val: dojo_core::status::SubscriptionState) -> Self {
+        match val {
+            dojo_core::status::SubscriptionState::Connecting => SubscriptionState::Connecting,
+            dojo_core::status::SubscriptionState::Live => SubscriptionState::Live,
+            dojo_core::status::SubscriptionState::Reconnecting { attempt, delay } => {
+                SubscriptionState::Reconnecting { attempt, delay_ms: delay.as_millis() as u64 }
+            }
+            dojo_core::status::SubscriptionState::Closed { reason } => {
+                SubscriptionState::Closed { reason }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Overflow {
+    DropOldest,
+    DropNewest,
+    Wait,
+}
+
+impl From<Overflow> for dojo_core::delivery::Overflow {
+    fn from(val: Overflow) -> Self {
+        match val {
+            Overflow::DropOldest => dojo_core::delivery::Overflow::DropOldest,
+            Overflow::DropNewest => dojo_core::delivery::Overflow::DropNewest,
+            Overflow::Wait => dojo_core::delivery::Overflow::Wait,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DeliveryPolicy {
+    pub buffer_size: Option<u64>,
+    pub overflow: Overflow,
+    pub coalesce: bool,
+    pub min_interval_ms: Option<u64>,
+}
+
+impl From<DeliveryPolicy> for dojo_core::delivery::DeliveryPolicy {
+    fn from(val: DeliveryPolicy) -> Self {
+        dojo_core::delivery::DeliveryPolicy {
+            buffer_size: val.buffer_size.map(|size| size as usize),
+            overflow: val.overflow.into(),
+            coalesce: val.coalesce,
+            min_interval: val.min_interval_ms.map(Duration::from_millis),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DeliveryStats {
+    pub delivered: u64,
+    pub dropped: u64,
+    pub coalesced: u64,
+}
+
+impl From<dojo_core::delivery::DeliveryStats> for DeliveryStats {
+    fn from(val: dojo_core::delivery::DeliveryStats) -> Self {
+        DeliveryStats { delivered: val.delivered, dropped: val.dropped, coalesced: val.coalesced }
+    }
+}
@@ -5,15 +5,40 @@
 /// and Torii client interactions
 mod utils;
 
+use std::fmt::Display;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::rc::Rc;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use cainome::cairo_serde::{self, CairoSerde};
+use dojo_core::batch;
+use dojo_core::cache::{CacheKey, Lookup, Scope};
+use dojo_core::change::EntityClassifier;
+use dojo_core::codec::Codec;
+use dojo_core::connection::Connection;
 use dojo_core::constants;
-use dojo_core::utils::watch_tx;
+use dojo_core::delivery::{self, Coalesce, DeliveryControl};
+use dojo_core::diff::EntityDiffer;
+use dojo_core::dispatch::Dispatcher;
+use dojo_core::history::{self, Timelines};
+use dojo_core::mux::{EntityFilter, Lease, Mux, TokenBalanceFilter, Topic};
+use dojo_core::optimistic::Optimistic;
+use dojo_core::pagination::{Paged, Paginator};
+use dojo_core::polling::EntitySource;
+use dojo_core::replay::{Buffer, Recorder, Replay};
+use dojo_core::subscription::{
+    invalidate_token_balances, subscribe, subscribe_entities, without_id, Handle, Live, Subscribed,
+};
+use dojo_core::upgrade::{upgrade_clauses, SchemaWatcher};
+use dojo_core::utils::{watch_tx, watch_tx_result};
 use dojo_world::contracts::naming::compute_selector_from_tag;
-use futures::{FutureExt, StreamExt};
+use futures::future::{select, Either, LocalBoxFuture};
+use futures::StreamExt;
 use js_sys::Array;
+use serde::de::DeserializeOwned;
 use serde::Serialize;
 use starknet::accounts::{
     Account as _, ConnectedAccount as _, ExecutionEncoding, SingleOwnerAccount,
@@ -23,21 +48,23 @@ use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::{JsonRpcClient, Provider as _};
 use starknet::signers::LocalWallet;
 use starknet_crypto::poseidon_hash_many;
-use stream_cancel::{StreamExt as _, Tripwire};
-use tokio::sync::oneshot;
+use stream_cancel::Tripwire;
 use wasm_bindgen::prelude::*;
 
 mod types;
 
 use types::{
     Account, AchievementProgression, AchievementQuery, Achievements, Activities, Activity,
-    ActivityQuery, AggregationEntry, AggregationQuery, Aggregations, BlockId, Call, Calls, Clause,
-    ClientConfig, Contract, ContractQuery, Contracts, ControllerQuery, Controllers, Entities,
-    Entity, KeysClauses, Message, PlayerAchievementQuery, PlayerAchievements, Provider, Query,
-    SearchQuery, SearchResponse, Signature, Subscription, Token, TokenBalance, TokenBalanceQuery,
-    TokenBalances, TokenContractQuery, TokenContracts, TokenQuery, TokenTransfer,
-    TokenTransferQuery, TokenTransfers, Tokens, ToriiClient, Transaction, TransactionFilter,
-    TransactionQuery, Transactions, WasmU256,
+    ActivityQuery, AggregationEntry, AggregationQuery, Aggregations, At, BlockId, CacheStats, Call,
+    Calls, Clause, ClientConfig, Contract, ContractQuery, Contracts, ControllerQuery, Controllers,
+    DeliveryPolicy, DeliveryStats, Diagnostics, EndpointStatuses, Entities, Entity, EntityChange,
+    EntityList, EntityLookup, EntityVersions, FieldChange, HistoryRange, KeysClause, KeysClauses,
+    Message, Model, ModelDispatcher, ModelSchema, ModelSchemas, OptimisticStore, PageIterator,
+    PlayerAchievementQuery, PlayerAchievements, Provider, Query, SchemaUpgrade, SearchQuery,
+    SearchResponse, Signature, Sink, Subscription, SubscriptionState, SubscriptionStream, Token,
+    TokenBalance, TokenBalanceQuery, TokenBalances, TokenContractQuery, TokenContracts, TokenQuery,
+    TokenTransfer, TokenTransferQuery, TokenTransfers, Tokens, ToriiClient, Transaction,
+    TransactionFilter, TransactionQuery, Transactions, Ty, TyBuilder, WasmU256, WorldSchema,
 };
 
 const JSON_COMPAT_SERIALIZER: serde_wasm_bindgen::Serializer =
@@ -676,12 +703,650 @@ pub fn parse_cairo_short_string(str: &str) -> Result<String, JsValue> {
     Ok(string)
 }
 
+/// Reads the value at a path of a model with `get`, see [`dojo_core::access::get`]
+fn access<T>(
+    model: Model,
+    path: &str,
+    get: impl FnOnce(&dojo_types::schema::Ty, &str) -> anyhow::Result<T>,
+) -> Result<T, JsValue> {
+    let children = model
+        .0
+        .into_iter()
+        .map(|(name, ty)| {
+            Ok(dojo_types::schema::Member {
+                name,
+                key: ty.key,
+                ty: utils::parse_ty_from_json(&ty)?,
+            })
+        })
+        .collect::<Result<_, String>>()
+        .map_err(|e| JsValue::from(format!("failed to parse model: {e}")))?;
+    let model = dojo_types::schema::Ty::Struct(dojo_types::schema::Struct {
+        name: String::new(),
+        children,
+    });
+
+    get(&model, path).map_err(|e| JsValue::from(e.to_string()))
+}
+
+/// Gets the type and value at a path within a model.
+///
+/// Paths are made of struct members and tuple indices separated by dots, enum variants
+/// following `::` and array indices in brackets, e.g. `vec.x`, `dir::Left`, `pair.1` or
+/// `items[2].x`. A variant only resolves when it is the selected one, the only one models hold
+///
+/// # Parameters
+/// * `model` - Model of an entity
+/// * `path` - Path of the member, e.g. `vec.x`
+///
+/// # Returns
+/// Result containing the type at the path, or an error naming the part of the path that doesn't
+/// resolve
+#[wasm_bindgen(js_name = modelGet)]
+pub fn model_get(model: Model, path: &str) -> Result<Ty, JsValue> {
+    let ty = access(model, path, |ty, path| dojo_core::access::get(ty, path).cloned())?;
+    Ok(utils::parse_ty_as_json_str(&ty, false))
+}
+
+/// Gets the u32 at a path within a model, see `modelGet`
+///
+/// # Parameters
+/// * `model` - Model of an entity
+/// * `path` - Path of the member, e.g. `vec.x`
+///
+/// # Returns
+/// Result containing the value, or an error if the path doesn't resolve to one with a value
+#[wasm_bindgen(js_name = modelGetU32)]
+pub fn model_get_u32(model: Model, path: &str) -> Result<u32, JsValue> {
+    access(model, path, dojo_core::access::get_u32)
+}
+
+/// Gets the felt252, ContractAddress, ClassHash or EthAddress as a hex string at a path within a model, see `modelGet`
+///
+/// # Parameters
+/// * `model` - Model of an entity
+/// * `path` - Path of the member, e.g. `vec.x`
+///
+/// # Returns
+/// Result containing the value, or an error if the path doesn't resolve to one with a value
+#[wasm_bindgen(js_name = modelGetFelt)]
+pub fn model_get_felt(model: Model, path: &str) -> Result<String, JsValue> {
+    access(model, path, dojo_core::access::get_felt).map(|value| format!("{value:#x}"))
+}
+
+/// Gets the u256 as a hex string at a path within a model, see `modelGet`
+///
+/// # Parameters
+/// * `model` - Model of an entity
+/// * `path` - Path of the member, e.g. `vec.x`
+///
+/// # Returns
+/// Result containing the value, or an error if the path doesn't resolve to one with a value
+#[wasm_bindgen(js_name = modelGetU256)]
+pub fn model_get_u256(model: Model, path: &str) -> Result<String, JsValue> {
+    access(model, path, dojo_core::access::get_u256).map(|value| format!("0x{value:x}"))
+}
+
+/// Gets the bool at a path within a model, see `modelGet`
+///
+/// # Parameters
+/// * `model` - Model of an entity
+/// * `path` - Path of the member, e.g. `vec.x`
+///
+/// # Returns
+/// Result containing the value, or an error if the path doesn't resolve to one with a value
+#[wasm_bindgen(js_name = modelGetBool)]
+pub fn model_get_bool(model: Model, path: &str) -> Result<bool, JsValue> {
+    access(model, path, dojo_core::access::get_bool)
+}
+
+/// Gets the ByteArray as a string at a path within a model, see `modelGet`
+///
+/// # Parameters
+/// * `model` - Model of an entity
+/// * `path` - Path of the member, e.g. `vec.x`
+///
+/// # Returns
+/// Result containing the value, or an error if the path doesn't resolve to one with a value
+#[wasm_bindgen(js_name = modelGetByteArray)]
+pub fn model_get_byte_array(model: Model, path: &str) -> Result<String, JsValue> {
+    access(model, path, dojo_core::access::get_byte_array)
+}
+
+/// Gets the name of the selected variant of the enum at a path within a model, see `modelGet`
+///
+/// # Parameters
+/// * `model` - Model of an entity
+/// * `path` - Path of the member, e.g. `vec.x`
+///
+/// # Returns
+/// Result containing the value, or an error if the path doesn't resolve to one with a value
+#[wasm_bindgen(js_name = modelGetVariant)]
+pub fn model_get_variant(model: Model, path: &str) -> Result<String, JsValue> {
+    access(model, path, dojo_core::access::get_variant)
+}
+
+/// Parses a textual filter into a clause.
+///
+/// Filters such as `ns-Position.x > 10 and keys(ns-Position) = [0x1, *]` combine member
+/// comparisons, keys and hashed keys clauses with `and`, `or` and parentheses.
+///
+/// # Parameters
+/// * `filter` - Filter to parse
+///
+/// # Returns
+/// Result containing the clause, or an error with the line and column of the failure
+#[wasm_bindgen(js_name = clauseParse)]
+pub fn clause_parse(filter: &str) -> Result<Clause, JsValue> {
+    let clause = dojo_core::filter::parse(filter)
+        .map_err(|e| JsValue::from(format!("failed to parse filter: {e}")))?;
+
+    Ok(clause.into())
+}
+
+/// Prints a clause as a textual filter, which `clauseParse` turns back into the clause
+///
+/// # Parameters
+/// * `clause` - Clause to print
+///
+/// # Returns
+/// The filter
+#[wasm_bindgen(js_name = clauseToString)]
+pub fn clause_to_string(clause: Clause) -> String {
+    dojo_core::filter::print(&clause.into())
+}
+
+/// Encodes a query as canonical JSON, shared by every binding
+///
+/// # Parameters
+/// * `query` - Query to encode
+///
+/// # Returns
+/// Result containing the JSON string or error
+#[wasm_bindgen(js_name = queryToJson)]
+pub fn query_to_json(query: Query) -> Result<String, JsValue> {
+    let query: torii_proto::Query = query.into();
+
+    query.to_json().map_err(|e| JsValue::from(e.to_string()))
+}
+
+/// Decodes a query from its canonical JSON encoding
+///
+/// # Parameters
+/// * `json` - JSON string of the query
+///
+/// # Returns
+/// Result containing the query or error
+#[wasm_bindgen(js_name = queryFromJson)]
+pub fn query_from_json(json: &str) -> Result<Query, JsValue> {
+    let query = torii_proto::Query::from_json(json).map_err(|e| JsValue::from(e.to_string()))?;
+
+    Ok(query.into())
+}
+
+/// Encodes a query in the compact binary encoding, shared by every binding
+///
+/// # Parameters
+/// * `query` - Query to encode
+///
+/// # Returns
+/// Result containing the encoded bytes or error
+#[wasm_bindgen(js_name = queryToBytes)]
+pub fn query_to_bytes(query: Query) -> Result<Vec<u8>, JsValue> {
+    let query: torii_proto::Query = query.into();
+
+    query.to_bytes().map_err(|e| JsValue::from(e.to_string()))
+}
+
+/// Decodes a query from its compact binary encoding
+///
+/// # Parameters
+/// * `bytes` - Encoded query
+///
+/// # Returns
+/// Result containing the query or error
+#[wasm_bindgen(js_name = queryFromBytes)]
+pub fn query_from_bytes(bytes: &[u8]) -> Result<Query, JsValue> {
+    let query = torii_proto::Query::from_bytes(bytes).map_err(|e| JsValue::from(e.to_string()))?;
+
+    Ok(query.into())
+}
+
+/// Encodes a clause as canonical JSON, shared by every binding
+///
+/// # Parameters
+/// * `clause` - Clause to encode
+///
+/// # Returns
+/// Result containing the JSON string or error
+#[wasm_bindgen(js_name = clauseToJson)]
+pub fn clause_to_json(clause: Clause) -> Result<String, JsValue> {
+    let clause: torii_proto::Clause = clause.into();
+
+    clause.to_json().map_err(|e| JsValue::from(e.to_string()))
+}
+
+/// Decodes a clause from its canonical JSON encoding
+///
+/// # Parameters
+/// * `json` - JSON string of the clause
+///
+/// # Returns
+/// Result containing the clause or error
+#[wasm_bindgen(js_name = clauseFromJson)]
+pub fn clause_from_json(json: &str) -> Result<Clause, JsValue> {
+    let clause = torii_proto::Clause::from_json(json).map_err(|e| JsValue::from(e.to_string()))?;
+
+    Ok(clause.into())
+}
+
+/// Encodes a clause in the compact binary encoding, shared by every binding
+///
+/// # Parameters
+/// * `clause` - Clause to encode
+///
+/// # Returns
+/// Result containing the encoded bytes or error
+#[wasm_bindgen(js_name = clauseToBytes)]
+pub fn clause_to_bytes(clause: Clause) -> Result<Vec<u8>, JsValue> {
+    let clause: torii_proto::Clause = clause.into();
+
+    clause.to_bytes().map_err(|e| JsValue::from(e.to_string()))
+}
+
+/// Decodes a clause from its compact binary encoding
+///
+/// # Parameters
+/// * `bytes` - Encoded clause
+///
+/// # Returns
+/// Result containing the clause or error
+#[wasm_bindgen(js_name = clauseFromBytes)]
+pub fn clause_from_bytes(bytes: &[u8]) -> Result<Clause, JsValue> {
+    let clause =
+        torii_proto::Clause::from_bytes(bytes).map_err(|e| JsValue::from(e.to_string()))?;
+
+    Ok(clause.into())
+}
+
+/// Runs a subscription on its own stream, completing it once the stream is live
+async fn spawn_subscription<Q, D, R>(
+    subscribed: Subscribed<Q, D, R>,
+    error: &str,
+) -> Result<Subscription, JsValue>
+where
+    Q: Clone + 'static,
+    D: Future<Output = ()> + 'static,
+    R: Future<Output = ()> + 'static,
+{
+    let Subscribed { handle, deliver, run } = subscribed;
+    wasm_bindgen_futures::spawn_local(deliver);
+    wasm_bindgen_futures::spawn_local(run);
+    if let Err(err) = handle.established().await {
+        return Err(JsValue::from(format!("{error}: {err}")));
+    }
+
+    let Handle { trigger, status, delivery, live } = handle;
+    let following = live.clone();
+    let stream =
+        SubscriptionStream::Own { id: Box::new(move || following.id()), live: Box::new(live) };
+    Ok(Subscription { trigger, status, delivery, stream })
+}
+
+/// Completes a subscription sharing a multiplexed stream, once the stream is live
+async fn shared_subscription<T: Topic, S>(
+    lease: Lease<T, S>,
+    delivery: DeliveryControl,
+    error: &str,
+) -> Result<Subscription, JsValue>
+where
+    Lease<T, S>: 'static,
+{
+    if !lease.status.established().await {
+        return Err(JsValue::from(error));
+    }
+
+    // The lease leaves the stream once dropped, there is nothing to trigger
+    let (trigger, _) = Tripwire::new();
+    let status = lease.status.clone();
+    let stream = SubscriptionStream::Shared { id: lease.id, lease: Box::new(lease) };
+    Ok(Subscription { trigger, status, delivery, stream })
+}
+
+/// Changes the query of a subscription sharing a multiplexed stream
+fn update_shared<T: Topic, S: FnMut(T::Item)>(
+    mux: &Mux<T, S>,
+    id: u64,
+    query: T::Query,
+) -> Result<(), JsValue> {
+    if !mux.shareable(&query) {
+        return Err(JsValue::from("Query cannot be served by a shared stream"));
+    }
+    if !mux.update(id, query) {
+        return Err(JsValue::from("failed to update subscription: subscription closed"));
+    }
+
+    Ok(())
+}
+
+/// Awaits a future, failing with a timeout error once the given duration elapses
+async fn with_timeout<F, T, E>(timeout: Option<Duration>, future: F) -> Result<T, String>
+where
+    F: Future<Output = Result<T, E>>,
+    E: Display,
+{
+    let Some(timeout) = timeout else {
+        return future.await.map_err(|e| e.to_string());
+    };
+
+    match select(Box::pin(future), gloo_timers::future::sleep(timeout)).await {
+        Either::Left((result, _)) => result.map_err(|e| e.to_string()),
+        Either::Right(_) => Err(format!("timed out after {}ms", timeout.as_millis())),
+    }
+}
+
+/// Fetches the page of a query from the active endpoint
+type FetchPage<Q, T> =
+    Box<dyn Fn(Q) -> LocalBoxFuture<'static, anyhow::Result<torii_proto::Page<T>>>>;
+
+/// Pages of a query in their JavaScript representation, see [`PageIterator`]
+pub(crate) trait Pages {
+    fn next_page(&mut self) -> LocalBoxFuture<'_, Result<Option<JsValue>, String>>;
+    fn previous_page(&mut self) -> LocalBoxFuture<'_, Result<Option<JsValue>, String>>;
+    fn next_item(&mut self) -> LocalBoxFuture<'_, Result<Option<JsValue>, String>>;
+}
+
+/// Walks the pages of a query, converting their items to `U`
+struct TypedPages<Q, T, U> {
+    connection: Arc<Connection>,
+    paginator: Paginator<Q, T, FetchPage<Q, T>>,
+    /// Fetches the next page in the background once a page is returned
+    prefetch: bool,
+    item: PhantomData<U>,
+}
+
+impl<Q, T, U> TypedPages<Q, T, U>
+where
+    Q: Paged + Serialize + 'static,
+    T: Serialize + DeserializeOwned + 'static,
+    U: From<T> + Serialize + 'static,
+{
+    /// Pages are requested as `kind` requests, see [`Connection::exchange`].
+    fn new<F, Fut, E>(
+        connection: Arc<Connection>,
+        kind: &'static str,
+        query: Q,
+        prefetch: Option<bool>,
+        fetch: F,
+    ) -> Self
+    where
+        F: Fn(Arc<torii_client::Client>, Q) -> Fut + 'static,
+        Fut: Future<Output = Result<torii_proto::Page<T>, E>> + 'static,
+        anyhow::Error: From<E>,
+    {
+        let fetch_connection = connection.clone();
+        let fetch = Rc::new(fetch);
+        let fetch: FetchPage<Q, T> = Box::new(move |query| {
+            let (connection, fetch) = (fetch_connection.clone(), fetch.clone());
+            let key = CacheKey::new(kind, &query);
+            Box::pin(async move { connection.exchange(key, |inner| fetch(inner, query)).await })
+        });
+
+        Self {
+            connection,
+            paginator: Paginator::new(query, fetch),
+            prefetch: prefetch.unwrap_or_default(),
+            item: PhantomData,
+        }
+    }
+
+    /// Probes the endpoints after a request went unanswered, then starts fetching the next page
+    /// when prefetching
+    async fn settle<R>(&mut self, result: &Result<R, String>) {
+        if result.is_err() {
+            self.connection.pool().recover_from_request().await;
+        }
+        if self.prefetch {
+            if let Some(task) = self.paginator.prefetch() {
+                wasm_bindgen_futures::spawn_local(task);
+            }
+        }
+    }
+
+    fn page(page: torii_proto::Page<T>) -> Result<JsValue, String> {
+        types::Page::<U>::from(page).serialize(&JSON_COMPAT_SERIALIZER).map_err(|e| e.to_string())
+    }
+}
+
+impl<Q, T, U> Pages for TypedPages<Q, T, U>
+where
+    Q: Paged + Serialize + 'static,
+    T: Serialize + DeserializeOwned + 'static,
+    U: From<T> + Serialize + 'static,
+{
+    fn next_page(&mut self) -> LocalBoxFuture<'_, Result<Option<JsValue>, String>> {
+        Box::pin(async move {
+            let timeout = self.connection.config().request_timeout;
+            let page = with_timeout(timeout, self.paginator.next_page()).await;
+            self.settle(&page).await;
+            page?.map(Self::page).transpose()
+        })
+    }
+
+    fn previous_page(&mut self) -> LocalBoxFuture<'_, Result<Option<JsValue>, String>> {
+        Box::pin(async move {
+            let timeout = self.connection.config().request_timeout;
+            let page = with_timeout(timeout, self.paginator.previous_page()).await;
+            self.settle(&page).await;
+            page?.map(Self::page).transpose()
+        })
+    }
+
+    fn next_item(&mut self) -> LocalBoxFuture<'_, Result<Option<JsValue>, String>> {
+        Box::pin(async move {
+            let timeout = self.connection.config().request_timeout;
+            let item = with_timeout(timeout, self.paginator.next_item()).await;
+            self.settle(&item).await;
+            item?
+                .map(|item| U::from(item).serialize(&JSON_COMPAT_SERIALIZER))
+                .transpose()
+                .map_err(|e| e.to_string())
+        })
+    }
+}
+
+/// Wraps pages in a [`PageIterator`] usable with `for await`, which gets the iterator by
+/// calling its `[Symbol.asyncIterator]` method. `Object.prototype.valueOf` returns the object
+/// it is called on.
+fn page_iterator(pages: impl Pages + 'static) -> JsValue {
+    let iterator =
+        JsValue::from(PageIterator { inner: futures::lock::Mutex::new(Box::new(pages)) });
+    if let Ok(value_of) = js_sys::Reflect::get(&js_sys::Object::new(), &JsValue::from("valueOf")) {
+        let _ = js_sys::Reflect::set(&iterator, &js_sys::Symbol::async_iterator(), &value_of);
+    }
+    iterator
+}
+
+impl ToriiClient {
+    fn from_connection(inner: Arc<Connection>) -> Self {
+        Self {
+            inner,
+            entities: Mux::new(dojo_core::mux::Entities),
+            event_messages: Mux::new(dojo_core::mux::EventMessages),
+            token_balances: Mux::new(dojo_core::mux::TokenBalances),
+            recording: None,
+        }
+    }
+
+    /// Subscribes to entities or event messages, joining the multiplexed stream of `mux` when
+    /// enabled and the filter can be shared
+    async fn entity_subscription<T>(
+        &self,
+        mux: &Mux<T, Sink<torii_proto::schema::Entity>>,
+        source: EntitySource,
+        filter: EntityFilter,
+        control: DeliveryControl,
+        on_item: impl FnMut(torii_proto::schema::Entity) + 'static,
+        error: &str,
+    ) -> Result<Subscription, JsValue>
+    where
+        T: Topic<Query = EntityFilter, Item = torii_proto::schema::Entity>,
+        Lease<T, Sink<torii_proto::schema::Entity>>: 'static,
+    {
+        let connection = self.inner.clone();
+        connection
+            .validate_clause(filter.clause.as_ref(), &filter.world_addresses)
+            .await
+            .map_err(|err| JsValue::from(err.to_string()))?;
+
+        if connection.config().multiplex && mux.shareable(&filter) {
+            let (on_item, delivery, deliver) = delivery::channel_with(
+                control,
+                Some(Coalesce::ENTITIES),
+                gloo_timers::future::sleep,
+                on_item,
+            );
+            wasm_bindgen_futures::spawn_local(deliver);
+            let (lease, task) =
+                mux.join(connection, gloo_timers::future::sleep, filter, Box::new(on_item));
+            if let Some(task) = task {
+                wasm_bindgen_futures::spawn_local(task);
+            }
+            return shared_subscription(lease, delivery, error).await;
+        }
+
+        let subscribed = subscribe_entities(
+            connection,
+            source,
+            filter,
+            gloo_timers::future::sleep,
+            control,
+            on_item,
+        );
+        spawn_subscription(subscribed, error).await
+    }
+
+    /// Changes the query of a subscription on its own stream, kept for its next reconnection
+    async fn update_own<Q, F, Fut, R, E>(
+        &self,
+        subscription: &Subscription,
+        query: Q,
+        update: F,
+    ) -> Result<(), JsValue>
+    where
+        Q: Clone + 'static,
+        F: FnOnce(u64, Q) -> Fut,
+        Fut: Future<Output = Result<R, E>>,
+        E: Display,
+    {
+        let live = match &subscription.stream {
+            SubscriptionStream::Own { live, .. } => live.downcast_ref::<Live<Q>>(),
+            SubscriptionStream::Shared { .. } => None,
+        };
+        let Some(live) = live else {
+            return Err(JsValue::from("Subscription cannot be updated with this query"));
+        };
+
+        self.request(live.update(query, update))
+            .await
+            .map(|_| ())
+            .map_err(|err| JsValue::from(format!("failed to update subscription: {err}")))
+    }
+
+    /// Awaits a Torii request, bounded by the configured request timeout
+    ///
+    /// A request that reached Torii but went unanswered triggers a health probe of the active
+    /// endpoint, so that the next one is routed to a healthy replica when fallback endpoints
+    /// are configured.
+    async fn request<F, T, E>(&self, future: F) -> Result<T, String>
+    where
+        F: Future<Output = Result<T, E>>,
+        E: Display,
+    {
+        let result = with_timeout(self.inner.config().request_timeout, future).await;
+        if result.is_err() {
+            self.inner.pool().recover_from_request().await;
+        }
+
+        result
+    }
+
+    /// Runs a query through the client's [`QueryCache`](dojo_core::cache::QueryCache) when one
+    /// is configured
+    ///
+    /// Expired results are served right away when the cache revalidates them in the background.
+    async fn cached_request<T, F, Fut, E>(
+        &self,
+        key: CacheKey,
+        scope: impl FnOnce(&T) -> Scope + 'static,
+        fetch: F,
+    ) -> Result<T, String>
+    where
+        T: Clone + Serialize + DeserializeOwned + Send + Sync + 'static,
+        F: FnOnce(Arc<torii_client::Client>) -> Fut + 'static,
+        Fut: Future<Output = Result<T, E>> + 'static,
+        anyhow::Error: From<E>,
+    {
+        let Some(cache) = self.inner.cache() else {
+            return self.request(self.inner.exchange(key, fetch)).await;
+        };
+
+        match cache.lookup::<T>(&key) {
+            Lookup::Fresh(value) | Lookup::Stale(value, None) => Ok(value),
+            Lookup::Stale(value, Some(ticket)) => {
+                let (cache, connection) = (cache.clone(), self.inner.clone());
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Ok(fresh) = connection.exchange(key, fetch).await {
+                        cache.store(ticket, scope(&fresh), fresh);
+                    }
+                });
+                Ok(value)
+            }
+            Lookup::Miss(ticket) => {
+                let value = self.request(self.inner.exchange(key, fetch)).await?;
+                cache.store(ticket, scope(&value), value.clone());
+                Ok(value)
+            }
+        }
+    }
+
+    /// Fetches the history of entities from the active endpoint, see
+    /// [`history::fetch_timelines`]
+    fn timelines<'a>(
+        &self,
+        world_addresses: &'a [Felt],
+        ids: &'a [Felt],
+        models: Vec<String>,
+    ) -> impl Future<Output = anyhow::Result<Timelines>> + 'a {
+        let connection = self.inner.clone();
+        let sql = connection.clone();
+        history::fetch_timelines(
+            world_addresses,
+            ids,
+            models,
+            move |query| {
+                let (connection, key) =
+                    (connection.clone(), CacheKey::normalized("entities", &query));
+                async move {
+                    connection
+                        .exchange(key, |inner| async move { inner.entities(query).await })
+                        .await
+                }
+            },
+            move |query| async move {
+                let key = CacheKey::new("sql", &query);
+                sql.exchange(key, |inner| async move { inner.sql(query).await }).await
+            },
+        )
+    }
+}
+
 #[wasm_bindgen]
 impl ToriiClient {
     /// Creates a new Torii client with the given configuration
     ///
     /// # Parameters
-    /// * `config` - Client configuration including URLs and world address
+    /// * `config` - Client configuration including URLs, fallback endpoints and world address
     ///
     /// # Returns
     /// Result containing ToriiClient instance or error
@@ -689,139 +1354,263 @@ impl ToriiClient {
     #[allow(deprecated)]
     pub async fn new(config: ClientConfig) -> Result<ToriiClient, JsValue> {
         console_error_panic_hook::set_once();
-        let ClientConfig { torii_url, .. } = config;
+        let config: dojo_core::config::ClientConfig = config.into();
 
-        let client = torii_client::Client::new(torii_url)
+        let connection = with_timeout(config.connect_timeout, Connection::connect(config.clone()))
             .await
             .map_err(|err| JsValue::from(format!("failed to build client: {err}")))?;
 
-        Ok(ToriiClient { inner: Arc::new(client) })
+        Ok(ToriiClient::from_connection(Arc::new(connection)))
     }
 
-    /// Gets controllers along with their usernames for the given contract addresses
+    /// Creates a Torii client that records its session
+    ///
+    /// Every query response and subscription message is kept in memory along with the time it
+    /// came in, and can be saved with `recording` to be played back with `ToriiClient.replay`.
     ///
     /// # Parameters
-    /// * `contract_addresses` - Array of contract addresses as hex strings. If empty, all
-    ///   controllers will be returned.
+    /// * `config` - Client configuration including URLs, fallback endpoints and world address
     ///
     /// # Returns
-    /// Result containing controllers or error
-    #[wasm_bindgen(js_name = getControllers)]
-    pub async fn get_controllers(&self, query: ControllerQuery) -> Result<Controllers, JsValue> {
-        let query = query.into();
+    /// Result containing ToriiClient instance or error
+    #[wasm_bindgen(js_name = record)]
+    pub async fn record(config: ClientConfig) -> Result<ToriiClient, JsValue> {
+        console_error_panic_hook::set_once();
+        let config: dojo_core::config::ClientConfig = config.into();
 
-        let controllers = self
-            .inner
-            .controllers(query)
+        let connection = with_timeout(config.connect_timeout, Connection::connect(config.clone()))
             .await
-            .map_err(|e| JsValue::from(format!("failed to get controllers: {e}")))?;
+            .map_err(|err| JsValue::from(format!("failed to build client: {err}")))?;
 
-        Ok(Controllers(controllers.into()))
+        let buffer = Buffer::default();
+        let recorder = Recorder::new(buffer.clone(), js_sys::Date::now() as u64);
+        let mut client = ToriiClient::from_connection(Arc::new(connection.with_recorder(recorder)));
+        client.recording = Some(buffer);
+        Ok(client)
     }
 
-    /// Gets contracts matching the given query
+    /// Creates a Torii client playing back a session recorded by a client created with
+    /// `ToriiClient.record`
+    ///
+    /// Queries get the recorded responses and subscriptions the recorded messages, without a
+    /// network. The replay is deterministic as long as the same queries are made and the
+    /// subscriptions are opened in the same order as in the recorded session.
     ///
     /// # Parameters
-    /// * `query` - ContractQuery parameters
+    /// * `config` - Client configuration, which should multiplex subscriptions and cache queries
+    ///   like the recorded client did. Its URLs aren't used.
+    /// * `recording` - Recording returned by `recording`
+    /// * `speed` - Playback speed, 1 being the original speed and Infinity playing messages
+    ///   without delays. Defaults to 1.
     ///
     /// # Returns
-    /// Result containing contracts or error
-    #[wasm_bindgen(js_name = getContracts)]
-    pub async fn get_contracts(&self, query: ContractQuery) -> Result<Contracts, JsValue> {
-        let query = query.into();
+    /// Result containing ToriiClient instance or error
+    #[wasm_bindgen(js_name = replay)]
+    pub fn replay(
+        config: ClientConfig,
+        recording: &str,
+        speed: Option<f64>,
+    ) -> Result<ToriiClient, JsValue> {
+        console_error_panic_hook::set_once();
+        let replay = Replay::parse(recording, speed.unwrap_or(1.0))
+            .map_err(|err| JsValue::from(format!("failed to read recording: {err}")))?;
 
-        let contracts = self
-            .inner
-            .contracts(query)
-            .await
-            .map_err(|e| JsValue::from(format!("failed to get contracts: {e}")))?;
+        Ok(ToriiClient::from_connection(Arc::new(Connection::replay(config.into(), replay))))
+    }
 
-        Ok(Contracts(contracts.into_iter().map(|c| c.into()).collect()))
+    /// Returns the session recorded so far
+    ///
+    /// # Returns
+    /// The recording, one JSON document per line, or undefined when the client was not created
+    /// with `ToriiClient.record`
+    #[wasm_bindgen(js_name = recording)]
+    pub fn recording(&self) -> Option<String> {
+        self.recording.as_ref().map(Buffer::contents)
     }
 
-    /// Gets transactions matching the given query
+    /// Probes every configured endpoint and returns their health
     ///
-    /// # Parameters
-    /// * `query` - Query parameters
+    /// Switches to a healthier replica if the active endpoint is down or lagging behind.
     ///
     /// # Returns
-    /// Result containing transactions or error
-    #[wasm_bindgen(js_name = getTransactions)]
-    pub async fn get_transactions(&self, query: TransactionQuery) -> Result<Transactions, JsValue> {
-        let query = query.into();
-        let transactions = self
-            .inner
-            .transactions(query)
-            .await
-            .map_err(|e| JsValue::from(format!("failed to get transactions: {e}")))?;
-        Ok(Transactions(transactions.into()))
+    /// Array of endpoint statuses
+    #[wasm_bindgen(js_name = getEndpoints)]
+    pub async fn get_endpoints(&self) -> Result<EndpointStatuses, JsValue> {
+        self.inner.pool().recover().await;
+
+        Ok(EndpointStatuses(self.inner.pool().statuses().into_iter().map(|s| s.into()).collect()))
     }
 
-    /// Subscribes to transactions
+    /// Returns the statistics of the query cache
     ///
-    /// # Parameters
-    /// * `filter` - Filter parameters
-    /// * `callback` - JavaScript function to call on updates
+    /// All counters are zero when the client was created without a cache.
     ///
     /// # Returns
-    /// Result containing subscription handle or error
-    #[wasm_bindgen(js_name = onTransaction)]
-    pub async fn on_transaction(
-        &self,
-        filter: Option<TransactionFilter>,
-        callback: js_sys::Function,
-    ) -> Result<Subscription, JsValue> {
-        let filter: Option<torii_proto::TransactionFilter> = filter.map(|f| f.into());
+    /// Hits, misses, invalidations and evictions of the cache
+    #[wasm_bindgen(js_name = cacheStats)]
+    pub fn cache_stats(&self) -> CacheStats {
+        self.inner.cache().map(|cache| cache.stats()).unwrap_or_default().into()
+    }
 
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-        let (trigger, tripwire) = Tripwire::new();
+    /// Checks a query against the model schemas of the client's worlds
+    ///
+    /// Reports unknown models, unresolved member paths, operators that don't apply to a member,
+    /// values that can't match it and key counts that differ from the model's.
+    ///
+    /// # Parameters
+    /// * `query` - Query to check
+    ///
+    /// # Returns
+    /// Result containing the diagnostics, empty when the query is valid, or error
+    #[wasm_bindgen(js_name = validateQuery)]
+    pub async fn validate_query(&self, query: Query) -> Result<Diagnostics, JsValue> {
+        let validator = self
+            .inner
+            .validator()
+            .await
+            .map_err(|err| JsValue::from(format!("failed to get worlds: {err}")))?;
+        let diagnostics = validator.validate_query(&query.into());
 
-        // Spawn a new task to handle the stream and reconnections
-        let client = self.inner.clone();
-        wasm_bindgen_futures::spawn_local(async move {
-            let mut backoff = 1000;
-            let max_backoff = 60000;
+        Ok(Diagnostics(diagnostics.into_iter().map(Into::into).collect()))
+    }
 
-            loop {
-                if let Ok(stream) = client.on_transaction(filter.clone()).await {
-                    backoff = 1000; // Reset backoff on successful connection
+    /// Gets the schema of a world, to list its namespaces and models and describe their
+    /// members
+    ///
+    /// # Parameters
+    /// * `world_address` - Address of the world as a hex string
+    ///
+    /// # Returns
+    /// Result containing the WorldSchema or error
+    #[wasm_bindgen(js_name = getWorldSchema)]
+    pub async fn get_world_schema(&self, world_address: String) -> Result<WorldSchema, JsValue> {
+        let world_address = Felt::from_hex(&world_address)
+            .map_err(|err| JsValue::from(format!("invalid world address: {err}")))?;
+        let world_addresses = vec![world_address];
+        let key = CacheKey::normalized("worlds", &world_addresses);
+        let worlds = self
+            .request(
+                self.inner
+                    .exchange(key, |inner| async move { inner.worlds(world_addresses).await }),
+            )
+            .await
+            .map_err(|err| JsValue::from(format!("failed to get worlds: {err}")))?;
 
-                    let mut stream = stream.take_until_if(tripwire.clone());
+        match worlds.first() {
+            Some(world) => Ok(WorldSchema { inner: dojo_core::schema::WorldSchema::new(world) }),
+            None => Err(JsValue::from(format!("world {world_address:#x} not found"))),
+        }
+    }
 
-                    while let Some(Ok(transaction)) = stream.next().await {
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(0).expect("Failed to send subscription ID");
-                        } else {
-                            let transaction: Transaction = transaction.into();
+    /// Drops every result of the query cache
+    #[wasm_bindgen(js_name = clearCache)]
+    pub fn clear_cache(&self) {
+        if let Some(cache) = self.inner.cache() {
+            cache.clear();
+        }
+    }
 
-                            let _ = callback.call1(
-                                &JsValue::null(),
-                                &transaction.serialize(&JSON_COMPAT_SERIALIZER).unwrap(),
-                            );
-                        }
-                    }
-                }
+    /// Gets controllers along with their usernames for the given contract addresses
+    ///
+    /// # Parameters
+    /// * `contract_addresses` - Array of contract addresses as hex strings. If empty, all
+    ///   controllers will be returned.
+    ///
+    /// # Returns
+    /// Result containing controllers or error
+    #[wasm_bindgen(js_name = getControllers)]
+    pub async fn get_controllers(&self, query: ControllerQuery) -> Result<Controllers, JsValue> {
+        let query: torii_proto::ControllerQuery = query.into();
+        let key = CacheKey::new("controllers", &query);
 
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
-                }
-                gloo_timers::future::TimeoutFuture::new(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
-            }
-        });
+        let controllers = self
+            .cached_request(
+                key,
+                |_| Scope::Unscoped,
+                move |inner| {
+                    let query = query.clone();
+                    async move { inner.controllers(query).await }
+                },
+            )
+            .await
+            .map_err(|e| JsValue::from(format!("failed to get controllers: {e}")))?;
 
-        let subscription_id = match sub_id_rx.await {
-            Ok(id) => id,
-            Err(_) => {
-                return Err(JsValue::from("Failed to establish token subscription"));
-            }
-        };
-        let subscription = Subscription { id: subscription_id, trigger };
+        Ok(Controllers(controllers.into()))
+    }
+
+    /// Gets contracts matching the given query
+    ///
+    /// # Parameters
+    /// * `query` - ContractQuery parameters
+    ///
+    /// # Returns
+    /// Result containing contracts or error
+    #[wasm_bindgen(js_name = getContracts)]
+    pub async fn get_contracts(&self, query: ContractQuery) -> Result<Contracts, JsValue> {
+        let query: torii_proto::ContractQuery = query.into();
+        let key = CacheKey::new("contracts", &query);
+
+        let contracts = self
+            .request(self.inner.exchange(key, |inner| async move { inner.contracts(query).await }))
+            .await
+            .map_err(|e| JsValue::from(format!("failed to get contracts: {e}")))?;
 
-        Ok(subscription)
+        Ok(Contracts(contracts.into_iter().map(|c| c.into()).collect()))
+    }
+
+    /// Gets transactions matching the given query
+    ///
+    /// # Parameters
+    /// * `query` - Query parameters
+    ///
+    /// # Returns
+    /// Result containing transactions or error
+    #[wasm_bindgen(js_name = getTransactions)]
+    pub async fn get_transactions(&self, query: TransactionQuery) -> Result<Transactions, JsValue> {
+        let query: torii_proto::TransactionQuery = query.into();
+        let key = CacheKey::new("transactions", &query);
+        let transactions = self
+            .request(
+                self.inner.exchange(key, |inner| async move { inner.transactions(query).await }),
+            )
+            .await
+            .map_err(|e| JsValue::from(format!("failed to get transactions: {e}")))?;
+        Ok(Transactions(transactions.into()))
+    }
+
+    /// Subscribes to transactions
+    ///
+    /// # Parameters
+    /// * `filter` - Filter parameters
+    /// * `callback` - JavaScript function to call on updates
+    ///
+    /// # Returns
+    /// Result containing subscription handle or error
+    #[wasm_bindgen(js_name = onTransaction)]
+    pub async fn on_transaction(
+        &self,
+        filter: Option<TransactionFilter>,
+        callback: js_sys::Function,
+    ) -> Result<Subscription, JsValue> {
+        let filter: Option<torii_proto::TransactionFilter> = filter.map(|f| f.into());
+
+        let subscribed = subscribe(
+            self.inner.clone(),
+            filter,
+            |client: Arc<torii_client::Client>, filter| async move {
+                client.on_transaction(filter).await.map(without_id)
+            },
+            gloo_timers::future::sleep,
+            move |transaction: torii_proto::Transaction| {
+                let transaction: Transaction = transaction.into();
+
+                let _ = callback.call1(
+                    &JsValue::null(),
+                    &transaction.serialize(&JSON_COMPAT_SERIALIZER).unwrap(),
+                );
+            },
+        );
+        spawn_subscription(subscribed, "Failed to establish transaction subscription").await
     }
 
     /// Gets token information for the given contract addresses
@@ -836,11 +1625,19 @@ impl ToriiClient {
     /// Result containing token information or error
     #[wasm_bindgen(js_name = getTokens)]
     pub async fn get_tokens(&self, query: TokenQuery) -> Result<Tokens, JsValue> {
-        let query = query.into();
+        let query: torii_proto::TokenQuery = query.into();
+        let key = CacheKey::normalized("tokens", &query);
+        let scope = Scope::tokens(&query);
 
         let tokens = self
-            .inner
-            .tokens(query)
+            .cached_request(
+                key,
+                move |_| scope,
+                move |inner| {
+                    let query = query.clone();
+                    async move { inner.tokens(query).await }
+                },
+            )
             .await
             .map_err(|e| JsValue::from(format!("failed to get tokens: {e}")))?;
 
@@ -875,57 +1672,21 @@ impl ToriiClient {
         let token_ids =
             token_ids.unwrap_or_default().into_iter().map(|t| t.into()).collect::<Vec<_>>();
 
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-        let (trigger, tripwire) = Tripwire::new();
-
-        // Spawn a new task to handle the stream and reconnections
-        let client = self.inner.clone();
-        wasm_bindgen_futures::spawn_local(async move {
-            let mut backoff = 1000;
-            let max_backoff = 60000;
-
-            loop {
-                if let Ok(stream) =
-                    client.on_token_updated(contract_addresses.clone(), token_ids.clone()).await
-                {
-                    backoff = 1000; // Reset backoff on successful connection
-
-                    let mut stream = stream.take_until_if(tripwire.clone());
-
-                    while let Some(Ok((id, token))) = stream.next().await {
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(id).expect("Failed to send subscription ID");
-                        } else {
-                            let token: Token = token.into();
-
-                            let _ = callback.call1(
-                                &JsValue::null(),
-                                &token.serialize(&JSON_COMPAT_SERIALIZER).unwrap(),
-                            );
-                        }
-                    }
-                }
-
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
-                }
-                gloo_timers::future::TimeoutFuture::new(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
-            }
-        });
-
-        let subscription_id = match sub_id_rx.await {
-            Ok(id) => id,
-            Err(_) => {
-                return Err(JsValue::from("Failed to establish token subscription"));
-            }
-        };
-        let subscription = Subscription { id: subscription_id, trigger };
-
-        Ok(subscription)
+        let subscribed = subscribe(
+            self.inner.clone(),
+            (contract_addresses, token_ids),
+            |client: Arc<torii_client::Client>, (contract_addresses, token_ids)| async move {
+                client.on_token_updated(contract_addresses, token_ids).await
+            },
+            gloo_timers::future::sleep,
+            move |token: torii_proto::Token| {
+                let token: Token = token.into();
+
+                let _ = callback
+                    .call1(&JsValue::null(), &token.serialize(&JSON_COMPAT_SERIALIZER).unwrap());
+            },
+        );
+        spawn_subscription(subscribed, "Failed to establish token subscription").await
     }
 
     /// Gets token balances for given accounts and contracts
@@ -944,11 +1705,19 @@ impl ToriiClient {
         &self,
         query: TokenBalanceQuery,
     ) -> Result<TokenBalances, JsValue> {
-        let query = query.into();
+        let query: torii_proto::TokenBalanceQuery = query.into();
+        let key = CacheKey::normalized("token_balances", &query);
+        let scope = Scope::token_balances(&query);
 
         let token_balances = self
-            .inner
-            .token_balances(query)
+            .cached_request(
+                key,
+                move |_| scope,
+                move |inner| {
+                    let query = query.clone();
+                    async move { inner.token_balances(query).await }
+                },
+            )
             .await
             .map_err(|e| JsValue::from(format!("failed to get token balances: {e}")))?;
 
@@ -971,11 +1740,13 @@ impl ToriiClient {
         &self,
         query: TokenContractQuery,
     ) -> Result<TokenContracts, JsValue> {
-        let query = query.into();
+        let query: torii_proto::TokenContractQuery = query.into();
+        let key = CacheKey::new("token_contracts", &query);
 
         let token_contracts = self
-            .inner
-            .token_contracts(query)
+            .request(
+                self.inner.exchange(key, |inner| async move { inner.token_contracts(query).await }),
+            )
             .await
             .map_err(|e| JsValue::from(format!("failed to get token contracts: {e}")))?;
 
@@ -994,11 +1765,13 @@ impl ToriiClient {
         &self,
         query: TokenTransferQuery,
     ) -> Result<TokenTransfers, JsValue> {
-        let query = query.into();
+        let query: torii_proto::TokenTransferQuery = query.into();
+        let key = CacheKey::new("token_transfers", &query);
 
         let token_transfers = self
-            .inner
-            .token_transfers(query)
+            .request(
+                self.inner.exchange(key, |inner| async move { inner.token_transfers(query).await }),
+            )
             .await
             .map_err(|e| JsValue::from(format!("failed to get token transfers: {e}")))?;
 
@@ -1014,11 +1787,13 @@ impl ToriiClient {
     /// Result containing aggregations or error
     #[wasm_bindgen(js_name = getAggregations)]
     pub async fn get_aggregations(&self, query: AggregationQuery) -> Result<Aggregations, JsValue> {
-        let query = query.into();
+        let query: torii_proto::AggregationQuery = query.into();
+        let key = CacheKey::new("aggregations", &query);
 
         let aggregations = self
-            .inner
-            .aggregations(query)
+            .request(
+                self.inner.exchange(key, |inner| async move { inner.aggregations(query).await }),
+            )
             .await
             .map_err(|e| JsValue::from(format!("failed to get aggregations: {e}")))?;
 
@@ -1034,11 +1809,13 @@ impl ToriiClient {
     /// Result containing achievements or error
     #[wasm_bindgen(js_name = getAchievements)]
     pub async fn get_achievements(&self, query: AchievementQuery) -> Result<Achievements, JsValue> {
-        let query = query.into();
+        let query: torii_proto::AchievementQuery = query.into();
+        let key = CacheKey::new("achievements", &query);
 
         let achievements = self
-            .inner
-            .achievements(query)
+            .request(
+                self.inner.exchange(key, |inner| async move { inner.achievements(query).await }),
+            )
             .await
             .map_err(|err| JsValue::from(format!("failed to get achievements: {err}")))?;
 
@@ -1057,12 +1834,16 @@ impl ToriiClient {
         &self,
         query: PlayerAchievementQuery,
     ) -> Result<PlayerAchievements, JsValue> {
-        let query = query.into();
+        let query: torii_proto::PlayerAchievementQuery = query.into();
+        let key = CacheKey::new("player_achievements", &query);
 
-        let player_achievements =
-            self.inner.player_achievements(query).await.map_err(|err| {
-                JsValue::from(format!("failed to get player achievements: {err}"))
-            })?;
+        let player_achievements = self
+            .request(
+                self.inner
+                    .exchange(key, |inner| async move { inner.player_achievements(query).await }),
+            )
+            .await
+            .map_err(|err| JsValue::from(format!("failed to get player achievements: {err}")))?;
 
         Ok(PlayerAchievements(player_achievements.into()))
     }
@@ -1077,11 +1858,11 @@ impl ToriiClient {
     /// Result containing activities or error
     #[wasm_bindgen(js_name = getActivities)]
     pub async fn get_activities(&self, query: ActivityQuery) -> Result<Activities, JsValue> {
-        let query = query.into();
+        let query: torii_proto::ActivityQuery = query.into();
+        let key = CacheKey::new("activities", &query);
 
         let activities = self
-            .inner
-            .activities(query)
+            .request(self.inner.exchange(key, |inner| async move { inner.activities(query).await }))
             .await
             .map_err(|e| JsValue::from(format!("failed to get activities: {e}")))?;
 
@@ -1097,19 +1878,214 @@ impl ToriiClient {
     /// Result containing matching entities or error
     #[wasm_bindgen(js_name = getEntities)]
     pub async fn get_entities(&self, query: Query) -> Result<Entities, JsValue> {
-        let results = self.inner.entities(query.into()).await;
+        let projection = query.projection();
+        let mut query: torii_proto::Query = query.into();
+        if let Some(projection) = &projection {
+            projection.apply_query(&mut query);
+        }
+        self.inner
+            .validate_clause(query.clause.as_ref(), &query.world_addresses)
+            .await
+            .map_err(|err| JsValue::from(format!("failed to get entities: {err}")))?;
+        let key = CacheKey::normalized("entities", &query);
+        let scope_query = query.clone();
+        let results = self
+            .cached_request(
+                key,
+                move |page| Scope::entities(EntitySource::Entities, &scope_query, page),
+                move |inner| {
+                    let query = query.clone();
+                    async move { inner.entities(query).await }
+                },
+            )
+            .await;
 
         match results {
-            Ok(entities) => Ok(Entities(entities.into())),
+            Ok(mut entities) => {
+                if let Some(projection) = &projection {
+                    projection.apply_page(&mut entities);
+                }
+                Ok(Entities(entities.into()))
+            }
             Err(err) => Err(JsValue::from(format!("failed to get entities: {err}"))),
         }
     }
 
+    /// Looks up entities by id, i.e. hashed keys
+    ///
+    /// Ids are requested in chunks running concurrently, sized so that any number of them fits
+    /// in the configured message size. An id known to several worlds comes with the entity of
+    /// the first one listed.
+    ///
+    /// # Parameters
+    /// * `ids` - Entity ids
+    /// * `world_addresses` - World addresses to look entities up in, all when unset
+    ///
+    /// # Returns
+    /// Result containing the entity of each id, null when it doesn't exist, or error
+    #[wasm_bindgen(js_name = getEntitiesByIds)]
+    pub async fn get_entities_by_ids(
+        &self,
+        ids: Vec<String>,
+        world_addresses: Option<Vec<String>>,
+    ) -> Result<EntityLookup, JsValue> {
+        let world_addresses = world_addresses
+            .unwrap_or_default()
+            .into_iter()
+            .map(|addr| Felt::from_hex(&addr).unwrap())
+            .collect::<Vec<_>>();
+        let ids = ids
+            .iter()
+            .map(|id| Felt::from_hex(id))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| JsValue::from(format!("invalid entity id: {err}")))?;
+
+        let connection = &self.inner;
+        let max_message_size = connection.config().max_message_size;
+        let lookup =
+            batch::entities_by_ids(&world_addresses, &ids, Vec::new(), max_message_size, |query| {
+                let key = CacheKey::normalized("entities", &query);
+                connection.exchange(key, |inner| async move { inner.entities(query).await })
+            });
+
+        match self.request(lookup).await {
+            Ok(lookup) => Ok(lookup.into()),
+            Err(err) => Err(JsValue::from(format!("failed to get entities by ids: {err}"))),
+        }
+    }
+
+    /// Looks up a model of entities by keys
+    ///
+    /// Entities are keyed by id, the Poseidon hash of their keys. Entities without the model
+    /// are reported as missing.
+    ///
+    /// # Parameters
+    /// * `model` - Tag of the model, e.g. `ns-Position`
+    /// * `keys` - Keys of each entity
+    /// * `world_addresses` - World addresses to look entities up in, all when unset
+    ///
+    /// # Returns
+    /// Result containing the entity of each id, null when it doesn't exist, or error
+    #[wasm_bindgen(js_name = getEntitiesByKeys)]
+    pub async fn get_entities_by_keys(
+        &self,
+        model: String,
+        keys: Vec<Array>,
+        world_addresses: Option<Vec<String>>,
+    ) -> Result<EntityLookup, JsValue> {
+        let world_addresses = world_addresses
+            .unwrap_or_default()
+            .into_iter()
+            .map(|addr| Felt::from_hex(&addr).unwrap())
+            .collect::<Vec<_>>();
+        let keys = keys
+            .iter()
+            .map(|keys| {
+                keys.iter()
+                    .map(|key| {
+                        let key = key.as_string().ok_or("keys must be hex strings")?;
+                        Felt::from_hex(&key).map_err(|err| err.to_string())
+                    })
+                    .collect::<Result<Vec<_>, String>>()
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| JsValue::from(format!("invalid entity keys: {err}")))?;
+
+        let connection = &self.inner;
+        let max_message_size = connection.config().max_message_size;
+        let lookup =
+            batch::entities_by_keys(&world_addresses, &model, &keys, max_message_size, |query| {
+                let key = CacheKey::normalized("entities", &query);
+                connection.exchange(key, |inner| async move { inner.entities(query).await })
+            });
+
+        match self.request(lookup).await {
+            Ok(lookup) => Ok(lookup.into()),
+            Err(err) => Err(JsValue::from(format!("failed to get entities by keys: {err}"))),
+        }
+    }
+
+    /// Gets every version of a model of an entity, in the order they were set
+    ///
+    /// # Parameters
+    /// * `hashed_keys` - Id of the entity
+    /// * `model` - Tag of the model, e.g. `ns-Position`
+    /// * `range` - Inclusive range of blocks or timestamps to keep, the whole history when unset
+    /// * `world_addresses` - World addresses to look the entity up in, all when unset
+    ///
+    /// # Returns
+    /// Result containing the versions, with their block and transaction, or error
+    #[wasm_bindgen(js_name = getEntityHistory)]
+    pub async fn get_entity_history(
+        &self,
+        hashed_keys: String,
+        model: String,
+        range: Option<HistoryRange>,
+        world_addresses: Option<Vec<String>>,
+    ) -> Result<EntityVersions, JsValue> {
+        let world_addresses = world_addresses
+            .unwrap_or_default()
+            .into_iter()
+            .map(|addr| Felt::from_hex(&addr).unwrap())
+            .collect::<Vec<_>>();
+        let hashed_keys = Felt::from_hex(&hashed_keys)
+            .map_err(|err| JsValue::from(format!("invalid hashed keys: {err}")))?;
+
+        let timelines = self.timelines(&world_addresses, &[hashed_keys], vec![model.clone()]);
+        match self.request(timelines).await {
+            Ok(mut timelines) => {
+                let versions = timelines.remove(&(hashed_keys, model)).unwrap_or_default();
+                let range = range.unwrap_or_default().into();
+                Ok(EntityVersions(
+                    history::within(versions, range).into_iter().map(Into::into).collect(),
+                ))
+            }
+            Err(err) => Err(JsValue::from(format!("failed to get entity history: {err}"))),
+        }
+    }
+
+    /// Rebuilds entities as they were at a block or time, from their history
+    ///
+    /// # Parameters
+    /// * `world_address` - Address of the world of the entities
+    /// * `ids` - Ids of the entities
+    /// * `at` - Block or timestamp to rebuild the entities at
+    ///
+    /// # Returns
+    /// Result containing the entities that existed at that point, each with the models set by
+    /// then, or error
+    #[wasm_bindgen(js_name = getEntitiesAt)]
+    pub async fn get_entities_at(
+        &self,
+        world_address: String,
+        ids: Vec<String>,
+        at: At,
+    ) -> Result<EntityList, JsValue> {
+        let world_address = Felt::from_hex(&world_address).unwrap();
+        let ids = ids
+            .iter()
+            .map(|id| Felt::from_hex(id))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| JsValue::from(format!("invalid entity ids: {err}")))?;
+
+        let timelines = self.timelines(&[world_address], &ids, vec![]);
+        match self.request(timelines).await {
+            Ok(timelines) => Ok(EntityList(
+                history::state_at(world_address, timelines, at.into())
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
+            )),
+            Err(err) => Err(JsValue::from(format!("failed to get entities at {at:?}: {err}"))),
+        }
+    }
+
     /// Gets all entities with pagination
     ///
     /// # Parameters
     /// * `limit` - Maximum number of entities to return
     /// * `cursor` - Cursor to start from
+    /// * `historical` - Whether to return every version of the entities instead of the latest
     ///
     /// # Returns
     /// Result containing paginated entities or error
@@ -1118,22 +2094,24 @@ impl ToriiClient {
         &self,
         limit: u32,
         cursor: Option<String>,
+        historical: Option<bool>,
     ) -> Result<Entities, JsValue> {
+        let query = torii_proto::Query {
+            world_addresses: vec![],
+            pagination: torii_proto::Pagination {
+                limit: Some(limit),
+                cursor,
+                direction: torii_proto::PaginationDirection::Forward,
+                order_by: vec![],
+            },
+            no_hashed_keys: false,
+            models: vec![],
+            historical: historical.unwrap_or_default(),
+            clause: None,
+        };
+        let key = CacheKey::normalized("entities", &query);
         let results = self
-            .inner
-            .entities(torii_proto::Query {
-                world_addresses: vec![],
-                pagination: torii_proto::Pagination {
-                    limit: Some(limit),
-                    cursor,
-                    direction: torii_proto::PaginationDirection::Forward,
-                    order_by: vec![],
-                },
-                no_hashed_keys: false,
-                models: vec![],
-                historical: false,
-                clause: None,
-            })
+            .request(self.inner.exchange(key, |inner| async move { inner.entities(query).await }))
             .await;
 
         match results {
@@ -1151,14 +2129,309 @@ impl ToriiClient {
     /// Result containing matching event messages or error
     #[wasm_bindgen(js_name = getEventMessages)]
     pub async fn get_event_messages(&self, query: Query) -> Result<Entities, JsValue> {
-        let results = self.inner.event_messages(query.into()).await;
+        let projection = query.projection();
+        let mut query: torii_proto::Query = query.into();
+        if let Some(projection) = &projection {
+            projection.apply_query(&mut query);
+        }
+        self.inner
+            .validate_clause(query.clause.as_ref(), &query.world_addresses)
+            .await
+            .map_err(|err| JsValue::from(format!("failed to get event_messages: {err}")))?;
+        let key = CacheKey::normalized("event_messages", &query);
+        let scope_query = query.clone();
+        let results = self
+            .cached_request(
+                key,
+                move |page| Scope::entities(EntitySource::EventMessages, &scope_query, page),
+                move |inner| {
+                    let query = query.clone();
+                    async move { inner.event_messages(query).await }
+                },
+            )
+            .await;
 
         match results {
-            Ok(event_messages) => Ok(Entities(event_messages.into())),
+            Ok(mut event_messages) => {
+                if let Some(projection) = &projection {
+                    projection.apply_page(&mut event_messages);
+                }
+                Ok(Entities(event_messages.into()))
+            }
             Err(err) => Err(JsValue::from(format!("failed to get event_messages: {err}"))),
         }
     }
 
+    /// Creates an iterator over every page of entities matching a query
+    ///
+    /// # Parameters
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
+    ///
+    /// # Returns
+    /// PageIterator yielding entities
+    #[wasm_bindgen(js_name = entitiesIterator, unchecked_return_type = "PageIterator")]
+    pub fn entities_iterator(&self, query: Query, prefetch: Option<bool>) -> JsValue {
+        let projection = query.projection();
+        let mut query: torii_proto::Query = query.into();
+        if let Some(projection) = &projection {
+            projection.apply_query(&mut query);
+        }
+        page_iterator(TypedPages::<_, _, Entity>::new(
+            self.inner.clone(),
+            "entities",
+            query,
+            prefetch,
+            move |client, query| {
+                let projection = projection.clone();
+                async move {
+                    let mut page = client.entities(query).await?;
+                    if let Some(projection) = &projection {
+                        projection.apply_page(&mut page);
+                    }
+                    anyhow::Ok(page)
+                }
+            },
+        ))
+    }
+
+    /// Creates an iterator over every page of event messages matching a query
+    ///
+    /// # Parameters
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
+    ///
+    /// # Returns
+    /// PageIterator yielding event messages
+    #[wasm_bindgen(js_name = eventMessagesIterator, unchecked_return_type = "PageIterator")]
+    pub fn event_messages_iterator(&self, query: Query, prefetch: Option<bool>) -> JsValue {
+        let projection = query.projection();
+        let mut query: torii_proto::Query = query.into();
+        if let Some(projection) = &projection {
+            projection.apply_query(&mut query);
+        }
+        page_iterator(TypedPages::<_, _, Entity>::new(
+            self.inner.clone(),
+            "event_messages",
+            query,
+            prefetch,
+            move |client, query| {
+                let projection = projection.clone();
+                async move {
+                    let mut page = client.event_messages(query).await?;
+                    if let Some(projection) = &projection {
+                        projection.apply_page(&mut page);
+                    }
+                    anyhow::Ok(page)
+                }
+            },
+        ))
+    }
+
+    /// Creates an iterator over every page of controllers matching a query
+    ///
+    /// # Parameters
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
+    ///
+    /// # Returns
+    /// PageIterator yielding controllers
+    #[wasm_bindgen(js_name = controllersIterator, unchecked_return_type = "PageIterator")]
+    pub fn controllers_iterator(&self, query: ControllerQuery, prefetch: Option<bool>) -> JsValue {
+        let query: torii_proto::ControllerQuery = query.into();
+        page_iterator(TypedPages::<_, _, Controller>::new(
+            self.inner.clone(),
+            "controllers",
+            query,
+            prefetch,
+            |client, query| async move { client.controllers(query).await },
+        ))
+    }
+
+    /// Creates an iterator over every page of tokens matching a query
+    ///
+    /// # Parameters
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
+    ///
+    /// # Returns
+    /// PageIterator yielding tokens
+    #[wasm_bindgen(js_name = tokensIterator, unchecked_return_type = "PageIterator")]
+    pub fn tokens_iterator(&self, query: TokenQuery, prefetch: Option<bool>) -> JsValue {
+        let query: torii_proto::TokenQuery = query.into();
+        page_iterator(TypedPages::<_, _, Token>::new(
+            self.inner.clone(),
+            "tokens",
+            query,
+            prefetch,
+            |client, query| async move { client.tokens(query).await },
+        ))
+    }
+
+    /// Creates an iterator over every page of token balances matching a query
+    ///
+    /// # Parameters
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
+    ///
+    /// # Returns
+    /// PageIterator yielding token balances
+    #[wasm_bindgen(js_name = tokenBalancesIterator, unchecked_return_type = "PageIterator")]
+    pub fn token_balances_iterator(
+        &self,
+        query: TokenBalanceQuery,
+        prefetch: Option<bool>,
+    ) -> JsValue {
+        let query: torii_proto::TokenBalanceQuery = query.into();
+        page_iterator(TypedPages::<_, _, TokenBalance>::new(
+            self.inner.clone(),
+            "token_balances",
+            query,
+            prefetch,
+            |client, query| async move { client.token_balances(query).await },
+        ))
+    }
+
+    /// Creates an iterator over every page of token transfers matching a query
+    ///
+    /// # Parameters
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
+    ///
+    /// # Returns
+    /// PageIterator yielding token transfers
+    #[wasm_bindgen(js_name = tokenTransfersIterator, unchecked_return_type = "PageIterator")]
+    pub fn token_transfers_iterator(
+        &self,
+        query: TokenTransferQuery,
+        prefetch: Option<bool>,
+    ) -> JsValue {
+        let query: torii_proto::TokenTransferQuery = query.into();
+        page_iterator(TypedPages::<_, _, TokenTransfer>::new(
+            self.inner.clone(),
+            "token_transfers",
+            query,
+            prefetch,
+            |client, query| async move { client.token_transfers(query).await },
+        ))
+    }
+
+    /// Creates an iterator over every page of transactions matching a query
+    ///
+    /// # Parameters
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
+    ///
+    /// # Returns
+    /// PageIterator yielding transactions
+    #[wasm_bindgen(js_name = transactionsIterator, unchecked_return_type = "PageIterator")]
+    pub fn transactions_iterator(
+        &self,
+        query: TransactionQuery,
+        prefetch: Option<bool>,
+    ) -> JsValue {
+        let query: torii_proto::TransactionQuery = query.into();
+        page_iterator(TypedPages::<_, _, Transaction>::new(
+            self.inner.clone(),
+            "transactions",
+            query,
+            prefetch,
+            |client, query| async move { client.transactions(query).await },
+        ))
+    }
+
+    /// Creates an iterator over every page of activities matching a query
+    ///
+    /// # Parameters
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
+    ///
+    /// # Returns
+    /// PageIterator yielding activities
+    #[wasm_bindgen(js_name = activitiesIterator, unchecked_return_type = "PageIterator")]
+    pub fn activities_iterator(&self, query: ActivityQuery, prefetch: Option<bool>) -> JsValue {
+        let query: torii_proto::ActivityQuery = query.into();
+        page_iterator(TypedPages::<_, _, Activity>::new(
+            self.inner.clone(),
+            "activities",
+            query,
+            prefetch,
+            |client, query| async move { client.activities(query).await },
+        ))
+    }
+
+    /// Creates an iterator over every page of achievements matching a query
+    ///
+    /// # Parameters
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
+    ///
+    /// # Returns
+    /// PageIterator yielding achievements
+    #[wasm_bindgen(js_name = achievementsIterator, unchecked_return_type = "PageIterator")]
+    pub fn achievements_iterator(
+        &self,
+        query: AchievementQuery,
+        prefetch: Option<bool>,
+    ) -> JsValue {
+        let query: torii_proto::AchievementQuery = query.into();
+        page_iterator(TypedPages::<_, _, Achievement>::new(
+            self.inner.clone(),
+            "achievements",
+            query,
+            prefetch,
+            |client, query| async move { client.achievements(query).await },
+        ))
+    }
+
+    /// Creates an iterator over every page of player achievements matching a query
+    ///
+    /// # Parameters
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
+    ///
+    /// # Returns
+    /// PageIterator yielding player achievements
+    #[wasm_bindgen(js_name = playerAchievementsIterator, unchecked_return_type = "PageIterator")]
+    pub fn player_achievements_iterator(
+        &self,
+        query: PlayerAchievementQuery,
+        prefetch: Option<bool>,
+    ) -> JsValue {
+        let query: torii_proto::PlayerAchievementQuery = query.into();
+        page_iterator(TypedPages::<_, _, PlayerAchievementEntry>::new(
+            self.inner.clone(),
+            "player_achievements",
+            query,
+            prefetch,
+            |client, query| async move { client.player_achievements(query).await },
+        ))
+    }
+
+    /// Creates an iterator over every page of aggregation entries matching a query
+    ///
+    /// # Parameters
+    /// * `query` - Query parameters, whose pagination sets the page size, order and first cursor
+    /// * `prefetch` - Whether to fetch the next page in the background once a page is returned
+    ///
+    /// # Returns
+    /// PageIterator yielding aggregation entries
+    #[wasm_bindgen(js_name = aggregationsIterator, unchecked_return_type = "PageIterator")]
+    pub fn aggregations_iterator(
+        &self,
+        query: AggregationQuery,
+        prefetch: Option<bool>,
+    ) -> JsValue {
+        let query: torii_proto::AggregationQuery = query.into();
+        page_iterator(TypedPages::<_, _, AggregationEntry>::new(
+            self.inner.clone(),
+            "aggregations",
+            query,
+            prefetch,
+            |client, query| async move { client.aggregations(query).await },
+        ))
+    }
+
     /// Subscribes to entity updates
     ///
     /// # Parameters
@@ -1180,57 +2453,233 @@ impl ToriiClient {
             .into_iter()
             .map(|addr| Felt::from_hex(&addr).unwrap())
             .collect::<Vec<_>>();
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-        let (trigger, tripwire) = Tripwire::new();
+        let filter = EntityFilter { clause, world_addresses };
+        let control = DeliveryControl::default();
+        let projection = control.clone();
+        self.entity_subscription(
+            &self.entities,
+            EntitySource::Entities,
+            filter,
+            control,
+            move |mut entity| {
+                if !projection.project(&mut entity) {
+                    return;
+                }
+                let entity: Entity = entity.into();
+
+                let _ = callback
+                    .call1(&JsValue::null(), &entity.serialize(&JSON_COMPAT_SERIALIZER).unwrap());
+            },
+            "Failed to establish entity subscription",
+        )
+        .await
+    }
 
-        // Spawn a new task to handle the stream and reconnections
-        let client = self.inner.clone();
-        wasm_bindgen_futures::spawn_local(async move {
-            let mut backoff = 1000;
-            let max_backoff = 60000;
-
-            loop {
-                if let Ok(stream) =
-                    client.on_entity_updated(clause.clone(), world_addresses.clone()).await
-                {
-                    backoff = 1000; // Reset backoff on successful connection
-
-                    let mut stream = stream.take_until_if(tripwire.clone());
-
-                    while let Some(Ok((id, entity))) = stream.next().await {
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(id).expect("Failed to send subscription ID");
-                        } else {
-                            let entity: Entity = entity.into();
-
-                            let _ = callback.call1(
-                                &JsValue::null(),
-                                &entity.serialize(&JSON_COMPAT_SERIALIZER).unwrap(),
-                            );
-                        }
+    /// Subscribes to entity updates and dispatches their models to the handlers of a
+    /// ModelDispatcher
+    ///
+    /// Handlers can be added to and removed from the dispatcher while the subscription is live.
+    ///
+    /// # Parameters
+    /// * `clause` - Optional clause for filtering updates
+    /// * `world_addresses` - Optional world addresses for filtering updates
+    /// * `dispatcher` - ModelDispatcher holding the handlers
+    ///
+    /// # Returns
+    /// Result containing subscription handle or error
+    #[wasm_bindgen(js_name = onEntityDispatch)]
+    pub async fn on_entity_dispatch(
+        &self,
+        clause: Option<Clause>,
+        world_addresses: Option<Vec<String>>,
+        dispatcher: &ModelDispatcher,
+    ) -> Result<Subscription, JsValue> {
+        let clause = clause.map(|c| c.into());
+        let world_addresses = world_addresses
+            .unwrap_or_default()
+            .into_iter()
+            .map(|addr| Felt::from_hex(&addr).unwrap())
+            .collect::<Vec<_>>();
+        let filter = EntityFilter { clause, world_addresses };
+        let dispatcher = dispatcher.inner.clone();
+        let control = DeliveryControl::default();
+        let projection = control.clone();
+        self.entity_subscription(
+            &self.entities,
+            EntitySource::Entities,
+            filter,
+            control,
+            move |entity| {
+                dispatcher.dispatch(&entity, |handler, model| {
+                    let mut model = model.clone();
+                    if !projection.project(&mut model) {
+                        return;
                     }
+                    let model: Model = model.into();
+
+                    let _ = handler.call2(
+                        &JsValue::null(),
+                        &model.serialize(&JSON_COMPAT_SERIALIZER).unwrap(),
+                        &JsValue::from(format!("{:#x}", entity.hashed_keys)),
+                    );
+                });
+            },
+            "Failed to establish entity subscription",
+        )
+        .await
+    }
+
+    /// Subscribes to entity updates with the predictions of an OptimisticStore applied
+    ///
+    /// # Parameters
+    /// * `clause` - Optional clause for filtering updates
+    /// * `world_addresses` - Optional world addresses for filtering updates
+    /// * `store` - OptimisticStore holding the predictions
+    /// * `callback` - JavaScript function called with the entity as it should be shown: its
+    ///   confirmed state with the pending predictions of the store on top
+    ///
+    /// # Returns
+    /// Result containing subscription handle or error
+    #[wasm_bindgen(js_name = onEntityOptimisticUpdated)]
+    pub async fn on_entity_optimistic_updated(
+        &self,
+        clause: Option<Clause>,
+        world_addresses: Option<Vec<String>>,
+        store: &OptimisticStore,
+        callback: js_sys::Function,
+    ) -> Result<Subscription, JsValue> {
+        let clause = clause.map(|c| c.into());
+        let world_addresses = world_addresses
+            .unwrap_or_default()
+            .into_iter()
+            .map(|addr| Felt::from_hex(&addr).unwrap())
+            .collect::<Vec<_>>();
+        let filter = EntityFilter { clause, world_addresses };
+        let optimistic = store.inner.clone();
+        let control = DeliveryControl::default();
+        let projection = control.clone();
+        self.entity_subscription(
+            &self.entities,
+            EntitySource::Entities,
+            filter,
+            control,
+            move |entity| {
+                let mut entity = optimistic.confirm(entity);
+                if !projection.project(&mut entity) {
+                    return;
                 }
+                let entity: Entity = entity.into();
+
+                let _ = callback
+                    .call1(&JsValue::null(), &entity.serialize(&JSON_COMPAT_SERIALIZER).unwrap());
+            },
+            "Failed to establish entity subscription",
+        )
+        .await
+    }
 
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
+    /// Subscribes to entity updates, tagged as creations, updates or deletions
+    ///
+    /// # Parameters
+    /// * `clause` - Optional clause for filtering updates
+    /// * `world_addresses` - Optional world addresses for filtering updates
+    /// * `callback` - JavaScript function called with `{ type: "created" | "updated" |
+    ///   "deleted", entity }`
+    ///
+    /// # Returns
+    /// Result containing subscription handle or error
+    #[wasm_bindgen(js_name = onEntityChanged)]
+    pub async fn on_entity_changed(
+        &self,
+        clause: Option<Clause>,
+        world_addresses: Option<Vec<String>>,
+        callback: js_sys::Function,
+    ) -> Result<Subscription, JsValue> {
+        let clause = clause.map(|c| c.into());
+        let world_addresses = world_addresses
+            .unwrap_or_default()
+            .into_iter()
+            .map(|addr| Felt::from_hex(&addr).unwrap())
+            .collect::<Vec<_>>();
+        let filter = EntityFilter { clause, world_addresses };
+        let mut classifier = EntityClassifier::new();
+        let control = DeliveryControl::default();
+        let projection = control.clone();
+        self.entity_subscription(
+            &self.entities,
+            EntitySource::Entities,
+            filter,
+            control,
+            move |entity| {
+                let mut change = classifier.classify(entity);
+                if !projection.project(&mut change) {
+                    return;
                 }
-                gloo_timers::future::TimeoutFuture::new(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
-            }
-        });
+                let change: EntityChange = change.into();
+
+                let _ = callback
+                    .call1(&JsValue::null(), &change.serialize(&JSON_COMPAT_SERIALIZER).unwrap());
+            },
+            "Failed to establish entity subscription",
+        )
+        .await
+    }
 
-        let subscription_id = match sub_id_rx.await {
-            Ok(id) => id,
-            Err(_) => {
-                return Err(JsValue::from("Failed to establish entity subscription"));
-            }
-        };
-        let subscription = Subscription { id: subscription_id, trigger };
+    /// Subscribes to entity updates, reported as field level changes
+    ///
+    /// Each update is compared with the previous state of the entity, and the callback is
+    /// called once per changed member, added or removed model.
+    ///
+    /// # Parameters
+    /// * `clause` - Optional clause for filtering updates
+    /// * `world_addresses` - Optional world addresses for filtering updates
+    /// * `callback` - JavaScript function to call with each `FieldChange`
+    ///
+    /// # Returns
+    /// Result containing subscription handle or error
+    #[wasm_bindgen(js_name = onEntityDiff)]
+    pub async fn on_entity_diff(
+        &self,
+        clause: Option<Clause>,
+        world_addresses: Option<Vec<String>>,
+        callback: js_sys::Function,
+    ) -> Result<Subscription, JsValue> {
+        let clause = clause.map(|c| c.into());
+        let world_addresses = world_addresses
+            .unwrap_or_default()
+            .into_iter()
+            .map(|addr| Felt::from_hex(&addr).unwrap())
+            .collect::<Vec<_>>();
+        let filter = EntityFilter { clause, world_addresses };
+        let mut differ = EntityDiffer::new();
+        let (upgrades, mut seen) = (self.inner.clone(), self.inner.upgrades().count());
+        let control = DeliveryControl::default();
+        let projection = control.clone();
+        self.entity_subscription(
+            &self.entities,
+            EntitySource::Entities,
+            filter,
+            control,
+            move |entity| {
+                // Upgraded models are set afresh rather than diffed across schemas
+                for model in upgrades.upgrades().since(&mut seen) {
+                    differ.forget_model(&model);
+                }
+                for mut change in differ.diff(&entity) {
+                    if !projection.project(&mut change) {
+                        continue;
+                    }
+                    let change: FieldChange = change.into();
 
-        Ok(subscription)
+                    let _ = callback.call1(
+                        &JsValue::null(),
+                        &change.serialize(&JSON_COMPAT_SERIALIZER).unwrap(),
+                    );
+                }
+            },
+            "Failed to establish entity diff subscription",
+        )
+        .await
     }
 
     /// Updates an existing entity subscription
@@ -1254,10 +2703,18 @@ impl ToriiClient {
             .into_iter()
             .map(|addr| Felt::from_hex(&addr).unwrap())
             .collect::<Vec<_>>();
-        self.inner
-            .update_entity_subscription(subscription.id, clause, world_addresses)
-            .await
-            .map_err(|err| JsValue::from(format!("failed to update subscription: {err}")))
+        let filter = EntityFilter { clause, world_addresses };
+        if let SubscriptionStream::Shared { id, .. } = subscription.stream {
+            return update_shared(&self.entities, id, filter);
+        }
+        self.update_own(subscription, filter, |id, filter| {
+            let EntityFilter { clause, world_addresses } = filter;
+            let key = CacheKey::new("update_entity_subscription", &(id, &clause, &world_addresses));
+            self.inner.exchange(key, |inner| async move {
+                inner.update_entity_subscription(id, clause, world_addresses).await
+            })
+        })
+        .await
     }
 
     /// Subscribes to event message updates
@@ -1281,57 +2738,80 @@ impl ToriiClient {
             .into_iter()
             .map(|addr| Felt::from_hex(&addr).unwrap())
             .collect::<Vec<_>>();
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-        let (trigger, tripwire) = Tripwire::new();
-
-        // Spawn a new task to handle the stream and reconnections
-        let client = self.inner.clone();
-        wasm_bindgen_futures::spawn_local(async move {
-            let mut backoff = 1000;
-            let max_backoff = 60000;
-
-            loop {
-                if let Ok(stream) =
-                    client.on_event_message_updated(clause.clone(), world_addresses.clone()).await
-                {
-                    backoff = 1000; // Reset backoff on successful connection
-
-                    let mut stream = stream.take_until_if(tripwire.clone());
-
-                    while let Some(Ok((id, entity))) = stream.next().await {
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(id).expect("Failed to send subscription ID");
-                        } else {
-                            let entity: Entity = entity.into();
-
-                            let _ = callback.call1(
-                                &JsValue::null(),
-                                &entity.serialize(&JSON_COMPAT_SERIALIZER).unwrap(),
-                            );
-                        }
-                    }
-                }
-
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
+        let filter = EntityFilter { clause, world_addresses };
+        let control = DeliveryControl::default();
+        let projection = control.clone();
+        self.entity_subscription(
+            &self.event_messages,
+            EntitySource::EventMessages,
+            filter,
+            control,
+            move |mut entity| {
+                if !projection.project(&mut entity) {
+                    return;
                 }
-                gloo_timers::future::TimeoutFuture::new(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
-            }
-        });
-
-        let subscription_id = match sub_id_rx.await {
-            Ok(id) => id,
-            Err(_) => {
-                return Err(JsValue::from("Failed to establish event message subscription"));
-            }
-        };
-        let subscription = Subscription { id: subscription_id, trigger };
+                let entity: Entity = entity.into();
+
+                let _ = callback
+                    .call1(&JsValue::null(), &entity.serialize(&JSON_COMPAT_SERIALIZER).unwrap());
+            },
+            "Failed to establish event message subscription",
+        )
+        .await
+    }
 
-        Ok(subscription)
+    /// Subscribes to event message updates and dispatches their models to the handlers of a
+    /// ModelDispatcher
+    ///
+    /// Handlers can be added to and removed from the dispatcher while the subscription is live.
+    ///
+    /// # Parameters
+    /// * `clause` - Optional clause for filtering updates
+    /// * `world_addresses` - Optional world addresses for filtering updates
+    /// * `dispatcher` - ModelDispatcher holding the handlers
+    ///
+    /// # Returns
+    /// Result containing subscription handle or error
+    #[wasm_bindgen(js_name = onEventMessageDispatch)]
+    pub async fn on_event_message_dispatch(
+        &self,
+        clause: Option<Clause>,
+        world_addresses: Option<Vec<String>>,
+        dispatcher: &ModelDispatcher,
+    ) -> Result<Subscription, JsValue> {
+        let clause = clause.map(|c| c.into());
+        let world_addresses = world_addresses
+            .unwrap_or_default()
+            .into_iter()
+            .map(|addr| Felt::from_hex(&addr).unwrap())
+            .collect::<Vec<_>>();
+        let filter = EntityFilter { clause, world_addresses };
+        let dispatcher = dispatcher.inner.clone();
+        let control = DeliveryControl::default();
+        let projection = control.clone();
+        self.entity_subscription(
+            &self.event_messages,
+            EntitySource::EventMessages,
+            filter,
+            control,
+            move |entity| {
+                dispatcher.dispatch(&entity, |handler, model| {
+                    let mut model = model.clone();
+                    if !projection.project(&mut model) {
+                        return;
+                    }
+                    let model: Model = model.into();
+
+                    let _ = handler.call2(
+                        &JsValue::null(),
+                        &model.serialize(&JSON_COMPAT_SERIALIZER).unwrap(),
+                        &JsValue::from(format!("{:#x}", entity.hashed_keys)),
+                    );
+                });
+            },
+            "Failed to establish event message subscription",
+        )
+        .await
     }
 
     /// Updates an existing event message subscription
@@ -1355,10 +2835,21 @@ impl ToriiClient {
             .into_iter()
             .map(|addr| Felt::from_hex(&addr).unwrap())
             .collect::<Vec<_>>();
-        self.inner
-            .update_event_message_subscription(subscription.id, clause, world_addresses)
-            .await
-            .map_err(|err| JsValue::from(format!("failed to update subscription: {err}")))
+        let filter = EntityFilter { clause, world_addresses };
+        if let SubscriptionStream::Shared { id, .. } = subscription.stream {
+            return update_shared(&self.event_messages, id, filter);
+        }
+        self.update_own(subscription, filter, |id, filter| {
+            let EntityFilter { clause, world_addresses } = filter;
+            let key = CacheKey::new(
+                "update_event_message_subscription",
+                &(id, &clause, &world_addresses),
+            );
+            self.inner.exchange(key, |inner| async move {
+                inner.update_event_message_subscription(id, clause, world_addresses).await
+            })
+        })
+        .await
     }
 
     /// Subscribes to Starknet events
@@ -1376,54 +2867,100 @@ impl ToriiClient {
         callback: js_sys::Function,
     ) -> Result<Subscription, JsValue> {
         let clauses: Vec<_> = clauses.into_iter().map(|c| c.into()).collect();
-        let (trigger, tripwire) = Tripwire::new();
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
+        let subscribed = subscribe(
+            self.inner.clone(),
+            clauses,
+            |client: Arc<torii_client::Client>, clauses| async move {
+                client.on_starknet_event(clauses).await.map(without_id)
+            },
+            gloo_timers::future::sleep,
+            move |event: torii_proto::Event| {
+                let _ = callback
+                    .call1(&JsValue::null(), &event.serialize(&JSON_COMPAT_SERIALIZER).unwrap());
+            },
+        );
+        spawn_subscription(subscribed, "Failed to establish Starknet event subscription").await
+    }
 
-        // Spawn a new task to handle the stream and reconnections
-        let client = self.inner.clone();
-        wasm_bindgen_futures::spawn_local(async move {
-            let mut backoff = 1000;
-            let max_backoff = 60000;
-
-            loop {
-                if let Ok(stream) = client.on_starknet_event(clauses.clone()).await {
-                    backoff = 1000; // Reset backoff on successful connection
-
-                    let mut stream = stream.take_until_if(tripwire.clone());
-
-                    while let Some(Ok(event)) = stream.next().await {
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(0).expect("Failed to send subscription ID");
-                        } else {
-                            let _ = callback.call1(
-                                &JsValue::null(),
-                                &event.serialize(&JSON_COMPAT_SERIALIZER).unwrap(),
-                            );
+    /// Watches a world for models and events registered or upgraded while the client runs
+    ///
+    /// Registration and upgrade events of the world trigger a refresh of its schema, as does
+    /// every connection of the subscription. The cached query results of the world and the
+    /// clause validator are dropped whenever a model changed
+    ///
+    /// # Parameters
+    /// * `world_address` - Address of the world as a hex string
+    /// * `store` - Optional OptimisticStore whose predictions of upgraded models are discarded
+    /// * `callback` - JavaScript function called with each registered or upgraded model, along
+    ///   with the changes of its schema
+    ///
+    /// # Returns
+    /// Result containing subscription handle or error
+    #[wasm_bindgen(js_name = onSchemaUpgrade)]
+    pub async fn on_schema_upgrade(
+        &self,
+        world_address: String,
+        store: Option<&OptimisticStore>,
+        callback: js_sys::Function,
+    ) -> Result<Subscription, JsValue> {
+        let world_address = Felt::from_hex(&world_address)
+            .map_err(|err| JsValue::from(format!("invalid world address: {err}")))?;
+        let connection = self.inner.clone();
+        let watcher = Rc::new(SchemaWatcher::new(world_address));
+        // Records the schema the upgrades are compared with
+        self.request(watcher.refresh(&connection))
+            .await
+            .map_err(|err| JsValue::from(format!("failed to get world schema: {err}")))?;
+
+        let store = store.map(|store| (store.inner.clone(), store.callback.clone()));
+        let refresh = {
+            let connection = connection.clone();
+            move || {
+                let (connection, watcher, store) =
+                    (connection.clone(), watcher.clone(), store.clone());
+                let callback = callback.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let Ok(upgrades) = watcher.refresh(&connection).await else { return };
+                    for upgrade in upgrades {
+                        if let Some((optimistic, on_view)) = &store {
+                            for view in optimistic.discard_model(&upgrade.new.tag) {
+                                let view: Entity = view.into();
+                                let _ = on_view.call1(
+                                    &JsValue::null(),
+                                    &view.serialize(&JSON_COMPAT_SERIALIZER).unwrap(),
+                                );
+                            }
                         }
+                        let upgrade: SchemaUpgrade = upgrade.into();
+                        let _ = callback.call1(
+                            &JsValue::null(),
+                            &upgrade.serialize(&JSON_COMPAT_SERIALIZER).unwrap(),
+                        );
                     }
-                }
+                });
+            }
+        };
 
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
+        let on_event = refresh.clone();
+        let subscribed = subscribe(
+            connection,
+            (),
+            |client: Arc<torii_client::Client>, ()| async move {
+                client.on_starknet_event(upgrade_clauses()).await.map(without_id)
+            },
+            gloo_timers::future::sleep,
+            move |_: torii_proto::Event| on_event(),
+        );
+        // Upgrades may have landed before the stream was live, or while reconnecting
+        let mut states = subscribed.handle.status.watch();
+        wasm_bindgen_futures::spawn_local(async move {
+            while let Some(state) = states.next().await {
+                if state == dojo_core::status::SubscriptionState::Live {
+                    refresh();
                 }
-                gloo_timers::future::TimeoutFuture::new(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
             }
         });
-
-        let subscription_id = match sub_id_rx.await {
-            Ok(id) => id,
-            Err(_) => {
-                return Err(JsValue::from("Failed to establish Starknet event subscription"));
-            }
-        };
-
-        let subscription = Subscription { id: subscription_id, trigger };
-
-        Ok(subscription)
+        spawn_subscription(subscribed, "Failed to establish schema upgrade subscription").await
     }
 
     /// Subscribes to indexer updates
@@ -1447,56 +2984,21 @@ impl ToriiClient {
                 })
             })
             .transpose()?;
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-        let (trigger, tripwire) = Tripwire::new();
-
-        // Spawn a new task to handle the stream and reconnections
-        let client = self.inner.clone();
-        wasm_bindgen_futures::spawn_local(async move {
-            let mut backoff = 1000;
-            let max_backoff = 60000;
-
-            loop {
-                if let Ok(stream) = client.on_contract_updated(contract_address).await {
-                    backoff = 1000; // Reset backoff on successful connection
-
-                    let mut stream = stream.take_until_if(tripwire.clone());
-
-                    while let Some(Ok(update)) = stream.next().await {
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(0).expect("Failed to send subscription ID");
-                        } else {
-                            let update: Contract = update.into();
-
-                            let _ = callback.call1(
-                                &JsValue::null(),
-                                &update.serialize(&JSON_COMPAT_SERIALIZER).unwrap(),
-                            );
-                        }
-                    }
-                }
-
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
-                }
-                gloo_timers::future::TimeoutFuture::new(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
-            }
-        });
-
-        let subscription_id = match sub_id_rx.await {
-            Ok(id) => id,
-            Err(_) => {
-                return Err(JsValue::from("Failed to establish contract subscription"));
-            }
-        };
-
-        let subscription = Subscription { id: subscription_id, trigger };
-
-        Ok(subscription)
+        let subscribed = subscribe(
+            self.inner.clone(),
+            contract_address,
+            |client: Arc<torii_client::Client>, contract_address| async move {
+                client.on_contract_updated(contract_address).await.map(without_id)
+            },
+            gloo_timers::future::sleep,
+            move |update: torii_proto::Contract| {
+                let update: Contract = update.into();
+
+                let _ = callback
+                    .call1(&JsValue::null(), &update.serialize(&JSON_COMPAT_SERIALIZER).unwrap());
+            },
+        );
+        spawn_subscription(subscribed, "Failed to establish contract subscription").await
     }
 
     /// Subscribes to token balance updates
@@ -1538,62 +3040,50 @@ impl ToriiClient {
         let token_ids =
             token_ids.unwrap_or_default().into_iter().map(|t| t.into()).collect::<Vec<_>>();
 
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-        let (trigger, tripwire) = Tripwire::new();
-
-        // Spawn a new task to handle the stream and reconnections
-        let client = self.inner.clone();
-        wasm_bindgen_futures::spawn_local(async move {
-            let mut backoff = 1000;
-            let max_backoff = 60000;
-
-            loop {
-                if let Ok(stream) = client
-                    .on_token_balance_updated(
-                        contract_addresses.clone(),
-                        account_addresses.clone(),
-                        token_ids.clone(),
-                    )
-                    .await
-                {
-                    backoff = 1000; // Reset backoff on successful connection
-
-                    let mut stream = stream.take_until_if(tripwire.clone());
+        let filter = TokenBalanceFilter { contract_addresses, account_addresses, token_ids };
+        let connection = self.inner.clone();
+        let on_item = move |balance: torii_proto::TokenBalance| {
+            let balance: TokenBalance = balance.into();
 
-                    while let Some(Ok((id, balance))) = stream.next().await {
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(id).expect("Failed to send subscription ID");
-                        } else {
-                            let balance: TokenBalance = balance.into();
-
-                            let _ = callback.call1(
-                                &JsValue::null(),
-                                &balance.serialize(&JSON_COMPAT_SERIALIZER).unwrap(),
-                            );
-                        }
-                    }
-                }
-
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
-                }
-                gloo_timers::future::TimeoutFuture::new(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
-            }
-        });
-
-        let subscription_id = match sub_id_rx.await {
-            Ok(id) => id,
-            Err(_) => {
-                return Err(JsValue::from("Failed to establish token balance subscription"));
-            }
+            let _ = callback
+                .call1(&JsValue::null(), &balance.serialize(&JSON_COMPAT_SERIALIZER).unwrap());
         };
-        let subscription = Subscription { id: subscription_id, trigger };
+        if connection.config().multiplex && self.token_balances.shareable(&filter) {
+            let (on_item, delivery, deliver) =
+                delivery::channel(None, gloo_timers::future::sleep, on_item);
+            wasm_bindgen_futures::spawn_local(deliver);
+            let (lease, task) = self.token_balances.join(
+                connection,
+                gloo_timers::future::sleep,
+                filter,
+                Box::new(on_item),
+            );
+            if let Some(task) = task {
+                wasm_bindgen_futures::spawn_local(task);
+            }
+            return shared_subscription(
+                lease,
+                delivery,
+                "Failed to establish token balance subscription",
+            )
+            .await;
+        }
 
-        Ok(subscription)
+        let on_item = invalidate_token_balances(&connection, on_item);
+        let subscribed = subscribe(
+            connection,
+            filter,
+            |client: Arc<torii_client::Client>, filter| async move {
+                let TokenBalanceFilter { contract_addresses, account_addresses, token_ids } =
+                    filter;
+                client
+                    .on_token_balance_updated(contract_addresses, account_addresses, token_ids)
+                    .await
+            },
+            gloo_timers::future::sleep,
+            on_item,
+        );
+        spawn_subscription(subscribed, "Failed to establish token balance subscription").await
     }
 
     /// Updates an existing token balance subscription
@@ -1632,15 +3122,28 @@ impl ToriiClient {
 
         let token_ids = token_ids.into_iter().map(|t| t.into()).collect::<Vec<_>>();
 
-        self.inner
-            .update_token_balance_subscription(
-                subscription.id,
-                contract_addresses,
-                account_addresses,
-                token_ids,
-            )
-            .await
-            .map_err(|err| JsValue::from(format!("failed to update subscription: {err}")))
+        let filter = TokenBalanceFilter { contract_addresses, account_addresses, token_ids };
+        if let SubscriptionStream::Shared { id, .. } = subscription.stream {
+            return update_shared(&self.token_balances, id, filter);
+        }
+        self.update_own(subscription, filter, |id, filter| {
+            let TokenBalanceFilter { contract_addresses, account_addresses, token_ids } = filter;
+            let key = CacheKey::new(
+                "update_token_balance_subscription",
+                &(id, &contract_addresses, &account_addresses, &token_ids),
+            );
+            self.inner.exchange(key, |inner| async move {
+                inner
+                    .update_token_balance_subscription(
+                        id,
+                        contract_addresses,
+                        account_addresses,
+                        token_ids,
+                    )
+                    .await
+            })
+        })
+        .await
     }
 
     /// Subscribes to token transfer updates
@@ -1675,70 +3178,33 @@ impl ToriiClient {
             .iter()
             .map(|addr| {
                 Felt::from_str(addr).map_err(|err| {
-                    JsValue::from(format!("failed to parse contract address: {err}"))
-                })
-            })
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let token_ids =
-            token_ids.unwrap_or_default().into_iter().map(|t| t.into()).collect::<Vec<_>>();
-
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-        let (trigger, tripwire) = Tripwire::new();
-
-        // Spawn a new task to handle the stream and reconnections
-        let client = self.inner.clone();
-        wasm_bindgen_futures::spawn_local(async move {
-            let mut backoff = 1000;
-            let max_backoff = 60000;
-
-            loop {
-                if let Ok(stream) = client
-                    .on_token_transfer_updated(
-                        contract_addresses.clone(),
-                        account_addresses.clone(),
-                        token_ids.clone(),
-                    )
-                    .await
-                {
-                    backoff = 1000; // Reset backoff on successful connection
-
-                    let mut stream = stream.take_until_if(tripwire.clone());
-
-                    while let Some(Ok(transfer)) = stream.next().await {
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(0).expect("Failed to send subscription ID");
-                        } else {
-                            let transfer: TokenTransfer = transfer.into();
-
-                            let _ = callback.call1(
-                                &JsValue::null(),
-                                &transfer.serialize(&JSON_COMPAT_SERIALIZER).unwrap(),
-                            );
-                        }
-                    }
-                }
-
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
-                }
-                gloo_timers::future::TimeoutFuture::new(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
-            }
-        });
+                    JsValue::from(format!("failed to parse contract address: {err}"))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
-        let subscription_id = match sub_id_rx.await {
-            Ok(id) => id,
-            Err(_) => {
-                return Err(JsValue::from("Failed to establish token transfer subscription"));
-            }
-        };
-        let subscription = Subscription { id: subscription_id, trigger };
+        let token_ids =
+            token_ids.unwrap_or_default().into_iter().map(|t| t.into()).collect::<Vec<_>>();
 
-        Ok(subscription)
+        let subscribed = subscribe(
+            self.inner.clone(),
+            (contract_addresses, account_addresses, token_ids),
+            |client: Arc<torii_client::Client>,
+             (contract_addresses, account_addresses, token_ids)| async move {
+                client
+                    .on_token_transfer_updated(contract_addresses, account_addresses, token_ids)
+                    .await
+                    .map(without_id)
+            },
+            gloo_timers::future::sleep,
+            move |transfer: torii_proto::TokenTransfer| {
+                let transfer: TokenTransfer = transfer.into();
+
+                let _ = callback
+                    .call1(&JsValue::null(), &transfer.serialize(&JSON_COMPAT_SERIALIZER).unwrap());
+            },
+        );
+        spawn_subscription(subscribed, "Failed to establish token transfer subscription").await
     }
 
     /// Subscribes to aggregation updates (leaderboards, stats, rankings)
@@ -1760,57 +3226,23 @@ impl ToriiClient {
         let aggregator_ids = aggregator_ids.unwrap_or_default();
         let entity_ids = entity_ids.unwrap_or_default();
 
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-        let (trigger, tripwire) = Tripwire::new();
-
-        // Spawn a new task to handle the stream and reconnections
-        let client = self.inner.clone();
-        wasm_bindgen_futures::spawn_local(async move {
-            let mut backoff = 1000;
-            let max_backoff = 60000;
-
-            loop {
-                if let Ok(stream) =
-                    client.on_aggregation_updated(aggregator_ids.clone(), entity_ids.clone()).await
-                {
-                    backoff = 1000; // Reset backoff on successful connection
-
-                    let mut stream = stream.take_until_if(tripwire.clone());
-
-                    while let Some(Ok((id, aggregation_entry))) = stream.next().await {
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(id).expect("Failed to send subscription ID");
-                        } else {
-                            let aggregation_entry: AggregationEntry = aggregation_entry.into();
-
-                            let _ = callback.call1(
-                                &JsValue::null(),
-                                &aggregation_entry.serialize(&JSON_COMPAT_SERIALIZER).unwrap(),
-                            );
-                        }
-                    }
-                }
-
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
-                }
-                gloo_timers::future::TimeoutFuture::new(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
-            }
-        });
-
-        let subscription_id = match sub_id_rx.await {
-            Ok(id) => id,
-            Err(_) => {
-                return Err(JsValue::from("Failed to establish aggregation subscription"));
-            }
-        };
-        let subscription = Subscription { id: subscription_id, trigger };
-
-        Ok(subscription)
+        let subscribed = subscribe(
+            self.inner.clone(),
+            (aggregator_ids, entity_ids),
+            |client: Arc<torii_client::Client>, (aggregator_ids, entity_ids)| async move {
+                client.on_aggregation_updated(aggregator_ids, entity_ids).await
+            },
+            gloo_timers::future::sleep,
+            move |aggregation_entry: torii_proto::AggregationEntry| {
+                let aggregation_entry: AggregationEntry = aggregation_entry.into();
+
+                let _ = callback.call1(
+                    &JsValue::null(),
+                    &aggregation_entry.serialize(&JSON_COMPAT_SERIALIZER).unwrap(),
+                );
+            },
+        );
+        spawn_subscription(subscribed, "Failed to establish aggregation subscription").await
     }
 
     /// Subscribes to activity updates (user session tracking)
@@ -1851,62 +3283,22 @@ impl ToriiClient {
             })
             .collect::<Result<Vec<_>, _>>()?;
 
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-        let (trigger, tripwire) = Tripwire::new();
-
-        // Spawn a new task to handle the stream and reconnections
-        let client = self.inner.clone();
-        wasm_bindgen_futures::spawn_local(async move {
-            let mut backoff = 1000;
-            let max_backoff = 60000;
-
-            loop {
-                if let Ok(stream) = client
-                    .on_activity_updated(
-                        world_addresses.clone(),
-                        namespaces.clone(),
-                        caller_addresses.clone(),
-                    )
-                    .await
-                {
-                    backoff = 1000; // Reset backoff on successful connection
-
-                    let mut stream = stream.take_until_if(tripwire.clone());
-
-                    while let Some(Ok((id, activity))) = stream.next().await {
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(id).expect("Failed to send subscription ID");
-                        } else {
-                            let activity: Activity = activity.into();
-
-                            let _ = callback.call1(
-                                &JsValue::null(),
-                                &activity.serialize(&JSON_COMPAT_SERIALIZER).unwrap(),
-                            );
-                        }
-                    }
-                }
-
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
-                }
-                gloo_timers::future::TimeoutFuture::new(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
-            }
-        });
-
-        let subscription_id = match sub_id_rx.await {
-            Ok(id) => id,
-            Err(_) => {
-                return Err(JsValue::from("Failed to establish activity subscription"));
-            }
-        };
-        let subscription = Subscription { id: subscription_id, trigger };
-
-        Ok(subscription)
+        let subscribed = subscribe(
+            self.inner.clone(),
+            (world_addresses, namespaces, caller_addresses),
+            |client: Arc<torii_client::Client>, query| async move {
+                let (world_addresses, namespaces, caller_addresses) = query;
+                client.on_activity_updated(world_addresses, namespaces, caller_addresses).await
+            },
+            gloo_timers::future::sleep,
+            move |activity: torii_proto::Activity| {
+                let activity: Activity = activity.into();
+
+                let _ = callback
+                    .call1(&JsValue::null(), &activity.serialize(&JSON_COMPAT_SERIALIZER).unwrap());
+            },
+        );
+        spawn_subscription(subscribed, "Failed to establish activity subscription").await
     }
 
     /// Updates an existing token transfer subscription
@@ -1946,15 +3338,25 @@ impl ToriiClient {
 
         let token_ids = token_ids.into_iter().map(|t| t.into()).collect::<Vec<_>>();
 
-        self.inner
-            .update_token_transfer_subscription(
-                subscription.id,
-                contract_addresses,
-                account_addresses,
-                token_ids,
-            )
-            .await
-            .map_err(|err| JsValue::from(format!("failed to update subscription: {err}")))
+        let query = (contract_addresses, account_addresses, token_ids);
+        self.update_own(subscription, query, |id, query| {
+            let (contract_addresses, account_addresses, token_ids) = query;
+            let key = CacheKey::new(
+                "update_token_transfer_subscription",
+                &(id, &contract_addresses, &account_addresses, &token_ids),
+            );
+            self.inner.exchange(key, |inner| async move {
+                inner
+                    .update_token_transfer_subscription(
+                        id,
+                        contract_addresses,
+                        account_addresses,
+                        token_ids,
+                    )
+                    .await
+            })
+        })
+        .await
     }
 
     /// Updates an existing aggregation subscription
@@ -1973,10 +3375,17 @@ impl ToriiClient {
         aggregator_ids: Vec<String>,
         entity_ids: Vec<String>,
     ) -> Result<(), JsValue> {
-        self.inner
-            .update_aggregation_subscription(subscription.id, aggregator_ids, entity_ids)
-            .await
-            .map_err(|err| JsValue::from(format!("failed to update subscription: {err}")))
+        self.update_own(subscription, (aggregator_ids, entity_ids), |id, query| {
+            let (aggregator_ids, entity_ids) = query;
+            let key = CacheKey::new(
+                "update_aggregation_subscription",
+                &(id, &aggregator_ids, &entity_ids),
+            );
+            self.inner.exchange(key, |inner| async move {
+                inner.update_aggregation_subscription(id, aggregator_ids, entity_ids).await
+            })
+        })
+        .await
     }
 
     /// Subscribes to achievement progression updates
@@ -2015,65 +3424,32 @@ impl ToriiClient {
 
         let achievement_ids = achievement_ids.unwrap_or_default();
 
-        let (sub_id_tx, sub_id_rx) = oneshot::channel();
-        let mut sub_id_tx = Some(sub_id_tx);
-        let (trigger, tripwire) = Tripwire::new();
-
-        // Spawn a new task to handle the stream and reconnections
-        let client = self.inner.clone();
-        wasm_bindgen_futures::spawn_local(async move {
-            let mut backoff = 1000;
-            let max_backoff = 60000;
-
-            loop {
-                if let Ok(stream) = client
+        let subscribed = subscribe(
+            self.inner.clone(),
+            (world_addresses, namespaces, player_addresses, achievement_ids),
+            |client: Arc<torii_client::Client>,
+             (world_addresses, namespaces, player_addresses, achievement_ids)| async move {
+                client
                     .on_achievement_progression_updated(
-                        world_addresses.clone(),
-                        namespaces.clone(),
-                        player_addresses.clone(),
-                        achievement_ids.clone(),
+                        world_addresses,
+                        namespaces,
+                        player_addresses,
+                        achievement_ids,
                     )
                     .await
-                {
-                    backoff = 1000; // Reset backoff on successful connection
-
-                    let mut stream = stream.take_until_if(tripwire.clone());
-
-                    while let Some(Ok((id, progression))) = stream.next().await {
-                        if let Some(tx) = sub_id_tx.take() {
-                            tx.send(id).expect("Failed to send subscription ID");
-                        } else {
-                            let progression: AchievementProgression = progression.into();
-
-                            let _ = callback.call1(
-                                &JsValue::null(),
-                                &progression.serialize(&JSON_COMPAT_SERIALIZER).unwrap(),
-                            );
-                        }
-                    }
-                }
-
-                // If we've reached this point, the stream has ended (possibly due to disconnection)
-                // We'll try to reconnect after a delay, unless the tripwire has been triggered
-                if tripwire.clone().now_or_never().unwrap_or_default() {
-                    break; // Exit the loop if the subscription has been cancelled
-                }
-                gloo_timers::future::TimeoutFuture::new(backoff).await;
-                backoff = std::cmp::min(backoff * 2, max_backoff);
-            }
-        });
-
-        let subscription_id = match sub_id_rx.await {
-            Ok(id) => id,
-            Err(_) => {
-                return Err(JsValue::from(
-                    "Failed to establish achievement progression subscription",
-                ));
-            }
-        };
-        let subscription = Subscription { id: subscription_id, trigger };
-
-        Ok(subscription)
+            },
+            gloo_timers::future::sleep,
+            move |progression: torii_proto::AchievementProgression| {
+                let progression: AchievementProgression = progression.into();
+
+                let _ = callback.call1(
+                    &JsValue::null(),
+                    &progression.serialize(&JSON_COMPAT_SERIALIZER).unwrap(),
+                );
+            },
+        );
+        spawn_subscription(subscribed, "Failed to establish achievement progression subscription")
+            .await
     }
 
     /// Updates achievement progression subscription
@@ -2106,16 +3482,26 @@ impl ToriiClient {
             .map(|addr| Felt::from_hex(&addr).unwrap())
             .collect::<Vec<_>>();
 
-        self.inner
-            .update_achievement_progression_subscription(
-                subscription.id,
-                world_addresses,
-                namespaces,
-                player_addresses,
-                achievement_ids,
-            )
-            .await
-            .map_err(|err| JsValue::from(format!("failed to update subscription: {err}")))
+        let query = (world_addresses, namespaces, player_addresses, achievement_ids);
+        self.update_own(subscription, query, |id, query| {
+            let (world_addresses, namespaces, player_addresses, achievement_ids) = query;
+            let key = CacheKey::new(
+                "update_achievement_progression_subscription",
+                &(id, &world_addresses, &namespaces, &player_addresses, &achievement_ids),
+            );
+            self.inner.exchange(key, |inner| async move {
+                inner
+                    .update_achievement_progression_subscription(
+                        id,
+                        world_addresses,
+                        namespaces,
+                        player_addresses,
+                        achievement_ids,
+                    )
+                    .await
+            })
+        })
+        .await
     }
 
     /// Updates an existing activity subscription
@@ -2152,15 +3538,20 @@ impl ToriiClient {
             })
             .collect::<Result<Vec<_>, _>>()?;
 
-        self.inner
-            .update_activity_subscription(
-                subscription.id,
-                world_addresses,
-                namespaces,
-                caller_addresses,
-            )
-            .await
-            .map_err(|err| JsValue::from(format!("failed to update subscription: {err}")))
+        let query = (world_addresses, namespaces, caller_addresses);
+        self.update_own(subscription, query, |id, query| {
+            let (world_addresses, namespaces, caller_addresses) = query;
+            let key = CacheKey::new(
+                "update_activity_subscription",
+                &(id, &world_addresses, &namespaces, &caller_addresses),
+            );
+            self.inner.exchange(key, |inner| async move {
+                inner
+                    .update_activity_subscription(id, world_addresses, namespaces, caller_addresses)
+                    .await
+            })
+        })
+        .await
     }
 
     /// Perform a full-text search across indexed entities using FTS5.
@@ -2187,8 +3578,12 @@ impl ToriiClient {
     /// ```
     #[wasm_bindgen(js_name = search)]
     pub async fn search(&self, query: SearchQuery) -> Result<SearchResponse, JsValue> {
-        let response =
-            self.inner.search(query.into()).await.map_err(|err| JsValue::from(err.to_string()))?;
+        let query: torii_proto::SearchQuery = query.into();
+        let key = CacheKey::new("search", &query);
+        let response = self
+            .request(self.inner.exchange(key, |inner| async move { inner.search(query).await }))
+            .await
+            .map_err(|err| JsValue::from(err.to_string()))?;
 
         Ok(response.into())
     }
@@ -2203,9 +3598,13 @@ impl ToriiClient {
     /// Result containing entity id of the offchain message or error
     #[wasm_bindgen(js_name = publishMessage)]
     pub async fn publish_message(&mut self, message: Message) -> Result<String, JsValue> {
+        let message: torii_proto::Message = message.into();
+        let key = CacheKey::new("publish_message", &message);
         let entity_id = self
-            .inner
-            .publish_message(message.into())
+            .request(
+                self.inner
+                    .exchange(key, |inner| async move { inner.publish_message(message).await }),
+            )
             .await
             .map_err(|err| JsValue::from(err.to_string()))?;
 
@@ -2227,9 +3626,13 @@ impl ToriiClient {
         let messages: Vec<torii_proto::Message> =
             messages.into_iter().map(|msg| msg.into()).collect::<Vec<_>>();
 
+        let key = CacheKey::new("publish_message_batch", &messages);
         let entity_ids = self
-            .inner
-            .publish_message_batch(messages)
+            .request(
+                self.inner.exchange(key, |inner| async move {
+                    inner.publish_message_batch(messages).await
+                }),
+            )
             .await
             .map_err(|err| JsValue::from(err.to_string()))?;
 
@@ -2237,10 +3640,411 @@ impl ToriiClient {
     }
 }
 
+#[wasm_bindgen]
+impl ModelDispatcher {
+    /// Creates a dispatcher routing entity updates to handlers registered by model
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self { inner: Dispatcher::new() }
+    }
+
+    /// Registers a handler called whenever the given model of an entity is updated
+    ///
+    /// # Parameters
+    /// * `model` - Tag of the model, e.g. `ns-Position`
+    /// * `callback` - JavaScript function called with the model and the hashed keys of the
+    ///   entity
+    /// * `keys` - Optional clause the model keys have to match
+    ///
+    /// # Returns
+    /// Id of the handler, to remove it
+    pub fn on(&self, model: String, callback: js_sys::Function, keys: Option<KeysClause>) -> u64 {
+        self.inner.register(model, keys.map(|k| k.into()), callback)
+    }
+
+    /// Removes a handler
+    ///
+    /// # Parameters
+    /// * `handler_id` - Id returned by `on`
+    ///
+    /// # Returns
+    /// false if there was no handler with this id
+    pub fn off(&self, handler_id: u64) -> bool {
+        self.inner.unregister(handler_id)
+    }
+}
+
+impl Default for ModelDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl OptimisticStore {
+    /// Creates a store applying predicted model changes to entity updates until their
+    /// transaction settles
+    ///
+    /// # Parameters
+    /// * `callback` - JavaScript function called with entities whose state changed because of a
+    ///   prediction, or because a prediction was rolled back or reconciled
+    #[wasm_bindgen(constructor)]
+    pub fn new(callback: js_sys::Function) -> Self {
+        Self { inner: Optimistic::new(), callback }
+    }
+
+    /// Predicts that a transaction sets the models of an entity. The models are applied to the
+    /// entity until Torii reports them, or the transaction succeeded and the entity was
+    /// updated, or the transaction reverts
+    ///
+    /// # Parameters
+    /// * `transaction_hash` - Hash of the transaction, as returned by `executeRaw`
+    /// * `entity` - Entity holding the predicted models
+    ///
+    /// # Returns
+    /// Result containing unit or error
+    pub fn predict(&self, transaction_hash: &str, entity: Entity) -> Result<(), JsValue> {
+        let transaction_hash = Felt::from_str(transaction_hash)
+            .map_err(|err| JsValue::from(format!("failed to parse transaction hash: {err}")))?;
+        let entity: torii_proto::schema::Entity =
+            entity.try_into().map_err(|e| JsValue::from(format!("failed to parse entity: {e}")))?;
+
+        self.emit(self.inner.predict(transaction_hash, entity));
+        Ok(())
+    }
+
+    /// Records the outcome of a transaction, for transactions not awaited with `watch`.
+    /// Predictions of a reverted transaction are rolled back
+    ///
+    /// # Parameters
+    /// * `transaction_hash` - Hash of the transaction
+    /// * `succeeded` - Whether the transaction succeeded
+    ///
+    /// # Returns
+    /// Result containing unit or error
+    pub fn settle(&self, transaction_hash: &str, succeeded: bool) -> Result<(), JsValue> {
+        let transaction_hash = Felt::from_str(transaction_hash)
+            .map_err(|err| JsValue::from(format!("failed to parse transaction hash: {err}")))?;
+
+        for view in self.inner.settle(transaction_hash, succeeded) {
+            self.emit(view);
+        }
+        Ok(())
+    }
+
+    /// Waits for the receipt of a transaction and settles its predictions. Predictions are kept
+    /// pending if the receipt can't be fetched, to be settled with `settle`
+    ///
+    /// # Parameters
+    /// * `provider` - Provider to fetch the receipt from
+    /// * `transaction_hash` - Hash of the transaction
+    ///
+    /// # Returns
+    /// Result containing whether the transaction succeeded, or error
+    pub async fn watch(
+        &self,
+        provider: &Provider,
+        transaction_hash: &str,
+    ) -> Result<bool, JsValue> {
+        let transaction_hash = Felt::from_str(transaction_hash)
+            .map_err(|err| JsValue::from(format!("failed to parse transaction hash: {err}")))?;
+
+        let succeeded = watch_tx_result(&provider.0, transaction_hash, gloo_timers::future::sleep)
+            .await
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        for view in self.inner.settle(transaction_hash, succeeded) {
+            self.emit(view);
+        }
+
+        Ok(succeeded)
+    }
+}
+
+impl OptimisticStore {
+    fn emit(&self, entity: torii_proto::schema::Entity) {
+        let entity: Entity = entity.into();
+        let _ = self
+            .callback
+            .call1(&JsValue::null(), &entity.serialize(&JSON_COMPAT_SERIALIZER).unwrap());
+    }
+}
+
+#[wasm_bindgen]
+impl WorldSchema {
+    /// Checks whether an entity of this world matches a clause, with the same semantics as
+    /// Torii's queries. Models are rebuilt from the schema, so that keys clauses see the keys
+    /// in declaration order
+    ///
+    /// # Parameters
+    /// * `entity` - Entity to check
+    /// * `clause` - Clause to evaluate
+    ///
+    /// # Returns
+    /// Result containing whether Torii would return the entity for the clause, or error
+    #[wasm_bindgen(js_name = entityMatchesClause)]
+    pub fn entity_matches_clause(&self, entity: Entity, clause: Clause) -> Result<bool, JsValue> {
+        let entity = entity
+            .with_schema(&self.inner)
+            .map_err(|e| JsValue::from(format!("failed to parse entity: {e}")))?;
+
+        Ok(dojo_core::clause::matches(&clause.into(), &entity))
+    }
+
+    /// Address of the world
+    #[wasm_bindgen(getter, js_name = worldAddress)]
+    pub fn world_address(&self) -> String {
+        format!("{:#x}", self.inner.world_address)
+    }
+
+    /// Namespaces having at least one model, sorted
+    #[wasm_bindgen(js_name = namespaces)]
+    pub fn namespaces(&self) -> Vec<String> {
+        self.inner.namespaces()
+    }
+
+    /// Models and events of the world, sorted by tag
+    ///
+    /// # Parameters
+    /// * `namespace` - Namespace to list the models of, all namespaces when unset
+    #[wasm_bindgen(js_name = models)]
+    pub fn models(&self, namespace: Option<String>) -> ModelSchemas {
+        let models = match namespace {
+            Some(namespace) => self.inner.models_in(&namespace),
+            None => self.inner.models().iter().collect(),
+        };
+        ModelSchemas(models.into_iter().cloned().map(Into::into).collect())
+    }
+
+    /// Looks up a model by tag, e.g. `ns-Position`
+    ///
+    /// # Returns
+    /// The model schema, undefined if the world has no such model
+    #[wasm_bindgen(js_name = model)]
+    pub fn model(&self, tag: &str) -> Option<ModelSchema> {
+        self.inner.model(tag).cloned().map(Into::into)
+    }
+
+    /// Looks up a model by selector, the Poseidon hash of its namespace and name
+    ///
+    /// # Returns
+    /// Result containing the model schema, undefined if the world has no such model, or error
+    #[wasm_bindgen(js_name = modelBySelector)]
+    pub fn model_by_selector(&self, selector: &str) -> Result<Option<ModelSchema>, JsValue> {
+        let selector = Felt::from_hex(selector)
+            .map_err(|err| JsValue::from(format!("invalid selector: {err}")))?;
+        Ok(self.inner.model_by_selector(selector).cloned().map(Into::into))
+    }
+
+    /// Creates a builder of a model, see `TyBuilder`
+    ///
+    /// # Returns
+    /// Result containing the builder, or an error if the world has no such model
+    #[wasm_bindgen(js_name = builder)]
+    pub fn builder(&self, tag: &str) -> Result<TyBuilder, JsValue> {
+        let model =
+            self.inner.model(tag).ok_or_else(|| JsValue::from(format!("no model `{tag}`")))?;
+        Ok(TyBuilder { inner: dojo_core::builder::TyBuilder::new(&model.schema) })
+    }
+}
+
+/// Sets a value of a builder, turning its error into a JS error
+fn set_at(set: impl FnOnce() -> anyhow::Result<()>) -> Result<(), JsValue> {
+    set().map_err(|e| JsValue::from(e.to_string()))
+}
+
+#[wasm_bindgen]
+impl TyBuilder {
+    /// Sets the u32 at a path, e.g. `vec.x`
+    ///
+    /// Paths are made of struct members and tuple indices separated by dots, enum variants
+    /// following `::` and array indices in brackets, as in `modelGet`. A variant in a path
+    /// selects it, and the index following the last element of an array appends an element.
+    /// A setter that fails leaves the model unchanged.
+    #[wasm_bindgen(js_name = setU32)]
+    pub fn set_u32(&mut self, path: &str, value: u32) -> Result<(), JsValue> {
+        set_at(|| self.inner.set_u32(path, value))
+    }
+
+    /// Sets the felt252, ContractAddress, ClassHash or EthAddress at a path from a hex string
+    #[wasm_bindgen(js_name = setFelt)]
+    pub fn set_felt(&mut self, path: &str, value: &str) -> Result<(), JsValue> {
+        let value =
+            Felt::from_hex(value).map_err(|err| JsValue::from(format!("invalid felt: {err}")))?;
+        set_at(|| self.inner.set_felt(path, value))
+    }
+
+    /// Sets the bool at a path
+    #[wasm_bindgen(js_name = setBool)]
+    pub fn set_bool(&mut self, path: &str, value: bool) -> Result<(), JsValue> {
+        set_at(|| self.inner.set_bool(path, value))
+    }
+
+    /// Sets the ByteArray at a path
+    #[wasm_bindgen(js_name = setByteArray)]
+    pub fn set_byte_array(&mut self, path: &str, value: &str) -> Result<(), JsValue> {
+        set_at(|| self.inner.set_byte_array(path, value))
+    }
+
+    /// Selects a variant of the enum at a path, keeping the value of the variant
+    #[wasm_bindgen(js_name = setVariant)]
+    pub fn set_variant(&mut self, path: &str, variant: &str) -> Result<(), JsValue> {
+        set_at(|| self.inner.set_variant(path, variant))
+    }
+
+    /// Sets any primitive or ByteArray at a path from text: decimal or 0x-prefixed hex for
+    /// numbers, e.g. the strings of `modelGetU256`, and `true` or `false` for booleans
+    #[wasm_bindgen(js_name = setStr)]
+    pub fn set_str(&mut self, path: &str, text: &str) -> Result<(), JsValue> {
+        set_at(|| self.inner.set_from_str(path, text))
+    }
+
+    /// Model built so far, as the models of entities
+    #[wasm_bindgen(js_name = model)]
+    pub fn model(&self) -> Result<Model, JsValue> {
+        match self.inner.ty() {
+            dojo_types::schema::Ty::Struct(model) => Ok(model.clone().into()),
+            ty => Err(JsValue::from(format!("{} is not a model", ty.name()))),
+        }
+    }
+
+    /// Model built so far as JSON, e.g. the message of an offchain message
+    ///
+    /// Structs are objects, unit variants their name and other variants an object of their name
+    /// and value, tuples and arrays are arrays and byte arrays strings.
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<JsValue, JsValue> {
+        let json = self.inner.to_json().map_err(|e| JsValue::from(e.to_string()))?;
+        json.serialize(&JSON_COMPAT_SERIALIZER).map_err(|e| JsValue::from(e.to_string()))
+    }
+
+    /// Model built so far serialized as Cairo calldata, as hex strings
+    #[wasm_bindgen(js_name = calldata)]
+    pub fn calldata(&self) -> Result<Vec<String>, JsValue> {
+        let felts = self.inner.calldata().map_err(|e| JsValue::from(e.to_string()))?;
+        Ok(felts.iter().map(|f| format!("{f:#x}")).collect())
+    }
+}
+
+#[wasm_bindgen]
+impl PageIterator {
+    /// Fetches the page after the last one returned
+    ///
+    /// # Returns
+    /// Result containing the page, undefined once every page was returned, or error
+    #[wasm_bindgen(js_name = nextPage)]
+    pub async fn next_page(&self) -> Result<JsValue, JsValue> {
+        let page = self.inner.lock().await.next_page().await;
+        page.map(|page| page.unwrap_or(JsValue::UNDEFINED))
+            .map_err(|err| JsValue::from(format!("failed to get next page: {err}")))
+    }
+
+    /// Fetches again the page before the last one returned
+    ///
+    /// # Returns
+    /// Result containing the page, undefined when on the first page, or error
+    #[wasm_bindgen(js_name = previousPage)]
+    pub async fn previous_page(&self) -> Result<JsValue, JsValue> {
+        let page = self.inner.lock().await.previous_page().await;
+        page.map(|page| page.unwrap_or(JsValue::UNDEFINED))
+            .map_err(|err| JsValue::from(format!("failed to get previous page: {err}")))
+    }
+
+    /// Returns the next item, fetching the next page when needed, as an async iterator result
+    ///
+    /// # Returns
+    /// Result containing `{ value, done }`, done once every item was returned, or error
+    pub async fn next(&self) -> Result<JsValue, JsValue> {
+        let item = self
+            .inner
+            .lock()
+            .await
+            .next_item()
+            .await
+            .map_err(|err| JsValue::from(format!("failed to get next item: {err}")))?;
+
+        let result = js_sys::Object::new();
+        js_sys::Reflect::set(&result, &JsValue::from("done"), &JsValue::from(item.is_none()))?;
+        js_sys::Reflect::set(
+            &result,
+            &JsValue::from("value"),
+            &item.unwrap_or(JsValue::UNDEFINED),
+        )?;
+        Ok(result.into())
+    }
+}
+
 #[wasm_bindgen]
 impl Subscription {
+    /// Gets the id Torii knows the subscription by, which changes when it fails over to
+    /// another endpoint
+    #[wasm_bindgen(getter)]
+    pub fn id(&self) -> u64 {
+        match &self.stream {
+            SubscriptionStream::Own { id, .. } => id(),
+            SubscriptionStream::Shared { id, .. } => *id,
+        }
+    }
+
     /// Cancels an active subscription
     pub fn cancel(self) {
+        // Shared subscriptions leave their stream when the lease is dropped
         self.trigger.cancel();
     }
+
+    /// Gets the current connection state of the subscription
+    ///
+    /// # Returns
+    /// One of `connecting`, `live`, `reconnecting` (with attempt and delay) or `closed` (with
+    /// reason)
+    #[wasm_bindgen(getter)]
+    pub fn state(&self) -> SubscriptionState {
+        self.status.state().into()
+    }
+
+    /// Registers a listener notified of every connection state transition
+    ///
+    /// The listener is immediately called with the current state, then keeps being called after
+    /// the subscription is cancelled, until it reports the `closed` state.
+    ///
+    /// # Parameters
+    /// * `callback` - JavaScript function called with each new SubscriptionState
+    #[wasm_bindgen(js_name = onStatus)]
+    pub fn on_status(&self, callback: js_sys::Function) {
+        let mut states = self.status.watch();
+        wasm_bindgen_futures::spawn_local(async move {
+            while let Some(state) = states.next().await {
+                let state: SubscriptionState = state.into();
+                let _ = callback
+                    .call1(&JsValue::null(), &state.serialize(&JSON_COMPAT_SERIALIZER).unwrap());
+            }
+        });
+    }
+
+    /// Sets how updates are buffered, coalesced and rate limited before reaching the callback
+    ///
+    /// # Parameters
+    /// * `policy` - DeliveryPolicy applied to the updates received from now on
+    #[wasm_bindgen(js_name = setDeliveryPolicy)]
+    pub fn set_delivery_policy(&self, policy: DeliveryPolicy) {
+        self.delivery.set_policy(policy.into());
+    }
+
+    /// Sets the models and members kept from the updates of an entity or event message
+    /// subscription, as they are delivered. Changes, diffs and predictions are computed from
+    /// whole updates, and updates left with nothing to deliver are skipped.
+    ///
+    /// # Parameters
+    /// * `selectors` - Model tags and member paths, e.g. `ns-Position` or `ns-Stats.health`,
+    ///   every model and member being kept when empty
+    #[wasm_bindgen(js_name = setProjection)]
+    pub fn set_projection(&self, selectors: Vec<String>) {
+        let projection = dojo_core::projection::Projection::new(&selectors);
+        self.delivery.set_projection(Some(projection).filter(|p| !p.is_empty()));
+    }
+
+    /// Gets the number of delivered, dropped and coalesced updates
+    #[wasm_bindgen(getter, js_name = deliveryStats)]
+    pub fn delivery_stats(&self) -> DeliveryStats {
+        self.delivery.stats().into()
+    }
 }
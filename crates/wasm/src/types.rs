@@ -1,7 +1,9 @@
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::time::Duration;
 
 use crypto_bigint::U256;
+use dojo_core::mux::Mux;
 use dojo_types::schema::Struct;
 use serde::{Deserialize, Serialize};
 use starknet::core::types::FunctionCall;
@@ -10,7 +12,9 @@ use starknet_crypto::Felt;
 use tsify_next::{declare, Tsify};
 use wasm_bindgen::prelude::*;
 
-use super::utils::{pad_to_hex, parse_ty_as_json_str};
+use super::utils::{
+    pad_to_hex, parse_struct_with_schema, parse_ty_as_json_str, parse_ty_from_json,
+};
 
 #[derive(Tsify, Serialize, Deserialize, Debug)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
@@ -547,6 +551,193 @@ pub struct ClientConfig {
     pub torii_url: String,
     #[serde(rename = "worldAddress")]
     pub world_address: String,
+    /// Additional Torii replicas to fail over to when `toriiUrl` becomes unhealthy
+    #[serde(rename = "fallbackUrls")]
+    pub fallback_urls: Option<Vec<String>>,
+    /// Maximum time to wait for the initial connection, in milliseconds
+    #[serde(rename = "connectTimeout")]
+    pub connect_timeout: Option<u64>,
+    /// Maximum time to wait for a single query, in milliseconds
+    #[serde(rename = "requestTimeout")]
+    pub request_timeout: Option<u64>,
+    /// Maximum size of a decoded gRPC message, in bytes
+    #[serde(rename = "maxMessageSize")]
+    pub max_message_size: Option<u64>,
+    pub retry: Option<RetryPolicy>,
+    /// Polls entity and event message updates when streaming keeps failing
+    pub polling: Option<PollingConfig>,
+    /// Shares one stream between subscriptions of the same kind
+    pub multiplex: Option<bool>,
+    /// Caches query results, invalidated by subscription updates
+    pub cache: Option<CacheConfig>,
+    /// Checks entity and event message clauses against the world schemas before sending them
+    #[serde(rename = "validateQueries")]
+    pub validate_queries: Option<bool>,
+}
+
+impl From<ClientConfig> for dojo_core::config::ClientConfig {
+    fn from(value: ClientConfig) -> Self {
+        let mut config = dojo_core::config::ClientConfig::new(value.torii_url);
+        config.fallback_urls = value.fallback_urls.unwrap_or_default();
+        config.connect_timeout = value.connect_timeout.map(Duration::from_millis);
+        config.request_timeout = value.request_timeout.map(Duration::from_millis);
+        if let Some(max_message_size) = value.max_message_size {
+            config.max_message_size = max_message_size as usize;
+        }
+        if let Some(retry) = value.retry {
+            config.retry = retry.into();
+        }
+        config.polling = value.polling.map(Into::into);
+        config.multiplex = value.multiplex.unwrap_or_default();
+        config.cache = value.cache.map(Into::into);
+        config.validate_queries = value.validate_queries.unwrap_or_default();
+        config
+    }
+}
+
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct EndpointStatuses(pub Vec<EndpointStatus>);
+
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct EndpointStatus {
+    pub url: String,
+    pub active: bool,
+    pub healthy: bool,
+    pub head: Option<u64>,
+    #[serde(rename = "lastError")]
+    pub last_error: Option<String>,
+}
+
+impl From<dojo_core::failover::EndpointStatus> for EndpointStatus {
+    fn from(value: dojo_core::failover::EndpointStatus) -> Self {
+        Self {
+            url: value.url,
+            active: value.active,
+            healthy: value.health.healthy,
+            head: value.health.head,
+            last_error: value.health.last_error,
+        }
+    }
+}
+
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct RetryPolicy {
+    /// Delay before the first reconnection attempt, in milliseconds
+    #[serde(rename = "initialBackoffMs")]
+    pub initial_backoff_ms: u64,
+    /// Upper bound for the delay between attempts, in milliseconds
+    #[serde(rename = "maxBackoffMs")]
+    pub max_backoff_ms: u64,
+    /// Factor applied to the delay after each failed attempt
+    pub multiplier: u32,
+    /// Number of consecutive failed attempts before giving up. Retries forever when unset.
+    #[serde(rename = "maxAttempts")]
+    pub max_attempts: Option<u32>,
+}
+
+impl From<RetryPolicy> for dojo_core::config::RetryPolicy {
+    fn from(value: RetryPolicy) -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(value.initial_backoff_ms),
+            max_backoff: Duration::from_millis(value.max_backoff_ms),
+            multiplier: value.multiplier,
+            max_attempts: value.max_attempts,
+        }
+    }
+}
+
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct PollingConfig {
+    /// Delay between two polls, in milliseconds
+    #[serde(rename = "intervalMs")]
+    pub interval_ms: u64,
+    /// Consecutive failed stream attempts before switching to polling. `0` polls right away.
+    #[serde(rename = "afterFailures")]
+    pub after_failures: u32,
+    /// Maximum number of entities fetched per request
+    #[serde(rename = "pageSize")]
+    pub page_size: u32,
+}
+
+impl From<PollingConfig> for dojo_core::config::PollingConfig {
+    fn from(value: PollingConfig) -> Self {
+        Self {
+            interval: Duration::from_millis(value.interval_ms),
+            after_failures: value.after_failures,
+            page_size: value.page_size,
+        }
+    }
+}
+
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct CacheConfig {
+    /// How long a result is served without querying Torii again, in milliseconds
+    #[serde(rename = "ttlMs")]
+    pub ttl_ms: u64,
+    /// Maximum number of cached results
+    #[serde(rename = "maxEntries")]
+    pub max_entries: u32,
+    /// Serves expired results while they are refreshed in the background
+    #[serde(rename = "staleWhileRevalidate")]
+    pub stale_while_revalidate: bool,
+}
+
+impl From<CacheConfig> for dojo_core::cache::CacheConfig {
+    fn from(value: CacheConfig) -> Self {
+        Self {
+            ttl: Duration::from_millis(value.ttl_ms),
+            max_entries: value.max_entries as usize,
+            stale_while_revalidate: value.stale_while_revalidate,
+        }
+    }
+}
+
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct Diagnostics(pub Vec<Diagnostic>);
+
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct Diagnostic {
+    /// Offending clause in the filter syntax
+    pub clause: Option<String>,
+    pub message: String,
+}
+
+impl From<dojo_core::validate::Diagnostic> for Diagnostic {
+    fn from(value: dojo_core::validate::Diagnostic) -> Self {
+        Self { clause: value.clause, message: value.message }
+    }
+}
+
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct CacheStats {
+    pub hits: u64,
+    #[serde(rename = "staleHits")]
+    pub stale_hits: u64,
+    pub misses: u64,
+    pub invalidations: u64,
+    pub evictions: u64,
+    pub entries: u64,
+}
+
+impl From<dojo_core::cache::CacheStats> for CacheStats {
+    fn from(value: dojo_core::cache::CacheStats) -> Self {
+        Self {
+            hits: value.hits,
+            stale_hits: value.stale_hits,
+            misses: value.misses,
+            invalidations: value.invalidations,
+            evictions: value.evictions,
+            entries: value.entries,
+        }
+    }
 }
 
 #[derive(Tsify, Serialize, Deserialize, Debug)]
@@ -643,10 +834,311 @@ impl From<torii_proto::schema::Entity> for Entity {
     }
 }
 
+impl Entity {
+    /// Converts the entity, rebuilding its models from the schema of its world so that members
+    /// are in declaration order and enums hold every variant, like in the entities Torii sends.
+    pub(crate) fn with_schema(
+        mut self,
+        schema: &dojo_core::schema::WorldSchema,
+    ) -> Result<torii_proto::schema::Entity, String> {
+        let models = std::mem::take(&mut self.models);
+        let mut entity = torii_proto::schema::Entity::try_from(self)?;
+        entity.models = models
+            .into_iter()
+            .map(|(tag, model)| {
+                let Some(dojo_types::schema::Ty::Struct(schema)) =
+                    schema.model(&tag).map(|model| &model.schema)
+                else {
+                    return Err(format!("Unknown model {tag}"));
+                };
+                let model = parse_struct_with_schema(schema, model.0)?;
+                Ok(Struct { name: tag, ..model })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(entity)
+    }
+}
+
+impl TryFrom<Entity> for torii_proto::schema::Entity {
+    type Error = String;
+
+    fn try_from(value: Entity) -> Result<Self, Self::Error> {
+        let timestamp = |secs: u64| {
+            chrono::DateTime::from_timestamp(secs as i64, 0)
+                .ok_or_else(|| format!("Invalid timestamp {secs}"))
+        };
+
+        Ok(Self {
+            world_address: Felt::from_str(&value.world_address).map_err(|e| e.to_string())?,
+            hashed_keys: Felt::from_str(&value.hashed_keys).map_err(|e| e.to_string())?,
+            models: value
+                .models
+                .into_iter()
+                .map(|(name, model)| {
+                    let children = model
+                        .0
+                        .into_iter()
+                        .map(|(name, ty)| {
+                            Ok(dojo_types::schema::Member {
+                                name,
+                                key: ty.key,
+                                ty: parse_ty_from_json(&ty)?,
+                            })
+                        })
+                        .collect::<Result<_, String>>()?;
+                    Ok(Struct { name, children })
+                })
+                .collect::<Result<_, String>>()?,
+            created_at: timestamp(value.created_at)?,
+            updated_at: timestamp(value.updated_at)?,
+            executed_at: timestamp(value.executed_at)?,
+        })
+    }
+}
+
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum EntityChange {
+    Created { entity: Entity },
+    Updated { entity: Entity },
+    Deleted { entity: Entity },
+}
+
+impl From<dojo_core::change::EntityChange> for EntityChange {
+    fn from(value: dojo_core::change::EntityChange) -> Self {
+        match value {
+            dojo_core::change::EntityChange::Created(entity) => {
+                Self::Created { entity: entity.into() }
+            }
+            dojo_core::change::EntityChange::Updated(entity) => {
+                Self::Updated { entity: entity.into() }
+            }
+            dojo_core::change::EntityChange::Deleted(entity) => {
+                Self::Deleted { entity: entity.into() }
+            }
+        }
+    }
+}
+
 #[derive(Tsify, Serialize, Deserialize, Debug)]
 #[tsify(into_wasm_abi, from_wasm_abi, hashmap_as_object)]
 pub struct Entities(pub Page<Entity>);
 
+/// Entities looked up by id, null when Torii doesn't know the entity
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi, hashmap_as_object)]
+pub struct EntityLookup(pub HashMap<String, Option<Entity>>);
+
+impl From<dojo_core::batch::Lookup> for EntityLookup {
+    fn from(value: dojo_core::batch::Lookup) -> Self {
+        Self(
+            value
+                .into_iter()
+                .map(|(id, entity)| (format!("{id:#x}"), entity.map(Into::into)))
+                .collect(),
+        )
+    }
+}
+
+/// Point in the history of a world, a timestamp being in seconds
+#[derive(Tsify, Serialize, Deserialize, Debug, Clone, Copy)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum At {
+    Block(u64),
+    Timestamp(u64),
+}
+
+impl From<At> for dojo_core::history::At {
+    fn from(value: At) -> Self {
+        match value {
+            At::Block(block) => dojo_core::history::At::Block(block),
+            At::Timestamp(timestamp) => dojo_core::history::At::Timestamp(timestamp),
+        }
+    }
+}
+
+/// Inclusive bounds of an entity history, unbounded when unset
+#[derive(Tsify, Serialize, Deserialize, Debug, Default)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct HistoryRange {
+    #[serde(default)]
+    pub from: Option<At>,
+    #[serde(default)]
+    pub to: Option<At>,
+}
+
+impl From<HistoryRange> for dojo_core::history::HistoryRange {
+    fn from(value: HistoryRange) -> Self {
+        Self { from: value.from.map(Into::into), to: value.to.map(Into::into) }
+    }
+}
+
+/// Value of a model set by a transaction. The block and transaction are unknown when its
+/// event couldn't be told apart from others of the same entity and time.
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi, hashmap_as_object)]
+pub struct EntityVersion {
+    pub block_number: Option<u64>,
+    pub transaction_hash: Option<String>,
+    pub timestamp: u64,
+    pub model: Model,
+}
+
+impl From<dojo_core::history::Version> for EntityVersion {
+    fn from(value: dojo_core::history::Version) -> Self {
+        Self {
+            block_number: value.block_number,
+            transaction_hash: value.transaction_hash.map(|hash| format!("{hash:#x}")),
+            timestamp: value.timestamp,
+            model: value.model.into(),
+        }
+    }
+}
+
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi, hashmap_as_object)]
+pub struct EntityVersions(pub Vec<EntityVersion>);
+
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi, hashmap_as_object)]
+pub struct EntityList(pub Vec<Entity>);
+
+/// Member of a model. Its type is described by the `type` and `type_name` of `ty`, which has
+/// no value.
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct MemberSchema {
+    pub name: String,
+    pub ty: Ty,
+}
+
+impl MemberSchema {
+    fn new(value: dojo_core::schema::MemberSchema, key: bool) -> Self {
+        Self { name: value.name, ty: parse_ty_as_json_str(&value.ty, key) }
+    }
+}
+
+/// Model or event registered in a world, along with the class declaring it
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct ModelSchema {
+    pub tag: String,
+    pub namespace: String,
+    pub name: String,
+    pub selector: String,
+    pub class_hash: String,
+    pub contract_address: String,
+    pub packed_size: u32,
+    pub unpacked_size: u32,
+    pub use_legacy_store: bool,
+    /// Whether this is an event rather than a model
+    pub is_event: bool,
+    /// Key members, in the order they make up the id of an entity
+    pub keys: Vec<MemberSchema>,
+    pub values: Vec<MemberSchema>,
+}
+
+impl From<dojo_core::schema::ModelSchema> for ModelSchema {
+    fn from(value: dojo_core::schema::ModelSchema) -> Self {
+        Self {
+            tag: value.tag,
+            namespace: value.namespace,
+            name: value.name,
+            selector: format!("{:#x}", value.selector),
+            class_hash: format!("{:#x}", value.class_hash),
+            contract_address: format!("{:#x}", value.contract_address),
+            packed_size: value.packed_size,
+            unpacked_size: value.unpacked_size,
+            use_legacy_store: value.use_legacy_store,
+            is_event: value.is_event,
+            keys: value.keys.into_iter().map(|member| MemberSchema::new(member, true)).collect(),
+            values: value
+                .values
+                .into_iter()
+                .map(|member| MemberSchema::new(member, false))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct ModelSchemas(pub Vec<ModelSchema>);
+
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+#[serde(rename_all = "camelCase")]
+pub enum TypeChangeKind {
+    Added,
+    Removed,
+    Changed,
+    KeyChanged,
+}
+
+impl From<dojo_core::upgrade::TypeChangeKind> for TypeChangeKind {
+    fn from(value: dojo_core::upgrade::TypeChangeKind) -> Self {
+        match value {
+            dojo_core::upgrade::TypeChangeKind::Added => Self::Added,
+            dojo_core::upgrade::TypeChangeKind::Removed => Self::Removed,
+            dojo_core::upgrade::TypeChangeKind::Changed => Self::Changed,
+            dojo_core::upgrade::TypeChangeKind::KeyChanged => Self::KeyChanged,
+        }
+    }
+}
+
+/// Structural difference between two versions of a model schema
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct TypeChange {
+    /// Path of the member or variant, e.g. `vec.x`, `dir::Left`, `pair.1` or `items[]`
+    pub path: String,
+    pub kind: TypeChangeKind,
+    /// Type before the change, null when added
+    #[serde(rename = "oldType")]
+    pub old_type: Option<Ty>,
+    /// Type after the change, null when removed
+    #[serde(rename = "newType")]
+    pub new_type: Option<Ty>,
+}
+
+impl From<dojo_core::upgrade::TypeChange> for TypeChange {
+    fn from(value: dojo_core::upgrade::TypeChange) -> Self {
+        Self {
+            path: value.path,
+            kind: value.kind.into(),
+            old_type: value.old.map(|ty| parse_ty_as_json_str(&ty, false)),
+            new_type: value.new.map(|ty| parse_ty_as_json_str(&ty, false)),
+        }
+    }
+}
+
+/// Model registered or upgraded in a world
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct SchemaUpgrade {
+    #[serde(rename = "worldAddress")]
+    pub world_address: String,
+    /// Schema before the upgrade, null when the model was just registered
+    #[serde(rename = "oldSchema")]
+    pub old_schema: Option<ModelSchema>,
+    #[serde(rename = "newSchema")]
+    pub new_schema: ModelSchema,
+    /// Structural changes of the schema, empty when only the class of the model changed
+    pub changes: Vec<TypeChange>,
+}
+
+impl From<dojo_core::upgrade::SchemaUpgrade> for SchemaUpgrade {
+    fn from(value: dojo_core::upgrade::SchemaUpgrade) -> Self {
+        Self {
+            world_address: format!("{:#x}", value.world_address),
+            old_schema: value.old.map(Into::into),
+            new_schema: value.new.into(),
+            changes: value.changes.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
 #[derive(Tsify, Serialize, Deserialize, Debug)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
 pub struct Call {
@@ -720,6 +1212,18 @@ pub struct Query {
     pub no_hashed_keys: bool,
     pub models: Vec<String>,
     pub historical: bool,
+    /// Model tags and member paths, e.g. `ns-Stats.health`, to keep from the returned
+    /// entities. Every model and member is kept when unset.
+    pub projection: Option<Vec<String>>,
+}
+
+impl Query {
+    /// Projection of the returned entities, `None` when the query doesn't set one
+    pub(crate) fn projection(&self) -> Option<dojo_core::projection::Projection> {
+        let selectors = self.projection.as_deref().unwrap_or_default();
+        Some(dojo_core::projection::Projection::new(selectors))
+            .filter(|projection| !projection.is_empty())
+    }
 }
 
 #[derive(Tsify, Serialize, Deserialize, Debug)]
@@ -742,6 +1246,17 @@ impl From<Pagination> for torii_proto::Pagination {
     }
 }
 
+impl From<torii_proto::Pagination> for Pagination {
+    fn from(value: torii_proto::Pagination) -> Self {
+        Self {
+            limit: value.limit,
+            cursor: value.cursor,
+            direction: value.direction.into(),
+            order_by: value.order_by.into_iter().map(|o| o.into()).collect(),
+        }
+    }
+}
+
 #[derive(Tsify, Serialize, Deserialize, Debug)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
 pub enum PaginationDirection {
@@ -758,6 +1273,15 @@ impl From<PaginationDirection> for torii_proto::PaginationDirection {
     }
 }
 
+impl From<torii_proto::PaginationDirection> for PaginationDirection {
+    fn from(value: torii_proto::PaginationDirection) -> Self {
+        match value {
+            torii_proto::PaginationDirection::Forward => Self::Forward,
+            torii_proto::PaginationDirection::Backward => Self::Backward,
+        }
+    }
+}
+
 #[derive(Tsify, Serialize, Deserialize, Debug)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
 pub struct OrderBy {
@@ -771,6 +1295,12 @@ impl From<OrderBy> for torii_proto::OrderBy {
     }
 }
 
+impl From<torii_proto::OrderBy> for OrderBy {
+    fn from(value: torii_proto::OrderBy) -> Self {
+        Self { field: value.field, direction: value.direction.into() }
+    }
+}
+
 #[derive(Tsify, Serialize, Deserialize, Debug)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
 pub enum OrderDirection {
@@ -787,6 +1317,15 @@ impl From<OrderDirection> for torii_proto::OrderDirection {
     }
 }
 
+impl From<torii_proto::OrderDirection> for OrderDirection {
+    fn from(value: torii_proto::OrderDirection) -> Self {
+        match value {
+            torii_proto::OrderDirection::Asc => Self::Asc,
+            torii_proto::OrderDirection::Desc => Self::Desc,
+        }
+    }
+}
+
 impl From<Query> for torii_proto::Query {
     fn from(value: Query) -> Self {
         Self {
@@ -804,6 +1343,24 @@ impl From<Query> for torii_proto::Query {
     }
 }
 
+impl From<torii_proto::Query> for Query {
+    fn from(value: torii_proto::Query) -> Self {
+        Self {
+            world_addresses: value
+                .world_addresses
+                .iter()
+                .map(|addr| format!("{addr:#x}"))
+                .collect(),
+            pagination: value.pagination.into(),
+            clause: value.clause.map(|c| c.into()),
+            no_hashed_keys: value.no_hashed_keys,
+            models: value.models,
+            historical: value.historical,
+            projection: None,
+        }
+    }
+}
+
 #[derive(Tsify, Serialize, Deserialize, Debug)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
 pub enum Clause {
@@ -829,6 +1386,15 @@ impl From<PatternMatching> for torii_proto::PatternMatching {
     }
 }
 
+impl From<torii_proto::PatternMatching> for PatternMatching {
+    fn from(value: torii_proto::PatternMatching) -> Self {
+        match value {
+            torii_proto::PatternMatching::FixedLen => Self::FixedLen,
+            torii_proto::PatternMatching::VariableLen => Self::VariableLen,
+        }
+    }
+}
+
 #[derive(Tsify, Serialize, Deserialize, Debug)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
 pub struct KeysClause {
@@ -864,6 +1430,20 @@ impl From<MemberValue> for torii_proto::MemberValue {
     }
 }
 
+impl From<torii_proto::MemberValue> for MemberValue {
+    fn from(value: torii_proto::MemberValue) -> Self {
+        match value {
+            torii_proto::MemberValue::Primitive(primitive) => {
+                MemberValue::Primitive(primitive.into())
+            }
+            torii_proto::MemberValue::String(string) => MemberValue::String(string),
+            torii_proto::MemberValue::List(list) => {
+                MemberValue::List(list.into_iter().map(|v| v.into()).collect())
+            }
+        }
+    }
+}
+
 #[derive(Tsify, Serialize, Deserialize, Debug)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
 pub struct MemberClause {
@@ -927,6 +1507,49 @@ impl From<Clause> for torii_proto::Clause {
     }
 }
 
+impl From<torii_proto::KeysClause> for KeysClause {
+    fn from(value: torii_proto::KeysClause) -> Self {
+        Self {
+            keys: value.keys.iter().map(|k| k.map(|k| format!("{k:#x}"))).collect(),
+            pattern_matching: value.pattern_matching.into(),
+            models: value.models,
+        }
+    }
+}
+
+impl From<torii_proto::MemberClause> for MemberClause {
+    fn from(value: torii_proto::MemberClause) -> Self {
+        Self {
+            model: value.model,
+            member: value.member,
+            operator: value.operator.into(),
+            value: value.value.into(),
+        }
+    }
+}
+
+impl From<torii_proto::CompositeClause> for CompositeClause {
+    fn from(value: torii_proto::CompositeClause) -> Self {
+        Self {
+            operator: value.operator.into(),
+            clauses: value.clauses.into_iter().map(|c| c.into()).collect(),
+        }
+    }
+}
+
+impl From<torii_proto::Clause> for Clause {
+    fn from(value: torii_proto::Clause) -> Self {
+        match value {
+            torii_proto::Clause::HashedKeys(keys) => {
+                Self::HashedKeys(keys.iter().map(|k| format!("{k:#x}")).collect())
+            }
+            torii_proto::Clause::Keys(keys) => Self::Keys(keys.into()),
+            torii_proto::Clause::Member(member) => Self::Member(member.into()),
+            torii_proto::Clause::Composite(composite) => Self::Composite(composite.into()),
+        }
+    }
+}
+
 #[derive(Tsify, Serialize, Deserialize, Debug)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
 pub enum LogicalOperator {
@@ -943,6 +1566,15 @@ impl From<LogicalOperator> for torii_proto::LogicalOperator {
     }
 }
 
+impl From<torii_proto::LogicalOperator> for LogicalOperator {
+    fn from(value: torii_proto::LogicalOperator) -> Self {
+        match value {
+            torii_proto::LogicalOperator::And => Self::And,
+            torii_proto::LogicalOperator::Or => Self::Or,
+        }
+    }
+}
+
 #[derive(Tsify, Serialize, Deserialize, Debug)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
 pub enum ComparisonOperator {
@@ -954,6 +1586,12 @@ pub enum ComparisonOperator {
     Lte,
     In,
     NotIn,
+    Contains,
+    ContainsAll,
+    ContainsAny,
+    ArrayLengthEq,
+    ArrayLengthGt,
+    ArrayLengthLt,
 }
 
 impl From<ComparisonOperator> for torii_proto::ComparisonOperator {
@@ -967,6 +1605,33 @@ impl From<ComparisonOperator> for torii_proto::ComparisonOperator {
             ComparisonOperator::Lte => Self::Lte,
             ComparisonOperator::In => Self::In,
             ComparisonOperator::NotIn => Self::NotIn,
+            ComparisonOperator::Contains => Self::Contains,
+            ComparisonOperator::ContainsAll => Self::ContainsAll,
+            ComparisonOperator::ContainsAny => Self::ContainsAny,
+            ComparisonOperator::ArrayLengthEq => Self::ArrayLengthEq,
+            ComparisonOperator::ArrayLengthGt => Self::ArrayLengthGt,
+            ComparisonOperator::ArrayLengthLt => Self::ArrayLengthLt,
+        }
+    }
+}
+
+impl From<torii_proto::ComparisonOperator> for ComparisonOperator {
+    fn from(value: torii_proto::ComparisonOperator) -> Self {
+        match value {
+            torii_proto::ComparisonOperator::Eq => Self::Eq,
+            torii_proto::ComparisonOperator::Neq => Self::Neq,
+            torii_proto::ComparisonOperator::Gt => Self::Gt,
+            torii_proto::ComparisonOperator::Gte => Self::Gte,
+            torii_proto::ComparisonOperator::Lt => Self::Lt,
+            torii_proto::ComparisonOperator::Lte => Self::Lte,
+            torii_proto::ComparisonOperator::In => Self::In,
+            torii_proto::ComparisonOperator::NotIn => Self::NotIn,
+            torii_proto::ComparisonOperator::Contains => Self::Contains,
+            torii_proto::ComparisonOperator::ContainsAll => Self::ContainsAll,
+            torii_proto::ComparisonOperator::ContainsAny => Self::ContainsAny,
+            torii_proto::ComparisonOperator::ArrayLengthEq => Self::ArrayLengthEq,
+            torii_proto::ComparisonOperator::ArrayLengthGt => Self::ArrayLengthGt,
+            torii_proto::ComparisonOperator::ArrayLengthLt => Self::ArrayLengthLt,
         }
     }
 }
@@ -1063,6 +1728,38 @@ impl From<Primitive> for dojo_types::primitive::Primitive {
     }
 }
 
+impl From<dojo_types::primitive::Primitive> for Primitive {
+    fn from(value: dojo_types::primitive::Primitive) -> Self {
+        let felt = |value: Option<Felt>| value.map(|value| format!("{value:#x}"));
+        match value {
+            dojo_types::primitive::Primitive::I8(value) => Self::I8(value),
+            dojo_types::primitive::Primitive::I16(value) => Self::I16(value),
+            dojo_types::primitive::Primitive::I32(value) => Self::I32(value),
+            dojo_types::primitive::Primitive::I64(value) => Self::I64(value),
+            dojo_types::primitive::Primitive::I128(value) => {
+                Self::I128(value.map(|value| value.to_string()))
+            }
+            dojo_types::primitive::Primitive::U8(value) => Self::U8(value),
+            dojo_types::primitive::Primitive::U16(value) => Self::U16(value),
+            dojo_types::primitive::Primitive::U32(value) => Self::U32(value),
+            dojo_types::primitive::Primitive::U64(value) => Self::U64(value),
+            dojo_types::primitive::Primitive::U128(value) => {
+                Self::U128(value.map(|value| value.to_string()))
+            }
+            dojo_types::primitive::Primitive::U256(value) => {
+                Self::U256(value.map(|value| format!("{value:x}")))
+            }
+            dojo_types::primitive::Primitive::Bool(value) => Self::Bool(value),
+            dojo_types::primitive::Primitive::Felt252(value) => Self::Felt252(felt(value)),
+            dojo_types::primitive::Primitive::ClassHash(value) => Self::ClassHash(felt(value)),
+            dojo_types::primitive::Primitive::ContractAddress(value) => {
+                Self::ContractAddress(felt(value))
+            }
+            dojo_types::primitive::Primitive::EthAddress(value) => Self::EthAddress(felt(value)),
+        }
+    }
+}
+
 #[derive(Tsify, Serialize, Deserialize, Debug)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
 pub struct Event {
@@ -1515,10 +2212,19 @@ impl From<torii_proto::SearchResponse> for SearchResponse {
 }
 
 // WASM-specific client types
+/// Consumer of a multiplexed stream
+pub(crate) type Sink<T> = Box<dyn FnMut(T)>;
+
 #[wasm_bindgen]
 pub struct ToriiClient {
     #[wasm_bindgen(skip)]
-    pub inner: std::sync::Arc<torii_client::Client>,
+    pub inner: std::sync::Arc<dojo_core::connection::Connection>,
+    pub(crate) entities: Mux<dojo_core::mux::Entities, Sink<torii_proto::schema::Entity>>,
+    pub(crate) event_messages:
+        Mux<dojo_core::mux::EventMessages, Sink<torii_proto::schema::Entity>>,
+    pub(crate) token_balances: Mux<dojo_core::mux::TokenBalances, Sink<torii_proto::TokenBalance>>,
+    /// Session recorded by a client created with `ToriiClient.record`
+    pub(crate) recording: Option<dojo_core::replay::Buffer>,
 }
 
 #[wasm_bindgen]
@@ -1538,8 +2244,203 @@ pub struct Account(
     >,
 );
 
+/// Iterator over every page of a query
+///
+/// Implements the async iterator protocol, yielding items with `for await`, and also walks
+/// whole pages with `nextPage` and `previousPage`.
+#[wasm_bindgen]
+pub struct PageIterator {
+    pub(crate) inner: futures::lock::Mutex<Box<dyn crate::Pages>>,
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const PAGE_ITERATOR_TS: &str = r#"
+interface PageIterator {
+    [Symbol.asyncIterator](): PageIterator;
+}
+"#;
+
 #[wasm_bindgen]
 pub struct Subscription {
-    pub id: u64,
     pub(crate) trigger: stream_cancel::Trigger,
+    pub(crate) status: dojo_core::status::SubscriptionStatus,
+    pub(crate) delivery: dojo_core::delivery::DeliveryControl,
+    pub(crate) stream: SubscriptionStream,
+}
+
+/// Stream a subscription is served by
+pub(crate) enum SubscriptionStream {
+    /// Its own stream, followed across failovers by a `Live` of its query
+    Own { id: Box<dyn Fn() -> u64>, live: Box<dyn std::any::Any> },
+    /// Membership of a multiplexed stream, see `ClientConfig::multiplex`
+    Shared { id: u64, lease: Box<dyn std::any::Any> },
+}
+
+#[wasm_bindgen]
+pub struct ModelDispatcher {
+    pub(crate) inner: dojo_core::dispatch::Dispatcher<js_sys::Function>,
+}
+
+/// Models of a world, looked up by tag or selector
+#[wasm_bindgen]
+pub struct WorldSchema {
+    pub(crate) inner: dojo_core::schema::WorldSchema,
+}
+
+/// Value of a model being built from its schema, e.g. to publish in a message
+#[wasm_bindgen]
+pub struct TyBuilder {
+    pub(crate) inner: dojo_core::builder::TyBuilder,
+}
+
+#[wasm_bindgen]
+pub struct OptimisticStore {
+    pub(crate) inner: dojo_core::optimistic::Optimistic,
+    /// Called with entities whose state changed because of a prediction or its settlement
+    pub(crate) callback: js_sys::Function,
+}
+
+#[derive(Tsify, Serialize, Deserialize, Debug, Default)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+#[serde(rename_all = "camelCase")]
+pub enum Overflow {
+    /// Holds the stream back until the callback caught up
+    #[default]
+    Wait,
+    DropOldest,
+    DropNewest,
+}
+
+impl From<Overflow> for dojo_core::delivery::Overflow {
+    fn from(value: Overflow) -> Self {
+        match value {
+            Overflow::DropOldest => Self::DropOldest,
+            Overflow::DropNewest => Self::DropNewest,
+            Overflow::Wait => Self::Wait,
+        }
+    }
+}
+
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct DeliveryPolicy {
+    /// Maximum number of buffered updates. Unbounded when unset.
+    #[serde(rename = "bufferSize")]
+    pub buffer_size: Option<u32>,
+    /// What happens when the buffer is full, `wait` by default
+    #[serde(default)]
+    pub overflow: Overflow,
+    /// Merges the buffered updates of each entity into a single one, by model. Only applies to
+    /// entity and event message subscriptions.
+    #[serde(default)]
+    pub coalesce: bool,
+    /// Minimum delay between two deliveries in milliseconds, e.g. 16 for once per frame
+    #[serde(rename = "minIntervalMs")]
+    pub min_interval_ms: Option<u64>,
+}
+
+impl From<DeliveryPolicy> for dojo_core::delivery::DeliveryPolicy {
+    fn from(value: DeliveryPolicy) -> Self {
+        Self {
+            buffer_size: value.buffer_size.map(|size| size as usize),
+            overflow: value.overflow.into(),
+            coalesce: value.coalesce,
+            min_interval: value.min_interval_ms.map(Duration::from_millis),
+        }
+    }
+}
+
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct DeliveryStats {
+    pub delivered: u64,
+    pub dropped: u64,
+    pub coalesced: u64,
+}
+
+impl From<dojo_core::delivery::DeliveryStats> for DeliveryStats {
+    fn from(value: dojo_core::delivery::DeliveryStats) -> Self {
+        Self { delivered: value.delivered, dropped: value.dropped, coalesced: value.coalesced }
+    }
+}
+
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SubscriptionState {
+    Connecting,
+    Live,
+    Reconnecting {
+        attempt: u32,
+        #[serde(rename = "delayMs")]
+        delay_ms: u64,
+    },
+    Closed {
+        reason: String,
+    },
+}
+
+impl From<dojo_core::status::SubscriptionState> for SubscriptionState {
+    fn from(value: dojo_core::status::SubscriptionState) -> Self {
+        match value {
+            dojo_core::status::SubscriptionState::Connecting => Self::Connecting,
+            dojo_core::status::SubscriptionState::Live => Self::Live,
+            dojo_core::status::SubscriptionState::Reconnecting { attempt, delay } => {
+                Self::Reconnecting { attempt, delay_ms: delay.as_millis() as u64 }
+            }
+            dojo_core::status::SubscriptionState::Closed { reason } => Self::Closed { reason },
+        }
+    }
+}
+
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+    EntityCreated,
+    ModelAdded,
+    MemberUpdated,
+    ModelRemoved,
+    EntityDeleted,
+}
+
+impl From<dojo_core::diff::ChangeKind> for ChangeKind {
+    fn from(value: dojo_core::diff::ChangeKind) -> Self {
+        match value {
+            dojo_core::diff::ChangeKind::EntityCreated => Self::EntityCreated,
+            dojo_core::diff::ChangeKind::ModelAdded => Self::ModelAdded,
+            dojo_core::diff::ChangeKind::MemberUpdated => Self::MemberUpdated,
+            dojo_core::diff::ChangeKind::ModelRemoved => Self::ModelRemoved,
+            dojo_core::diff::ChangeKind::EntityDeleted => Self::EntityDeleted,
+        }
+    }
+}
+
+#[derive(Tsify, Serialize, Deserialize, Debug)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct FieldChange {
+    #[serde(rename = "hashedKeys")]
+    pub hashed_keys: String,
+    pub kind: ChangeKind,
+    pub model: String,
+    /// Path of the changed member within the model, e.g. `vec.x`. Empty when the whole model
+    /// changed.
+    pub path: String,
+    #[serde(rename = "oldValue")]
+    pub old_value: Option<Ty>,
+    #[serde(rename = "newValue")]
+    pub new_value: Option<Ty>,
+}
+
+impl From<dojo_core::diff::FieldChange> for FieldChange {
+    fn from(value: dojo_core::diff::FieldChange) -> Self {
+        Self {
+            hashed_keys: format!("{:#x}", value.hashed_keys),
+            kind: value.kind.into(),
+            model: value.model,
+            path: value.path,
+            old_value: value.old.map(|ty| parse_ty_as_json_str(&ty, false)),
+            new_value: value.new.map(|ty| parse_ty_as_json_str(&ty, false)),
+        }
+    }
 }
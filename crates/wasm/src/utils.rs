@@ -1,9 +1,13 @@
 use std::collections::HashMap;
+use std::str::FromStr;
 
+use crypto_bigint::U256;
+use dojo_types::primitive::Primitive;
 use gloo_utils::format::JsValueSerdeExt;
 use num_bigint::BigUint;
 use num_traits::Num;
 use serde_json::Value as JsonValue;
+use starknet_crypto::Felt;
 use wasm_bindgen::JsValue;
 
 use super::types::{EnumValue, Ty};
@@ -107,6 +111,219 @@ pub fn parse_ty_as_json_str(ty: &dojo_types::schema::Ty, key: bool) -> Ty {
     }
 }
 
+/// Inverse of [`parse_ty_as_json_str`]. Struct members are in no particular order, and enums only
+/// hold their active variant.
+pub fn parse_ty_from_json(ty: &Ty) -> Result<dojo_types::schema::Ty, String> {
+    let invalid = |name: &str| format!("Invalid {name} value for {}", ty.type_name);
+
+    Ok(match ty.r#type.as_str() {
+        "primitive" => {
+            let value = ty.value.into_serde::<JsonValue>().map_err(|_| invalid("primitive"))?;
+            dojo_types::schema::Ty::Primitive(parse_primitive(&ty.type_name, &value)?)
+        }
+        "struct" => {
+            let members = serde_wasm_bindgen::from_value::<HashMap<String, Ty>>(ty.value.clone())
+                .map_err(|_| invalid("struct"))?;
+            dojo_types::schema::Ty::Struct(dojo_types::schema::Struct {
+                name: ty.type_name.clone(),
+                children: members
+                    .into_iter()
+                    .map(|(name, ty)| {
+                        Ok(dojo_types::schema::Member {
+                            name,
+                            key: ty.key,
+                            ty: parse_ty_from_json(&ty)?,
+                        })
+                    })
+                    .collect::<Result<_, String>>()?,
+            })
+        }
+        "enum" => {
+            let option = serde_wasm_bindgen::from_value::<Option<EnumValue>>(ty.value.clone())
+                .map_err(|_| invalid("enum"))?;
+            let options = option
+                .map(|option| {
+                    Ok(dojo_types::schema::EnumOption {
+                        name: option.option,
+                        ty: parse_ty_from_json(&option.value)?,
+                    })
+                })
+                .transpose()?;
+            dojo_types::schema::Ty::Enum(dojo_types::schema::Enum {
+                name: ty.type_name.clone(),
+                option: options.as_ref().map(|_| 0),
+                options: options.into_iter().collect(),
+            })
+        }
+        "tuple" | "array" => {
+            let values = serde_wasm_bindgen::from_value::<Vec<Ty>>(ty.value.clone())
+                .map_err(|_| invalid(&ty.r#type))?
+                .iter()
+                .map(parse_ty_from_json)
+                .collect::<Result<Vec<_>, _>>()?;
+            if ty.r#type == "tuple" {
+                dojo_types::schema::Ty::Tuple(values)
+            } else {
+                dojo_types::schema::Ty::Array(values)
+            }
+        }
+        "fixed_size_array" => {
+            let array = serde_wasm_bindgen::from_value::<FixedSizeArray>(ty.value.clone())
+                .map_err(|_| invalid("fixed size array"))?;
+            let values = array.array.iter().map(parse_ty_from_json).collect::<Result<_, _>>()?;
+            dojo_types::schema::Ty::FixedSizeArray((values, array.size))
+        }
+        "bytearray" => dojo_types::schema::Ty::ByteArray(
+            serde_wasm_bindgen::from_value(ty.value.clone()).map_err(|_| invalid("byte array"))?,
+        ),
+        other => return Err(format!("Unknown type {other}")),
+    })
+}
+
+/// Like [`parse_ty_from_json`], but following `schema`: struct members come in declaration
+/// order and enums hold every variant, so that values compare equal to the ones Torii sends.
+pub fn parse_ty_with_schema(
+    schema: &dojo_types::schema::Ty,
+    ty: &Ty,
+) -> Result<dojo_types::schema::Ty, String> {
+    let invalid = |name: &str| format!("Invalid {name} value for {}", ty.type_name);
+
+    Ok(match schema {
+        dojo_types::schema::Ty::Struct(schema) => {
+            let members = serde_wasm_bindgen::from_value::<HashMap<String, Ty>>(ty.value.clone())
+                .map_err(|_| invalid("struct"))?;
+            dojo_types::schema::Ty::Struct(parse_struct_with_schema(schema, members)?)
+        }
+        dojo_types::schema::Ty::Enum(schema) => {
+            let option = serde_wasm_bindgen::from_value::<Option<EnumValue>>(ty.value.clone())
+                .map_err(|_| invalid("enum"))?;
+            let mut options = schema.options.clone();
+            let option = match option {
+                Some(option) => {
+                    let index =
+                        options.iter().position(|o| o.name == option.option).ok_or_else(|| {
+                            format!("Unknown variant {} of {}", option.option, schema.name)
+                        })?;
+                    options[index].ty =
+                        parse_ty_with_schema(&schema.options[index].ty, &option.value)?;
+                    Some(index as u8)
+                }
+                None => None,
+            };
+            dojo_types::schema::Ty::Enum(dojo_types::schema::Enum {
+                name: schema.name.clone(),
+                option,
+                options,
+            })
+        }
+        dojo_types::schema::Ty::Tuple(schema) => {
+            let values = serde_wasm_bindgen::from_value::<Vec<Ty>>(ty.value.clone())
+                .map_err(|_| invalid("tuple"))?;
+            if values.len() != schema.len() {
+                return Err(invalid("tuple"));
+            }
+            let values = schema
+                .iter()
+                .zip(&values)
+                .map(|(schema, ty)| parse_ty_with_schema(schema, ty))
+                .collect::<Result<_, _>>()?;
+            dojo_types::schema::Ty::Tuple(values)
+        }
+        dojo_types::schema::Ty::Array(schema) => {
+            let element = schema.first().ok_or_else(|| invalid("array"))?;
+            let values = serde_wasm_bindgen::from_value::<Vec<Ty>>(ty.value.clone())
+                .map_err(|_| invalid("array"))?
+                .iter()
+                .map(|ty| parse_ty_with_schema(element, ty))
+                .collect::<Result<_, _>>()?;
+            dojo_types::schema::Ty::Array(values)
+        }
+        dojo_types::schema::Ty::FixedSizeArray((schema, size)) => {
+            let element = schema.first().ok_or_else(|| invalid("fixed size array"))?;
+            let array = serde_wasm_bindgen::from_value::<FixedSizeArray>(ty.value.clone())
+                .map_err(|_| invalid("fixed size array"))?;
+            let values = array
+                .array
+                .iter()
+                .map(|ty| parse_ty_with_schema(element, ty))
+                .collect::<Result<_, _>>()?;
+            dojo_types::schema::Ty::FixedSizeArray((values, *size))
+        }
+        dojo_types::schema::Ty::Primitive(_) | dojo_types::schema::Ty::ByteArray(_) => {
+            parse_ty_from_json(ty)?
+        }
+    })
+}
+
+/// Builds a struct from its members keyed by name, in the order of `schema`.
+pub fn parse_struct_with_schema(
+    schema: &dojo_types::schema::Struct,
+    mut members: HashMap<String, Ty>,
+) -> Result<dojo_types::schema::Struct, String> {
+    let children = schema
+        .children
+        .iter()
+        .map(|member| {
+            let ty = members
+                .remove(&member.name)
+                .ok_or_else(|| format!("Missing member {} of {}", member.name, schema.name))?;
+            Ok(dojo_types::schema::Member {
+                name: member.name.clone(),
+                key: member.key,
+                ty: parse_ty_with_schema(&member.ty, &ty)?,
+            })
+        })
+        .collect::<Result<_, String>>()?;
+
+    Ok(dojo_types::schema::Struct { name: schema.name.clone(), children })
+}
+
+fn parse_primitive(type_name: &str, value: &JsonValue) -> Result<Primitive, String> {
+    let text = match value {
+        JsonValue::Null => None,
+        JsonValue::String(s) => Some(s.clone()),
+        JsonValue::Number(n) => Some(n.to_string()),
+        JsonValue::Bool(b) => Some(b.to_string()),
+        _ => return Err(format!("Invalid primitive value for {type_name}")),
+    };
+    let text = text.as_deref();
+
+    Ok(match type_name {
+        "bool" => Primitive::Bool(text.map(|t| t == "true")),
+        "i8" => Primitive::I8(parse_int(text)?),
+        "i16" => Primitive::I16(parse_int(text)?),
+        "i32" => Primitive::I32(parse_int(text)?),
+        "i64" => Primitive::I64(parse_int(text)?),
+        "i128" => Primitive::I128(parse_int(text)?),
+        "u8" => Primitive::U8(parse_int(text)?),
+        "u16" => Primitive::U16(parse_int(text)?),
+        "u32" => Primitive::U32(parse_int(text)?),
+        "u64" => Primitive::U64(parse_int(text)?),
+        "u128" => Primitive::U128(parse_int(text)?),
+        "u256" => {
+            Primitive::U256(text.map(pad_to_hex).transpose()?.map(|hex| U256::from_be_hex(&hex)))
+        }
+        "felt252" => Primitive::Felt252(parse_felt(text)?),
+        "ClassHash" => Primitive::ClassHash(parse_felt(text)?),
+        "ContractAddress" => Primitive::ContractAddress(parse_felt(text)?),
+        "EthAddress" => Primitive::EthAddress(parse_felt(text)?),
+        other => return Err(format!("Unknown primitive {other}")),
+    })
+}
+
+fn parse_int<T: Num>(text: Option<&str>) -> Result<Option<T>, String> {
+    text.map(|text| match text.strip_prefix("0x") {
+        Some(hex) => T::from_str_radix(hex, 16),
+        None => T::from_str_radix(text, 10),
+    })
+    .transpose()
+    .map_err(|_| format!("Invalid integer {}", text.unwrap_or_default()))
+}
+
+fn parse_felt(text: Option<&str>) -> Result<Option<Felt>, String> {
+    text.map(Felt::from_str).transpose().map_err(|e| e.to_string())
+}
+
 pub fn pad_to_hex(input: &str) -> Result<String, String> {
     // Process the input to determine format and parse accordingly
     let big_value = if input.starts_with("0x") || input.starts_with("0X") {